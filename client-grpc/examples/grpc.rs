@@ -76,7 +76,10 @@ async fn add_vertiports(client: &GisClient) -> Result<(), Box<dyn std::error::Er
     ];
 
     let response = client
-        .update_vertiports(UpdateVertiportsRequest { vertiports })
+        .update_vertiports(UpdateVertiportsRequest {
+            vertiports,
+            dry_run: false,
+        })
         .await?;
 
     println!("RESPONSE={:?}", response.into_inner());
@@ -137,6 +140,8 @@ async fn add_aircraft(connection: &mut redis::Connection) -> Result<(), ()> {
                 },
                 timestamp_network: Utc::now(),
                 timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
             },
         )
         .collect();
@@ -157,8 +162,10 @@ async fn add_aircraft(connection: &mut redis::Connection) -> Result<(), ()> {
             identifier: Some(identifier.to_string()),
             session_id: None,
             aircraft_type: AircraftType::Rotorcraft,
+            operator_id: None,
             timestamp_network: Utc::now(),
             timestamp_asset: None,
+            force_type: false,
         })
         .collect();
 
@@ -232,6 +239,18 @@ async fn add_flight_paths(client: &GisClient) -> Result<(), ()> {
             timestamp_end: Some((Utc::now() + Duration::try_minutes(20).unwrap()).into()),
             simulated: false,
             aircraft_type: AircraftType::Rotorcraft as i32,
+            allow_rebind: false,
+            operator_id: None,
+            srid: None,
+            historical: false,
+            dry_run: false,
+            aircraft_identifiers: vec![],
+            tags: vec![],
+            destination_identifier: None,
+            reservation_secs: None,
+            scenario_id: None,
+            geom_wkt: None,
+            geom_ewkb: None,
         })
         .collect();
 
@@ -306,7 +325,10 @@ async fn best_path_flight_avoidance(
 
     let vertiports = vec![alkmaar_1.clone(), alkmaar_2.clone()];
     let _ = client
-        .update_vertiports(UpdateVertiportsRequest { vertiports })
+        .update_vertiports(UpdateVertiportsRequest {
+            vertiports,
+            dry_run: false,
+        })
         .await?;
 
     let time_start: DateTime<Utc> = Utc::now();
@@ -322,6 +344,11 @@ async fn best_path_flight_avoidance(
         time_start: Some(time_start.clone().into()),
         time_end: Some(time_end.clone().into()),
         limit: 1,
+        soft_window: false,
+        cruise_velocity_mps: None,
+        verbose: false,
+        tag_filter: None,
+        max_segments: None,
     };
 
     let response = client.best_path(request).await?.into_inner();
@@ -357,6 +384,18 @@ async fn best_path_flight_avoidance(
         timestamp_end: Some(time_end.into()),
         simulated: false,
         aircraft_type: AircraftType::Rotorcraft as i32,
+        allow_rebind: false,
+        operator_id: None,
+        srid: None,
+        historical: false,
+        dry_run: false,
+        aircraft_identifiers: vec![],
+        tags: vec![],
+        destination_identifier: None,
+        reservation_secs: None,
+        scenario_id: None,
+        geom_wkt: None,
+        geom_ewkb: None,
     };
 
     let _ = client.update_flight_path(request).await?.into_inner();
@@ -374,6 +413,11 @@ async fn best_path_flight_avoidance(
         time_start: Some(time_start.clone().into()),
         time_end: Some(time_end.clone().into()),
         limit: 1,
+        soft_window: false,
+        cruise_velocity_mps: None,
+        verbose: false,
+        tag_filter: None,
+        max_segments: None,
     };
 
     let response = client.best_path(request).await?.into_inner();
@@ -407,6 +451,18 @@ async fn best_path_flight_avoidance(
         timestamp_end: Some(time_end.into()),
         simulated: false,
         aircraft_type: AircraftType::Rotorcraft as i32,
+        allow_rebind: false,
+        operator_id: None,
+        srid: None,
+        historical: false,
+        dry_run: false,
+        aircraft_identifiers: vec![],
+        tags: vec![],
+        destination_identifier: None,
+        reservation_secs: None,
+        scenario_id: None,
+        geom_wkt: None,
+        geom_ewkb: None,
     };
 
     let _ = client.update_flight_path(request).await?.into_inner();
@@ -426,6 +482,11 @@ async fn best_path_flight_avoidance(
         time_start: Some(time_start.clone().into()),
         time_end: Some(time_end.clone().into()),
         limit: 1,
+        soft_window: false,
+        cruise_velocity_mps: None,
+        verbose: false,
+        tag_filter: None,
+        max_segments: None,
     };
 
     let response = client.best_path(request).await?.into_inner();
@@ -450,6 +511,11 @@ async fn best_path_flight_avoidance(
         time_start: Some((time_end.clone() + Duration::try_seconds(1).unwrap()).into()),
         time_end: Some((time_end.clone() + Duration::try_minutes(1).unwrap()).into()),
         limit: 1,
+        soft_window: false,
+        cruise_velocity_mps: None,
+        verbose: false,
+        tag_filter: None,
+        max_segments: None,
     };
 
     let response = client.best_path(request).await?.into_inner();
@@ -472,6 +538,11 @@ async fn best_path_flight_avoidance(
         time_start: Some((time_end - Duration::try_seconds(2).unwrap()).into()),
         time_end: Some((time_end + Duration::try_minutes(13).unwrap()).into()),
         limit: 1,
+        soft_window: false,
+        cruise_velocity_mps: None,
+        verbose: false,
+        tag_filter: None,
+        max_segments: None,
     };
 
     let response = client.best_path(request).await?.into_inner();
@@ -498,6 +569,12 @@ async fn get_flights(client: &GisClient) -> Result<(), Box<dyn std::error::Error
             window_max_y: 52.376,
             time_start: Some(time_start),
             time_end: Some(time_end),
+            operator_id: None,
+            order_by: FlightOrder::TimeStart as i32,
+            tag_filter: None,
+            velocity_samples: 0,
+            skeleton_only: false,
+            path_alignment: true,
         };
 
         let response = client.get_flights(request).await?.into_inner();
@@ -525,6 +602,11 @@ async fn best_paths(client: &GisClient) -> Result<(), Box<dyn std::error::Error>
             time_start: Some(time_start),
             time_end: Some(time_end),
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let response = client.best_path(request).await?.into_inner();
@@ -568,6 +650,11 @@ async fn best_paths(client: &GisClient) -> Result<(), Box<dyn std::error::Error>
             vertices,
             time_start: Some(time_start),
             time_end: Some(time_end),
+            source: Some("LVNL".to_string()),
+            external_reference: Some("A0001/24".to_string()),
+            tags: vec![],
+            geom_wkt: None,
+            geom_ewkb: None,
         });
 
         // No Fly 2
@@ -603,9 +690,19 @@ async fn best_paths(client: &GisClient) -> Result<(), Box<dyn std::error::Error>
             vertices,
             time_start: None,
             time_end: None,
+            source: Some("LVNL".to_string()),
+            external_reference: None,
+            tags: vec![],
+            geom_wkt: None,
+            geom_ewkb: None,
         });
 
-        let response = client.update_zones(UpdateZonesRequest { zones }).await?;
+        let response = client
+            .update_zones(UpdateZonesRequest {
+                zones,
+                dry_run: false,
+            })
+            .await?;
 
         println!("RESPONSE={:?}", response.into_inner());
     }
@@ -623,6 +720,11 @@ async fn best_paths(client: &GisClient) -> Result<(), Box<dyn std::error::Error>
             time_start: Some(time_start),
             time_end: Some(time_end),
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let mut response = client.best_path(request).await?.into_inner();
@@ -649,6 +751,11 @@ async fn best_paths(client: &GisClient) -> Result<(), Box<dyn std::error::Error>
             time_start: Some(time_start),
             time_end: Some(time_end),
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let response = client.best_path(request).await?.into_inner();
@@ -670,6 +777,11 @@ async fn best_paths(client: &GisClient) -> Result<(), Box<dyn std::error::Error>
             time_start: Some(time_start),
             time_end: Some(time_end),
             limit: 5,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let response = client.best_path(request).await?.into_inner();
@@ -29,7 +29,7 @@ cfg_if::cfg_if! {
                 cfg.manager = Some(ManagerConfig { recycling_method: RecyclingMethod::Fast });
 
                 let _pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).unwrap();
-                let grpc_service = ServerImpl { };
+                let grpc_service = ServerImpl::default();
                 lib_common::grpc::mock::start_mock_server(
                     server,
                     RpcServiceServer::new(grpc_service),
@@ -136,6 +136,282 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
         self.get_client().await?.get_flights(request).await
     }
 
+    async fn get_tile(
+        &self,
+        request: GetTileRequest,
+    ) -> Result<tonic::Response<GetTileResponse>, tonic::Status> {
+        grpc_info!("(get_tile) {} client.", self.get_name());
+        grpc_debug!("(get_tile) request: {:?}", request);
+        self.get_client().await?.get_tile(request).await
+    }
+
+    async fn get_aircraft_track(
+        &self,
+        request: GetAircraftTrackRequest,
+    ) -> Result<tonic::Response<GetAircraftTrackResponse>, tonic::Status> {
+        grpc_info!("(get_aircraft_track) {} client.", self.get_name());
+        grpc_debug!("(get_aircraft_track) request: {:?}", request);
+        self.get_client().await?.get_aircraft_track(request).await
+    }
+
+    async fn segmentize_path(
+        &self,
+        request: SegmentizePathRequest,
+    ) -> Result<tonic::Response<SegmentizePathResponse>, tonic::Status> {
+        grpc_info!("(segmentize_path) {} client.", self.get_name());
+        grpc_debug!("(segmentize_path) request: {:?}", request);
+        self.get_client().await?.segmentize_path(request).await
+    }
+
+    async fn get_flight_conflicts(
+        &self,
+        request: GetFlightConflictsRequest,
+    ) -> Result<tonic::Response<GetFlightConflictsResponse>, tonic::Status> {
+        grpc_info!("(get_flight_conflicts) {} client.", self.get_name());
+        grpc_debug!("(get_flight_conflicts) request: {:?}", request);
+        self.get_client().await?.get_flight_conflicts(request).await
+    }
+
+    async fn get_ingestion_status(
+        &self,
+        request: GetIngestionStatusRequest,
+    ) -> Result<tonic::Response<GetIngestionStatusResponse>, tonic::Status> {
+        grpc_info!("(get_ingestion_status) {} client.", self.get_name());
+        grpc_debug!("(get_ingestion_status) request: {:?}", request);
+        self.get_client().await?.get_ingestion_status(request).await
+    }
+
+    async fn register_telemetry_identifier(
+        &self,
+        request: RegisterTelemetryIdentifierRequest,
+    ) -> Result<tonic::Response<RegisterTelemetryIdentifierResponse>, tonic::Status> {
+        grpc_info!(
+            "(register_telemetry_identifier) {} client.",
+            self.get_name()
+        );
+        grpc_debug!("(register_telemetry_identifier) request: {:?}", request);
+        self.get_client()
+            .await?
+            .register_telemetry_identifier(request)
+            .await
+    }
+
+    async fn ingest_binary_telemetry(
+        &self,
+        request: IngestBinaryTelemetryRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(ingest_binary_telemetry) {} client.", self.get_name());
+        grpc_debug!("(ingest_binary_telemetry) request: {:?}", request);
+        self.get_client()
+            .await?
+            .ingest_binary_telemetry(request)
+            .await
+    }
+
+    async fn remove_zones_by_source(
+        &self,
+        request: RemoveZonesBySourceRequest,
+    ) -> Result<tonic::Response<RemoveZonesBySourceResponse>, tonic::Status> {
+        grpc_info!("(remove_zones_by_source) {} client.", self.get_name());
+        grpc_debug!("(remove_zones_by_source) request: {:?}", request);
+        self.get_client()
+            .await?
+            .remove_zones_by_source(request)
+            .await
+    }
+
+    async fn get_vertiport_throughput(
+        &self,
+        request: GetVertiportThroughputRequest,
+    ) -> Result<tonic::Response<GetVertiportThroughputResponse>, tonic::Status> {
+        grpc_info!("(get_vertiport_throughput) {} client.", self.get_name());
+        grpc_debug!("(get_vertiport_throughput) request: {:?}", request);
+        self.get_client()
+            .await?
+            .get_vertiport_throughput(request)
+            .await
+    }
+
+    async fn get_flight_segments(
+        &self,
+        request: GetFlightSegmentsRequest,
+    ) -> Result<tonic::Response<GetFlightSegmentsResponse>, tonic::Status> {
+        grpc_info!("(get_flight_segments) {} client.", self.get_name());
+        grpc_debug!("(get_flight_segments) request: {:?}", request);
+        self.get_client().await?.get_flight_segments(request).await
+    }
+
+    async fn delete_zone(
+        &self,
+        request: DeleteZoneRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(delete_zone) {} client.", self.get_name());
+        grpc_debug!("(delete_zone) request: {:?}", request);
+        self.get_client().await?.delete_zone(request).await
+    }
+
+    async fn restore_zone(
+        &self,
+        request: RestoreZoneRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(restore_zone) {} client.", self.get_name());
+        grpc_debug!("(restore_zone) request: {:?}", request);
+        self.get_client().await?.restore_zone(request).await
+    }
+
+    async fn delete_flight(
+        &self,
+        request: DeleteFlightRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(delete_flight) {} client.", self.get_name());
+        grpc_debug!("(delete_flight) request: {:?}", request);
+        self.get_client().await?.delete_flight(request).await
+    }
+
+    async fn restore_flight(
+        &self,
+        request: RestoreFlightRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(restore_flight) {} client.", self.get_name());
+        grpc_debug!("(restore_flight) request: {:?}", request);
+        self.get_client().await?.restore_flight(request).await
+    }
+
+    async fn wait_for_flight_applied(
+        &self,
+        request: WaitForFlightAppliedRequest,
+    ) -> Result<tonic::Response<WaitForFlightAppliedResponse>, tonic::Status> {
+        grpc_info!("(wait_for_flight_applied) {} client.", self.get_name());
+        grpc_debug!("(wait_for_flight_applied) request: {:?}", request);
+        self.get_client()
+            .await?
+            .wait_for_flight_applied(request)
+            .await
+    }
+
+    async fn get_altitude_occupancy(
+        &self,
+        request: GetAltitudeOccupancyRequest,
+    ) -> Result<tonic::Response<GetAltitudeOccupancyResponse>, tonic::Status> {
+        grpc_info!("(get_altitude_occupancy) {} client.", self.get_name());
+        grpc_debug!("(get_altitude_occupancy) request: {:?}", request);
+        self.get_client()
+            .await?
+            .get_altitude_occupancy(request)
+            .await
+    }
+
+    async fn confirm_flight(
+        &self,
+        request: ConfirmFlightRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(confirm_flight) {} client.", self.get_name());
+        grpc_debug!("(confirm_flight) request: {:?}", request);
+        self.get_client().await?.confirm_flight(request).await
+    }
+
+    async fn what_if(
+        &self,
+        request: WhatIfRequest,
+    ) -> Result<tonic::Response<WhatIfResponse>, tonic::Status> {
+        grpc_info!("(what_if) {} client.", self.get_name());
+        grpc_debug!("(what_if) request: {:?}", request);
+        self.get_client().await?.what_if(request).await
+    }
+
+    async fn pause_ingestion(
+        &self,
+        request: PauseIngestionRequest,
+    ) -> Result<tonic::Response<PauseIngestionResponse>, tonic::Status> {
+        grpc_info!("(pause_ingestion) {} client.", self.get_name());
+        grpc_debug!("(pause_ingestion) request: {:?}", request);
+        self.get_client().await?.pause_ingestion(request).await
+    }
+
+    async fn resume_ingestion(
+        &self,
+        request: ResumeIngestionRequest,
+    ) -> Result<tonic::Response<ResumeIngestionResponse>, tonic::Status> {
+        grpc_info!("(resume_ingestion) {} client.", self.get_name());
+        grpc_debug!("(resume_ingestion) request: {:?}", request);
+        self.get_client().await?.resume_ingestion(request).await
+    }
+
+    async fn build_flight_path(
+        &self,
+        request: BuildFlightPathRequest,
+    ) -> Result<tonic::Response<BuildFlightPathResponse>, tonic::Status> {
+        grpc_info!("(build_flight_path) {} client.", self.get_name());
+        grpc_debug!("(build_flight_path) request: {:?}", request);
+        self.get_client().await?.build_flight_path(request).await
+    }
+
+    async fn replace_zones(
+        &self,
+        request: ReplaceZonesRequest,
+    ) -> Result<tonic::Response<ReplaceZonesResponse>, tonic::Status> {
+        grpc_info!("(replace_zones) {} client.", self.get_name());
+        grpc_debug!("(replace_zones) request: {:?}", request);
+        self.get_client().await?.replace_zones(request).await
+    }
+
+    async fn get_service_info(
+        &self,
+        request: GetServiceInfoRequest,
+    ) -> Result<tonic::Response<GetServiceInfoResponse>, tonic::Status> {
+        grpc_info!("(get_service_info) {} client.", self.get_name());
+        grpc_debug!("(get_service_info) request: {:?}", request);
+        self.get_client().await?.get_service_info(request).await
+    }
+
+    async fn update_corridors(
+        &self,
+        request: UpdateCorridorsRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(update_corridors) {} client.", self.get_name());
+        grpc_debug!("(update_corridors) request: {:?}", request);
+        self.get_client().await?.update_corridors(request).await
+    }
+
+    async fn get_corridors(
+        &self,
+        request: GetCorridorsRequest,
+    ) -> Result<tonic::Response<GetCorridorsResponse>, tonic::Status> {
+        grpc_info!("(get_corridors) {} client.", self.get_name());
+        grpc_debug!("(get_corridors) request: {:?}", request);
+        self.get_client().await?.get_corridors(request).await
+    }
+
+    async fn delete_corridor(
+        &self,
+        request: DeleteCorridorRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_info!("(delete_corridor) {} client.", self.get_name());
+        grpc_debug!("(delete_corridor) request: {:?}", request);
+        self.get_client().await?.delete_corridor(request).await
+    }
+
+    async fn get_corridor_allocation(
+        &self,
+        request: GetCorridorAllocationRequest,
+    ) -> Result<tonic::Response<GetCorridorAllocationResponse>, tonic::Status> {
+        grpc_info!("(get_corridor_allocation) {} client.", self.get_name());
+        grpc_debug!("(get_corridor_allocation) request: {:?}", request);
+        self.get_client()
+            .await?
+            .get_corridor_allocation(request)
+            .await
+    }
+
+    async fn get_flight(
+        &self,
+        request: GetFlightRequest,
+    ) -> Result<tonic::Response<GetFlightResponse>, tonic::Status> {
+        grpc_info!("(get_flight) {} client.", self.get_name());
+        grpc_debug!("(get_flight) request: {:?}", request);
+        self.get_client().await?.get_flight(request).await
+    }
+
     // async fn nearest_neighbors(
     //     &self,
     //     request: NearestNeighborRequest,
@@ -158,7 +434,11 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
     ) -> Result<tonic::Response<Self::ReadyResponse>, tonic::Status> {
         grpc_warn!("(is_ready MOCK) {} client.", self.get_name());
         grpc_debug!("(is_ready MOCK) request: {:?}", request);
-        Ok(tonic::Response::new(ReadyResponse { ready: true }))
+        Ok(tonic::Response::new(ReadyResponse {
+            ready: true,
+            readiness: Readiness::Ready as i32,
+            domains: vec![],
+        }))
     }
 
     async fn update_waypoints(
@@ -167,7 +447,10 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
     ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
         grpc_warn!("(update_waypoints MOCK) {} client.", self.get_name());
         grpc_debug!("(update_waypoints MOCK) request: {:?}", request);
-        Ok(tonic::Response::new(UpdateResponse { updated: true }))
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     async fn update_vertiports(
@@ -176,7 +459,10 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
     ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
         grpc_warn!("(update_vertiports MOCK) {} client.", self.get_name());
         grpc_debug!("(update_vertiports MOCK) request: {:?}", request);
-        Ok(tonic::Response::new(UpdateResponse { updated: true }))
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     async fn update_zones(
@@ -185,7 +471,10 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
     ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
         grpc_warn!("(update_zones MOCK) {} client.", self.get_name());
         grpc_debug!("(update_zones MOCK) request: {:?}", request);
-        Ok(tonic::Response::new(UpdateResponse { updated: true }))
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     async fn update_flight_path(
@@ -194,7 +483,10 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
     ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
         grpc_warn!("(update_flight_path MOCK) {} client.", self.get_name());
         grpc_debug!("(update_flight_path MOCK) request: {:?}", request);
-        Ok(tonic::Response::new(UpdateResponse { updated: true }))
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     async fn best_path(
@@ -216,6 +508,9 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
                     }),
                 }],
                 distance_meters: 0.0,
+                time_departure: None,
+                time_arrival: None,
+                cost: None,
             }],
         }))
     }
@@ -237,6 +532,7 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
                         altitude_meters: 50.0,
                     }),
                     timestamp: Some(chrono::Utc::now().into()),
+                    bucket_seconds: 0,
                 }],
                 simulated: true,
                 aircraft_type: crate::prelude::AircraftType::Undeclared.into(),
@@ -251,12 +547,361 @@ impl crate::service::Client<RpcServiceClient<Channel>> for GisClient {
                     track_angle_degrees: 12.0,
                     ground_speed_mps: 5.0,
                     vertical_speed_mps: 1.0,
+                    horizontal_accuracy_meters: Some(10.0),
+                    vertical_accuracy_meters: Some(15.0),
+                }),
+                velocity_samples: vec![crate::VelocitySample {
+                    timestamp: Some(chrono::Utc::now().into()),
+                    ground_speed_mps: 5.0,
+                    vertical_speed_mps: 1.0,
+                    track_angle_degrees: 12.0,
+                }],
+                time_start: Some(chrono::Utc::now().into()),
+                time_end: Some(chrono::Utc::now().into()),
+                path_alignment: Some(crate::PathAlignment {
+                    segment_index: 0,
+                    lateral_deviation_meters: 1.5,
+                    vertical_deviation_meters: -0.5,
+                    fraction_complete: 0.25,
+                    off_path: false,
+                }),
+                conflict_check: Some(crate::ConflictCheck {
+                    conflicted: false,
+                    conflict_count: 0,
+                    checked_at: Some(chrono::Utc::now().into()),
                 }),
             }],
+            partial: false,
             // isas: vec![],
         }))
     }
 
+    async fn get_tile(
+        &self,
+        request: GetTileRequest,
+    ) -> Result<tonic::Response<GetTileResponse>, tonic::Status> {
+        grpc_warn!("(get_tile MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_tile MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetTileResponse { tile: vec![] }))
+    }
+
+    async fn get_aircraft_track(
+        &self,
+        request: GetAircraftTrackRequest,
+    ) -> Result<tonic::Response<GetAircraftTrackResponse>, tonic::Status> {
+        grpc_warn!("(get_aircraft_track MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_aircraft_track MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetAircraftTrackResponse {
+            positions: vec![],
+            point_count: 0,
+        }))
+    }
+
+    async fn segmentize_path(
+        &self,
+        request: SegmentizePathRequest,
+    ) -> Result<tonic::Response<SegmentizePathResponse>, tonic::Status> {
+        grpc_warn!("(segmentize_path MOCK) {} client.", self.get_name());
+        grpc_debug!("(segmentize_path MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(SegmentizePathResponse {
+            segments: vec![],
+        }))
+    }
+
+    async fn get_flight_conflicts(
+        &self,
+        request: GetFlightConflictsRequest,
+    ) -> Result<tonic::Response<GetFlightConflictsResponse>, tonic::Status> {
+        grpc_warn!("(get_flight_conflicts MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_flight_conflicts MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetFlightConflictsResponse {
+            conflicts: vec![],
+            corridor_conflicts: vec![],
+        }))
+    }
+
+    async fn get_ingestion_status(
+        &self,
+        request: GetIngestionStatusRequest,
+    ) -> Result<tonic::Response<GetIngestionStatusResponse>, tonic::Status> {
+        grpc_warn!("(get_ingestion_status MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_ingestion_status MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetIngestionStatusResponse {
+            queues: vec![],
+            paused: false,
+        }))
+    }
+
+    async fn register_telemetry_identifier(
+        &self,
+        request: RegisterTelemetryIdentifierRequest,
+    ) -> Result<tonic::Response<RegisterTelemetryIdentifierResponse>, tonic::Status> {
+        grpc_warn!(
+            "(register_telemetry_identifier MOCK) {} client.",
+            self.get_name()
+        );
+        grpc_debug!(
+            "(register_telemetry_identifier MOCK) request: {:?}",
+            request
+        );
+        Ok(tonic::Response::new(RegisterTelemetryIdentifierResponse {
+            index: 1,
+        }))
+    }
+
+    async fn ingest_binary_telemetry(
+        &self,
+        request: IngestBinaryTelemetryRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(ingest_binary_telemetry MOCK) {} client.", self.get_name());
+        grpc_debug!("(ingest_binary_telemetry MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn remove_zones_by_source(
+        &self,
+        request: RemoveZonesBySourceRequest,
+    ) -> Result<tonic::Response<RemoveZonesBySourceResponse>, tonic::Status> {
+        grpc_warn!("(remove_zones_by_source MOCK) {} client.", self.get_name());
+        grpc_debug!("(remove_zones_by_source MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(RemoveZonesBySourceResponse {
+            removed: 0,
+        }))
+    }
+
+    async fn get_vertiport_throughput(
+        &self,
+        request: GetVertiportThroughputRequest,
+    ) -> Result<tonic::Response<GetVertiportThroughputResponse>, tonic::Status> {
+        grpc_warn!(
+            "(get_vertiport_throughput MOCK) {} client.",
+            self.get_name()
+        );
+        grpc_debug!("(get_vertiport_throughput MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetVertiportThroughputResponse {
+            series: vec![],
+        }))
+    }
+
+    async fn get_flight_segments(
+        &self,
+        request: GetFlightSegmentsRequest,
+    ) -> Result<tonic::Response<GetFlightSegmentsResponse>, tonic::Status> {
+        grpc_warn!("(get_flight_segments MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_flight_segments MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetFlightSegmentsResponse {
+            segments: vec![],
+        }))
+    }
+
+    async fn delete_zone(
+        &self,
+        request: DeleteZoneRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(delete_zone MOCK) {} client.", self.get_name());
+        grpc_debug!("(delete_zone MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn restore_zone(
+        &self,
+        request: RestoreZoneRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(restore_zone MOCK) {} client.", self.get_name());
+        grpc_debug!("(restore_zone MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn delete_flight(
+        &self,
+        request: DeleteFlightRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(delete_flight MOCK) {} client.", self.get_name());
+        grpc_debug!("(delete_flight MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn restore_flight(
+        &self,
+        request: RestoreFlightRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(restore_flight MOCK) {} client.", self.get_name());
+        grpc_debug!("(restore_flight MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn wait_for_flight_applied(
+        &self,
+        request: WaitForFlightAppliedRequest,
+    ) -> Result<tonic::Response<WaitForFlightAppliedResponse>, tonic::Status> {
+        grpc_warn!("(wait_for_flight_applied MOCK) {} client.", self.get_name());
+        grpc_debug!("(wait_for_flight_applied MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(WaitForFlightAppliedResponse {
+            completed: true,
+            success: true,
+            error: None,
+        }))
+    }
+
+    async fn get_altitude_occupancy(
+        &self,
+        request: GetAltitudeOccupancyRequest,
+    ) -> Result<tonic::Response<GetAltitudeOccupancyResponse>, tonic::Status> {
+        grpc_warn!("(get_altitude_occupancy MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_altitude_occupancy MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetAltitudeOccupancyResponse {
+            bands: vec![],
+        }))
+    }
+
+    async fn confirm_flight(
+        &self,
+        request: ConfirmFlightRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(confirm_flight MOCK) {} client.", self.get_name());
+        grpc_debug!("(confirm_flight MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn what_if(
+        &self,
+        request: WhatIfRequest,
+    ) -> Result<tonic::Response<WhatIfResponse>, tonic::Status> {
+        grpc_warn!("(what_if MOCK) {} client.", self.get_name());
+        grpc_debug!("(what_if MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(WhatIfResponse::default()))
+    }
+
+    async fn pause_ingestion(
+        &self,
+        request: PauseIngestionRequest,
+    ) -> Result<tonic::Response<PauseIngestionResponse>, tonic::Status> {
+        grpc_warn!("(pause_ingestion MOCK) {} client.", self.get_name());
+        grpc_debug!("(pause_ingestion MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(PauseIngestionResponse { idle: true }))
+    }
+
+    async fn resume_ingestion(
+        &self,
+        request: ResumeIngestionRequest,
+    ) -> Result<tonic::Response<ResumeIngestionResponse>, tonic::Status> {
+        grpc_warn!("(resume_ingestion MOCK) {} client.", self.get_name());
+        grpc_debug!("(resume_ingestion MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(ResumeIngestionResponse::default()))
+    }
+
+    async fn build_flight_path(
+        &self,
+        request: BuildFlightPathRequest,
+    ) -> Result<tonic::Response<BuildFlightPathResponse>, tonic::Status> {
+        grpc_warn!("(build_flight_path MOCK) {} client.", self.get_name());
+        grpc_debug!("(build_flight_path MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(BuildFlightPathResponse::default()))
+    }
+
+    async fn replace_zones(
+        &self,
+        request: ReplaceZonesRequest,
+    ) -> Result<tonic::Response<ReplaceZonesResponse>, tonic::Status> {
+        grpc_warn!("(replace_zones MOCK) {} client.", self.get_name());
+        grpc_debug!("(replace_zones MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(ReplaceZonesResponse::default()))
+    }
+
+    async fn get_service_info(
+        &self,
+        request: GetServiceInfoRequest,
+    ) -> Result<tonic::Response<GetServiceInfoResponse>, tonic::Status> {
+        grpc_warn!("(get_service_info MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_service_info MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetServiceInfoResponse {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: "unknown".to_string(),
+            features: vec![],
+            schema_version: Some(1),
+            database_status: "ok".to_string(),
+            service_area: None,
+            service_area_buffer_meters: 0.0,
+        }))
+    }
+
+    async fn update_corridors(
+        &self,
+        request: UpdateCorridorsRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(update_corridors MOCK) {} client.", self.get_name());
+        grpc_debug!("(update_corridors MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn get_corridors(
+        &self,
+        request: GetCorridorsRequest,
+    ) -> Result<tonic::Response<GetCorridorsResponse>, tonic::Status> {
+        grpc_warn!("(get_corridors MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_corridors MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetCorridorsResponse::default()))
+    }
+
+    async fn delete_corridor(
+        &self,
+        request: DeleteCorridorRequest,
+    ) -> Result<tonic::Response<UpdateResponse>, tonic::Status> {
+        grpc_warn!("(delete_corridor MOCK) {} client.", self.get_name());
+        grpc_debug!("(delete_corridor MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    async fn get_corridor_allocation(
+        &self,
+        request: GetCorridorAllocationRequest,
+    ) -> Result<tonic::Response<GetCorridorAllocationResponse>, tonic::Status> {
+        grpc_warn!("(get_corridor_allocation MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_corridor_allocation MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetCorridorAllocationResponse {
+            identifier: request.identifier,
+            ..Default::default()
+        }))
+    }
+
+    async fn get_flight(
+        &self,
+        request: GetFlightRequest,
+    ) -> Result<tonic::Response<GetFlightResponse>, tonic::Status> {
+        grpc_warn!("(get_flight MOCK) {} client.", self.get_name());
+        grpc_debug!("(get_flight MOCK) request: {:?}", request);
+        Ok(tonic::Response::new(GetFlightResponse {
+            flight: Some(Flight {
+                session_id: Some(request.flight_identifier),
+                ..Default::default()
+            }),
+        }))
+    }
+
     // async fn nearest_neighbors(
     //     &self,
     //     request: NearestNeighborRequest,
@@ -6,22 +6,120 @@
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ReadyRequest {}
 /// Ready Response object
-#[derive(Eq, Copy)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ReadyResponse {
-    /// True if ready
+    /// True if ready (ready or degraded)
     #[prost(bool, tag = "1")]
     pub ready: bool,
+    /// Verdict from the database connection and the freshness of each
+    ///   data domain
+    #[prost(enumeration = "Readiness", tag = "2")]
+    pub readiness: i32,
+    /// Freshness of each data domain
+    #[prost(message, repeated, tag = "3")]
+    pub domains: ::prost::alloc::vec::Vec<DomainHealth>,
+}
+/// Freshness of a data domain
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DomainHealth {
+    /// Domain name: aircraft_positions, ingestion_queue_depth,
+    ///   ingestion_queue_lag or flight_updates
+    #[prost(string, tag = "1")]
+    pub domain: ::prost::alloc::string::String,
+    /// Value compared against the threshold
+    #[prost(enumeration = "DomainStatus", tag = "2")]
+    pub status: i32,
+    /// Age in milliseconds or queue depth in messages, unset if it
+    ///   couldn't be measured
+    #[prost(uint64, optional, tag = "3")]
+    pub value: ::core::option::Option<u64>,
+    /// Threshold in the unit of the value, 0 if the probe is disabled
+    #[prost(uint64, tag = "4")]
+    pub threshold: u64,
+}
+/// Service Info Request object
+///
+/// No arguments
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetServiceInfoRequest {}
+/// Service Info Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetServiceInfoResponse {
+    /// Crate version of the service
+    #[prost(string, tag = "1")]
+    pub version: ::prost::alloc::string::String,
+    /// Git commit the service was built from, "unknown" if not available
+    #[prost(string, tag = "2")]
+    pub git_sha: ::prost::alloc::string::String,
+    /// Cargo features enabled at build time
+    #[prost(string, repeated, tag = "3")]
+    pub features: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Highest schema version applied to the database, unset if unknown
+    #[prost(int32, optional, tag = "4")]
+    pub schema_version: ::core::option::Option<i32>,
+    /// "ok" if the schema version was read, otherwise why it couldn't be
+    #[prost(string, tag = "5")]
+    pub database_status: ::prost::alloc::string::String,
+    /// Operating region as WKT, unset if geometries aren't restricted
+    #[prost(string, optional, tag = "6")]
+    pub service_area: ::core::option::Option<::prost::alloc::string::String>,
+    /// Distance outside of the service area still accepted in meters
+    #[prost(double, tag = "7")]
+    pub service_area_buffer_meters: f64,
 }
 /// General update response object
-#[derive(Eq, Copy)]
+#[derive(Eq)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateResponse {
     /// True if updated
     #[prost(bool, tag = "1")]
     pub updated: bool,
+    /// True if this is the result of a dry run and nothing was written
+    #[prost(bool, tag = "2")]
+    pub dry_run: bool,
+    /// Identifiers of the written (or, in a dry run, validated) items
+    #[prost(string, repeated, tag = "3")]
+    pub identifiers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Items that replaced a pending update of the same aircraft in the
+    ///   ingest queue
+    #[prost(uint32, tag = "4")]
+    pub coalesced: u32,
+    /// Items dropped because the ingest queue was full
+    #[prost(uint32, tag = "5")]
+    pub shed: u32,
+    /// In a dry run of a flight path update, the stored flights the path
+    ///   would conflict with
+    #[prost(string, repeated, tag = "6")]
+    pub conflict_identifiers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// An invalid string field, sent in the details of an INVALID_ARGUMENT
+///   status
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StringViolation {
+    /// Name of the request field
+    #[prost(string, tag = "1")]
+    pub field: ::prost::alloc::string::String,
+    /// Rule that failed
+    #[prost(enumeration = "StringRule", tag = "2")]
+    pub rule: i32,
+    /// Length of the string in characters, for the length rules
+    #[prost(uint32, tag = "3")]
+    pub length: u32,
+    /// Min or max length in characters, for the length rules
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+    /// First character that isn't allowed, for the character rule
+    #[prost(string, optional, tag = "5")]
+    pub character: ::core::option::Option<::prost::alloc::string::String>,
+    /// Index of the character in characters, for the character rule
+    #[prost(uint32, tag = "6")]
+    pub index: u32,
 }
 /// Geospatial Coordinates
 #[derive(Copy)]
@@ -73,6 +171,9 @@ pub struct UpdateVertiportsRequest {
     /// Nodes to update
     #[prost(message, repeated, tag = "1")]
     pub vertiports: ::prost::alloc::vec::Vec<Vertiport>,
+    /// Validate the update without writing it
+    #[prost(bool, tag = "2")]
+    pub dry_run: bool,
 }
 /// Update Waypoints Request object
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -108,6 +209,23 @@ pub struct Zone {
     /// End datetime for this zone
     #[prost(message, optional, tag = "7")]
     pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Authority that published this zone
+    #[prost(string, optional, tag = "8")]
+    pub source: ::core::option::Option<::prost::alloc::string::String>,
+    /// External reference for this zone (NOTAM id, etc.)
+    #[prost(string, optional, tag = "9")]
+    pub external_reference: ::core::option::Option<::prost::alloc::string::String>,
+    /// Operational tags ("exercise-redwing", "vip", ...), at most 10
+    #[prost(string, repeated, tag = "10")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Footprint of the zone as (E)WKT POLYGON or MULTIPOLYGON, instead of
+    ///   the vertices. SRID 4326 or none, Z ordinates are ignored.
+    #[prost(string, optional, tag = "11")]
+    pub geom_wkt: ::core::option::Option<::prost::alloc::string::String>,
+    /// Footprint of the zone as (E)WKB POLYGON or MULTIPOLYGON, instead of
+    ///   the vertices. SRID 4326 or none, Z ordinates are ignored.
+    #[prost(bytes = "vec", optional, tag = "12")]
+    pub geom_ewkb: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
 /// Update No Fly Zones Request object
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -116,6 +234,55 @@ pub struct UpdateZonesRequest {
     /// Nodes to update
     #[prost(message, repeated, tag = "1")]
     pub zones: ::prost::alloc::vec::Vec<Zone>,
+    /// Validate the update without writing it
+    #[prost(bool, tag = "2")]
+    pub dry_run: bool,
+}
+/// Remove Zones By Source Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveZonesBySourceRequest {
+    /// Authority whose zones are removed
+    #[prost(string, tag = "1")]
+    pub source: ::prost::alloc::string::String,
+}
+/// Remove Zones By Source Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveZonesBySourceResponse {
+    /// Number of zones removed
+    #[prost(uint64, tag = "1")]
+    pub removed: u64,
+}
+/// Replace Zones Request object
+///
+/// The complete set of active zones published by an authority
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplaceZonesRequest {
+    /// Authority whose zones are replaced
+    #[prost(string, tag = "1")]
+    pub source: ::prost::alloc::string::String,
+    /// The new set of zones, zones without a source are assigned to it
+    #[prost(message, repeated, tag = "2")]
+    pub zones: ::prost::alloc::vec::Vec<Zone>,
+    /// Validate the replacement without writing it
+    #[prost(bool, tag = "3")]
+    pub dry_run: bool,
+}
+/// Replace Zones Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplaceZonesResponse {
+    /// Number of zones that didn't exist yet
+    #[prost(uint64, tag = "1")]
+    pub created: u64,
+    /// Number of existing zones overwritten
+    #[prost(uint64, tag = "2")]
+    pub updated: u64,
+    /// Number of zones of the source missing from the set, removed
+    #[prost(uint64, tag = "3")]
+    pub removed: u64,
 }
 /// Update flight paths
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -142,6 +309,147 @@ pub struct UpdateFlightPathRequest {
     /// The planned end time of the flight
     #[prost(message, optional, tag = "7")]
     pub timestamp_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Allow reassigning an existing flight to a different aircraft
+    #[prost(bool, tag = "8")]
+    pub allow_rebind: bool,
+    /// The operator (owner) of the flight
+    #[prost(string, optional, tag = "9")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// SRID of the path coordinates (default 4326, WGS84)
+    /// For projected systems, longitude holds x and latitude holds y
+    #[prost(int32, optional, tag = "10")]
+    pub srid: ::core::option::Option<i32>,
+    /// Flight is a replay of a past flight, no warning is logged if it
+    ///   has already ended
+    #[prost(bool, tag = "11")]
+    pub historical: bool,
+    /// Validate the update without writing it
+    #[prost(bool, tag = "12")]
+    pub dry_run: bool,
+    /// Additional aircraft flying this flight (formation or swarm)
+    #[prost(string, repeated, tag = "13")]
+    pub aircraft_identifiers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Operational tags ("exercise-redwing", "vip", ...), at most 10
+    #[prost(string, repeated, tag = "14")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Identifier of the destination vertiport
+    #[prost(string, optional, tag = "15")]
+    pub destination_identifier: ::core::option::Option<::prost::alloc::string::String>,
+    /// Reserve the airspace for this many seconds instead of planning the
+    ///   flight. The reservation is removed unless confirmed with
+    ///   confirmFlight before it expires.
+    #[prost(uint32, optional, tag = "16")]
+    pub reservation_secs: ::core::option::Option<u32>,
+    /// Simulation run the flight belongs to, simulated flights only
+    #[prost(string, optional, tag = "17")]
+    pub scenario_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// The path as (E)WKT LINESTRING Z, instead of the path points.
+    ///   SRID 4326 or none.
+    #[prost(string, optional, tag = "18")]
+    pub geom_wkt: ::core::option::Option<::prost::alloc::string::String>,
+    /// The path as (E)WKB LINESTRING Z, instead of the path points.
+    ///   SRID 4326 or none.
+    #[prost(bytes = "vec", optional, tag = "19")]
+    pub geom_ewkb: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Segmentize Path Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentizePathRequest {
+    /// The path to segment
+    #[prost(message, repeated, tag = "1")]
+    pub path: ::prost::alloc::vec::Vec<PointZ>,
+    /// The planned start time of the path
+    #[prost(message, optional, tag = "2")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// The planned end time of the path
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Max length of each segment
+    #[prost(float, tag = "4")]
+    pub segment_length_meters: f32,
+}
+/// A timed segment of a path
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PathSegment {
+    /// Segment start point
+    #[prost(message, optional, tag = "1")]
+    pub start: ::core::option::Option<PointZ>,
+    /// Segment end point
+    #[prost(message, optional, tag = "2")]
+    pub end: ::core::option::Option<PointZ>,
+    /// Time the segment starts
+    #[prost(message, optional, tag = "3")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time the segment ends
+    #[prost(message, optional, tag = "4")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// Segmentize Path Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentizePathResponse {
+    /// Computed segments
+    #[prost(message, repeated, tag = "1")]
+    pub segments: ::prost::alloc::vec::Vec<PathSegment>,
+}
+/// Get Flight Conflicts Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightConflictsRequest {
+    /// The path to check
+    #[prost(message, repeated, tag = "1")]
+    pub path: ::prost::alloc::vec::Vec<PointZ>,
+    /// The planned start time of the path
+    #[prost(message, optional, tag = "2")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// The planned end time of the path
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Minimum separation from other flights
+    #[prost(float, tag = "4")]
+    pub distance_meters: f32,
+    /// Only check flights matching this filter
+    #[prost(message, optional, tag = "5")]
+    pub tag_filter: ::core::option::Option<TagFilter>,
+}
+/// A stored flight segment conflicting with a checked path
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlightConflict {
+    /// The conflicting flight
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+    /// The aircraft assigned to the conflicting flight
+    #[prost(string, tag = "2")]
+    pub aircraft_identifier: ::prost::alloc::string::String,
+    /// The conflicting segment of the stored flight
+    #[prost(message, repeated, tag = "3")]
+    pub segment: ::prost::alloc::vec::Vec<PointZ>,
+    /// The point on the stored segment closest to the checked path
+    #[prost(message, optional, tag = "4")]
+    pub closest_point: ::core::option::Option<PointZ>,
+    /// Minimum 3D distance between the stored segment and the checked path
+    #[prost(double, tag = "5")]
+    pub distance_meters: f64,
+    /// Start of the overlapping time interval
+    #[prost(message, optional, tag = "6")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// End of the overlapping time interval
+    #[prost(message, optional, tag = "7")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// Get Flight Conflicts Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightConflictsResponse {
+    /// Conflicts with stored flights
+    #[prost(message, repeated, tag = "1")]
+    pub conflicts: ::prost::alloc::vec::Vec<FlightConflict>,
+    /// Corridors the path passes through while they are full
+    #[prost(message, repeated, tag = "2")]
+    pub corridor_conflicts: ::prost::alloc::vec::Vec<CorridorConflict>,
 }
 /// Best Path Request object
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -168,6 +476,25 @@ pub struct BestPathRequest {
     /// Number of paths to return
     #[prost(int32, tag = "7")]
     pub limit: i32,
+    /// Treat time_start as the earliest departure and time_end as the
+    ///   latest arrival, choosing the departure time within that window
+    #[prost(bool, tag = "8")]
+    pub soft_window: bool,
+    /// Cruise velocity used to estimate flight duration with a soft window
+    ///   (defaults to 20 m/s)
+    #[prost(float, optional, tag = "9")]
+    pub cruise_velocity_mps: ::core::option::Option<f32>,
+    /// Include a breakdown of the routing cost in each path
+    #[prost(bool, tag = "10")]
+    pub verbose: bool,
+    /// Only avoid zones and flights matching this filter
+    #[prost(message, optional, tag = "11")]
+    pub tag_filter: ::core::option::Option<TagFilter>,
+    /// Max segments (legs between consecutive nodes) of each path. Routes
+    ///   needing more segments are not considered, if none is left the
+    ///   request fails with no path found.
+    #[prost(uint32, optional, tag = "12")]
+    pub max_segments: ::core::option::Option<u32>,
 }
 /// / Geospatial Point with Altitude
 #[derive(Copy)]
@@ -211,6 +538,45 @@ pub struct Path {
     /// Total distance of this path
     #[prost(float, tag = "2")]
     pub distance_meters: f32,
+    /// Chosen departure time
+    #[prost(message, optional, tag = "3")]
+    pub time_departure: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Expected arrival time
+    #[prost(message, optional, tag = "4")]
+    pub time_arrival: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Routing cost breakdown (only with a verbose request)
+    #[prost(message, optional, tag = "5")]
+    pub cost: ::core::option::Option<PathCost>,
+}
+/// Routing cost of a segment between two consecutive path nodes
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PathSegmentCost {
+    /// Index of the segment's starting node
+    #[prost(int32, tag = "1")]
+    pub index: i32,
+    /// Cost of the progress made toward the target
+    #[prost(float, tag = "2")]
+    pub distance_cost: f32,
+    /// Cost of the distance flown away from the target, incurred going
+    ///   around no-fly zones
+    #[prost(float, tag = "3")]
+    pub zone_avoidance_penalty: f32,
+}
+/// Routing cost breakdown of a path
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PathCost {
+    /// Total routing cost, the sum of the segment costs
+    #[prost(float, tag = "1")]
+    pub total_cost: f32,
+    /// Multiplier applied to segment distances, raised each time the path
+    ///   is re-checked with shorter segments around existing flights
+    #[prost(float, tag = "2")]
+    pub segment_factor: f32,
+    /// Cost of each segment
+    #[prost(message, repeated, tag = "3")]
+    pub segments: ::prost::alloc::vec::Vec<PathSegmentCost>,
 }
 /// Best Path Response object
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -220,6 +586,31 @@ pub struct BestPathResponse {
     #[prost(message, repeated, tag = "1")]
     pub paths: ::prost::alloc::vec::Vec<Path>,
 }
+/// Get Tile Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTileRequest {
+    /// Zoom level
+    #[prost(uint32, tag = "1")]
+    pub z: u32,
+    /// Tile column
+    #[prost(uint32, tag = "2")]
+    pub x: u32,
+    /// Tile row
+    #[prost(uint32, tag = "3")]
+    pub y: u32,
+    /// Layers to include in the tile
+    #[prost(enumeration = "TileLayer", repeated, tag = "4")]
+    pub layers: ::prost::alloc::vec::Vec<i32>,
+}
+/// Get Tile Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTileResponse {
+    /// Mapbox Vector Tile (MVT) encoded tile
+    #[prost(bytes = "vec", tag = "1")]
+    pub tile: ::prost::alloc::vec::Vec<u8>,
+}
 /// Get Flights Request object
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -242,223 +633,2214 @@ pub struct GetFlightsRequest {
     /// Time window end
     #[prost(message, optional, tag = "6")]
     pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Only return flights and aircraft of this operator
+    #[prost(string, optional, tag = "7")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Result ordering (default FLIGHT_ORDER_FLIGHT_IDENTIFIER)
+    /// Ties are broken by flight identifier, then aircraft identifier
+    #[prost(enumeration = "FlightOrder", tag = "8")]
+    pub order_by: i32,
+    /// Only return flights matching this filter
+    #[prost(message, optional, tag = "9")]
+    pub tag_filter: ::core::option::Option<TagFilter>,
+    /// Recent velocity samples to include per aircraft, at most 10
+    ///   (default 0, none)
+    #[prost(uint32, tag = "10")]
+    pub velocity_samples: u32,
+    /// Only return flight identifiers and time bounds, without aircraft
+    ///   state, positions or velocity samples (default false)
+    #[prost(bool, tag = "11")]
+    pub skeleton_only: bool,
+    /// Include where each aircraft is along its planned path (default false)
+    #[prost(bool, tag = "12")]
+    pub path_alignment: bool,
 }
-/// Timestamped position of an aircraft
+/// Get Aircraft Track Request object
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct TimePosition {
-    /// Aircraft Position
-    #[prost(message, optional, tag = "1")]
-    pub position: ::core::option::Option<PointZ>,
-    /// Timestamp
+pub struct GetAircraftTrackRequest {
+    /// Aircraft identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// Time window start
     #[prost(message, optional, tag = "2")]
-    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time window end
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Return at most one position per N seconds (0 for raw data)
+    #[prost(uint32, tag = "4")]
+    pub resolution_seconds: u32,
+    /// Only return the track if the aircraft belongs to this operator
+    #[prost(string, optional, tag = "5")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// SRID of the returned positions (default 4326, WGS84)
+    /// For projected systems, longitude holds x and latitude holds y
+    #[prost(int32, optional, tag = "6")]
+    pub srid: ::core::option::Option<i32>,
 }
-/// The state of the aircraft including position, status, and velocity
+/// Get Aircraft Track Response object
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct AircraftState {
-    /// The timestamp of the state
-    #[prost(message, optional, tag = "1")]
-    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
-    /// The operational status of the aircraft
-    #[prost(enumeration = "crate::prelude::OperationalStatus", tag = "2")]
-    pub status: i32,
-    /// The position of the aircraft
-    #[prost(message, optional, tag = "3")]
-    pub position: ::core::option::Option<PointZ>,
-    /// The track angle of the aircraft
-    #[prost(float, tag = "4")]
-    pub track_angle_degrees: f32,
-    /// The ground speed of the aircraft
-    #[prost(float, tag = "5")]
-    pub ground_speed_mps: f32,
-    /// The vertical speed of the aircraft
-    #[prost(float, tag = "6")]
-    pub vertical_speed_mps: f32,
+pub struct GetAircraftTrackResponse {
+    /// Timestamped positions of the aircraft
+    #[prost(message, repeated, tag = "1")]
+    pub positions: ::prost::alloc::vec::Vec<TimePosition>,
+    /// Number of positions returned
+    #[prost(uint32, tag = "2")]
+    pub point_count: u32,
 }
-/// Aircraft Flight Information
+/// Get Ingestion Status Request object
+///
+/// No arguments
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct Flight {
-    /// Flight identifier, if on assigned flight
-    #[prost(string, optional, tag = "1")]
-    pub session_id: ::core::option::Option<::prost::alloc::string::String>,
-    /// Aircraft identifier
-    #[prost(string, optional, tag = "2")]
-    pub aircraft_id: ::core::option::Option<::prost::alloc::string::String>,
-    /// If this is a simulated aircraft
-    #[prost(bool, tag = "3")]
-    pub simulated: bool,
-    /// The timestamped positions of the aircraft
-    #[prost(message, repeated, tag = "4")]
-    pub positions: ::prost::alloc::vec::Vec<TimePosition>,
+pub struct GetIngestionStatusRequest {}
+/// Status of a Redis ingestion queue
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueueStatus {
+    /// Queue key
+    #[prost(string, tag = "1")]
+    pub queue: ::prost::alloc::string::String,
+    /// Number of messages waiting to be processed
+    #[prost(uint64, tag = "2")]
+    pub depth: u64,
+    /// Age of the oldest waiting message, if any
+    #[prost(uint64, optional, tag = "3")]
+    pub oldest_age_ms: ::core::option::Option<u64>,
+}
+/// Get Ingestion Status Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetIngestionStatusResponse {
+    /// Status of each ingestion queue
+    #[prost(message, repeated, tag = "1")]
+    pub queues: ::prost::alloc::vec::Vec<QueueStatus>,
+    /// True if the Redis consumers are paused
+    #[prost(bool, tag = "7")]
+    pub paused: bool,
+}
+/// Pause Ingestion Request object
+///
+/// No arguments
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PauseIngestionRequest {}
+/// Pause Ingestion Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PauseIngestionResponse {
+    /// True if no consumer was still processing messages when the call
+    ///   returned, false if the wait timed out
+    #[prost(bool, tag = "1")]
+    pub idle: bool,
+}
+/// Resume Ingestion Request object
+///
+/// No arguments
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ResumeIngestionRequest {}
+/// Resume Ingestion Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ResumeIngestionResponse {
+    /// True if the consumers were paused
+    #[prost(bool, tag = "1")]
+    pub was_paused: bool,
+}
+/// A routing node given by its type and identifier
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeRef {
+    /// The node type
+    #[prost(enumeration = "NodeType", tag = "1")]
+    pub node_type: i32,
+    /// The node identifier
+    #[prost(string, tag = "2")]
+    pub identifier: ::prost::alloc::string::String,
+}
+/// Build Flight Path Request object
+///
+/// The flight is routed between consecutive nodes and stored like an
+///   updateFlightPath request
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BuildFlightPathRequest {
+    /// The unique identifier for the flight
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+    /// The unique identifier for the aircraft
+    #[prost(string, tag = "2")]
+    pub aircraft_identifier: ::prost::alloc::string::String,
     /// The type of aircraft
-    #[prost(enumeration = "crate::prelude::AircraftType", tag = "5")]
+    #[prost(enumeration = "crate::prelude::AircraftType", tag = "3")]
     pub aircraft_type: i32,
-    /// The state of the aircraft
+    /// The nodes to fly through in order, an aircraft may only be the
+    ///   first node and every other node must be a vertiport
+    #[prost(message, repeated, tag = "4")]
+    pub nodes: ::prost::alloc::vec::Vec<NodeRef>,
+    /// Earliest departure from the first node
+    #[prost(message, optional, tag = "5")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Latest arrival at the last node
     #[prost(message, optional, tag = "6")]
-    pub state: ::core::option::Option<AircraftState>,
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Cruise velocity used to estimate leg durations (defaults to 20 m/s)
+    #[prost(float, optional, tag = "7")]
+    pub cruise_velocity_mps: ::core::option::Option<f32>,
 }
-/// Get Flights Response object
+/// Build Flight Path Response object
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetFlightsResponse {
-    /// Flights in the requested zone
+pub struct BuildFlightPathResponse {
+    /// The stored path
     #[prost(message, repeated, tag = "1")]
-    pub flights: ::prost::alloc::vec::Vec<Flight>,
+    pub path: ::prost::alloc::vec::Vec<PointZ>,
+    /// Total distance of the path
+    #[prost(float, tag = "2")]
+    pub distance_meters: f32,
+    /// Departure from the first node
+    #[prost(message, optional, tag = "3")]
+    pub time_departure: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Arrival at the last node
+    #[prost(message, optional, tag = "4")]
+    pub time_arrival: ::core::option::Option<::lib_common::time::Timestamp>,
 }
-/// The nodes involved in the best path request
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum NodeType {
-    /// Vertiport
-    Vertiport = 0,
-    /// Waypoint
-    Waypoint = 1,
-    /// Aircraft
-    Aircraft = 2,
+/// Register Telemetry Identifier Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterTelemetryIdentifierRequest {
+    /// Aircraft identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
 }
-impl NodeType {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            NodeType::Vertiport => "VERTIPORT",
-            NodeType::Waypoint => "WAYPOINT",
-            NodeType::Aircraft => "AIRCRAFT",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "VERTIPORT" => Some(Self::Vertiport),
-            "WAYPOINT" => Some(Self::Waypoint),
-            "AIRCRAFT" => Some(Self::Aircraft),
-            _ => None,
-        }
-    }
+/// Register Telemetry Identifier Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterTelemetryIdentifierResponse {
+    /// Index to use for this aircraft in binary telemetry records
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
 }
-/// Airspace Zone Type
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum ZoneType {
-    /// Vertiport
-    Port = 0,
-    /// Restriction
-    Restriction = 1,
+/// Ingest Binary Telemetry Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IngestBinaryTelemetryRequest {
+    /// Concatenated fixed-layout telemetry records
+    #[prost(bytes = "vec", tag = "1")]
+    pub payload: ::prost::alloc::vec::Vec<u8>,
+    /// Validate the update without writing it
+    #[prost(bool, tag = "2")]
+    pub dry_run: bool,
 }
-impl ZoneType {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            ZoneType::Port => "PORT",
-            ZoneType::Restriction => "RESTRICTION",
+/// Get Vertiport Throughput Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetVertiportThroughputRequest {
+    /// Vertiport identifier ("unknown" for flights not near a vertiport)
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// Time range start
+    #[prost(message, optional, tag = "2")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time range end
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// Flights through a vertiport in one hour
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VertiportThroughput {
+    /// Start of the hour (UTC)
+    #[prost(message, optional, tag = "1")]
+    pub hour: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Number of departures
+    #[prost(uint64, tag = "2")]
+    pub departures: u64,
+    /// Number of arrivals
+    #[prost(uint64, tag = "3")]
+    pub arrivals: u64,
+}
+/// Get Vertiport Throughput Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetVertiportThroughputResponse {
+    /// Hourly series, hours without flights are omitted
+    #[prost(message, repeated, tag = "1")]
+    pub series: ::prost::alloc::vec::Vec<VertiportThroughput>,
+}
+/// Get Flight Segments Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightSegmentsRequest {
+    /// Flight identifier
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+    /// Max number of segments to return
+    #[prost(uint32, optional, tag = "2")]
+    pub limit: ::core::option::Option<u32>,
+    /// Number of segments to skip from the start of the path
+    #[prost(uint32, optional, tag = "3")]
+    pub offset: ::core::option::Option<u32>,
+}
+/// A stored segment of a flight path
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlightSegment {
+    /// Position of the segment along the path, starting at 0
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// Segment points
+    #[prost(message, repeated, tag = "2")]
+    pub points: ::prost::alloc::vec::Vec<PointZ>,
+    /// Time the segment starts
+    #[prost(message, optional, tag = "3")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time the segment ends
+    #[prost(message, optional, tag = "4")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// Get Flight Segments Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightSegmentsResponse {
+    /// Segments ordered along the path
+    #[prost(message, repeated, tag = "1")]
+    pub segments: ::prost::alloc::vec::Vec<FlightSegment>,
+}
+/// Delete Zone Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteZoneRequest {
+    /// Zone identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+}
+/// Restore Zone Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreZoneRequest {
+    /// Zone identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+}
+/// Delete Flight Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteFlightRequest {
+    /// Flight identifier
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+    /// Why the flight was cancelled
+    #[prost(string, optional, tag = "2")]
+    pub reason: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Restore Flight Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreFlightRequest {
+    /// Flight identifier
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+}
+/// Confirm Flight Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmFlightRequest {
+    /// Identifier of the reserved flight
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+}
+/// What-If Request object
+///
+/// A synthetic aircraft state, evaluated without being stored
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WhatIfRequest {
+    /// Position of the synthetic aircraft
+    #[prost(message, optional, tag = "1")]
+    pub position: ::core::option::Option<PointZ>,
+    /// Ground speed in meters per second
+    #[prost(float, tag = "2")]
+    pub ground_speed_mps: f32,
+    /// Track angle in degrees clockwise from true north
+    #[prost(float, tag = "3")]
+    pub track_angle_degrees: f32,
+    /// Vertical speed in meters per second, positive up
+    #[prost(float, tag = "4")]
+    pub vertical_speed_mps: f32,
+    /// Time of the state, now if unset
+    #[prost(message, optional, tag = "5")]
+    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Candidate path continuing from the position, the state is
+    ///   extrapolated at a constant velocity if empty
+    #[prost(message, repeated, tag = "6")]
+    pub path: ::prost::alloc::vec::Vec<PointZ>,
+    /// Time evaluated ahead of the state, 60 seconds if unset
+    #[prost(uint32, tag = "7")]
+    pub lookahead_secs: u32,
+    /// Minimum separation from other flights and traffic
+    #[prost(float, tag = "8")]
+    pub distance_meters: f32,
+    /// Only check zones and flights matching this filter
+    #[prost(message, optional, tag = "9")]
+    pub tag_filter: ::core::option::Option<TagFilter>,
+}
+/// A zone entered by a what-if path
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WhatIfZoneHit {
+    /// The zone identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// The zone type
+    #[prost(enumeration = "ZoneType", tag = "2")]
+    pub zone_type: i32,
+}
+/// A live aircraft near a what-if state
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NearbyAircraft {
+    /// The aircraft identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// Last reported position
+    #[prost(message, optional, tag = "2")]
+    pub position: ::core::option::Option<PointZ>,
+    /// 3D distance from the synthetic aircraft in meters
+    #[prost(double, tag = "3")]
+    pub distance_meters: f64,
+}
+/// What-If Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WhatIfResponse {
+    /// Zones entered by the path, in order
+    #[prost(message, repeated, tag = "1")]
+    pub zones: ::prost::alloc::vec::Vec<WhatIfZoneHit>,
+    /// Conflicts with stored flights, one per flight
+    #[prost(message, repeated, tag = "2")]
+    pub conflicts: ::prost::alloc::vec::Vec<FlightConflict>,
+    /// Live aircraft within the separation distance, nearest first
+    #[prost(message, repeated, tag = "3")]
+    pub traffic: ::prost::alloc::vec::Vec<NearbyAircraft>,
+}
+/// Stream Compliance Alerts Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamComplianceAlertsRequest {
+    /// Last alert received before reconnecting, persisted alerts after it
+    ///   are sent first (live alerts only if unset)
+    #[prost(uint64, optional, tag = "1")]
+    pub last_event_id: ::core::option::Option<u64>,
+}
+/// An airborne aircraft without an active flight
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ComplianceAlert {
+    /// The aircraft identifier
+    #[prost(string, tag = "1")]
+    pub aircraft_identifier: ::prost::alloc::string::String,
+    /// Last known position of the aircraft
+    #[prost(message, optional, tag = "2")]
+    pub position: ::core::option::Option<PointZ>,
+    /// When the aircraft was first seen airborne without a flight
+    #[prost(message, optional, tag = "3")]
+    pub unbound_since: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time the aircraft has been airborne without a flight
+    #[prost(uint64, tag = "4")]
+    pub unbound_seconds: u64,
+    /// Id of the alert in the event backlog, increasing (0 if it couldn't
+    ///   be persisted)
+    #[prost(uint64, tag = "5")]
+    pub event_id: u64,
+}
+/// Stream Zone Changes Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamZoneChangesRequest {
+    /// Last change received before reconnecting, persisted changes after it
+    ///   are sent first (live changes only if unset)
+    #[prost(uint64, optional, tag = "1")]
+    pub last_event_id: ::core::option::Option<u64>,
+}
+/// A committed change of stored zones
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ZoneChanged {
+    /// The change type
+    #[prost(enumeration = "ZoneChangeType", tag = "1")]
+    pub change_type: i32,
+    /// Identifiers of the changed zones
+    #[prost(string, repeated, tag = "2")]
+    pub identifiers: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// When the change was committed
+    #[prost(message, optional, tag = "3")]
+    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Id of the change in the event backlog, increasing (0 if it couldn't
+    ///   be persisted)
+    #[prost(uint64, tag = "4")]
+    pub event_id: u64,
+}
+/// Stream Flight Events Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamFlightEventsRequest {
+    /// Last event received before reconnecting, persisted events after it
+    ///   are sent first (live events only if unset)
+    #[prost(uint64, optional, tag = "1")]
+    pub last_event_id: ::core::option::Option<u64>,
+    /// Only stream events of flights of this operator
+    #[prost(string, optional, tag = "2")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A committed lifecycle step of a flight
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlightEvent {
+    /// The event type
+    #[prost(enumeration = "FlightEventType", tag = "1")]
+    pub event_type: i32,
+    /// The flight identifier
+    #[prost(string, tag = "2")]
+    pub flight_identifier: ::prost::alloc::string::String,
+    /// When the step was committed
+    #[prost(message, optional, tag = "3")]
+    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Id of the event in the event backlog, increasing (0 if it couldn't
+    ///   be persisted)
+    #[prost(uint64, tag = "4")]
+    pub event_id: u64,
+    /// The operator of the flight
+    #[prost(string, optional, tag = "5")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Wait For Flight Applied Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WaitForFlightAppliedRequest {
+    /// Correlation id of the queued flight path
+    #[prost(string, tag = "1")]
+    pub correlation_id: ::prost::alloc::string::String,
+    /// Max time to wait in milliseconds (capped by the server)
+    #[prost(uint64, tag = "2")]
+    pub timeout_ms: u64,
+}
+/// Wait For Flight Applied Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WaitForFlightAppliedResponse {
+    /// If the queued flight path was processed before the timeout
+    #[prost(bool, tag = "1")]
+    pub completed: bool,
+    /// If the flight path was stored
+    #[prost(bool, tag = "2")]
+    pub success: bool,
+    /// Reason the flight path was rejected
+    #[prost(string, optional, tag = "3")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Stream Aircraft GeoJSON Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamAircraftGeoJsonRequest {
+    /// Include simulated aircraft
+    #[prost(bool, tag = "1")]
+    pub include_simulated: bool,
+    /// Only stream aircraft of this operator
+    #[prost(string, optional, tag = "2")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A chunk of a streamed GeoJSON export
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GeoJsonChunk {
+    /// Position of this chunk in the stream, starting at 0
+    #[prost(uint32, tag = "1")]
+    pub sequence: u32,
+    /// Newline-delimited GeoJSON Features, each line is a complete Feature
+    #[prost(string, tag = "2")]
+    pub features: ::prost::alloc::string::String,
+    /// Number of features in this chunk
+    #[prost(uint32, tag = "3")]
+    pub feature_count: u32,
+}
+/// Export CSV Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportCsvRequest {
+    /// The dataset to export
+    #[prost(enumeration = "CsvDataset", tag = "1")]
+    pub dataset: i32,
+    /// Time window start
+    #[prost(message, optional, tag = "2")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time window end
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// GPS Rectangular Window Corner Min X
+    #[prost(double, tag = "4")]
+    pub window_min_x: f64,
+    /// GPS Rectangular Window Corner Min Y
+    #[prost(double, tag = "5")]
+    pub window_min_y: f64,
+    /// GPS Rectangular Window Corner Max X
+    #[prost(double, tag = "6")]
+    pub window_max_x: f64,
+    /// GPS Rectangular Window Corner Max Y
+    #[prost(double, tag = "7")]
+    pub window_max_y: f64,
+}
+/// A chunk of a streamed CSV export
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CsvChunk {
+    /// Position of this chunk in the stream, starting at 0
+    #[prost(uint32, tag = "1")]
+    pub sequence: u32,
+    /// RFC 4180 records, each ending with CRLF. The first chunk starts
+    ///   with the header record.
+    #[prost(string, tag = "2")]
+    pub data: ::prost::alloc::string::String,
+    /// Number of data records in this chunk, the header isn't counted
+    #[prost(uint32, tag = "3")]
+    pub row_count: u32,
+}
+/// Get Altitude Occupancy Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAltitudeOccupancyRequest {
+    /// Vertices of the area, the first and last must be equal
+    #[prost(message, repeated, tag = "1")]
+    pub vertices: ::prost::alloc::vec::Vec<Coordinates>,
+    /// Time window start
+    #[prost(message, optional, tag = "2")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Time window end
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Bottom of the lowest band in meters
+    #[prost(float, tag = "4")]
+    pub altitude_meters_min: f32,
+    /// Top of the highest band in meters
+    #[prost(float, tag = "5")]
+    pub altitude_meters_max: f32,
+    /// Height of each band in meters
+    #[prost(float, tag = "6")]
+    pub band_height_meters: f32,
+}
+/// Flight segments and aircraft in one altitude band
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AltitudeBand {
+    /// Bottom of the band in meters
+    #[prost(float, tag = "1")]
+    pub altitude_meters_min: f32,
+    /// Top of the band in meters
+    #[prost(float, tag = "2")]
+    pub altitude_meters_max: f32,
+    /// Number of flight segments overlapping the band
+    #[prost(uint64, tag = "3")]
+    pub segment_count: u64,
+    /// Number of aircraft currently in the band
+    #[prost(uint64, tag = "4")]
+    pub aircraft_count: u64,
+}
+/// Get Altitude Occupancy Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAltitudeOccupancyResponse {
+    /// Bands from lowest to highest, including empty bands
+    #[prost(message, repeated, tag = "1")]
+    pub bands: ::prost::alloc::vec::Vec<AltitudeBand>,
+}
+/// Filter on operational tags
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagFilter {
+    /// Tags to match, no filtering if empty
+    #[prost(string, repeated, tag = "1")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// How the tags are matched
+    #[prost(enumeration = "TagMatch", tag = "2")]
+    pub mode: i32,
+}
+/// Timestamped position of an aircraft
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TimePosition {
+    /// Aircraft Position
+    #[prost(message, optional, tag = "1")]
+    pub position: ::core::option::Option<PointZ>,
+    /// Timestamp
+    #[prost(message, optional, tag = "2")]
+    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Width of the archive bucket this position summarizes in seconds,
+    ///   0 for full-rate positions
+    #[prost(uint32, tag = "3")]
+    pub bucket_seconds: u32,
+}
+/// The state of the aircraft including position, status, and velocity
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AircraftState {
+    /// The timestamp of the state
+    #[prost(message, optional, tag = "1")]
+    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// The operational status of the aircraft
+    #[prost(enumeration = "crate::prelude::OperationalStatus", tag = "2")]
+    pub status: i32,
+    /// The position of the aircraft
+    #[prost(message, optional, tag = "3")]
+    pub position: ::core::option::Option<PointZ>,
+    /// The track angle of the aircraft
+    #[prost(float, tag = "4")]
+    pub track_angle_degrees: f32,
+    /// The ground speed of the aircraft
+    #[prost(float, tag = "5")]
+    pub ground_speed_mps: f32,
+    /// The vertical speed of the aircraft
+    #[prost(float, tag = "6")]
+    pub vertical_speed_mps: f32,
+    /// The horizontal accuracy of the position in meters, if reported
+    #[prost(float, optional, tag = "7")]
+    pub horizontal_accuracy_meters: ::core::option::Option<f32>,
+    /// The vertical accuracy of the position in meters, if reported
+    #[prost(float, optional, tag = "8")]
+    pub vertical_accuracy_meters: ::core::option::Option<f32>,
+}
+/// A reported velocity of an aircraft
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VelocitySample {
+    /// When the velocity was reported
+    #[prost(message, optional, tag = "1")]
+    pub timestamp: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// The ground speed of the aircraft
+    #[prost(float, tag = "2")]
+    pub ground_speed_mps: f32,
+    /// The vertical speed of the aircraft
+    #[prost(float, tag = "3")]
+    pub vertical_speed_mps: f32,
+    /// The track angle of the aircraft
+    #[prost(float, tag = "4")]
+    pub track_angle_degrees: f32,
+}
+/// The position of an aircraft relative to its planned path
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PathAlignment {
+    /// Index of the path segment closest to the aircraft, starting at 0
+    #[prost(uint32, tag = "1")]
+    pub segment_index: u32,
+    /// Horizontal distance from the closest point of the path in meters
+    #[prost(double, tag = "2")]
+    pub lateral_deviation_meters: f64,
+    /// Altitude above (positive) or below (negative) the closest point of
+    ///   the path in meters
+    #[prost(double, tag = "3")]
+    pub vertical_deviation_meters: f64,
+    /// Fraction of the path already travelled (0.0 to 1.0)
+    #[prost(double, tag = "4")]
+    pub fraction_complete: f64,
+    /// If the aircraft is further from the path than the tolerances
+    #[prost(bool, tag = "5")]
+    pub off_path: bool,
+}
+/// Latest result of the periodic conflict check of a planned flight
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConflictCheck {
+    /// If the flight came too close to another flight or crossed a zone
+    #[prost(bool, tag = "1")]
+    pub conflicted: bool,
+    /// Number of conflicting flights and zones
+    #[prost(uint32, tag = "2")]
+    pub conflict_count: u32,
+    /// When the check ran
+    #[prost(message, optional, tag = "3")]
+    pub checked_at: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// A previously clean flight found conflicting by the periodic conflict
+///   check
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlightConflicted {
+    /// The flight identifier
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+    /// The check that found the conflicts
+    #[prost(message, optional, tag = "2")]
+    pub conflict_check: ::core::option::Option<ConflictCheck>,
+    /// Id of the event in the event backlog, increasing (0 if it couldn't
+    ///   be persisted)
+    #[prost(uint64, tag = "3")]
+    pub event_id: u64,
+}
+/// Stream Flight Conflicts Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamFlightConflictsRequest {
+    /// Last event received before reconnecting, persisted events after it
+    ///   are sent first (live events only if unset)
+    #[prost(uint64, optional, tag = "1")]
+    pub last_event_id: ::core::option::Option<u64>,
+}
+/// Aircraft Flight Information
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Flight {
+    /// Flight identifier, if on assigned flight
+    #[prost(string, optional, tag = "1")]
+    pub session_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Aircraft identifier
+    #[prost(string, optional, tag = "2")]
+    pub aircraft_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// If this is a simulated aircraft
+    #[prost(bool, tag = "3")]
+    pub simulated: bool,
+    /// The timestamped positions of the aircraft
+    #[prost(message, repeated, tag = "4")]
+    pub positions: ::prost::alloc::vec::Vec<TimePosition>,
+    /// The type of aircraft
+    #[prost(enumeration = "crate::prelude::AircraftType", tag = "5")]
+    pub aircraft_type: i32,
+    /// The state of the aircraft
+    #[prost(message, optional, tag = "6")]
+    pub state: ::core::option::Option<AircraftState>,
+    /// Recent velocity samples of the aircraft, oldest first. Fewer than
+    ///   requested if the aircraft hasn't reported as many.
+    #[prost(message, repeated, tag = "7")]
+    pub velocity_samples: ::prost::alloc::vec::Vec<VelocitySample>,
+    /// Start of the flight plan, if on assigned flight
+    #[prost(message, optional, tag = "8")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// End of the flight plan, if on assigned flight
+    #[prost(message, optional, tag = "9")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Where the aircraft is along its planned path, if requested and the
+    ///   aircraft has reported a position on a flight with a stored path
+    #[prost(message, optional, tag = "10")]
+    pub path_alignment: ::core::option::Option<PathAlignment>,
+    /// Latest periodic conflict check of the flight, if it was checked
+    #[prost(message, optional, tag = "11")]
+    pub conflict_check: ::core::option::Option<ConflictCheck>,
+}
+/// Get Flights Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightsResponse {
+    /// Flights in the requested zone
+    #[prost(message, repeated, tag = "1")]
+    pub flights: ::prost::alloc::vec::Vec<Flight>,
+    /// If aircraft state, positions and velocity samples were left out,
+    ///   because they were not requested or the deadline was too close
+    #[prost(bool, tag = "2")]
+    pub partial: bool,
+}
+/// Get Flight Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightRequest {
+    /// Flight identifier
+    #[prost(string, tag = "1")]
+    pub flight_identifier: ::prost::alloc::string::String,
+}
+/// Get Flight Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFlightResponse {
+    /// The flight, with the state of its aircraft if it reported a position
+    ///   and the latest conflict check if it was checked
+    #[prost(message, optional, tag = "1")]
+    pub flight: ::core::option::Option<Flight>,
+}
+/// A tube around a centerline that a limited number of flights may occupy
+///   at the same time
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Corridor {
+    /// Corridor identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// Centerline of the corridor, at least two points
+    #[prost(message, repeated, tag = "2")]
+    pub centerline: ::prost::alloc::vec::Vec<PointZ>,
+    /// Width of the corridor in meters
+    #[prost(double, tag = "3")]
+    pub width_meters: f64,
+    /// Height of the corridor in meters
+    #[prost(double, tag = "4")]
+    pub height_meters: f64,
+    /// Max number of flights in the corridor at the same time
+    #[prost(uint32, tag = "5")]
+    pub capacity: u32,
+}
+/// Update Corridors Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateCorridorsRequest {
+    /// Corridors to create or overwrite
+    #[prost(message, repeated, tag = "1")]
+    pub corridors: ::prost::alloc::vec::Vec<Corridor>,
+}
+/// Get Corridors Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCorridorsRequest {}
+/// Get Corridors Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCorridorsResponse {
+    /// All corridors, ordered by identifier
+    #[prost(message, repeated, tag = "1")]
+    pub corridors: ::prost::alloc::vec::Vec<Corridor>,
+}
+/// Delete Corridor Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteCorridorRequest {
+    /// Corridor identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+}
+/// Get Corridor Allocation Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCorridorAllocationRequest {
+    /// Corridor identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// Start of the time range
+    #[prost(message, optional, tag = "2")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// End of the time range
+    #[prost(message, optional, tag = "3")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Length of a time slice in seconds
+    #[prost(uint32, tag = "4")]
+    pub slice_seconds: u32,
+}
+/// Flights in a corridor during a time slice
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CorridorSlice {
+    /// Start of the slice
+    #[prost(message, optional, tag = "1")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// End of the slice, the last slice ends with the time range
+    #[prost(message, optional, tag = "2")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// Flights with a segment in the corridor during the slice
+    #[prost(uint32, tag = "3")]
+    pub flight_count: u32,
+    /// True if more flights than the capacity of the corridor
+    #[prost(bool, tag = "4")]
+    pub over_capacity: bool,
+}
+/// Get Corridor Allocation Response object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCorridorAllocationResponse {
+    /// Corridor identifier
+    #[prost(string, tag = "1")]
+    pub identifier: ::prost::alloc::string::String,
+    /// Max number of flights in the corridor at the same time
+    #[prost(uint32, tag = "2")]
+    pub capacity: u32,
+    /// Every slice of the time range, oldest first
+    #[prost(message, repeated, tag = "3")]
+    pub slices: ::prost::alloc::vec::Vec<CorridorSlice>,
+}
+/// A checked path passing through a corridor that is already full
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CorridorConflict {
+    /// Corridor identifier
+    #[prost(string, tag = "1")]
+    pub corridor_identifier: ::prost::alloc::string::String,
+    /// Max number of flights in the corridor at the same time
+    #[prost(uint32, tag = "2")]
+    pub capacity: u32,
+    /// Stored flights in the corridor while the path is in it
+    #[prost(uint32, tag = "3")]
+    pub flight_count: u32,
+    /// When the path enters the full corridor
+    #[prost(message, optional, tag = "4")]
+    pub time_start: ::core::option::Option<::lib_common::time::Timestamp>,
+    /// When the path leaves the full corridor
+    #[prost(message, optional, tag = "5")]
+    pub time_end: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// Stream Aircraft Positions Request object
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamAircraftPositionsRequest {
+    /// Vertices of the region, closed automatically (all positions if
+    ///   empty)
+    #[prost(message, repeated, tag = "1")]
+    pub vertices: ::prost::alloc::vec::Vec<Coordinates>,
+    /// Only send ENTER and LEAVE events
+    #[prost(bool, tag = "2")]
+    pub events_only: bool,
+    /// Min time between UPDATE events of an aircraft in seconds (every
+    ///   update if 0)
+    #[prost(uint32, tag = "3")]
+    pub decimation_secs: u32,
+    /// Only stream positions of aircraft of this operator
+    #[prost(string, optional, tag = "4")]
+    pub operator_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A written aircraft position and its meaning for the subscription
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AircraftPositionEvent {
+    /// The event type
+    #[prost(enumeration = "PositionEventType", tag = "1")]
+    pub event_type: i32,
+    /// The aircraft identifier
+    #[prost(string, tag = "2")]
+    pub identifier: ::prost::alloc::string::String,
+    /// The aircraft position
+    #[prost(message, optional, tag = "3")]
+    pub position: ::core::option::Option<PointZ>,
+    /// Network timestamp of the position
+    #[prost(message, optional, tag = "4")]
+    pub timestamp_network: ::core::option::Option<::lib_common::time::Timestamp>,
+}
+/// The nodes involved in the best path request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NodeType {
+    /// Vertiport
+    Vertiport = 0,
+    /// Waypoint
+    Waypoint = 1,
+    /// Aircraft
+    Aircraft = 2,
+}
+impl NodeType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NodeType::Vertiport => "VERTIPORT",
+            NodeType::Waypoint => "WAYPOINT",
+            NodeType::Aircraft => "AIRCRAFT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "VERTIPORT" => Some(Self::Vertiport),
+            "WAYPOINT" => Some(Self::Waypoint),
+            "TILE_LAYER_AIRCRAFT" => Some(Self::Aircraft),
+            _ => None,
+        }
+    }
+}
+/// Overall readiness of the service
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Readiness {
+    /// Database reachable and every data domain fresh
+    Ready = 0,
+    /// Database reachable, a data domain is stale or couldn't be measured
+    Degraded = 1,
+    /// Database unreachable
+    Unready = 2,
+}
+impl Readiness {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Readiness::Ready => "READINESS_READY",
+            Readiness::Degraded => "READINESS_DEGRADED",
+            Readiness::Unready => "READINESS_UNREADY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "READINESS_READY" => Some(Self::Ready),
+            "READINESS_DEGRADED" => Some(Self::Degraded),
+            "READINESS_UNREADY" => Some(Self::Unready),
+            _ => None,
+        }
+    }
+}
+/// Freshness of a data domain against its threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DomainStatus {
+    /// Couldn't be measured
+    Unknown = 0,
+    /// Within the threshold
+    Fresh = 1,
+    /// Beyond the threshold
+    Stale = 2,
+    /// Threshold of 0, not checked
+    Disabled = 3,
+}
+impl DomainStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DomainStatus::Unknown => "DOMAIN_STATUS_UNKNOWN",
+            DomainStatus::Fresh => "DOMAIN_STATUS_FRESH",
+            DomainStatus::Stale => "DOMAIN_STATUS_STALE",
+            DomainStatus::Disabled => "DOMAIN_STATUS_DISABLED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DOMAIN_STATUS_UNKNOWN" => Some(Self::Unknown),
+            "DOMAIN_STATUS_FRESH" => Some(Self::Fresh),
+            "DOMAIN_STATUS_STALE" => Some(Self::Stale),
+            "DOMAIN_STATUS_DISABLED" => Some(Self::Disabled),
+            _ => None,
+        }
+    }
+}
+/// Airspace Zone Type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ZoneType {
+    /// Vertiport
+    Port = 0,
+    /// Restriction
+    Restriction = 1,
+}
+impl ZoneType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ZoneType::Port => "PORT",
+            ZoneType::Restriction => "RESTRICTION",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PORT" => Some(Self::Port),
+            "RESTRICTION" => Some(Self::Restriction),
+            _ => None,
+        }
+    }
+}
+/// Ordering of get_flights results
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FlightOrder {
+    /// By flight identifier, aircraft without a flight last
+    FlightIdentifier = 0,
+    /// By planned start time, aircraft without a flight last
+    TimeStart = 1,
+    /// By distance from the center of the window, nearest first
+    Distance = 2,
+}
+impl FlightOrder {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            FlightOrder::FlightIdentifier => "FLIGHT_ORDER_FLIGHT_IDENTIFIER",
+            FlightOrder::TimeStart => "FLIGHT_ORDER_TIME_START",
+            FlightOrder::Distance => "FLIGHT_ORDER_DISTANCE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FLIGHT_ORDER_FLIGHT_IDENTIFIER" => Some(Self::FlightIdentifier),
+            "FLIGHT_ORDER_TIME_START" => Some(Self::TimeStart),
+            "FLIGHT_ORDER_DISTANCE" => Some(Self::Distance),
+            _ => None,
+        }
+    }
+}
+/// Layers of a map tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TileLayer {
+    /// Aircraft positions
+    Aircraft = 0,
+    /// Flight paths
+    Flights = 1,
+    /// Zone footprints
+    Zones = 2,
+    /// Vertiport footprints
+    Vertiports = 3,
+}
+impl TileLayer {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TileLayer::Aircraft => "TILE_LAYER_AIRCRAFT",
+            TileLayer::Flights => "TILE_LAYER_FLIGHTS",
+            TileLayer::Zones => "TILE_LAYER_ZONES",
+            TileLayer::Vertiports => "TILE_LAYER_VERTIPORTS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TILE_LAYER_AIRCRAFT" => Some(Self::Aircraft),
+            "TILE_LAYER_FLIGHTS" => Some(Self::Flights),
+            "TILE_LAYER_ZONES" => Some(Self::Zones),
+            "TILE_LAYER_VERTIPORTS" => Some(Self::Vertiports),
+            _ => None,
+        }
+    }
+}
+/// How the tags of a tag filter are matched
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TagMatch {
+    /// Items with any of the tags
+    Any = 0,
+    /// Items with all of the tags
+    All = 1,
+    /// Items with none of the tags, including untagged items
+    Exclude = 2,
+}
+impl TagMatch {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TagMatch::Any => "TAG_MATCH_ANY",
+            TagMatch::All => "TAG_MATCH_ALL",
+            TagMatch::Exclude => "TAG_MATCH_EXCLUDE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TAG_MATCH_ANY" => Some(Self::Any),
+            "TAG_MATCH_ALL" => Some(Self::All),
+            "TAG_MATCH_EXCLUDE" => Some(Self::Exclude),
+            _ => None,
+        }
+    }
+}
+/// Datasets exported as CSV
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CsvDataset {
+    /// Flights and their full paths
+    Flights = 0,
+    /// Timed segments of the flight paths
+    FlightSegments = 1,
+    /// Aircraft position and velocity history
+    AircraftHistory = 2,
+}
+impl CsvDataset {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CsvDataset::Flights => "CSV_DATASET_FLIGHTS",
+            CsvDataset::FlightSegments => "CSV_DATASET_FLIGHT_SEGMENTS",
+            CsvDataset::AircraftHistory => "CSV_DATASET_AIRCRAFT_HISTORY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CSV_DATASET_FLIGHTS" => Some(Self::Flights),
+            "CSV_DATASET_FLIGHT_SEGMENTS" => Some(Self::FlightSegments),
+            "CSV_DATASET_AIRCRAFT_HISTORY" => Some(Self::AircraftHistory),
+            _ => None,
+        }
+    }
+}
+/// Validation rules of string fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum StringRule {
+    /// The string doesn't match the expected format
+    Mismatch = 0,
+    /// The string is shorter than allowed (e.g. empty)
+    TooShort = 1,
+    /// The string is longer than allowed
+    TooLong = 2,
+    /// The string contains a character that isn't allowed
+    Character = 3,
+    /// The string contains a forbidden keyword
+    Forbidden = 4,
+}
+impl StringRule {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            StringRule::Mismatch => "STRING_RULE_MISMATCH",
+            StringRule::TooShort => "STRING_RULE_TOO_SHORT",
+            StringRule::TooLong => "STRING_RULE_TOO_LONG",
+            StringRule::Character => "STRING_RULE_CHARACTER",
+            StringRule::Forbidden => "STRING_RULE_FORBIDDEN",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "STRING_RULE_MISMATCH" => Some(Self::Mismatch),
+            "STRING_RULE_TOO_SHORT" => Some(Self::TooShort),
+            "STRING_RULE_TOO_LONG" => Some(Self::TooLong),
+            "STRING_RULE_CHARACTER" => Some(Self::Character),
+            "STRING_RULE_FORBIDDEN" => Some(Self::Forbidden),
+            _ => None,
+        }
+    }
+}
+/// Kind of a change of stored zones
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ZoneChangeType {
+    /// Zones were created or overwritten
+    Updated = 0,
+    /// Zones were soft-deleted
+    Deleted = 1,
+    /// Deleted zones were restored
+    Restored = 2,
+}
+impl ZoneChangeType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ZoneChangeType::Updated => "ZONE_CHANGE_TYPE_UPDATED",
+            ZoneChangeType::Deleted => "ZONE_CHANGE_TYPE_DELETED",
+            ZoneChangeType::Restored => "ZONE_CHANGE_TYPE_RESTORED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ZONE_CHANGE_TYPE_UPDATED" => Some(Self::Updated),
+            "ZONE_CHANGE_TYPE_DELETED" => Some(Self::Deleted),
+            "ZONE_CHANGE_TYPE_RESTORED" => Some(Self::Restored),
+            _ => None,
+        }
+    }
+}
+/// Step of the lifecycle of a stored flight
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FlightEventType {
+    /// The flight was filed or its plan updated
+    Filed = 0,
+    /// The reservation of the flight was confirmed
+    Confirmed = 1,
+    /// The flight was soft-deleted (cancelled)
+    Deleted = 2,
+    /// The deleted flight was restored
+    Restored = 3,
+    /// The reservation of the flight expired without being confirmed, the
+    ///   flight was removed
+    Expired = 4,
+}
+impl FlightEventType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            FlightEventType::Filed => "FLIGHT_EVENT_TYPE_FILED",
+            FlightEventType::Confirmed => "FLIGHT_EVENT_TYPE_CONFIRMED",
+            FlightEventType::Deleted => "FLIGHT_EVENT_TYPE_DELETED",
+            FlightEventType::Restored => "FLIGHT_EVENT_TYPE_RESTORED",
+            FlightEventType::Expired => "FLIGHT_EVENT_TYPE_EXPIRED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FLIGHT_EVENT_TYPE_FILED" => Some(Self::Filed),
+            "FLIGHT_EVENT_TYPE_CONFIRMED" => Some(Self::Confirmed),
+            "FLIGHT_EVENT_TYPE_DELETED" => Some(Self::Deleted),
+            "FLIGHT_EVENT_TYPE_RESTORED" => Some(Self::Restored),
+            "FLIGHT_EVENT_TYPE_EXPIRED" => Some(Self::Expired),
+            _ => None,
+        }
+    }
+}
+/// Meaning of a position for a subscription
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PositionEventType {
+    /// The aircraft entered the region
+    Enter = 0,
+    /// The aircraft moved within the region
+    Update = 1,
+    /// The aircraft left the region
+    Leave = 2,
+}
+impl PositionEventType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PositionEventType::Enter => "POSITION_EVENT_TYPE_ENTER",
+            PositionEventType::Update => "POSITION_EVENT_TYPE_UPDATE",
+            PositionEventType::Leave => "POSITION_EVENT_TYPE_LEAVE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "POSITION_EVENT_TYPE_ENTER" => Some(Self::Enter),
+            "POSITION_EVENT_TYPE_UPDATE" => Some(Self::Update),
+            "POSITION_EVENT_TYPE_LEAVE" => Some(Self::Leave),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod rpc_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct RpcServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl RpcServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> RpcServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> RpcServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            RpcServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn is_ready(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReadyRequest>,
+        ) -> std::result::Result<tonic::Response<super::ReadyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/isReady");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "isReady"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_vertiports(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateVertiportsRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/updateVertiports",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "updateVertiports"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_waypoints(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateWaypointsRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/updateWaypoints",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "updateWaypoints"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_zones(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateZonesRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/updateZones",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "updateZones"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_flight_path(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateFlightPathRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/updateFlightPath",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "updateFlightPath"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn best_path(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BestPathRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BestPathResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/bestPath");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "bestPath"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_flights(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetFlightsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetFlightsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getFlights",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getFlights"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_tile(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTileRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTileResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/getTile");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "getTile"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_aircraft_track(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAircraftTrackRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAircraftTrackResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getAircraftTrack",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getAircraftTrack"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn segmentize_path(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SegmentizePathRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SegmentizePathResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/segmentizePath",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "segmentizePath"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_flight_conflicts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetFlightConflictsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetFlightConflictsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getFlightConflicts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getFlightConflicts"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_ingestion_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIngestionStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIngestionStatusResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getIngestionStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getIngestionStatus"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn register_telemetry_identifier(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterTelemetryIdentifierRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterTelemetryIdentifierResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/registerTelemetryIdentifier",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "registerTelemetryIdentifier"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn ingest_binary_telemetry(
+            &mut self,
+            request: impl tonic::IntoRequest<super::IngestBinaryTelemetryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/ingestBinaryTelemetry",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "ingestBinaryTelemetry"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn remove_zones_by_source(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveZonesBySourceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveZonesBySourceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/removeZonesBySource",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "removeZonesBySource"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_vertiport_throughput(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetVertiportThroughputRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetVertiportThroughputResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getVertiportThroughput",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getVertiportThroughput"));
+            self.inner.unary(req, path, codec).await
         }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "PORT" => Some(Self::Port),
-            "RESTRICTION" => Some(Self::Restriction),
-            _ => None,
+        pub async fn get_flight_segments(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetFlightSegmentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetFlightSegmentsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getFlightSegments",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getFlightSegments"));
+            self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated client implementations.
-pub mod rpc_service_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    #[derive(Debug, Clone)]
-    pub struct RpcServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl RpcServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
+        pub async fn delete_zone(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteZoneRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/deleteZone",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "deleteZone"));
+            self.inner.unary(req, path, codec).await
         }
-    }
-    impl<T> RpcServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
+        pub async fn restore_zone(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RestoreZoneRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/restoreZone",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "restoreZone"));
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
+        pub async fn delete_flight(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteFlightRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/deleteFlight",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "deleteFlight"));
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> RpcServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + Send + Sync,
-        {
-            RpcServiceClient::new(InterceptedService::new(inner, interceptor))
+        pub async fn restore_flight(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RestoreFlightRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/restoreFlight",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "restoreFlight"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn stream_compliance_alerts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamComplianceAlertsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ComplianceAlert>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/streamComplianceAlerts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "streamComplianceAlerts"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn wait_for_flight_applied(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WaitForFlightAppliedRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WaitForFlightAppliedResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/waitForFlightApplied",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "waitForFlightApplied"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn stream_aircraft_geo_json(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamAircraftGeoJsonRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::GeoJsonChunk>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/streamAircraftGeoJson",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "streamAircraftGeoJson"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn get_altitude_occupancy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAltitudeOccupancyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAltitudeOccupancyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getAltitudeOccupancy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getAltitudeOccupancy"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn confirm_flight(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfirmFlightRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/confirmFlight",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "confirmFlight"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
+        pub async fn what_if(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WhatIfRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WhatIfResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/whatIf");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "whatIf"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
+        pub async fn pause_ingestion(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PauseIngestionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PauseIngestionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/pauseIngestion",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "pauseIngestion"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
+        pub async fn resume_ingestion(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ResumeIngestionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ResumeIngestionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/resumeIngestion",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "resumeIngestion"));
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
+        pub async fn build_flight_path(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BuildFlightPathRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BuildFlightPathResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/buildFlightPath",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "buildFlightPath"));
+            self.inner.unary(req, path, codec).await
         }
-        pub async fn is_ready(
+        pub async fn replace_zones(
             &mut self,
-            request: impl tonic::IntoRequest<super::ReadyRequest>,
-        ) -> std::result::Result<tonic::Response<super::ReadyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::ReplaceZonesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReplaceZonesResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -469,15 +2851,21 @@ pub mod rpc_service_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/isReady");
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/replaceZones",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "isReady"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "replaceZones"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_vertiports(
+        pub async fn get_service_info(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateVertiportsRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::GetServiceInfoRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetServiceInfoResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -489,17 +2877,42 @@ pub mod rpc_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/grpc.RpcService/updateVertiports",
+                "/grpc.RpcService/getServiceInfo",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("grpc.RpcService", "updateVertiports"));
+                .insert(GrpcMethod::new("grpc.RpcService", "getServiceInfo"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_waypoints(
+        pub async fn export_csv(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateWaypointsRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::ExportCsvRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::CsvChunk>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/exportCsv");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "exportCsv"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn update_corridors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateCorridorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -511,17 +2924,20 @@ pub mod rpc_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/grpc.RpcService/updateWaypoints",
+                "/grpc.RpcService/updateCorridors",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("grpc.RpcService", "updateWaypoints"));
+                .insert(GrpcMethod::new("grpc.RpcService", "updateCorridors"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_zones(
+        pub async fn get_corridors(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateZonesRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::GetCorridorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCorridorsResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -533,17 +2949,20 @@ pub mod rpc_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/grpc.RpcService/updateZones",
+                "/grpc.RpcService/getCorridors",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("grpc.RpcService", "updateZones"));
+                .insert(GrpcMethod::new("grpc.RpcService", "getCorridors"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_flight_path(
+        pub async fn delete_corridor(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateFlightPathRequest>,
-        ) -> std::result::Result<tonic::Response<super::UpdateResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::DeleteCorridorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -555,18 +2974,18 @@ pub mod rpc_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/grpc.RpcService/updateFlightPath",
+                "/grpc.RpcService/deleteCorridor",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("grpc.RpcService", "updateFlightPath"));
+                .insert(GrpcMethod::new("grpc.RpcService", "deleteCorridor"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn best_path(
+        pub async fn get_corridor_allocation(
             &mut self,
-            request: impl tonic::IntoRequest<super::BestPathRequest>,
+            request: impl tonic::IntoRequest<super::GetCorridorAllocationRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::BestPathResponse>,
+            tonic::Response<super::GetCorridorAllocationResponse>,
             tonic::Status,
         > {
             self.inner
@@ -579,16 +2998,19 @@ pub mod rpc_service_client {
                     )
                 })?;
             let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/grpc.RpcService/bestPath");
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getCorridorAllocation",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("grpc.RpcService", "bestPath"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getCorridorAllocation"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_flights(
+        pub async fn stream_aircraft_positions(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetFlightsRequest>,
+            request: impl tonic::IntoRequest<super::StreamAircraftPositionsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetFlightsResponse>,
+            tonic::Response<tonic::codec::Streaming<super::AircraftPositionEvent>>,
             tonic::Status,
         > {
             self.inner
@@ -602,11 +3024,111 @@ pub mod rpc_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/grpc.RpcService/getFlights",
+                "/grpc.RpcService/streamAircraftPositions",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("grpc.RpcService", "getFlights"));
+                .insert(GrpcMethod::new("grpc.RpcService", "streamAircraftPositions"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn stream_zone_changes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamZoneChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ZoneChanged>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/streamZoneChanges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "streamZoneChanges"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn stream_flight_events(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamFlightEventsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::FlightEvent>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/streamFlightEvents",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "streamFlightEvents"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn stream_flight_conflicts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamFlightConflictsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::FlightConflicted>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/streamFlightConflicts",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "streamFlightConflicts"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn get_flight(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetFlightRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetFlightResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/grpc.RpcService/getFlight",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("grpc.RpcService", "getFlight"));
             self.inner.unary(req, path, codec).await
         }
     }
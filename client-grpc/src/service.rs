@@ -83,7 +83,10 @@ where
     /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
     ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
     ///     let client = GisClient::new_client(&host, port, "gis");
-    ///     let request = gis::UpdateVertiportsRequest { vertiports: vec![] };
+    ///     let request = gis::UpdateVertiportsRequest {
+    ///         vertiports: vec![],
+    ///         dry_run: false,
+    ///     };
     ///     let response = client.update_vertiports(request).await?;
     ///     println!("RESPONSE={:?}", response.into_inner());
     ///     Ok(())
@@ -110,7 +113,10 @@ where
     /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
     ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
     ///     let client = GisClient::new_client(&host, port, "gis");
-    ///     let request = gis::UpdateZonesRequest { zones: vec![] };
+    ///     let request = gis::UpdateZonesRequest {
+    ///         zones: vec![],
+    ///         dry_run: false,
+    ///     };
     ///     let response = client.update_zones(request).await?;
     ///     println!("RESPONSE={:?}", response.into_inner());
     ///     Ok(())
@@ -146,6 +152,18 @@ where
     ///         timestamp_start: Some(Utc::now().into()),
     ///         timestamp_end: Some(Utc::now().into()),
     ///         path: vec![],
+    ///         allow_rebind: false,
+    ///         operator_id: None,
+    ///         srid: None,
+    ///         historical: false,
+    ///         dry_run: false,
+    ///         aircraft_identifiers: vec![],
+    ///         tags: vec![],
+    ///         destination_identifier: None,
+    ///         reservation_secs: None,
+    ///         scenario_id: None,
+    ///         geom_wkt: None,
+    ///         geom_ewkb: None,
     ///     };
     ///     let response = client.update_flight_path(request).await?;
     ///     println!("RESPONSE={:?}", response.into_inner());
@@ -183,7 +201,12 @@ where
     ///         target_type: 0,
     ///         time_start: Some(time_start),
     ///         time_end: Some(time_end),
-    ///         limit: 1
+    ///         limit: 1,
+    ///         soft_window: false,
+    ///         cruise_velocity_mps: None,
+    ///         verbose: false,
+    ///         tag_filter: None,
+    ///         max_segments: None,
     ///     };
     ///     let response = client.best_path(request).await?;
     ///     println!("RESPONSE={:?}", response.into_inner());
@@ -221,6 +244,12 @@ where
     ///         window_max_y: 0.0,
     ///         time_start: Some(time_start),
     ///         time_end: Some(time_end),
+    ///         operator_id: None,
+    ///         order_by: gis::FlightOrder::FlightIdentifier as i32,
+    ///         tag_filter: None,
+    ///         velocity_samples: 0,
+    ///         skeleton_only: false,
+    ///         path_alignment: false,
     ///     };
     ///     let response = client.get_flights(request).await?;
     ///     println!("RESPONSE={:?}", response.into_inner());
@@ -232,6 +261,965 @@ where
         request: super::GetFlightsRequest,
     ) -> Result<tonic::Response<super::GetFlightsResponse>, tonic::Status>;
 
+    /// Returns a [`tonic::Response`] containing a [`GetTileResponse`](super::GetTileResponse)
+    /// Takes an [`GetTileRequest`](super::GetTileRequest).
+    ///
+    /// The tile is encoded as a Mapbox Vector Tile (MVT) with one layer
+    ///  per requested [`TileLayer`](super::TileLayer).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetTileRequest {
+    ///         z: 12,
+    ///         x: 2104,
+    ///         y: 1346,
+    ///         layers: vec![gis::TileLayer::Aircraft as i32, gis::TileLayer::Zones as i32],
+    ///     };
+    ///     let response = client.get_tile(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_tile(
+        &self,
+        request: super::GetTileRequest,
+    ) -> Result<tonic::Response<super::GetTileResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetAircraftTrackResponse`](super::GetAircraftTrackResponse)
+    /// Takes an [`GetAircraftTrackRequest`](super::GetAircraftTrackRequest).
+    ///
+    /// A non-zero `resolution_seconds` downsamples the track to one position
+    ///  per time bucket, always keeping the first and last positions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetAircraftTrackRequest {
+    ///         identifier: "Aircraft".to_string(),
+    ///         time_start: Some(Utc::now().into()),
+    ///         time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+    ///         resolution_seconds: 10,
+    ///         operator_id: None,
+    ///         srid: None,
+    ///     };
+    ///     let response = client.get_aircraft_track(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_aircraft_track(
+        &self,
+        request: super::GetAircraftTrackRequest,
+    ) -> Result<tonic::Response<super::GetAircraftTrackResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`SegmentizePathResponse`](super::SegmentizePathResponse)
+    /// Takes an [`SegmentizePathRequest`](super::SegmentizePathRequest).
+    ///
+    /// Previews how a path would be split into timed segments.
+    /// Nothing is stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::SegmentizePathRequest {
+    ///         path: vec![
+    ///             gis::PointZ { latitude: 52.3745905, longitude: 4.9160036, altitude_meters: 100.0 },
+    ///             gis::PointZ { latitude: 52.3749819, longitude: 4.9156925, altitude_meters: 120.0 },
+    ///         ],
+    ///         time_start: Some(Utc::now().into()),
+    ///         time_end: Some((Utc::now() + Duration::try_minutes(1).unwrap()).into()),
+    ///         segment_length_meters: 40.0,
+    ///     };
+    ///     let response = client.segmentize_path(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn segmentize_path(
+        &self,
+        request: super::SegmentizePathRequest,
+    ) -> Result<tonic::Response<super::SegmentizePathResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetFlightConflictsResponse`](super::GetFlightConflictsResponse)
+    /// Takes an [`GetFlightConflictsRequest`](super::GetFlightConflictsRequest).
+    ///
+    /// Each conflict includes the stored segment, the closest-approach point
+    ///  on that segment, the minimum 3D distance and the overlapping time interval.
+    /// Corridors the path would push to or beyond their capacity are listed
+    ///  separately as corridor conflicts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetFlightConflictsRequest {
+    ///         path: vec![
+    ///             gis::PointZ { latitude: 52.3745905, longitude: 4.9160036, altitude_meters: 100.0 },
+    ///             gis::PointZ { latitude: 52.3749819, longitude: 4.9156925, altitude_meters: 120.0 },
+    ///         ],
+    ///         time_start: Some(Utc::now().into()),
+    ///         time_end: Some((Utc::now() + Duration::try_minutes(1).unwrap()).into()),
+    ///         distance_meters: 10.0,
+    ///         tag_filter: None,
+    ///     };
+    ///     let response = client.get_flight_conflicts(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_flight_conflicts(
+        &self,
+        request: super::GetFlightConflictsRequest,
+    ) -> Result<tonic::Response<super::GetFlightConflictsResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetIngestionStatusResponse`](super::GetIngestionStatusResponse)
+    /// Takes an [`GetIngestionStatusRequest`](super::GetIngestionStatusRequest).
+    ///
+    /// Reports the depth of each Redis ingestion queue and the age of
+    ///  its oldest unprocessed message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetIngestionStatusRequest {};
+    ///     let response = client.get_ingestion_status(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_ingestion_status(
+        &self,
+        request: super::GetIngestionStatusRequest,
+    ) -> Result<tonic::Response<super::GetIngestionStatusResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`RegisterTelemetryIdentifierResponse`](super::RegisterTelemetryIdentifierResponse)
+    /// Takes an [`RegisterTelemetryIdentifierRequest`](super::RegisterTelemetryIdentifierRequest).
+    ///
+    /// The returned index identifies the aircraft in binary telemetry
+    ///  records sent with `ingest_binary_telemetry`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::RegisterTelemetryIdentifierRequest {
+    ///         identifier: "Aircraft".to_string(),
+    ///     };
+    ///     let response = client.register_telemetry_identifier(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn register_telemetry_identifier(
+        &self,
+        request: super::RegisterTelemetryIdentifierRequest,
+    ) -> Result<tonic::Response<super::RegisterTelemetryIdentifierResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`IngestBinaryTelemetryRequest`](super::IngestBinaryTelemetryRequest).
+    ///
+    /// The payload is a sequence of fixed-layout 28-byte records, see
+    ///  the ICD for the layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::IngestBinaryTelemetryRequest {
+    ///         payload: vec![0; 28],
+    ///     };
+    ///     let response = client.ingest_binary_telemetry(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn ingest_binary_telemetry(
+        &self,
+        request: super::IngestBinaryTelemetryRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`RemoveZonesBySourceResponse`](super::RemoveZonesBySourceResponse)
+    /// Takes an [`RemoveZonesBySourceRequest`](super::RemoveZonesBySourceRequest).
+    ///
+    /// Removes all zones published by the given source authority.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::RemoveZonesBySourceRequest {
+    ///         source: "LVNL".to_string(),
+    ///     };
+    ///     let response = client.remove_zones_by_source(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn remove_zones_by_source(
+        &self,
+        request: super::RemoveZonesBySourceRequest,
+    ) -> Result<tonic::Response<super::RemoveZonesBySourceResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetVertiportThroughputResponse`](super::GetVertiportThroughputResponse)
+    /// Takes an [`GetVertiportThroughputRequest`](super::GetVertiportThroughputRequest).
+    ///
+    /// Returns the hourly departures and arrivals of a vertiport.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetVertiportThroughputRequest {
+    ///         identifier: "Kamino".to_string(),
+    ///         time_start: Some((Utc::now() - Duration::try_days(1).unwrap()).into()),
+    ///         time_end: Some(Utc::now().into()),
+    ///     };
+    ///     let response = client.get_vertiport_throughput(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_vertiport_throughput(
+        &self,
+        request: super::GetVertiportThroughputRequest,
+    ) -> Result<tonic::Response<super::GetVertiportThroughputResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetFlightSegmentsResponse`](super::GetFlightSegmentsResponse)
+    /// Takes an [`GetFlightSegmentsRequest`](super::GetFlightSegmentsRequest).
+    ///
+    /// Returns the stored segments of a flight, ordered along its path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetFlightSegmentsRequest {
+    ///         flight_identifier: "FLIGHT-1".to_string(),
+    ///         limit: Some(100),
+    ///         offset: None,
+    ///     };
+    ///     let response = client.get_flight_segments(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_flight_segments(
+        &self,
+        request: super::GetFlightSegmentsRequest,
+    ) -> Result<tonic::Response<super::GetFlightSegmentsResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`DeleteZoneRequest`](super::DeleteZoneRequest).
+    ///
+    /// Soft-deletes a zone, it can be restored within the undo window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::DeleteZoneRequest {
+    ///         identifier: "NFZ-1".to_string(),
+    ///     };
+    ///     let response = client.delete_zone(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn delete_zone(
+        &self,
+        request: super::DeleteZoneRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`RestoreZoneRequest`](super::RestoreZoneRequest).
+    ///
+    /// Restores a zone deleted within the undo window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::RestoreZoneRequest {
+    ///         identifier: "NFZ-1".to_string(),
+    ///     };
+    ///     let response = client.restore_zone(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn restore_zone(
+        &self,
+        request: super::RestoreZoneRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`DeleteFlightRequest`](super::DeleteFlightRequest).
+    ///
+    /// Soft-deletes a flight, it can be restored within the undo window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::DeleteFlightRequest {
+    ///         flight_identifier: "FLIGHT-1".to_string(),
+    ///         reason: Some("Weather".to_string()),
+    ///     };
+    ///     let response = client.delete_flight(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn delete_flight(
+        &self,
+        request: super::DeleteFlightRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`RestoreFlightRequest`](super::RestoreFlightRequest).
+    ///
+    /// Restores a flight deleted within the undo window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::RestoreFlightRequest {
+    ///         flight_identifier: "FLIGHT-1".to_string(),
+    ///     };
+    ///     let response = client.restore_flight(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn restore_flight(
+        &self,
+        request: super::RestoreFlightRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`WaitForFlightAppliedResponse`](super::WaitForFlightAppliedResponse)
+    /// Takes an [`WaitForFlightAppliedRequest`](super::WaitForFlightAppliedRequest).
+    ///
+    /// Blocks until a flight path pushed to the Redis queue with the
+    ///  given correlation id has been applied, or the timeout expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::WaitForFlightAppliedRequest {
+    ///         correlation_id: "6f1d9a4e-0b1c-4c55-a7c8-3d2e1f0a9b8c".to_string(),
+    ///         timeout_ms: 5000,
+    ///     };
+    ///     let response = client.wait_for_flight_applied(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn wait_for_flight_applied(
+        &self,
+        request: super::WaitForFlightAppliedRequest,
+    ) -> Result<tonic::Response<super::WaitForFlightAppliedResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetAltitudeOccupancyResponse`](super::GetAltitudeOccupancyResponse)
+    /// Takes an [`GetAltitudeOccupancyRequest`](super::GetAltitudeOccupancyRequest).
+    ///
+    /// Counts the flight segments and aircraft in each altitude band
+    ///  of an area during a time window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let time_start: Timestamp = Utc::now().into();
+    ///     let time_end: Timestamp = Utc::now().into();
+    ///     let request = gis::GetAltitudeOccupancyRequest {
+    ///         vertices: vec![
+    ///             gis::Coordinates { latitude: 52.37, longitude: 4.91 },
+    ///             gis::Coordinates { latitude: 52.37, longitude: 4.92 },
+    ///             gis::Coordinates { latitude: 52.38, longitude: 4.92 },
+    ///             gis::Coordinates { latitude: 52.37, longitude: 4.91 },
+    ///         ],
+    ///         time_start: Some(time_start),
+    ///         time_end: Some(time_end),
+    ///         altitude_meters_min: 0.0,
+    ///         altitude_meters_max: 300.0,
+    ///         band_height_meters: 30.0,
+    ///     };
+    ///     let response = client.get_altitude_occupancy(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_altitude_occupancy(
+        &self,
+        request: super::GetAltitudeOccupancyRequest,
+    ) -> Result<tonic::Response<super::GetAltitudeOccupancyResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`ConfirmFlightRequest`](super::ConfirmFlightRequest).
+    ///
+    /// Confirms a flight reserved with `reservation_secs` before the
+    ///  reservation expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::ConfirmFlightRequest {
+    ///         flight_identifier: "FLIGHT-1".to_string(),
+    ///     };
+    ///     let response = client.confirm_flight(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn confirm_flight(
+        &self,
+        request: super::ConfirmFlightRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`WhatIfResponse`](super::WhatIfResponse)
+    /// Takes an [`WhatIfRequest`](super::WhatIfRequest).
+    ///
+    /// Evaluates a synthetic aircraft state against stored zones, flights
+    ///  and live traffic without storing anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::WhatIfRequest {
+    ///         position: Some(gis::PointZ { latitude: 52.3745905, longitude: 4.9160036, altitude_meters: 100.0 }),
+    ///         ground_speed_mps: 20.0,
+    ///         track_angle_degrees: 90.0,
+    ///         vertical_speed_mps: 0.0,
+    ///         timestamp: None,
+    ///         path: vec![],
+    ///         lookahead_secs: 60,
+    ///         distance_meters: 50.0,
+    ///         tag_filter: None,
+    ///     };
+    ///     let response = client.what_if(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn what_if(
+        &self,
+        request: super::WhatIfRequest,
+    ) -> Result<tonic::Response<super::WhatIfResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`PauseIngestionResponse`](super::PauseIngestionResponse)
+    /// Takes an [`PauseIngestionRequest`](super::PauseIngestionRequest).
+    ///
+    /// Pauses the Redis consumers, messages accumulate in the queues until
+    ///  resumed. Returns once the messages being processed are done.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::PauseIngestionRequest {};
+    ///     let response = client.pause_ingestion(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn pause_ingestion(
+        &self,
+        request: super::PauseIngestionRequest,
+    ) -> Result<tonic::Response<super::PauseIngestionResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`ResumeIngestionResponse`](super::ResumeIngestionResponse)
+    /// Takes an [`ResumeIngestionRequest`](super::ResumeIngestionRequest).
+    ///
+    /// Resumes the Redis consumers paused with `pause_ingestion`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::ResumeIngestionRequest {};
+    ///     let response = client.resume_ingestion(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn resume_ingestion(
+        &self,
+        request: super::ResumeIngestionRequest,
+    ) -> Result<tonic::Response<super::ResumeIngestionResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`BuildFlightPathResponse`](super::BuildFlightPathResponse)
+    /// Takes an [`BuildFlightPathRequest`](super::BuildFlightPathRequest).
+    ///
+    /// Routes between consecutive nodes with `best_path` and stores the
+    ///  joined path as a flight. A routing failure reports which leg failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::BuildFlightPathRequest {
+    ///         flight_identifier: "flight-1".to_string(),
+    ///         aircraft_identifier: "Mantis".to_string(),
+    ///         nodes: vec![
+    ///             gis::NodeRef {
+    ///                 node_type: gis::NodeType::Vertiport as i32,
+    ///                 identifier: "vertiport-a".to_string(),
+    ///             },
+    ///             gis::NodeRef {
+    ///                 node_type: gis::NodeType::Vertiport as i32,
+    ///                 identifier: "vertiport-b".to_string(),
+    ///             },
+    ///         ],
+    ///         time_start: Some(Utc::now().into()),
+    ///         time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+    ///         ..Default::default()
+    ///     };
+    ///     let response = client.build_flight_path(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn build_flight_path(
+        &self,
+        request: super::BuildFlightPathRequest,
+    ) -> Result<tonic::Response<super::BuildFlightPathResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`ReplaceZonesResponse`](super::ReplaceZonesResponse)
+    /// Takes an [`ReplaceZonesRequest`](super::ReplaceZonesRequest).
+    ///
+    /// Replaces all zones published by a source with a new set in a single
+    ///  transaction. Zones missing from the set are soft-deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::ReplaceZonesRequest {
+    ///         source: "LVNL".to_string(),
+    ///         zones: vec![],
+    ///         dry_run: true,
+    ///     };
+    ///     let response = client.replace_zones(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn replace_zones(
+        &self,
+        request: super::ReplaceZonesRequest,
+    ) -> Result<tonic::Response<super::ReplaceZonesResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetServiceInfoResponse`](super::GetServiceInfoResponse)
+    /// Takes an [`GetServiceInfoRequest`](super::GetServiceInfoRequest).
+    ///
+    /// Returns the version, git commit and enabled features of the server
+    ///  and the schema version applied to its database.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetServiceInfoRequest {};
+    ///     let response = client.get_service_info(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_service_info(
+        &self,
+        request: super::GetServiceInfoRequest,
+    ) -> Result<tonic::Response<super::GetServiceInfoResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`UpdateCorridorsRequest`](super::UpdateCorridorsRequest).
+    ///
+    /// Creates or replaces corridors, each a tube around a centerline with
+    ///  a capacity of simultaneous flights.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::UpdateCorridorsRequest {
+    ///         corridors: vec![gis::Corridor {
+    ///             identifier: "CORRIDOR-1".to_string(),
+    ///             centerline: vec![
+    ///                 gis::PointZ { latitude: 52.3745905, longitude: 4.9160036, altitude_meters: 100.0 },
+    ///                 gis::PointZ { latitude: 52.3749819, longitude: 4.9156925, altitude_meters: 100.0 },
+    ///             ],
+    ///             width_meters: 100.0,
+    ///             height_meters: 50.0,
+    ///             capacity: 4,
+    ///         }],
+    ///     };
+    ///     let response = client.update_corridors(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn update_corridors(
+        &self,
+        request: super::UpdateCorridorsRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetCorridorsResponse`](super::GetCorridorsResponse)
+    /// Takes an [`GetCorridorsRequest`](super::GetCorridorsRequest).
+    ///
+    /// Returns all corridors.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetCorridorsRequest {};
+    ///     let response = client.get_corridors(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_corridors(
+        &self,
+        request: super::GetCorridorsRequest,
+    ) -> Result<tonic::Response<super::GetCorridorsResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`UpdateResponse`](super::UpdateResponse)
+    /// Takes an [`DeleteCorridorRequest`](super::DeleteCorridorRequest).
+    ///
+    /// Deletes a corridor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::DeleteCorridorRequest {
+    ///         identifier: "CORRIDOR-1".to_string(),
+    ///     };
+    ///     let response = client.delete_corridor(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn delete_corridor(
+        &self,
+        request: super::DeleteCorridorRequest,
+    ) -> Result<tonic::Response<super::UpdateResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetCorridorAllocationResponse`](super::GetCorridorAllocationResponse)
+    /// Takes an [`GetCorridorAllocationRequest`](super::GetCorridorAllocationRequest).
+    ///
+    /// Returns the number of flights in a corridor for each time slice of a
+    ///  range, flagging the slices over the corridor capacity.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetCorridorAllocationRequest {
+    ///         identifier: "CORRIDOR-1".to_string(),
+    ///         time_start: Some(Utc::now().into()),
+    ///         time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+    ///         slice_seconds: 300,
+    ///     };
+    ///     let response = client.get_corridor_allocation(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_corridor_allocation(
+        &self,
+        request: super::GetCorridorAllocationRequest,
+    ) -> Result<tonic::Response<super::GetCorridorAllocationResponse>, tonic::Status>;
+
+    /// Returns a [`tonic::Response`] containing a [`GetFlightResponse`](super::GetFlightResponse)
+    /// Takes an [`GetFlightRequest`](super::GetFlightRequest).
+    ///
+    /// Returns a single flight with the state of its aircraft and the
+    ///  latest result of the periodic conflict check.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tonic::Status`] with [`Code::Unknown`](tonic::Code::Unknown) if
+    /// the server is not ready.
+    ///
+    /// # Examples
+    /// ```
+    /// use lib_common::grpc::get_endpoint_from_env;
+    /// use svc_gis_client_grpc::prelude::*;
+    ///
+    /// async fn example () -> Result<(), Box<dyn std::error::Error>> {
+    ///     let (host, port) = get_endpoint_from_env("SERVER_HOSTNAME", "SERVER_PORT_GRPC");
+    ///     let client = GisClient::new_client(&host, port, "gis");
+    ///     let request = gis::GetFlightRequest {
+    ///         flight_identifier: "FLIGHT-1".to_string(),
+    ///     };
+    ///     let response = client.get_flight(request).await?;
+    ///     println!("RESPONSE={:?}", response.into_inner());
+    ///     Ok(())
+    /// }
+    /// ```
+    async fn get_flight(
+        &self,
+        request: super::GetFlightRequest,
+    ) -> Result<tonic::Response<super::GetFlightResponse>, tonic::Status>;
+
     // /// Returns a [`tonic::Response`] containing a [`NearestNeighborResponse`](super::NearestNeighborResponse)
     // /// Takes an [`NearestNeighborRequest`](super::NearestNeighborRequest).
     // ///
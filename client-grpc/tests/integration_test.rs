@@ -150,7 +150,10 @@ async fn test_add_vertiport() -> Result<(), Box<dyn std::error::Error>> {
     ];
 
     let response = client
-        .update_vertiports(UpdateVertiportsRequest { vertiports })
+        .update_vertiports(UpdateVertiportsRequest {
+            vertiports,
+            dry_run: false,
+        })
         .await?;
 
     println!("Response: {:?}", response);
@@ -10,6 +10,15 @@ pub const REDIS_KEY_AIRCRAFT_POSITION: &str = "gis:aircraft:position";
 /// The key for the Redis queue containing aircraft velocity information
 pub const REDIS_KEY_AIRCRAFT_VELOCITY: &str = "gis:aircraft:velocity";
 
+/// The key for the Redis queue containing combined aircraft position and velocity information
+pub const REDIS_KEY_AIRCRAFT_TELEMETRY: &str = "gis:aircraft:telemetry";
+
+/// The key for the Redis queue containing flight paths
+pub const REDIS_KEY_FLIGHT_PATH: &str = "gis:flight:path";
+
+/// The key folder for the completion records of queued flight paths
+pub const REDIS_KEY_FLIGHT_APPLIED: &str = "gis:flight:applied";
+
 /// Aircraft Type
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 #[derive(strum::EnumString)]
@@ -127,7 +136,13 @@ pub struct AircraftPosition {
     /// The timestamp reported by the asset
     pub timestamp_asset: Option<DateTime<Utc>>,
 
-    // TODO(R5): location uncertainty
+    /// Horizontal accuracy of the position in meters (e.g. HPL/HFOM)
+    #[serde(default)]
+    pub horizontal_accuracy_meters: Option<f32>,
+
+    /// Vertical accuracy of the position in meters
+    #[serde(default)]
+    pub vertical_accuracy_meters: Option<f32>,
 }
 
 /// Generic Identification Information for an Aircraft
@@ -142,11 +157,19 @@ pub struct AircraftId {
     /// The type of aircraft
     pub aircraft_type: AircraftType,
 
+    /// The operator (owner) of this aircraft
+    pub operator_id: Option<String>,
+
     /// The network timestamp of the identification
     pub timestamp_network: DateTime<Utc>,
 
     /// The timestamp reported by the asset
-    pub timestamp_asset: Option<DateTime<Utc>>
+    pub timestamp_asset: Option<DateTime<Utc>>,
+
+    /// Replace a declared aircraft type even with an undeclared type or a
+    ///  type of an incompatible category
+    #[serde(default)]
+    pub force_type: bool
 }
 
 /// Generic Velocity Information for an Aircraft
@@ -178,3 +201,79 @@ pub struct AircraftVelocity {
 
     // TODO(R5): velocity uncertainty
 }
+
+/// Combined Position and Velocity Information for an Aircraft
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AircraftTelemetry {
+    /// The unique identifier for the aircraft
+    pub identifier: String,
+
+    /// The 3D position of the aircraft
+    pub position: Position,
+
+    /// The velocity of the aircraft relative to ground in meters per second
+    pub velocity_horizontal_ground_mps: f32,
+
+    /// The velocity of the aircraft relative to the air in meters per second
+    pub velocity_horizontal_air_mps: Option<f32>,
+
+    /// The vertical velocity of the aircraft in meters per second
+    pub velocity_vertical_mps: f32,
+
+    /// The angle of the velocity vector with respect to true north in degrees
+    pub track_angle_degrees: f32,
+
+    /// The network timestamp of the telemetry
+    pub timestamp_network: DateTime<Utc>,
+
+    /// The timestamp reported by the asset
+    pub timestamp_asset: Option<DateTime<Utc>>
+}
+
+/// Flight Path queued for the PostGIS database
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FlightPathMessage {
+    /// Identifier used to wait for this message to be applied
+    ///  No completion is recorded if absent
+    pub correlation_id: Option<String>,
+
+    /// The unique identifier for the flight
+    pub flight_identifier: Option<String>,
+
+    /// The unique identifier for the aircraft
+    pub aircraft_identifier: Option<String>,
+
+    /// If this is a simulated flight
+    pub simulated: bool,
+
+    /// The type of aircraft
+    pub aircraft_type: AircraftType,
+
+    /// The path of the aircraft
+    pub path: Vec<Position>,
+
+    /// The planned start time of the flight
+    pub timestamp_start: DateTime<Utc>,
+
+    /// The planned end time of the flight
+    pub timestamp_end: DateTime<Utc>,
+
+    /// Allow reassigning an existing flight to a different aircraft
+    #[serde(default)]
+    pub allow_rebind: bool,
+
+    /// The operator (owner) of the flight
+    pub operator_id: Option<String>,
+
+    /// The destination vertiport of the flight
+    pub destination_identifier: Option<String>,
+
+    /// Simulation run the flight belongs to, simulated flights only
+    #[serde(default)]
+    pub scenario_id: Option<String>,
+
+    /// Time the producer queued the message, to report how far behind
+    ///  the ingestion of flight paths is
+    #[serde(default)]
+    pub timestamp_enqueued: Option<DateTime<Utc>>,
+}
@@ -11,8 +11,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "::lib_common::time::Timestamp",
         )
         .type_attribute("ReadyRequest", "#[derive(Eq, Copy)]")
-        .type_attribute("ReadyResponse", "#[derive(Eq, Copy)]")
-        .type_attribute("UpdateResponse", "#[derive(Eq, Copy)]")
+        // Not Copy since it lists the updated identifiers and dry run
+        //  conflicts, clone it instead
+        .type_attribute("UpdateResponse", "#[derive(Eq)]")
         .type_attribute("PointZ", "#[derive(Copy)]")
         .type_attribute("PathSegment", "#[derive(Copy)]")
         .type_attribute("Coordinates", "#[derive(Copy)]");
@@ -41,10 +42,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .type_attribute("ZoneType", "#[derive(::postgres_types::ToSql)]")
         .type_attribute("ZoneType", "#[derive(::num_derive::FromPrimitive)]")
         .type_attribute("ZoneType", r#"#[postgres(name = "zonetype")]"#)
+        .type_attribute("TileLayer", "#[derive(::num_derive::FromPrimitive)]")
+        .type_attribute("FlightOrder", "#[derive(::num_derive::FromPrimitive)]")
+        .type_attribute("TagMatch", "#[derive(::num_derive::FromPrimitive)]")
+        .type_attribute("CsvDataset", "#[derive(::num_derive::FromPrimitive)]")
         .build_client(false)
         .compile(&[proto_file], &[proto_dir])?;
 
     println!("cargo:rerun-if-changed={}", proto_file);
 
+    // Commit reported by the service info RPC, GIT_SHA takes precedence
+    //  for builds without the repository (e.g. docker)
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            std::process::Command::new("git")
+                .args(["rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|sha| sha.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=SVC_GIS_GIT_SHA={}", git_sha);
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+
     Ok(())
 }
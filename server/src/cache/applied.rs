@@ -0,0 +1,309 @@
+//! Completion records of queued flight paths
+//!
+//! Producers push a [`FlightPathMessage`](crate::types::FlightPathMessage)
+//!  with a correlation id to the flight path queue. Once the consumer has
+//!  processed the message it records the outcome under a short-lived key,
+//!  which [`wait_for_flight_applied`] polls until it appears.
+
+use crate::grpc::server::grpc_server::WaitForFlightAppliedResponse;
+use crate::types::REDIS_KEY_FLIGHT_APPLIED;
+use deadpool_redis::redis;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+/// Time a completion record is kept
+pub const FLIGHT_APPLIED_TTL_SECS: u64 = 300;
+
+/// Max time a single wait may block
+pub const MAX_WAIT_FOR_FLIGHT_APPLIED_MS: u64 = 30_000;
+
+/// Interval between checks for the completion record
+const WAIT_POLL_INTERVAL_MS: u64 = 50;
+
+/// Allowed characters in a correlation id
+pub const CORRELATION_ID_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,64}$";
+
+/// Possible errors recording or waiting for completion
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AppliedError {
+    /// Could not get client
+    Client,
+
+    /// Redis operation failed
+    OperationFailed,
+
+    /// Invalid correlation id
+    CorrelationId,
+}
+
+impl std::fmt::Display for AppliedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AppliedError::Client => write!(f, "Could not get cache client."),
+            AppliedError::OperationFailed => write!(f, "Cache operation failed."),
+            AppliedError::CorrelationId => write!(f, "Invalid correlation id provided."),
+        }
+    }
+}
+
+/// Outcome of applying a queued flight path
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FlightApplied {
+    /// If the flight path was stored
+    pub success: bool,
+
+    /// Reason the flight path was rejected
+    pub error: Option<String>,
+}
+
+/// Key of the completion record for a correlation id
+fn applied_key(correlation_id: &str) -> String {
+    format!("{REDIS_KEY_FLIGHT_APPLIED}:{correlation_id}")
+}
+
+/// Validates a correlation id
+fn check_correlation_id(correlation_id: &str) -> Result<(), AppliedError> {
+    crate::postgis::utils::check_string(correlation_id, CORRELATION_ID_REGEX).map_err(|e| {
+        cache_error!(
+            "(check_correlation_id) invalid correlation id '{}': {}",
+            correlation_id,
+            e
+        );
+        AppliedError::CorrelationId
+    })
+}
+
+/// Records the outcome of applying a queued flight path
+pub async fn record_flight_applied(
+    correlation_id: &str,
+    applied: &FlightApplied,
+) -> Result<(), AppliedError> {
+    cache_debug!("(record_flight_applied) entry, correlation_id: '{correlation_id}'.");
+    check_correlation_id(correlation_id)?;
+
+    let Some(pool) = super::status::STATUS_POOL.get() else {
+        cache_error!("(record_flight_applied) could not get Redis pool.");
+        return Err(AppliedError::Client);
+    };
+
+    let mut connection = pool.get().await.map_err(|e| {
+        cache_error!(
+            "(record_flight_applied) could not get connection from Redis pool: {}",
+            e
+        );
+        AppliedError::Client
+    })?;
+
+    let value = serde_json::to_string(applied).map_err(|e| {
+        cache_error!("(record_flight_applied) could not serialize outcome: {}", e);
+        AppliedError::OperationFailed
+    })?;
+
+    redis::cmd("SET")
+        .arg(applied_key(correlation_id))
+        .arg(value)
+        .arg("EX")
+        .arg(FLIGHT_APPLIED_TTL_SECS)
+        .query_async::<_, ()>(&mut connection)
+        .await
+        .map_err(|e| {
+            cache_error!(
+                "(record_flight_applied) could not record outcome for {correlation_id}: {}",
+                e
+            );
+            AppliedError::OperationFailed
+        })
+}
+
+/// Gets the outcome of a queued flight path, if already applied
+async fn get_flight_applied(
+    connection: &mut deadpool_redis::Connection,
+    key: &str,
+) -> Result<Option<FlightApplied>, AppliedError> {
+    let value: Option<Vec<u8>> = redis::cmd("GET")
+        .arg(key)
+        .query_async(connection)
+        .await
+        .map_err(|e| {
+            cache_error!("(get_flight_applied) could not get {key}: {}", e);
+            AppliedError::OperationFailed
+        })?;
+
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    serde_json::from_slice(&value).map(Some).map_err(|e| {
+        cache_error!("(get_flight_applied) could not deserialize {key}: {}", e);
+        AppliedError::OperationFailed
+    })
+}
+
+/// Polls `lookup` until it returns an outcome or the timeout expires
+///
+/// Returns Ok(None) on timeout.
+async fn wait_for<F, Fut>(
+    mut lookup: F,
+    timeout: Duration,
+) -> Result<Option<FlightApplied>, AppliedError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<FlightApplied>, AppliedError>>,
+{
+    let poll = async {
+        loop {
+            if let Some(applied) = lookup().await? {
+                return Ok(applied);
+            }
+
+            tokio::time::sleep(Duration::from_millis(WAIT_POLL_INTERVAL_MS)).await;
+        }
+    };
+
+    match tokio::time::timeout(timeout, poll).await {
+        Ok(result) => result.map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Blocks until the flight path queued with `correlation_id` has been
+///  applied or `timeout_ms` expires
+///
+/// The timeout is capped at [`MAX_WAIT_FOR_FLIGHT_APPLIED_MS`].
+pub async fn wait_for_flight_applied(
+    correlation_id: &str,
+    timeout_ms: u64,
+) -> Result<WaitForFlightAppliedResponse, AppliedError> {
+    cache_debug!("(wait_for_flight_applied) entry, correlation_id: '{correlation_id}'.");
+    check_correlation_id(correlation_id)?;
+
+    let Some(pool) = super::status::STATUS_POOL.get() else {
+        cache_error!("(wait_for_flight_applied) could not get Redis pool.");
+        return Err(AppliedError::Client);
+    };
+
+    let mut connection = pool.get().await.map_err(|e| {
+        cache_error!(
+            "(wait_for_flight_applied) could not get connection from Redis pool: {}",
+            e
+        );
+        AppliedError::Client
+    })?;
+
+    let key = applied_key(correlation_id);
+    let timeout = Duration::from_millis(timeout_ms.min(MAX_WAIT_FOR_FLIGHT_APPLIED_MS));
+    let connection = tokio::sync::Mutex::new(&mut connection);
+    let applied = wait_for(
+        || async {
+            let mut connection = connection.lock().await;
+            get_flight_applied(&mut connection, &key).await
+        },
+        timeout,
+    )
+    .await?;
+
+    Ok(match applied {
+        Some(applied) => WaitForFlightAppliedResponse {
+            completed: true,
+            success: applied.success,
+            error: applied.error,
+        },
+        None => WaitForFlightAppliedResponse {
+            completed: false,
+            success: false,
+            error: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn ut_applied_key() {
+        assert_eq!(applied_key("abc-123"), "gis:flight:applied:abc-123");
+    }
+
+    #[test]
+    fn ut_check_correlation_id() {
+        assert!(check_correlation_id("6f1d9a4e-0b1c-4c55-a7c8-3d2e1f0a9b8c").is_ok());
+        assert_eq!(
+            check_correlation_id("").unwrap_err(),
+            AppliedError::CorrelationId
+        );
+        assert_eq!(
+            check_correlation_id("a:b").unwrap_err(),
+            AppliedError::CorrelationId
+        );
+        assert_eq!(
+            check_correlation_id(&"a".repeat(65)).unwrap_err(),
+            AppliedError::CorrelationId
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_wait_for_lagging_consumer() {
+        let record: Arc<Mutex<Option<FlightApplied>>> = Arc::new(Mutex::new(None));
+
+        // Consumer applies the flight path after a delay
+        let consumer_record = record.clone();
+        let consumer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            *consumer_record.lock().unwrap() = Some(FlightApplied {
+                success: false,
+                error: Some("Invalid Time Provided.".to_string()),
+            });
+        });
+
+        let start = std::time::Instant::now();
+        let applied = wait_for(
+            || {
+                let record = record.clone();
+                async move { Ok(record.lock().unwrap().clone()) }
+            },
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!applied.success);
+        assert_eq!(applied.error, Some("Invalid Time Provided.".to_string()));
+
+        consumer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn ut_wait_for_timeout() {
+        let result = wait_for(|| async { Ok(None) }, Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert_eq!(result, None);
+
+        let result = wait_for(
+            || async { Err(AppliedError::OperationFailed) },
+            Duration::from_millis(100),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(result, AppliedError::OperationFailed);
+    }
+
+    #[tokio::test]
+    async fn ut_wait_for_flight_applied_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_wait_for_flight_applied_client_failure) start");
+
+        let result = wait_for_flight_applied("abc-123", 100).await.unwrap_err();
+        assert_eq!(result, AppliedError::Client);
+
+        let result = wait_for_flight_applied("a b", 100).await.unwrap_err();
+        assert_eq!(result, AppliedError::CorrelationId);
+
+        ut_info!("(ut_wait_for_flight_applied_client_failure) success");
+    }
+}
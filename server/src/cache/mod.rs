@@ -3,7 +3,10 @@
 
 #[macro_use]
 pub mod macros;
+pub mod applied;
+pub mod pause;
 pub mod pool;
+pub mod status;
 
 use pool::RedisPool;
 use serde::Deserialize;
@@ -18,6 +21,13 @@ pub struct Consumer {
 
     /// The time to sleep between consuming data
     pub sleep_ms: u64,
+
+    /// Max duration of a consumed flight (0 disables the limit)
+    pub max_flight_duration_secs: u64,
+
+    /// Keep consumed identifications from moving aircraft to another
+    ///  operator
+    pub operator_enforcement: bool,
 }
 
 impl Consumer {
@@ -33,7 +43,12 @@ impl Consumer {
             return Err(());
         };
 
-        Ok(Self { pool, sleep_ms })
+        Ok(Self {
+            pool,
+            sleep_ms,
+            max_flight_duration_secs: config.max_flight_duration_secs,
+            operator_enforcement: config.operator_enforcement,
+        })
     }
 }
 
@@ -57,27 +72,35 @@ where
     fn sleep_ms(&self) -> u64;
 
     /// Starts a loop to consume data from the Redis queue
+    ///
+    /// Returns once shutdown begins, after processing the last popped items.
+    /// While paused (see [`pause`]) messages are left in the queue.
     async fn begin(&mut self) -> Result<(), ()> {
         let mut redis_pool: RedisPool = self.pool();
         let mut connection = redis_pool.pool.get().await.map_err(|e| {
             cache_error!("(AircraftConsumer::begin) could not get connection from Redis pool: {e}");
         })?;
 
-        loop {
-            match redis_pool.pop(&mut connection).await {
-                Ok(results) => {
-                    let _ = self.process(results).await;
-                }
-                Err(e) => {
-                    cache_error!(
-                        "(AircraftConsumer::begin) could not get aircraft from Redis: {}",
-                        e
-                    );
+        while !crate::shutdown::is_shutting_down() {
+            if let Some(_busy) = pause::active() {
+                match redis_pool.pop(&mut connection).await {
+                    Ok(results) => {
+                        let _ = self.process(results).await;
+                    }
+                    Err(e) => {
+                        cache_error!(
+                            "(AircraftConsumer::begin) could not get aircraft from Redis: {}",
+                            e
+                        );
+                    }
                 }
             }
 
             tokio::time::sleep(std::time::Duration::from_millis(self.sleep_ms())).await;
         }
+
+        cache_info!("(AircraftConsumer::begin) stopped for shutdown.");
+        Ok(())
     }
 }
 
@@ -0,0 +1,124 @@
+//! Pausing of the Redis consumers at runtime
+//!
+//! While paused the consumers leave messages in the Redis queues, so they
+//!  accumulate during maintenance and are processed once resumed.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// If the Redis consumers are paused
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Consumers currently popping or processing messages
+static BUSY: AtomicU64 = AtomicU64::new(0);
+
+/// Longest wait for consumers to finish their messages when pausing
+const PAUSE_TIMEOUT_MS: u64 = 30_000;
+
+/// Time between checks for idle consumers when pausing
+const PAUSE_POLL_MS: u64 = 10;
+
+/// Marks a consumer as busy until dropped
+#[derive(Debug)]
+pub struct Busy(());
+
+impl Drop for Busy {
+    fn drop(&mut self) {
+        BUSY.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Marks a consumer as busy, or None if the consumers are paused
+///
+/// The consumer is counted before the flag is checked, so [`pause`] can't
+///  miss messages popped right as it sets the flag.
+pub fn active() -> Option<Busy> {
+    BUSY.fetch_add(1, Ordering::SeqCst);
+    let busy = Busy(());
+    match PAUSED.load(Ordering::SeqCst) {
+        true => None,
+        false => Some(busy),
+    }
+}
+
+/// If the Redis consumers are paused
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Pauses the Redis consumers
+///
+/// Waits for messages being processed to finish. Returns false if they
+///  didn't within [`PAUSE_TIMEOUT_MS`], the consumers stay paused anyway.
+pub async fn pause() -> bool {
+    cache_info!("(pause) pausing Redis consumers.");
+    PAUSED.store(true, Ordering::SeqCst);
+
+    let mut waited_ms = 0;
+    while BUSY.load(Ordering::SeqCst) > 0 {
+        if waited_ms >= PAUSE_TIMEOUT_MS {
+            cache_warn!("(pause) consumers still busy after {waited_ms}ms.");
+            return false;
+        }
+
+        tokio::time::sleep(Duration::from_millis(PAUSE_POLL_MS)).await;
+        waited_ms += PAUSE_POLL_MS;
+    }
+
+    cache_info!("(pause) Redis consumers paused.");
+    true
+}
+
+/// Resumes the Redis consumers, returns true if they were paused
+pub fn resume() -> bool {
+    cache_info!("(resume) resuming Redis consumers.");
+    PAUSED.swap(false, Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn ut_pause_resume() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_pause_resume) start");
+
+        // A consumer taking 20ms per message
+        let processed = Arc::new(AtomicU64::new(0));
+        let counter = processed.clone();
+        let consumer = tokio::spawn(async move {
+            loop {
+                if let Some(_busy) = active() {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(processed.load(Ordering::SeqCst) > 0);
+
+        // The message in progress finishes before the pause returns
+        assert!(pause().await);
+        assert!(is_paused());
+        assert_eq!(BUSY.load(Ordering::SeqCst), 0);
+
+        let paused_at = processed.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(processed.load(Ordering::SeqCst), paused_at);
+
+        assert!(resume());
+        assert!(!resume());
+        assert!(!is_paused());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(processed.load(Ordering::SeqCst) > paused_at);
+
+        consumer.abort();
+        ut_info!("(ut_pause_resume) success");
+    }
+}
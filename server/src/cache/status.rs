@@ -0,0 +1,213 @@
+//! Ingestion status of the Redis queues
+
+use crate::grpc::server::grpc_server::{GetIngestionStatusResponse, QueueStatus};
+use crate::types::{
+    REDIS_KEY_AIRCRAFT_ID, REDIS_KEY_AIRCRAFT_POSITION, REDIS_KEY_AIRCRAFT_TELEMETRY,
+    REDIS_KEY_AIRCRAFT_VELOCITY, REDIS_KEY_FLIGHT_PATH,
+};
+use chrono::{DateTime, Utc};
+use deadpool_redis::redis;
+use once_cell::sync::OnceCell;
+use std::sync::Mutex;
+
+/// Redis pool used to inspect the ingestion queues
+pub static STATUS_POOL: OnceCell<deadpool_redis::Pool> = OnceCell::new();
+
+/// Queues consumed by this service
+pub const INGESTION_QUEUES: [&str; 5] = [
+    REDIS_KEY_AIRCRAFT_ID,
+    REDIS_KEY_AIRCRAFT_POSITION,
+    REDIS_KEY_AIRCRAFT_VELOCITY,
+    REDIS_KEY_AIRCRAFT_TELEMETRY,
+    REDIS_KEY_FLIGHT_PATH,
+];
+
+/// Possible errors getting the ingestion status
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StatusError {
+    /// Could not get client
+    Client,
+
+    /// Redis operation failed
+    OperationFailed,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StatusError::Client => write!(f, "Could not get cache client."),
+            StatusError::OperationFailed => write!(f, "Cache operation failed."),
+        }
+    }
+}
+
+/// Ingestion queues at the last status read, for inspection
+static LAST_QUEUES: Mutex<Vec<QueueStatus>> = Mutex::new(Vec::new());
+
+/// Gets the field of the messages of a queue holding the time they were
+///  queued
+///
+/// Flight paths carry no network timestamp, producers stamp the time they
+///  queued them instead.
+fn timestamp_field(queue: &str) -> &'static str {
+    match queue {
+        REDIS_KEY_FLIGHT_PATH => "timestamp_enqueued",
+        _ => "timestamp_network",
+    }
+}
+
+/// Gets the age of a queued message from its timestamp `field`
+///
+/// Returns None if the message has no valid timestamp.
+fn message_age_ms(data: &[u8], field: &str, now: DateTime<Utc>) -> Option<u64> {
+    let value: serde_json::Value = serde_json::from_slice(data).ok()?;
+    let timestamp = value.get(field)?.as_str()?;
+    let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let age = now.signed_duration_since(timestamp.with_timezone(&Utc));
+
+    Some(age.num_milliseconds().max(0) as u64)
+}
+
+/// Gets the depth and the age of the oldest message of each ingestion queue
+///  at the last status read, see [`get_ingestion_status`]
+pub fn queue_gauges() -> Vec<QueueStatus> {
+    match LAST_QUEUES.lock() {
+        Ok(queues) => queues.clone(),
+        Err(e) => e.into_inner().clone(),
+    }
+}
+
+/// Gets the depth of each ingestion queue and the age of its oldest message
+pub async fn get_ingestion_status() -> Result<GetIngestionStatusResponse, StatusError> {
+    cache_debug!("(get_ingestion_status) entry.");
+
+    let Some(pool) = STATUS_POOL.get() else {
+        cache_error!("(get_ingestion_status) could not get Redis pool.");
+        return Err(StatusError::Client);
+    };
+
+    let mut connection = pool.get().await.map_err(|e| {
+        cache_error!(
+            "(get_ingestion_status) could not get connection from Redis pool: {}",
+            e
+        );
+        StatusError::Client
+    })?;
+
+    let now = Utc::now();
+    let mut queues: Vec<QueueStatus> = vec![];
+    for queue in INGESTION_QUEUES {
+        let depth: u64 = redis::cmd("LLEN")
+            .arg(queue)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| {
+                cache_error!(
+                    "(get_ingestion_status) could not get depth of {queue}: {}",
+                    e
+                );
+                StatusError::OperationFailed
+            })?;
+
+        // Producers and consumers both work on the tail of the list,
+        //  so the oldest message is at the head
+        let oldest: Option<Vec<u8>> = redis::cmd("LINDEX")
+            .arg(queue)
+            .arg(0)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| {
+                cache_error!(
+                    "(get_ingestion_status) could not get oldest message of {queue}: {}",
+                    e
+                );
+                StatusError::OperationFailed
+            })?;
+
+        queues.push(QueueStatus {
+            queue: queue.to_string(),
+            depth,
+            oldest_age_ms: oldest
+                .and_then(|data| message_age_ms(&data, timestamp_field(queue), now)),
+        });
+    }
+
+    match LAST_QUEUES.lock() {
+        Ok(mut last) => *last = queues.clone(),
+        Err(e) => *e.into_inner() = queues.clone(),
+    }
+
+    Ok(GetIngestionStatusResponse {
+        queues,
+        paused: super::pause::is_paused(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AircraftPosition, Position};
+    use chrono::Duration;
+
+    #[test]
+    fn ut_message_age_ms() {
+        let now = Utc::now();
+        let position = AircraftPosition {
+            identifier: "Aircraft".to_string(),
+            position: Position {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: now - Duration::try_seconds(5).unwrap(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        };
+
+        let field = timestamp_field(REDIS_KEY_AIRCRAFT_POSITION);
+        let data = serde_json::to_vec(&position).unwrap();
+        assert_eq!(message_age_ms(&data, field, now), Some(5000));
+
+        // Clock skew doesn't produce negative ages
+        let data = serde_json::to_vec(&AircraftPosition {
+            timestamp_network: now + Duration::try_seconds(1).unwrap(),
+            ..position
+        })
+        .unwrap();
+        assert_eq!(message_age_ms(&data, field, now), Some(0));
+
+        assert_eq!(message_age_ms(b"not json", field, now), None);
+        assert_eq!(message_age_ms(br#"{"identifier": "x"}"#, field, now), None);
+
+        // Flight paths are aged from the time they were queued
+        let field = timestamp_field(REDIS_KEY_FLIGHT_PATH);
+        let timestamp_enqueued = now - Duration::try_seconds(2).unwrap();
+        let data = serde_json::to_vec(&serde_json::json!({
+            "flight_identifier": "FLIGHT-1",
+            "timestamp_start": now,
+            "timestamp_enqueued": timestamp_enqueued,
+        }))
+        .unwrap();
+        assert_eq!(message_age_ms(&data, field, now), Some(2000));
+
+        // Without a stamp from the producer
+        let data = serde_json::to_vec(&serde_json::json!({
+            "flight_identifier": "FLIGHT-1",
+            "timestamp_start": now,
+        }))
+        .unwrap();
+        assert_eq!(message_age_ms(&data, field, now), None);
+    }
+
+    #[tokio::test]
+    async fn ut_get_ingestion_status_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_ingestion_status_client_failure) start");
+
+        let result = get_ingestion_status().await.unwrap_err();
+        assert_eq!(result, StatusError::Client);
+
+        ut_info!("(ut_get_ingestion_status_client_failure) success");
+    }
+}
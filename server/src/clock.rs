@@ -0,0 +1,119 @@
+//! Source of the current time
+//!
+//! Validations compare against [`now`] instead of `Utc::now()` so that
+//!  replays and tests can control the time. The system clock is used
+//!  unless a replay start time is set at startup.
+//!
+//! Elapsed time (e.g. routing time limits) is still measured with the
+//!  system clock.
+
+use chrono::{DateTime, Duration, Utc};
+use std::cell::Cell;
+use std::sync::RwLock;
+
+/// Time source of the service
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Clock {
+    /// The system clock
+    System,
+
+    /// The system clock shifted to a replayed time, advancing in real time
+    Replay {
+        /// Replayed time minus system time
+        offset: Duration,
+    },
+}
+
+/// Time source of the service, shared by all threads
+static CLOCK: RwLock<Clock> = RwLock::new(Clock::System);
+
+thread_local! {
+    /// Time frozen by [`freeze`] for the current thread only
+    static FROZEN: Cell<Option<DateTime<Utc>>> = const { Cell::new(None) };
+}
+
+/// Gets the current time of the service
+pub fn now() -> DateTime<Utc> {
+    if let Some(frozen) = FROZEN.with(Cell::get) {
+        return frozen;
+    }
+
+    match *CLOCK.read().unwrap_or_else(|e| e.into_inner()) {
+        Clock::System => Utc::now(),
+        Clock::Replay { offset } => Utc::now() + offset,
+    }
+}
+
+/// Sets the time source of the service
+pub fn set(clock: Clock) {
+    *CLOCK.write().unwrap_or_else(|e| e.into_inner()) = clock;
+}
+
+/// Starts a replay: the clock jumps to `time_start` and advances in real
+///  time from there
+pub fn start_replay(time_start: DateTime<Utc>) {
+    set(Clock::Replay {
+        offset: time_start - Utc::now(),
+    });
+}
+
+/// Time frozen for the current thread, unfrozen when dropped
+#[derive(Debug)]
+pub struct FrozenClock {
+    /// Frozen time of the thread before this one, for nesting
+    previous: Option<DateTime<Utc>>,
+}
+
+impl FrozenClock {
+    /// Moves the frozen time forward (or back, with a negative duration)
+    pub fn advance(&self, duration: Duration) {
+        FROZEN.with(|frozen| frozen.set(frozen.get().map(|time| time + duration)));
+    }
+}
+
+impl Drop for FrozenClock {
+    fn drop(&mut self) {
+        FROZEN.with(|frozen| frozen.set(self.previous));
+    }
+}
+
+/// Freezes [`now`] at a time for the current thread
+///
+/// Only the calling thread sees the frozen time so that tests running in
+///  parallel don't affect each other. Tasks spawned on other threads of a
+///  multi-threaded runtime see the shared clock.
+pub fn freeze(time: DateTime<Utc>) -> FrozenClock {
+    FrozenClock {
+        previous: FROZEN.with(|frozen| frozen.replace(Some(time))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_freeze() {
+        let time = Utc::now() - Duration::try_days(30).unwrap();
+        {
+            let clock = freeze(time);
+            assert_eq!(now(), time);
+
+            clock.advance(Duration::try_minutes(5).unwrap());
+            assert_eq!(now(), time + Duration::try_minutes(5).unwrap());
+
+            // Nested freezes restore the outer time
+            {
+                let _inner = freeze(time - Duration::try_days(1).unwrap());
+                assert_eq!(now(), time - Duration::try_days(1).unwrap());
+            }
+            assert_eq!(now(), time + Duration::try_minutes(5).unwrap());
+
+            // Other threads keep the shared clock
+            let other = std::thread::spawn(now).join().unwrap();
+            assert!(other > time + Duration::try_days(29).unwrap());
+        }
+
+        assert!(now() > time + Duration::try_days(29).unwrap());
+    }
+}
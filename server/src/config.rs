@@ -20,10 +20,118 @@ pub struct Config {
     pub db_client_key: String,
     /// port to be used for gRPC server
     pub docker_port_grpc: u16,
+    /// port to be used for the metrics endpoint (0 disables it)
+    pub docker_port_rest: u16,
     /// path to log configuration YAML file
     pub log_config: String,
+    /// log output format, `text` uses the encoders of the log configuration
+    ///  file and `json` writes structured records to its appenders instead
+    pub log_format: String,
     /// redis details
     pub redis: deadpool_redis::Config,
+    /// interval between database maintenance runs (0 disables maintenance)
+    pub pg_maintenance_interval_secs: u64,
+    /// if VACUUM should be run along with ANALYZE during maintenance
+    pub pg_maintenance_vacuum: bool,
+    /// size of the dedicated aircraft telemetry pool (0 shares the main pool)
+    pub pg_telemetry_pool_size: usize,
+    /// comma separated databases holding extra aircraft shards, on the
+    ///  same server as the primary (unset keeps all aircraft on the primary)
+    pub pg_aircraft_shards: Option<String>,
+    /// time after which the database cancels a statement (0 disables the
+    ///  timeout)
+    pub pg_statement_timeout_secs: u64,
+    /// interval between vertiport throughput recordings (0 disables recording)
+    pub throughput_interval_secs: u64,
+    /// max distance from a vertiport for a path endpoint to count as its flight
+    pub vertiport_snap_distance_meters: f64,
+    /// max wait for another replica to finish database initialization
+    pub psql_init_lock_timeout_secs: u64,
+    /// time after deletion during which zones and flights can be restored
+    pub soft_delete_undo_window_secs: u64,
+    /// time after deletion before zones and flights are purged
+    pub soft_delete_retention_secs: u64,
+    /// interval between purges of soft-deleted zones and flights and of
+    ///  expired flight reservations (0 disables purging)
+    pub soft_delete_purge_interval_secs: u64,
+    /// age after which aircraft positions are archived by maintenance
+    ///  (0 disables the archival)
+    pub history_hot_retention_secs: u64,
+    /// age after which archived aircraft positions are removed (0 keeps them)
+    pub history_archive_retention_secs: u64,
+    /// width of an archived aircraft position bucket
+    pub history_archive_bucket_secs: u32,
+    /// max duration of a flight path update (0 disables the limit)
+    pub max_flight_duration_secs: u64,
+    /// max wait for in-flight database transactions on shutdown
+    pub shutdown_grace_period_secs: u64,
+    /// interval between checks for airborne aircraft without a flight
+    ///  (0 disables the check)
+    pub compliance_check_interval_secs: u64,
+    /// time an aircraft must be airborne without a flight before an alert
+    pub compliance_debounce_secs: u64,
+    /// if simulated aircraft are checked for a flight
+    pub compliance_include_simulated: bool,
+    /// interval between conflict checks of upcoming flights (0 disables the
+    ///  check)
+    pub conflict_check_interval_secs: u64,
+    /// how far ahead flights are checked for conflicts, by start time
+    pub conflict_check_horizon_secs: u64,
+    /// max flights checked for conflicts at the same time
+    pub conflict_check_concurrency: u32,
+    /// comma separated tags of flights left out of flight validation and
+    ///  conflict checks, e.g. exercise traffic (none if unset)
+    pub conflict_exclude_tags: Option<String>,
+    /// comma-separated `longitude latitude` vertices of the operating region
+    ///  outside of which aircraft positions, flight paths and zones are
+    ///  rejected (no restriction if unset)
+    pub service_area: Option<String>,
+    /// distance outside of the service area still accepted, in meters
+    pub service_area_buffer_meters: f64,
+    /// if best_path cross-checks routed distances against the geodesic
+    ///  distance (debugging aid)
+    pub best_path_distance_check: bool,
+    /// if flight updates, deletions, restorations and confirmations
+    ///  carrying an operator claim may only touch that operator's flights,
+    ///  and aircraft writes only that operator's aircraft
+    pub operator_enforcement: bool,
+    /// how aircraft positions are written: "upsert" overwrites the latest
+    ///  position, "append" only appends to the history
+    pub aircraft_position_mode: String,
+    /// quantization step of stored longitudes and latitudes in degrees
+    ///  (0 keeps the full precision)
+    pub coordinate_quantum_degrees: f64,
+    /// quantization step of stored altitudes in meters (0 keeps the full
+    ///  precision)
+    pub altitude_quantum_meters: f64,
+    /// if the unquantized aircraft positions are kept in the history
+    pub keep_raw_position_history: bool,
+    /// consistent reports needed to accept an aircraft at a position it
+    ///  couldn't have flown to (0 disables the plausibility check)
+    pub relocation_reports: u32,
+    /// tasks writing queued aircraft positions and telemetry (0 writes them
+    ///  synchronously)
+    pub ingest_writers: u32,
+    /// aircraft with pending updates before updates are shed
+    pub ingest_queue_capacity: u32,
+    /// rounding step of returned distances and altitudes in meters (0
+    ///  keeps the full single precision)
+    pub distance_resolution_meters: f64,
+    /// RFC 3339 time the service clock starts at for replays (system time
+    ///  if unset)
+    pub replay_start_time: Option<String>,
+    /// age of the newest aircraft position after which readiness is
+    ///  degraded (0 disables the probe)
+    pub readiness_position_max_age_secs: u64,
+    /// depth of an ingestion queue after which readiness is degraded (0
+    ///  disables the probe)
+    pub readiness_queue_max_depth: u64,
+    /// age of the oldest queued message after which readiness is degraded
+    ///  (0 disables the probe)
+    pub readiness_queue_max_lag_secs: u64,
+    /// time since the last flight update after which readiness is degraded
+    ///  (0 disables the probe)
+    pub readiness_flight_update_max_age_secs: u64,
 }
 
 impl Default for Config {
@@ -38,7 +146,9 @@ impl Config {
     pub fn new() -> Self {
         Config {
             docker_port_grpc: 50051,
+            docker_port_rest: 8000,
             log_config: String::from("log4rs.yaml"),
+            log_format: String::from("text"),
             pg: deadpool_postgres::Config::new(),
             db_ca_cert: "".to_string(),
             db_client_cert: "".to_string(),
@@ -48,6 +158,46 @@ impl Config {
                 pool: None,
                 connection: None,
             },
+            pg_maintenance_interval_secs: 0,
+            pg_maintenance_vacuum: false,
+            pg_telemetry_pool_size: 4,
+            pg_aircraft_shards: None,
+            pg_statement_timeout_secs: 30,
+            throughput_interval_secs: 300,
+            vertiport_snap_distance_meters: 100.0,
+            psql_init_lock_timeout_secs: 120,
+            soft_delete_undo_window_secs: 86_400,
+            soft_delete_retention_secs: 604_800,
+            soft_delete_purge_interval_secs: 3600,
+            history_hot_retention_secs: 604_800,
+            history_archive_retention_secs: 7_776_000,
+            history_archive_bucket_secs: 10,
+            max_flight_duration_secs: 43_200,
+            shutdown_grace_period_secs: 30,
+            compliance_check_interval_secs: 30,
+            compliance_debounce_secs: 60,
+            compliance_include_simulated: false,
+            conflict_check_interval_secs: 60,
+            conflict_check_horizon_secs: 1800,
+            conflict_check_concurrency: 4,
+            conflict_exclude_tags: None,
+            service_area: None,
+            service_area_buffer_meters: 0.0,
+            best_path_distance_check: false,
+            operator_enforcement: false,
+            aircraft_position_mode: String::from("upsert"),
+            coordinate_quantum_degrees: 1e-7,
+            altitude_quantum_meters: 0.1,
+            keep_raw_position_history: false,
+            relocation_reports: 3,
+            ingest_writers: 0,
+            ingest_queue_capacity: 10_000,
+            distance_resolution_meters: 0.1,
+            replay_start_time: None,
+            readiness_position_max_age_secs: 60,
+            readiness_queue_max_depth: 10_000,
+            readiness_queue_max_lag_secs: 30,
+            readiness_flight_update_max_age_secs: 0,
         }
     }
 
@@ -59,7 +209,144 @@ impl Config {
 
         config::Config::builder()
             .set_default("docker_port_grpc", default_config.docker_port_grpc)?
+            .set_default("docker_port_rest", default_config.docker_port_rest)?
             .set_default("log_config", default_config.log_config)?
+            .set_default("log_format", default_config.log_format)?
+            .set_default(
+                "pg_maintenance_interval_secs",
+                default_config.pg_maintenance_interval_secs,
+            )?
+            .set_default(
+                "pg_maintenance_vacuum",
+                default_config.pg_maintenance_vacuum,
+            )?
+            .set_default(
+                "pg_telemetry_pool_size",
+                default_config.pg_telemetry_pool_size as u64,
+            )?
+            .set_default(
+                "pg_statement_timeout_secs",
+                default_config.pg_statement_timeout_secs,
+            )?
+            .set_default(
+                "throughput_interval_secs",
+                default_config.throughput_interval_secs,
+            )?
+            .set_default(
+                "vertiport_snap_distance_meters",
+                default_config.vertiport_snap_distance_meters,
+            )?
+            .set_default(
+                "psql_init_lock_timeout_secs",
+                default_config.psql_init_lock_timeout_secs,
+            )?
+            .set_default(
+                "soft_delete_undo_window_secs",
+                default_config.soft_delete_undo_window_secs,
+            )?
+            .set_default(
+                "soft_delete_retention_secs",
+                default_config.soft_delete_retention_secs,
+            )?
+            .set_default(
+                "soft_delete_purge_interval_secs",
+                default_config.soft_delete_purge_interval_secs,
+            )?
+            .set_default(
+                "history_hot_retention_secs",
+                default_config.history_hot_retention_secs,
+            )?
+            .set_default(
+                "history_archive_retention_secs",
+                default_config.history_archive_retention_secs,
+            )?
+            .set_default(
+                "history_archive_bucket_secs",
+                default_config.history_archive_bucket_secs,
+            )?
+            .set_default(
+                "max_flight_duration_secs",
+                default_config.max_flight_duration_secs,
+            )?
+            .set_default(
+                "shutdown_grace_period_secs",
+                default_config.shutdown_grace_period_secs,
+            )?
+            .set_default(
+                "compliance_check_interval_secs",
+                default_config.compliance_check_interval_secs,
+            )?
+            .set_default(
+                "compliance_debounce_secs",
+                default_config.compliance_debounce_secs,
+            )?
+            .set_default(
+                "compliance_include_simulated",
+                default_config.compliance_include_simulated,
+            )?
+            .set_default(
+                "conflict_check_interval_secs",
+                default_config.conflict_check_interval_secs,
+            )?
+            .set_default(
+                "conflict_check_horizon_secs",
+                default_config.conflict_check_horizon_secs,
+            )?
+            .set_default(
+                "conflict_check_concurrency",
+                default_config.conflict_check_concurrency,
+            )?
+            .set_default(
+                "service_area_buffer_meters",
+                default_config.service_area_buffer_meters,
+            )?
+            .set_default(
+                "best_path_distance_check",
+                default_config.best_path_distance_check,
+            )?
+            .set_default("operator_enforcement", default_config.operator_enforcement)?
+            .set_default(
+                "aircraft_position_mode",
+                default_config.aircraft_position_mode,
+            )?
+            .set_default(
+                "coordinate_quantum_degrees",
+                default_config.coordinate_quantum_degrees,
+            )?
+            .set_default(
+                "altitude_quantum_meters",
+                default_config.altitude_quantum_meters,
+            )?
+            .set_default(
+                "keep_raw_position_history",
+                default_config.keep_raw_position_history,
+            )?
+            .set_default("relocation_reports", default_config.relocation_reports)?
+            .set_default("ingest_writers", default_config.ingest_writers)?
+            .set_default(
+                "ingest_queue_capacity",
+                default_config.ingest_queue_capacity,
+            )?
+            .set_default(
+                "distance_resolution_meters",
+                default_config.distance_resolution_meters,
+            )?
+            .set_default(
+                "readiness_position_max_age_secs",
+                default_config.readiness_position_max_age_secs,
+            )?
+            .set_default(
+                "readiness_queue_max_depth",
+                default_config.readiness_queue_max_depth,
+            )?
+            .set_default(
+                "readiness_queue_max_lag_secs",
+                default_config.readiness_queue_max_lag_secs,
+            )?
+            .set_default(
+                "readiness_flight_update_max_age_secs",
+                default_config.readiness_flight_update_max_age_secs,
+            )?
             .add_source(Environment::default().separator("__"))
             .build()?
             .try_deserialize()
@@ -78,10 +365,52 @@ mod tests {
         let config = Config::default();
 
         assert_eq!(config.docker_port_grpc, 50051);
+        assert_eq!(config.docker_port_rest, 8000);
         assert_eq!(config.log_config, String::from("log4rs.yaml"));
+        assert_eq!(config.log_format, String::from("text"));
         assert!(config.redis.url.is_none());
         assert!(config.redis.pool.is_none());
         assert!(config.redis.connection.is_none());
+        assert_eq!(config.pg_maintenance_interval_secs, 0);
+        assert!(!config.pg_maintenance_vacuum);
+        assert_eq!(config.pg_telemetry_pool_size, 4);
+        assert!(config.pg_aircraft_shards.is_none());
+        assert_eq!(config.pg_statement_timeout_secs, 30);
+        assert_eq!(config.throughput_interval_secs, 300);
+        assert_eq!(config.vertiport_snap_distance_meters, 100.0);
+        assert_eq!(config.psql_init_lock_timeout_secs, 120);
+        assert_eq!(config.soft_delete_undo_window_secs, 86_400);
+        assert_eq!(config.soft_delete_retention_secs, 604_800);
+        assert_eq!(config.soft_delete_purge_interval_secs, 3600);
+        assert_eq!(config.history_hot_retention_secs, 604_800);
+        assert_eq!(config.history_archive_retention_secs, 7_776_000);
+        assert_eq!(config.history_archive_bucket_secs, 10);
+        assert_eq!(config.max_flight_duration_secs, 43_200);
+        assert_eq!(config.shutdown_grace_period_secs, 30);
+        assert_eq!(config.compliance_check_interval_secs, 30);
+        assert_eq!(config.compliance_debounce_secs, 60);
+        assert!(!config.compliance_include_simulated);
+        assert_eq!(config.conflict_check_interval_secs, 60);
+        assert_eq!(config.conflict_check_horizon_secs, 1800);
+        assert_eq!(config.conflict_check_concurrency, 4);
+        assert!(config.conflict_exclude_tags.is_none());
+        assert!(config.service_area.is_none());
+        assert_eq!(config.service_area_buffer_meters, 0.0);
+        assert!(!config.best_path_distance_check);
+        assert!(!config.operator_enforcement);
+        assert_eq!(config.aircraft_position_mode, String::from("upsert"));
+        assert_eq!(config.coordinate_quantum_degrees, 1e-7);
+        assert_eq!(config.altitude_quantum_meters, 0.1);
+        assert!(!config.keep_raw_position_history);
+        assert_eq!(config.relocation_reports, 3);
+        assert_eq!(config.ingest_writers, 0);
+        assert_eq!(config.ingest_queue_capacity, 10_000);
+        assert_eq!(config.distance_resolution_meters, 0.1);
+        assert!(config.replay_start_time.is_none());
+        assert_eq!(config.readiness_position_max_age_secs, 60);
+        assert_eq!(config.readiness_queue_max_depth, 10_000);
+        assert_eq!(config.readiness_queue_max_lag_secs, 30);
+        assert_eq!(config.readiness_flight_update_max_age_secs, 0);
 
         ut_info!("(test_config_from_default) Success.");
     }
@@ -92,23 +421,116 @@ mod tests {
         ut_info!("(test_config_from_default) Start.");
 
         std::env::set_var("DOCKER_PORT_GRPC", "6789");
+        std::env::set_var("DOCKER_PORT_REST", "6790");
         std::env::set_var("LOG_CONFIG", "config_file.yaml");
+        std::env::set_var("LOG_FORMAT", "json");
         std::env::set_var("REDIS__URL", "redis://test_redis:6379");
         std::env::set_var("REDIS__POOL__MAX_SIZE", "16");
         std::env::set_var("REDIS__POOL__TIMEOUTS__WAIT__SECS", "2");
         std::env::set_var("REDIS__POOL__TIMEOUTS__WAIT__NANOS", "0");
+        std::env::set_var("PG_MAINTENANCE_INTERVAL_SECS", "3600");
+        std::env::set_var("PG_MAINTENANCE_VACUUM", "true");
+        std::env::set_var("PG_TELEMETRY_POOL_SIZE", "2");
+        std::env::set_var("PG_AIRCRAFT_SHARDS", "gis_shard_1");
+        std::env::set_var("PG_STATEMENT_TIMEOUT_SECS", "5");
+        std::env::set_var("THROUGHPUT_INTERVAL_SECS", "60");
+        std::env::set_var("VERTIPORT_SNAP_DISTANCE_METERS", "250.5");
+        std::env::set_var("PSQL_INIT_LOCK_TIMEOUT_SECS", "30");
+        std::env::set_var("SOFT_DELETE_UNDO_WINDOW_SECS", "600");
+        std::env::set_var("SOFT_DELETE_RETENTION_SECS", "3600");
+        std::env::set_var("SOFT_DELETE_PURGE_INTERVAL_SECS", "300");
+        std::env::set_var("HISTORY_HOT_RETENTION_SECS", "86400");
+        std::env::set_var("HISTORY_ARCHIVE_RETENTION_SECS", "2592000");
+        std::env::set_var("HISTORY_ARCHIVE_BUCKET_SECS", "60");
+        std::env::set_var("MAX_FLIGHT_DURATION_SECS", "7200");
+        std::env::set_var("SHUTDOWN_GRACE_PERIOD_SECS", "10");
+        std::env::set_var("COMPLIANCE_CHECK_INTERVAL_SECS", "15");
+        std::env::set_var("COMPLIANCE_DEBOUNCE_SECS", "120");
+        std::env::set_var("COMPLIANCE_INCLUDE_SIMULATED", "true");
+        std::env::set_var("CONFLICT_CHECK_INTERVAL_SECS", "120");
+        std::env::set_var("CONFLICT_CHECK_HORIZON_SECS", "3600");
+        std::env::set_var("CONFLICT_CHECK_CONCURRENCY", "2");
+        std::env::set_var("CONFLICT_EXCLUDE_TAGS", "exercise-redwing,training");
+        std::env::set_var("SERVICE_AREA", "4.8 52.3, 5.0 52.3, 5.0 52.4");
+        std::env::set_var("SERVICE_AREA_BUFFER_METERS", "500.0");
+        std::env::set_var("BEST_PATH_DISTANCE_CHECK", "true");
+        std::env::set_var("OPERATOR_ENFORCEMENT", "true");
+        std::env::set_var("AIRCRAFT_POSITION_MODE", "append");
+        std::env::set_var("COORDINATE_QUANTUM_DEGREES", "0.000001");
+        std::env::set_var("ALTITUDE_QUANTUM_METERS", "0.5");
+        std::env::set_var("KEEP_RAW_POSITION_HISTORY", "true");
+        std::env::set_var("RELOCATION_REPORTS", "5");
+        std::env::set_var("INGEST_WRITERS", "4");
+        std::env::set_var("INGEST_QUEUE_CAPACITY", "500");
+        std::env::set_var("DISTANCE_RESOLUTION_METERS", "0.01");
+        std::env::set_var("REPLAY_START_TIME", "2024-05-01T08:00:00Z");
+        std::env::set_var("READINESS_POSITION_MAX_AGE_SECS", "120");
+        std::env::set_var("READINESS_QUEUE_MAX_DEPTH", "0");
+        std::env::set_var("READINESS_QUEUE_MAX_LAG_SECS", "10");
+        std::env::set_var("READINESS_FLIGHT_UPDATE_MAX_AGE_SECS", "900");
 
         let config = Config::try_from_env();
         assert!(config.is_ok());
         let config = config.unwrap();
 
         assert_eq!(config.docker_port_grpc, 6789);
+        assert_eq!(config.docker_port_rest, 6790);
         assert_eq!(config.log_config, String::from("config_file.yaml"));
+        assert_eq!(config.log_format, String::from("json"));
         assert_eq!(
             config.redis.url,
             Some(String::from("redis://test_redis:6379"))
         );
         assert!(config.redis.pool.is_some());
+        assert_eq!(config.pg_maintenance_interval_secs, 3600);
+        assert!(config.pg_maintenance_vacuum);
+        assert_eq!(config.pg_telemetry_pool_size, 2);
+        assert_eq!(config.pg_aircraft_shards, Some(String::from("gis_shard_1")));
+        assert_eq!(config.pg_statement_timeout_secs, 5);
+        assert_eq!(config.throughput_interval_secs, 60);
+        assert_eq!(config.vertiport_snap_distance_meters, 250.5);
+        assert_eq!(config.psql_init_lock_timeout_secs, 30);
+        assert_eq!(config.soft_delete_undo_window_secs, 600);
+        assert_eq!(config.soft_delete_retention_secs, 3600);
+        assert_eq!(config.soft_delete_purge_interval_secs, 300);
+        assert_eq!(config.history_hot_retention_secs, 86400);
+        assert_eq!(config.history_archive_retention_secs, 2_592_000);
+        assert_eq!(config.history_archive_bucket_secs, 60);
+        assert_eq!(config.max_flight_duration_secs, 7200);
+        assert_eq!(config.shutdown_grace_period_secs, 10);
+        assert_eq!(config.compliance_check_interval_secs, 15);
+        assert_eq!(config.compliance_debounce_secs, 120);
+        assert!(config.compliance_include_simulated);
+        assert_eq!(config.conflict_check_interval_secs, 120);
+        assert_eq!(config.conflict_check_horizon_secs, 3600);
+        assert_eq!(config.conflict_check_concurrency, 2);
+        assert_eq!(
+            config.conflict_exclude_tags,
+            Some(String::from("exercise-redwing,training"))
+        );
+        assert_eq!(
+            config.service_area,
+            Some(String::from("4.8 52.3, 5.0 52.3, 5.0 52.4"))
+        );
+        assert_eq!(config.service_area_buffer_meters, 500.0);
+        assert!(config.best_path_distance_check);
+        assert!(config.operator_enforcement);
+        assert_eq!(config.aircraft_position_mode, String::from("append"));
+        assert_eq!(config.coordinate_quantum_degrees, 0.000001);
+        assert_eq!(config.altitude_quantum_meters, 0.5);
+        assert!(config.keep_raw_position_history);
+        assert_eq!(config.relocation_reports, 5);
+        assert_eq!(config.ingest_writers, 4);
+        assert_eq!(config.ingest_queue_capacity, 500);
+        assert_eq!(config.distance_resolution_meters, 0.01);
+        assert_eq!(
+            config.replay_start_time,
+            Some(String::from("2024-05-01T08:00:00Z"))
+        );
+        assert_eq!(config.readiness_position_max_age_secs, 120);
+        assert_eq!(config.readiness_queue_max_depth, 0);
+        assert_eq!(config.readiness_queue_max_lag_secs, 10);
+        assert_eq!(config.readiness_flight_update_max_age_secs, 900);
 
         ut_info!("(test_config_from_env) Success.");
     }
@@ -15,128 +15,1156 @@ use std::net::SocketAddr;
 use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 
+/// Stream of compliance alerts returned by `stream_compliance_alerts`
+pub type ComplianceAlertStream = std::pin::Pin<
+    Box<dyn futures::Stream<Item = Result<grpc_server::ComplianceAlert, Status>> + Send>,
+>;
+
+/// Stream of GeoJSON chunks returned by `stream_aircraft_geo_json`
+pub type GeoJsonChunkStream = std::pin::Pin<
+    Box<dyn futures::Stream<Item = Result<grpc_server::GeoJsonChunk, Status>> + Send>,
+>;
+
+/// Stream of position events returned by `stream_aircraft_positions`
+pub type AircraftPositionEventStream = std::pin::Pin<
+    Box<dyn futures::Stream<Item = Result<grpc_server::AircraftPositionEvent, Status>> + Send>,
+>;
+
+/// Stream of zone changes returned by `stream_zone_changes`
+pub type ZoneChangedStream =
+    std::pin::Pin<Box<dyn futures::Stream<Item = Result<grpc_server::ZoneChanged, Status>> + Send>>;
+
+/// Stream of flight lifecycle events returned by `stream_flight_events`
+pub type FlightEventStream =
+    std::pin::Pin<Box<dyn futures::Stream<Item = Result<grpc_server::FlightEvent, Status>> + Send>>;
+
+/// Stream of flights found conflicting returned by `stream_flight_conflicts`
+pub type FlightConflictedStream = std::pin::Pin<
+    Box<dyn futures::Stream<Item = Result<grpc_server::FlightConflicted, Status>> + Send>,
+>;
+
+/// Stream of CSV chunks returned by `export_csv`
+pub type CsvChunkStream =
+    std::pin::Pin<Box<dyn futures::Stream<Item = Result<grpc_server::CsvChunk, Status>> + Send>>;
+
+/// Maps an invalid string field to an INVALID_ARGUMENT status, with a
+///  [`grpc_server::StringViolation`] in the details
+fn string_violation_status(field: &str, e: utils::StringError, message: String) -> Status {
+    let to_u32 = |value: usize| u32::try_from(value).unwrap_or(u32::MAX);
+    let mut violation = grpc_server::StringViolation {
+        field: field.to_string(),
+        ..Default::default()
+    };
+
+    match e {
+        utils::StringError::TooShort { length, min } => {
+            violation.rule = grpc_server::StringRule::TooShort as i32;
+            violation.length = to_u32(length);
+            violation.limit = to_u32(min);
+        }
+        utils::StringError::TooLong { length, max } => {
+            violation.rule = grpc_server::StringRule::TooLong as i32;
+            violation.length = to_u32(length);
+            violation.limit = to_u32(max);
+        }
+        utils::StringError::Character { character, index } => {
+            violation.rule = grpc_server::StringRule::Character as i32;
+            violation.character = Some(character.to_string());
+            violation.index = to_u32(index);
+        }
+        utils::StringError::ContainsForbidden => {
+            violation.rule = grpc_server::StringRule::Forbidden as i32;
+        }
+        utils::StringError::Mismatch | utils::StringError::Regex => {
+            violation.rule = grpc_server::StringRule::Mismatch as i32;
+        }
+    }
+
+    Status::with_details(
+        tonic::Code::InvalidArgument,
+        message,
+        prost::Message::encode_to_vec(&violation).into(),
+    )
+}
+
+/// Maps a flight update error to a gRPC status
+///
+/// Classified database errors get a specific code so that callers can
+///  tell a conflict or an outage apart from a bug.
+fn flight_update_status(e: PostgisError) -> Status {
+    match e {
+        PostgisError::FlightPath(flight::FlightError::Database(kind)) => match kind {
+            DbErrorKind::AlreadyExists => Status::already_exists(e.to_string()),
+            DbErrorKind::ForeignKeyViolation => Status::failed_precondition(e.to_string()),
+            DbErrorKind::SerializationFailure => Status::aborted(e.to_string()),
+            DbErrorKind::Connection | DbErrorKind::ReadOnly => Status::unavailable(e.to_string()),
+            DbErrorKind::Timeout => Status::deadline_exceeded(e.to_string()),
+            DbErrorKind::Other => Status::internal(e.to_string()),
+        },
+        PostgisError::FlightPath(flight::FlightError::Timeout) => {
+            Status::deadline_exceeded(e.to_string())
+        }
+        PostgisError::FlightPath(flight::FlightError::Operator) => {
+            Status::permission_denied(e.to_string())
+        }
+        PostgisError::FlightPath(
+            flight::FlightError::ServiceArea | flight::FlightError::Geometry(_),
+        ) => Status::invalid_argument(e.to_string()),
+        PostgisError::FlightPath(flight::FlightError::InvalidString(field, violation)) => {
+            string_violation_status(field, violation, e.to_string())
+        }
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+/// Metadata key of the operator claim, set by the auth interceptor in front
+///  of this service
+pub const OPERATOR_CLAIM_KEY: &str = "x-operator-id";
+
+/// Gets the operator claim of a request, `None` without enforcement or
+///  without a claim
+fn operator_claim<T>(request: &Request<T>, enforcement: bool) -> Result<Option<String>, Status> {
+    if !enforcement {
+        return Ok(None);
+    }
+
+    let Some(value) = request.metadata().get(OPERATOR_CLAIM_KEY) else {
+        return Ok(None);
+    };
+
+    let claim = value.to_str().map_err(|e| {
+        grpc_error!("(operator_claim) invalid operator claim: {}", e);
+        Status::invalid_argument("Invalid operator claim.")
+    })?;
+
+    utils::check_label(claim).map_err(|e| {
+        grpc_error!("(operator_claim) invalid operator claim {}: {}", claim, e);
+        Status::invalid_argument(format!("Invalid operator claim: {e}"))
+    })?;
+
+    Ok(Some(claim.to_string()))
+}
+
+/// Validates the operator filter of a stream subscription
+fn stream_operator_filter(operator_id: &Option<String>) -> Result<(), Status> {
+    let Some(operator_id) = operator_id else {
+        return Ok(());
+    };
+
+    utils::check_label(operator_id).map_err(|e| {
+        grpc_error!(
+            "(stream_operator_filter) invalid operator_id {}: {}",
+            operator_id,
+            e
+        );
+        Status::invalid_argument(format!("Invalid operator_id: {e}"))
+    })
+}
+
+/// Parses a `grpc-timeout` header value: up to 8 digits followed by a
+///  unit (`H`, `M`, `S`, `m`, `u` or `n`)
+fn parse_grpc_timeout(value: &str) -> Option<std::time::Duration> {
+    let digits = value.get(..value.len().checked_sub(1)?)?;
+    if digits.is_empty() || digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let amount: u64 = digits.parse().ok()?;
+    match &value[digits.len()..] {
+        "H" => Some(std::time::Duration::from_secs(amount * 3600)),
+        "M" => Some(std::time::Duration::from_secs(amount * 60)),
+        "S" => Some(std::time::Duration::from_secs(amount)),
+        "m" => Some(std::time::Duration::from_millis(amount)),
+        "u" => Some(std::time::Duration::from_micros(amount)),
+        "n" => Some(std::time::Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+/// Deadline of a request, from the `grpc-timeout` header set by the client
+///
+/// The timeout is measured from when the handler is called.
+fn request_deadline<T>(request: &Request<T>) -> Option<std::time::Instant> {
+    let value = request.metadata().get("grpc-timeout")?.to_str().ok()?;
+    let timeout = parse_grpc_timeout(value)?;
+    std::time::Instant::now().checked_add(timeout)
+}
+
 /// struct to implement the gRPC server functions
-#[derive(Debug, Copy, Clone)]
-pub struct ServerImpl {}
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ServerImpl {
+    /// Time after deletion during which zones and flights can be restored
+    pub soft_delete_undo_window_secs: u64,
+
+    /// Max duration of a flight (0 disables the limit)
+    pub max_flight_duration_secs: u64,
+
+    /// Cross-check best_path distances against the geodesic distance
+    pub best_path_distance_check: bool,
+
+    /// Restrict flight and aircraft mutations carrying an operator claim to
+    ///  the flights and aircraft of that operator
+    pub operator_enforcement: bool,
+}
 
 #[cfg(not(feature = "stub_server"))]
 #[tonic::async_trait]
 impl RpcService for ServerImpl {
-    /// Returns ready:true when service is available
+    type StreamComplianceAlertsStream = ComplianceAlertStream;
+    type StreamAircraftGeoJsonStream = GeoJsonChunkStream;
+    type ExportCsvStream = CsvChunkStream;
+    type StreamAircraftPositionsStream = AircraftPositionEventStream;
+    type StreamZoneChangesStream = ZoneChangedStream;
+    type StreamFlightEventsStream = FlightEventStream;
+    type StreamFlightConflictsStream = FlightConflictedStream;
+
+    /// Returns ready:true when the database is reachable, with the
+    ///  freshness of each data domain
     #[cfg(not(tarpaulin_include))]
     async fn is_ready(
         &self,
         _request: Request<ReadyRequest>,
     ) -> Result<Response<ReadyResponse>, Status> {
         grpc_debug!("(is_ready) entry.");
-        let response = ReadyResponse { ready: true };
+        let response = crate::health::get_readiness().await;
         Ok(Response::new(response))
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn update_vertiports(
+    async fn update_vertiports(
+        &self,
+        request: Request<grpc_server::UpdateVertiportsRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(update_vertiports) entry.");
+
+        let request = request.into_inner();
+        let identifiers = request
+            .vertiports
+            .iter()
+            .map(|v| v.identifier.clone())
+            .collect();
+
+        // Update nodes in PostGIS
+        match vertiport::update_vertiports(request.vertiports, request.dry_run).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: !request.dry_run,
+                dry_run: request.dry_run,
+                identifiers,
+                ..Default::default()
+            })),
+            Err(e) => {
+                grpc_error!("(update_vertiports) error updating vertiports.");
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn update_waypoints(
+        &self,
+        request: Request<grpc_server::UpdateWaypointsRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(update_waypoints) entry.");
+
+        // Update nodes in PostGIS
+        match waypoint::update_waypoints(request.into_inner().waypoints).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(e) => {
+                grpc_error!("(update_waypoints) error updating nodes: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn update_zones(
+        &self,
+        request: Request<grpc_server::UpdateZonesRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(update_zones) entry.");
+
+        let request = request.into_inner();
+        let identifiers = request.zones.iter().map(|z| z.identifier.clone()).collect();
+
+        // WKT and WKB footprints become vertices, checked like any other
+        let zones = match request
+            .zones
+            .into_iter()
+            .map(zone::resolve_zone_geometry)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(zones) => zones,
+            Err(e) => {
+                grpc_warn!("(update_zones) invalid zone footprint: {}", e);
+                return Err(Status::invalid_argument(e.to_string()));
+            }
+        };
+
+        // Explain invalid geometries before touching the tables
+        match zone::zone_geometry_issues(&zones).await {
+            Ok(issues) if !issues.is_empty() => {
+                let details = issues
+                    .iter()
+                    .map(|issue| issue.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ");
+
+                grpc_warn!("(update_zones) invalid zone geometries: {}", details);
+                return Err(Status::invalid_argument(format!(
+                    "invalid zone geometries: {details}"
+                )));
+            }
+            Ok(_) => (),
+            Err(e) => {
+                grpc_error!("(update_zones) could not validate zone geometries: {}", e);
+                return Err(Status::internal(e.to_string()));
+            }
+        }
+
+        // Update nodes in PostGIS
+        match zone::update_zones(zones, request.dry_run).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: !request.dry_run,
+                dry_run: request.dry_run,
+                identifiers,
+                ..Default::default()
+            })),
+            Err(e @ zone::ZoneError::Timeout) => {
+                grpc_error!("(update_zones) error updating zones: {}", e);
+                Err(Status::deadline_exceeded(e.to_string()))
+            }
+            Err(e @ (zone::ZoneError::ServiceArea | zone::ZoneError::Geometry(_))) => {
+                grpc_warn!("(update_zones) invalid zones: {}", e);
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                grpc_error!("(update_zones) error updating zones: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn update_flight_path(
+        &self,
+        request: Request<grpc_server::UpdateFlightPathRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(update_flight_path) entry.");
+
+        let claim = operator_claim(&request, self.operator_enforcement)?;
+        let request = request.into_inner();
+        let dry_run = request.dry_run;
+        let identifiers = request.flight_identifier.clone().into_iter().collect();
+        let probe = dry_run.then(|| request.clone());
+
+        // Update nodes in PostGIS
+        if let Err(e) =
+            flight::update_flight_path_for(request, self.max_flight_duration_secs, claim.as_deref())
+                .await
+        {
+            grpc_error!("(update_flight_path) error updating flight path: {}", e);
+            return Err(flight_update_status(e));
+        }
+
+        // A dry run also reports the conflicts the flight would have
+        let conflict_identifiers = match probe {
+            Some(flight) => flight::probe_flight_conflicts(flight).await.map_err(|e| {
+                grpc_error!("(update_flight_path) error probing conflicts: {}", e);
+                flight_update_status(e)
+            })?,
+            None => vec![],
+        };
+
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: !dry_run,
+            dry_run,
+            identifiers,
+            conflict_identifiers,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn best_path(
+        &self,
+        request: Request<grpc_server::BestPathRequest>,
+    ) -> Result<Response<grpc_server::BestPathResponse>, Status> {
+        grpc_debug!("(best_path) entry.");
+        let request = request.into_inner();
+        match best_path::best_path(request, self.best_path_distance_check).await {
+            Ok(paths) => {
+                let response = grpc_server::BestPathResponse { paths };
+                Ok(Response::new(response))
+            }
+            Err(e) => {
+                grpc_error!("(best_path) error getting best path: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flights(
+        &self,
+        request: Request<grpc_server::GetFlightsRequest>,
+    ) -> Result<Response<grpc_server::GetFlightsResponse>, Status> {
+        grpc_debug!("(get_flights) entry.");
+        let deadline = request_deadline(&request);
+        let request = request.into_inner();
+        match flight::get_flights_until(request, deadline).await {
+            Ok(response) => {
+                if response.partial {
+                    grpc_debug!(
+                        "(get_flights) returning {} flights without aircraft data.",
+                        response.flights.len()
+                    );
+                }
+
+                Ok(Response::new(response))
+            }
+            Err(e @ flight::FlightError::Timeout) => {
+                grpc_error!("(get_flights) error getting flights: {}", e);
+                Err(Status::deadline_exceeded(e.to_string()))
+            }
+            Err(e) => {
+                grpc_error!("(get_flights) error getting flights: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_tile(
+        &self,
+        request: Request<grpc_server::GetTileRequest>,
+    ) -> Result<Response<grpc_server::GetTileResponse>, Status> {
+        grpc_debug!("(get_tile) entry.");
+        match tile::get_tile(request.into_inner()).await {
+            Ok(tile) => Ok(Response::new(grpc_server::GetTileResponse { tile })),
+            Err(e) => {
+                grpc_error!("(get_tile) error getting tile: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_aircraft_track(
+        &self,
+        request: Request<grpc_server::GetAircraftTrackRequest>,
+    ) -> Result<Response<grpc_server::GetAircraftTrackResponse>, Status> {
+        grpc_debug!("(get_aircraft_track) entry.");
+        match aircraft::get_aircraft_track(request.into_inner()).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(get_aircraft_track) error getting track: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn segmentize_path(
+        &self,
+        request: Request<grpc_server::SegmentizePathRequest>,
+    ) -> Result<Response<grpc_server::SegmentizePathResponse>, Status> {
+        grpc_debug!("(segmentize_path) entry.");
+        match flight::segmentize_path(request.into_inner()).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(segmentize_path) error segmentizing path: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flight_conflicts(
+        &self,
+        request: Request<grpc_server::GetFlightConflictsRequest>,
+    ) -> Result<Response<grpc_server::GetFlightConflictsResponse>, Status> {
+        grpc_debug!("(get_flight_conflicts) entry.");
+        match flight::get_flight_conflicts(request.into_inner()).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(get_flight_conflicts) error getting conflicts: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_ingestion_status(
+        &self,
+        request: Request<grpc_server::GetIngestionStatusRequest>,
+    ) -> Result<Response<grpc_server::GetIngestionStatusResponse>, Status> {
+        grpc_debug!("(get_ingestion_status) entry.");
+        let _request = request.into_inner();
+        match crate::cache::status::get_ingestion_status().await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(get_ingestion_status) error getting status: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn register_telemetry_identifier(
+        &self,
+        request: Request<grpc_server::RegisterTelemetryIdentifierRequest>,
+    ) -> Result<Response<grpc_server::RegisterTelemetryIdentifierResponse>, Status> {
+        grpc_debug!("(register_telemetry_identifier) entry.");
+        let request = request.into_inner();
+        match telemetry::register_identifier(&request.identifier).await {
+            Ok(index) => Ok(Response::new(
+                grpc_server::RegisterTelemetryIdentifierResponse { index },
+            )),
+            Err(e) => {
+                grpc_error!(
+                    "(register_telemetry_identifier) error registering identifier: {}",
+                    e
+                );
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn ingest_binary_telemetry(
+        &self,
+        request: Request<grpc_server::IngestBinaryTelemetryRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(ingest_binary_telemetry) entry.");
+        let claim = operator_claim(&request, self.operator_enforcement)?;
+        let request = request.into_inner();
+        let dry_run = request.dry_run;
+        match telemetry::ingest_binary(&request.payload, dry_run, claim.as_deref()).await {
+            Ok(outcome) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: !dry_run && outcome.accepted + outcome.coalesced > 0,
+                dry_run,
+                coalesced: outcome.coalesced,
+                shed: outcome.shed,
+                ..Default::default()
+            })),
+            Err(e @ PostgisError::Aircraft(aircraft::AircraftError::Operator)) => {
+                grpc_warn!("(ingest_binary_telemetry) {}", e);
+                Err(Status::permission_denied(e.to_string()))
+            }
+            Err(e) => {
+                grpc_error!("(ingest_binary_telemetry) error ingesting telemetry: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn remove_zones_by_source(
+        &self,
+        request: Request<grpc_server::RemoveZonesBySourceRequest>,
+    ) -> Result<Response<grpc_server::RemoveZonesBySourceResponse>, Status> {
+        grpc_debug!("(remove_zones_by_source) entry.");
+        let request = request.into_inner();
+        match zone::remove_zones_by_source(&request.source).await {
+            Ok(removed) => Ok(Response::new(grpc_server::RemoveZonesBySourceResponse {
+                removed,
+            })),
+            Err(e) => {
+                grpc_error!("(remove_zones_by_source) error removing zones: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_vertiport_throughput(
+        &self,
+        request: Request<grpc_server::GetVertiportThroughputRequest>,
+    ) -> Result<Response<grpc_server::GetVertiportThroughputResponse>, Status> {
+        grpc_debug!("(get_vertiport_throughput) entry.");
+        let request = request.into_inner();
+        match throughput::get_vertiport_throughput(request).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(get_vertiport_throughput) error getting throughput: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flight_segments(
+        &self,
+        request: Request<grpc_server::GetFlightSegmentsRequest>,
+    ) -> Result<Response<grpc_server::GetFlightSegmentsResponse>, Status> {
+        grpc_debug!("(get_flight_segments) entry.");
+        let request = request.into_inner();
+        match flight::get_flight_segments(request).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(PostgisError::FlightPath(flight::FlightError::NotFound)) => {
+                grpc_warn!("(get_flight_segments) no segments found.");
+                Err(Status::not_found("No segments found for flight."))
+            }
+            Err(e) => {
+                grpc_error!("(get_flight_segments) error getting segments: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn delete_zone(
+        &self,
+        request: Request<grpc_server::DeleteZoneRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(delete_zone) entry.");
+        let request = request.into_inner();
+        match zone::delete_zone(&request.identifier).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(zone::ZoneError::NotFound) => {
+                grpc_warn!("(delete_zone) not found.");
+                Err(Status::not_found("No matching zone found."))
+            }
+            Err(e) => {
+                grpc_error!("(delete_zone) error deleting zone: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn restore_zone(
+        &self,
+        request: Request<grpc_server::RestoreZoneRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(restore_zone) entry.");
+        let request = request.into_inner();
+        match zone::restore_zone(&request.identifier, self.soft_delete_undo_window_secs).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(zone::ZoneError::NotFound) => {
+                grpc_warn!("(restore_zone) not found.");
+                Err(Status::not_found("No zone deleted within the undo window."))
+            }
+            Err(e) => {
+                grpc_error!("(restore_zone) error restoring zone: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn delete_flight(
+        &self,
+        request: Request<grpc_server::DeleteFlightRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(delete_flight) entry.");
+        let claim = operator_claim(&request, self.operator_enforcement)?;
+        let request = request.into_inner();
+        if let Some(claim) = claim {
+            flight::check_flight_operator(&request.flight_identifier, &claim)
+                .await
+                .map_err(flight_update_status)?;
+        }
+
+        match flight::delete_flight(&request.flight_identifier, request.reason.as_deref()).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(PostgisError::FlightPath(flight::FlightError::NotFound)) => {
+                grpc_warn!("(delete_flight) not found.");
+                Err(Status::not_found("No matching flight found."))
+            }
+            Err(e) => {
+                grpc_error!("(delete_flight) error deleting flight: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn restore_flight(
+        &self,
+        request: Request<grpc_server::RestoreFlightRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(restore_flight) entry.");
+        let claim = operator_claim(&request, self.operator_enforcement)?;
+        let request = request.into_inner();
+        if let Some(claim) = claim {
+            flight::check_flight_operator(&request.flight_identifier, &claim)
+                .await
+                .map_err(flight_update_status)?;
+        }
+
+        match flight::restore_flight(
+            &request.flight_identifier,
+            self.soft_delete_undo_window_secs,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(PostgisError::FlightPath(flight::FlightError::NotFound)) => {
+                grpc_warn!("(restore_flight) not found.");
+                Err(Status::not_found(
+                    "No flight deleted within the undo window.",
+                ))
+            }
+            Err(e) => {
+                grpc_error!("(restore_flight) error restoring flight: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_compliance_alerts(
+        &self,
+        request: Request<grpc_server::StreamComplianceAlertsRequest>,
+    ) -> Result<Response<Self::StreamComplianceAlertsStream>, Status> {
+        grpc_debug!("(stream_compliance_alerts) entry.");
+        let request = request.into_inner();
+        let stream = futures::StreamExt::map(compliance::alert_stream(request.last_event_id), Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn wait_for_flight_applied(
+        &self,
+        request: Request<grpc_server::WaitForFlightAppliedRequest>,
+    ) -> Result<Response<grpc_server::WaitForFlightAppliedResponse>, Status> {
+        grpc_debug!("(wait_for_flight_applied) entry.");
+        let request = request.into_inner();
+        match crate::cache::applied::wait_for_flight_applied(
+            &request.correlation_id,
+            request.timeout_ms,
+        )
+        .await
+        {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(wait_for_flight_applied) error waiting for flight: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_aircraft_geo_json(
+        &self,
+        request: Request<grpc_server::StreamAircraftGeoJsonRequest>,
+    ) -> Result<Response<Self::StreamAircraftGeoJsonStream>, Status> {
+        grpc_debug!("(stream_aircraft_geo_json) entry.");
+        let request = request.into_inner();
+        stream_operator_filter(&request.operator_id)?;
+        match export::aircraft_geojson_stream(
+            request.include_simulated,
+            request.operator_id.as_deref(),
+        )
+        .await
+        {
+            Ok(stream) => {
+                let stream = futures::StreamExt::map(stream, |chunk| {
+                    chunk.map_err(|e| {
+                        grpc_error!("(stream_aircraft_geo_json) error exporting aircraft: {}", e);
+                        Status::internal(e.to_string())
+                    })
+                });
+                Ok(Response::new(Box::pin(stream)))
+            }
+            Err(e) => {
+                grpc_error!("(stream_aircraft_geo_json) error exporting aircraft: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn export_csv(
+        &self,
+        request: Request<grpc_server::ExportCsvRequest>,
+    ) -> Result<Response<Self::ExportCsvStream>, Status> {
+        grpc_debug!("(export_csv) entry.");
+        let request = request.into_inner();
+        let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+            grpc_error!("(export_csv) could not get psql pool.");
+            return Err(Status::unavailable("Could not get backend client."));
+        };
+
+        match export::csv_stream(&request, pool).await {
+            Ok(stream) => {
+                let stream = futures::StreamExt::map(stream, |chunk| {
+                    chunk.map_err(|e| {
+                        grpc_error!("(export_csv) error exporting rows: {}", e);
+                        Status::internal(e.to_string())
+                    })
+                });
+                Ok(Response::new(Box::pin(stream)))
+            }
+            Err(
+                e @ PostgisError::Export(
+                    export::ExportError::Dataset
+                    | export::ExportError::Time
+                    | export::ExportError::Location,
+                ),
+            ) => {
+                grpc_warn!("(export_csv) invalid request: {}", e);
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                grpc_error!("(export_csv) error exporting rows: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_altitude_occupancy(
+        &self,
+        request: Request<grpc_server::GetAltitudeOccupancyRequest>,
+    ) -> Result<Response<grpc_server::GetAltitudeOccupancyResponse>, Status> {
+        grpc_debug!("(get_altitude_occupancy) entry.");
+        let request = request.into_inner();
+        match occupancy::get_altitude_occupancy(request).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => {
+                grpc_error!("(get_altitude_occupancy) error getting occupancy: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn confirm_flight(
+        &self,
+        request: Request<grpc_server::ConfirmFlightRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(confirm_flight) entry.");
+        let claim = operator_claim(&request, self.operator_enforcement)?;
+        let request = request.into_inner();
+        if let Some(claim) = claim {
+            flight::check_flight_operator(&request.flight_identifier, &claim)
+                .await
+                .map_err(flight_update_status)?;
+        }
+
+        match flight::confirm_flight(&request.flight_identifier).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(PostgisError::FlightPath(flight::FlightError::NotFound)) => {
+                grpc_warn!("(confirm_flight) not found.");
+                Err(Status::not_found(
+                    "No unexpired reservation for this flight.",
+                ))
+            }
+            Err(e) => {
+                grpc_error!("(confirm_flight) error confirming flight: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn what_if(
+        &self,
+        request: Request<grpc_server::WhatIfRequest>,
+    ) -> Result<Response<grpc_server::WhatIfResponse>, Status> {
+        grpc_debug!("(what_if) entry.");
+        let request = request.into_inner();
+        match what_if::what_if(request).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(PostgisError::WhatIf(
+                e @ (what_if::WhatIfError::Client
+                | what_if::WhatIfError::DBError
+                | what_if::WhatIfError::Segments),
+            )) => {
+                grpc_error!("(what_if) error evaluating state: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+            Err(PostgisError::WhatIf(e)) => {
+                grpc_warn!("(what_if) invalid request: {}", e);
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                grpc_error!("(what_if) error evaluating state: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn pause_ingestion(
+        &self,
+        request: Request<grpc_server::PauseIngestionRequest>,
+    ) -> Result<Response<grpc_server::PauseIngestionResponse>, Status> {
+        grpc_debug!("(pause_ingestion) entry.");
+        let _request = request.into_inner();
+        let idle = crate::cache::pause::pause().await;
+        if !idle {
+            grpc_warn!("(pause_ingestion) consumers still busy.");
+        }
+
+        Ok(Response::new(grpc_server::PauseIngestionResponse { idle }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn resume_ingestion(
+        &self,
+        request: Request<grpc_server::ResumeIngestionRequest>,
+    ) -> Result<Response<grpc_server::ResumeIngestionResponse>, Status> {
+        grpc_debug!("(resume_ingestion) entry.");
+        let _request = request.into_inner();
+        let was_paused = crate::cache::pause::resume();
+        Ok(Response::new(grpc_server::ResumeIngestionResponse {
+            was_paused,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn build_flight_path(
+        &self,
+        request: Request<grpc_server::BuildFlightPathRequest>,
+    ) -> Result<Response<grpc_server::BuildFlightPathResponse>, Status> {
+        grpc_debug!("(build_flight_path) entry.");
+        let request = request.into_inner();
+        match route::build_flight_path(request, self.max_flight_duration_secs).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(PostgisError::Route(e @ route::RouteError::Client)) => {
+                grpc_error!("(build_flight_path) error building flight path: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+            Err(PostgisError::Route(e @ route::RouteError::Leg(..))) => {
+                grpc_warn!("(build_flight_path) routing failed: {}", e);
+                Err(Status::failed_precondition(e.to_string()))
+            }
+            Err(PostgisError::Route(e)) => {
+                grpc_warn!("(build_flight_path) invalid request: {}", e);
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                grpc_error!("(build_flight_path) error building flight path: {}", e);
+                Err(flight_update_status(e))
+            }
+        }
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn replace_zones(
         &self,
-        request: Request<grpc_server::UpdateVertiportsRequest>,
-    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
-        grpc_debug!("(update_vertiports) entry.");
-
-        // Update nodes in PostGIS
-        match vertiport::update_vertiports(request.into_inner().vertiports).await {
-            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse { updated: true })),
-            Err(e) => {
-                grpc_error!("(update_vertiports) error updating vertiports.");
+        request: Request<grpc_server::ReplaceZonesRequest>,
+    ) -> Result<Response<grpc_server::ReplaceZonesResponse>, Status> {
+        grpc_debug!("(replace_zones) entry.");
+        let request = request.into_inner();
+        match zone::replace_zones(&request.source, request.zones, request.dry_run).await {
+            Ok(replacement) => Ok(Response::new(grpc_server::ReplaceZonesResponse {
+                created: replacement.created,
+                updated: replacement.updated,
+                removed: replacement.removed,
+            })),
+            Err(e @ (zone::ZoneError::Client | zone::ZoneError::DBError)) => {
+                grpc_error!("(replace_zones) error replacing zones: {}", e);
                 Err(Status::internal(e.to_string()))
             }
+            Err(e @ zone::ZoneError::Timeout) => {
+                grpc_error!("(replace_zones) error replacing zones: {}", e);
+                Err(Status::deadline_exceeded(e.to_string()))
+            }
+            Err(e) => {
+                grpc_warn!("(replace_zones) invalid request: {}", e);
+                Err(Status::invalid_argument(e.to_string()))
+            }
         }
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn update_waypoints(
+    async fn get_service_info(
         &self,
-        request: Request<grpc_server::UpdateWaypointsRequest>,
-    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
-        grpc_debug!("(update_waypoints) entry.");
+        request: Request<grpc_server::GetServiceInfoRequest>,
+    ) -> Result<Response<grpc_server::GetServiceInfoResponse>, Status> {
+        grpc_debug!("(get_service_info) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(crate::info::get_service_info().await))
+    }
 
-        // Update nodes in PostGIS
-        match waypoint::update_waypoints(request.into_inner().waypoints).await {
-            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse { updated: true })),
+    #[cfg(not(tarpaulin_include))]
+    async fn update_corridors(
+        &self,
+        request: Request<grpc_server::UpdateCorridorsRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_debug!("(update_corridors) entry.");
+        match corridor::update_corridors(request.into_inner().corridors).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
             Err(e) => {
-                grpc_error!("(update_waypoints) error updating nodes: {}", e);
+                grpc_error!("(update_corridors) error updating corridors: {}", e);
                 Err(Status::internal(e.to_string()))
             }
         }
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn update_zones(
+    async fn get_corridors(
         &self,
-        request: Request<grpc_server::UpdateZonesRequest>,
-    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
-        grpc_debug!("(update_zones) entry.");
-
-        // Update nodes in PostGIS
-        match zone::update_zones(request.into_inner().zones).await {
-            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse { updated: true })),
+        request: Request<grpc_server::GetCorridorsRequest>,
+    ) -> Result<Response<grpc_server::GetCorridorsResponse>, Status> {
+        grpc_debug!("(get_corridors) entry.");
+        let _request = request.into_inner();
+        match corridor::get_corridors().await {
+            Ok(corridors) => Ok(Response::new(grpc_server::GetCorridorsResponse {
+                corridors,
+            })),
             Err(e) => {
-                grpc_error!("(update_zones) error updating zones: {}", e);
+                grpc_error!("(get_corridors) error getting corridors: {}", e);
                 Err(Status::internal(e.to_string()))
             }
         }
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn update_flight_path(
+    async fn delete_corridor(
         &self,
-        request: Request<grpc_server::UpdateFlightPathRequest>,
+        request: Request<grpc_server::DeleteCorridorRequest>,
     ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
-        grpc_debug!("(update_flight_path) entry.");
-
-        // Update nodes in PostGIS
-        match flight::update_flight_path(request.into_inner()).await {
-            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse { updated: true })),
+        grpc_debug!("(delete_corridor) entry.");
+        let request = request.into_inner();
+        match corridor::delete_corridor(&request.identifier).await {
+            Ok(_) => Ok(Response::new(grpc_server::UpdateResponse {
+                updated: true,
+                ..Default::default()
+            })),
+            Err(corridor::CorridorError::NotFound) => {
+                grpc_warn!("(delete_corridor) not found.");
+                Err(Status::not_found("No matching corridor found."))
+            }
             Err(e) => {
-                grpc_error!("(update_flight_path) error updating flight path: {}", e);
+                grpc_error!("(delete_corridor) error deleting corridor: {}", e);
                 Err(Status::internal(e.to_string()))
             }
         }
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn best_path(
+    async fn get_corridor_allocation(
         &self,
-        request: Request<grpc_server::BestPathRequest>,
-    ) -> Result<Response<grpc_server::BestPathResponse>, Status> {
-        grpc_debug!("(best_path) entry.");
+        request: Request<grpc_server::GetCorridorAllocationRequest>,
+    ) -> Result<Response<grpc_server::GetCorridorAllocationResponse>, Status> {
+        grpc_debug!("(get_corridor_allocation) entry.");
         let request = request.into_inner();
-        match best_path::best_path(request).await {
-            Ok(paths) => {
-                let response = grpc_server::BestPathResponse { paths };
-                Ok(Response::new(response))
+        match corridor::get_corridor_allocation(request).await {
+            Ok(response) => Ok(Response::new(response)),
+            Err(corridor::CorridorError::NotFound) => {
+                grpc_warn!("(get_corridor_allocation) not found.");
+                Err(Status::not_found("No matching corridor found."))
             }
             Err(e) => {
-                grpc_error!("(best_path) error getting best path: {}", e);
+                grpc_error!(
+                    "(get_corridor_allocation) error getting corridor allocation: {}",
+                    e
+                );
                 Err(Status::internal(e.to_string()))
             }
         }
     }
 
     #[cfg(not(tarpaulin_include))]
-    async fn get_flights(
+    async fn stream_aircraft_positions(
         &self,
-        request: Request<grpc_server::GetFlightsRequest>,
-    ) -> Result<Response<grpc_server::GetFlightsResponse>, Status> {
-        grpc_debug!("(get_flights) entry.");
+        request: Request<grpc_server::StreamAircraftPositionsRequest>,
+    ) -> Result<Response<Self::StreamAircraftPositionsStream>, Status> {
+        grpc_debug!("(stream_aircraft_positions) entry.");
         let request = request.into_inner();
-        match flight::get_flights(request).await {
-            Ok(flights) => {
-                let response = grpc_server::GetFlightsResponse {
-                    flights,
-                    // isas: vec![],
-                };
-                Ok(Response::new(response))
+        let options = subscription::SubscriptionOptions::try_new(
+            &request.vertices,
+            request.events_only,
+            request.decimation_secs,
+        )
+        .map_err(|e| {
+            grpc_error!("(stream_aircraft_positions) invalid region: {}", e);
+            Status::invalid_argument(e.to_string())
+        })?;
+
+        stream_operator_filter(&request.operator_id)?;
+        let options = subscription::SubscriptionOptions {
+            operator_id: request.operator_id,
+            ..options
+        };
+
+        let stream = futures::StreamExt::map(subscription::position_stream(options), |event| {
+            Ok(event.into())
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_zone_changes(
+        &self,
+        request: Request<grpc_server::StreamZoneChangesRequest>,
+    ) -> Result<Response<Self::StreamZoneChangesStream>, Status> {
+        grpc_debug!("(stream_zone_changes) entry.");
+        let request = request.into_inner();
+        let stream = futures::StreamExt::map(zone::zone_change_stream(request.last_event_id), Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_flight_events(
+        &self,
+        request: Request<grpc_server::StreamFlightEventsRequest>,
+    ) -> Result<Response<Self::StreamFlightEventsStream>, Status> {
+        grpc_debug!("(stream_flight_events) entry.");
+        let request = request.into_inner();
+        stream_operator_filter(&request.operator_id)?;
+        let stream = futures::StreamExt::map(
+            flight::flight_event_stream(request.last_event_id, request.operator_id),
+            Ok,
+        );
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_flight_conflicts(
+        &self,
+        request: Request<grpc_server::StreamFlightConflictsRequest>,
+    ) -> Result<Response<Self::StreamFlightConflictsStream>, Status> {
+        grpc_debug!("(stream_flight_conflicts) entry.");
+        let request = request.into_inner();
+        let stream =
+            futures::StreamExt::map(conflict_check::conflict_stream(request.last_event_id), Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flight(
+        &self,
+        request: Request<grpc_server::GetFlightRequest>,
+    ) -> Result<Response<grpc_server::GetFlightResponse>, Status> {
+        grpc_debug!("(get_flight) entry.");
+        let request = request.into_inner();
+        match flight::get_flight(&request.flight_identifier).await {
+            Ok(flight) => Ok(Response::new(grpc_server::GetFlightResponse {
+                flight: Some(flight),
+            })),
+            Err(PostgisError::FlightPath(flight::FlightError::NotFound)) => {
+                grpc_warn!("(get_flight) not found.");
+                Err(Status::not_found("No matching flight found."))
             }
             Err(e) => {
-                grpc_error!("(get_flights) error getting flights: {}", e);
+                grpc_error!("(get_flight) error getting flight: {}", e);
                 Err(Status::internal(e.to_string()))
             }
         }
@@ -191,7 +1219,12 @@ pub async fn grpc_server(
         }
     };
 
-    let imp = ServerImpl {};
+    let imp = ServerImpl {
+        soft_delete_undo_window_secs: config.soft_delete_undo_window_secs,
+        max_flight_duration_secs: config.max_flight_duration_secs,
+        best_path_distance_check: config.best_path_distance_check,
+        operator_enforcement: config.operator_enforcement,
+    };
     let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
     health_reporter
         .set_serving::<RpcServiceServer<ServerImpl>>()
@@ -218,13 +1251,25 @@ pub async fn grpc_server(
 #[cfg(feature = "stub_server")]
 #[tonic::async_trait]
 impl RpcService for ServerImpl {
+    type StreamComplianceAlertsStream = ComplianceAlertStream;
+    type StreamAircraftGeoJsonStream = GeoJsonChunkStream;
+    type ExportCsvStream = CsvChunkStream;
+    type StreamAircraftPositionsStream = AircraftPositionEventStream;
+    type StreamZoneChangesStream = ZoneChangedStream;
+    type StreamFlightEventsStream = FlightEventStream;
+    type StreamFlightConflictsStream = FlightConflictedStream;
+
     #[cfg(not(tarpaulin_include))]
     async fn is_ready(
         &self,
         _request: Request<ReadyRequest>,
     ) -> Result<Response<ReadyResponse>, Status> {
         grpc_warn!("(is_ready MOCK) entry.");
-        let response = ReadyResponse { ready: true };
+        let response = ReadyResponse {
+            ready: true,
+            readiness: grpc_server::Readiness::Ready as i32,
+            domains: vec![],
+        };
         Ok(Response::new(response))
     }
 
@@ -235,7 +1280,10 @@ impl RpcService for ServerImpl {
     ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
         grpc_warn!("(update_vertiports MOCK) entry.");
 
-        Ok(Response::new(grpc_server::UpdateResponse { updated: true }))
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     #[cfg(not(tarpaulin_include))]
@@ -245,7 +1293,10 @@ impl RpcService for ServerImpl {
     ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
         grpc_warn!("(update_waypoints MOCK) entry.");
 
-        Ok(Response::new(grpc_server::UpdateResponse { updated: true }))
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     #[cfg(not(tarpaulin_include))]
@@ -255,17 +1306,24 @@ impl RpcService for ServerImpl {
     ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
         grpc_warn!("(update_zones MOCK) entry.");
 
-        Ok(Response::new(grpc_server::UpdateResponse { updated: true }))
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     #[cfg(not(tarpaulin_include))]
     async fn update_flight_path(
         &self,
-        _request: Request<grpc_server::UpdateFlightPathRequest>,
+        request: Request<grpc_server::UpdateFlightPathRequest>,
     ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
         grpc_debug!("(update_flight_path MOCK) entry.");
+        operator_claim(&request, self.operator_enforcement)?;
 
-        Ok(Response::new(grpc_server::UpdateResponse { updated: true }))
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
     }
 
     #[cfg(not(tarpaulin_include))]
@@ -275,7 +1333,7 @@ impl RpcService for ServerImpl {
     ) -> Result<Response<grpc_server::BestPathResponse>, Status> {
         grpc_warn!("(best_path MOCK) entry.");
         let request = request.into_inner();
-        match best_path::best_path(request).await {
+        match best_path::best_path(request, self.best_path_distance_check).await {
             Ok(paths) => {
                 let response = grpc_server::BestPathResponse { paths };
                 Ok(Response::new(response))
@@ -293,12 +1351,10 @@ impl RpcService for ServerImpl {
         request: Request<grpc_server::GetFlightsRequest>,
     ) -> Result<Response<grpc_server::GetFlightsResponse>, Status> {
         grpc_warn!("(get_flights MOCK) entry.");
+        let deadline = request_deadline(&request);
         let request = request.into_inner();
-        match flight::get_flights(request).await {
-            Ok(flights) => {
-                let response = grpc_server::GetFlightsResponse { flights };
-                Ok(Response::new(response))
-            }
+        match flight::get_flights_until(request, deadline).await {
+            Ok(response) => Ok(Response::new(response)),
             Err(e) => {
                 grpc_error!("(get_flights MOCK) error getting flights.");
                 Err(Status::internal(e.to_string()))
@@ -306,6 +1362,424 @@ impl RpcService for ServerImpl {
         }
     }
 
+    #[cfg(not(tarpaulin_include))]
+    async fn get_tile(
+        &self,
+        request: Request<grpc_server::GetTileRequest>,
+    ) -> Result<Response<grpc_server::GetTileResponse>, Status> {
+        grpc_warn!("(get_tile MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetTileResponse { tile: vec![] }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_aircraft_track(
+        &self,
+        request: Request<grpc_server::GetAircraftTrackRequest>,
+    ) -> Result<Response<grpc_server::GetAircraftTrackResponse>, Status> {
+        grpc_warn!("(get_aircraft_track MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetAircraftTrackResponse {
+            positions: vec![],
+            point_count: 0,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn segmentize_path(
+        &self,
+        request: Request<grpc_server::SegmentizePathRequest>,
+    ) -> Result<Response<grpc_server::SegmentizePathResponse>, Status> {
+        grpc_warn!("(segmentize_path MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::SegmentizePathResponse {
+            segments: vec![],
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flight_conflicts(
+        &self,
+        request: Request<grpc_server::GetFlightConflictsRequest>,
+    ) -> Result<Response<grpc_server::GetFlightConflictsResponse>, Status> {
+        grpc_warn!("(get_flight_conflicts MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetFlightConflictsResponse {
+            conflicts: vec![],
+            corridor_conflicts: vec![],
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_ingestion_status(
+        &self,
+        request: Request<grpc_server::GetIngestionStatusRequest>,
+    ) -> Result<Response<grpc_server::GetIngestionStatusResponse>, Status> {
+        grpc_warn!("(get_ingestion_status MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetIngestionStatusResponse {
+            queues: vec![],
+            paused: false,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn register_telemetry_identifier(
+        &self,
+        request: Request<grpc_server::RegisterTelemetryIdentifierRequest>,
+    ) -> Result<Response<grpc_server::RegisterTelemetryIdentifierResponse>, Status> {
+        grpc_warn!("(register_telemetry_identifier MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(
+            grpc_server::RegisterTelemetryIdentifierResponse { index: 1 },
+        ))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn ingest_binary_telemetry(
+        &self,
+        request: Request<grpc_server::IngestBinaryTelemetryRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(ingest_binary_telemetry MOCK) entry.");
+        operator_claim(&request, self.operator_enforcement)?;
+        let request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: !request.dry_run,
+            dry_run: request.dry_run,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn remove_zones_by_source(
+        &self,
+        request: Request<grpc_server::RemoveZonesBySourceRequest>,
+    ) -> Result<Response<grpc_server::RemoveZonesBySourceResponse>, Status> {
+        grpc_warn!("(remove_zones_by_source MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::RemoveZonesBySourceResponse {
+            removed: 0,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_vertiport_throughput(
+        &self,
+        request: Request<grpc_server::GetVertiportThroughputRequest>,
+    ) -> Result<Response<grpc_server::GetVertiportThroughputResponse>, Status> {
+        grpc_warn!("(get_vertiport_throughput MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetVertiportThroughputResponse {
+            series: vec![],
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flight_segments(
+        &self,
+        request: Request<grpc_server::GetFlightSegmentsRequest>,
+    ) -> Result<Response<grpc_server::GetFlightSegmentsResponse>, Status> {
+        grpc_warn!("(get_flight_segments MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetFlightSegmentsResponse {
+            segments: vec![],
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn delete_zone(
+        &self,
+        request: Request<grpc_server::DeleteZoneRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(delete_zone MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn restore_zone(
+        &self,
+        request: Request<grpc_server::RestoreZoneRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(restore_zone MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn delete_flight(
+        &self,
+        request: Request<grpc_server::DeleteFlightRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(delete_flight MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn restore_flight(
+        &self,
+        request: Request<grpc_server::RestoreFlightRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(restore_flight MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_compliance_alerts(
+        &self,
+        _request: Request<grpc_server::StreamComplianceAlertsRequest>,
+    ) -> Result<Response<Self::StreamComplianceAlertsStream>, Status> {
+        grpc_warn!("(stream_compliance_alerts MOCK) entry.");
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn wait_for_flight_applied(
+        &self,
+        request: Request<grpc_server::WaitForFlightAppliedRequest>,
+    ) -> Result<Response<grpc_server::WaitForFlightAppliedResponse>, Status> {
+        grpc_warn!("(wait_for_flight_applied MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::WaitForFlightAppliedResponse {
+            completed: true,
+            success: true,
+            error: None,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_aircraft_geo_json(
+        &self,
+        request: Request<grpc_server::StreamAircraftGeoJsonRequest>,
+    ) -> Result<Response<Self::StreamAircraftGeoJsonStream>, Status> {
+        grpc_warn!("(stream_aircraft_geo_json MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn export_csv(
+        &self,
+        request: Request<grpc_server::ExportCsvRequest>,
+    ) -> Result<Response<Self::ExportCsvStream>, Status> {
+        grpc_warn!("(export_csv MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_altitude_occupancy(
+        &self,
+        request: Request<grpc_server::GetAltitudeOccupancyRequest>,
+    ) -> Result<Response<grpc_server::GetAltitudeOccupancyResponse>, Status> {
+        grpc_warn!("(get_altitude_occupancy MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetAltitudeOccupancyResponse {
+            bands: vec![],
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn confirm_flight(
+        &self,
+        request: Request<grpc_server::ConfirmFlightRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(confirm_flight MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn what_if(
+        &self,
+        request: Request<grpc_server::WhatIfRequest>,
+    ) -> Result<Response<grpc_server::WhatIfResponse>, Status> {
+        grpc_warn!("(what_if MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::WhatIfResponse::default()))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn pause_ingestion(
+        &self,
+        request: Request<grpc_server::PauseIngestionRequest>,
+    ) -> Result<Response<grpc_server::PauseIngestionResponse>, Status> {
+        grpc_warn!("(pause_ingestion MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::PauseIngestionResponse {
+            idle: true,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn resume_ingestion(
+        &self,
+        request: Request<grpc_server::ResumeIngestionRequest>,
+    ) -> Result<Response<grpc_server::ResumeIngestionResponse>, Status> {
+        grpc_warn!("(resume_ingestion MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(
+            grpc_server::ResumeIngestionResponse::default(),
+        ))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn build_flight_path(
+        &self,
+        request: Request<grpc_server::BuildFlightPathRequest>,
+    ) -> Result<Response<grpc_server::BuildFlightPathResponse>, Status> {
+        grpc_warn!("(build_flight_path MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(
+            grpc_server::BuildFlightPathResponse::default(),
+        ))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn replace_zones(
+        &self,
+        request: Request<grpc_server::ReplaceZonesRequest>,
+    ) -> Result<Response<grpc_server::ReplaceZonesResponse>, Status> {
+        grpc_warn!("(replace_zones MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::ReplaceZonesResponse::default()))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_service_info(
+        &self,
+        request: Request<grpc_server::GetServiceInfoRequest>,
+    ) -> Result<Response<grpc_server::GetServiceInfoResponse>, Status> {
+        grpc_warn!("(get_service_info MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetServiceInfoResponse {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: crate::info::GIT_SHA.to_string(),
+            features: crate::info::enabled_features(),
+            schema_version: Some(crate::postgis::PSQL_SCHEMA_VERSION),
+            database_status: "ok".to_string(),
+            service_area: None,
+            service_area_buffer_meters: 0.0,
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn update_corridors(
+        &self,
+        request: Request<grpc_server::UpdateCorridorsRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(update_corridors MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_corridors(
+        &self,
+        request: Request<grpc_server::GetCorridorsRequest>,
+    ) -> Result<Response<grpc_server::GetCorridorsResponse>, Status> {
+        grpc_warn!("(get_corridors MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::GetCorridorsResponse::default()))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn delete_corridor(
+        &self,
+        request: Request<grpc_server::DeleteCorridorRequest>,
+    ) -> Result<Response<grpc_server::UpdateResponse>, Status> {
+        grpc_warn!("(delete_corridor MOCK) entry.");
+        let _request = request.into_inner();
+        Ok(Response::new(grpc_server::UpdateResponse {
+            updated: true,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_corridor_allocation(
+        &self,
+        request: Request<grpc_server::GetCorridorAllocationRequest>,
+    ) -> Result<Response<grpc_server::GetCorridorAllocationResponse>, Status> {
+        grpc_warn!("(get_corridor_allocation MOCK) entry.");
+        let request = request.into_inner();
+        Ok(Response::new(grpc_server::GetCorridorAllocationResponse {
+            identifier: request.identifier,
+            ..Default::default()
+        }))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_aircraft_positions(
+        &self,
+        _request: Request<grpc_server::StreamAircraftPositionsRequest>,
+    ) -> Result<Response<Self::StreamAircraftPositionsStream>, Status> {
+        grpc_warn!("(stream_aircraft_positions MOCK) entry.");
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_zone_changes(
+        &self,
+        _request: Request<grpc_server::StreamZoneChangesRequest>,
+    ) -> Result<Response<Self::StreamZoneChangesStream>, Status> {
+        grpc_warn!("(stream_zone_changes MOCK) entry.");
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_flight_events(
+        &self,
+        _request: Request<grpc_server::StreamFlightEventsRequest>,
+    ) -> Result<Response<Self::StreamFlightEventsStream>, Status> {
+        grpc_warn!("(stream_flight_events MOCK) entry.");
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn stream_flight_conflicts(
+        &self,
+        _request: Request<grpc_server::StreamFlightConflictsRequest>,
+    ) -> Result<Response<Self::StreamFlightConflictsStream>, Status> {
+        grpc_warn!("(stream_flight_conflicts MOCK) entry.");
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    async fn get_flight(
+        &self,
+        request: Request<grpc_server::GetFlightRequest>,
+    ) -> Result<Response<grpc_server::GetFlightResponse>, Status> {
+        grpc_warn!("(get_flight MOCK) entry.");
+        let request = request.into_inner();
+        Ok(Response::new(grpc_server::GetFlightResponse {
+            flight: Some(grpc_server::Flight {
+                session_id: Some(request.flight_identifier),
+                ..Default::default()
+            }),
+        }))
+    }
+
     // #[cfg(not(tarpaulin_include))]
     // async fn nearest_neighbors(
     //     &self,
@@ -331,10 +1805,145 @@ mod tests {
 
     #[tokio::test]
     async fn test_grpc_server_is_ready() {
-        let imp = ServerImpl {};
+        let imp = ServerImpl::default();
         let result = imp.is_ready(Request::new(ReadyRequest {})).await;
         assert!(result.is_ok());
         let result: ReadyResponse = result.unwrap().into_inner();
-        assert_eq!(result.ready, true);
+
+        // Not ready without a database
+        assert_eq!(result.ready, cfg!(feature = "stub_server"));
+    }
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        use std::time::Duration;
+
+        assert_eq!(parse_grpc_timeout("2H"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_grpc_timeout("3M"), Some(Duration::from_secs(180)));
+        assert_eq!(parse_grpc_timeout("5S"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_grpc_timeout("250m"), Some(Duration::from_millis(250)));
+        assert_eq!(
+            parse_grpc_timeout("99999999u"),
+            Some(Duration::from_micros(99_999_999))
+        );
+        assert_eq!(parse_grpc_timeout("10n"), Some(Duration::from_nanos(10)));
+
+        for invalid in ["", "S", "5", "5s", "+5S", "-5S", "123456789S", "5 S", "5é"] {
+            assert_eq!(parse_grpc_timeout(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_request_deadline() {
+        let mut request = Request::new(ReadyRequest {});
+        assert!(request_deadline(&request).is_none());
+
+        request
+            .metadata_mut()
+            .insert("grpc-timeout", "1S".parse().unwrap());
+        let deadline = request_deadline(&request).unwrap();
+        assert!(deadline > std::time::Instant::now());
+        assert!(deadline <= std::time::Instant::now() + std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_operator_claim() {
+        let mut request = Request::new(ReadyRequest {});
+        assert_eq!(operator_claim(&request, true).unwrap(), None);
+
+        request
+            .metadata_mut()
+            .insert(OPERATOR_CLAIM_KEY, "Operator".parse().unwrap());
+        assert_eq!(
+            operator_claim(&request, true).unwrap(),
+            Some("Operator".to_string())
+        );
+
+        // Ignored without enforcement
+        assert_eq!(operator_claim(&request, false).unwrap(), None);
+
+        request
+            .metadata_mut()
+            .insert(OPERATOR_CLAIM_KEY, "'Operator'".parse().unwrap());
+        let status = operator_claim(&request, true).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_stream_operator_filter() {
+        assert!(stream_operator_filter(&None).is_ok());
+        assert!(stream_operator_filter(&Some("Operator".to_string())).is_ok());
+
+        let status = stream_operator_filter(&Some("'Operator'".to_string())).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_flight_update_status() {
+        let status = |kind| {
+            flight_update_status(PostgisError::FlightPath(flight::FlightError::Database(
+                kind,
+            )))
+            .code()
+        };
+
+        assert_eq!(
+            status(DbErrorKind::AlreadyExists),
+            tonic::Code::AlreadyExists
+        );
+        assert_eq!(
+            status(DbErrorKind::ForeignKeyViolation),
+            tonic::Code::FailedPrecondition
+        );
+        assert_eq!(
+            status(DbErrorKind::SerializationFailure),
+            tonic::Code::Aborted
+        );
+        assert_eq!(status(DbErrorKind::Connection), tonic::Code::Unavailable);
+        assert_eq!(status(DbErrorKind::ReadOnly), tonic::Code::Unavailable);
+        assert_eq!(status(DbErrorKind::Timeout), tonic::Code::DeadlineExceeded);
+        assert_eq!(
+            flight_update_status(PostgisError::FlightPath(flight::FlightError::Timeout)).code(),
+            tonic::Code::DeadlineExceeded
+        );
+        assert_eq!(
+            flight_update_status(PostgisError::FlightPath(flight::FlightError::DBError)).code(),
+            tonic::Code::Internal
+        );
+    }
+
+    #[test]
+    fn test_flight_update_status_string_violation() {
+        let violation = |e| {
+            let status = flight_update_status(PostgisError::FlightPath(
+                flight::FlightError::InvalidString("aircraft_identifier", e),
+            ));
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+            assert!(status.message().contains("aircraft_identifier"));
+            <grpc_server::StringViolation as prost::Message>::decode(status.details()).unwrap()
+        };
+
+        let details = violation(utils::StringError::TooLong {
+            length: 21,
+            max: 20,
+        });
+        assert_eq!(details.field, "aircraft_identifier");
+        assert_eq!(details.rule, grpc_server::StringRule::TooLong as i32);
+        assert_eq!((details.length, details.limit), (21, 20));
+
+        let details = violation(utils::StringError::TooShort { length: 0, min: 1 });
+        assert_eq!(details.rule, grpc_server::StringRule::TooShort as i32);
+        assert_eq!((details.length, details.limit), (0, 1));
+
+        let details = violation(utils::StringError::Character {
+            character: ';',
+            index: 3,
+        });
+        assert_eq!(details.rule, grpc_server::StringRule::Character as i32);
+        assert_eq!(details.character, Some(";".to_string()));
+        assert_eq!(details.index, 3);
+
+        let details = violation(utils::StringError::Mismatch);
+        assert_eq!(details.rule, grpc_server::StringRule::Mismatch as i32);
     }
 }
@@ -0,0 +1,410 @@
+//! Readiness of this instance
+//!
+//! A reachable database alone doesn't make the instance useful: if no
+//!  position was ingested for minutes the feed upstream is broken. Each
+//!  data domain is measured and compared against a configured threshold,
+//!  a threshold of 0 disables the check of that domain.
+
+use crate::grpc::server::grpc_server::{
+    DomainHealth, DomainStatus, QueueStatus, Readiness, ReadyResponse,
+};
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Age of the newest stored aircraft position
+pub const DOMAIN_AIRCRAFT_POSITIONS: &str = "aircraft_positions";
+
+/// Messages waiting in the fullest ingestion queue
+pub const DOMAIN_INGESTION_QUEUE_DEPTH: &str = "ingestion_queue_depth";
+
+/// Age of the oldest message waiting in an ingestion queue
+pub const DOMAIN_INGESTION_QUEUE_LAG: &str = "ingestion_queue_lag";
+
+/// Time since the last committed flight update
+pub const DOMAIN_FLIGHT_UPDATES: &str = "flight_updates";
+
+/// Thresholds of the data domains, 0 disables a check
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Thresholds {
+    /// Max age of the newest aircraft position
+    pub position_max_age_secs: u64,
+
+    /// Max messages waiting in an ingestion queue
+    pub queue_max_depth: u64,
+
+    /// Max age of the oldest message waiting in an ingestion queue
+    pub queue_max_lag_secs: u64,
+
+    /// Max time since the last committed flight update
+    pub flight_update_max_age_secs: u64,
+}
+
+impl From<&crate::Config> for Thresholds {
+    fn from(config: &crate::Config) -> Self {
+        Thresholds {
+            position_max_age_secs: config.readiness_position_max_age_secs,
+            queue_max_depth: config.readiness_queue_max_depth,
+            queue_max_lag_secs: config.readiness_queue_max_lag_secs,
+            flight_update_max_age_secs: config.readiness_flight_update_max_age_secs,
+        }
+    }
+}
+
+/// Configured thresholds, every check is disabled if unset
+pub static THRESHOLDS: OnceCell<Thresholds> = OnceCell::new();
+
+/// Startup time, flight updates are aged from it until the first one
+pub static STARTED_AT: OnceCell<DateTime<Utc>> = OnceCell::new();
+
+/// Age of the newest aircraft position at the last readiness check
+pub static POSITION_AGE_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Depth of the fullest ingestion queue at the last readiness check
+pub static QUEUE_DEPTH: AtomicU64 = AtomicU64::new(0);
+
+/// Age of the oldest queued message at the last readiness check
+pub static QUEUE_LAG_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Time since the last flight update at the last readiness check
+pub static FLIGHT_UPDATE_AGE_MS: AtomicU64 = AtomicU64::new(0);
+
+/// [`Readiness`] of the last readiness check
+pub static READINESS: AtomicU64 = AtomicU64::new(Readiness::Ready as u64);
+
+/// Values measured for the data domains, `None` if not measured
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+struct Measurements {
+    position_age_ms: Option<u64>,
+    queue_depth: Option<u64>,
+    queue_lag_ms: Option<u64>,
+    flight_update_age_ms: Option<u64>,
+}
+
+/// Milliseconds from a time until now, 0 for times in the future
+fn age_ms(time: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    now.signed_duration_since(time).num_milliseconds().max(0) as u64
+}
+
+/// Compares a measured value against the threshold of its domain
+fn domain_health(domain: &str, value: Option<u64>, threshold: u64) -> DomainHealth {
+    let status = match (value, threshold) {
+        (_, 0) => DomainStatus::Disabled,
+        (None, _) => DomainStatus::Unknown,
+        (Some(value), threshold) if value > threshold => DomainStatus::Stale,
+        _ => DomainStatus::Fresh,
+    };
+
+    DomainHealth {
+        domain: domain.to_string(),
+        status: status as i32,
+        value,
+        threshold,
+    }
+}
+
+/// Overall verdict: unready without a database, degraded if a checked
+///  domain is stale or couldn't be measured
+fn verdict(database_reachable: bool, domains: &[DomainHealth]) -> Readiness {
+    if !database_reachable {
+        return Readiness::Unready;
+    }
+
+    let degraded = domains.iter().any(|domain| {
+        domain.status == DomainStatus::Stale as i32 || domain.status == DomainStatus::Unknown as i32
+    });
+
+    if degraded {
+        Readiness::Degraded
+    } else {
+        Readiness::Ready
+    }
+}
+
+/// Builds the readiness response from the measured values
+fn evaluate(
+    database_reachable: bool,
+    measurements: &Measurements,
+    thresholds: &Thresholds,
+) -> ReadyResponse {
+    let domains = vec![
+        domain_health(
+            DOMAIN_AIRCRAFT_POSITIONS,
+            measurements.position_age_ms,
+            thresholds.position_max_age_secs.saturating_mul(1000),
+        ),
+        domain_health(
+            DOMAIN_INGESTION_QUEUE_DEPTH,
+            measurements.queue_depth,
+            thresholds.queue_max_depth,
+        ),
+        domain_health(
+            DOMAIN_INGESTION_QUEUE_LAG,
+            measurements.queue_lag_ms,
+            thresholds.queue_max_lag_secs.saturating_mul(1000),
+        ),
+        domain_health(
+            DOMAIN_FLIGHT_UPDATES,
+            measurements.flight_update_age_ms,
+            thresholds.flight_update_max_age_secs.saturating_mul(1000),
+        ),
+    ];
+
+    let readiness = verdict(database_reachable, &domains);
+    ReadyResponse {
+        ready: readiness != Readiness::Unready,
+        readiness: readiness as i32,
+        domains,
+    }
+}
+
+/// Keeps the last measured values for the metrics endpoint, see
+///  [`crate::metrics`]
+fn update_gauges(measurements: &Measurements, readiness: Readiness) {
+    let gauges = [
+        (&POSITION_AGE_MS, measurements.position_age_ms),
+        (&QUEUE_DEPTH, measurements.queue_depth),
+        (&QUEUE_LAG_MS, measurements.queue_lag_ms),
+        (&FLIGHT_UPDATE_AGE_MS, measurements.flight_update_age_ms),
+    ];
+
+    for (gauge, value) in gauges {
+        if let Some(value) = value {
+            gauge.store(value, Ordering::Relaxed);
+        }
+    }
+
+    READINESS.store(readiness as u64, Ordering::Relaxed);
+}
+
+/// Age of the oldest message waiting in any ingestion queue, 0 if all are
+///  empty
+///
+/// Unknown if a waiting message can't be aged, e.g. a flight path queued
+///  without an enqueue time, rather than hiding that queue's lag.
+fn queue_lag_ms(queues: &[QueueStatus]) -> Option<u64> {
+    queues
+        .iter()
+        .filter(|queue| queue.depth > 0)
+        .try_fold(0, |lag: u64, queue| Some(lag.max(queue.oldest_age_ms?)))
+}
+
+/// Measures the enabled data domains
+async fn measure(database_reachable: bool, thresholds: &Thresholds) -> Measurements {
+    let now = crate::clock::now();
+    let mut measurements = Measurements::default();
+
+    if database_reachable && thresholds.position_max_age_secs > 0 {
+        measurements.position_age_ms =
+            match crate::postgis::aircraft::get_latest_position_update().await {
+                Ok(latest) => latest.map(|time| age_ms(time, now)),
+                Err(e) => {
+                    log::warn!("(measure) could not get the latest position update: {e}");
+                    None
+                }
+            };
+    }
+
+    if thresholds.queue_max_depth > 0 || thresholds.queue_max_lag_secs > 0 {
+        match crate::cache::status::get_ingestion_status().await {
+            Ok(status) => {
+                measurements.queue_depth = status.queues.iter().map(|queue| queue.depth).max();
+                measurements.queue_lag_ms = queue_lag_ms(&status.queues);
+            }
+            Err(e) => log::warn!("(measure) could not get the ingestion status: {e}"),
+        }
+    }
+
+    if thresholds.flight_update_max_age_secs > 0 {
+        let last_update = crate::postgis::flight::LAST_FLIGHT_UPDATE_MS.load(Ordering::Relaxed);
+        let last_update = DateTime::from_timestamp_millis(last_update).filter(|_| last_update > 0);
+        measurements.flight_update_age_ms = last_update
+            .into_iter()
+            .chain(STARTED_AT.get().copied())
+            .max()
+            .map(|time| age_ms(time, now));
+    }
+
+    measurements
+}
+
+/// Gets the readiness of this instance and the health of each data domain
+///
+/// Never fails: an unreachable database makes the instance unready, a
+///  stale or unmeasurable domain only degrades it.
+pub async fn get_readiness() -> ReadyResponse {
+    let thresholds = THRESHOLDS.get().copied().unwrap_or_default();
+    let database_reachable = crate::postgis::get_schema_version().await.is_ok();
+    let measurements = measure(database_reachable, &thresholds).await;
+    let response = evaluate(database_reachable, &measurements, &thresholds);
+
+    let readiness = Readiness::try_from(response.readiness).unwrap_or(Readiness::Unready);
+    update_gauges(&measurements, readiness);
+    if readiness != Readiness::Ready {
+        log::warn!(
+            "(get_readiness) {}: {:?}",
+            readiness.as_str_name(),
+            response.domains
+        );
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(response: &ReadyResponse, domain: &str) -> DomainStatus {
+        let status = response
+            .domains
+            .iter()
+            .find(|health| health.domain == domain)
+            .expect("domain not reported")
+            .status;
+
+        DomainStatus::try_from(status).expect("invalid status")
+    }
+
+    #[test]
+    fn ut_queue_lag_ms() {
+        let queue = |depth, oldest_age_ms| QueueStatus {
+            queue: "queue".to_string(),
+            depth,
+            oldest_age_ms,
+        };
+
+        assert_eq!(queue_lag_ms(&[]), Some(0));
+        assert_eq!(queue_lag_ms(&[queue(0, None), queue(0, None)]), Some(0));
+        assert_eq!(
+            queue_lag_ms(&[queue(3, Some(500)), queue(1, Some(2000)), queue(0, None)]),
+            Some(2000)
+        );
+
+        // A waiting message without a timestamp
+        assert_eq!(queue_lag_ms(&[queue(3, Some(500)), queue(1, None)]), None);
+    }
+
+    #[test]
+    fn ut_domain_health() {
+        let health = domain_health(DOMAIN_INGESTION_QUEUE_DEPTH, Some(10), 10);
+        assert_eq!(health.status, DomainStatus::Fresh as i32);
+        assert_eq!(health.value, Some(10));
+        assert_eq!(health.threshold, 10);
+
+        let health = domain_health(DOMAIN_INGESTION_QUEUE_DEPTH, Some(11), 10);
+        assert_eq!(health.status, DomainStatus::Stale as i32);
+
+        let health = domain_health(DOMAIN_INGESTION_QUEUE_DEPTH, None, 10);
+        assert_eq!(health.status, DomainStatus::Unknown as i32);
+
+        let health = domain_health(DOMAIN_INGESTION_QUEUE_DEPTH, Some(11), 0);
+        assert_eq!(health.status, DomainStatus::Disabled as i32);
+        let health = domain_health(DOMAIN_INGESTION_QUEUE_DEPTH, None, 0);
+        assert_eq!(health.status, DomainStatus::Disabled as i32);
+    }
+
+    #[test]
+    fn ut_verdict() {
+        let fresh = domain_health(DOMAIN_FLIGHT_UPDATES, Some(1), 10);
+        let stale = domain_health(DOMAIN_FLIGHT_UPDATES, Some(11), 10);
+        let unknown = domain_health(DOMAIN_FLIGHT_UPDATES, None, 10);
+        let disabled = domain_health(DOMAIN_FLIGHT_UPDATES, None, 0);
+
+        assert_eq!(verdict(true, &[]), Readiness::Ready);
+        assert_eq!(
+            verdict(true, &[fresh.clone(), disabled.clone()]),
+            Readiness::Ready
+        );
+        assert_eq!(
+            verdict(true, &[fresh.clone(), stale.clone()]),
+            Readiness::Degraded
+        );
+        assert_eq!(verdict(true, &[unknown]), Readiness::Degraded);
+        assert_eq!(verdict(false, &[fresh]), Readiness::Unready);
+        assert_eq!(verdict(false, &[stale]), Readiness::Unready);
+    }
+
+    #[test]
+    fn ut_evaluate() {
+        let thresholds = Thresholds {
+            position_max_age_secs: 60,
+            queue_max_depth: 100,
+            queue_max_lag_secs: 0,
+            flight_update_max_age_secs: 900,
+        };
+        let measurements = Measurements {
+            position_age_ms: Some(59_000),
+            queue_depth: Some(100),
+            queue_lag_ms: None,
+            flight_update_age_ms: Some(900_000),
+        };
+
+        let response = evaluate(true, &measurements, &thresholds);
+        assert!(response.ready);
+        assert_eq!(response.readiness, Readiness::Ready as i32);
+        assert_eq!(response.domains.len(), 4);
+        assert_eq!(
+            status(&response, DOMAIN_INGESTION_QUEUE_LAG),
+            DomainStatus::Disabled
+        );
+
+        // Positions stopped arriving, still serving
+        let response = evaluate(
+            true,
+            &Measurements {
+                position_age_ms: Some(61_000),
+                ..measurements
+            },
+            &thresholds,
+        );
+        assert!(response.ready);
+        assert_eq!(response.readiness, Readiness::Degraded as i32);
+        assert_eq!(
+            status(&response, DOMAIN_AIRCRAFT_POSITIONS),
+            DomainStatus::Stale
+        );
+        assert_eq!(
+            status(&response, DOMAIN_FLIGHT_UPDATES),
+            DomainStatus::Fresh
+        );
+
+        let response = evaluate(false, &measurements, &thresholds);
+        assert!(!response.ready);
+        assert_eq!(response.readiness, Readiness::Unready as i32);
+
+        // Nothing checked
+        let response = evaluate(true, &Measurements::default(), &Thresholds::default());
+        assert_eq!(response.readiness, Readiness::Ready as i32);
+        assert!(response
+            .domains
+            .iter()
+            .all(|health| health.status == DomainStatus::Disabled as i32));
+    }
+
+    #[test]
+    fn ut_age_ms() {
+        let now = Utc::now();
+        assert_eq!(
+            age_ms(now - chrono::Duration::try_seconds(2).unwrap(), now),
+            2000
+        );
+        assert_eq!(
+            age_ms(now + chrono::Duration::try_seconds(2).unwrap(), now),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_get_readiness_no_database() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_readiness_no_database) start");
+
+        let response = get_readiness().await;
+        assert!(!response.ready);
+        assert_eq!(response.readiness, Readiness::Unready as i32);
+        assert_eq!(READINESS.load(Ordering::Relaxed), Readiness::Unready as u64);
+
+        ut_info!("(ut_get_readiness_no_database) success");
+    }
+}
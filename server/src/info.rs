@@ -0,0 +1,83 @@
+//! Build and schema information of this instance
+//!
+//! Used to debug mismatches between replicas and the database they share.
+
+use crate::grpc::server::grpc_server::GetServiceInfoResponse;
+use crate::postgis::service_area::{service_area_wkt, SERVICE_AREA_BUFFER_METERS};
+
+/// Git commit the service was built from, set by the build script
+pub const GIT_SHA: &str = env!("SVC_GIS_GIT_SHA");
+
+/// Cargo features and whether they were enabled at build time
+const FEATURES: &[(&str, bool)] = &[
+    ("dev", cfg!(feature = "dev")),
+    ("mock", cfg!(feature = "mock")),
+    ("stub_backends", cfg!(feature = "stub_backends")),
+    ("stub_client", cfg!(feature = "stub_client")),
+    ("stub_server", cfg!(feature = "stub_server")),
+    ("test_util", cfg!(feature = "test_util")),
+    ("vendored-openssl", cfg!(feature = "vendored-openssl")),
+];
+
+/// Cargo features enabled at build time
+pub fn enabled_features() -> Vec<String> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Gets the build information and the applied schema version
+///
+/// Never fails: if the database can't be reached the schema version is
+///  left unset and the reason is reported in `database_status`.
+pub async fn get_service_info() -> GetServiceInfoResponse {
+    let (schema_version, database_status) = match crate::postgis::get_schema_version().await {
+        Ok(Some(version)) => (Some(version), "ok".to_string()),
+        Ok(None) => (None, "no schema version recorded".to_string()),
+        Err(e) => (None, e.to_string()),
+    };
+
+    GetServiceInfoResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: GIT_SHA.to_string(),
+        features: enabled_features(),
+        schema_version,
+        database_status,
+        service_area: service_area_wkt(),
+        service_area_buffer_meters: SERVICE_AREA_BUFFER_METERS.get().copied().unwrap_or(0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_enabled_features() {
+        let features = enabled_features();
+
+        assert_eq!(
+            features.contains(&"mock".to_string()),
+            cfg!(feature = "mock")
+        );
+        assert!(features
+            .iter()
+            .all(|feature| FEATURES.iter().any(|(name, _)| name == feature)));
+    }
+
+    #[tokio::test]
+    async fn ut_get_service_info_no_database() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_service_info_no_database) start");
+
+        let info = get_service_info().await;
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.git_sha.is_empty());
+        assert_eq!(info.schema_version, None);
+        assert_ne!(info.database_status, "ok");
+
+        ut_info!("(ut_get_service_info_no_database) success");
+    }
+}
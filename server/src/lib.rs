@@ -7,9 +7,15 @@ use tokio::sync::OnceCell;
 pub mod test_util;
 
 pub mod cache;
+pub mod clock;
 pub mod config;
 pub mod grpc;
+pub mod health;
+pub mod info;
+pub mod logging;
+pub mod metrics;
 pub mod postgis;
+pub mod shutdown;
 
 /// Types used with svc-gis Redis queues
 pub mod types {
@@ -61,7 +67,64 @@ pub async fn load_logger_config_from_file(config_file: &str) -> Result<(), Strin
     }
 }
 
-/// Tokio signal handler that will wait for a user to press CTRL+C.
+/// Initialize a log4rs logger with provided configuration file path,
+///  writing structured JSON instead of the encoders of the file
+///
+/// Used instead of [`load_logger_config_from_file`] when `LOG_FORMAT=json`,
+///  levels, loggers and appenders still come from the file.
+pub async fn load_structured_logger_config_from_file(config_file: &str) -> Result<(), String> {
+    let log_handle = get_log_handle()
+        .await
+        .ok_or("(load_structured_logger_config_from_file) Could not get the log handle.")?;
+    match log4rs::config::load_config_file(config_file, logging::structured_deserializers()) {
+        Ok(config) => {
+            log_handle.set_config(config);
+            Ok(())
+        }
+        Err(e) => Err(format!(
+            "(logger) Could not parse log config file [{}]: {}.",
+            config_file, e,
+        )),
+    }
+}
+
+/// Replace the logger configuration with structured JSON output to stdout
+///
+/// Fallback of [`load_structured_logger_config_from_file`] when the log
+///  configuration file can't be loaded.
+pub async fn load_structured_logger(level: log::LevelFilter) -> Result<(), String> {
+    let log_handle = get_log_handle()
+        .await
+        .ok_or("(load_structured_logger) Could not get the log handle.")?;
+    let config = logging::structured_config(level)?;
+    log_handle.set_config(config);
+    Ok(())
+}
+
+/// Waits for CTRL+C or, on unix, SIGTERM (sent by container runtimes)
+#[cfg(not(tarpaulin_include))]
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("(shutdown_signal) expect tokio signal SIGTERM");
+
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result.expect("(shutdown_signal) expect tokio signal ctrl-c")
+            }
+            _ = terminate.recv() => (),
+        }
+    }
+
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c()
+        .await
+        .expect("(shutdown_signal) expect tokio signal ctrl-c");
+}
+
+/// Tokio signal handler that will wait for a user to press CTRL+C (or SIGTERM).
 /// This signal handler can be used in our [`axum::Server`] method `with_graceful_shutdown`
 /// and in our [`tonic::transport::Server`] method `serve_with_shutdown`.
 ///
@@ -104,9 +167,7 @@ pub async fn shutdown_signal(
         Some(receiver) => receiver
             .await
             .expect("(shutdown_signal) expect tokio signal oneshot Receiver"),
-        None => tokio::signal::ctrl_c()
-            .await
-            .expect("(shutdown_signal) expect tokio signal ctrl-c"),
+        None => wait_for_signal().await,
     }
 
     log::warn!("(shutdown_signal) server shutdown for [{}].", server);
@@ -0,0 +1,311 @@
+//! # Logging
+//!
+//! Structured JSON output for the `postgis_*!` and `grpc_*!` log macros.
+//!
+//! The macros produce messages of the form `(function) text`. The
+//!  [`StructuredEncoder`] writes the function name and the key-values
+//!  attached at the call site (e.g. `postgis_info!(count = n; "...")`) as
+//!  separate JSON fields, so log aggregators can filter on the function
+//!  name, request identifiers, row counts and durations.
+//!
+//! Structured output keeps the log configuration file: levels, loggers and
+//!  appenders are loaded from it as usual, only the encoders of its
+//!  appenders are replaced (see [`structured_deserializers`]).
+
+use log::kv::{Error as KvError, Key, Value as KvValue, VisitSource};
+use log::Record;
+use log4rs::config::{Deserialize, Deserializers};
+use log4rs::encode::{Encode, Write};
+use serde_json::{Map, Value};
+
+/// Value of the `LOG_FORMAT` setting which enables structured output
+pub const LOG_FORMAT_JSON: &str = "json";
+
+/// Encoder kinds of the log configuration file written as structured JSON
+pub const STRUCTURED_ENCODER_KINDS: [&str; 2] = ["pattern", "json"];
+
+/// log4rs encoder writing one JSON object per log record
+#[derive(Debug, Default, Copy, Clone)]
+pub struct StructuredEncoder;
+
+impl StructuredEncoder {
+    /// Create a new structured encoder
+    pub fn new() -> Self {
+        StructuredEncoder
+    }
+}
+
+/// Builds a [`StructuredEncoder`] in place of a configured encoder,
+///  ignoring its settings
+#[derive(Debug, Default, Copy, Clone)]
+pub struct StructuredEncoderDeserializer;
+
+impl Deserialize for StructuredEncoderDeserializer {
+    type Trait = dyn Encode;
+    type Config = serde::de::IgnoredAny;
+
+    fn deserialize(
+        &self,
+        _config: Self::Config,
+        _deserializers: &Deserializers,
+    ) -> anyhow::Result<Box<dyn Encode>> {
+        Ok(Box::new(StructuredEncoder::new()))
+    }
+}
+
+/// Gets the deserializers of a log configuration file with the encoders of
+///  [`STRUCTURED_ENCODER_KINDS`] replaced by the [`StructuredEncoder`]
+///
+/// Appenders without an encoder in the file keep their default encoder.
+pub fn structured_deserializers() -> Deserializers {
+    let mut deserializers = Deserializers::default();
+    for kind in STRUCTURED_ENCODER_KINDS {
+        deserializers.insert(kind, StructuredEncoderDeserializer);
+    }
+
+    deserializers
+}
+
+/// Splits a `(function) text` message into the function name and the text
+fn split_function(message: &str) -> (Option<&str>, &str) {
+    let Some(rest) = message.strip_prefix('(') else {
+        return (None, message);
+    };
+
+    let Some(end) = rest.find(')') else {
+        return (None, message);
+    };
+
+    let function = &rest[..end];
+    if function.is_empty() || function.contains(char::is_whitespace) {
+        return (None, message);
+    }
+
+    (Some(function), rest[end + 1..].trim_start())
+}
+
+/// Collects the key-values of a log record as JSON fields
+struct FieldCollector(Map<String, Value>);
+
+impl<'kvs> VisitSource<'kvs> for FieldCollector {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: KvValue<'kvs>) -> Result<(), KvError> {
+        let value = if let Some(number) = value.to_i64() {
+            Value::from(number)
+        } else if let Some(number) = value.to_u64() {
+            Value::from(number)
+        } else if let Some(number) = value.to_f64().filter(|number| number.is_finite()) {
+            Value::from(number)
+        } else if let Some(flag) = value.to_bool() {
+            Value::from(flag)
+        } else {
+            Value::from(value.to_string())
+        };
+
+        self.0.insert(key.as_str().to_string(), value);
+        Ok(())
+    }
+}
+
+/// Gets the key-values attached to a log record
+fn record_fields(record: &Record) -> Map<String, Value> {
+    let mut collector = FieldCollector(Map::new());
+    if let Err(e) = record.key_values().visit(&mut collector) {
+        collector
+            .0
+            .insert("fields_error".to_string(), Value::from(e.to_string()));
+    }
+
+    collector.0
+}
+
+/// Builds the JSON object for a log record
+fn record_to_json(record: &Record, time: String) -> Value {
+    let message = record.args().to_string();
+    let (function, _) = split_function(&message);
+
+    let mut object = Map::new();
+    object.insert("time".to_string(), Value::from(time));
+    object.insert("level".to_string(), Value::from(record.level().as_str()));
+    object.insert("target".to_string(), Value::from(record.target()));
+    object.insert(
+        "module".to_string(),
+        record.module_path().map(Value::from).unwrap_or(Value::Null),
+    );
+    object.insert(
+        "function".to_string(),
+        function.map(Value::from).unwrap_or(Value::Null),
+    );
+    object.insert(
+        "file".to_string(),
+        record.file().map(Value::from).unwrap_or(Value::Null),
+    );
+    object.insert(
+        "line".to_string(),
+        record.line().map(Value::from).unwrap_or(Value::Null),
+    );
+    object.insert("fields".to_string(), Value::Object(record_fields(record)));
+    object.insert("message".to_string(), Value::from(message));
+
+    Value::Object(object)
+}
+
+impl Encode for StructuredEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let time = chrono::Utc::now().to_rfc3339();
+        let json = record_to_json(record, time);
+        serde_json::to_writer(&mut *w, &json)?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Builds a log4rs configuration writing structured JSON to stdout, used
+///  when the log configuration file can't be loaded
+pub fn structured_config(level: log::LevelFilter) -> Result<log4rs::config::Config, String> {
+    let stdout = log4rs::append::console::ConsoleAppender::builder()
+        .encoder(Box::new(StructuredEncoder::new()))
+        .build();
+
+    log4rs::config::Config::builder()
+        .appender(log4rs::config::Appender::builder().build("stdout", Box::new(stdout)))
+        .build(
+            log4rs::config::Root::builder()
+                .appender("stdout")
+                .build(level),
+        )
+        .map_err(|e| format!("(structured_config) could not build log config: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log4rs::encode::writer::simple::SimpleWriter;
+
+    #[test]
+    fn ut_split_function() {
+        assert_eq!(
+            split_function("(get_flights) entry."),
+            (Some("get_flights"), "entry.")
+        );
+        assert_eq!(split_function("no function"), (None, "no function"));
+        assert_eq!(
+            split_function("(not a name) text"),
+            (None, "(not a name) text")
+        );
+        assert_eq!(split_function("(unclosed text"), (None, "(unclosed text"));
+    }
+
+    #[test]
+    fn ut_record_fields() {
+        let key_values = [
+            ("flight", KvValue::from("F-1")),
+            ("count", KvValue::from(3u64)),
+            ("duration_ms", KvValue::from(12.5)),
+            ("dry_run", KvValue::from(true)),
+        ];
+        let record = Record::builder()
+            .args(format_args!("(get_flights) success."))
+            .key_values(&key_values)
+            .build();
+
+        let fields = record_fields(&record);
+        assert_eq!(fields.get("flight"), Some(&Value::from("F-1")));
+        assert_eq!(fields.get("count"), Some(&Value::from(3)));
+        assert_eq!(fields.get("duration_ms"), Some(&Value::from(12.5)));
+        assert_eq!(fields.get("dry_run"), Some(&Value::from(true)));
+        assert_eq!(fields.len(), 4);
+
+        // Message text isn't parsed
+        let record = Record::builder()
+            .args(format_args!("(get_flights) found, count: '3'."))
+            .build();
+        assert!(record_fields(&record).is_empty());
+    }
+
+    #[test]
+    fn ut_structured_encoder() {
+        let key_values = [
+            ("zone", KvValue::from("ZONE-1")),
+            ("duration_ms", KvValue::from(4u64)),
+        ];
+        let record = Record::builder()
+            .args(format_args!("(delete_zone) entry."))
+            .key_values(&key_values)
+            .level(log::Level::Debug)
+            .target("backend::postgis")
+            .module_path(Some("svc_gis::postgis::zone"))
+            .file(Some("server/src/postgis/zone.rs"))
+            .line(Some(42))
+            .build();
+
+        let mut buffer = vec![];
+        StructuredEncoder::new()
+            .encode(&mut SimpleWriter(&mut buffer), &record)
+            .unwrap();
+
+        let line = String::from_utf8(buffer).unwrap();
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.lines().count(), 1);
+
+        let json: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["level"], "DEBUG");
+        assert_eq!(json["target"], "backend::postgis");
+        assert_eq!(json["module"], "svc_gis::postgis::zone");
+        assert_eq!(json["function"], "delete_zone");
+        assert_eq!(json["file"], "server/src/postgis/zone.rs");
+        assert_eq!(json["line"], 42);
+        assert_eq!(json["fields"]["zone"], "ZONE-1");
+        assert_eq!(json["fields"]["duration_ms"], 4);
+        assert_eq!(json["message"], "(delete_zone) entry.");
+        assert!(json["time"].is_string());
+    }
+
+    #[test]
+    fn ut_structured_config() {
+        assert!(structured_config(log::LevelFilter::Info).is_ok());
+    }
+
+    #[test]
+    fn ut_structured_deserializers() {
+        let path =
+            std::env::temp_dir().join(format!("svc-gis-structured-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+appenders:
+  stdout:
+    kind: console
+    encoder:
+      pattern: "{d} | {l} | {m}{n}"
+  json:
+    kind: console
+    encoder:
+      kind: json
+root:
+  level: warn
+  appenders:
+    - stdout
+loggers:
+  backend::postgis:
+    level: debug
+    appenders:
+      - json
+"#,
+        )
+        .unwrap();
+
+        let config = log4rs::config::load_config_file(&path, structured_deserializers());
+        std::fs::remove_file(&path).unwrap();
+
+        // Levels and loggers come from the file
+        let config = config.unwrap();
+        assert_eq!(config.root().max_log_level(), log::LevelFilter::Warn);
+        assert_eq!(config.appenders().len(), 2);
+        let logger = config
+            .loggers()
+            .iter()
+            .find(|logger| logger.name() == "backend::postgis")
+            .unwrap();
+        assert_eq!(logger.max_log_level(), log::LevelFilter::Debug);
+    }
+}
@@ -1,8 +1,9 @@
 //! Main function starting the server and initializing dependencies.
 
 use crate::types::{
-    AircraftId, AircraftPosition, AircraftVelocity, REDIS_KEY_AIRCRAFT_ID,
-    REDIS_KEY_AIRCRAFT_POSITION, REDIS_KEY_AIRCRAFT_VELOCITY,
+    AircraftId, AircraftPosition, AircraftTelemetry, AircraftVelocity, FlightPathMessage,
+    REDIS_KEY_AIRCRAFT_ID, REDIS_KEY_AIRCRAFT_POSITION, REDIS_KEY_AIRCRAFT_TELEMETRY,
+    REDIS_KEY_AIRCRAFT_VELOCITY, REDIS_KEY_FLIGHT_PATH,
 };
 use cache::Consumer;
 use log::info;
@@ -16,6 +17,7 @@ async fn start_redis_consumers(config: &Config) -> Result<(), ()> {
     let mut id_consumer = Consumer::new(config, REDIS_KEY_AIRCRAFT_ID, 500).await?;
     let mut position_consumer = Consumer::new(config, REDIS_KEY_AIRCRAFT_POSITION, 100).await?;
     let mut velocity_consumer = Consumer::new(config, REDIS_KEY_AIRCRAFT_VELOCITY, 100).await?;
+    let mut telemetry_consumer = Consumer::new(config, REDIS_KEY_AIRCRAFT_TELEMETRY, 100).await?;
 
     tokio::spawn(
         async move { <Consumer as IsConsumer<AircraftId>>::begin(&mut id_consumer).await },
@@ -29,6 +31,19 @@ async fn start_redis_consumers(config: &Config) -> Result<(), ()> {
         <Consumer as IsConsumer<AircraftVelocity>>::begin(&mut velocity_consumer).await
     });
 
+    tokio::spawn(async move {
+        <Consumer as IsConsumer<AircraftTelemetry>>::begin(&mut telemetry_consumer).await
+    });
+
+    //
+    // Flights
+    //
+    let mut flight_consumer = Consumer::new(config, REDIS_KEY_FLIGHT_PATH, 100).await?;
+
+    tokio::spawn(async move {
+        <Consumer as IsConsumer<FlightPathMessage>>::begin(&mut flight_consumer).await
+    });
+
     Ok(())
 }
 
@@ -39,11 +54,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Will use default config settings if no environment vars are found.
     let config = Config::try_from_env().unwrap_or_default();
 
-    // Try to load log configuration from the provided log file.
+    // Try to load log configuration from the provided log file, with
+    //  structured JSON encoders if enabled.
     // Will default to stdout debug logging if the file can not be loaded.
-    load_logger_config_from_file(config.log_config.as_str())
-        .await
-        .or_else(|e| Ok::<(), String>(log::error!("(main) {}", e)))?;
+    if config.log_format == logging::LOG_FORMAT_JSON {
+        if let Err(e) = load_structured_logger_config_from_file(config.log_config.as_str()).await {
+            load_structured_logger(log::LevelFilter::Debug)
+                .await
+                .or_else(|e| Ok::<(), String>(log::error!("(main) {}", e)))?;
+            log::error!("(main) {}", e);
+        }
+    } else {
+        load_logger_config_from_file(config.log_config.as_str())
+            .await
+            .or_else(|e| Ok::<(), String>(log::error!("(main) {}", e)))?;
+    }
 
     info!("(main) Server startup.");
 
@@ -54,7 +79,247 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         panic!("Could not set DEADPOOL_POSTGIS.");
     }
 
-    postgis::psql_init().await?;
+    // Dedicated pool so aircraft updates don't queue behind reads
+    if let Some(pool) = postgis::pool::create_telemetry_pool(config.clone()) {
+        if crate::postgis::DEADPOOL_POSTGIS_TELEMETRY
+            .set(pool)
+            .is_err()
+        {
+            log::error!("(main) Could not set DEADPOOL_POSTGIS_TELEMETRY.");
+            panic!("Could not set DEADPOOL_POSTGIS_TELEMETRY.");
+        }
+    }
+
+    // Extra databases holding a share of the aircraft, if configured
+    let shards = postgis::pool::create_shard_pools(config.clone());
+    if !shards.is_empty() {
+        info!(
+            "(main) Aircraft sharded over {} databases.",
+            shards.len() + 1
+        );
+        if postgis::shard::DEADPOOL_POSTGIS_SHARDS.set(shards).is_err() {
+            log::error!("(main) Could not set DEADPOOL_POSTGIS_SHARDS.");
+            panic!("Could not set DEADPOOL_POSTGIS_SHARDS.");
+        }
+    }
+
+    // Reject geometries outside of the service area, if configured
+    if let Some(area) = &config.service_area {
+        let Ok(area) = postgis::service_area::parse_service_area(area) else {
+            log::error!("(main) Invalid SERVICE_AREA: {area}");
+            panic!("Invalid SERVICE_AREA.");
+        };
+
+        if postgis::service_area::SERVICE_AREA.set(area).is_err() {
+            log::error!("(main) Could not set SERVICE_AREA.");
+            panic!("Could not set SERVICE_AREA.");
+        }
+
+        let buffer_meters = config.service_area_buffer_meters;
+        if !buffer_meters.is_finite() || buffer_meters < 0.0 {
+            log::error!("(main) Invalid SERVICE_AREA_BUFFER_METERS: {buffer_meters}");
+            panic!("Invalid SERVICE_AREA_BUFFER_METERS.");
+        }
+
+        if postgis::service_area::SERVICE_AREA_BUFFER_METERS
+            .set(buffer_meters)
+            .is_err()
+        {
+            log::error!("(main) Could not set SERVICE_AREA_BUFFER_METERS.");
+            panic!("Could not set SERVICE_AREA_BUFFER_METERS.");
+        }
+    }
+
+    // Freshness thresholds of the readiness check
+    if health::THRESHOLDS
+        .set(health::Thresholds::from(&config))
+        .is_err()
+    {
+        log::error!("(main) Could not set readiness THRESHOLDS.");
+        panic!("Could not set readiness THRESHOLDS.");
+    }
+
+    if health::STARTED_AT.set(clock::now()).is_err() {
+        log::error!("(main) Could not set STARTED_AT.");
+        panic!("Could not set STARTED_AT.");
+    }
+
+    // Append-only position history, if configured
+    let Ok(mode) = config
+        .aircraft_position_mode
+        .parse::<postgis::aircraft::PositionWriteMode>()
+    else {
+        log::error!(
+            "(main) Invalid AIRCRAFT_POSITION_MODE: {}",
+            config.aircraft_position_mode
+        );
+        panic!("Invalid AIRCRAFT_POSITION_MODE.");
+    };
+
+    if postgis::aircraft::POSITION_WRITE_MODE.set(mode).is_err() {
+        log::error!("(main) Could not set POSITION_WRITE_MODE.");
+        panic!("Could not set POSITION_WRITE_MODE.");
+    }
+
+    // Exercise traffic left out of conflict checks, if configured
+    if let Some(tags) = &config.conflict_exclude_tags {
+        let Ok(tags) = postgis::tags::parse_tags(tags) else {
+            log::error!("(main) Invalid CONFLICT_EXCLUDE_TAGS: {tags}");
+            panic!("Invalid CONFLICT_EXCLUDE_TAGS.");
+        };
+
+        if postgis::tags::CONFLICT_EXCLUDED_TAGS.set(tags).is_err() {
+            log::error!("(main) Could not set CONFLICT_EXCLUDED_TAGS.");
+            panic!("Could not set CONFLICT_EXCLUDED_TAGS.");
+        }
+    }
+
+    // Precision of stored positions and flight paths
+    let Ok(quantization) = postgis::utils::Quantization::new(
+        config.coordinate_quantum_degrees,
+        config.altitude_quantum_meters,
+    ) else {
+        log::error!(
+            "(main) Invalid COORDINATE_QUANTUM_DEGREES or ALTITUDE_QUANTUM_METERS: {}, {}",
+            config.coordinate_quantum_degrees,
+            config.altitude_quantum_meters
+        );
+        panic!("Invalid COORDINATE_QUANTUM_DEGREES or ALTITUDE_QUANTUM_METERS.");
+    };
+
+    if postgis::utils::QUANTIZATION.set(quantization).is_err() {
+        log::error!("(main) Could not set QUANTIZATION.");
+        panic!("Could not set QUANTIZATION.");
+    }
+
+    // Precision of returned distances
+    if !config.distance_resolution_meters.is_finite() || config.distance_resolution_meters < 0.0 {
+        log::error!(
+            "(main) Invalid DISTANCE_RESOLUTION_METERS: {}",
+            config.distance_resolution_meters
+        );
+        panic!("Invalid DISTANCE_RESOLUTION_METERS.");
+    }
+
+    if postgis::utils::DISTANCE_RESOLUTION_METERS
+        .set(config.distance_resolution_meters)
+        .is_err()
+    {
+        log::error!("(main) Could not set DISTANCE_RESOLUTION_METERS.");
+        panic!("Could not set DISTANCE_RESOLUTION_METERS.");
+    }
+
+    // Replayed time, if configured
+    if let Some(time_start) = &config.replay_start_time {
+        let Ok(time_start) = chrono::DateTime::parse_from_rfc3339(time_start) else {
+            log::error!("(main) Invalid REPLAY_START_TIME: {time_start}");
+            panic!("Invalid REPLAY_START_TIME.");
+        };
+
+        log::info!("(main) Replaying from {time_start}.");
+        clock::start_replay(time_start.with_timezone(&chrono::Utc));
+    }
+
+    if postgis::aircraft::KEEP_RAW_POSITION_HISTORY
+        .set(config.keep_raw_position_history)
+        .is_err()
+    {
+        log::error!("(main) Could not set KEEP_RAW_POSITION_HISTORY.");
+        panic!("Could not set KEEP_RAW_POSITION_HISTORY.");
+    }
+
+    if postgis::aircraft::RELOCATION_REPORTS
+        .set(config.relocation_reports)
+        .is_err()
+    {
+        log::error!("(main) Could not set RELOCATION_REPORTS.");
+        panic!("Could not set RELOCATION_REPORTS.");
+    }
+
+    postgis::psql_init(config.psql_init_lock_timeout_secs).await?;
+
+    // Queue aircraft updates so a lagging database doesn't block ingestion,
+    //  if enabled
+    if config.ingest_writers > 0
+        && postgis::aircraft::start_ingest_writers(
+            config.ingest_writers as usize,
+            config.ingest_queue_capacity as usize,
+        )
+        .is_err()
+    {
+        log::error!("(main) Could not start ingest writers.");
+        panic!("Could not start ingest writers.");
+    }
+
+    // Start periodic maintenance of hot tables, if enabled
+    if config.pg_maintenance_interval_secs > 0 {
+        tokio::spawn(postgis::maintenance::begin(
+            config.pg_maintenance_interval_secs,
+            config.pg_maintenance_vacuum,
+            postgis::archive::ArchivePolicy {
+                hot_retention_secs: config.history_hot_retention_secs,
+                archive_retention_secs: config.history_archive_retention_secs,
+                bucket_secs: config.history_archive_bucket_secs,
+            },
+        ));
+    }
+
+    // Drop expired events and events beyond the capacity from the backlog
+    tokio::spawn(postgis::backlog::begin_trim());
+
+    // Purge soft-deleted rows past retention, so their identifiers can be
+    //  reused, if enabled
+    if config.soft_delete_purge_interval_secs > 0 {
+        tokio::spawn(postgis::maintenance::begin_purge(
+            config.soft_delete_purge_interval_secs,
+            config.soft_delete_retention_secs,
+        ));
+    }
+
+    // Record vertiport throughput of completed flights, if enabled
+    //  The first run backfills flights that completed before this version
+    if config.throughput_interval_secs > 0 {
+        tokio::spawn(postgis::throughput::begin(
+            config.throughput_interval_secs,
+            config.vertiport_snap_distance_meters,
+        ));
+    }
+
+    // Alert on airborne aircraft without a flight, if enabled
+    if config.compliance_check_interval_secs > 0 {
+        tokio::spawn(postgis::compliance::begin(
+            config.compliance_check_interval_secs,
+            config.compliance_debounce_secs,
+            config.compliance_include_simulated,
+        ));
+    }
+
+    // Check upcoming flights for new conflicts, if enabled
+    if config.conflict_check_interval_secs > 0 {
+        tokio::spawn(postgis::conflict_check::begin(
+            config.conflict_check_interval_secs,
+            config.conflict_check_horizon_secs,
+            config.conflict_check_concurrency,
+        ));
+    }
+
+    // Redis pool for reporting the ingestion status
+    match config
+        .redis
+        .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+    {
+        Ok(pool) => {
+            if cache::status::STATUS_POOL.set(pool).is_err() {
+                log::error!("(main) Could not set STATUS_POOL.");
+            }
+        }
+        Err(e) => log::error!("(main) Could not create Redis status pool: {}", e),
+    }
+
+    // Serve the counters and gauges for scraping, if enabled
+    if config.docker_port_rest > 0 {
+        tokio::spawn(metrics::metrics_server(config.docker_port_rest));
+    }
 
     // Start the Redis consumers
     if start_redis_consumers(&config).await.is_err() {
@@ -62,8 +327,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         panic!("Could not start Redis consumers.");
     }
 
+    // On CTRL+C or SIGTERM stop the Redis consumers and the gRPC server,
+    //  which finishes requests already received
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(async move {
+        shutdown_signal("main", None).await;
+        shutdown::begin();
+        let _ = shutdown_tx.send(());
+    });
+
     // Start GRPC Server
-    tokio::spawn(grpc::server::grpc_server(config, None)).await?;
+    let grace_period_secs = config.shutdown_grace_period_secs;
+    tokio::spawn(grpc::server::grpc_server(config, Some(shutdown_rx))).await?;
+
+    // Let in-flight transactions finish before closing the pools
+    shutdown::shutdown(grace_period_secs).await;
 
     info!("(main) Server shutdown.");
 
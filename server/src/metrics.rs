@@ -0,0 +1,392 @@
+//! Registry of the counters and gauges of this instance
+//!
+//! Counters and gauges are kept next to the code updating them, this
+//!  module lists them once and renders them in the Prometheus text format,
+//!  served on `/metrics` by [`metrics_server`].
+
+use crate::postgis::{aircraft, backlog, best_path, flight, ingest, pool, service_area};
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+
+/// Prefix of the metric names
+const PREFIX: &str = "svc_gis";
+
+/// Kind of a metric family
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MetricKind {
+    /// Only increases, since startup
+    Counter,
+
+    /// Value at the time of the scrape or of the last measurement
+    Gauge,
+}
+
+impl std::fmt::Display for MetricKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MetricKind::Counter => write!(f, "counter"),
+            MetricKind::Gauge => write!(f, "gauge"),
+        }
+    }
+}
+
+/// Metric family with its samples, one per set of labels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Family {
+    /// Name without the [`PREFIX`]
+    pub name: &'static str,
+
+    /// Description of the metric
+    pub help: &'static str,
+
+    /// Kind of the metric
+    pub kind: MetricKind,
+
+    /// Label names and values with the value of each sample
+    pub samples: Vec<(Vec<(&'static str, String)>, u64)>,
+}
+
+/// Metric without labels read from a static at each scrape
+struct Registered {
+    name: &'static str,
+    help: &'static str,
+    kind: MetricKind,
+    value: fn() -> u64,
+}
+
+/// Metrics without labels
+const REGISTERED: &[Registered] = &[
+    Registered {
+        name: "dropped_events_total",
+        help: "Persisted events dropped from the full event backlog.",
+        kind: MetricKind::Counter,
+        value: backlog::dropped_events,
+    },
+    Registered {
+        name: "failover_recycles_total",
+        help: "Database pool recycles triggered by a suspected primary switchover.",
+        kind: MetricKind::Counter,
+        value: || pool::FAILOVER_RECYCLES.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "out_of_area_rejections_total",
+        help: "Positions, flight paths and zones rejected outside of the service area.",
+        kind: MetricKind::Counter,
+        value: || service_area::OUT_OF_AREA_REJECTIONS.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "implausible_positions_total",
+        help: "Aircraft positions quarantined as implausible.",
+        kind: MetricKind::Counter,
+        value: || aircraft::IMPLAUSIBLE_POSITIONS.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "undeclared_types_ignored_total",
+        help: "Undeclared types ignored for aircraft with a declared type.",
+        kind: MetricKind::Counter,
+        value: || aircraft::UNDECLARED_TYPES_IGNORED.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "type_transitions_rejected_total",
+        help: "Identifications rejected for an incompatible type change.",
+        kind: MetricKind::Counter,
+        value: || aircraft::TYPE_TRANSITIONS_REJECTED.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "reservations_confirmed_total",
+        help: "Flight reservations confirmed.",
+        kind: MetricKind::Counter,
+        value: || flight::RESERVATIONS_CONFIRMED.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "reservations_expired_total",
+        help: "Flight reservations removed after expiring.",
+        kind: MetricKind::Counter,
+        value: || flight::RESERVATIONS_EXPIRED.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "distance_mismatches_total",
+        help: "Paths whose routed distance failed the distance check.",
+        kind: MetricKind::Counter,
+        value: || best_path::DISTANCE_MISMATCH_COUNT.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "ingest_coalesced_total",
+        help: "Aircraft updates that replaced a pending update of the same aircraft.",
+        kind: MetricKind::Counter,
+        value: || ingest::INGEST_COALESCED.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "ingest_shed_total",
+        help: "Aircraft updates dropped because an ingest queue was full.",
+        kind: MetricKind::Counter,
+        value: || ingest::INGEST_SHED.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "ingest_queue_depth",
+        help: "Aircraft updates waiting in the ingest queues.",
+        kind: MetricKind::Gauge,
+        value: || ingest::INGEST_QUEUE_DEPTH.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "ingest_writer_lag_ms",
+        help: "Age of the oldest update waiting in the ingest queues.",
+        kind: MetricKind::Gauge,
+        value: || aircraft::ingest_writer_lag().as_millis() as u64,
+    },
+    Registered {
+        name: "readiness",
+        help: "Readiness of the last readiness check (0 ready, 1 degraded, 2 unready).",
+        kind: MetricKind::Gauge,
+        value: || crate::health::READINESS.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "readiness_position_age_ms",
+        help: "Age of the newest aircraft position at the last readiness check.",
+        kind: MetricKind::Gauge,
+        value: || crate::health::POSITION_AGE_MS.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "readiness_queue_depth",
+        help: "Depth of the fullest ingestion queue at the last readiness check.",
+        kind: MetricKind::Gauge,
+        value: || crate::health::QUEUE_DEPTH.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "readiness_queue_lag_ms",
+        help: "Age of the oldest queued message at the last readiness check.",
+        kind: MetricKind::Gauge,
+        value: || crate::health::QUEUE_LAG_MS.load(Ordering::Relaxed),
+    },
+    Registered {
+        name: "readiness_flight_update_age_ms",
+        help: "Time since the last flight update at the last readiness check.",
+        kind: MetricKind::Gauge,
+        value: || crate::health::FLIGHT_UPDATE_AGE_MS.load(Ordering::Relaxed),
+    },
+];
+
+/// Build information as labels of a constant gauge
+fn build_info() -> Family {
+    Family {
+        name: "build_info",
+        help: "Version, git commit and enabled features of this instance.",
+        kind: MetricKind::Gauge,
+        samples: vec![(
+            vec![
+                ("version", env!("CARGO_PKG_VERSION").to_string()),
+                ("git_sha", crate::info::GIT_SHA.to_string()),
+                ("features", crate::info::enabled_features().join(",")),
+            ],
+            1,
+        )],
+    }
+}
+
+/// Depth and oldest message age of each Redis ingestion queue at the last
+///  status read
+fn queue_families() -> Vec<Family> {
+    let queues = crate::cache::status::queue_gauges();
+    let label = |queue: &str| vec![("queue", queue.to_string())];
+
+    vec![
+        Family {
+            name: "ingestion_queue_depth",
+            help: "Messages waiting in the Redis ingestion queue at the last status read.",
+            kind: MetricKind::Gauge,
+            samples: queues
+                .iter()
+                .map(|status| (label(&status.queue), status.depth))
+                .collect(),
+        },
+        Family {
+            name: "ingestion_queue_oldest_age_ms",
+            help: "Age of the oldest message of the Redis ingestion queue at the last status read.",
+            kind: MetricKind::Gauge,
+            samples: queues
+                .iter()
+                .filter_map(|status| Some((label(&status.queue), status.oldest_age_ms?)))
+                .collect(),
+        },
+    ]
+}
+
+/// Usage of each database connection pool
+fn pool_families() -> Vec<Family> {
+    let pools = pool::pool_gauges();
+    let family =
+        |name: &'static str, help: &'static str, value: fn(&pool::PoolGauges) -> usize| Family {
+            name,
+            help,
+            kind: MetricKind::Gauge,
+            samples: pools
+                .iter()
+                .map(|(pool, gauges)| (vec![("pool", pool.to_string())], value(gauges) as u64))
+                .collect(),
+        };
+
+    vec![
+        family(
+            "pool_max_size",
+            "Max connections of the database pool.",
+            |gauges| gauges.max_size,
+        ),
+        family(
+            "pool_size",
+            "Connections open in the database pool.",
+            |gauges| gauges.size,
+        ),
+        family(
+            "pool_available",
+            "Open connections of the database pool not in use.",
+            |gauges| gauges.available,
+        ),
+        family(
+            "pool_waiting",
+            "Callers waiting for a connection of the database pool.",
+            |gauges| gauges.waiting,
+        ),
+    ]
+}
+
+/// Reads every registered metric
+pub fn gather() -> Vec<Family> {
+    let mut families = vec![build_info()];
+    families.extend(REGISTERED.iter().map(|metric| Family {
+        name: metric.name,
+        help: metric.help,
+        kind: metric.kind,
+        samples: vec![(vec![], (metric.value)())],
+    }));
+    families.extend(queue_families());
+    families.extend(pool_families());
+    families
+}
+
+/// Escapes a label value for the text format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+/// Renders metric families in the Prometheus text format
+pub fn render(families: &[Family]) -> String {
+    let mut text = String::new();
+    for family in families {
+        let name = format!("{PREFIX}_{}", family.name);
+        let _ = writeln!(text, "# HELP {name} {}", family.help);
+        let _ = writeln!(text, "# TYPE {name} {}", family.kind);
+        for (labels, value) in &family.samples {
+            let labels = labels
+                .iter()
+                .map(|(label, value)| format!(r#"{label}="{}""#, escape_label(value)))
+                .collect::<Vec<String>>();
+
+            if labels.is_empty() {
+                let _ = writeln!(text, "{name} {value}");
+            } else {
+                let _ = writeln!(text, "{name}{{{}}} {value}", labels.join(","));
+            }
+        }
+    }
+
+    text
+}
+
+/// Responds with the current metrics
+async fn metrics_handler() -> impl axum::response::IntoResponse {
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        render(&gather()),
+    )
+}
+
+/// Serves the metrics on `/metrics` until the process exits
+#[cfg(not(tarpaulin_include))]
+pub async fn metrics_server(port: u16) {
+    log::debug!("(metrics_server) entry.");
+    let addr: SocketAddr = match format!("[::]:{}", port).parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            log::error!("(metrics_server) Failed to parse metrics address: {}", e);
+            return;
+        }
+    };
+
+    let app = axum::Router::new().route("/metrics", axum::routing::get(metrics_handler));
+
+    log::info!("(metrics_server) Serving metrics on: {}.", addr);
+    if let Err(e) = axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+    {
+        log::error!("(metrics_server) Could not start metrics server: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_render() {
+        let families = vec![
+            Family {
+                name: "events_total",
+                help: "Events.",
+                kind: MetricKind::Counter,
+                samples: vec![(vec![], 3)],
+            },
+            Family {
+                name: "queue_depth",
+                help: "Depth.",
+                kind: MetricKind::Gauge,
+                samples: vec![
+                    (vec![("queue", "a".to_string())], 1),
+                    (vec![("queue", "b\"c".to_string())], 2),
+                ],
+            },
+        ];
+
+        assert_eq!(
+            render(&families),
+            "# HELP svc_gis_events_total Events.\n\
+            # TYPE svc_gis_events_total counter\n\
+            svc_gis_events_total 3\n\
+            # HELP svc_gis_queue_depth Depth.\n\
+            # TYPE svc_gis_queue_depth gauge\n\
+            svc_gis_queue_depth{queue=\"a\"} 1\n\
+            svc_gis_queue_depth{queue=\"b\\\"c\"} 2\n"
+        );
+    }
+
+    #[test]
+    fn ut_gather() {
+        let recycles = pool::FAILOVER_RECYCLES.load(Ordering::Relaxed);
+        let families = gather();
+
+        // Names are unique and every registered metric is read
+        let mut names: Vec<&str> = families.iter().map(|family| family.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), families.len());
+        assert!(REGISTERED.iter().all(|metric| names.contains(&metric.name)));
+
+        let family = families
+            .iter()
+            .find(|family| family.name == "failover_recycles_total")
+            .unwrap();
+        assert_eq!(family.kind, MetricKind::Counter);
+        assert!(family.samples[0].1 >= recycles);
+
+        assert!(families[0].samples[0]
+            .0
+            .contains(&("version", env!("CARGO_PKG_VERSION").to_string())));
+    }
+}
@@ -1,19 +1,271 @@
 //! This module contains functions for updating aircraft in the PostGIS database.
 
+use super::ingest::{self, IngestOutcome, IngestQueue};
+use super::service_area::check_service_area;
 use super::{psql_transaction, PostgisError, DEFAULT_SRID, PSQL_SCHEMA};
 
 use crate::cache::{Consumer, Processor};
-use crate::postgis::utils::StringError;
+use crate::grpc::server::grpc_server::{
+    GetAircraftTrackRequest, GetAircraftTrackResponse, PointZ as GrpcPointZ, TimePosition,
+};
+use crate::postgis::utils::{StringError, LAT_MAX, LAT_MIN, LON_MAX, LON_MIN};
 use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
 use postgis::ewkb::PointZ;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tonic::async_trait;
 
 use crate::types::{
-    AircraftId, AircraftPosition, AircraftType, AircraftVelocity, OperationalStatus,
+    AircraftId, AircraftPosition, AircraftTelemetry, AircraftType, AircraftVelocity,
+    OperationalStatus, Position,
 };
 
-/// Allowed characters in a identifier
-pub const IDENTIFIER_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,255}$";
+/// Allowed characters in a identifier, no longer than the `VARCHAR(20)`
+///  identifier columns
+pub const IDENTIFIER_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,20}$";
+
+/// Max time between downsampled points of an aircraft track
+pub const MAX_TRACK_RESOLUTION_SECONDS: u32 = 3600;
+
+/// Max snapshots returned by [`get_telemetry_history`]
+pub const MAX_TELEMETRY_HISTORY_ROWS: i64 = 10_000;
+
+/// Maximum number of status changes returned by [`get_status_history`]
+pub const MAX_STATUS_HISTORY_ROWS: i64 = 10_000;
+
+/// Maximum number of type changes returned by [`get_type_history`]
+pub const MAX_TYPE_HISTORY_ROWS: i64 = 10_000;
+
+/// If the unquantized positions are kept in the history, false if unset
+pub static KEEP_RAW_POSITION_HISTORY: OnceCell<bool> = OnceCell::new();
+
+/// Gets the unquantized position to keep in the history, if configured
+fn raw_history_position(position: &Position) -> Option<PointZ> {
+    KEEP_RAW_POSITION_HISTORY
+        .get()
+        .copied()
+        .unwrap_or_default()
+        .then(|| super::utils::raw_pointz(position))
+}
+
+/// How position updates are written, [`PositionWriteMode::Upsert`] if unset
+pub static POSITION_WRITE_MODE: OnceCell<PositionWriteMode> = OnceCell::new();
+
+/// How aircraft position updates are written
+#[derive(Debug, Copy, Clone, Default, PartialEq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum PositionWriteMode {
+    /// Overwrite the latest position in the aircraft table and append to
+    ///  the history
+    #[default]
+    Upsert,
+
+    /// Only append to the history, the latest position is read from the
+    ///  `latest_aircraft_position` view
+    Append,
+}
+
+/// Gets the configured position write mode
+fn position_write_mode() -> PositionWriteMode {
+    POSITION_WRITE_MODE.get().copied().unwrap_or_default()
+}
+
+/// Consistent reports needed to accept an aircraft at a position it couldn't
+///  have flown to (e.g. moved by truck), [`DEFAULT_RELOCATION_REPORTS`] if
+///  unset. 0 disables the plausibility check.
+pub static RELOCATION_REPORTS: OnceCell<u32> = OnceCell::new();
+
+/// Default number of consistent reports to accept a relocated aircraft
+pub const DEFAULT_RELOCATION_REPORTS: u32 = 3;
+
+/// Position updates quarantined as implausible since startup
+pub static IMPLAUSIBLE_POSITIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Undeclared types ignored for aircraft with a declared type since startup
+pub static UNDECLARED_TYPES_IGNORED: AtomicU64 = AtomicU64::new(0);
+
+/// Identifications rejected for an incompatible type change since startup
+pub static TYPE_TRANSITIONS_REJECTED: AtomicU64 = AtomicU64::new(0);
+
+/// Latest pending position per aircraft, if the ingest guard is started
+///  (see [`start_ingest_writers`])
+static POSITION_QUEUE: OnceCell<IngestQueue<String, AircraftPosition>> = OnceCell::new();
+
+/// Latest pending telemetry per aircraft, if the ingest guard is started
+///  (see [`start_ingest_writers`])
+static TELEMETRY_QUEUE: OnceCell<IngestQueue<String, AircraftTelemetry>> = OnceCell::new();
+
+/// Shortest time between two reports when computing an implied speed, so
+///  reports with the same timestamp don't imply an infinite speed
+const MIN_PLAUSIBILITY_INTERVAL_SECS: f64 = 1.0;
+
+/// Gets the configured number of reports to accept a relocated aircraft
+fn relocation_reports() -> u32 {
+    RELOCATION_REPORTS
+        .get()
+        .copied()
+        .unwrap_or(DEFAULT_RELOCATION_REPORTS)
+}
+
+/// Max plausible speed of an aircraft type in meters per second
+///
+/// Generous upper bounds, only meant to catch positioning glitches.
+pub fn max_plausible_speed_mps(aircraft_type: AircraftType) -> f64 {
+    match aircraft_type {
+        AircraftType::Rocket => 3_000.0,
+        AircraftType::Undeclared | AircraftType::Other | AircraftType::Aeroplane => 350.0,
+        AircraftType::Hybridlift => 150.0,
+        AircraftType::Rotorcraft | AircraftType::Glider | AircraftType::Unpowered => 120.0,
+        AircraftType::Gyroplane => 80.0,
+        AircraftType::Freeballoon | AircraftType::Airship => 60.0,
+        AircraftType::Ornithopter => 50.0,
+        AircraftType::Kite | AircraftType::Tethered => 40.0,
+        AircraftType::Captiveballoon => 20.0,
+        AircraftType::Groundobstacle => 10.0,
+    }
+}
+
+/// Broad category of an aircraft type
+///
+/// One airframe doesn't move between these categories, a change most
+///  likely means that an identifier was reused.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum AircraftCategory {
+    /// Aeroplanes and gliders
+    FixedWing,
+
+    /// Rotorcraft and gyroplanes
+    RotaryWing,
+
+    /// Balloons and airships
+    LighterThanAir,
+}
+
+/// Category of an aircraft type, `None` for types that may be reclassified
+///  into any category (undeclared, hybrid lift, other, ...)
+fn aircraft_category(aircraft_type: AircraftType) -> Option<AircraftCategory> {
+    match aircraft_type {
+        AircraftType::Aeroplane | AircraftType::Glider => Some(AircraftCategory::FixedWing),
+        AircraftType::Rotorcraft | AircraftType::Gyroplane => Some(AircraftCategory::RotaryWing),
+        AircraftType::Freeballoon | AircraftType::Captiveballoon | AircraftType::Airship => {
+            Some(AircraftCategory::LighterThanAir)
+        }
+        AircraftType::Undeclared
+        | AircraftType::Hybridlift
+        | AircraftType::Ornithopter
+        | AircraftType::Kite
+        | AircraftType::Unpowered
+        | AircraftType::Rocket
+        | AircraftType::Tethered
+        | AircraftType::Groundobstacle
+        | AircraftType::Other => None,
+    }
+}
+
+/// Type to store for an identified aircraft, given its stored type
+///
+/// An undeclared type doesn't replace a declared one and a change between
+///  incompatible categories (e.g. aeroplane to rotorcraft) is rejected,
+///  unless `force` is set.
+fn resolve_aircraft_type(
+    previous: Option<AircraftType>,
+    incoming: AircraftType,
+    force: bool,
+) -> Result<AircraftType, AircraftError> {
+    let Some(previous) = previous else {
+        return Ok(incoming);
+    };
+
+    if force {
+        return Ok(incoming);
+    }
+
+    if matches!(incoming, AircraftType::Undeclared) && !matches!(previous, AircraftType::Undeclared)
+    {
+        return Ok(previous);
+    }
+
+    match (aircraft_category(previous), aircraft_category(incoming)) {
+        (Some(from), Some(to)) if from != to => Err(AircraftError::TypeTransition),
+        _ => Ok(incoming),
+    }
+}
+
+/// Checks that an identification doesn't move an owned aircraft to another
+///  operator
+///
+/// An identification without an operator keeps the stored one, an aircraft
+///  without an operator can be claimed by any.
+fn check_operator_change(
+    stored: Option<&str>,
+    incoming: Option<&str>,
+) -> Result<(), AircraftError> {
+    match (stored, incoming) {
+        (Some(stored), Some(incoming)) if stored != incoming => Err(AircraftError::Operator),
+        _ => Ok(()),
+    }
+}
+
+/// A stored or incoming position report
+#[derive(Debug, Copy, Clone)]
+struct Report {
+    geom: PointZ,
+    timestamp: DateTime<Utc>,
+}
+
+/// Speed needed to travel between two reports in meters per second
+fn implied_speed_mps(from: &Report, to: &Report) -> f64 {
+    let distance_meters = super::utils::distance_meters(&from.geom, &to.geom) as f64;
+    let seconds = ((to.timestamp - from.timestamp).num_milliseconds().abs() as f64 / 1000.0)
+        .max(MIN_PLAUSIBILITY_INTERVAL_SECS);
+
+    distance_meters / seconds
+}
+
+/// Checks that an aircraft could have reached a reported position
+///
+/// `accepted` is the last accepted report and `outliers` the reports
+///  quarantined since, newest first. A report that is consistent with the
+///  `relocation_reports - 1` newest outliers is accepted as a relocation.
+fn check_plausibility(
+    candidate: &Report,
+    accepted: Option<&Report>,
+    outliers: &[Report],
+    max_speed_mps: f64,
+    relocation_reports: u32,
+) -> Result<(), AircraftError> {
+    if relocation_reports == 0 {
+        return Ok(());
+    }
+
+    match accepted {
+        None if outliers.is_empty() => return Ok(()),
+        Some(accepted) if implied_speed_mps(accepted, candidate) <= max_speed_mps => return Ok(()),
+        _ => (),
+    }
+
+    let mut consistent: u32 = 1;
+    let mut newest = candidate;
+    for outlier in outliers {
+        if consistent >= relocation_reports || implied_speed_mps(outlier, newest) > max_speed_mps {
+            break;
+        }
+
+        consistent += 1;
+        newest = outlier;
+    }
+
+    if consistent >= relocation_reports {
+        postgis_info!(
+            "(check_plausibility) accepting relocation after {} consistent reports.",
+            consistent
+        );
+        return Ok(());
+    }
+
+    Err(AircraftError::Implausible)
+}
 
 /// Possible errors with aircraft requests
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -32,6 +284,24 @@ pub enum AircraftError {
 
     /// DBError error
     DBError,
+
+    /// Invalid resolution
+    Resolution,
+
+    /// Position implies an impossible speed
+    Implausible,
+
+    /// Invalid position accuracy
+    Accuracy,
+
+    /// Position outside of the service area
+    ServiceArea,
+
+    /// Aircraft type change between incompatible categories
+    TypeTransition,
+
+    /// Aircraft belongs to another operator than the operator claim
+    Operator,
 }
 
 impl std::fmt::Display for AircraftError {
@@ -42,6 +312,14 @@ impl std::fmt::Display for AircraftError {
             AircraftError::Identifier => write!(f, "Invalid identifier(s) provided."),
             AircraftError::Client => write!(f, "Could not get backend client."),
             AircraftError::DBError => write!(f, "Unknown backend error."),
+            AircraftError::Resolution => write!(f, "Invalid resolution provided."),
+            AircraftError::Implausible => write!(f, "Implausible position provided."),
+            AircraftError::Accuracy => write!(f, "Invalid position accuracy provided."),
+            AircraftError::ServiceArea => write!(f, "Position outside of the service area."),
+            AircraftError::TypeTransition => {
+                write!(f, "Incompatible aircraft type change provided.")
+            }
+            AircraftError::Operator => write!(f, "Aircraft belongs to another operator."),
         }
     }
 }
@@ -52,6 +330,61 @@ pub(super) fn get_table_name() -> &'static str {
     FULL_NAME
 }
 
+/// Gets the name of the aircraft position history table
+pub(super) fn get_history_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."aircraft_history""#,);
+    FULL_NAME
+}
+
+/// Gets the name of the aircraft status change table
+pub(super) fn get_status_history_table_name() -> &'static str {
+    static FULL_NAME: &str =
+        const_format::formatcp!(r#""{PSQL_SCHEMA}"."aircraft_status_history""#,);
+    FULL_NAME
+}
+
+/// Gets the name of the aircraft type change table
+pub(super) fn get_type_history_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."aircraft_type_history""#,);
+    FULL_NAME
+}
+
+/// Gets the name of the view holding the newest history row of each aircraft
+pub(super) fn get_latest_position_view_name() -> &'static str {
+    static FULL_NAME: &str =
+        const_format::formatcp!(r#""{PSQL_SCHEMA}"."latest_aircraft_position""#,);
+    FULL_NAME
+}
+
+/// Gets the name of the view of the aircraft table with the current
+///  position of each aircraft taken from its newest history row
+pub(super) fn get_position_view_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."aircraft_position""#,);
+    FULL_NAME
+}
+
+/// Gets what to read the current aircraft positions from
+///
+/// The aircraft table holds them in [`PositionWriteMode::Upsert`]. In
+///  [`PositionWriteMode::Append`] it only registers aircraft, the view
+///  with the same columns reads positions from the history instead.
+pub(super) fn position_source() -> &'static str {
+    match position_write_mode() {
+        PositionWriteMode::Upsert => get_table_name(),
+        PositionWriteMode::Append => get_position_view_name(),
+    }
+}
+
+/// Statement registering an aircraft without changing its position, used
+///  instead of the position upsert in [`PositionWriteMode::Append`]
+fn register_aircraft_stmt() -> String {
+    format!(
+        r#"INSERT INTO {table_name} ("identifier") VALUES ($1)
+        ON CONFLICT ("identifier") DO NOTHING;"#,
+        table_name = get_table_name()
+    )
+}
+
 /// Verifies that a identifier is valid
 pub fn check_identifier(identifier: &str) -> Result<(), StringError> {
     super::utils::check_string(identifier, IDENTIFIER_REGEX)
@@ -59,10 +392,16 @@ pub fn check_identifier(identifier: &str) -> Result<(), StringError> {
 
 /// Initializes the PostGIS database for aircraft.
 pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table and enum declarations for aircraft, also applied to
+///  each aircraft shard
+pub(super) fn psql_init_statements() -> Vec<String> {
     // Create Aircraft Table
     let type_enum_name = "aircrafttype";
     let status_enum_name = "opstatus";
-    let statements = vec![
+    vec![
         super::psql_enum_declaration::<AircraftType>(type_enum_name),
         super::psql_enum_declaration::<OperationalStatus>(status_enum_name),
         format!(
@@ -79,17 +418,164 @@ pub async fn psql_init() -> Result<(), PostgisError> {
                 "last_position_update" TIMESTAMPTZ,
                 "last_velocity_update" TIMESTAMPTZ,
                 "simulated" BOOLEAN DEFAULT FALSE,
-                "op_status" {status_enum_name} NOT NULL DEFAULT '{status_enum_default}'
+                "op_status" {status_enum_name} NOT NULL DEFAULT '{status_enum_default}',
+                "operator_id" VARCHAR(255)
             );"#,
             table_name = get_table_name(),
             type_enum_default = AircraftType::Undeclared.to_string(),
             status_enum_default = OperationalStatus::Undeclared.to_string()
         ),
-    ];
-
-    psql_transaction(statements).await
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "operator_id" VARCHAR(255);"#,
+            table_name = get_table_name(),
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "aircraft_operator_id_idx" ON {table_name} ("operator_id");"#,
+            table_name = get_table_name(),
+        ),
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "identifier" VARCHAR(20) NOT NULL,
+                "geom" GEOMETRY(POINTZ, {DEFAULT_SRID}) NOT NULL,
+                "timestamp_network" TIMESTAMPTZ NOT NULL
+            );"#,
+            table_name = get_history_table_name(),
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "aircraft_history_identifier_idx" ON {table_name} ("identifier", "timestamp_network");"#,
+            table_name = get_history_table_name(),
+        ),
+        // Velocities are only known for rows written by combined telemetry
+        format!(
+            r#"ALTER TABLE {table_name}
+                ADD COLUMN IF NOT EXISTS "velocity_horizontal_ground_mps" FLOAT(4),
+                ADD COLUMN IF NOT EXISTS "velocity_horizontal_air_mps" FLOAT(4),
+                ADD COLUMN IF NOT EXISTS "velocity_vertical_mps" FLOAT(4),
+                ADD COLUMN IF NOT EXISTS "track_angle_degrees" FLOAT(4),
+                ADD COLUMN IF NOT EXISTS "timestamp_asset" TIMESTAMPTZ;"#,
+            table_name = get_history_table_name(),
+        ),
+        // Unquantized position, if configured
+        format!(
+            r#"ALTER TABLE {table_name}
+                ADD COLUMN IF NOT EXISTS "geom_raw" GEOMETRY(POINTZ, {DEFAULT_SRID});"#,
+            table_name = get_history_table_name(),
+        ),
+        // Positions quarantined by the plausibility check
+        format!(
+            r#"ALTER TABLE {table_name}
+                ADD COLUMN IF NOT EXISTS "outlier" BOOLEAN NOT NULL DEFAULT FALSE;"#,
+            table_name = get_history_table_name(),
+        ),
+        // Reported accuracy of the current position
+        format!(
+            r#"ALTER TABLE {table_name}
+                ADD COLUMN IF NOT EXISTS "horizontal_accuracy_meters" FLOAT(4),
+                ADD COLUMN IF NOT EXISTS "vertical_accuracy_meters" FLOAT(4);"#,
+            table_name = get_table_name(),
+        ),
+        format!(
+            r#"ALTER TABLE {table_name}
+                ADD COLUMN IF NOT EXISTS "horizontal_accuracy_meters" FLOAT(4),
+                ADD COLUMN IF NOT EXISTS "vertical_accuracy_meters" FLOAT(4);"#,
+            table_name = get_history_table_name(),
+        ),
+        // Current position when the history is the source of truth
+        format!(
+            r#"CREATE OR REPLACE VIEW {view_name} AS
+                SELECT DISTINCT ON ("identifier")
+                    "identifier",
+                    "geom",
+                    "velocity_horizontal_ground_mps",
+                    "velocity_horizontal_air_mps",
+                    "velocity_vertical_mps",
+                    "track_angle_degrees",
+                    "timestamp_network",
+                    "timestamp_asset",
+                    "horizontal_accuracy_meters",
+                    "vertical_accuracy_meters"
+                FROM {table_name}
+                WHERE NOT "outlier"
+                ORDER BY "identifier", "timestamp_network" DESC;"#,
+            view_name = get_latest_position_view_name(),
+            table_name = get_history_table_name(),
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "aircraft_history_latest_idx" ON {table_name} ("identifier", "timestamp_network" DESC);"#,
+            table_name = get_history_table_name(),
+        ),
+        // The aircraft table with the position of the newest history row, in
+        //  the column order of the table
+        format!(
+            r#"CREATE OR REPLACE VIEW {view_name} AS
+                SELECT
+                    "aircraft"."identifier",
+                    "aircraft"."session_id",
+                    "aircraft"."aircraft_type",
+                    COALESCE(
+                        "latest"."velocity_horizontal_ground_mps",
+                        "aircraft"."velocity_horizontal_ground_mps"
+                    ) AS "velocity_horizontal_ground_mps",
+                    COALESCE(
+                        "latest"."velocity_horizontal_air_mps",
+                        "aircraft"."velocity_horizontal_air_mps"
+                    ) AS "velocity_horizontal_air_mps",
+                    COALESCE(
+                        "latest"."velocity_vertical_mps",
+                        "aircraft"."velocity_vertical_mps"
+                    ) AS "velocity_vertical_mps",
+                    COALESCE(
+                        "latest"."track_angle_degrees",
+                        "aircraft"."track_angle_degrees"
+                    ) AS "track_angle_degrees",
+                    "latest"."geom",
+                    "aircraft"."last_identifier_update",
+                    "latest"."timestamp_network" AS "last_position_update",
+                    "aircraft"."last_velocity_update",
+                    "aircraft"."simulated",
+                    "aircraft"."op_status",
+                    "aircraft"."operator_id",
+                    "latest"."horizontal_accuracy_meters",
+                    "latest"."vertical_accuracy_meters"
+                FROM {table_name} AS "aircraft"
+                LEFT JOIN {latest_view_name} AS "latest"
+                    ON "latest"."identifier" = "aircraft"."identifier";"#,
+            view_name = get_position_view_name(),
+            table_name = get_table_name(),
+            latest_view_name = get_latest_position_view_name(),
+        ),
+        // Audit log of operational status changes
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "identifier" VARCHAR(20) NOT NULL,
+                "op_status" {status_enum_name} NOT NULL,
+                "previous_status" {status_enum_name},
+                "timestamp" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );"#,
+            table_name = get_status_history_table_name(),
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "aircraft_status_history_identifier_idx" ON {table_name} ("identifier", "timestamp");"#,
+            table_name = get_status_history_table_name(),
+        ),
+        // Audit log of reclassifications
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "identifier" VARCHAR(20) NOT NULL,
+                "aircraft_type" {type_enum_name} NOT NULL,
+                "previous_type" {type_enum_name} NOT NULL,
+                "timestamp" TIMESTAMPTZ NOT NULL
+            );"#,
+            table_name = get_type_history_table_name(),
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "aircraft_type_history_identifier_idx" ON {table_name} ("identifier", "timestamp");"#,
+            table_name = get_type_history_table_name(),
+        ),
+    ]
 }
 
+// Queued messages carry no dry run flag, they are always committed
 #[async_trait]
 impl Processor<AircraftId> for Consumer {
     async fn process(&mut self, items: Vec<AircraftId>) -> Result<(), ()> {
@@ -97,7 +583,9 @@ impl Processor<AircraftId> for Consumer {
             return Ok(());
         }
 
-        update_aircraft_id(items).await.map_err(|_| ())
+        update_aircraft_id_for(items, false, self.operator_enforcement)
+            .await
+            .map_err(|_| ())
     }
 }
 
@@ -108,7 +596,10 @@ impl Processor<AircraftPosition> for Consumer {
             return Ok(());
         }
 
-        update_aircraft_position(items).await.map_err(|_| ())
+        update_aircraft_position(items, false)
+            .await
+            .map(|_| ())
+            .map_err(|_| ())
     }
 }
 
@@ -119,7 +610,21 @@ impl Processor<AircraftVelocity> for Consumer {
             return Ok(());
         }
 
-        update_aircraft_velocity(items).await.map_err(|_| ())
+        update_aircraft_velocity(items, false).await.map_err(|_| ())
+    }
+}
+
+#[async_trait]
+impl Processor<AircraftTelemetry> for Consumer {
+    async fn process(&mut self, items: Vec<AircraftTelemetry>) -> Result<(), ()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        update_aircraft_telemetry(items, false)
+            .await
+            .map(|_| ())
+            .map_err(|_| ())
     }
 }
 
@@ -129,21 +634,27 @@ fn validate_identification(
     session_id: &Option<String>,
 ) -> Result<(), PostgisError> {
     if let Some(identifier) = caa_identifier {
-        check_identifier(identifier).ok().ok_or_else(|| {
-            postgis_error!("(validate_id_message) invalid identifier: {:?}", identifier);
+        check_identifier(identifier).map_err(|e| {
+            postgis_error!(
+                "(validate_id_message) invalid identifier {:?}: {}",
+                identifier,
+                e
+            );
 
             PostgisError::Aircraft(AircraftError::Identifier)
         })?;
     }
 
     if let Some(session_id) = session_id {
-        super::flight::check_flight_identifier(session_id)
-            .ok()
-            .ok_or_else(|| {
-                postgis_error!("(validate_id_message) invalid session_id: {:?}", session_id);
+        super::flight::check_flight_identifier(session_id).map_err(|e| {
+            postgis_error!(
+                "(validate_id_message) invalid session_id {:?}: {}",
+                session_id,
+                e
+            );
 
-                PostgisError::Aircraft(AircraftError::Identifier)
-            })?;
+            PostgisError::Aircraft(AircraftError::Identifier)
+        })?;
     }
 
     if caa_identifier.is_none() && session_id.is_none() {
@@ -161,6 +672,18 @@ fn validate_identification(
 fn validate_id_message(item: &AircraftId, now: &DateTime<Utc>) -> Result<(), PostgisError> {
     validate_identification(&item.identifier, &item.session_id)?;
 
+    if let Some(operator_id) = &item.operator_id {
+        super::utils::check_label(operator_id).map_err(|e| {
+            postgis_error!(
+                "(validate_id_message) invalid operator_id {}: {}",
+                operator_id,
+                e
+            );
+
+            PostgisError::Aircraft(AircraftError::Identifier)
+        })?;
+    }
+
     if item.timestamp_network > *now {
         postgis_error!(
             "(validate_id_message) could not validate timestamp_network (in future): {}",
@@ -176,10 +699,31 @@ fn validate_id_message(item: &AircraftId, now: &DateTime<Utc>) -> Result<(), Pos
 /// Pulls queued aircraft id messages from Redis Queue
 /// Updates aircraft in the PostGIS database.
 /// Confirms with Redis Queue that item was processed.
-pub async fn update_aircraft_id(aircraft: Vec<AircraftId>) -> Result<(), PostgisError> {
+///
+/// The type of a known aircraft goes through [`resolve_aircraft_type`]:
+///  undeclared types keep the stored type (the timestamp is still
+///  updated) and incompatible changes are skipped, unless `force_type` is
+///  set on the message.
+///
+/// A dry run executes the same statements and rolls them back.
+pub async fn update_aircraft_id(
+    aircraft: Vec<AircraftId>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
+    update_aircraft_id_for(aircraft, dry_run, false).await
+}
+
+/// Updates aircraft identifications as [`update_aircraft_id`] does, with
+///  `operator_enforcement` skipping identifications that would move an
+///  aircraft to another operator (see [`check_operator_change`])
+pub async fn update_aircraft_id_for(
+    aircraft: Vec<AircraftId>,
+    dry_run: bool,
+    operator_enforcement: bool,
+) -> Result<(), PostgisError> {
     postgis_debug!("(update_aircraft_id) entry.");
 
-    let now = Utc::now();
+    let now = crate::clock::now();
     let aircraft: Vec<AircraftId> = aircraft
         .into_iter()
         .filter(|item| validate_id_message(item, &now).is_ok())
@@ -189,11 +733,31 @@ pub async fn update_aircraft_id(aircraft: Vec<AircraftId>) -> Result<(), Postgis
         return Ok(());
     }
 
-    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+    let primary = crate::postgis::get_telemetry_pool();
+    let Some(shards) = super::shard::partition(primary, aircraft, |item| {
+        item.identifier
+            .as_deref()
+            .or(item.session_id.as_deref())
+            .unwrap_or_default()
+    }) else {
         postgis_error!("(update_aircraft_id) could not get psql pool.");
         return Err(PostgisError::Aircraft(AircraftError::Client));
     };
 
+    for (pool, aircraft) in shards {
+        update_aircraft_id_on(pool, aircraft, dry_run, operator_enforcement).await?;
+    }
+
+    Ok(())
+}
+
+/// Updates the identification of aircraft on a single shard
+async fn update_aircraft_id_on(
+    pool: &deadpool_postgres::Pool,
+    aircraft: Vec<AircraftId>,
+    dry_run: bool,
+    operator_enforcement: bool,
+) -> Result<(), PostgisError> {
     let mut client = pool.get().await.map_err(|e| {
         postgis_error!(
             "(update_aircraft_id) could not get client from psql connection pool: {}",
@@ -203,28 +767,63 @@ pub async fn update_aircraft_id(aircraft: Vec<AircraftId>) -> Result<(), Postgis
         PostgisError::Aircraft(AircraftError::Client)
     })?;
 
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!("(update_aircraft_id) could not create transaction: {}", e);
 
         PostgisError::Aircraft(AircraftError::DBError)
     })?;
 
+    let previous_stmt = transaction
+        .prepare_cached(&format!(
+            r#"SELECT "aircraft_type", "operator_id" FROM {table_name} WHERE "identifier" = $1 FOR UPDATE;"#,
+            table_name = get_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_aircraft_id) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    // A changed type of a known aircraft is appended to the type history,
+    //  the "previous" snapshot is taken before the upsert runs
     let stmt = transaction
         .prepare_cached(&format!(
             r#"
-        INSERT INTO {table_name} (
+        WITH "previous" AS (
+            SELECT "aircraft_type" FROM {table_name} WHERE "identifier" = $1
+        ), "upserted" AS (
+            INSERT INTO {table_name} (
+                "identifier",
+                "session_id",
+                "aircraft_type",
+                "last_identifier_update",
+                "operator_id"
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ("identifier") DO UPDATE
+                SET "session_id" = EXCLUDED."session_id",
+                    "aircraft_type" = EXCLUDED."aircraft_type",
+                    "last_identifier_update" = EXCLUDED."last_identifier_update",
+                    "operator_id" = COALESCE(EXCLUDED."operator_id", {table_name}."operator_id")
+            RETURNING "aircraft_type"
+        )
+        INSERT INTO {type_history_table_name} (
             "identifier",
-            "session_id",
             "aircraft_type",
-            "last_identifier_update"
+            "previous_type",
+            "timestamp"
         )
-        VALUES ($1, $2, $3, $4)
-        ON CONFLICT ("identifier") DO UPDATE
-            SET "session_id" = EXCLUDED."session_id",
-                "aircraft_type" = EXCLUDED."aircraft_type",
-                "last_identifier_update" = EXCLUDED."last_identifier_update";
+        SELECT $1, "upserted"."aircraft_type", "previous"."aircraft_type", $4
+        FROM "upserted", "previous"
+        WHERE "upserted"."aircraft_type" <> "previous"."aircraft_type";
         "#,
-            table_name = get_table_name()
+            table_name = get_table_name(),
+            type_history_table_name = get_type_history_table_name()
         ))
         .await
         .map_err(|e| {
@@ -236,14 +835,67 @@ pub async fn update_aircraft_id(aircraft: Vec<AircraftId>) -> Result<(), Postgis
         })?;
 
     for craft in &aircraft {
+        let (previous, previous_operator): (Option<AircraftType>, Option<String>) = transaction
+            .query_opt(&previous_stmt, &[&craft.identifier])
+            .await
+            .and_then(|row| match row {
+                Some(row) => Ok((row.try_get("aircraft_type")?, row.try_get("operator_id")?)),
+                None => Ok((None, None)),
+            })
+            .map_err(|e| {
+                postgis_error!("(update_aircraft_id) could not get aircraft type: {}", e);
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?;
+
+        if operator_enforcement {
+            if let Err(e) =
+                check_operator_change(previous_operator.as_deref(), craft.operator_id.as_deref())
+            {
+                postgis_error!(
+                    "(update_aircraft_id) skipping {:?}, {:?} to {:?}: {}",
+                    craft.identifier,
+                    previous_operator,
+                    craft.operator_id,
+                    e
+                );
+                continue;
+            }
+        }
+
+        let aircraft_type =
+            match resolve_aircraft_type(previous, craft.aircraft_type, craft.force_type) {
+                Ok(aircraft_type) => aircraft_type,
+                Err(e) => {
+                    postgis_error!(
+                        "(update_aircraft_id) skipping {:?}, {:?} to {:?}: {}",
+                        craft.identifier,
+                        previous,
+                        craft.aircraft_type,
+                        e
+                    );
+                    TYPE_TRANSITIONS_REJECTED.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+            };
+
+        if aircraft_type as i32 != craft.aircraft_type as i32 {
+            postgis_warn!(
+                "(update_aircraft_id) ignoring undeclared type for {:?}, keeping {:?}.",
+                craft.identifier,
+                aircraft_type
+            );
+            UNDECLARED_TYPES_IGNORED.fetch_add(1, Ordering::Relaxed);
+        }
+
         transaction
             .execute(
                 &stmt,
                 &[
                     &craft.identifier,
                     &craft.session_id,
-                    &craft.aircraft_type,
+                    &aircraft_type,
                     &craft.timestamp_network,
+                    &craft.operator_id,
                 ],
             )
             .await
@@ -253,9 +905,9 @@ pub async fn update_aircraft_id(aircraft: Vec<AircraftId>) -> Result<(), Postgis
             })?;
     }
 
-    match transaction.commit().await {
+    match super::commit_or_rollback(transaction, dry_run).await {
         Ok(_) => {
-            postgis_debug!("(update_aircraft_id) success.");
+            postgis_debug!("(update_aircraft_id) success, dry run: {dry_run}.");
             Ok(())
         }
         Err(e) => {
@@ -270,7 +922,32 @@ fn validate_position_message(
     item: &AircraftPosition,
     now: &DateTime<Utc>,
 ) -> Result<(), PostgisError> {
-    if item.position.latitude < -90.0 || item.position.latitude > 90.0 {
+    // NaN fails every range comparison
+    if !item.position.latitude.is_finite()
+        || !item.position.longitude.is_finite()
+        || !item.position.altitude_meters.is_finite()
+    {
+        postgis_error!(
+            "(validate_position_message) non-finite position: {:?}",
+            item.position
+        );
+        return Err(PostgisError::Aircraft(AircraftError::Location));
+    }
+
+    let valid_accuracy = |accuracy: Option<f32>| accuracy.iter().all(|a| *a >= 0.0);
+    if !valid_accuracy(item.horizontal_accuracy_meters)
+        || !valid_accuracy(item.vertical_accuracy_meters)
+    {
+        postgis_error!(
+            "(validate_position_message) invalid accuracy for aircraft {}: {:?}, {:?}",
+            item.identifier,
+            item.horizontal_accuracy_meters,
+            item.vertical_accuracy_meters
+        );
+        return Err(PostgisError::Aircraft(AircraftError::Accuracy));
+    }
+
+    if item.position.latitude < LAT_MIN || item.position.latitude > LAT_MAX {
         postgis_error!(
             "(validate_position_message) could not validate latitude: {}",
             item.position.latitude
@@ -278,7 +955,7 @@ fn validate_position_message(
         return Err(PostgisError::Aircraft(AircraftError::Location));
     }
 
-    if item.position.longitude < -180.0 || item.position.longitude > 180.0 {
+    if item.position.longitude < LON_MIN || item.position.longitude > LON_MAX {
         postgis_error!(
             "(validate_position_message) could not validate longitude: {}",
             item.position.longitude
@@ -287,6 +964,16 @@ fn validate_position_message(
         return Err(PostgisError::Aircraft(AircraftError::Location));
     }
 
+    if check_service_area([(item.position.longitude, item.position.latitude)]).is_err() {
+        postgis_error!(
+            "(validate_position_message) position outside of service area for aircraft {}: {:?}",
+            item.identifier,
+            item.position
+        );
+
+        return Err(PostgisError::Aircraft(AircraftError::ServiceArea));
+    }
+
     if let Err(e) = check_identifier(&item.identifier) {
         postgis_error!(
             "(validate_position_message) could not validate identifier: {}",
@@ -308,25 +995,210 @@ fn validate_position_message(
     Ok(())
 }
 
-/// Updates aircraft position in the PostGIS database.
-pub async fn update_aircraft_position(aircraft: Vec<AircraftPosition>) -> Result<(), PostgisError> {
-    postgis_debug!("(update_aircraft_position) entry.");
-
-    let now = Utc::now();
-    let aircraft: Vec<AircraftPosition> = aircraft
-        .into_iter()
-        .filter(|item| validate_position_message(item, &now).is_ok())
-        .collect();
+/// Prepares the statement getting the newest reports of an aircraft, see
+///  [`check_recent_reports`]
+///
+/// The newest reports are enough to find the last accepted position and
+///  the outliers that may make up a relocation.
+async fn prepare_recent_reports_stmt(
+    transaction: &deadpool_postgres::Transaction<'_>,
+) -> Result<tokio_postgres::Statement, PostgisError> {
+    transaction
+        .prepare_cached(&format!(
+            r#"
+        SELECT
+            "history"."geom",
+            "history"."timestamp_network",
+            "history"."outlier",
+            "aircraft"."aircraft_type"
+        FROM {table_name} AS "history"
+        LEFT JOIN {aircraft_table_name} AS "aircraft"
+            ON "aircraft"."identifier" = "history"."identifier"
+        WHERE "history"."identifier" = $1
+        ORDER BY "history"."timestamp_network" DESC
+        LIMIT $2;
+        "#,
+            table_name = get_history_table_name(),
+            aircraft_table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(prepare_recent_reports_stmt) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })
+}
 
-    if aircraft.is_empty() {
-        return Ok(());
-    }
+/// Checks a position against the newest reports of the aircraft
+///
+/// The outer error is a database failure, the inner one the result of
+///  [`check_plausibility`].
+async fn check_recent_reports(
+    transaction: &deadpool_postgres::Transaction<'_>,
+    recent_stmt: &tokio_postgres::Statement,
+    identifier: &str,
+    candidate: &Report,
+) -> Result<Result<(), AircraftError>, PostgisError> {
+    let relocation_reports = relocation_reports();
+    let rows = transaction
+        .query(recent_stmt, &[&identifier, &(relocation_reports as i64)])
+        .await
+        .map_err(|e| {
+            postgis_error!("(check_recent_reports) could not get recent reports: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
 
-    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
-        postgis_error!("(update_aircraft_position) could not get psql pool.");
-        return Err(PostgisError::Aircraft(AircraftError::Client));
-    };
+    let mut aircraft_type = AircraftType::Undeclared;
+    let mut accepted: Option<Report> = None;
+    let mut outliers: Vec<Report> = vec![];
+    for row in rows {
+        let parse = || -> Result<(Report, bool, Option<AircraftType>), tokio_postgres::Error> {
+            Ok((
+                Report {
+                    geom: row.try_get("geom")?,
+                    timestamp: row.try_get("timestamp_network")?,
+                },
+                row.try_get("outlier")?,
+                row.try_get("aircraft_type")?,
+            ))
+        };
 
+        let (report, outlier, stored_type) = parse().map_err(|e| {
+            postgis_error!(
+                "(check_recent_reports) could not parse recent report: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+        aircraft_type = stored_type.unwrap_or(aircraft_type);
+        if !outlier {
+            accepted = Some(report);
+            break;
+        }
+
+        outliers.push(report);
+    }
+
+    Ok(check_plausibility(
+        candidate,
+        accepted.as_ref(),
+        &outliers,
+        max_plausible_speed_mps(aircraft_type),
+        relocation_reports,
+    ))
+}
+
+/// Starts the ingest guard: aircraft positions and telemetry are queued,
+///  latest wins per aircraft, and written by `writers` tasks each
+///
+/// At most `capacity` aircraft are pending in each queue, updates of other
+///  aircraft are shed until the writers catch up.
+pub fn start_ingest_writers(writers: usize, capacity: usize) -> Result<(), ()> {
+    postgis_info!("(start_ingest_writers) starting {writers} writers, capacity {capacity}.");
+    if POSITION_QUEUE.set(IngestQueue::new(capacity)).is_err()
+        || TELEMETRY_QUEUE.set(IngestQueue::new(capacity)).is_err()
+    {
+        postgis_error!("(start_ingest_writers) ingest writers already started.");
+        return Err(());
+    }
+
+    let (Some(positions), Some(telemetry)) = (POSITION_QUEUE.get(), TELEMETRY_QUEUE.get()) else {
+        return Err(());
+    };
+
+    for _ in 0..writers {
+        tokio::spawn(ingest::write_queued(
+            "update_aircraft_position",
+            positions,
+            |items| write_aircraft_position(items, false),
+        ));
+        tokio::spawn(ingest::write_queued(
+            "update_aircraft_telemetry",
+            telemetry,
+            |items| write_aircraft_telemetry(items, false),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Time the oldest update pending in the ingest queues has been waiting,
+///  zero if none is or the ingest guard isn't started
+pub fn ingest_writer_lag() -> std::time::Duration {
+    let positions = POSITION_QUEUE.get().map(|queue| queue.oldest_age());
+    let telemetry = TELEMETRY_QUEUE.get().map(|queue| queue.oldest_age());
+    positions.max(telemetry).unwrap_or_default()
+}
+
+/// Updates aircraft position in the PostGIS database.
+///
+/// Positions the aircraft couldn't have reached since its last accepted
+///  position are only kept in the history, flagged as outliers (see
+///  [`check_plausibility`]).
+///
+/// If the ingest guard is started valid positions are queued instead, see
+///  [`start_ingest_writers`], and this returns without waiting for the
+///  database. Dry runs are never queued, they execute the same statements
+///  and roll them back.
+pub async fn update_aircraft_position(
+    aircraft: Vec<AircraftPosition>,
+    dry_run: bool,
+) -> Result<IngestOutcome, PostgisError> {
+    postgis_debug!("(update_aircraft_position) entry.");
+
+    let now = crate::clock::now();
+    let aircraft: Vec<AircraftPosition> = aircraft
+        .into_iter()
+        .filter(|item| validate_position_message(item, &now).is_ok())
+        .collect();
+
+    if aircraft.is_empty() {
+        return Ok(IngestOutcome::default());
+    }
+
+    if let (false, Some(queue)) = (dry_run, POSITION_QUEUE.get()) {
+        let outcome = queue.push_all("update_aircraft_position", aircraft, |item| {
+            item.identifier.clone()
+        });
+        return Ok(outcome);
+    }
+
+    let accepted = aircraft.len() as u32;
+    write_aircraft_position(aircraft, dry_run).await?;
+    Ok(IngestOutcome {
+        accepted,
+        ..Default::default()
+    })
+}
+
+/// Writes validated aircraft positions to their shards
+async fn write_aircraft_position(
+    aircraft: Vec<AircraftPosition>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
+    let primary = crate::postgis::get_telemetry_pool();
+    let Some(shards) = super::shard::partition(primary, aircraft, |item| item.identifier.as_str())
+    else {
+        postgis_error!("(write_aircraft_position) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
+
+    for (pool, aircraft) in shards {
+        update_aircraft_position_on(pool, aircraft, dry_run).await?;
+    }
+
+    Ok(())
+}
+
+/// Updates the position of aircraft on a single shard
+async fn update_aircraft_position_on(
+    pool: &deadpool_postgres::Pool,
+    aircraft: Vec<AircraftPosition>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
     let mut client = pool.get().await.map_err(|e| {
         postgis_error!(
             "(update_aircraft_position) could not get client from psql connection pool: {}",
@@ -335,6 +1207,8 @@ pub async fn update_aircraft_position(aircraft: Vec<AircraftPosition>) -> Result
         PostgisError::Aircraft(AircraftError::Client)
     })?;
 
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!(
             "(update_aircraft_position) could not create transaction: {}",
@@ -343,20 +1217,52 @@ pub async fn update_aircraft_position(aircraft: Vec<AircraftPosition>) -> Result
         PostgisError::Aircraft(AircraftError::DBError)
     })?;
 
-    let stmt = transaction
-        .prepare_cached(&format!(
+    let mode = position_write_mode();
+    let stmt = match mode {
+        PositionWriteMode::Upsert => format!(
             r#"
         INSERT INTO {table_name} (
             "identifier",
             "geom",
-            "last_position_update"
+            "last_position_update",
+            "horizontal_accuracy_meters",
+            "vertical_accuracy_meters"
         )
-        VALUES ($1, $2, $3)
+        VALUES ($1, $2, $3, $4, $5)
         ON CONFLICT ("identifier") DO UPDATE
             SET "geom" = EXCLUDED."geom",
-                "last_position_update" = EXCLUDED."last_position_update";
+                "last_position_update" = EXCLUDED."last_position_update",
+                "horizontal_accuracy_meters" = EXCLUDED."horizontal_accuracy_meters",
+                "vertical_accuracy_meters" = EXCLUDED."vertical_accuracy_meters";
         "#,
             table_name = get_table_name()
+        ),
+        PositionWriteMode::Append => register_aircraft_stmt(),
+    };
+
+    let stmt = transaction.prepare_cached(&stmt).await.map_err(|e| {
+        postgis_error!(
+            "(update_aircraft_position) could not prepare cached statement: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::DBError)
+    })?;
+
+    let history_stmt = transaction
+        .prepare_cached(&format!(
+            r#"
+        INSERT INTO {table_name} (
+            "identifier",
+            "geom",
+            "timestamp_network",
+            "geom_raw",
+            "outlier",
+            "horizontal_accuracy_meters",
+            "vertical_accuracy_meters"
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+            table_name = get_history_table_name()
         ))
         .await
         .map_err(|e| {
@@ -367,6 +1273,11 @@ pub async fn update_aircraft_position(aircraft: Vec<AircraftPosition>) -> Result
             PostgisError::Aircraft(AircraftError::DBError)
         })?;
 
+    let relocation_reports = relocation_reports();
+    let recent_stmt = prepare_recent_reports_stmt(&transaction).await?;
+
+    // Published to the position streams once committed
+    let mut accepted: Vec<&AircraftPosition> = vec![];
     for craft in &aircraft {
         let Ok(geom) = PointZ::try_from(craft.position) else {
             postgis_error!(
@@ -378,8 +1289,70 @@ pub async fn update_aircraft_position(aircraft: Vec<AircraftPosition>) -> Result
             continue;
         };
 
+        let candidate = Report {
+            geom,
+            timestamp: craft.timestamp_network,
+        };
+
+        let outlier = relocation_reports > 0
+            && match check_recent_reports(&transaction, &recent_stmt, &craft.identifier, &candidate)
+                .await?
+            {
+                Ok(()) => false,
+                Err(e) => {
+                    postgis_warn!(
+                        "(update_aircraft_position) quarantining position of aircraft {}: {}",
+                        craft.identifier,
+                        e
+                    );
+                    IMPLAUSIBLE_POSITIONS.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+            };
+
+        // Outliers are only kept in the history
+        if !outlier {
+            match mode {
+                PositionWriteMode::Upsert => {
+                    transaction
+                        .execute(
+                            &stmt,
+                            &[
+                                &craft.identifier,
+                                &geom,
+                                &craft.timestamp_network,
+                                &craft.horizontal_accuracy_meters,
+                                &craft.vertical_accuracy_meters,
+                            ],
+                        )
+                        .await
+                }
+                PositionWriteMode::Append => transaction.execute(&stmt, &[&craft.identifier]).await,
+            }
+            .map_err(|e| {
+                postgis_error!(
+                    "(update_aircraft_position) could not execute transaction: {}",
+                    e
+                );
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?;
+
+            accepted.push(craft);
+        }
+
         transaction
-            .execute(&stmt, &[&craft.identifier, &geom, &craft.timestamp_network])
+            .execute(
+                &history_stmt,
+                &[
+                    &craft.identifier,
+                    &geom,
+                    &craft.timestamp_network,
+                    &raw_history_position(&craft.position),
+                    &outlier,
+                    &craft.horizontal_accuracy_meters,
+                    &craft.vertical_accuracy_meters,
+                ],
+            )
             .await
             .map_err(|e| {
                 postgis_error!(
@@ -390,9 +1363,17 @@ pub async fn update_aircraft_position(aircraft: Vec<AircraftPosition>) -> Result
             })?;
     }
 
-    match transaction.commit().await {
+    match super::commit_or_rollback(transaction, dry_run).await {
+        Ok(_) if dry_run => {
+            postgis_debug!("(update_aircraft_position) success, dry run.");
+            Ok(())
+        }
         Ok(_) => {
             postgis_debug!("(update_aircraft_position) success.");
+            for craft in accepted {
+                super::subscription::publish(craft);
+            }
+
             Ok(())
         }
         Err(e) => {
@@ -432,10 +1413,15 @@ fn validate_velocity_message(
 }
 
 /// Updates aircraft velocity in the PostGIS database.
-pub async fn update_aircraft_velocity(aircraft: Vec<AircraftVelocity>) -> Result<(), PostgisError> {
+///
+/// A dry run executes the same statements and rolls them back.
+pub async fn update_aircraft_velocity(
+    aircraft: Vec<AircraftVelocity>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
     postgis_debug!("(update_aircraft_velocity) entry.");
 
-    let now = Utc::now();
+    let now = crate::clock::now();
     let aircraft: Vec<AircraftVelocity> = aircraft
         .into_iter()
         .filter(|item| validate_velocity_message(item, &now).is_ok())
@@ -445,11 +1431,26 @@ pub async fn update_aircraft_velocity(aircraft: Vec<AircraftVelocity>) -> Result
         return Ok(());
     }
 
-    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+    let primary = crate::postgis::get_telemetry_pool();
+    let Some(shards) = super::shard::partition(primary, aircraft, |item| item.identifier.as_str())
+    else {
         postgis_error!("(update_aircraft_velocity) could not get psql pool.");
         return Err(PostgisError::Aircraft(AircraftError::Client));
     };
 
+    for (pool, aircraft) in shards {
+        update_aircraft_velocity_on(pool, aircraft, dry_run).await?;
+    }
+
+    Ok(())
+}
+
+/// Updates the velocity of aircraft on a single shard
+async fn update_aircraft_velocity_on(
+    pool: &deadpool_postgres::Pool,
+    aircraft: Vec<AircraftVelocity>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
     let mut client = pool.get().await.map_err(|e| {
         postgis_error!(
             "(update_aircraft_velocity) could not get client from psql connection pool: {}",
@@ -458,6 +1459,8 @@ pub async fn update_aircraft_velocity(aircraft: Vec<AircraftVelocity>) -> Result
         PostgisError::Aircraft(AircraftError::Client)
     })?;
 
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!(
             "(update_aircraft_velocity) could not create transaction: {}",
@@ -515,9 +1518,9 @@ pub async fn update_aircraft_velocity(aircraft: Vec<AircraftVelocity>) -> Result
             })?;
     }
 
-    match transaction.commit().await {
+    match super::commit_or_rollback(transaction, dry_run).await {
         Ok(_) => {
-            postgis_debug!("(update_aircraft_velocity) success.");
+            postgis_debug!("(update_aircraft_velocity) success, dry run: {dry_run}.");
             Ok(())
         }
         Err(e) => {
@@ -530,168 +1533,1522 @@ pub async fn update_aircraft_velocity(aircraft: Vec<AircraftVelocity>) -> Result
     }
 }
 
-/// Gets the geometry of an aircraft given its identifier.
-pub async fn get_aircraft_pointz(identifier: &str) -> Result<PointZ, PostgisError> {
-    let stmt = format!(
-        r#"SELECT "geom" FROM {table_name} WHERE "identifier" = $1;"#,
-        table_name = get_table_name()
-    );
+/// Validates the provided aircraft telemetry
+///
+/// Position and velocity are validated together; if either is invalid
+///  the whole item is rejected.
+pub(super) fn validate_telemetry_message(
+    item: &AircraftTelemetry,
+    now: &DateTime<Utc>,
+) -> Result<(), PostgisError> {
+    let position = AircraftPosition {
+        identifier: item.identifier.clone(),
+        position: item.position,
+        timestamp_network: item.timestamp_network,
+        timestamp_asset: item.timestamp_asset,
+        horizontal_accuracy_meters: None,
+        vertical_accuracy_meters: None,
+    };
 
-    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
-        postgis_error!("(get_aircraft_pointz) could not get psql pool.");
-        return Err(PostgisError::Aircraft(AircraftError::Client));
+    validate_position_message(&position, now)?;
+
+    let velocity = AircraftVelocity {
+        identifier: item.identifier.clone(),
+        velocity_horizontal_ground_mps: item.velocity_horizontal_ground_mps,
+        velocity_horizontal_air_mps: item.velocity_horizontal_air_mps,
+        velocity_vertical_mps: item.velocity_vertical_mps,
+        track_angle_degrees: item.track_angle_degrees,
+        timestamp_network: item.timestamp_network,
+        timestamp_asset: item.timestamp_asset,
     };
 
-    let client = pool.get().await.map_err(|e| {
-        postgis_error!(
-            "(get_aircraft_pointz) could not get client from psql connection pool: {}",
-            e
-        );
-        PostgisError::Aircraft(AircraftError::Client)
-    })?;
+    validate_velocity_message(&velocity, now)?;
 
-    client
-        .query_one(&stmt, &[&identifier])
-        .await
-        .map_err(|e| {
-            postgis_error!("(get_aircraft_pointz) could not prepare cached statement: {}", e);
-            PostgisError::Aircraft(AircraftError::DBError)
-        })?
-        .try_get::<_, PointZ>("geom")
-        .map_err(|e| {
-            postgis_error!("(get_aircraft_pointz) zero or more than one records found for aircraft '{identifier}': {}", e);
-            PostgisError::Aircraft(AircraftError::DBError)
-        })
-}
+    let finite = [
+        item.velocity_horizontal_ground_mps,
+        item.velocity_vertical_mps,
+        item.track_angle_degrees,
+    ]
+    .iter()
+    .chain(item.velocity_horizontal_air_mps.iter())
+    .all(|v| v.is_finite());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::Position;
-    use chrono::Duration;
+    if !finite || !item.position.altitude_meters.is_finite() {
+        postgis_error!(
+            "(validate_telemetry_message) non-finite telemetry for aircraft {}.",
+            item.identifier
+        );
 
-    #[tokio::test]
-    async fn ut_client_failure() {
-        crate::get_log_handle().await;
-        ut_info!("(ut_client_failure) start");
+        return Err(PostgisError::Aircraft(AircraftError::Location));
+    }
 
-        let nodes = vec![("aircraft", 52.3745905, 4.9160036)];
-        let aircraft: Vec<AircraftPosition> = nodes
-            .iter()
-            .map(|(label, latitude, longitude)| AircraftPosition {
-                identifier: label.to_string(),
-                position: Position {
-                    latitude: *latitude,
-                    longitude: *longitude,
-                    altitude_meters: 100.0,
-                },
-                timestamp_network: Utc::now(),
-                timestamp_asset: None,
-            })
-            .collect();
+    Ok(())
+}
 
-        let result = update_aircraft_position(aircraft).await.unwrap_err();
-        assert_eq!(result, PostgisError::Aircraft(AircraftError::Client));
+/// A telemetry snapshot from the aircraft history
+///
+/// Velocities are `None` for rows written by position-only updates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySnapshot {
+    /// The aircraft identifier
+    pub identifier: String,
 
-        ut_info!("(ut_client_failure) success");
-    }
+    /// The position of the aircraft
+    pub geom: PointZ,
 
-    #[tokio::test]
-    async fn ut_aircraft_to_gis_invalid_label() {
-        crate::get_log_handle().await;
-        ut_info!("(ut_aircraft_position_to_gis_invalid_label) start");
+    /// The velocity relative to ground in meters per second
+    pub velocity_horizontal_ground_mps: Option<f32>,
 
-        for label in &[
-            "NULL",
-            "Aircraft;",
-            "'Aircraft'",
-            "Aircraft \'",
-            &"X".repeat(1000),
-        ] {
-            let position = AircraftPosition {
-                identifier: label.to_string(),
-                position: Position {
-                    latitude: 0.0,
-                    longitude: 0.0,
-                    altitude_meters: 100.0,
-                },
-                timestamp_network: Utc::now(),
-                timestamp_asset: None,
-            };
+    /// The velocity relative to the air in meters per second
+    pub velocity_horizontal_air_mps: Option<f32>,
 
-            let velocity = AircraftVelocity {
-                identifier: label.to_string(),
-                timestamp_network: Utc::now(),
-                velocity_horizontal_ground_mps: 0.0,
-                velocity_horizontal_air_mps: None,
-                velocity_vertical_mps: 0.0,
-                track_angle_degrees: 0.0,
-                timestamp_asset: None,
-            };
+    /// The vertical velocity in meters per second
+    pub velocity_vertical_mps: Option<f32>,
 
-            let id = AircraftId {
-                identifier: Some(label.to_string()),
-                session_id: None,
-                timestamp_network: Utc::now(),
-                aircraft_type: AircraftType::Rotorcraft,
-                timestamp_asset: None,
-            };
+    /// The angle of the velocity vector with respect to true north in degrees
+    pub track_angle_degrees: Option<f32>,
 
-            let result = validate_position_message(&position, &Utc::now()).unwrap_err();
-            assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+    /// The network timestamp of the telemetry
+    pub timestamp_network: DateTime<Utc>,
 
-            let result = validate_velocity_message(&velocity, &Utc::now()).unwrap_err();
-            assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+    /// The timestamp reported by the asset
+    pub timestamp_asset: Option<DateTime<Utc>>,
+}
 
-            let result = validate_id_message(&id, &Utc::now()).unwrap_err();
-            assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+impl TelemetrySnapshot {
+    /// Creates the history snapshot of a combined telemetry update
+    fn from_telemetry(item: &AircraftTelemetry, geom: PointZ) -> Self {
+        TelemetrySnapshot {
+            identifier: item.identifier.clone(),
+            geom,
+            velocity_horizontal_ground_mps: Some(item.velocity_horizontal_ground_mps),
+            velocity_horizontal_air_mps: item.velocity_horizontal_air_mps,
+            velocity_vertical_mps: Some(item.velocity_vertical_mps),
+            track_angle_degrees: Some(item.track_angle_degrees),
+            timestamp_network: item.timestamp_network,
+            timestamp_asset: item.timestamp_asset,
         }
-
-        ut_info!("(ut_aircraft_position_to_gis_invalid_label) success");
     }
+}
 
-    #[tokio::test]
-    async fn ut_aircraft_id_no_identifier() {
-        crate::get_log_handle().await;
-        ut_info!("(ut_aircraft_id_no_identifier) start");
-
-        let id = AircraftId {
-            identifier: None,
-            session_id: None,
-            timestamp_network: Utc::now(),
-            aircraft_type: AircraftType::Rotorcraft,
-            timestamp_asset: None,
-        };
+impl TryFrom<tokio_postgres::Row> for TelemetrySnapshot {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(TelemetrySnapshot {
+            identifier: row.try_get("identifier")?,
+            geom: row.try_get("geom")?,
+            velocity_horizontal_ground_mps: row.try_get("velocity_horizontal_ground_mps")?,
+            velocity_horizontal_air_mps: row.try_get("velocity_horizontal_air_mps")?,
+            velocity_vertical_mps: row.try_get("velocity_vertical_mps")?,
+            track_angle_degrees: row.try_get("track_angle_degrees")?,
+            timestamp_network: row.try_get("timestamp_network")?,
+            timestamp_asset: row.try_get("timestamp_asset")?,
+        })
+    }
+}
 
-        let result = validate_id_message(&id, &Utc::now()).unwrap_err();
-        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+/// Updates aircraft position and velocity in the PostGIS database
+///  with a single statement per aircraft.
+///
+/// Each aircraft gets one history row holding the full snapshot.
+/// Implausible positions are only kept in the history, flagged as outliers,
+///  as in [`update_aircraft_position`].
+///
+/// If the ingest guard is started valid telemetry is queued instead, see
+///  [`start_ingest_writers`], and this returns without waiting for the
+///  database. Dry runs are never queued, they execute the same statements
+///  and roll them back.
+pub async fn update_aircraft_telemetry(
+    aircraft: Vec<AircraftTelemetry>,
+    dry_run: bool,
+) -> Result<IngestOutcome, PostgisError> {
+    postgis_debug!("(update_aircraft_telemetry) entry.");
+
+    let now = crate::clock::now();
+    let aircraft: Vec<AircraftTelemetry> = aircraft
+        .into_iter()
+        .filter(|item| validate_telemetry_message(item, &now).is_ok())
+        .collect();
 
-        ut_info!("(ut_aircraft_id_no_identifier) success");
+    if aircraft.is_empty() {
+        return Ok(IngestOutcome::default());
     }
 
-    #[tokio::test]
-    async fn ut_aircraft_position_to_gis_invalid_location() {
-        crate::get_log_handle().await;
-        ut_info!("(ut_aircraft_position_to_gis_invalid_location) start");
+    if let (false, Some(queue)) = (dry_run, TELEMETRY_QUEUE.get()) {
+        let outcome = queue.push_all("update_aircraft_telemetry", aircraft, |item| {
+            item.identifier.clone()
+        });
+        return Ok(outcome);
+    }
 
-        let coords = vec![(-90.1, 0.0), (90.1, 0.0), (0.0, -180.1), (0.0, 180.1)];
-        for coord in coords {
-            let aircraft = AircraftPosition {
-                position: Position {
-                    latitude: coord.0,
-                    longitude: coord.1,
-                    altitude_meters: 100.0,
-                },
-                identifier: "Aircraft".to_string(),
-                timestamp_network: Utc::now(),
-                timestamp_asset: None,
-            };
+    let accepted = aircraft.len() as u32;
+    write_aircraft_telemetry(aircraft, dry_run).await?;
+    Ok(IngestOutcome {
+        accepted,
+        ..Default::default()
+    })
+}
 
-            let result = validate_position_message(&aircraft, &Utc::now()).unwrap_err();
-            assert_eq!(result, PostgisError::Aircraft(AircraftError::Location));
-        }
+/// Writes validated aircraft telemetry to their shards
+async fn write_aircraft_telemetry(
+    aircraft: Vec<AircraftTelemetry>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
+    let primary = crate::postgis::get_telemetry_pool();
+    let Some(shards) = super::shard::partition(primary, aircraft, |item| item.identifier.as_str())
+    else {
+        postgis_error!("(write_aircraft_telemetry) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
 
-        ut_info!("(ut_aircraft_position_to_gis_invalid_location) success");
+    for (pool, aircraft) in shards {
+        super::pool::retry_after_failover("update_aircraft_telemetry", || {
+            update_aircraft_telemetry_on(pool, aircraft.clone(), dry_run)
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Updates the telemetry of aircraft on a single shard
+pub(super) async fn update_aircraft_telemetry_on(
+    pool: &deadpool_postgres::Pool,
+    aircraft: Vec<AircraftTelemetry>,
+    dry_run: bool,
+) -> Result<(), PostgisError> {
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(update_aircraft_telemetry) could not get client from psql connection pool: {}",
+            e
+        );
+        super::classify_pool_error(&e);
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!(
+            "(update_aircraft_telemetry) could not create transaction: {}",
+            e
+        );
+        super::classify_db_error(&e);
+        PostgisError::Aircraft(AircraftError::DBError)
+    })?;
+
+    let mode = position_write_mode();
+    let stmt = match mode {
+        PositionWriteMode::Upsert => format!(
+            r#"
+        INSERT INTO {table_name} (
+            "identifier",
+            "geom",
+            "velocity_horizontal_ground_mps",
+            "velocity_horizontal_air_mps",
+            "velocity_vertical_mps",
+            "track_angle_degrees",
+            "last_position_update",
+            "last_velocity_update"
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $7
+        ) ON CONFLICT ("identifier") DO UPDATE
+            SET "geom" = EXCLUDED."geom",
+                "velocity_horizontal_ground_mps" = EXCLUDED."velocity_horizontal_ground_mps",
+                "velocity_horizontal_air_mps" = EXCLUDED."velocity_horizontal_air_mps",
+                "velocity_vertical_mps" = EXCLUDED."velocity_vertical_mps",
+                "track_angle_degrees" = EXCLUDED."track_angle_degrees",
+                "last_position_update" = EXCLUDED."last_position_update",
+                "last_velocity_update" = EXCLUDED."last_velocity_update",
+                -- telemetry doesn't report accuracy, don't keep the previous one
+                "horizontal_accuracy_meters" = NULL,
+                "vertical_accuracy_meters" = NULL;"#,
+            table_name = get_table_name()
+        ),
+        PositionWriteMode::Append => register_aircraft_stmt(),
+    };
+
+    let stmt = transaction.prepare_cached(&stmt).await.map_err(|e| {
+        postgis_error!(
+            "(update_aircraft_telemetry) could not prepare cached statement: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::DBError)
+    })?;
+
+    let history_stmt = transaction
+        .prepare_cached(&format!(
+            r#"
+        INSERT INTO {table_name} (
+            "identifier",
+            "geom",
+            "velocity_horizontal_ground_mps",
+            "velocity_horizontal_air_mps",
+            "velocity_vertical_mps",
+            "track_angle_degrees",
+            "timestamp_network",
+            "timestamp_asset",
+            "geom_raw",
+            "outlier"
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
+        "#,
+            table_name = get_history_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_aircraft_telemetry) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    let relocation_reports = relocation_reports();
+    let recent_stmt = prepare_recent_reports_stmt(&transaction).await?;
+
+    // Published to the position streams once committed
+    let mut accepted: Vec<AircraftPosition> = vec![];
+    for craft in &aircraft {
+        let Ok(geom) = PointZ::try_from(craft.position) else {
+            postgis_error!(
+                "(update_aircraft_telemetry) could not convert position to PointZ for aircraft {:?}: {:?}",
+                craft.identifier,
+                craft.position
+            );
+
+            continue;
+        };
+
+        let candidate = Report {
+            geom,
+            timestamp: craft.timestamp_network,
+        };
+
+        let outlier = relocation_reports > 0
+            && match check_recent_reports(&transaction, &recent_stmt, &craft.identifier, &candidate)
+                .await?
+            {
+                Ok(()) => false,
+                Err(e) => {
+                    postgis_warn!(
+                        "(update_aircraft_telemetry) quarantining telemetry of aircraft {}: {}",
+                        craft.identifier,
+                        e
+                    );
+                    IMPLAUSIBLE_POSITIONS.fetch_add(1, Ordering::Relaxed);
+                    true
+                }
+            };
+
+        // Outliers are only kept in the history
+        if !outlier {
+            match mode {
+                PositionWriteMode::Upsert => {
+                    transaction
+                        .execute(
+                            &stmt,
+                            &[
+                                &craft.identifier,
+                                &geom,
+                                &craft.velocity_horizontal_ground_mps,
+                                &craft.velocity_horizontal_air_mps,
+                                &craft.velocity_vertical_mps,
+                                &craft.track_angle_degrees,
+                                &craft.timestamp_network,
+                            ],
+                        )
+                        .await
+                }
+                PositionWriteMode::Append => transaction.execute(&stmt, &[&craft.identifier]).await,
+            }
+            .map_err(|e| {
+                postgis_error!(
+                    "(update_aircraft_telemetry) could not execute transaction: {}",
+                    e
+                );
+                super::classify_db_error(&e);
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?;
+
+            accepted.push(AircraftPosition {
+                identifier: craft.identifier.clone(),
+                position: craft.position,
+                timestamp_network: craft.timestamp_network,
+                timestamp_asset: craft.timestamp_asset,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            });
+        }
+
+        let snapshot = TelemetrySnapshot::from_telemetry(craft, geom);
+        transaction
+            .execute(
+                &history_stmt,
+                &[
+                    &snapshot.identifier,
+                    &snapshot.geom,
+                    &snapshot.velocity_horizontal_ground_mps,
+                    &snapshot.velocity_horizontal_air_mps,
+                    &snapshot.velocity_vertical_mps,
+                    &snapshot.track_angle_degrees,
+                    &snapshot.timestamp_network,
+                    &snapshot.timestamp_asset,
+                    &raw_history_position(&craft.position),
+                    &outlier,
+                ],
+            )
+            .await
+            .map_err(|e| {
+                postgis_error!(
+                    "(update_aircraft_telemetry) could not execute transaction: {}",
+                    e
+                );
+                super::classify_db_error(&e);
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?;
+    }
+
+    match super::commit_or_rollback(transaction, dry_run).await {
+        Ok(_) if dry_run => {
+            postgis_debug!("(update_aircraft_telemetry) success, dry run.");
+            Ok(())
+        }
+        Ok(_) => {
+            postgis_debug!("(update_aircraft_telemetry) success.");
+            for craft in &accepted {
+                super::subscription::publish(craft);
+            }
+
+            Ok(())
+        }
+        Err(e) => {
+            postgis_error!(
+                "(update_aircraft_telemetry) could not commit transaction: {}",
+                e
+            );
+            super::classify_db_error(&e);
+            Err(PostgisError::Aircraft(AircraftError::DBError))
+        }
+    }
+}
+
+/// Checks that aircraft writes carrying an operator claim only touch the
+///  claiming operator's aircraft
+///
+/// Aircraft must be identified as the operator's (see
+///  [`update_aircraft_id`]) before its claim can write them, an unknown
+///  aircraft or one without an operator isn't anyone's.
+pub async fn check_aircraft_operator(
+    identifiers: Vec<String>,
+    operator_claim: &str,
+) -> Result<(), PostgisError> {
+    postgis_debug!("(check_aircraft_operator) entry, operator: '{operator_claim}'.");
+    let stmt = format!(
+        r#"SELECT "checked"."identifier"
+        FROM UNNEST($1::VARCHAR[]) AS "checked"("identifier")
+        LEFT JOIN {table_name} AS "aircraft" ON "aircraft"."identifier" = "checked"."identifier"
+        WHERE "aircraft"."operator_id" IS DISTINCT FROM $2::VARCHAR;"#,
+        table_name = get_table_name()
+    );
+
+    let primary = crate::postgis::get_telemetry_pool();
+    let Some(shards) =
+        super::shard::partition(primary, identifiers, |identifier| identifier.as_str())
+    else {
+        postgis_error!("(check_aircraft_operator) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
+
+    for (pool, identifiers) in shards {
+        let client = pool.get().await.map_err(|e| {
+            postgis_error!(
+                "(check_aircraft_operator) could not get client from psql connection pool: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::Client)
+        })?;
+
+        let foreign = client
+            .query(&stmt, &[&identifiers, &operator_claim])
+            .await
+            .map_err(|e| {
+                postgis_error!("(check_aircraft_operator) could not execute query: {}", e);
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?;
+
+        if let Some(row) = foreign.first() {
+            let identifier: String = row.try_get("identifier").unwrap_or_default();
+            postgis_error!(
+                "(check_aircraft_operator) aircraft '{identifier}' doesn't belong to operator {operator_claim}."
+            );
+            return Err(PostgisError::Aircraft(AircraftError::Operator));
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the operator of an aircraft, `None` for an unknown aircraft or one
+///  without an operator
+pub async fn get_aircraft_operator(identifier: &str) -> Result<Option<String>, PostgisError> {
+    let stmt = format!(
+        r#"SELECT "operator_id" FROM {table_name} WHERE "identifier" = $1;"#,
+        table_name = get_table_name()
+    );
+
+    let primary = crate::postgis::DEADPOOL_POSTGIS.get();
+    let Some(pool) = super::shard::get_shard_pool(primary, identifier) else {
+        postgis_error!("(get_aircraft_operator) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_aircraft_operator) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let operator_id: Option<Option<String>> = client
+        .query_opt(&stmt, &[&identifier])
+        .await
+        .and_then(|row| row.map(|row| row.try_get("operator_id")).transpose())
+        .map_err(|e| {
+            postgis_error!("(get_aircraft_operator) could not execute query: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    Ok(operator_id.flatten())
+}
+
+/// Gets the geometry of an aircraft given its identifier.
+pub async fn get_aircraft_pointz(identifier: &str) -> Result<PointZ, PostgisError> {
+    let stmt = format!(
+        r#"SELECT "geom" FROM {table_name} WHERE "identifier" = $1;"#,
+        table_name = position_source()
+    );
+
+    let primary = crate::postgis::DEADPOOL_POSTGIS.get();
+    let Some(pool) = super::shard::get_shard_pool(primary, identifier) else {
+        postgis_error!("(get_aircraft_pointz) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_aircraft_pointz) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    client
+        .query_one(&stmt, &[&identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_aircraft_pointz) could not prepare cached statement: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?
+        .try_get::<_, PointZ>("geom")
+        .map_err(|e| {
+            postgis_error!("(get_aircraft_pointz) zero or more than one records found for aircraft '{identifier}': {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })
+}
+
+/// Gets the network time of the newest stored aircraft position, `None` if
+///  no position was ever stored
+///
+/// Every aircraft shard is checked.
+pub async fn get_latest_position_update() -> Result<Option<DateTime<Utc>>, PostgisError> {
+    let stmt = match position_write_mode() {
+        PositionWriteMode::Upsert => format!(
+            r#"SELECT MAX("last_position_update") AS "latest" FROM {};"#,
+            get_table_name()
+        ),
+        PositionWriteMode::Append => format!(
+            r#"SELECT MAX("timestamp_network") AS "latest" FROM {} WHERE NOT "outlier";"#,
+            get_history_table_name()
+        ),
+    };
+
+    let pools = super::shard::get_shard_pools(crate::postgis::DEADPOOL_POSTGIS.get());
+    if pools.is_empty() {
+        postgis_error!("(get_latest_position_update) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    }
+
+    let mut latest: Option<DateTime<Utc>> = None;
+    for pool in pools {
+        let client = pool.get().await.map_err(|e| {
+            postgis_error!(
+                "(get_latest_position_update) could not get client from psql connection pool: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::Client)
+        })?;
+
+        let shard_latest = client
+            .query_one(&stmt, &[])
+            .await
+            .map_err(|e| {
+                postgis_error!(
+                    "(get_latest_position_update) could not execute statement: {}",
+                    e
+                );
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?
+            .try_get::<_, Option<DateTime<Utc>>>("latest")
+            .map_err(|e| {
+                postgis_error!("(get_latest_position_update) could not read time: {}", e);
+                PostgisError::Aircraft(AircraftError::DBError)
+            })?;
+
+        latest = latest.max(shard_latest);
+    }
+
+    Ok(latest)
+}
+
+/// A timestamped position from the aircraft history
+#[derive(Debug, Clone, PartialEq)]
+struct TrackPoint {
+    geom: PointZ,
+    timestamp: DateTime<Utc>,
+
+    /// Width of the archive bucket the point summarizes, 0 if full-rate
+    bucket_seconds: u32,
+}
+
+/// Gets the track of an aircraft from its position history, optionally
+///  downsampled to one point per `resolution_seconds`.
+///
+/// Buckets are aligned to the UNIX epoch so that boundaries are stable
+///  across requests with different time windows. The first point of each
+///  bucket is kept, and the last point of the track is always preserved.
+/// A resolution of 0 returns the raw track.
+///
+/// Archived history (see [`super::archive`]) contributes the first and last
+///  position of each bucket. Both tables are read by a single statement so
+///  a concurrent archival can't leave a gap or duplicate at the boundary.
+pub async fn get_aircraft_track(
+    request: GetAircraftTrackRequest,
+) -> Result<GetAircraftTrackResponse, PostgisError> {
+    postgis_debug!("(get_aircraft_track) entry.");
+
+    check_identifier(&request.identifier).map_err(|e| {
+        postgis_error!(
+            "(get_aircraft_track) invalid identifier {}: {}",
+            request.identifier,
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Identifier)
+    })?;
+
+    let (Some(time_start), Some(time_end)) = (request.time_start, request.time_end) else {
+        postgis_error!("(get_aircraft_track) time_start and time_end are required.");
+        return Err(PostgisError::Aircraft(AircraftError::Time));
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end <= time_start {
+        postgis_error!("(get_aircraft_track) time_end must be after time_start.");
+        return Err(PostgisError::Aircraft(AircraftError::Time));
+    }
+
+    if let Some(operator_id) = &request.operator_id {
+        super::utils::check_label(operator_id).map_err(|e| {
+            postgis_error!(
+                "(get_aircraft_track) invalid operator_id {}: {}",
+                operator_id,
+                e
+            );
+            PostgisError::Aircraft(AircraftError::Identifier)
+        })?;
+    }
+
+    if let Some(srid) = request.srid {
+        super::utils::check_srid(srid).map_err(|e| {
+            postgis_error!("(get_aircraft_track) invalid output SRID {}: {}", srid, e);
+            PostgisError::Aircraft(AircraftError::Location)
+        })?;
+    }
+
+    if request.resolution_seconds > MAX_TRACK_RESOLUTION_SECONDS {
+        postgis_error!(
+            "(get_aircraft_track) invalid resolution: {}s",
+            request.resolution_seconds
+        );
+        return Err(PostgisError::Aircraft(AircraftError::Resolution));
+    }
+
+    let primary = crate::postgis::DEADPOOL_POSTGIS.get();
+    let Some(pool) = super::shard::get_shard_pool(primary, &request.identifier) else {
+        postgis_error!("(get_aircraft_track) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_aircraft_track) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"WITH "track" AS (
+                SELECT "geom", "timestamp_network", 0 AS "bucket_seconds"
+                FROM {table_name}
+                WHERE "identifier" = $1
+                    AND "timestamp_network" >= $2
+                    AND "timestamp_network" <= $3
+                    AND NOT "outlier"
+                UNION ALL
+                SELECT "first_geom", "first_timestamp", "bucket_seconds"
+                FROM {archive_table_name}
+                WHERE "identifier" = $1
+                    AND "first_timestamp" >= $2
+                    AND "first_timestamp" <= $3
+                UNION ALL
+                SELECT "last_geom", "last_timestamp", "bucket_seconds"
+                FROM {archive_table_name}
+                WHERE "identifier" = $1
+                    AND "last_timestamp" > "first_timestamp"
+                    AND "last_timestamp" >= $2
+                    AND "last_timestamp" <= $3
+            ), "points" AS (
+                SELECT
+                    "geom",
+                    "timestamp_network",
+                    "bucket_seconds",
+                    ROW_NUMBER() OVER (ORDER BY "timestamp_network" ASC) AS "index",
+                    COUNT(*) OVER () AS "raw_count"
+                FROM "track"
+                WHERE $4::VARCHAR IS NULL
+                    OR EXISTS (
+                        SELECT 1 FROM {aircraft_table_name}
+                        WHERE "identifier" = $1 AND "operator_id" = $4
+                    )
+            ), "buckets" AS (
+                SELECT
+                    "index",
+                    CASE WHEN $5::INTEGER = 0 THEN "index"::FLOAT8
+                    ELSE EXTRACT(EPOCH FROM date_bin(
+                        make_interval(secs => $5::INTEGER),
+                        "timestamp_network",
+                        TIMESTAMPTZ 'epoch'
+                    ))::FLOAT8 END AS "bucket"
+                FROM "points"
+            ), "firsts" AS (
+                SELECT DISTINCT ON ("bucket") "index"
+                FROM "buckets"
+                ORDER BY "bucket", "index" ASC
+            )
+            SELECT "geom", "timestamp_network", "bucket_seconds", "raw_count"
+            FROM "points"
+            WHERE "index" = "raw_count"
+                OR "index" IN (SELECT "index" FROM "firsts")
+            ORDER BY "index" ASC;"#,
+            table_name = get_history_table_name(),
+            archive_table_name = super::archive::get_archive_table_name(),
+            aircraft_table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_aircraft_track) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    let rows = client
+        .query(
+            &stmt,
+            &[
+                &request.identifier,
+                &time_start,
+                &time_end,
+                &request.operator_id,
+                &(request.resolution_seconds as i32),
+            ],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_aircraft_track) could not execute query: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    let raw_count: i64 = match rows.first() {
+        Some(row) => row.try_get("raw_count").map_err(|e| {
+            postgis_error!("(get_aircraft_track) could not get track size: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?,
+        None => 0,
+    };
+
+    let points = rows
+        .into_iter()
+        .map(|row| {
+            let bucket_seconds: i32 = row.try_get("bucket_seconds")?;
+            Ok(TrackPoint {
+                geom: row.try_get("geom")?,
+                timestamp: row.try_get("timestamp_network")?,
+                bucket_seconds: bucket_seconds as u32,
+            })
+        })
+        .collect::<Result<Vec<TrackPoint>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_aircraft_track) could not get track data: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    // Positions are returned in the requested CRS, if any
+    let geoms = points
+        .iter()
+        .map(|point| point.geom)
+        .collect::<Vec<PointZ>>();
+    let geoms = match request.srid {
+        Some(srid) if srid != DEFAULT_SRID => {
+            super::utils::transform_points(geoms, DEFAULT_SRID, srid)
+                .await
+                .map_err(|e| {
+                    postgis_error!(
+                        "(get_aircraft_track) could not transform track to SRID {}: {}",
+                        srid,
+                        e
+                    );
+                    PostgisError::Aircraft(AircraftError::Location)
+                })?
+        }
+        _ => geoms,
+    };
+
+    let positions = points
+        .into_iter()
+        .zip(geoms)
+        .map(|(point, geom)| TimePosition {
+            position: Some(GrpcPointZ::from(geom)),
+            timestamp: Some(point.timestamp.into()),
+            bucket_seconds: point.bucket_seconds,
+        })
+        .collect::<Vec<TimePosition>>();
+
+    postgis_debug!(
+        "(get_aircraft_track) downsampled {} points to {}.",
+        raw_count,
+        positions.len()
+    );
+
+    Ok(GetAircraftTrackResponse {
+        point_count: positions.len() as u32,
+        positions,
+    })
+}
+
+/// Gets the telemetry snapshots of an aircraft between two times, oldest first
+///
+/// Returns at most [`MAX_TELEMETRY_HISTORY_ROWS`] snapshots.
+pub async fn get_telemetry_history(
+    identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Result<Vec<TelemetrySnapshot>, PostgisError> {
+    postgis_debug!("(get_telemetry_history) entry, aircraft: '{identifier}'.");
+    check_identifier(identifier).map_err(|e| {
+        postgis_error!(
+            "(get_telemetry_history) invalid identifier {}: {}",
+            identifier,
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Identifier)
+    })?;
+
+    if time_end <= time_start {
+        postgis_error!("(get_telemetry_history) time_end must be after time_start.");
+        return Err(PostgisError::Aircraft(AircraftError::Time));
+    }
+
+    let primary = crate::postgis::DEADPOOL_POSTGIS.get();
+    let Some(pool) = super::shard::get_shard_pool(primary, identifier) else {
+        postgis_error!("(get_telemetry_history) could not get psql pool.");
+        return Err(PostgisError::Aircraft(AircraftError::Client));
+    };
+
+    get_telemetry_history_on(pool, identifier, time_start, time_end).await
+}
+
+/// Gets the telemetry snapshots of an aircraft from the shard holding it,
+///  oldest first
+pub(super) async fn get_telemetry_history_on(
+    pool: &deadpool_postgres::Pool,
+    identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Result<Vec<TelemetrySnapshot>, PostgisError> {
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_telemetry_history) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT
+                "identifier",
+                "geom",
+                "velocity_horizontal_ground_mps",
+                "velocity_horizontal_air_mps",
+                "velocity_vertical_mps",
+                "track_angle_degrees",
+                "timestamp_network",
+                "timestamp_asset"
+            FROM {table_name}
+            WHERE "identifier" = $1
+                AND "timestamp_network" >= $2
+                AND "timestamp_network" <= $3
+                AND NOT "outlier"
+            ORDER BY "timestamp_network" ASC
+            LIMIT $4;"#,
+        table_name = get_history_table_name(),
+    );
+
+    super::query_cached(
+        &client,
+        &stmt,
+        &[
+            &identifier,
+            &time_start,
+            &time_end,
+            &MAX_TELEMETRY_HISTORY_ROWS,
+        ],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(get_telemetry_history) could not execute query: {}", e);
+        PostgisError::Aircraft(AircraftError::DBError)
+    })?
+    .into_iter()
+    .map(TelemetrySnapshot::try_from)
+    .collect::<Result<Vec<TelemetrySnapshot>, tokio_postgres::error::Error>>()
+    .map_err(|e| {
+        postgis_error!("(get_telemetry_history) could not get history data: {}", e);
+        PostgisError::Aircraft(AircraftError::DBError)
+    })
+}
+
+/// Gets the earliest and latest timestamps of an aircraft's position
+///  history, `None` if there is no history
+///
+/// Covers archived history and skips outliers, matching the positions
+///  returned by [`get_aircraft_track`].
+pub async fn get_history_extent(
+    identifier: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>, PostgisError> {
+    postgis_debug!("(get_history_extent) entry, aircraft: '{identifier}'.");
+    check_identifier(identifier).map_err(|e| {
+        postgis_error!(
+            "(get_history_extent) invalid identifier {}: {}",
+            identifier,
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Identifier)
+    })?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_history_extent) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT MIN("first_timestamp") AS "first", MAX("last_timestamp") AS "last"
+            FROM (
+                SELECT
+                    MIN("timestamp_network") AS "first_timestamp",
+                    MAX("timestamp_network") AS "last_timestamp"
+                FROM {table_name}
+                WHERE "identifier" = $1 AND NOT "outlier"
+                UNION ALL
+                SELECT MIN("first_timestamp"), MAX("last_timestamp")
+                FROM {archive_table_name}
+                WHERE "identifier" = $1
+            ) AS "extents";"#,
+        table_name = get_history_table_name(),
+        archive_table_name = super::archive::get_archive_table_name(),
+    );
+
+    let rows = super::query_cached(&client, &stmt, &[&identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_history_extent) could not execute query: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    let Some(row) = rows.first() else {
+        return Ok(None);
+    };
+
+    let first: Option<DateTime<Utc>> = row.try_get("first").map_err(|e| {
+        postgis_error!("(get_history_extent) could not get first timestamp: {}", e);
+        PostgisError::Aircraft(AircraftError::DBError)
+    })?;
+
+    let last: Option<DateTime<Utc>> = row.try_get("last").map_err(|e| {
+        postgis_error!("(get_history_extent) could not get last timestamp: {}", e);
+        PostgisError::Aircraft(AircraftError::DBError)
+    })?;
+
+    Ok(first.zip(last))
+}
+
+/// A recorded change of an aircraft's operational status
+#[derive(Debug, Clone)]
+pub struct StatusChange {
+    /// The aircraft identifier
+    pub identifier: String,
+
+    /// The new status
+    pub status: OperationalStatus,
+
+    /// The status before the change, `None` for a newly registered aircraft
+    pub previous_status: Option<OperationalStatus>,
+
+    /// When the change was recorded
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TryFrom<tokio_postgres::Row> for StatusChange {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(StatusChange {
+            identifier: row.try_get("identifier")?,
+            status: row.try_get("op_status")?,
+            previous_status: row.try_get("previous_status")?,
+            timestamp: row.try_get("timestamp")?,
+        })
+    }
+}
+
+/// Splits a status batch into identifier and status arrays for `UNNEST`
+///
+/// Only the last status of an aircraft listed more than once is kept, an
+///  upsert can't change the same row twice.
+fn status_batch_arrays(
+    statuses: &[(String, OperationalStatus)],
+) -> Result<(Vec<String>, Vec<String>), AircraftError> {
+    let mut latest: HashMap<&str, OperationalStatus> = HashMap::new();
+    let mut order: Vec<&str> = vec![];
+    for (identifier, status) in statuses {
+        check_identifier(identifier).map_err(|e| {
+            postgis_error!(
+                "(status_batch_arrays) invalid identifier {}: {}",
+                identifier,
+                e
+            );
+            AircraftError::Identifier
+        })?;
+
+        if latest.insert(identifier.as_str(), *status).is_none() {
+            order.push(identifier.as_str());
+        }
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|identifier| (identifier.to_string(), latest[identifier].to_string()))
+        .unzip())
+}
+
+/// Sets the operational status of many aircraft with a single statement
+///
+/// Unknown aircraft are registered. Each actual change is appended to the
+///  status history, setting an aircraft to its current status records
+///  nothing. Returns the number of recorded changes.
+pub async fn update_aircraft_status_batch(
+    statuses: Vec<(String, OperationalStatus)>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<u64, PostgisError> {
+    postgis_debug!(
+        "(update_aircraft_status_batch) entry, {} aircraft.",
+        statuses.len()
+    );
+
+    let (identifiers, statuses) = status_batch_arrays(&statuses).map_err(PostgisError::Aircraft)?;
+
+    if identifiers.is_empty() {
+        return Ok(0);
+    }
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(update_aircraft_status_batch) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    // The "previous" snapshot is taken before the upsert runs
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"
+        WITH "input" AS (
+            SELECT "identifier", "op_status"::opstatus AS "op_status"
+            FROM UNNEST($1::VARCHAR[], $2::TEXT[]) AS "batch" ("identifier", "op_status")
+        ), "previous" AS (
+            SELECT "input"."identifier", "aircraft"."op_status" AS "previous_status"
+            FROM "input"
+            LEFT JOIN {table_name} AS "aircraft"
+                ON "aircraft"."identifier" = "input"."identifier"
+        ), "changed" AS (
+            INSERT INTO {table_name} ("identifier", "op_status")
+            SELECT "identifier", "op_status" FROM "input"
+            ON CONFLICT ("identifier") DO UPDATE
+                SET "op_status" = EXCLUDED."op_status"
+                WHERE {table_name}."op_status" IS DISTINCT FROM EXCLUDED."op_status"
+            RETURNING "identifier", "op_status"
+        )
+        INSERT INTO {history_table_name} (
+            "identifier",
+            "op_status",
+            "previous_status",
+            "timestamp"
+        )
+        SELECT
+            "changed"."identifier",
+            "changed"."op_status",
+            "previous"."previous_status",
+            NOW()
+        FROM "changed"
+        JOIN "previous" ON "previous"."identifier" = "changed"."identifier";"#,
+            table_name = get_table_name(),
+            history_table_name = get_status_history_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_aircraft_status_batch) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+    let changes = client
+        .execute(&stmt, &[&identifiers, &statuses])
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_aircraft_status_batch) could not execute statement: {}",
+                e
+            );
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?;
+
+    postgis_debug!("(update_aircraft_status_batch) success, {changes} change(s) recorded.");
+    Ok(changes)
+}
+
+/// Gets the recorded status changes of an aircraft, oldest first
+///
+/// Returns at most [`MAX_STATUS_HISTORY_ROWS`] changes.
+pub async fn get_status_history(
+    identifier: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<StatusChange>, PostgisError> {
+    postgis_debug!("(get_status_history) entry, aircraft: '{identifier}'.");
+    check_identifier(identifier).map_err(|e| {
+        postgis_error!(
+            "(get_status_history) invalid identifier {}: {}",
+            identifier,
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Identifier)
+    })?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_status_history) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT
+                "identifier",
+                "op_status",
+                "previous_status",
+                "timestamp"
+            FROM {table_name}
+            WHERE "identifier" = $1
+            ORDER BY "timestamp" ASC
+            LIMIT $2;"#,
+        table_name = get_status_history_table_name(),
+    );
+
+    super::query_cached(&client, &stmt, &[&identifier, &MAX_STATUS_HISTORY_ROWS])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_status_history) could not execute query: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?
+        .into_iter()
+        .map(StatusChange::try_from)
+        .collect::<Result<Vec<StatusChange>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_status_history) could not get status changes: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })
+}
+
+/// A recorded change of an aircraft's type
+#[derive(Debug, Clone)]
+pub struct TypeChange {
+    /// The aircraft identifier
+    pub identifier: String,
+
+    /// The new type
+    pub aircraft_type: AircraftType,
+
+    /// The type before the change
+    pub previous_type: AircraftType,
+
+    /// Network timestamp of the identification that changed the type
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TryFrom<tokio_postgres::Row> for TypeChange {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(TypeChange {
+            identifier: row.try_get("identifier")?,
+            aircraft_type: row.try_get("aircraft_type")?,
+            previous_type: row.try_get("previous_type")?,
+            timestamp: row.try_get("timestamp")?,
+        })
+    }
+}
+
+/// Gets the recorded type changes of an aircraft, oldest first
+///
+/// Identifications of a known aircraft with a different type are recorded
+///  by [`update_aircraft_id`], registering an aircraft records nothing.
+///  Returns at most [`MAX_TYPE_HISTORY_ROWS`] changes.
+pub async fn get_type_history(
+    identifier: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<TypeChange>, PostgisError> {
+    postgis_debug!("(get_type_history) entry, aircraft: '{identifier}'.");
+    check_identifier(identifier).map_err(|e| {
+        postgis_error!(
+            "(get_type_history) invalid identifier {}: {}",
+            identifier,
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Identifier)
+    })?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_type_history) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Aircraft(AircraftError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT
+                "identifier",
+                "aircraft_type",
+                "previous_type",
+                "timestamp"
+            FROM {table_name}
+            WHERE "identifier" = $1
+            ORDER BY "timestamp" ASC
+            LIMIT $2;"#,
+        table_name = get_type_history_table_name(),
+    );
+
+    super::query_cached(&client, &stmt, &[&identifier, &MAX_TYPE_HISTORY_ROWS])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_type_history) could not execute query: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })?
+        .into_iter()
+        .map(TypeChange::try_from)
+        .collect::<Result<Vec<TypeChange>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_type_history) could not get type changes: {}", e);
+            PostgisError::Aircraft(AircraftError::DBError)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Position;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn ut_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_client_failure) start");
+
+        let nodes = vec![("aircraft", 52.3745905, 4.9160036)];
+        let aircraft: Vec<AircraftPosition> = nodes
+            .iter()
+            .map(|(label, latitude, longitude)| AircraftPosition {
+                identifier: label.to_string(),
+                position: Position {
+                    latitude: *latitude,
+                    longitude: *longitude,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            })
+            .collect();
+
+        let result = update_aircraft_position(aircraft, false).await.unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Client));
+
+        ut_info!("(ut_client_failure) success");
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_to_gis_invalid_label() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_position_to_gis_invalid_label) start");
+
+        for label in &[
+            "NULL",
+            "Aircraft;",
+            "'Aircraft'",
+            "Aircraft \'",
+            &"X".repeat(1000),
+            // Longer than the identifier columns
+            &"X".repeat(21),
+        ] {
+            let position = AircraftPosition {
+                identifier: label.to_string(),
+                position: Position {
+                    latitude: 0.0,
+                    longitude: 0.0,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            };
+
+            let velocity = AircraftVelocity {
+                identifier: label.to_string(),
+                timestamp_network: Utc::now(),
+                velocity_horizontal_ground_mps: 0.0,
+                velocity_horizontal_air_mps: None,
+                velocity_vertical_mps: 0.0,
+                track_angle_degrees: 0.0,
+                timestamp_asset: None,
+            };
+
+            let id = AircraftId {
+                identifier: Some(label.to_string()),
+                session_id: None,
+                timestamp_network: Utc::now(),
+                aircraft_type: AircraftType::Rotorcraft,
+                operator_id: None,
+                timestamp_asset: None,
+                force_type: false,
+            };
+
+            let result = validate_position_message(&position, &Utc::now()).unwrap_err();
+            assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+            let result = validate_velocity_message(&velocity, &Utc::now()).unwrap_err();
+            assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+            let result = validate_id_message(&id, &Utc::now()).unwrap_err();
+            assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+        }
+
+        ut_info!("(ut_aircraft_position_to_gis_invalid_label) success");
+    }
+
+    #[test]
+    fn ut_check_identifier_length() {
+        assert!(check_identifier(&"X".repeat(20)).is_ok());
+        assert_eq!(
+            check_identifier(&"X".repeat(21)).unwrap_err(),
+            StringError::TooLong {
+                length: 21,
+                max: 20
+            }
+        );
+        assert_eq!(
+            check_identifier("").unwrap_err(),
+            StringError::TooShort { length: 0, min: 1 }
+        );
+
+        // Operators aren't stored in the identifier columns
+        assert!(crate::postgis::utils::check_label(&"X".repeat(255)).is_ok());
+    }
+
+    #[test]
+    fn ut_check_identifier_character() {
+        assert_eq!(
+            check_identifier("AIR;CRAFT").unwrap_err(),
+            StringError::Character {
+                character: ';',
+                index: 3
+            }
+        );
+
+        // Characters are counted, not bytes
+        assert_eq!(
+            check_identifier("ÄIRCRAFT").unwrap_err(),
+            StringError::Character {
+                character: 'Ä',
+                index: 0
+            }
+        );
+        assert_eq!(
+            check_identifier(&"Ä".repeat(11)).unwrap_err(),
+            StringError::Character {
+                character: 'Ä',
+                index: 0
+            }
+        );
+        assert_eq!(
+            check_identifier(&"Ä".repeat(21)).unwrap_err(),
+            StringError::TooLong {
+                length: 21,
+                max: 20
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_id_no_identifier() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_id_no_identifier) start");
+
+        let id = AircraftId {
+            identifier: None,
+            session_id: None,
+            timestamp_network: Utc::now(),
+            aircraft_type: AircraftType::Rotorcraft,
+            operator_id: None,
+            timestamp_asset: None,
+            force_type: false,
+        };
+
+        let result = validate_id_message(&id, &Utc::now()).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+        let id = AircraftId {
+            identifier: Some("Aircraft".to_string()),
+            operator_id: Some("Operator;".to_string()),
+            ..id
+        };
+
+        let result = validate_id_message(&id, &Utc::now()).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+        ut_info!("(ut_aircraft_id_no_identifier) success");
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_position_to_gis_invalid_location() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_position_to_gis_invalid_location) start");
+
+        let coords = vec![
+            (-90.1, 0.0, 100.0),
+            (90.1, 0.0, 100.0),
+            (0.0, -180.1, 100.0),
+            (0.0, 180.1, 100.0),
+            (f64::NAN, 0.0, 100.0),
+            (0.0, f64::NAN, 100.0),
+            (0.0, 0.0, f64::NAN),
+            (f64::INFINITY, 0.0, 100.0),
+            (0.0, f64::NEG_INFINITY, 100.0),
+            (0.0, 0.0, f64::INFINITY),
+        ];
+        for coord in coords {
+            let aircraft = AircraftPosition {
+                position: Position {
+                    latitude: coord.0,
+                    longitude: coord.1,
+                    altitude_meters: coord.2,
+                },
+                identifier: "Aircraft".to_string(),
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            };
+
+            let result = validate_position_message(&aircraft, &Utc::now()).unwrap_err();
+            assert_eq!(result, PostgisError::Aircraft(AircraftError::Location));
+        }
+
+        ut_info!("(ut_aircraft_position_to_gis_invalid_location) success");
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_position_to_gis_invalid_accuracy() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_position_to_gis_invalid_accuracy) start");
+
+        let position = AircraftPosition {
+            position: Position {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            identifier: "Aircraft".to_string(),
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: Some(0.0),
+            vertical_accuracy_meters: Some(12.5),
+        };
+        validate_position_message(&position, &Utc::now()).unwrap();
+
+        let accuracies = vec![
+            (Some(-1.0), None),
+            (None, Some(-0.1)),
+            (Some(f32::NAN), None),
+            (None, Some(f32::NAN)),
+        ];
+        for (horizontal_accuracy_meters, vertical_accuracy_meters) in accuracies {
+            let position = AircraftPosition {
+                horizontal_accuracy_meters,
+                vertical_accuracy_meters,
+                ..position.clone()
+            };
+
+            let result = validate_position_message(&position, &Utc::now()).unwrap_err();
+            assert_eq!(result, PostgisError::Aircraft(AircraftError::Accuracy));
+        }
+
+        ut_info!("(ut_aircraft_position_to_gis_invalid_accuracy) success");
     }
 
     #[tokio::test]
@@ -709,6 +3066,8 @@ mod tests {
             },
             identifier: "Aircraft".to_string(),
             timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
         };
 
         let velocity = AircraftVelocity {
@@ -726,7 +3085,9 @@ mod tests {
             identifier: Some("Aircraft".to_string()),
             session_id: None,
             aircraft_type: AircraftType::Rotorcraft,
+            operator_id: None,
             timestamp_asset: None,
+            force_type: false,
         };
 
         let result = validate_position_message(&position, &Utc::now()).unwrap_err();
@@ -740,4 +3101,391 @@ mod tests {
 
         ut_info!("(ut_aircraft_position_to_gis_invalid_time) success");
     }
+
+    fn telemetry() -> AircraftTelemetry {
+        AircraftTelemetry {
+            identifier: "Aircraft".to_string(),
+            position: Position {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            velocity_horizontal_ground_mps: 10.0,
+            velocity_horizontal_air_mps: Some(12.0),
+            velocity_vertical_mps: 1.0,
+            track_angle_degrees: 90.0,
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_telemetry_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_telemetry_invalid) start");
+
+        let now = Utc::now() + Duration::try_seconds(1).unwrap();
+        validate_telemetry_message(&telemetry(), &now).unwrap();
+
+        let mut item = telemetry();
+        item.identifier = "Aircraft;".to_string();
+        let result = validate_telemetry_message(&item, &now).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+        let mut item = telemetry();
+        item.position.latitude = 90.1;
+        let result = validate_telemetry_message(&item, &now).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Location));
+
+        let mut item = telemetry();
+        item.velocity_vertical_mps = f32::NAN;
+        let result = validate_telemetry_message(&item, &now).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Location));
+
+        let mut item = telemetry();
+        item.velocity_horizontal_air_mps = Some(f32::INFINITY);
+        let result = validate_telemetry_message(&item, &now).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Location));
+
+        let mut item = telemetry();
+        item.timestamp_network = now + Duration::try_days(1).unwrap();
+        let result = validate_telemetry_message(&item, &now).unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Time));
+
+        ut_info!("(ut_aircraft_telemetry_invalid) success");
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_telemetry_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_telemetry_client_failure) start");
+
+        let mut item = telemetry();
+        item.timestamp_network = Utc::now() - Duration::try_seconds(1).unwrap();
+        let result = update_aircraft_telemetry(vec![item], false)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Client));
+
+        // Nothing valid to update
+        let mut item = telemetry();
+        item.identifier = "Aircraft;".to_string();
+        update_aircraft_telemetry(vec![item], false).await.unwrap();
+
+        ut_info!("(ut_aircraft_telemetry_client_failure) success");
+    }
+
+    #[test]
+    fn ut_telemetry_snapshot_from_telemetry() {
+        let mut item = telemetry();
+        item.timestamp_asset = Some(item.timestamp_network);
+        let geom = PointZ::try_from(item.position).unwrap();
+        let snapshot = TelemetrySnapshot::from_telemetry(&item, geom);
+
+        // One complete row per combined update
+        assert_eq!(snapshot.identifier, item.identifier);
+        assert_eq!(snapshot.geom, geom);
+        assert_eq!(
+            snapshot.velocity_horizontal_ground_mps,
+            Some(item.velocity_horizontal_ground_mps)
+        );
+        assert_eq!(
+            snapshot.velocity_horizontal_air_mps,
+            item.velocity_horizontal_air_mps
+        );
+        assert!(snapshot.velocity_horizontal_air_mps.is_some());
+        assert_eq!(
+            snapshot.velocity_vertical_mps,
+            Some(item.velocity_vertical_mps)
+        );
+        assert_eq!(snapshot.track_angle_degrees, Some(item.track_angle_degrees));
+        assert_eq!(snapshot.timestamp_network, item.timestamp_network);
+        assert_eq!(snapshot.timestamp_asset, item.timestamp_asset);
+        assert!(snapshot.timestamp_asset.is_some());
+    }
+
+    #[tokio::test]
+    async fn ut_get_telemetry_history_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_telemetry_history_invalid) start");
+
+        let time_end = Utc::now();
+        let time_start = time_end - Duration::try_hours(1).unwrap();
+
+        let result = get_telemetry_history("Aircraft;", time_start, time_end)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+        let result = get_telemetry_history("Aircraft", time_end, time_start)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Time));
+
+        let result = get_telemetry_history("Aircraft", time_start, time_end)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Client));
+
+        ut_info!("(ut_get_telemetry_history_invalid) success");
+    }
+
+    #[tokio::test]
+    async fn ut_get_aircraft_track_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_aircraft_track_invalid) start");
+
+        let request = GetAircraftTrackRequest {
+            identifier: "Aircraft;".to_string(),
+            time_start: Some(Utc::now().into()),
+            time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            resolution_seconds: 0,
+            operator_id: None,
+            srid: None,
+        };
+
+        let result = get_aircraft_track(request.clone()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+
+        let mut request = GetAircraftTrackRequest {
+            identifier: "Aircraft".to_string(),
+            ..request
+        };
+
+        request.resolution_seconds = MAX_TRACK_RESOLUTION_SECONDS + 1;
+        let result = get_aircraft_track(request.clone()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Resolution));
+
+        request.resolution_seconds = 0;
+        request.operator_id = Some("Operator;".to_string());
+        let result = get_aircraft_track(request.clone()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Identifier));
+        request.operator_id = None;
+
+        request.srid = Some(-1);
+        let result = get_aircraft_track(request.clone()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Location));
+        request.srid = None;
+
+        request.resolution_seconds = 0;
+        request.time_end = request.time_start.clone();
+        let result = get_aircraft_track(request.clone()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Aircraft(AircraftError::Time));
+
+        ut_info!("(ut_get_aircraft_track_invalid) success");
+    }
+
+    #[test]
+    fn ut_position_write_mode() {
+        use std::str::FromStr;
+
+        assert_eq!(PositionWriteMode::default(), PositionWriteMode::Upsert);
+        assert_eq!(position_write_mode(), PositionWriteMode::Upsert);
+        assert_eq!(
+            PositionWriteMode::from_str("upsert").unwrap(),
+            PositionWriteMode::Upsert
+        );
+        assert_eq!(
+            PositionWriteMode::from_str("append").unwrap(),
+            PositionWriteMode::Append
+        );
+        assert_eq!(PositionWriteMode::Append.to_string(), "append");
+        assert!(PositionWriteMode::from_str("insert").is_err());
+    }
+
+    /// A report `meters_north` north of a reference point, `seconds` after `t0`
+    fn report(t0: DateTime<Utc>, meters_north: f64, seconds: i64) -> Report {
+        Report {
+            geom: PointZ::new(
+                4.9160036,
+                52.3745905 + meters_north / 111_195.0,
+                100.0,
+                Some(DEFAULT_SRID),
+            ),
+            timestamp: t0 + chrono::Duration::try_seconds(seconds).unwrap(),
+        }
+    }
+
+    #[test]
+    fn ut_check_plausibility() {
+        let t0 = Utc::now();
+        let max_speed = max_plausible_speed_mps(AircraftType::Rotorcraft);
+        let accepted = report(t0, 0.0, 0);
+
+        // First report of an aircraft
+        assert!(check_plausibility(&accepted, None, &[], max_speed, 3).is_ok());
+
+        // 100 m in 1 s
+        let candidate = report(t0, 100.0, 1);
+        assert!(check_plausibility(&candidate, Some(&accepted), &[], max_speed, 3).is_ok());
+
+        // 80 km in 1 s
+        let candidate = report(t0, 80_000.0, 1);
+        assert_eq!(
+            check_plausibility(&candidate, Some(&accepted), &[], max_speed, 3).unwrap_err(),
+            AircraftError::Implausible
+        );
+
+        // Same timestamp as the last report
+        let candidate = report(t0, 1_000.0, 0);
+        assert_eq!(
+            check_plausibility(&candidate, Some(&accepted), &[], max_speed, 3).unwrap_err(),
+            AircraftError::Implausible
+        );
+
+        // Disabled
+        let candidate = report(t0, 80_000.0, 1);
+        assert!(check_plausibility(&candidate, Some(&accepted), &[], max_speed, 0).is_ok());
+
+        // Relocated: the third consistent report is accepted
+        let outliers = vec![report(t0, 80_010.0, 2), report(t0, 80_000.0, 1)];
+        let candidate = report(t0, 80_020.0, 3);
+        assert_eq!(
+            check_plausibility(&candidate, Some(&accepted), &outliers[1..], max_speed, 3)
+                .unwrap_err(),
+            AircraftError::Implausible
+        );
+        assert!(check_plausibility(&candidate, Some(&accepted), &outliers, max_speed, 3).is_ok());
+
+        // Glitches in different places don't add up to a relocation
+        let outliers = vec![report(t0, -80_000.0, 2), report(t0, 80_000.0, 1)];
+        assert_eq!(
+            check_plausibility(&candidate, Some(&accepted), &outliers, max_speed, 3).unwrap_err(),
+            AircraftError::Implausible
+        );
+
+        // No accepted report among the newest reports
+        let outliers = vec![report(t0, 80_010.0, 2)];
+        assert_eq!(
+            check_plausibility(&candidate, None, &outliers, max_speed, 3).unwrap_err(),
+            AircraftError::Implausible
+        );
+    }
+
+    #[test]
+    fn ut_max_plausible_speed_mps() {
+        use strum::IntoEnumIterator;
+
+        for aircraft_type in AircraftType::iter() {
+            assert!(max_plausible_speed_mps(aircraft_type) > 0.0);
+        }
+
+        assert!(
+            max_plausible_speed_mps(AircraftType::Aeroplane)
+                > max_plausible_speed_mps(AircraftType::Rotorcraft)
+        );
+        assert_eq!(relocation_reports(), DEFAULT_RELOCATION_REPORTS);
+    }
+
+    #[test]
+    fn ut_resolve_aircraft_type() {
+        let resolve = |previous, incoming, force| {
+            resolve_aircraft_type(previous, incoming, force).map(|t: AircraftType| t as i32)
+        };
+
+        // New aircraft take any type
+        assert_eq!(
+            resolve(None, AircraftType::Undeclared, false),
+            Ok(AircraftType::Undeclared as i32)
+        );
+
+        // Undeclared keeps the declared type, unless forced
+        assert_eq!(
+            resolve(
+                Some(AircraftType::Rotorcraft),
+                AircraftType::Undeclared,
+                false
+            ),
+            Ok(AircraftType::Rotorcraft as i32)
+        );
+        assert_eq!(
+            resolve(
+                Some(AircraftType::Rotorcraft),
+                AircraftType::Undeclared,
+                true
+            ),
+            Ok(AircraftType::Undeclared as i32)
+        );
+
+        // Declaring a type
+        assert_eq!(
+            resolve(
+                Some(AircraftType::Undeclared),
+                AircraftType::Aeroplane,
+                false
+            ),
+            Ok(AircraftType::Aeroplane as i32)
+        );
+
+        // Within a category or from a type without a category
+        assert_eq!(
+            resolve(Some(AircraftType::Aeroplane), AircraftType::Glider, false),
+            Ok(AircraftType::Glider as i32)
+        );
+        assert_eq!(
+            resolve(
+                Some(AircraftType::Hybridlift),
+                AircraftType::Rotorcraft,
+                false
+            ),
+            Ok(AircraftType::Rotorcraft as i32)
+        );
+
+        // Incompatible categories, unless forced
+        for (from, to) in [
+            (AircraftType::Aeroplane, AircraftType::Rotorcraft),
+            (AircraftType::Gyroplane, AircraftType::Airship),
+            (AircraftType::Freeballoon, AircraftType::Glider),
+        ] {
+            assert_eq!(
+                resolve(Some(from), to, false),
+                Err(AircraftError::TypeTransition)
+            );
+            assert_eq!(resolve(Some(from), to, true), Ok(to as i32));
+        }
+    }
+
+    #[test]
+    fn ut_check_operator_change() {
+        assert!(check_operator_change(None, Some("Operator-A")).is_ok());
+        assert!(check_operator_change(Some("Operator-A"), None).is_ok());
+        assert!(check_operator_change(Some("Operator-A"), Some("Operator-A")).is_ok());
+        assert_eq!(
+            check_operator_change(Some("Operator-A"), Some("Operator-B")),
+            Err(AircraftError::Operator)
+        );
+    }
+
+    #[test]
+    fn ut_status_batch_arrays() {
+        let batch = vec![
+            ("A1".to_string(), OperationalStatus::Ground),
+            ("A2".to_string(), OperationalStatus::Airborne),
+            ("A1".to_string(), OperationalStatus::Emergency),
+        ];
+
+        // The last status of a repeated aircraft wins, in first-seen order
+        let (identifiers, statuses) = status_batch_arrays(&batch).unwrap();
+        assert_eq!(identifiers, vec!["A1".to_string(), "A2".to_string()]);
+        assert_eq!(
+            statuses,
+            vec![
+                OperationalStatus::Emergency.to_string(),
+                OperationalStatus::Airborne.to_string()
+            ]
+        );
+
+        let (identifiers, statuses) = status_batch_arrays(&[]).unwrap();
+        assert!(identifiers.is_empty());
+        assert!(statuses.is_empty());
+
+        let batch = vec![
+            ("A1".to_string(), OperationalStatus::Ground),
+            ("A 2".to_string(), OperationalStatus::Ground),
+        ];
+        assert_eq!(
+            status_batch_arrays(&batch).unwrap_err(),
+            AircraftError::Identifier
+        );
+    }
 }
@@ -0,0 +1,331 @@
+//! Downsampled archival of the aircraft position history.
+//!
+//! The full-rate history is kept for the hot retention. Older rows are
+//!  aggregated to one row per aircraft per bucket in the archive table and
+//!  deleted from the history, in batches of [`ARCHIVE_BATCH_ROWS`] rows.
+//!  Archived buckets are kept for the archive retention.
+
+use super::{psql_transaction, PostgisError, PsqlError, DEFAULT_SRID, PSQL_SCHEMA};
+use chrono::{DateTime, Utc};
+
+/// Max rows archived or purged by a single statement
+pub const ARCHIVE_BATCH_ROWS: i64 = 10_000;
+
+/// Archival settings of the aircraft position history
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ArchivePolicy {
+    /// Age after which positions are archived (0 disables the archival)
+    pub hot_retention_secs: u64,
+
+    /// Age after which archived buckets are removed (0 keeps them)
+    pub archive_retention_secs: u64,
+
+    /// Width of an archive bucket
+    pub bucket_secs: u32,
+}
+
+/// Rows moved or removed by an archival run
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct ArchiveSummary {
+    /// History rows aggregated into the archive
+    pub archived: u64,
+
+    /// Archived buckets removed past the archive retention
+    pub purged: u64,
+}
+
+/// Gets the name of the aircraft history archive table
+pub(super) fn get_archive_table_name() -> &'static str {
+    static FULL_NAME: &str =
+        const_format::formatcp!(r#""{PSQL_SCHEMA}"."aircraft_history_archive""#,);
+    FULL_NAME
+}
+
+/// Initializes the PostGIS database for the history archive.
+pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the archive table declaration, also applied to each aircraft shard
+pub(super) fn psql_init_statements() -> Vec<String> {
+    vec![format!(
+        r#"CREATE TABLE IF NOT EXISTS {table_name} (
+            "identifier" VARCHAR(20) NOT NULL,
+            "bucket_start" TIMESTAMPTZ NOT NULL,
+            "bucket_seconds" INTEGER NOT NULL,
+            "first_geom" GEOMETRY(POINTZ, {DEFAULT_SRID}) NOT NULL,
+            "first_timestamp" TIMESTAMPTZ NOT NULL,
+            "last_geom" GEOMETRY(POINTZ, {DEFAULT_SRID}) NOT NULL,
+            "last_timestamp" TIMESTAMPTZ NOT NULL,
+            "altitude_meters_min" FLOAT8 NOT NULL,
+            "altitude_meters_max" FLOAT8 NOT NULL,
+            "velocity_horizontal_ground_mps_avg" FLOAT8,
+            "point_count" INTEGER NOT NULL,
+            PRIMARY KEY ("identifier", "bucket_start")
+        );"#,
+        table_name = get_archive_table_name()
+    )]
+}
+
+/// Gets the time before which positions are archived
+///
+/// Aligned down to a bucket boundary so that a bucket is only archived once
+///  all of its positions are past the hot retention.
+pub fn archive_cutoff(now: DateTime<Utc>, policy: &ArchivePolicy) -> Option<DateTime<Utc>> {
+    if policy.hot_retention_secs == 0 || policy.bucket_secs == 0 {
+        return None;
+    }
+
+    let cutoff = now.timestamp() - policy.hot_retention_secs as i64;
+    let cutoff = cutoff - cutoff.rem_euclid(policy.bucket_secs as i64);
+    DateTime::<Utc>::from_timestamp(cutoff, 0)
+}
+
+/// Moves history rows older than the hot retention to the archive, then
+///  removes archived buckets older than the archive retention
+///
+/// Each batch is a single statement, so an interrupted run leaves no row
+///  both archived and in the history. A bucket split across batches is
+///  merged into the same archive row.
+pub async fn archive_history(policy: ArchivePolicy) -> Result<ArchiveSummary, PostgisError> {
+    postgis_debug!("(archive_history) entry, policy: {:?}", policy);
+
+    let Some(cutoff) = archive_cutoff(crate::clock::now(), &policy) else {
+        return Ok(ArchiveSummary::default());
+    };
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(archive_history) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Connection));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(archive_history) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    let archive_stmt = client
+        .prepare_cached(&format!(
+            r#"WITH "batch" AS (
+                DELETE FROM {history_table_name} WHERE ctid IN (
+                    SELECT ctid FROM {history_table_name}
+                    WHERE "timestamp_network" < $1
+                    ORDER BY "timestamp_network" ASC
+                    LIMIT $2
+                )
+                RETURNING
+                    "identifier",
+                    "geom",
+                    "timestamp_network",
+                    "velocity_horizontal_ground_mps",
+                    "outlier"
+            ), "buckets" AS (
+                SELECT
+                    "identifier",
+                    to_timestamp(
+                        floor(extract(epoch FROM "timestamp_network") / $3::INTEGER) * $3::INTEGER
+                    ) AS "bucket_start",
+                    (array_agg("geom" ORDER BY "timestamp_network" ASC))[1] AS "first_geom",
+                    MIN("timestamp_network") AS "first_timestamp",
+                    (array_agg("geom" ORDER BY "timestamp_network" DESC))[1] AS "last_geom",
+                    MAX("timestamp_network") AS "last_timestamp",
+                    MIN(ST_Z("geom")) AS "altitude_meters_min",
+                    MAX(ST_Z("geom")) AS "altitude_meters_max",
+                    AVG("velocity_horizontal_ground_mps") AS "velocity_horizontal_ground_mps_avg",
+                    COUNT(*) AS "point_count"
+                FROM "batch"
+                WHERE NOT "outlier"
+                GROUP BY "identifier", "bucket_start"
+            ), "archived" AS (
+                INSERT INTO {archive_table_name} AS "archive" (
+                    "identifier",
+                    "bucket_start",
+                    "bucket_seconds",
+                    "first_geom",
+                    "first_timestamp",
+                    "last_geom",
+                    "last_timestamp",
+                    "altitude_meters_min",
+                    "altitude_meters_max",
+                    "velocity_horizontal_ground_mps_avg",
+                    "point_count"
+                )
+                SELECT
+                    "identifier",
+                    "bucket_start",
+                    $3::INTEGER,
+                    "first_geom",
+                    "first_timestamp",
+                    "last_geom",
+                    "last_timestamp",
+                    "altitude_meters_min",
+                    "altitude_meters_max",
+                    "velocity_horizontal_ground_mps_avg",
+                    "point_count"
+                FROM "buckets"
+                ON CONFLICT ("identifier", "bucket_start") DO UPDATE
+                    SET "first_geom" = CASE
+                            WHEN EXCLUDED."first_timestamp" < "archive"."first_timestamp"
+                            THEN EXCLUDED."first_geom"
+                            ELSE "archive"."first_geom"
+                        END,
+                        "first_timestamp" = LEAST("archive"."first_timestamp", EXCLUDED."first_timestamp"),
+                        "last_geom" = CASE
+                            WHEN EXCLUDED."last_timestamp" > "archive"."last_timestamp"
+                            THEN EXCLUDED."last_geom"
+                            ELSE "archive"."last_geom"
+                        END,
+                        "last_timestamp" = GREATEST("archive"."last_timestamp", EXCLUDED."last_timestamp"),
+                        "altitude_meters_min" = LEAST("archive"."altitude_meters_min", EXCLUDED."altitude_meters_min"),
+                        "altitude_meters_max" = GREATEST("archive"."altitude_meters_max", EXCLUDED."altitude_meters_max"),
+                        "velocity_horizontal_ground_mps_avg" = CASE
+                            WHEN "archive"."velocity_horizontal_ground_mps_avg" IS NULL
+                            THEN EXCLUDED."velocity_horizontal_ground_mps_avg"
+                            WHEN EXCLUDED."velocity_horizontal_ground_mps_avg" IS NULL
+                            THEN "archive"."velocity_horizontal_ground_mps_avg"
+                            ELSE (
+                                "archive"."velocity_horizontal_ground_mps_avg" * "archive"."point_count"
+                                + EXCLUDED."velocity_horizontal_ground_mps_avg" * EXCLUDED."point_count"
+                            ) / ("archive"."point_count" + EXCLUDED."point_count")
+                        END,
+                        "point_count" = "archive"."point_count" + EXCLUDED."point_count"
+            )
+            SELECT COUNT(*) FROM "batch";"#,
+            history_table_name = super::aircraft::get_history_table_name(),
+            archive_table_name = get_archive_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(archive_history) could not prepare cached statement: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let bucket_secs = policy.bucket_secs as i32;
+    let mut summary = ArchiveSummary::default();
+    loop {
+        let moved: i64 = client
+            .query_one(&archive_stmt, &[&cutoff, &ARCHIVE_BATCH_ROWS, &bucket_secs])
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|e| {
+                postgis_error!("(archive_history) could not archive batch: {}", e);
+                PostgisError::Psql(PsqlError::Execute)
+            })?;
+
+        summary.archived += moved as u64;
+        if moved < ARCHIVE_BATCH_ROWS || crate::shutdown::is_shutting_down() {
+            break;
+        }
+    }
+
+    if policy.archive_retention_secs > 0 {
+        let purge_stmt = client
+            .prepare_cached(&format!(
+                r#"DELETE FROM {table_name} WHERE ctid IN (
+                    SELECT ctid FROM {table_name}
+                    WHERE "bucket_start" < NOW() - make_interval(secs => $1::FLOAT8)
+                    LIMIT $2
+                );"#,
+                table_name = get_archive_table_name(),
+            ))
+            .await
+            .map_err(|e| {
+                postgis_error!(
+                    "(archive_history) could not prepare cached statement: {}",
+                    e
+                );
+                PostgisError::Psql(PsqlError::Execute)
+            })?;
+
+        let retention = policy.archive_retention_secs as f64;
+        loop {
+            let purged = client
+                .execute(&purge_stmt, &[&retention, &ARCHIVE_BATCH_ROWS])
+                .await
+                .map_err(|e| {
+                    postgis_error!("(archive_history) could not purge batch: {}", e);
+                    PostgisError::Psql(PsqlError::Execute)
+                })?;
+
+            summary.purged += purged;
+            if purged < ARCHIVE_BATCH_ROWS as u64 || crate::shutdown::is_shutting_down() {
+                break;
+            }
+        }
+    }
+
+    postgis_info!(
+        "(archive_history) archived {} positions, purged {} buckets.",
+        summary.archived,
+        summary.purged
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_archive_cutoff() {
+        let policy = ArchivePolicy {
+            hot_retention_secs: 3600,
+            archive_retention_secs: 0,
+            bucket_secs: 60,
+        };
+
+        let now = DateTime::<Utc>::from_timestamp(1_700_003_645, 0).unwrap();
+        let cutoff = archive_cutoff(now, &policy).unwrap();
+        assert_eq!(cutoff.timestamp(), 1_700_000_040);
+
+        // Already aligned
+        let now = DateTime::<Utc>::from_timestamp(1_700_003_640, 0).unwrap();
+        let cutoff = archive_cutoff(now, &policy).unwrap();
+        assert_eq!(cutoff.timestamp(), 1_700_000_040);
+
+        let disabled = ArchivePolicy {
+            hot_retention_secs: 0,
+            ..policy
+        };
+        assert!(archive_cutoff(now, &disabled).is_none());
+
+        let no_buckets = ArchivePolicy {
+            bucket_secs: 0,
+            ..policy
+        };
+        assert!(archive_cutoff(now, &no_buckets).is_none());
+    }
+
+    #[tokio::test]
+    async fn ut_archive_history_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_archive_history_client_failure) start");
+
+        let policy = ArchivePolicy {
+            hot_retention_secs: 3600,
+            archive_retention_secs: 86400,
+            bucket_secs: 60,
+        };
+
+        let result = archive_history(policy).await.unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Connection));
+
+        // Disabled archival doesn't need the database
+        let policy = ArchivePolicy {
+            hot_retention_secs: 0,
+            ..policy
+        };
+        assert_eq!(
+            archive_history(policy).await.unwrap(),
+            ArchiveSummary::default()
+        );
+
+        ut_info!("(ut_archive_history_client_failure) success");
+    }
+}
@@ -0,0 +1,570 @@
+//! Persisted backlog of streamed events
+//!
+//! Events are kept in a bounded table so that consumers reconnecting after
+//!  a disconnect or a service restart can receive the events they missed,
+//!  identified by an increasing event id. The backlog keeps the newest
+//!  [`EVENT_BACKLOG_CAPACITY`] events for at most [`EVENT_BACKLOG_TTL_SECS`],
+//!  give or take a [`EVENT_BACKLOG_TRIM_INTERVAL_SECS`].
+//!  Events dropped from a full backlog are counted in [`DROPPED_EVENTS`].
+//!
+//! Events of a change to flights or zones are persisted in the transaction
+//!  of the change with [`persist`] and sent once it commits, other events
+//!  are published with [`publish`]. Each kind of event has its own channel,
+//!  streamed with [`event_stream`]. The backlog is trimmed periodically by
+//!  [`begin_trim`].
+
+use super::{psql_transaction, PostgisError, PSQL_SCHEMA};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::broadcast;
+
+/// Max events kept in the backlog, the oldest are dropped first
+pub const EVENT_BACKLOG_CAPACITY: i64 = 10_000;
+
+/// Time an event is kept in the backlog
+pub const EVENT_BACKLOG_TTL_SECS: i64 = 86_400;
+
+/// Max events read from the backlog by a single query
+pub const MAX_BACKLOG_READ_ROWS: i64 = 500;
+
+/// Interval at which expired events and events beyond the capacity are
+///  removed from the backlog
+pub const EVENT_BACKLOG_TRIM_INTERVAL_SECS: u64 = 60;
+
+/// Interval at which event streams check for shutdown
+const EVENT_STREAM_POLL_INTERVAL_MS: u64 = 1_000;
+
+/// Events dropped from the full backlog since startup, expired events are
+///  not counted
+pub static DROPPED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Possible errors with the event backlog
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BacklogError {
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for BacklogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BacklogError::Client => write!(f, "Could not get backend client."),
+            BacklogError::DBError => write!(f, "Database error."),
+        }
+    }
+}
+
+/// Kind of a persisted event
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EventKind {
+    /// A [`ComplianceAlert`](crate::grpc::server::grpc_server::ComplianceAlert)
+    ComplianceAlert,
+
+    /// A [`FlightConflicted`](crate::grpc::server::grpc_server::FlightConflicted)
+    FlightConflicted,
+
+    /// A [`ZoneChanged`](crate::grpc::server::grpc_server::ZoneChanged)
+    ZoneChanged,
+
+    /// A [`FlightEvent`](crate::grpc::server::grpc_server::FlightEvent)
+    FlightEvent,
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EventKind::ComplianceAlert => write!(f, "compliance_alert"),
+            EventKind::FlightConflicted => write!(f, "flight_conflicted"),
+            EventKind::ZoneChanged => write!(f, "zone_changed"),
+            EventKind::FlightEvent => write!(f, "flight_event"),
+        }
+    }
+}
+
+/// An event persisted in the backlog and published on a channel
+pub trait PersistedEvent: prost::Message + Default + Clone + 'static {
+    /// Kind of the event in the backlog
+    const KIND: EventKind;
+
+    /// Id of the event in the backlog, 0 if it couldn't be persisted
+    fn event_id(&self) -> u64;
+
+    /// Sets the id of the event in the backlog
+    fn set_event_id(&mut self, event_id: u64);
+}
+
+/// An event read from the backlog
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacklogEvent {
+    /// Id of the event, increasing
+    pub id: u64,
+
+    /// The encoded event
+    pub payload: Vec<u8>,
+}
+
+/// Gets the name of the event backlog table
+pub(super) fn get_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."event_backlog""#,);
+    FULL_NAME
+}
+
+/// Initializes the PostGIS database for the event backlog.
+pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for the event backlog
+pub(super) fn psql_init_statements() -> Vec<String> {
+    vec![
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+            "id" BIGSERIAL PRIMARY KEY,
+            "kind" VARCHAR(32) NOT NULL,
+            "payload" BYTEA NOT NULL,
+            "created_at" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "event_backlog_kind_idx" ON {table_name} ("kind", "id");"#,
+            table_name = get_table_name()
+        ),
+    ]
+}
+
+/// First key of the advisory lock serializing the writes to the backlog
+const EVENT_BACKLOG_LOCK_KEY: i32 = 0x6576_656e; // "even"
+
+/// Serializes the events of this service from their persistence to their
+///  sending, so that subscribers receive them in order of their event id
+static EVENT_ORDER: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Appends an event to the backlog within `transaction`, returning its id
+///
+/// The event is only stored if the transaction commits. Writers take turns
+///  until they commit, so event ids become visible in increasing order and
+///  a consumer resuming after an id can't miss an event committed later
+///  with a lower id. Write events last, right before committing.
+pub async fn push_event_in(
+    transaction: &deadpool_postgres::Transaction<'_>,
+    kind: EventKind,
+    payload: &[u8],
+) -> Result<u64, PostgisError> {
+    postgis_debug!("(push_event_in) entry, kind: '{kind}'.");
+    transaction
+        .execute(
+            "SELECT pg_advisory_xact_lock($1, 0);",
+            &[&EVENT_BACKLOG_LOCK_KEY],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(push_event_in) could not lock the backlog: {}", e);
+            PostgisError::Backlog(BacklogError::DBError)
+        })?;
+
+    let stmt = transaction
+        .prepare_cached(&format!(
+            r#"INSERT INTO {table_name} ("kind", "payload")
+            VALUES ($1, $2)
+            RETURNING "id";"#,
+            table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(push_event_in) could not prepare cached statement: {}", e);
+            PostgisError::Backlog(BacklogError::DBError)
+        })?;
+
+    let id: i64 = transaction
+        .query_one(&stmt, &[&kind.to_string(), &payload])
+        .await
+        .map_err(|e| {
+            postgis_error!("(push_event_in) could not insert event: {}", e);
+            PostgisError::Backlog(BacklogError::DBError)
+        })?
+        .try_get("id")
+        .map_err(|e| {
+            postgis_error!("(push_event_in) could not get event id: {}", e);
+            PostgisError::Backlog(BacklogError::DBError)
+        })?;
+
+    Ok(id as u64)
+}
+
+/// Removes the expired events and the events beyond
+///  [`EVENT_BACKLOG_CAPACITY`], returning the number of events dropped from
+///  the full backlog
+pub async fn trim_backlog(pool: &deadpool_postgres::Pool) -> Result<u64, PostgisError> {
+    postgis_debug!("(trim_backlog) entry.");
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(trim_backlog) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Backlog(BacklogError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"DELETE FROM {table_name}
+            WHERE "created_at" < $3 - $2::BIGINT * INTERVAL '1 second'
+                OR "id" <= (
+                    SELECT "id" FROM {table_name}
+                    ORDER BY "id" DESC
+                    OFFSET $1 LIMIT 1
+                )
+            RETURNING "created_at" >= $3 - $2::BIGINT * INTERVAL '1 second' AS "overflow";"#,
+        table_name = get_table_name()
+    );
+
+    let dropped = super::query_cached(
+        &client,
+        &stmt,
+        &[
+            &EVENT_BACKLOG_CAPACITY,
+            &EVENT_BACKLOG_TTL_SECS,
+            &crate::clock::now(),
+        ],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(trim_backlog) could not trim backlog: {}", e);
+        PostgisError::Backlog(BacklogError::DBError)
+    })?
+    .iter()
+    .filter(|row| row.try_get::<_, bool>("overflow").unwrap_or(false))
+    .count() as u64;
+
+    if dropped > 0 {
+        postgis_warn!("(trim_backlog) backlog full, dropped {dropped} oldest events.");
+        DROPPED_EVENTS.fetch_add(dropped, Ordering::Relaxed);
+    }
+
+    Ok(dropped)
+}
+
+/// Starts a loop trimming the backlog every
+///  [`EVENT_BACKLOG_TRIM_INTERVAL_SECS`], see [`trim_backlog`]
+pub async fn begin_trim() {
+    postgis_info!(
+        "(begin_trim) trimming the event backlog every {EVENT_BACKLOG_TRIM_INTERVAL_SECS}s."
+    );
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+        EVENT_BACKLOG_TRIM_INTERVAL_SECS,
+    ));
+
+    loop {
+        interval.tick().await;
+        let Some(pool) = super::DEADPOOL_POSTGIS.get() else {
+            postgis_warn!("(begin_trim) could not get psql pool.");
+            continue;
+        };
+
+        // A failed trim only delays the removal
+        if let Err(e) = trim_backlog(pool).await {
+            postgis_warn!("(begin_trim) could not trim the event backlog: {e}");
+        }
+    }
+}
+
+/// Gets the events of a kind after an event id, oldest first
+///
+/// Returns at most `limit` events, capped at [`MAX_BACKLOG_READ_ROWS`].
+pub async fn get_events_after(
+    kind: EventKind,
+    last_event_id: u64,
+    limit: i64,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<BacklogEvent>, PostgisError> {
+    postgis_debug!("(get_events_after) entry, kind: '{kind}', after: {last_event_id}.");
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_events_after) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Backlog(BacklogError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT "id", "payload"
+            FROM {table_name}
+            WHERE "kind" = $1 AND "id" > $2
+            ORDER BY "id" ASC
+            LIMIT $3;"#,
+        table_name = get_table_name()
+    );
+
+    let last_event_id = i64::try_from(last_event_id).unwrap_or(i64::MAX);
+    let limit = limit.clamp(0, MAX_BACKLOG_READ_ROWS);
+    super::query_cached(&client, &stmt, &[&kind.to_string(), &last_event_id, &limit])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_events_after) could not execute query: {}", e);
+            PostgisError::Backlog(BacklogError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            let id: i64 = row.try_get("id")?;
+            Ok(BacklogEvent {
+                id: id as u64,
+                payload: row.try_get("payload")?,
+            })
+        })
+        .collect::<Result<Vec<BacklogEvent>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_events_after) could not get events: {}", e);
+            PostgisError::Backlog(BacklogError::DBError)
+        })
+}
+
+/// Gets the number of events dropped from the full backlog since startup
+pub fn dropped_events() -> u64 {
+    DROPPED_EVENTS.load(Ordering::Relaxed)
+}
+
+/// Events persisted within a transaction, sent to subscribers once it
+///  commits
+///
+/// Other events of this service wait until these are sent or dropped.
+pub struct Outbox<T: PersistedEvent> {
+    events: Vec<T>,
+    _order: tokio::sync::MutexGuard<'static, ()>,
+}
+
+impl<T: PersistedEvent> Outbox<T> {
+    /// Sends the events to the subscribers of `channel`, call after the
+    ///  transaction committed
+    pub fn send(self, channel: &broadcast::Sender<T>) {
+        for event in self.events {
+            // No error if nobody is subscribed
+            let _ = channel.send(event);
+        }
+    }
+}
+
+/// Persists events in the backlog within `transaction`, setting their ids
+///
+/// The events are only stored if the transaction commits, the change and
+///  its events are kept or lost together. Send them with [`Outbox::send`]
+///  once committed.
+pub async fn persist<T: PersistedEvent>(
+    transaction: &deadpool_postgres::Transaction<'_>,
+    mut events: Vec<T>,
+) -> Result<Outbox<T>, PostgisError> {
+    let order = EVENT_ORDER.lock().await;
+    for event in events.iter_mut() {
+        let event_id = push_event_in(transaction, T::KIND, &event.encode_to_vec()).await?;
+        event.set_event_id(event_id);
+    }
+
+    Ok(Outbox {
+        events,
+        _order: order,
+    })
+}
+
+/// Persists an event in the backlog in its own transaction
+async fn persist_alone<T: PersistedEvent>(
+    event: T,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Outbox<T>, PostgisError> {
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(persist_alone) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Backlog(BacklogError::Client)
+    })?;
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("(persist_alone) could not create transaction: {}", e);
+        PostgisError::Backlog(BacklogError::DBError)
+    })?;
+
+    let outbox = persist(&transaction, vec![event]).await?;
+    transaction.commit().await.map_err(|e| {
+        postgis_error!("(persist_alone) could not commit transaction: {}", e);
+        PostgisError::Backlog(BacklogError::DBError)
+    })?;
+
+    Ok(outbox)
+}
+
+/// Persists an event that isn't part of a change to flights or zones in
+///  the backlog and sends it to the subscribers of `channel`
+///
+/// An event that couldn't be persisted is still sent, without an event id.
+pub async fn publish<T: PersistedEvent>(channel: &broadcast::Sender<T>, event: T) {
+    let result = match super::DEADPOOL_POSTGIS.get() {
+        Some(pool) => persist_alone(event.clone(), pool).await,
+        None => {
+            postgis_error!("(publish) could not get psql pool.");
+            Err(PostgisError::Backlog(BacklogError::Client))
+        }
+    };
+
+    match result {
+        Ok(outbox) => outbox.send(channel),
+        Err(e) => {
+            postgis_warn!("(publish) could not persist '{}' event: {e}", T::KIND);
+
+            // No error if nobody is subscribed
+            let _ = channel.send(event);
+        }
+    }
+}
+
+/// If an event wasn't already sent to a subscriber that received up to
+///  `last_event_id`
+///
+/// Events that couldn't be persisted have no id and are always new.
+fn is_new_event<T: PersistedEvent>(event: &T, last_event_id: u64) -> bool {
+    event.event_id() == 0 || event.event_id() > last_event_id
+}
+
+/// State of an event stream
+struct EventStreamState<T> {
+    /// Live events
+    receiver: broadcast::Receiver<T>,
+
+    /// Persisted events read but not sent yet
+    queued: VecDeque<T>,
+
+    /// If persisted events after `last_event_id` remain to be read
+    replaying: bool,
+
+    /// Id of the last event sent
+    last_event_id: u64,
+}
+
+impl<T: PersistedEvent> EventStreamState<T> {
+    /// Reads the next persisted events after the last sent one
+    ///
+    /// Stops replaying once the backlog is exhausted or can't be read, live
+    ///  events are sent from then on.
+    async fn read_backlog(&mut self) {
+        let Some(pool) = super::DEADPOOL_POSTGIS.get() else {
+            postgis_warn!("(read_backlog) could not get psql pool, skipping missed events.");
+            self.replaying = false;
+            return;
+        };
+
+        let events = match get_events_after(
+            T::KIND,
+            self.last_event_id,
+            MAX_BACKLOG_READ_ROWS,
+            pool,
+        )
+        .await
+        {
+            Ok(events) => events,
+            Err(e) => {
+                postgis_warn!(
+                    "(read_backlog) could not read missed '{}' events: {e}",
+                    T::KIND
+                );
+                self.replaying = false;
+                return;
+            }
+        };
+
+        self.replaying = events.len() as i64 == MAX_BACKLOG_READ_ROWS;
+        for event in events {
+            match T::decode(event.payload.as_slice()) {
+                Ok(mut decoded) => {
+                    decoded.set_event_id(event.id);
+                    self.queued.push_back(decoded);
+                }
+                Err(e) => {
+                    postgis_warn!(
+                        "(read_backlog) could not decode '{}' event {}: {e}",
+                        T::KIND,
+                        event.id
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Stream of the events published on `channel` after subscribing
+///
+/// With a `last_event_id`, the persisted events after it are sent first.
+///  Events are sent once and in order of their event id, and a subscriber
+///  falling behind the live channel catches up from the backlog once it
+///  received a persisted event.
+///
+/// Ends once shutdown begins so it doesn't hold up the gRPC server.
+pub fn event_stream<T: PersistedEvent>(
+    channel: &broadcast::Sender<T>,
+    last_event_id: Option<u64>,
+) -> impl futures::Stream<Item = T> {
+    // Subscribed before reading the backlog so no event is missed in between
+    let state = EventStreamState {
+        receiver: channel.subscribe(),
+        queued: VecDeque::new(),
+        replaying: last_event_id.is_some(),
+        last_event_id: last_event_id.unwrap_or(0),
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        while !crate::shutdown::is_shutting_down() {
+            if let Some(event) = state.queued.pop_front() {
+                if is_new_event(&event, state.last_event_id) {
+                    state.last_event_id = state.last_event_id.max(event.event_id());
+                    return Some((event, state));
+                }
+
+                continue;
+            }
+
+            if state.replaying {
+                state.read_backlog().await;
+                continue;
+            }
+
+            let poll = std::time::Duration::from_millis(EVENT_STREAM_POLL_INTERVAL_MS);
+            match tokio::time::timeout(poll, state.receiver.recv()).await {
+                Ok(Ok(event)) => state.queued.push_back(event),
+                Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                    postgis_warn!(
+                        "(event_stream) subscriber missed {skipped} '{}' events.",
+                        T::KIND
+                    );
+
+                    // Without a sent event there's no position in the backlog
+                    state.replaying = state.last_event_id > 0;
+                }
+                Ok(Err(broadcast::error::RecvError::Closed)) => return None,
+                Err(_) => (),
+            }
+        }
+
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::server::grpc_server::ComplianceAlert;
+
+    #[test]
+    fn ut_is_new_event() {
+        let alert = |event_id| ComplianceAlert {
+            aircraft_identifier: "A1".to_string(),
+            event_id,
+            ..Default::default()
+        };
+
+        assert!(is_new_event(&alert(11), 10));
+        assert!(!is_new_event(&alert(10), 10));
+        assert!(!is_new_event(&alert(3), 10));
+
+        // Not persisted
+        assert!(is_new_event(&alert(0), 10));
+    }
+}
@@ -2,15 +2,19 @@
 use super::PostgisError;
 use super::DEFAULT_SRID;
 use crate::grpc::server::grpc_server::{
-    BestPathRequest, NodeType, Path as GrpcPath, PathNode as GrpcPathNode, PointZ as GrpcPointZ,
+    BestPathRequest, NodeType, Path as GrpcPath, PathCost as GrpcPathCost,
+    PathNode as GrpcPathNode, PathSegmentCost as GrpcPathSegmentCost, PointZ as GrpcPointZ,
 };
 use crate::postgis::aircraft::get_aircraft_pointz;
+use crate::postgis::flight::FlightConflict;
+use crate::postgis::tags::TagFilter;
 use crate::postgis::vertiport::get_vertiport_centroidz;
 use chrono::Duration;
 use lib_common::time::*;
 use num_traits::FromPrimitive;
 use postgis::ewkb::{LineStringT, PointZ};
-use std::collections::{BinaryHeap, VecDeque};
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 
 /// Look for waypoints within N meters when routing between two points
 ///  Saves computation time by doing shortest path on a smaller graph
@@ -28,12 +32,40 @@ const MAX_PATH_NODE_COUNT_LIMIT: usize = 5;
 /// Max paths to return
 const MAX_PATH_COUNT_LIMIT: usize = 5;
 
+/// Cruise velocity assumed for soft windows if none is provided
+const DEFAULT_CRUISE_VELOCITY_MPS: f32 = 20.0;
+
+/// Shortest soft window accepted, no flight is shorter than this
+const MIN_FLIGHT_DURATION_SECONDS: i64 = 60;
+
+/// Max departure times tried for each path with a soft window
+const MAX_DEPARTURE_CANDIDATES: i64 = 12;
+
+/// Max vertiports returned by [`reachable_vertiports`]
+pub const MAX_REACHABLE_VERTIPORTS: usize = 50;
+
+/// Max waypoints used as relays by [`reachable_vertiports`], nearest first
+const MAX_REACHABLE_WAYPOINTS: usize = 50;
+
+/// Max candidate vertiports routed by [`reachable_vertiports`], nearest
+///  first as the crow flies
+///
+/// More than [`MAX_REACHABLE_VERTIPORTS`] since detours can reorder them.
+const MAX_REACHABLE_CANDIDATES: i64 = 2 * MAX_REACHABLE_VERTIPORTS as i64;
+
+/// Max relative difference between a path's routed distance and its
+///  geodesic length before the distance check reports a mismatch
+pub const DISTANCE_CHECK_TOLERANCE: f64 = 0.01;
+
+/// Number of paths whose routed distance failed the distance check
+pub static DISTANCE_MISMATCH_COUNT: AtomicU64 = AtomicU64::new(0);
+
 impl From<PointZ> for GrpcPointZ {
     fn from(field: PointZ) -> Self {
         Self {
             longitude: field.x,
             latitude: field.y,
-            altitude_meters: field.z as f32,
+            altitude_meters: super::utils::response_meters(field.z),
         }
     }
 }
@@ -54,17 +86,100 @@ impl PartialEq for PathNode {
 #[derive(Debug, Clone)]
 struct Path {
     path: Vec<PathNode>,
-    distance_traversed_meters: f32,
+    /// Summed in double precision, see
+    ///  [`super::utils::haversine_distance_meters`]
+    distance_traversed_meters: f64,
     distance_to_target_meters: f32,
     segment_factor: f32,
+
+    /// Departure and arrival time, set once the path is validated
+    schedule: Option<(DateTime<Utc>, DateTime<Utc>)>,
 }
 
 impl Path {
     fn heuristic(&self) -> f32 {
-        (self.distance_traversed_meters + self.distance_to_target_meters) * self.segment_factor
+        ((self.distance_traversed_meters + self.distance_to_target_meters as f64)
+            * self.segment_factor as f64) as f32
+    }
+
+    /// Breakdown of the routing cost of a completed path
+    ///
+    /// Each segment's distance is split into the progress made toward the
+    ///  target and the remainder, which is the detour flown around no-fly
+    ///  zones. Both are scaled by the segment factor so the segment costs
+    ///  sum to the path's heuristic.
+    fn cost(&self) -> GrpcPathCost {
+        let Some(target) = self.path.last() else {
+            return GrpcPathCost {
+                total_cost: self.heuristic(),
+                segment_factor: self.segment_factor,
+                segments: vec![],
+            };
+        };
+
+        let segments = self
+            .path
+            .windows(2)
+            .enumerate()
+            .map(|(index, pair)| {
+                let distance_meters = super::utils::distance_meters(&pair[0].geom, &pair[1].geom);
+                let progress_meters = super::utils::distance_meters(&pair[0].geom, &target.geom)
+                    - super::utils::distance_meters(&pair[1].geom, &target.geom);
+
+                GrpcPathSegmentCost {
+                    index: index as i32,
+                    distance_cost: progress_meters * self.segment_factor,
+                    zone_avoidance_penalty: (distance_meters - progress_meters)
+                        * self.segment_factor,
+                }
+            })
+            .collect();
+
+        GrpcPathCost {
+            total_cost: self.heuristic(),
+            segment_factor: self.segment_factor,
+            segments,
+        }
+    }
+
+    /// Relative difference between the routed distance and the length of
+    ///  the path on the WGS84 ellipsoid
+    fn distance_error(&self) -> f64 {
+        let geodesic_meters = self
+            .path
+            .windows(2)
+            .map(|pair| super::utils::geodesic_distance_meters(&pair[0].geom, &pair[1].geom))
+            .sum::<f64>();
+
+        if geodesic_meters == 0.0 {
+            return 0.0;
+        }
+
+        ((self.distance_traversed_meters - geodesic_meters) / geodesic_meters).abs()
     }
 }
 
+/// Cross-checks the routed distance of a path against its geodesic length
+///
+/// Returns false and counts the mismatch if they disagree by more than
+///  [`DISTANCE_CHECK_TOLERANCE`].
+fn check_path_distance(path: &Path) -> bool {
+    let error = path.distance_error();
+    if error <= DISTANCE_CHECK_TOLERANCE {
+        return true;
+    }
+
+    DISTANCE_MISMATCH_COUNT.fetch_add(1, AtomicOrdering::Relaxed);
+    postgis_error!(
+        "(check_path_distance) routed distance disagrees with geodesic distance, distance_meters: '{}', error: '{}', mismatches: '{}'.",
+        path.distance_traversed_meters,
+        error,
+        DISTANCE_MISMATCH_COUNT.load(AtomicOrdering::Relaxed)
+    );
+
+    false
+}
+
 // Reverse the ordering so that the BinaryHeap is a min-heap
 impl Ord for Path {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
@@ -133,6 +248,15 @@ pub enum PathError {
 
     /// Flight Plan Intersection
     FlightPlanIntersection,
+
+    /// Invalid cruise velocity
+    InvalidVelocity,
+
+    /// Invalid tag filter
+    InvalidTagFilter,
+
+    /// Invalid max number of segments
+    InvalidMaxSegments,
 }
 
 impl std::fmt::Display for PathError {
@@ -150,7 +274,61 @@ impl std::fmt::Display for PathError {
             PathError::Internal => write!(f, "Internal error."),
             PathError::ZoneIntersection => write!(f, "Zone intersection error."),
             PathError::FlightPlanIntersection => write!(f, "Flight plan intersection error."),
+            PathError::InvalidVelocity => write!(f, "Invalid cruise velocity."),
+            PathError::InvalidTagFilter => write!(f, "Invalid tag filter."),
+            PathError::InvalidMaxSegments => write!(f, "Invalid max number of segments."),
+        }
+    }
+}
+
+/// The time window a path must be flown in
+///
+/// A hard window is the flight's schedule. A soft window is the earliest
+///  departure and latest arrival, and the router picks the departure.
+#[derive(Debug, Copy, Clone)]
+struct TimeWindow {
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    soft: bool,
+    cruise_velocity_mps: f32,
+}
+
+impl TimeWindow {
+    /// Departure and arrival times to try for a path of the given length
+    ///
+    /// With a hard window the flight occupies the whole window. With a soft
+    ///  window departures are spread evenly from the earliest departure to
+    ///  the latest departure that still arrives in time, earliest first.
+    ///  Returns no candidates if the flight doesn't fit in the window.
+    fn candidates(&self, distance_meters: f32) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.soft {
+            return vec![(self.time_start, self.time_end)];
+        }
+
+        let duration_ms = (distance_meters / self.cruise_velocity_mps * 1000.0).ceil() as i64;
+        let Some(duration) = Duration::try_milliseconds(duration_ms) else {
+            return vec![];
+        };
+
+        let latest_departure = self.time_end - duration;
+        if latest_departure < self.time_start {
+            return vec![];
         }
+
+        let slack_ms = (latest_departure - self.time_start).num_milliseconds();
+        let steps = MAX_DEPARTURE_CANDIDATES - 1;
+        let mut candidates = (0..=steps)
+            .filter_map(|i| Duration::try_milliseconds(slack_ms * i / steps))
+            .map(|offset| {
+                (
+                    self.time_start + offset,
+                    self.time_start + offset + duration,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        candidates.dedup();
+        candidates
     }
 }
 
@@ -160,9 +338,16 @@ struct PathRequest {
     target_identifier: String,
     origin_type: NodeType,
     target_type: NodeType,
-    time_start: DateTime<Utc>,
-    time_end: DateTime<Utc>,
+    window: TimeWindow,
     limit: usize,
+    verbose: bool,
+    tag_filter: TagFilter,
+
+    /// Max nodes of a path, from the requested max segments
+    max_node_count: usize,
+
+    /// A max number of segments was requested
+    segments_limited: bool,
 }
 
 impl TryFrom<BestPathRequest> for PathRequest {
@@ -193,7 +378,7 @@ impl TryFrom<BestPathRequest> for PathRequest {
             return Err(PostgisError::BestPath(PathError::InvalidStartNode));
         };
 
-        let Ok(_) = super::utils::check_string(
+        if let Err(e) = super::utils::check_string(
             &request.origin_identifier,
             match origin_type {
                 NodeType::Vertiport => crate::postgis::vertiport::IDENTIFIER_REGEX,
@@ -206,14 +391,15 @@ impl TryFrom<BestPathRequest> for PathRequest {
                     return Err(PostgisError::BestPath(PathError::InvalidStartNode));
                 }
             },
-        ) else {
+        ) {
             postgis_error!(
-                "(try_from BestPathRequest) invalid start node identifier: {:?}",
-                request.origin_identifier
+                "(try_from BestPathRequest) invalid start node identifier {:?}: {}",
+                request.origin_identifier,
+                e
             );
 
             return Err(PostgisError::BestPath(PathError::InvalidStartNode));
-        };
+        }
 
         let Some(target_type) = FromPrimitive::from_i32(request.target_type) else {
             postgis_error!(
@@ -223,7 +409,7 @@ impl TryFrom<BestPathRequest> for PathRequest {
             return Err(PostgisError::BestPath(PathError::InvalidEndNode));
         };
 
-        let Ok(_) = super::utils::check_string(
+        if let Err(e) = super::utils::check_string(
             &request.target_identifier,
             match target_type {
                 NodeType::Vertiport => crate::postgis::vertiport::IDENTIFIER_REGEX,
@@ -235,17 +421,18 @@ impl TryFrom<BestPathRequest> for PathRequest {
                     return Err(PostgisError::BestPath(PathError::InvalidEndNode));
                 }
             },
-        ) else {
+        ) {
             postgis_error!(
-                "(try_from BestPathRequest) invalid end node identifier: {:?}",
-                request.target_identifier
+                "(try_from BestPathRequest) invalid end node identifier {:?}: {}",
+                request.target_identifier,
+                e
             );
 
             return Err(PostgisError::BestPath(PathError::InvalidEndNode));
-        };
+        }
 
         let time_start: DateTime<Utc> = match request.time_start {
-            None => Utc::now(),
+            None => crate::clock::now(),
             Some(time) => time.into(),
         };
 
@@ -255,7 +442,7 @@ impl TryFrom<BestPathRequest> for PathRequest {
         };
 
         let time_end: DateTime<Utc> = match request.time_end {
-            None => Utc::now() + delta,
+            None => crate::clock::now() + delta,
             Some(time) => time.into(),
         };
 
@@ -263,23 +450,76 @@ impl TryFrom<BestPathRequest> for PathRequest {
             return Err(PostgisError::BestPath(PathError::InvalidTimeWindow));
         }
 
-        if time_end < Utc::now() {
+        if time_end < crate::clock::now() {
             return Err(PostgisError::BestPath(PathError::InvalidEndTime));
         }
 
+        let cruise_velocity_mps = request
+            .cruise_velocity_mps
+            .unwrap_or(DEFAULT_CRUISE_VELOCITY_MPS);
+
+        if !cruise_velocity_mps.is_finite() || cruise_velocity_mps <= 0.0 {
+            postgis_error!(
+                "(try_from BestPathRequest) invalid cruise velocity: {}",
+                cruise_velocity_mps
+            );
+            return Err(PostgisError::BestPath(PathError::InvalidVelocity));
+        }
+
+        if request.soft_window
+            && (time_end - time_start).num_seconds() < MIN_FLIGHT_DURATION_SECONDS
+        {
+            postgis_error!(
+                "(try_from BestPathRequest) soft window shorter than the minimum flight duration ({}s).",
+                MIN_FLIGHT_DURATION_SECONDS
+            );
+            return Err(PostgisError::BestPath(PathError::InvalidTimeWindow));
+        }
+
+        let tag_filter = TagFilter::try_from(request.tag_filter).map_err(|e| {
+            postgis_error!("(try_from BestPathRequest) invalid tag filter: {}", e);
+            PostgisError::BestPath(PathError::InvalidTagFilter)
+        })?;
+
+        // Limits below the planner's own only make the search smaller
+        let max_node_count = match request.max_segments {
+            None => MAX_PATH_NODE_COUNT_LIMIT,
+            Some(0) => {
+                postgis_error!("(try_from BestPathRequest) max segments must be at least 1.");
+                return Err(PostgisError::BestPath(PathError::InvalidMaxSegments));
+            }
+            Some(max_segments) => (max_segments as usize)
+                .saturating_add(1)
+                .min(MAX_PATH_NODE_COUNT_LIMIT),
+        };
+
         Ok(PathRequest {
             origin_identifier: request.origin_identifier,
             target_identifier: request.target_identifier,
             origin_type,
             target_type,
-            time_start,
-            time_end,
+            window: TimeWindow {
+                time_start,
+                time_end,
+                soft: request.soft_window,
+                cruise_velocity_mps,
+            },
             limit,
+            verbose: request.verbose,
+            tag_filter,
+            max_node_count,
+            segments_limited: request.max_segments.is_some(),
         })
     }
 }
 
 /// Checks if the path intersects with any no-fly zones or existing flights
+///  matching the tag filter
+///
+/// Returns the conflicts of the first segment of the path that comes too
+///  close to existing flights, none if the path is clear. Intersecting a
+///  no-fly zone is an error.
+#[allow(clippy::too_many_arguments)]
 async fn intersection_checks(
     client: &deadpool_postgres::Client,
     points: Vec<PointZ>,
@@ -288,11 +528,12 @@ async fn intersection_checks(
     time_end: DateTime<Utc>,
     origin_identifier: &str,
     target_identifier: &str,
-) -> Result<(), PostgisError> {
+    tag_filter: &TagFilter,
+) -> Result<Vec<FlightConflict>, PostgisError> {
     // TODO(R5): This is dependent on the aircraft type
     //  Small drones can come closer to one another than large drones
     //  or rideshare vehicles
-    const ALLOWABLE_DISTANCE_M: f64 = 10.0;
+    const ALLOWABLE_DISTANCE_M: f64 = crate::postgis::flight::FLIGHT_SEPARATION_METERS;
     let segments = super::utils::segmentize(points.clone(), time_start, time_end, segment_length)
         .await
         .map_err(|e| {
@@ -308,7 +549,7 @@ async fn intersection_checks(
     };
 
     // Check if any of the zones overlap this path
-    let zone_stmt = crate::postgis::zone::get_zone_intersection_stmt(client).await?;
+    let zone_stmt = crate::postgis::zone::get_zone_intersection_stmt(client, tag_filter).await?;
     if let Ok(result) = client
         .query_one(
             &zone_stmt,
@@ -318,6 +559,7 @@ async fn intersection_checks(
                 &time_end,
                 &origin_identifier,
                 &target_identifier,
+                &tag_filter.tags,
             ],
         )
         .await
@@ -330,7 +572,9 @@ async fn intersection_checks(
     }
 
     // Check if this conflicts with other flights' segments
-    let flights_stmt = crate::postgis::flight::get_flight_intersection_stmt(client).await?;
+    let flights_stmt =
+        crate::postgis::flight::get_flight_intersection_stmt(client, tag_filter).await?;
+    let now = crate::clock::now();
 
     // TODO(R5): Is it faster to do a join statement on the segments table?
     for segment in segments {
@@ -342,6 +586,8 @@ async fn intersection_checks(
                     &ALLOWABLE_DISTANCE_M,
                     &segment.time_start,
                     &segment.time_end,
+                    &tag_filter.tags,
+                    &now,
                 ],
             )
             .await
@@ -359,16 +605,25 @@ async fn intersection_checks(
                 "(intersection_checks) flight path intersects with existing flight paths"
             );
 
+            let conflicts = result
+                .into_iter()
+                .map(FlightConflict::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| {
+                    postgis_error!("(intersection_checks) could not get conflict data: {}", e);
+                    PostgisError::BestPath(PathError::DBError)
+                })?;
+
             postgis_debug!(
-                "(intersection_checks) flight path: {:?}, intersects with: {:?}",
+                "(intersection_checks) flight path: {:?}, conflicts with: {:?}",
                 segment.geom.points,
-                result
+                conflicts
             );
-            return Err(PostgisError::BestPath(PathError::FlightPlanIntersection));
+            return Ok(conflicts);
         }
     }
 
-    Ok(())
+    Ok(vec![])
 }
 
 /// Modified A* algorithm for finding the best path between two points
@@ -376,10 +631,11 @@ async fn intersection_checks(
 async fn mod_a_star(
     origin_node: PathNode,
     target_node: PathNode,
-    time_start: DateTime<Utc>,
-    time_end: DateTime<Utc>,
+    window: TimeWindow,
     waypoints: Vec<super::waypoint::Waypoint>,
     limit: usize,
+    max_node_count: usize,
+    tag_filter: &TagFilter,
 ) -> Result<Vec<Path>, PostgisError> {
     postgis_debug!("(mod_a_star) entry.");
 
@@ -422,6 +678,7 @@ async fn mod_a_star(
         ),
         distance_traversed_meters: 0.,
         segment_factor: 2.0,
+        schedule: None,
     };
 
     potentials.push(starting_path);
@@ -472,12 +729,12 @@ async fn mod_a_star(
                 return Err(PostgisError::BestPath(PathError::NoPath));
             };
 
-            let distance_meters = super::utils::distance_meters(&last.geom, &p.geom);
+            let distance_meters = super::utils::haversine_distance_meters(&last.geom, &p.geom);
             let mut tmp = current.clone();
             tmp.distance_traversed_meters += distance_meters;
 
             // Don't allow flights to exceed max distance
-            if tmp.distance_traversed_meters > MAX_FLIGHT_DISTANCE_METERS {
+            if tmp.distance_traversed_meters > MAX_FLIGHT_DISTANCE_METERS as f64 {
                 continue;
             }
 
@@ -492,7 +749,7 @@ async fn mod_a_star(
                 //  waypoints should only be used to get around a local no-fly zone, to
                 //  so the total path length should be 2 (origin and target) plus a limited
                 //  number of nodes needed to circumvent 1-2 no-fly zones
-                if tmp.path.len() < MAX_PATH_NODE_COUNT_LIMIT {
+                if tmp.path.len() < max_node_count {
                     potentials.push(tmp);
                 }
 
@@ -504,39 +761,57 @@ async fn mod_a_star(
 
             // Path 3D linestring for zone intersection check
             let points = tmp.path.iter().map(|p| p.geom).collect::<Vec<PointZ>>();
-            let segment_length = tmp.distance_traversed_meters / tmp.segment_factor;
+            let segment_length = (tmp.distance_traversed_meters / tmp.segment_factor as f64) as f32;
             postgis_debug!(
                 "(mod_a_star) evaluating path (SF: {}): {:?}",
                 tmp.segment_factor,
                 tmp.path
             );
-            match intersection_checks(
-                &client,
-                points,
-                segment_length,
-                time_start,
-                time_end,
-                &origin_node.identifier,
-                &target_node.identifier,
-            )
-            .await
-            {
-                Ok(_) => (),
-                Err(PostgisError::BestPath(PathError::ZoneIntersection)) => {
-                    continue;
-                }
-                Err(PostgisError::BestPath(PathError::FlightPlanIntersection)) => {
-                    if segment_length < super::flight::MAX_FLIGHT_SEGMENT_LENGTH_METERS {
-                        tmp.segment_factor *= 2.0;
-                        potentials.push(tmp);
-                    }
 
-                    continue;
+            // With a soft window, try departures from earliest to latest
+            //  until one avoids the (time-varying) zones and flights
+            let candidates = window.candidates(tmp.distance_traversed_meters as f32);
+            if candidates.is_empty() {
+                postgis_debug!("(mod_a_star) path too long for the time window.");
+                continue;
+            }
+
+            let mut flight_intersection = false;
+            for (departure, arrival) in candidates {
+                match intersection_checks(
+                    &client,
+                    points.clone(),
+                    segment_length,
+                    departure,
+                    arrival,
+                    &origin_node.identifier,
+                    &target_node.identifier,
+                    tag_filter,
+                )
+                .await
+                {
+                    Ok(conflicts) if conflicts.is_empty() => {
+                        tmp.schedule = Some((departure, arrival));
+                        break;
+                    }
+                    Ok(_) => flight_intersection = true,
+                    Err(PostgisError::BestPath(PathError::ZoneIntersection)) => (),
+                    Err(e) => {
+                        postgis_error!("(mod_a_star) intersection checks failed: {}", e);
+                        return Err(e);
+                    }
                 }
-                Err(e) => {
-                    postgis_error!("(mod_a_star) intersection checks failed: {}", e);
-                    return Err(e);
+            }
+
+            if tmp.schedule.is_none() {
+                if flight_intersection
+                    && segment_length < super::flight::MAX_FLIGHT_SEGMENT_LENGTH_METERS
+                {
+                    tmp.segment_factor *= 2.0;
+                    potentials.push(tmp);
                 }
+
+                continue;
             }
 
             // Valid routes are pushed
@@ -563,9 +838,14 @@ async fn mod_a_star(
 ///
 /// No-Fly zones can extend flights, isolate aircraft, or disable vertiports entirely.
 #[cfg(not(tarpaulin_include))]
-pub async fn best_path(request: BestPathRequest) -> Result<Vec<GrpcPath>, PostgisError> {
+pub async fn best_path(
+    request: BestPathRequest,
+    distance_check: bool,
+) -> Result<Vec<GrpcPath>, PostgisError> {
     postgis_info!("(best_path) request: {:?}", request);
+    let start = std::time::Instant::now();
     let request = PathRequest::try_from(request)?;
+    let verbose = request.verbose;
 
     let origin_geom = match request.origin_type {
         NodeType::Vertiport => get_vertiport_centroidz(&request.origin_identifier).await?,
@@ -619,16 +899,47 @@ pub async fn best_path(request: BestPathRequest) -> Result<Vec<GrpcPath>, Postgi
         geom: target_geom,
     };
 
+    // Even the direct route must fit in a soft window
+    if request
+        .window
+        .candidates(super::utils::distance_meters(&origin_geom, &target_geom))
+        .is_empty()
+    {
+        postgis_error!("(best_path) time window is shorter than the minimum flight duration.");
+        return Err(PostgisError::BestPath(PathError::InvalidTimeWindow));
+    }
+
     let result = mod_a_star(
         origin_node,
         target_node,
-        request.time_start,
-        request.time_end,
+        request.window,
         waypoints,
         request.limit,
+        request.max_node_count,
+        &request.tag_filter,
     )
     .await?;
 
+    if result.is_empty() && request.segments_limited {
+        postgis_warn!(
+            "(best_path) no path found within {} segments.",
+            request.max_node_count - 1
+        );
+        return Err(PostgisError::BestPath(PathError::NoPath));
+    }
+
+    if distance_check {
+        result.iter().for_each(|path| {
+            check_path_distance(path);
+        });
+    }
+
+    postgis_info!(
+        count = result.len(),
+        duration_ms = start.elapsed().as_millis();
+        "(best_path) success."
+    );
+
     Ok(result
         .into_iter()
         .map(|path| GrpcPath {
@@ -643,11 +954,436 @@ pub async fn best_path(request: BestPathRequest) -> Result<Vec<GrpcPath>, Postgi
                     geom: Some(p.geom.into()),
                 })
                 .collect(),
-            distance_meters: path.distance_traversed_meters,
+            distance_meters: super::utils::response_meters(path.distance_traversed_meters),
+            time_departure: path.schedule.map(|(departure, _)| departure.into()),
+            time_arrival: path.schedule.map(|(_, arrival)| arrival.into()),
+            cost: verbose.then(|| path.cost()),
         })
         .collect::<Vec<GrpcPath>>())
 }
 
+/// Ratio of the length of a path to the distance between its ends
+///
+/// `None` if the path ends where it starts.
+fn detour_factor(points: &[PointZ]) -> Option<f64> {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return None;
+    };
+
+    let direct_meters = super::utils::distance_meters(first, last) as f64;
+    if direct_meters == 0.0 {
+        return None;
+    }
+
+    let routed_meters = points
+        .windows(2)
+        .map(|pair| super::utils::distance_meters(&pair[0], &pair[1]) as f64)
+        .sum::<f64>();
+
+    Some(routed_meters / direct_meters)
+}
+
+/// How much longer the best route between two vertiports is than the
+///  direct great-circle distance between them
+///
+/// A factor of 1 is a direct route, large factors point to airspace
+///  forcing detours. Fails like [`best_path`], with
+///  [`PathError::NoPath`] if no route exists in the time window.
+#[cfg(not(tarpaulin_include))]
+pub async fn route_detour_factor(
+    origin_identifier: &str,
+    target_identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Result<f64, PostgisError> {
+    postgis_debug!(
+        "(route_detour_factor) entry, origin: '{origin_identifier}', target: '{target_identifier}'."
+    );
+
+    let request = BestPathRequest {
+        origin_identifier: origin_identifier.to_string(),
+        target_identifier: target_identifier.to_string(),
+        origin_type: NodeType::Vertiport as i32,
+        target_type: NodeType::Vertiport as i32,
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        limit: 1,
+        ..Default::default()
+    };
+
+    let Some(path) = best_path(request, false).await?.into_iter().next() else {
+        postgis_error!("(route_detour_factor) no route found.");
+        return Err(PostgisError::BestPath(PathError::NoPath));
+    };
+
+    let points = path
+        .path
+        .iter()
+        .filter_map(|node| node.geom.as_ref())
+        .map(|geom| {
+            PointZ::new(
+                geom.longitude,
+                geom.latitude,
+                geom.altitude_meters as f64,
+                Some(DEFAULT_SRID),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let Some(factor) = detour_factor(&points) else {
+        postgis_error!("(route_detour_factor) origin and target are at the same location.");
+        return Err(PostgisError::BestPath(PathError::InvalidEndNode));
+    };
+
+    postgis_debug!("(route_detour_factor) success, factor: {factor}.");
+    Ok(factor)
+}
+
+/// A vertiport reachable from an origin vertiport
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReachableVertiport {
+    /// The vertiport identifier
+    pub identifier: String,
+
+    /// The shortest unobstructed path to the vertiport
+    pub path: GrpcPath,
+}
+
+/// Shortest distances from the first node to every other node
+///
+/// The nodes form a complete graph minus the `blocked` edges (pairs of
+///  node indices, lower index first). Only waypoints relay paths, a path
+///  never passes through a vertiport. Paths longer than
+///  `max_distance_meters` are discarded.
+///
+/// Returns the distance and the node indices of the path for each
+///  reachable node.
+fn shortest_paths(
+    nodes: &[PathNode],
+    blocked: &HashSet<(usize, usize)>,
+    max_distance_meters: f32,
+) -> Vec<Option<(f32, Vec<usize>)>> {
+    let mut distances = vec![f32::INFINITY; nodes.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; nodes.len()];
+    let mut visited = vec![false; nodes.len()];
+    if let Some(first) = distances.first_mut() {
+        *first = 0.0;
+    }
+
+    // Dense graph, so a linear scan for the closest node is fine
+    while let Some(current) = (0..nodes.len())
+        .filter(|&i| !visited[i] && distances[i].is_finite())
+        .min_by(|&a, &b| distances[a].total_cmp(&distances[b]))
+    {
+        visited[current] = true;
+        if current != 0 && nodes[current].node_type != NodeType::Waypoint as i32 {
+            continue;
+        }
+
+        for next in 0..nodes.len() {
+            if visited[next] || blocked.contains(&(current.min(next), current.max(next))) {
+                continue;
+            }
+
+            let distance = distances[current]
+                + super::utils::distance_meters(&nodes[current].geom, &nodes[next].geom);
+
+            if distance <= max_distance_meters && distance < distances[next] {
+                distances[next] = distance;
+                previous[next] = Some(current);
+            }
+        }
+    }
+
+    (0..nodes.len())
+        .map(|i| {
+            if !distances[i].is_finite() {
+                return None;
+            }
+
+            let mut path = vec![i];
+            while let Some(p) = previous[*path.last()?] {
+                path.push(p);
+            }
+
+            path.reverse();
+            Some((distances[i], path))
+        })
+        .collect()
+}
+
+/// Finds the edges between nodes that cross an active zone
+///
+/// The zones of the vertiports at either end of an edge don't block it.
+async fn get_blocked_edges(
+    client: &deadpool_postgres::Client,
+    nodes: &[PathNode],
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Result<HashSet<(usize, usize)>, PostgisError> {
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"WITH "nodes" AS (
+                SELECT ("dumped"."dp").path[1] AS "idx", ("dumped"."dp").geom AS "geom"
+                FROM (
+                    SELECT ST_DumpPoints($1::GEOMETRY(LINESTRINGZ, {DEFAULT_SRID})) AS "dp"
+                ) AS "dumped"
+            )
+            SELECT "a"."idx" AS "a", "b"."idx" AS "b"
+            FROM "nodes" AS "a"
+            JOIN "nodes" AS "b" ON "a"."idx" < "b"."idx"
+            WHERE EXISTS (
+                SELECT 1 FROM {zones_table_name} AS "zones"
+                WHERE
+                    ("zones"."time_start" <= $3 OR "zones"."time_start" IS NULL)
+                    AND ("zones"."time_end" >= $2 OR "zones"."time_end" IS NULL)
+                    AND "zones"."deleted_at" IS NULL
+                    AND "zones"."identifier" <> ($4::TEXT[])["a"."idx"]
+                    AND "zones"."identifier" <> ($4::TEXT[])["b"."idx"]
+                    AND ST_3DIntersects("zones"."geom", ST_MakeLine("a"."geom", "b"."geom"))
+            );"#,
+            zones_table_name = super::zone::get_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_blocked_edges) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::BestPath(PathError::DBError)
+        })?;
+
+    let geom = LineStringT {
+        points: nodes.iter().map(|n| n.geom).collect(),
+        srid: Some(DEFAULT_SRID),
+    };
+
+    // Waypoints don't have zones
+    let zone_identifiers = nodes
+        .iter()
+        .map(|n| match n.node_type == NodeType::Waypoint as i32 {
+            true => String::new(),
+            false => n.identifier.clone(),
+        })
+        .collect::<Vec<String>>();
+
+    client
+        .query(&stmt, &[&geom, &time_start, &time_end, &zone_identifiers])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_blocked_edges) could not execute query: {}", e);
+            PostgisError::BestPath(PathError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            // ST_DumpPoints indices start at 1
+            let a: i32 = row.try_get("a")?;
+            let b: i32 = row.try_get("b")?;
+            Ok(((a - 1) as usize, (b - 1) as usize))
+        })
+        .collect::<Result<HashSet<(usize, usize)>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_blocked_edges) could not get edge data: {}", e);
+            PostgisError::BestPath(PathError::DBError)
+        })
+}
+
+/// Finds the vertiports reachable from an origin vertiport within a
+///  distance budget, avoiding the zones active during the time window
+///
+/// This is a one-to-many version of [`best_path`] over the same graph of
+///  waypoints at each flight level. Conflicts with other flights are not
+///  considered, they're checked when the flight itself is routed.
+///
+/// Returns at most [`MAX_REACHABLE_VERTIPORTS`], nearest first.
+pub async fn reachable_vertiports(
+    origin_identifier: &str,
+    max_distance_meters: f32,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<ReachableVertiport>, PostgisError> {
+    postgis_debug!("(reachable_vertiports) entry, origin: '{origin_identifier}'.");
+    if let Err(e) = super::utils::check_string(
+        origin_identifier,
+        crate::postgis::vertiport::IDENTIFIER_REGEX,
+    ) {
+        postgis_error!(
+            "(reachable_vertiports) invalid origin identifier {}: {}",
+            origin_identifier,
+            e
+        );
+        return Err(PostgisError::BestPath(PathError::InvalidStartNode));
+    }
+
+    if !max_distance_meters.is_finite()
+        || max_distance_meters <= 0.0
+        || max_distance_meters > MAX_FLIGHT_DISTANCE_METERS
+    {
+        postgis_error!(
+            "(reachable_vertiports) invalid max distance: {}",
+            max_distance_meters
+        );
+        return Err(PostgisError::BestPath(PathError::InvalidLimit));
+    }
+
+    if time_end < time_start {
+        postgis_error!("(reachable_vertiports) time_end is before time_start.");
+        return Err(PostgisError::BestPath(PathError::InvalidTimeWindow));
+    }
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(reachable_vertiports) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::BestPath(PathError::Client)
+    })?;
+
+    let origin_geom = get_vertiport_centroidz(origin_identifier).await?;
+
+    // Candidate vertiports within the budget as the crow flies, capped
+    //  before the blocked edges are looked up for every pair of nodes
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT
+                "identifier",
+                ST_Force3DZ(ST_Centroid("geom"), "altitude_meters") AS "geom"
+            FROM {table_name}
+            WHERE
+                "identifier" <> $1
+                AND ST_DWithin(
+                    ST_Centroid("geom")::GEOGRAPHY,
+                    $2::GEOGRAPHY,
+                    $3::FLOAT(4),
+                    false
+                )
+            ORDER BY
+                ST_Distance(ST_Centroid("geom")::GEOGRAPHY, $2::GEOGRAPHY, false),
+                "identifier"
+            LIMIT $4;"#,
+            table_name = super::vertiport::get_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(reachable_vertiports) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::BestPath(PathError::DBError)
+        })?;
+
+    let vertiports = client
+        .query(
+            &stmt,
+            &[
+                &origin_identifier,
+                &origin_geom,
+                &max_distance_meters,
+                &MAX_REACHABLE_CANDIDATES,
+            ],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(reachable_vertiports) could not execute query: {}", e);
+            PostgisError::BestPath(PathError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            Ok(PathNode {
+                node_type: NodeType::Vertiport as i32,
+                identifier: row.try_get("identifier")?,
+                geom: row.try_get("geom")?,
+            })
+        })
+        .collect::<Result<Vec<PathNode>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(reachable_vertiports) could not get vertiport data: {}", e);
+            PostgisError::BestPath(PathError::DBError)
+        })?;
+
+    if vertiports.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut waypoints = crate::postgis::waypoint::get_waypoints_near_geometry(
+        &postgis::ewkb::GeometryT::Point(origin_geom),
+        max_distance_meters,
+    )
+    .await?;
+
+    let distance_to_origin = |w: &super::waypoint::Waypoint| {
+        super::utils::distance_meters(
+            &PointZ::new(w.geom.x, w.geom.y, origin_geom.z, w.geom.srid),
+            &origin_geom,
+        )
+    };
+
+    waypoints.sort_by(|a, b| distance_to_origin(a).total_cmp(&distance_to_origin(b)));
+    waypoints.truncate(MAX_REACHABLE_WAYPOINTS);
+
+    let mut nodes = vec![PathNode {
+        node_type: NodeType::Vertiport as i32,
+        identifier: origin_identifier.to_string(),
+        geom: origin_geom,
+    }];
+
+    nodes.extend(waypoints.into_iter().flat_map(|w| {
+        FLIGHT_LEVELS.iter().map(move |fl| PathNode {
+            node_type: NodeType::Waypoint as i32,
+            identifier: w.identifier.clone(),
+            geom: PointZ::new(w.geom.x, w.geom.y, *fl as f64, w.geom.srid),
+        })
+    }));
+
+    nodes.extend(vertiports);
+
+    let blocked = get_blocked_edges(&client, &nodes, time_start, time_end).await?;
+    let mut reachable = shortest_paths(&nodes, &blocked, max_distance_meters)
+        .into_iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(i, _)| nodes[*i].node_type == NodeType::Vertiport as i32)
+        .filter_map(|(i, result)| {
+            let (distance_meters, path) = result?;
+            Some(ReachableVertiport {
+                identifier: nodes[i].identifier.clone(),
+                path: GrpcPath {
+                    path: path
+                        .iter()
+                        .enumerate()
+                        .map(|(index, n)| GrpcPathNode {
+                            index: index as i32,
+                            node_type: nodes[*n].node_type,
+                            identifier: nodes[*n].identifier.clone(),
+                            geom: Some(nodes[*n].geom.into()),
+                        })
+                        .collect(),
+                    distance_meters,
+                    time_departure: None,
+                    time_arrival: None,
+                    cost: None,
+                },
+            })
+        })
+        .collect::<Vec<ReachableVertiport>>();
+
+    reachable.sort_by(|a, b| {
+        a.path
+            .distance_meters
+            .total_cmp(&b.path.distance_meters)
+            .then_with(|| a.identifier.cmp(&b.identifier))
+    });
+
+    reachable.truncate(MAX_REACHABLE_VERTIPORTS);
+    postgis_debug!(
+        "(reachable_vertiports) found {} reachable vertiports.",
+        reachable.len()
+    );
+
+    Ok(reachable)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -663,6 +1399,11 @@ mod tests {
             time_start: None,
             time_end: None,
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request);
@@ -679,6 +1420,11 @@ mod tests {
             time_start: None,
             time_end: None,
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request).unwrap_err();
@@ -695,6 +1441,11 @@ mod tests {
             time_start: None,
             time_end: None,
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request).unwrap_err();
@@ -715,6 +1466,11 @@ mod tests {
             time_start: Some(time_start),
             time_end: Some(time_end.clone()),
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request).unwrap_err();
@@ -729,6 +1485,11 @@ mod tests {
             time_start: None,
             time_end: Some(time_end),
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request).unwrap_err();
@@ -745,6 +1506,11 @@ mod tests {
             time_start: Some(time_start),
             time_end: None,
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request).unwrap_err();
@@ -766,12 +1532,47 @@ mod tests {
             time_start: Some(time_start),
             time_end: Some(time_end),
             limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request).unwrap_err();
         assert_eq!(result, PostgisError::BestPath(PathError::InvalidEndTime));
     }
 
+    #[test]
+    fn ut_request_time_end_replayed() {
+        // Window in the past of the system clock
+        let time_start = Utc::now() - Duration::try_days(10).unwrap();
+        let time_end = time_start + Duration::try_hours(1).unwrap();
+        let request = BestPathRequest {
+            origin_identifier: uuid::Uuid::new_v4().to_string(),
+            target_identifier: uuid::Uuid::new_v4().to_string(),
+            origin_type: grpc_server::NodeType::Vertiport as i32,
+            target_type: grpc_server::NodeType::Vertiport as i32,
+            time_start: Some(time_start.into()),
+            time_end: Some(time_end.into()),
+            limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
+        };
+
+        // Valid while the replayed time is before the end time
+        let clock = crate::clock::freeze(time_start);
+        PathRequest::try_from(request.clone()).unwrap();
+
+        // Invalid once the replayed time passes it, without waiting
+        clock.advance(Duration::try_hours(2).unwrap());
+        let result = PathRequest::try_from(request).unwrap_err();
+        assert_eq!(result, PostgisError::BestPath(PathError::InvalidEndTime));
+    }
+
     #[test]
     fn ut_request_invalid_limit() {
         // End time (assumed) is before start time
@@ -787,6 +1588,11 @@ mod tests {
             time_start: Some(time_start),
             time_end: Some(time_end),
             limit: -1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
         };
 
         let result = PathRequest::try_from(request.clone()).unwrap_err();
@@ -801,6 +1607,234 @@ mod tests {
         assert_eq!(result, PostgisError::BestPath(PathError::InvalidLimit));
     }
 
+    #[test]
+    fn ut_request_max_segments() {
+        let mut request = BestPathRequest {
+            origin_identifier: uuid::Uuid::new_v4().to_string(),
+            target_identifier: uuid::Uuid::new_v4().to_string(),
+            origin_type: grpc_server::NodeType::Vertiport as i32,
+            target_type: grpc_server::NodeType::Vertiport as i32,
+            limit: 1,
+            ..Default::default()
+        };
+
+        let result = PathRequest::try_from(request.clone()).unwrap();
+        assert_eq!(result.max_node_count, MAX_PATH_NODE_COUNT_LIMIT);
+        assert!(!result.segments_limited);
+
+        // Direct routes only
+        request.max_segments = Some(1);
+        let result = PathRequest::try_from(request.clone()).unwrap();
+        assert_eq!(result.max_node_count, 2);
+        assert!(result.segments_limited);
+
+        request.max_segments = Some(u32::MAX);
+        let result = PathRequest::try_from(request.clone()).unwrap();
+        assert_eq!(result.max_node_count, MAX_PATH_NODE_COUNT_LIMIT);
+
+        request.max_segments = Some(0);
+        let result = PathRequest::try_from(request).unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::BestPath(PathError::InvalidMaxSegments)
+        );
+    }
+
+    #[test]
+    fn ut_request_soft_window() {
+        let time_start: Timestamp = (Utc::now() + Duration::try_hours(1).unwrap()).into();
+        let time_end: Timestamp = (Utc::now()
+            + Duration::try_hours(1).unwrap()
+            + Duration::try_seconds(MIN_FLIGHT_DURATION_SECONDS - 1).unwrap())
+        .into();
+
+        let mut request = BestPathRequest {
+            origin_identifier: uuid::Uuid::new_v4().to_string(),
+            target_identifier: uuid::Uuid::new_v4().to_string(),
+            origin_type: grpc_server::NodeType::Vertiport as i32,
+            target_type: grpc_server::NodeType::Vertiport as i32,
+            time_start: Some(time_start),
+            time_end: Some(time_end),
+            limit: 1,
+            soft_window: false,
+            cruise_velocity_mps: None,
+            verbose: false,
+            tag_filter: None,
+            max_segments: None,
+        };
+
+        // Short windows are fine when hard
+        let result = PathRequest::try_from(request.clone()).unwrap();
+        assert!(!result.window.soft);
+
+        // Shorter than the minimum flight duration
+        request.soft_window = true;
+        let result = PathRequest::try_from(request.clone()).unwrap_err();
+        assert_eq!(result, PostgisError::BestPath(PathError::InvalidTimeWindow));
+
+        request.time_end = None;
+        let result = PathRequest::try_from(request.clone()).unwrap();
+        assert!(result.window.soft);
+        assert_eq!(
+            result.window.cruise_velocity_mps,
+            DEFAULT_CRUISE_VELOCITY_MPS
+        );
+
+        request.cruise_velocity_mps = Some(0.0);
+        let result = PathRequest::try_from(request.clone()).unwrap_err();
+        assert_eq!(result, PostgisError::BestPath(PathError::InvalidVelocity));
+    }
+
+    #[test]
+    fn ut_window_candidates() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_hours(1).unwrap();
+        let mut window = TimeWindow {
+            time_start,
+            time_end,
+            soft: false,
+            cruise_velocity_mps: 10.0,
+        };
+
+        // Hard windows are the schedule
+        assert_eq!(window.candidates(1000.0), vec![(time_start, time_end)]);
+
+        // 1000 meters at 10 m/s
+        window.soft = true;
+        let duration = Duration::try_seconds(100).unwrap();
+        let candidates = window.candidates(1000.0);
+        assert_eq!(candidates.len(), MAX_DEPARTURE_CANDIDATES as usize);
+        assert_eq!(candidates[0], (time_start, time_start + duration));
+        assert_eq!(candidates.last().unwrap(), &(time_end - duration, time_end));
+
+        // Every departure falls within the window and arrives in time
+        for (departure, arrival) in candidates.iter() {
+            assert!(*departure >= time_start);
+            assert!(*arrival <= time_end);
+            assert_eq!(*arrival - *departure, duration);
+        }
+
+        // Exactly fits
+        let candidates = window.candidates(36_000.0);
+        assert_eq!(candidates, vec![(time_start, time_end)]);
+
+        // Doesn't fit
+        assert!(window.candidates(36_010.0).is_empty());
+    }
+
+    #[test]
+    fn ut_shortest_paths() {
+        let node = |node_type: NodeType, identifier: &str, x: f64, y: f64| PathNode {
+            node_type: node_type as i32,
+            identifier: identifier.to_string(),
+            geom: PointZ::new(x, y, 0.0, Some(DEFAULT_SRID)),
+        };
+
+        let nodes = vec![
+            node(NodeType::Vertiport, "origin", 4.90, 52.37),
+            node(NodeType::Vertiport, "direct", 4.91, 52.37),
+            node(NodeType::Waypoint, "waypoint", 4.90, 52.38),
+            node(NodeType::Vertiport, "around", 4.91, 52.38),
+            node(NodeType::Vertiport, "far", 5.50, 52.37),
+            node(NodeType::Vertiport, "isolated", 4.92, 52.37),
+        ];
+
+        // origin -> around crosses a zone, as do all edges to isolated
+        //  except from other vertiports, which don't relay
+        let blocked = HashSet::from([(0, 3), (0, 5), (2, 5)]);
+        let result = shortest_paths(&nodes, &blocked, 5_000.0);
+
+        assert_eq!(result[0], Some((0.0, vec![0])));
+        assert_eq!(result[1].as_ref().unwrap().1, vec![0, 1]);
+
+        // Around the zone through the waypoint
+        let (distance, path) = result[3].clone().unwrap();
+        assert_eq!(path, vec![0, 2, 3]);
+        assert!(distance > super::super::utils::distance_meters(&nodes[0].geom, &nodes[3].geom));
+        assert!(distance <= 5_000.0);
+
+        // Over budget
+        assert!(result[4].is_none());
+
+        // Only reachable through a vertiport
+        assert!(result[5].is_none());
+
+        // Smaller budget, the detour is too long
+        let result = shortest_paths(&nodes, &blocked, 1_000.0);
+        assert!(result[1].is_some());
+        assert!(result[3].is_none());
+    }
+
+    #[test]
+    fn ut_path_cost() {
+        let node = |identifier: &str, x: f64, y: f64| PathNode {
+            node_type: NodeType::Waypoint as i32,
+            identifier: identifier.to_string(),
+            geom: PointZ {
+                x,
+                y,
+                z: 80.0,
+                srid: Some(DEFAULT_SRID),
+            },
+        };
+
+        // Detour to the north of the direct line between origin and target
+        let nodes = vec![
+            node("origin", 4.90, 52.37),
+            node("waypoint", 4.95, 52.40),
+            node("target", 5.00, 52.37),
+        ];
+
+        let distance_traversed_meters = nodes
+            .windows(2)
+            .map(|pair| {
+                super::super::utils::haversine_distance_meters(&pair[0].geom, &pair[1].geom)
+            })
+            .sum::<f64>();
+
+        let path = Path {
+            path: nodes.clone(),
+            distance_traversed_meters,
+            distance_to_target_meters: 0.,
+            segment_factor: 4.0,
+            schedule: None,
+        };
+
+        let cost = path.cost();
+        assert_eq!(cost.total_cost, path.heuristic());
+        assert_eq!(cost.segment_factor, 4.0);
+        assert_eq!(cost.segments.len(), 2);
+        assert_eq!(cost.segments[0].index, 0);
+        assert_eq!(cost.segments[1].index, 1);
+
+        let sum = cost
+            .segments
+            .iter()
+            .map(|s| s.distance_cost + s.zone_avoidance_penalty)
+            .sum::<f32>();
+        assert!((sum - cost.total_cost).abs() < cost.total_cost * 1e-4);
+
+        // Progress toward the target adds up to the direct distance
+        let direct = super::super::utils::distance_meters(&nodes[0].geom, &nodes[2].geom);
+        let progress = cost.segments.iter().map(|s| s.distance_cost).sum::<f32>();
+        assert!((progress - direct * 4.0).abs() < direct * 1e-3);
+        assert!(cost.segments[0].zone_avoidance_penalty > 0.0);
+
+        // Direct path has no zone avoidance penalty
+        let path = Path {
+            path: vec![nodes[0].clone(), nodes[2].clone()],
+            distance_traversed_meters: direct as f64,
+            distance_to_target_meters: 0.,
+            segment_factor: 2.0,
+            schedule: None,
+        };
+
+        let cost = path.cost();
+        assert_eq!(cost.segments.len(), 1);
+        assert!(cost.segments[0].zone_avoidance_penalty.abs() < 1e-3);
+        assert_eq!(cost.total_cost, direct * 2.0);
+    }
+
     #[test]
     fn ut_path_order() {
         // End time (assumed) is before start time
@@ -811,6 +1845,7 @@ mod tests {
             distance_traversed_meters: 2.,
             distance_to_target_meters: 0.,
             segment_factor: 2.0,
+            schedule: None,
         };
 
         let path2 = Path {
@@ -818,6 +1853,7 @@ mod tests {
             distance_traversed_meters: 1.,
             distance_to_target_meters: 0.,
             segment_factor: 2.0,
+            schedule: None,
         };
 
         paths.push(path1);
@@ -826,4 +1862,67 @@ mod tests {
         assert_eq!(paths.pop().unwrap().distance_traversed_meters, 1.);
         assert_eq!(paths.pop().unwrap().distance_traversed_meters, 2.);
     }
+
+    #[test]
+    fn ut_check_path_distance() {
+        let node = |identifier: &str, x: f64, y: f64| PathNode {
+            node_type: NodeType::Waypoint as i32,
+            identifier: identifier.to_string(),
+            geom: PointZ {
+                x,
+                y,
+                z: 80.0,
+                srid: Some(DEFAULT_SRID),
+            },
+        };
+
+        // 10 km along the equator
+        let nodes = vec![node("origin", 0.0, 0.0), node("target", 0.0898315284, 0.0)];
+        let mut path = Path {
+            path: nodes,
+            distance_traversed_meters: super::super::utils::haversine_distance_meters(
+                &PointZ::new(0.0, 0.0, 80.0, Some(DEFAULT_SRID)),
+                &PointZ::new(0.0898315284, 0.0, 80.0, Some(DEFAULT_SRID)),
+            ),
+            distance_to_target_meters: 0.,
+            segment_factor: 2.0,
+            schedule: None,
+        };
+
+        assert!((path.distance_traversed_meters - 10_000.0).abs() < 10_000.0 * 0.005);
+        assert!(path.distance_error() < 0.005);
+
+        let mismatches = DISTANCE_MISMATCH_COUNT.load(AtomicOrdering::Relaxed);
+        assert!(check_path_distance(&path));
+
+        // A distance in planar degrees is far off
+        path.distance_traversed_meters = 0.0898315284;
+        assert!(!check_path_distance(&path));
+        assert!(DISTANCE_MISMATCH_COUNT.load(AtomicOrdering::Relaxed) > mismatches);
+    }
+
+    #[test]
+    fn ut_detour_factor() {
+        let point = |x: f64, y: f64| PointZ::new(x, y, 0.0, Some(DEFAULT_SRID));
+
+        // Straight line, the middle point is on the way
+        let direct = [point(4.90, 52.37), point(4.91, 52.37), point(4.92, 52.37)];
+        let factor = detour_factor(&direct).unwrap();
+        assert!((factor - 1.0).abs() < 1e-3);
+
+        // Around a zone between the two ends
+        let detour = [point(4.90, 52.37), point(4.91, 52.38), point(4.92, 52.37)];
+        let factor = detour_factor(&detour).unwrap();
+        assert!(factor > 1.0);
+
+        let legs = super::super::utils::distance_meters(&detour[0], &detour[1])
+            + super::super::utils::distance_meters(&detour[1], &detour[2]);
+        let direct_meters = super::super::utils::distance_meters(&detour[0], &detour[2]);
+        assert!((factor - (legs / direct_meters) as f64).abs() < 1e-3);
+
+        // Back where it started
+        let round_trip = [point(4.90, 52.37), point(4.91, 52.37), point(4.90, 52.37)];
+        assert_eq!(detour_factor(&round_trip), None);
+        assert_eq!(detour_factor(&[]), None);
+    }
 }
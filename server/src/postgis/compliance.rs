@@ -0,0 +1,380 @@
+//! This module contains periodic compliance checks on live aircraft.
+//!
+//! Airborne aircraft without an active flight (bound through the
+//!  aircraft identifier or session id) are published on the compliance
+//!  alert channel, which is streamed by `streamComplianceAlerts`. Alerts
+//!  are also persisted in the event backlog (see [`super::backlog`]) so that
+//!  reconnecting subscribers can receive the alerts they missed.
+//!
+//! An aircraft is considered airborne if its position is recent and it
+//!  either declared an airborne operational status or is moving faster
+//!  than a ground vehicle would taxi.
+
+use super::backlog::{self, EventKind, PersistedEvent};
+use super::shard::ShardRows;
+use super::{PostgisError, PsqlError};
+use crate::grpc::server::grpc_server::ComplianceAlert;
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use postgis::ewkb::PointZ;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+use tokio_postgres::types::ToSql;
+
+/// Capacity of the alert channel, slow subscribers miss older alerts
+const ALERT_CHANNEL_CAPACITY: usize = 256;
+
+/// Aircraft without a position update in this time are not checked
+const AIRBORNE_MAX_POSITION_AGE_SECS: i64 = 60;
+
+/// Min ground speed of an aircraft considered airborne
+const AIRBORNE_MIN_GROUND_SPEED_MPS: f32 = 5.0;
+
+/// Min vertical speed of an aircraft considered airborne
+const AIRBORNE_MIN_VERTICAL_SPEED_MPS: f32 = 1.0;
+
+/// Condition on the aircraft table matching airborne aircraft, $1 is the
+///  oldest accepted position update, $2 if simulated aircraft are included
+///  and $3, $4 are the min ground and vertical speeds
+const AIRBORNE_CONDITION: &str = r#""geom" IS NOT NULL
+    AND "last_position_update" >= $1
+    AND ($2 OR NOT COALESCE("simulated", FALSE))
+    AND (
+        "op_status" = 'Airborne'
+        OR ABS(COALESCE("velocity_horizontal_ground_mps", 0)) >= $3
+        OR ABS(COALESCE("velocity_vertical_mps", 0)) >= $4
+    )"#;
+
+/// Compliance alert channel
+pub static ALERTS: Lazy<broadcast::Sender<ComplianceAlert>> =
+    Lazy::new(|| broadcast::channel(ALERT_CHANNEL_CAPACITY).0);
+
+impl PersistedEvent for ComplianceAlert {
+    const KIND: EventKind = EventKind::ComplianceAlert;
+
+    fn event_id(&self) -> u64 {
+        self.event_id
+    }
+
+    fn set_event_id(&mut self, event_id: u64) {
+        self.event_id = event_id;
+    }
+}
+
+/// An airborne aircraft without an active flight
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnboundAircraft {
+    /// The aircraft identifier
+    pub identifier: String,
+
+    /// Last known position
+    pub position: PointZ,
+}
+
+/// Tracks unbound aircraft across checks so each is reported once
+#[derive(Debug, Default)]
+pub struct UnboundTracker {
+    /// When each unbound aircraft was first seen, and if it was reported
+    seen: HashMap<String, (DateTime<Utc>, bool)>,
+}
+
+impl UnboundTracker {
+    /// Updates the tracker with the unbound aircraft found by a check
+    ///
+    /// An aircraft is reported once it has been unbound for at least
+    ///  `debounce`, and again only after it has landed or been bound to a
+    ///  flight in between. Returns the alerts to publish.
+    pub fn update(
+        &mut self,
+        now: DateTime<Utc>,
+        unbound: Vec<UnboundAircraft>,
+        debounce: Duration,
+    ) -> Vec<ComplianceAlert> {
+        let mut seen = HashMap::new();
+        let mut alerts = vec![];
+
+        for aircraft in unbound {
+            let (since, mut reported) = self
+                .seen
+                .remove(&aircraft.identifier)
+                .unwrap_or((now, false));
+
+            let unbound_for = now - since;
+            if !reported && unbound_for >= debounce {
+                reported = true;
+                alerts.push(ComplianceAlert {
+                    aircraft_identifier: aircraft.identifier.clone(),
+                    position: Some(aircraft.position.into()),
+                    unbound_since: Some(since.into()),
+                    unbound_seconds: unbound_for.num_seconds().max(0) as u64,
+                    event_id: 0,
+                });
+            }
+
+            seen.insert(aircraft.identifier, (since, reported));
+        }
+
+        self.seen = seen;
+        alerts
+    }
+}
+
+/// Finds airborne aircraft without an active flight
+pub async fn find_unbound_aircraft(
+    now: DateTime<Utc>,
+    include_simulated: bool,
+) -> Result<Vec<UnboundAircraft>, PostgisError> {
+    postgis_debug!("(find_unbound_aircraft) entry.");
+
+    let Some(position_age) = Duration::try_seconds(AIRBORNE_MAX_POSITION_AGE_SECS) else {
+        postgis_error!("(find_unbound_aircraft) could not get position age.");
+        return Err(PostgisError::Psql(PsqlError::Execute));
+    };
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(find_unbound_aircraft) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Connection));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(find_unbound_aircraft) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    // Airborne aircraft on other shards, bound to a flight or not
+    let position_since = now - position_age;
+    let shard_rows = ShardRows::fetch(
+        &client,
+        super::aircraft::position_source(),
+        AIRBORNE_CONDITION,
+        &[
+            &position_since,
+            &include_simulated,
+            &AIRBORNE_MIN_GROUND_SPEED_MPS,
+            &AIRBORNE_MIN_VERTICAL_SPEED_MPS,
+        ],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(find_unbound_aircraft) could not get shard rows: {}", e);
+        PostgisError::Psql(PsqlError::Execute)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT "aircraft"."identifier", "aircraft"."geom"
+            FROM {aircraft_table_name} AS "aircraft"
+            WHERE {AIRBORNE_CONDITION}
+                AND NOT EXISTS (
+                    SELECT 1 FROM {flights_table_name} AS "flights"
+                    WHERE (
+                        "flights"."aircraft_identifier" = "aircraft"."identifier"
+                        OR "flights"."flight_identifier" = "aircraft"."session_id"
+                    )
+                    AND "flights"."deleted_at" IS NULL
+                    AND "flights"."time_start" <= $5
+                    AND "flights"."time_end" >= $5
+                )
+            ORDER BY "aircraft"."identifier";"#,
+            aircraft_table_name = shard_rows.source(6),
+            flights_table_name = super::flight::get_flights_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(find_unbound_aircraft) could not prepare statement: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let mut params: Vec<&(dyn ToSql + Sync)> = vec![
+        &position_since,
+        &include_simulated,
+        &AIRBORNE_MIN_GROUND_SPEED_MPS,
+        &AIRBORNE_MIN_VERTICAL_SPEED_MPS,
+        &now,
+    ];
+    params.extend(shard_rows.param());
+
+    let rows = client.query(&stmt, &params).await.map_err(|e| {
+        postgis_error!("(find_unbound_aircraft) could not execute query: {}", e);
+        PostgisError::Psql(PsqlError::Execute)
+    })?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(UnboundAircraft {
+                identifier: row.try_get("identifier")?,
+                position: row.try_get("geom")?,
+            })
+        })
+        .collect::<Result<Vec<UnboundAircraft>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(find_unbound_aircraft) could not parse row: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })
+}
+
+/// Runs one check, publishing alerts for newly unbound aircraft
+///
+/// Returns the number of alerts published.
+pub async fn check_unbound_aircraft(
+    tracker: &mut UnboundTracker,
+    debounce: Duration,
+    include_simulated: bool,
+) -> Result<usize, PostgisError> {
+    let now = crate::clock::now();
+    let unbound = find_unbound_aircraft(now, include_simulated).await?;
+    let alerts = tracker.update(now, unbound, debounce);
+
+    let count = alerts.len();
+    for alert in alerts {
+        postgis_warn!(
+            "(check_unbound_aircraft) airborne without a flight, aircraft: '{}', unbound_seconds: '{}'.",
+            alert.aircraft_identifier,
+            alert.unbound_seconds
+        );
+
+        publish_alert(alert).await;
+    }
+
+    Ok(count)
+}
+
+/// Persists an alert in the event backlog and sends it to subscribers
+///
+/// An alert that couldn't be persisted is still sent, without an event id.
+pub async fn publish_alert(alert: ComplianceAlert) {
+    backlog::publish(&ALERTS, alert).await;
+}
+
+/// Starts a loop checking for unbound aircraft every `interval_secs`
+pub async fn begin(interval_secs: u64, debounce_secs: u64, include_simulated: bool) {
+    postgis_info!(
+        "(begin) checking for aircraft without a flight every {interval_secs}s (debounce: {debounce_secs}s)."
+    );
+
+    let Some(debounce) = Duration::try_seconds(debounce_secs as i64) else {
+        postgis_error!("(begin) invalid debounce: {debounce_secs}s.");
+        return;
+    };
+
+    let mut tracker = UnboundTracker::default();
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        if let Err(e) = check_unbound_aircraft(&mut tracker, debounce, include_simulated).await {
+            postgis_warn!("(begin) could not check for aircraft without a flight: {e}");
+        }
+    }
+}
+
+/// Stream of the compliance alerts published after subscribing
+///
+/// With a `last_event_id`, the persisted alerts after it are sent first,
+///  see [`backlog::event_stream`].
+pub fn alert_stream(last_event_id: Option<u64>) -> impl futures::Stream<Item = ComplianceAlert> {
+    backlog::event_stream(&ALERTS, last_event_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn aircraft(identifier: &str) -> UnboundAircraft {
+        UnboundAircraft {
+            identifier: identifier.to_string(),
+            position: PointZ::new(
+                4.9160036,
+                52.3745905,
+                100.0,
+                Some(super::super::DEFAULT_SRID),
+            ),
+        }
+    }
+
+    #[test]
+    fn ut_unbound_tracker_debounce() {
+        let mut tracker = UnboundTracker::default();
+        let debounce = Duration::try_seconds(60).unwrap();
+        let start = Utc::now();
+
+        // Not reported until unbound for the debounce time
+        let alerts = tracker.update(start, vec![aircraft("A1")], debounce);
+        assert!(alerts.is_empty());
+
+        let now = start + Duration::try_seconds(30).unwrap();
+        let alerts = tracker.update(now, vec![aircraft("A1"), aircraft("A2")], debounce);
+        assert!(alerts.is_empty());
+
+        let now = start + Duration::try_seconds(60).unwrap();
+        let alerts = tracker.update(now, vec![aircraft("A1"), aircraft("A2")], debounce);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].aircraft_identifier, "A1");
+        assert_eq!(alerts[0].unbound_seconds, 60);
+        assert_eq!(alerts[0].unbound_since, Some(start.into()));
+        assert!(alerts[0].position.is_some());
+
+        // Reported once per episode
+        let now = start + Duration::try_seconds(90).unwrap();
+        let alerts = tracker.update(now, vec![aircraft("A1"), aircraft("A2")], debounce);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].aircraft_identifier, "A2");
+        assert_eq!(alerts[0].unbound_seconds, 60);
+
+        // A1 lands (or is bound to a flight), then flies unbound again
+        let now = start + Duration::try_seconds(120).unwrap();
+        assert!(tracker
+            .update(now, vec![aircraft("A2")], debounce)
+            .is_empty());
+
+        let now = start + Duration::try_seconds(150).unwrap();
+        assert!(tracker
+            .update(now, vec![aircraft("A1"), aircraft("A2")], debounce)
+            .is_empty());
+
+        let now = start + Duration::try_seconds(210).unwrap();
+        let alerts = tracker.update(now, vec![aircraft("A1"), aircraft("A2")], debounce);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].aircraft_identifier, "A1");
+    }
+
+    #[test]
+    fn ut_unbound_tracker_no_debounce() {
+        let mut tracker = UnboundTracker::default();
+        let alerts = tracker.update(Utc::now(), vec![aircraft("A1")], Duration::zero());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].unbound_seconds, 0);
+    }
+
+    #[tokio::test]
+    async fn ut_alert_stream() {
+        let mut stream = Box::pin(alert_stream(None));
+        let alert = ComplianceAlert {
+            aircraft_identifier: "A1".to_string(),
+            ..Default::default()
+        };
+
+        ALERTS.send(alert.clone()).unwrap();
+        assert_eq!(stream.next().await, Some(alert));
+    }
+
+    #[tokio::test]
+    async fn ut_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_client_failure) start");
+
+        let result = find_unbound_aircraft(Utc::now(), false).await.unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Connection));
+
+        let mut tracker = UnboundTracker::default();
+        let result = check_unbound_aircraft(&mut tracker, Duration::zero(), false)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Connection));
+
+        ut_info!("(ut_client_failure) success");
+    }
+}
@@ -0,0 +1,488 @@
+//! This module contains the periodic conflict check of planned flights.
+//!
+//! Flights starting within a look-ahead horizon are validated again (see
+//!  [`super::flight::validate_flight_comprehensive`]), since flights and
+//!  zones stored after them may now conflict. The latest result of each
+//!  flight is stored and returned with it by `getFlights` and `getFlight`.
+//!  A flight found conflicting after a clean check is published on the
+//!  flight conflict channel, which is streamed by `streamFlightConflicts`,
+//!  and persisted in the event backlog (see [`super::backlog`]) as a
+//!  [`FlightConflicted`] event.
+//!
+//! Simulated flights are not checked, like they aren't conflicts of
+//!  other flights.
+
+use super::backlog::{self, EventKind, PersistedEvent};
+use super::flight::FlightIssue;
+use super::{psql_transaction, PostgisError, PsqlError, PSQL_SCHEMA};
+use crate::grpc::server::grpc_server::{
+    ConflictCheck, FlightConflicted, PointZ as GrpcPointZ, UpdateFlightPathRequest,
+};
+use crate::types::AircraftType;
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use postgis::ewkb::{LineStringT, PointZ};
+use tokio::sync::broadcast;
+
+/// Capacity of the flight conflict channel, slow subscribers miss older
+///  events
+const CONFLICT_CHANNEL_CAPACITY: usize = 256;
+
+/// Flight conflict channel
+pub static FLIGHT_CONFLICTS: Lazy<broadcast::Sender<FlightConflicted>> =
+    Lazy::new(|| broadcast::channel(CONFLICT_CHANNEL_CAPACITY).0);
+
+impl PersistedEvent for FlightConflicted {
+    const KIND: EventKind = EventKind::FlightConflicted;
+
+    fn event_id(&self) -> u64 {
+        self.event_id
+    }
+
+    fn set_event_id(&mut self, event_id: u64) {
+        self.event_id = event_id;
+    }
+}
+
+/// A planned flight due for a conflict check
+#[derive(Debug, Clone)]
+struct PlannedFlight {
+    /// The flight identifier
+    flight_identifier: String,
+
+    /// The aircraft assigned to the flight
+    aircraft_identifier: String,
+
+    /// The type of aircraft
+    aircraft_type: AircraftType,
+
+    /// The full path
+    path: LineStringT<PointZ>,
+
+    /// Start of the flight
+    time_start: DateTime<Utc>,
+
+    /// End of the flight
+    time_end: DateTime<Utc>,
+}
+
+impl TryFrom<tokio_postgres::Row> for PlannedFlight {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(PlannedFlight {
+            flight_identifier: row.try_get("flight_identifier")?,
+            aircraft_identifier: row.try_get("aircraft_identifier")?,
+            aircraft_type: row.try_get("aircraft_type")?,
+            path: row.try_get("geom")?,
+            time_start: row.try_get("time_start")?,
+            time_end: row.try_get("time_end")?,
+        })
+    }
+}
+
+impl From<&PlannedFlight> for UpdateFlightPathRequest {
+    fn from(flight: &PlannedFlight) -> Self {
+        UpdateFlightPathRequest {
+            flight_identifier: Some(flight.flight_identifier.clone()),
+            aircraft_identifier: Some(flight.aircraft_identifier.clone()),
+            aircraft_type: flight.aircraft_type as i32,
+            path: flight
+                .path
+                .points
+                .iter()
+                .copied()
+                .map(GrpcPointZ::from)
+                .collect(),
+            timestamp_start: Some(flight.time_start.into()),
+            timestamp_end: Some(flight.time_end.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Gets the name of the conflict check table
+pub(super) fn get_table_name() -> &'static str {
+    static FULL_NAME: &str =
+        const_format::formatcp!(r#""{PSQL_SCHEMA}"."flight_conflict_checks""#,);
+    FULL_NAME
+}
+
+/// Initializes the PostGIS database for flight conflict checks.
+pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for flight conflict checks
+pub(super) fn psql_init_statements() -> Vec<String> {
+    vec![format!(
+        r#"CREATE TABLE IF NOT EXISTS {table_name} (
+            "flight_identifier" VARCHAR(20) UNIQUE PRIMARY KEY NOT NULL,
+            "conflict_count" INTEGER NOT NULL,
+            "checked_at" TIMESTAMPTZ NOT NULL
+        );"#,
+        table_name = get_table_name()
+    )]
+}
+
+/// Builds the conflict check of a flight from its stored columns, `None`
+///  if the flight wasn't checked yet
+pub(super) fn conflict_check(
+    conflict_count: Option<i32>,
+    checked_at: Option<DateTime<Utc>>,
+) -> Option<ConflictCheck> {
+    let (Some(conflict_count), Some(checked_at)) = (conflict_count, checked_at) else {
+        return None;
+    };
+
+    let conflict_count = conflict_count.max(0) as u32;
+    Some(ConflictCheck {
+        conflicted: conflict_count > 0,
+        conflict_count,
+        checked_at: Some(checked_at.into()),
+    })
+}
+
+/// Counts the conflicting flights and zones among the issues of a flight
+///
+/// Other issues come from the stored flight itself and don't change
+///  between checks.
+fn conflict_count(issues: &[FlightIssue]) -> u32 {
+    issues
+        .iter()
+        .filter(|issue| matches!(issue, FlightIssue::Zone { .. } | FlightIssue::Conflict(_)))
+        .count() as u32
+}
+
+/// If a check with `conflict_count` conflicts turns a flight from clean to
+///  conflicted
+///
+/// A flight checked for the first time is considered clean before, as it
+///  was when filed.
+fn became_conflicted(previous_count: Option<i32>, conflict_count: u32) -> bool {
+    conflict_count > 0 && previous_count.unwrap_or(0) == 0
+}
+
+/// Gets the flights starting between `now` and `now + horizon`, soonest
+///  first
+async fn find_upcoming_flights(
+    now: DateTime<Utc>,
+    horizon: Duration,
+) -> Result<Vec<PlannedFlight>, PostgisError> {
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(find_upcoming_flights) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Connection));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(find_upcoming_flights) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT
+                "flight_identifier",
+                "aircraft_identifier",
+                "aircraft_type",
+                "geom",
+                "time_start",
+                "time_end"
+            FROM {flights_table_name}
+            WHERE "geom" IS NOT NULL
+                AND "deleted_at" IS NULL
+                AND "simulated" = FALSE
+                AND ("reserved_until" IS NULL OR "reserved_until" > $1)
+                AND "time_start" >= $1
+                AND "time_start" <= $2
+                AND "time_end" IS NOT NULL
+            ORDER BY "time_start", "flight_identifier";"#,
+        flights_table_name = super::flight::get_flights_table_name(),
+    );
+
+    let rows = super::query_cached(&client, &stmt, &[&now, &(now + horizon)])
+        .await
+        .map_err(|e| {
+            postgis_error!("(find_upcoming_flights) could not execute query: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    rows.into_iter()
+        .map(PlannedFlight::try_from)
+        .collect::<Result<Vec<PlannedFlight>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(find_upcoming_flights) could not parse row: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })
+}
+
+/// Stores the result of a check, returning the conflict count of the
+///  previous check if any
+async fn store_check(
+    flight_identifier: &str,
+    conflict_count: u32,
+    checked_at: DateTime<Utc>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Option<i32>, PostgisError> {
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(store_check) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"WITH "previous" AS (
+                SELECT "conflict_count" FROM {table_name}
+                WHERE "flight_identifier" = $1
+            )
+            INSERT INTO {table_name} ("flight_identifier", "conflict_count", "checked_at")
+            VALUES ($1, $2, $3)
+            ON CONFLICT ("flight_identifier") DO UPDATE
+                SET "conflict_count" = EXCLUDED."conflict_count",
+                    "checked_at" = EXCLUDED."checked_at"
+            RETURNING (SELECT "conflict_count" FROM "previous") AS "previous_count";"#,
+        table_name = get_table_name()
+    );
+
+    let conflict_count = conflict_count as i32;
+    let previous_count: Option<i32> = super::query_cached(
+        &client,
+        &stmt,
+        &[&flight_identifier, &conflict_count, &checked_at],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(store_check) could not store check: {}", e);
+        PostgisError::Psql(PsqlError::Execute)
+    })?
+    .first()
+    .ok_or_else(|| {
+        postgis_error!("(store_check) no row returned for the check.");
+        PostgisError::Psql(PsqlError::Execute)
+    })?
+    .try_get("previous_count")
+    .map_err(|e| {
+        postgis_error!("(store_check) could not get previous check: {}", e);
+        PostgisError::Psql(PsqlError::Execute)
+    })?;
+
+    Ok(previous_count)
+}
+
+/// Checks a flight against the current airspace and stores the result
+///
+/// Returns the event to publish if the flight became conflicted.
+async fn check_flight(
+    flight: &PlannedFlight,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Option<FlightConflicted>, PostgisError> {
+    let request = UpdateFlightPathRequest::from(flight);
+    let issues = super::flight::validate_flight_comprehensive(&request, pool).await?;
+    let conflict_count = conflict_count(&issues);
+
+    let checked_at = crate::clock::now();
+    let previous_count =
+        store_check(&flight.flight_identifier, conflict_count, checked_at, pool).await?;
+
+    if !became_conflicted(previous_count, conflict_count) {
+        return Ok(None);
+    }
+
+    Ok(Some(FlightConflicted {
+        flight_identifier: flight.flight_identifier.clone(),
+        conflict_check: conflict_check(Some(conflict_count as i32), Some(checked_at)),
+        event_id: 0,
+    }))
+}
+
+/// Publishes a flight conflicted event and persists it in the event
+///  backlog
+async fn publish_conflicted(event: FlightConflicted) {
+    postgis_warn!(
+        "(publish_conflicted) flight became conflicted, flight: '{}', conflicts: '{}'.",
+        event.flight_identifier,
+        event
+            .conflict_check
+            .as_ref()
+            .map_or(0, |check| check.conflict_count)
+    );
+
+    backlog::publish(&FLIGHT_CONFLICTS, event).await;
+}
+
+/// Stream of the flights found conflicting after subscribing
+///
+/// With a `last_event_id`, the persisted events after it are sent first,
+///  see [`backlog::event_stream`].
+pub fn conflict_stream(
+    last_event_id: Option<u64>,
+) -> impl futures::Stream<Item = FlightConflicted> {
+    backlog::event_stream(&FLIGHT_CONFLICTS, last_event_id)
+}
+
+/// Runs one check of the flights starting within `horizon`, with at most
+///  `concurrency` flights checked at a time
+///
+/// A flight that can't be checked keeps its previous result. Returns the
+///  number of flights that became conflicted.
+pub async fn check_upcoming_flights(
+    horizon: Duration,
+    concurrency: usize,
+) -> Result<usize, PostgisError> {
+    let flights = find_upcoming_flights(crate::clock::now(), horizon).await?;
+    postgis_debug!(
+        "(check_upcoming_flights) checking {} flights.",
+        flights.len()
+    );
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(check_upcoming_flights) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Connection));
+    };
+
+    let results: Vec<(String, Result<Option<FlightConflicted>, PostgisError>)> =
+        futures::stream::iter(flights.iter())
+            .map(|flight| async move {
+                (
+                    flight.flight_identifier.clone(),
+                    check_flight(flight, pool).await,
+                )
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+    let mut count = 0;
+    for (flight_identifier, result) in results {
+        match result {
+            Ok(Some(event)) => {
+                publish_conflicted(event).await;
+                count += 1;
+            }
+            Ok(None) => (),
+            Err(e) => {
+                postgis_warn!(
+                    "(check_upcoming_flights) could not check flight {flight_identifier}: {e}"
+                );
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Starts a loop checking the flights starting within `horizon_secs` every
+///  `interval_secs`, at most `concurrency` at a time
+pub async fn begin(interval_secs: u64, horizon_secs: u64, concurrency: u32) {
+    postgis_info!(
+        "(begin) checking flights starting within {horizon_secs}s for conflicts every {interval_secs}s (concurrency: {concurrency})."
+    );
+
+    let Some(horizon) = Duration::try_seconds(horizon_secs as i64) else {
+        postgis_error!("(begin) invalid horizon: {horizon_secs}s.");
+        return;
+    };
+
+    if concurrency == 0 {
+        postgis_error!("(begin) invalid concurrency: {concurrency}.");
+        return;
+    }
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        if let Err(e) = check_upcoming_flights(horizon, concurrency as usize).await {
+            postgis_warn!("(begin) could not check flights for conflicts: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgis::flight::FlightConflict;
+
+    fn conflict() -> FlightIssue {
+        let point = PointZ::new(
+            4.9160036,
+            52.3745905,
+            100.0,
+            Some(super::super::DEFAULT_SRID),
+        );
+        FlightIssue::Conflict(FlightConflict {
+            flight_identifier: "F2".to_string(),
+            aircraft_identifier: "A2".to_string(),
+            segment: LineStringT {
+                points: vec![point, point],
+                srid: Some(super::super::DEFAULT_SRID),
+            },
+            closest_point: point,
+            distance_meters: 5.0,
+            time_start: None,
+            time_end: None,
+        })
+    }
+
+    #[test]
+    fn ut_conflict_count() {
+        assert_eq!(conflict_count(&[]), 0);
+
+        // Issues of the flight itself aren't conflicts
+        let issues = vec![
+            FlightIssue::Altitude {
+                index: 0,
+                altitude_meters: -1.0,
+            },
+            FlightIssue::Zone {
+                identifier: "Z1".to_string(),
+            },
+            conflict(),
+        ];
+        assert_eq!(conflict_count(&issues), 2);
+    }
+
+    #[test]
+    fn ut_became_conflicted() {
+        assert!(became_conflicted(None, 1));
+        assert!(became_conflicted(Some(0), 2));
+        assert!(!became_conflicted(None, 0));
+        assert!(!became_conflicted(Some(0), 0));
+
+        // Already conflicted, or cleared
+        assert!(!became_conflicted(Some(1), 3));
+        assert!(!became_conflicted(Some(2), 0));
+    }
+
+    #[test]
+    fn ut_conflict_check() {
+        let checked_at = Utc::now();
+        assert!(conflict_check(None, None).is_none());
+        assert!(conflict_check(Some(1), None).is_none());
+
+        let check = conflict_check(Some(0), Some(checked_at)).unwrap();
+        assert!(!check.conflicted);
+        assert_eq!(check.conflict_count, 0);
+        assert_eq!(check.checked_at, Some(checked_at.into()));
+
+        let check = conflict_check(Some(3), Some(checked_at)).unwrap();
+        assert!(check.conflicted);
+        assert_eq!(check.conflict_count, 3);
+    }
+
+    #[tokio::test]
+    async fn ut_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_client_failure) start");
+
+        let horizon = Duration::try_minutes(30).unwrap();
+        let result = check_upcoming_flights(horizon, 1).await.unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Connection));
+
+        ut_info!("(ut_client_failure) success");
+    }
+}
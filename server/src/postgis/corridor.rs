@@ -0,0 +1,1055 @@
+//! This module contains functions for storing corridors and reporting their
+//!  allocation.
+//!
+//! A corridor is a tube around a centerline in which at most `capacity`
+//!  flights may be at the same time. A flight segment is in the corridor if
+//!  it comes within half the corridor width of the centerline.
+
+use super::{psql_transaction, PostgisError, DEFAULT_SRID, PSQL_SCHEMA};
+use crate::grpc::server::grpc_server::{
+    Corridor as GrpcCorridor, CorridorConflict as GrpcCorridorConflict, CorridorSlice,
+    GetCorridorAllocationRequest, GetCorridorAllocationResponse, PointZ as GrpcPointZ,
+};
+use crate::postgis::utils::Segment;
+use chrono::{DateTime, Duration, Utc};
+use deadpool_postgres::Object;
+use postgis::ewkb::{LineStringT, PointZ, PolygonZ};
+use std::ops::Range;
+
+/// Allowed characters in a corridor identifier
+pub const IDENTIFIER_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,255}$";
+
+/// Min number of centerline points
+pub const MIN_CENTERLINE_POINTS: usize = 2;
+
+/// Max number of time slices returned by [`get_corridor_allocation`]
+pub const MAX_CORRIDOR_SLICES: i64 = 1_000;
+
+/// Possible errors with corridors
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CorridorError {
+    /// Invalid corridor identifier
+    Identifier,
+
+    /// Invalid centerline
+    Location,
+
+    /// Invalid width or height
+    Dimensions,
+
+    /// Invalid capacity
+    Capacity,
+
+    /// Invalid time range
+    Time,
+
+    /// Invalid slice length, or too many slices
+    Slices,
+
+    /// No corridors provided
+    NoCorridors,
+
+    /// No matching corridor found
+    NotFound,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for CorridorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CorridorError::Identifier => write!(f, "Invalid identifier provided."),
+            CorridorError::Location => write!(f, "Invalid centerline provided."),
+            CorridorError::Dimensions => write!(f, "Invalid width or height provided."),
+            CorridorError::Capacity => write!(f, "Invalid capacity provided."),
+            CorridorError::Time => write!(f, "Invalid time range provided."),
+            CorridorError::Slices => write!(f, "Invalid slice length provided."),
+            CorridorError::NoCorridors => write!(f, "No corridors were provided."),
+            CorridorError::NotFound => write!(f, "No matching corridor found."),
+            CorridorError::Client => write!(f, "Could not get backend client."),
+            CorridorError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// A validated corridor
+#[derive(Debug, Clone, PartialEq)]
+struct Corridor {
+    identifier: String,
+    geom: LineStringT<PointZ>,
+    width_meters: f64,
+    height_meters: f64,
+    capacity: i32,
+}
+
+impl TryFrom<GrpcCorridor> for Corridor {
+    type Error = CorridorError;
+
+    fn try_from(corridor: GrpcCorridor) -> Result<Self, Self::Error> {
+        if let Err(e) = super::utils::check_string(&corridor.identifier, IDENTIFIER_REGEX) {
+            postgis_error!(
+                "(try_from) invalid corridor identifier {}: {}",
+                corridor.identifier,
+                e
+            );
+            return Err(CorridorError::Identifier);
+        }
+
+        if corridor.centerline.len() < MIN_CENTERLINE_POINTS {
+            postgis_error!(
+                "(try_from) corridor {} needs at least {} centerline points.",
+                corridor.identifier,
+                MIN_CENTERLINE_POINTS
+            );
+            return Err(CorridorError::Location);
+        }
+
+        let points = corridor
+            .centerline
+            .into_iter()
+            .map(PointZ::try_from)
+            .collect::<Result<Vec<PointZ>, _>>()
+            .map_err(|_| {
+                postgis_error!(
+                    "(try_from) invalid centerline point in corridor {}.",
+                    corridor.identifier
+                );
+                CorridorError::Location
+            })?;
+
+        if let Err(index) =
+            super::service_area::check_service_area(points.iter().map(|point| (point.x, point.y)))
+        {
+            postgis_error!(
+                "(try_from) point {} of corridor {} is outside of the service area: {:?}",
+                index,
+                corridor.identifier,
+                points[index]
+            );
+            return Err(CorridorError::Location);
+        }
+
+        for dimension in [corridor.width_meters, corridor.height_meters] {
+            if !dimension.is_finite() || dimension <= 0.0 {
+                postgis_error!(
+                    "(try_from) invalid dimensions for corridor {}: {} x {} meters.",
+                    corridor.identifier,
+                    corridor.width_meters,
+                    corridor.height_meters
+                );
+                return Err(CorridorError::Dimensions);
+            }
+        }
+
+        let capacity = match i32::try_from(corridor.capacity) {
+            Ok(capacity) if capacity > 0 => capacity,
+            _ => {
+                postgis_error!(
+                    "(try_from) invalid capacity for corridor {}: {}",
+                    corridor.identifier,
+                    corridor.capacity
+                );
+                return Err(CorridorError::Capacity);
+            }
+        };
+
+        Ok(Corridor {
+            identifier: corridor.identifier,
+            geom: LineStringT {
+                points,
+                srid: Some(DEFAULT_SRID),
+            },
+            width_meters: corridor.width_meters,
+            height_meters: corridor.height_meters,
+            capacity,
+        })
+    }
+}
+
+/// Gets the name of the corridors table
+pub(super) fn get_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."corridors""#,);
+    FULL_NAME
+}
+
+/// Initializes the PostGIS database for corridors.
+pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for corridors
+pub(super) fn psql_init_statements() -> Vec<String> {
+    vec![
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "identifier" VARCHAR(255) UNIQUE PRIMARY KEY NOT NULL,
+                "geom" GEOMETRY(LINESTRINGZ, {DEFAULT_SRID}) NOT NULL,
+                "width_meters" FLOAT8 NOT NULL,
+                "height_meters" FLOAT8 NOT NULL,
+                "capacity" INTEGER NOT NULL,
+                "last_updated" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "corridors_geom_idx" ON {table_name} USING GIST (ST_Transform("geom", 4978));"#,
+            table_name = get_table_name()
+        ),
+    ]
+}
+
+/// Creates or replaces corridors
+pub async fn update_corridors(corridors: Vec<GrpcCorridor>) -> Result<(), CorridorError> {
+    postgis_debug!("(update_corridors) entry.");
+    if corridors.is_empty() {
+        return Err(CorridorError::NoCorridors);
+    }
+
+    let corridors: Vec<Corridor> = corridors
+        .into_iter()
+        .map(Corridor::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(update_corridors) could not get psql pool.");
+        return Err(CorridorError::Client);
+    };
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(update_corridors) could not get client from psql connection pool: {}",
+            e
+        );
+        CorridorError::Client
+    })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("(update_corridors) could not create transaction: {}", e);
+        CorridorError::DBError
+    })?;
+
+    let stmt = transaction
+        .prepare_cached(&format!(
+            r#"INSERT INTO {table_name} (
+                "identifier",
+                "geom",
+                "width_meters",
+                "height_meters",
+                "capacity",
+                "last_updated"
+            )
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT ("identifier")
+            DO UPDATE
+                SET "geom" = EXCLUDED."geom",
+                    "width_meters" = EXCLUDED."width_meters",
+                    "height_meters" = EXCLUDED."height_meters",
+                    "capacity" = EXCLUDED."capacity",
+                    "last_updated" = EXCLUDED."last_updated";
+            "#,
+            table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_corridors) could not prepare cached statement: {}",
+                e
+            );
+            CorridorError::DBError
+        })?;
+
+    for corridor in &corridors {
+        transaction
+            .execute(
+                &stmt,
+                &[
+                    &corridor.identifier,
+                    &corridor.geom,
+                    &corridor.width_meters,
+                    &corridor.height_meters,
+                    &corridor.capacity,
+                ],
+            )
+            .await
+            .map_err(|e| {
+                postgis_error!("(update_corridors) could not execute transaction: {}", e);
+                CorridorError::DBError
+            })?;
+    }
+
+    match transaction.commit().await {
+        Ok(_) => {
+            postgis_debug!("(update_corridors) success.");
+            Ok(())
+        }
+        Err(e) => {
+            postgis_error!("(update_corridors) could not commit transaction: {}", e);
+            Err(CorridorError::DBError)
+        }
+    }
+}
+
+/// Gets all corridors, ordered by identifier
+pub async fn get_corridors() -> Result<Vec<GrpcCorridor>, CorridorError> {
+    postgis_debug!("(get_corridors) entry.");
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_corridors) could not get psql pool.");
+        return Err(CorridorError::Client);
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_corridors) could not get client from psql connection pool: {}",
+            e
+        );
+        CorridorError::Client
+    })?;
+
+    let stmt = format!(
+        r#"SELECT "identifier", "geom", "width_meters", "height_meters", "capacity"
+            FROM {table_name}
+            ORDER BY "identifier";"#,
+        table_name = get_table_name()
+    );
+
+    super::query_cached(&client, &stmt, &[])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_corridors) could not execute query: {}", e);
+            CorridorError::DBError
+        })?
+        .into_iter()
+        .map(|row| {
+            let geom: LineStringT<PointZ> = row.try_get("geom")?;
+            let capacity: i32 = row.try_get("capacity")?;
+            Ok(GrpcCorridor {
+                identifier: row.try_get("identifier")?,
+                centerline: geom.points.into_iter().map(GrpcPointZ::from).collect(),
+                width_meters: row.try_get("width_meters")?,
+                height_meters: row.try_get("height_meters")?,
+                capacity: capacity.max(0) as u32,
+            })
+        })
+        .collect::<Result<Vec<GrpcCorridor>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_corridors) could not get corridor data: {}", e);
+            CorridorError::DBError
+        })
+}
+
+/// Deletes a corridor
+pub async fn delete_corridor(identifier: &str) -> Result<(), CorridorError> {
+    postgis_debug!("(delete_corridor) entry, corridor: '{identifier}'.");
+    if let Err(e) = super::utils::check_string(identifier, IDENTIFIER_REGEX) {
+        postgis_error!("(delete_corridor) invalid identifier {}: {}", identifier, e);
+        return Err(CorridorError::Identifier);
+    }
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(delete_corridor) could not get psql pool.");
+        return Err(CorridorError::Client);
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(delete_corridor) could not get client from psql connection pool: {}",
+            e
+        );
+        CorridorError::Client
+    })?;
+
+    let stmt = format!(
+        r#"DELETE FROM {table_name} WHERE "identifier" = $1;"#,
+        table_name = get_table_name()
+    );
+
+    let deleted = client.execute(&stmt, &[&identifier]).await.map_err(|e| {
+        postgis_error!("(delete_corridor) could not execute statement: {}", e);
+        CorridorError::DBError
+    })?;
+
+    match deleted {
+        0 => Err(CorridorError::NotFound),
+        _ => Ok(()),
+    }
+}
+
+/// Validates an allocation request, returning the time range and the
+///  slice length
+fn validate_allocation_request(
+    request: &GetCorridorAllocationRequest,
+) -> Result<(DateTime<Utc>, DateTime<Utc>, Duration), CorridorError> {
+    if let Err(e) = super::utils::check_string(&request.identifier, IDENTIFIER_REGEX) {
+        postgis_error!(
+            "(validate_allocation_request) invalid identifier {}: {}",
+            request.identifier,
+            e
+        );
+        return Err(CorridorError::Identifier);
+    }
+
+    let (Some(time_start), Some(time_end)) = (request.time_start.clone(), request.time_end.clone())
+    else {
+        postgis_error!("(validate_allocation_request) time_start and time_end are required.");
+        return Err(CorridorError::Time);
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end <= time_start {
+        postgis_error!("(validate_allocation_request) time_end must be after time_start.");
+        return Err(CorridorError::Time);
+    }
+
+    let Some(slice) = Duration::try_seconds(request.slice_seconds as i64)
+        .filter(|slice| *slice > Duration::zero())
+    else {
+        postgis_error!(
+            "(validate_allocation_request) invalid slice length: {}s.",
+            request.slice_seconds
+        );
+        return Err(CorridorError::Slices);
+    };
+
+    let range_seconds = (time_end - time_start).num_seconds();
+    let slice_seconds = slice.num_seconds();
+    let slices = (range_seconds + slice_seconds - 1) / slice_seconds;
+    if slices > MAX_CORRIDOR_SLICES {
+        postgis_error!(
+            "(validate_allocation_request) {} slices requested, at most {} allowed.",
+            slices,
+            MAX_CORRIDOR_SLICES
+        );
+        return Err(CorridorError::Slices);
+    }
+
+    Ok((time_start, time_end, slice))
+}
+
+/// Gets the number of flights in a corridor for each time slice of a range
+///
+/// The last slice is cut short at the end of the range. A slice is over
+///  capacity when more flights than the corridor capacity are in the
+///  corridor at some point of it.
+pub async fn get_corridor_allocation(
+    request: GetCorridorAllocationRequest,
+) -> Result<GetCorridorAllocationResponse, CorridorError> {
+    postgis_debug!("(get_corridor_allocation) entry.");
+    let (time_start, time_end, slice) = validate_allocation_request(&request)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_corridor_allocation) could not get psql pool.");
+        return Err(CorridorError::Client);
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_corridor_allocation) could not get client from psql connection pool: {}",
+            e
+        );
+        CorridorError::Client
+    })?;
+
+    let stmt = format!(
+        r#"SELECT "capacity" FROM {table_name} WHERE "identifier" = $1;"#,
+        table_name = get_table_name()
+    );
+
+    let capacity: i32 = super::query_cached(&client, &stmt, &[&request.identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_corridor_allocation) could not execute query: {}", e);
+            CorridorError::DBError
+        })?
+        .first()
+        .ok_or_else(|| {
+            postgis_warn!(
+                "(get_corridor_allocation) no corridor '{}'.",
+                request.identifier
+            );
+            CorridorError::NotFound
+        })?
+        .try_get("capacity")
+        .map_err(|e| {
+            postgis_error!("(get_corridor_allocation) could not get capacity: {}", e);
+            CorridorError::DBError
+        })?;
+
+    let stmt = format!(
+        r#"WITH "corridor" AS (
+                SELECT "geom", "width_meters"
+                FROM {table_name}
+                WHERE "identifier" = $1
+            ), "slices" AS (
+                SELECT
+                    "slice_start" AS "time_start",
+                    LEAST("slice_start" + make_interval(secs => $4::FLOAT8), $3) AS "time_end"
+                FROM generate_series(
+                    $2::TIMESTAMPTZ,
+                    $3::TIMESTAMPTZ - INTERVAL '1 microsecond',
+                    make_interval(secs => $4::FLOAT8)
+                ) AS "slice_start"
+            ), "inside" AS (
+                SELECT
+                    "segments"."flight_identifier",
+                    "segments"."time_start",
+                    "segments"."time_end"
+                FROM {segments_table_name} AS "segments"
+                JOIN {flights_table_name} AS "flights"
+                    ON "flights"."flight_identifier" = "segments"."flight_identifier"
+                CROSS JOIN "corridor"
+                WHERE
+                    ("segments"."time_start" < $3 OR "segments"."time_start" IS NULL)
+                    AND ("segments"."time_end" > $2 OR "segments"."time_end" IS NULL)
+                    AND "flights"."simulated" = FALSE
+                    AND "flights"."deleted_at" IS NULL
+                    AND ("flights"."reserved_until" IS NULL OR "flights"."reserved_until" > $5)
+                    AND ST_3DDWithin(
+                        ST_Transform("segments"."geom", 4978),
+                        ST_Transform("corridor"."geom", 4978),
+                        "corridor"."width_meters" / 2.0
+                    )
+            )
+            SELECT
+                "slices"."time_start",
+                "slices"."time_end",
+                COUNT(DISTINCT "inside"."flight_identifier") AS "flight_count"
+            FROM "slices"
+            LEFT JOIN "inside"
+                ON ("inside"."time_start" < "slices"."time_end" OR "inside"."time_start" IS NULL)
+                AND ("inside"."time_end" > "slices"."time_start" OR "inside"."time_end" IS NULL)
+            GROUP BY "slices"."time_start", "slices"."time_end"
+            ORDER BY "slices"."time_start";"#,
+        table_name = get_table_name(),
+        segments_table_name = super::flight::get_flight_segments_table_name(),
+        flights_table_name = super::flight::get_flights_table_name(),
+    );
+
+    let slice_seconds = slice.num_seconds() as f64;
+    let slices = super::query_cached(
+        &client,
+        &stmt,
+        &[
+            &request.identifier,
+            &time_start,
+            &time_end,
+            &slice_seconds,
+            &crate::clock::now(),
+        ],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(get_corridor_allocation) could not execute query: {}", e);
+        CorridorError::DBError
+    })?
+    .into_iter()
+    .map(|row| {
+        let slice_start: DateTime<Utc> = row.try_get("time_start")?;
+        let slice_end: DateTime<Utc> = row.try_get("time_end")?;
+        let flight_count: i64 = row.try_get("flight_count")?;
+
+        Ok(CorridorSlice {
+            time_start: Some(slice_start.into()),
+            time_end: Some(slice_end.into()),
+            flight_count: flight_count.max(0) as u32,
+            over_capacity: flight_count > capacity as i64,
+        })
+    })
+    .collect::<Result<Vec<CorridorSlice>, tokio_postgres::error::Error>>()
+    .map_err(|e| {
+        postgis_error!(
+            "(get_corridor_allocation) could not get allocation data: {}",
+            e
+        );
+        CorridorError::DBError
+    })?;
+
+    Ok(GetCorridorAllocationResponse {
+        identifier: request.identifier,
+        capacity: capacity.max(0) as u32,
+        slices,
+    })
+}
+
+/// A full corridor on a checked path
+#[derive(Debug, Clone, PartialEq)]
+struct CorridorConflict {
+    corridor_identifier: String,
+    capacity: u32,
+    flight_count: u32,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+}
+
+impl From<CorridorConflict> for GrpcCorridorConflict {
+    fn from(conflict: CorridorConflict) -> Self {
+        GrpcCorridorConflict {
+            corridor_identifier: conflict.corridor_identifier,
+            capacity: conflict.capacity,
+            flight_count: conflict.flight_count,
+            time_start: Some(conflict.time_start.into()),
+            time_end: Some(conflict.time_end.into()),
+        }
+    }
+}
+
+/// Adds a conflict, extending the previous conflict with the same corridor
+///  if the path stays in that corridor
+fn merge_corridor_conflict(conflicts: &mut Vec<CorridorConflict>, conflict: CorridorConflict) {
+    let previous = conflicts.iter_mut().rev().find(|previous| {
+        previous.corridor_identifier == conflict.corridor_identifier
+            && previous.time_end >= conflict.time_start
+    });
+
+    match previous {
+        Some(previous) => {
+            previous.time_end = previous.time_end.max(conflict.time_end);
+            previous.flight_count = previous.flight_count.max(conflict.flight_count);
+        }
+        None => conflicts.push(conflict),
+    }
+}
+
+/// Reads a full corridor found for a checked segment
+fn corridor_conflict_from_row(
+    row: &tokio_postgres::Row,
+    segment: &Segment,
+) -> Result<CorridorConflict, tokio_postgres::error::Error> {
+    let capacity: i32 = row.try_get("capacity")?;
+    let flight_count: i64 = row.try_get("flight_count")?;
+    Ok(CorridorConflict {
+        corridor_identifier: row.try_get("identifier")?,
+        capacity: capacity.max(0) as u32,
+        flight_count: flight_count.max(0) as u32,
+        time_start: segment.time_start,
+        time_end: segment.time_end,
+    })
+}
+
+/// Gets the corridors that checked path segments would take beyond their
+///  capacity
+///
+/// A segment conflicts with a corridor if it's in the corridor while the
+///  corridor already holds `capacity` stored flights or more. Consecutive
+///  segments in the same full corridor are reported as one conflict.
+pub(super) async fn get_corridor_conflicts(
+    client: &Object,
+    segments: &[Segment],
+) -> Result<Vec<GrpcCorridorConflict>, PostgisError> {
+    postgis_debug!("(get_corridor_conflicts) entry.");
+    let stmt = format!(
+        r#"SELECT
+                "corridors"."identifier",
+                "corridors"."capacity",
+                "occupancy"."flight_count"
+            FROM {table_name} AS "corridors"
+            CROSS JOIN LATERAL (
+                SELECT COUNT(DISTINCT "segments"."flight_identifier") AS "flight_count"
+                FROM {segments_table_name} AS "segments"
+                JOIN {flights_table_name} AS "flights"
+                    ON "flights"."flight_identifier" = "segments"."flight_identifier"
+                WHERE
+                    ("segments"."time_start" < $3 OR "segments"."time_start" IS NULL)
+                    AND ("segments"."time_end" > $2 OR "segments"."time_end" IS NULL)
+                    AND "flights"."simulated" = FALSE
+                    AND "flights"."deleted_at" IS NULL
+                    AND ("flights"."reserved_until" IS NULL OR "flights"."reserved_until" > $4)
+                    AND ST_3DDWithin(
+                        ST_Transform("segments"."geom", 4978),
+                        ST_Transform("corridors"."geom", 4978),
+                        "corridors"."width_meters" / 2.0
+                    )
+            ) AS "occupancy"
+            WHERE
+                ST_3DDWithin(
+                    ST_Transform("corridors"."geom", 4978),
+                    ST_Transform($1, 4978),
+                    "corridors"."width_meters" / 2.0
+                )
+                AND "occupancy"."flight_count" >= "corridors"."capacity"
+            ORDER BY "corridors"."identifier";"#,
+        table_name = get_table_name(),
+        segments_table_name = super::flight::get_flight_segments_table_name(),
+        flights_table_name = super::flight::get_flights_table_name(),
+    );
+
+    let now = crate::clock::now();
+    let mut conflicts: Vec<CorridorConflict> = vec![];
+    for segment in segments {
+        let rows = super::query_cached(
+            client,
+            &stmt,
+            &[&segment.geom, &segment.time_start, &segment.time_end, &now],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_corridor_conflicts) could not execute query: {}", e);
+            PostgisError::Corridor(CorridorError::DBError)
+        })?;
+
+        for row in rows {
+            let conflict = corridor_conflict_from_row(&row, segment).map_err(|e| {
+                postgis_error!(
+                    "(get_corridor_conflicts) could not get conflict data: {}",
+                    e
+                );
+                PostgisError::Corridor(CorridorError::DBError)
+            })?;
+
+            merge_corridor_conflict(&mut conflicts, conflict);
+        }
+    }
+
+    Ok(conflicts.into_iter().map(Into::into).collect())
+}
+
+/// A flight in a corridor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorridorFlight {
+    /// Flight identifier
+    pub flight_identifier: String,
+
+    /// Aircraft flying the flight
+    pub aircraft_identifier: String,
+}
+
+/// Gets the floor of a corridor volume: the corridor footprint at the bottom
+///  of the altitude band
+fn corridor_floor(
+    corridor_geom: &PolygonZ,
+    altitude_band: &Range<f64>,
+) -> Result<PolygonZ, CorridorError> {
+    if !altitude_band.start.is_finite()
+        || !altitude_band.end.is_finite()
+        || altitude_band.end <= altitude_band.start
+    {
+        postgis_error!(
+            "(corridor_floor) invalid altitude band: {} to {} meters.",
+            altitude_band.start,
+            altitude_band.end
+        );
+        return Err(CorridorError::Dimensions);
+    }
+
+    let Some(exterior) = corridor_geom.rings.first() else {
+        postgis_error!("(corridor_floor) corridor geometry has no rings.");
+        return Err(CorridorError::Location);
+    };
+
+    if exterior.points.len() < super::utils::MIN_NUM_POLYGON_VERTICES
+        || exterior.points.first() != exterior.points.last()
+        || exterior
+            .points
+            .iter()
+            .any(|point| super::utils::validate_pointz(point).is_err())
+    {
+        postgis_error!("(corridor_floor) invalid corridor geometry: {:?}", exterior);
+        return Err(CorridorError::Location);
+    }
+
+    let mut floor = corridor_geom.clone();
+    floor.srid = Some(DEFAULT_SRID);
+    for ring in floor.rings.iter_mut() {
+        ring.srid = Some(DEFAULT_SRID);
+        for point in ring.points.iter_mut() {
+            point.z = altitude_band.start;
+            point.srid = Some(DEFAULT_SRID);
+        }
+    }
+
+    Ok(floor)
+}
+
+/// Gets the flights with a segment inside of a corridor at a given time
+///
+/// The corridor is the footprint extruded over the altitude band, a segment
+///  is inside if it intersects that volume, as flights are checked against
+///  no-fly zones. Ordered by flight identifier.
+pub async fn flights_in_corridor(
+    corridor_geom: &PolygonZ,
+    time: DateTime<Utc>,
+    altitude_band: Range<f64>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<CorridorFlight>, PostgisError> {
+    postgis_debug!("(flights_in_corridor) entry, time: {time}.");
+    let floor = corridor_floor(corridor_geom, &altitude_band).map_err(PostgisError::Corridor)?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(flights_in_corridor) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Corridor(CorridorError::Client)
+    })?;
+
+    // Flights are narrowed down by their envelope ("isa") index first
+    let stmt = format!(
+        r#"WITH "corridor" AS (
+                SELECT ST_Extrude(
+                    $1::GEOMETRY(POLYGONZ, {DEFAULT_SRID}), 0, 0, $4::FLOAT8 - $3::FLOAT8
+                ) AS "geom"
+            )
+            SELECT DISTINCT
+                "flights"."flight_identifier",
+                "flights"."aircraft_identifier"
+            FROM {segments_table_name} AS "segments"
+            JOIN {flights_table_name} AS "flights"
+                ON "flights"."flight_identifier" = "segments"."flight_identifier"
+            CROSS JOIN "corridor"
+            WHERE
+                "segments"."time_start" <= $2
+                AND "segments"."time_end" >= $2
+                AND "flights"."simulated" = FALSE
+                AND "flights"."deleted_at" IS NULL
+                AND ("flights"."reserved_until" IS NULL OR "flights"."reserved_until" > $5)
+                AND "flights"."isa" && ST_Force2D($1)
+                AND ST_3DIntersects("corridor"."geom", "segments"."geom")
+            ORDER BY "flights"."flight_identifier";"#,
+        segments_table_name = super::flight::get_flight_segments_table_name(),
+        flights_table_name = super::flight::get_flights_table_name(),
+    );
+
+    super::query_cached(
+        &client,
+        &stmt,
+        &[
+            &floor,
+            &time,
+            &altitude_band.start,
+            &altitude_band.end,
+            &crate::clock::now(),
+        ],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(flights_in_corridor) could not execute query: {}", e);
+        PostgisError::Corridor(CorridorError::DBError)
+    })?
+    .into_iter()
+    .map(|row| {
+        Ok(CorridorFlight {
+            flight_identifier: row.try_get("flight_identifier")?,
+            aircraft_identifier: row.try_get("aircraft_identifier")?,
+        })
+    })
+    .collect::<Result<Vec<CorridorFlight>, tokio_postgres::error::Error>>()
+    .map_err(|e| {
+        postgis_error!("(flights_in_corridor) could not get flight data: {}", e);
+        PostgisError::Corridor(CorridorError::DBError)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corridor() -> GrpcCorridor {
+        GrpcCorridor {
+            identifier: "CORRIDOR-1".to_string(),
+            centerline: vec![
+                GrpcPointZ {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.3749819,
+                    longitude: 4.9156925,
+                    altitude_meters: 100.0,
+                },
+            ],
+            width_meters: 100.0,
+            height_meters: 50.0,
+            capacity: 1,
+        }
+    }
+
+    #[test]
+    fn ut_corridor_try_from() {
+        let corridor = Corridor::try_from(corridor()).unwrap();
+        assert_eq!(corridor.geom.points.len(), 2);
+        assert_eq!(corridor.geom.srid, Some(DEFAULT_SRID));
+        assert_eq!(corridor.capacity, 1);
+
+        let mut invalid = self::corridor();
+        invalid.identifier = "CORRIDOR 1".to_string();
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Identifier
+        );
+
+        let mut invalid = self::corridor();
+        invalid.centerline.truncate(1);
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Location
+        );
+
+        let mut invalid = self::corridor();
+        invalid.centerline[1].latitude = f64::NAN;
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Location
+        );
+
+        let mut invalid = self::corridor();
+        invalid.width_meters = 0.0;
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Dimensions
+        );
+
+        let mut invalid = self::corridor();
+        invalid.height_meters = f64::INFINITY;
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Dimensions
+        );
+
+        let mut invalid = self::corridor();
+        invalid.capacity = 0;
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Capacity
+        );
+
+        let mut invalid = self::corridor();
+        invalid.capacity = u32::MAX;
+        assert_eq!(
+            Corridor::try_from(invalid).unwrap_err(),
+            CorridorError::Capacity
+        );
+    }
+
+    #[test]
+    fn ut_validate_allocation_request() {
+        let time_start = Utc::now();
+        let request = GetCorridorAllocationRequest {
+            identifier: "CORRIDOR-1".to_string(),
+            time_start: Some(time_start.into()),
+            time_end: Some((time_start + Duration::try_hours(1).unwrap()).into()),
+            slice_seconds: 300,
+        };
+
+        let (_, _, slice) = validate_allocation_request(&request).unwrap();
+        assert_eq!(slice.num_seconds(), 300);
+
+        let mut invalid = request.clone();
+        invalid.time_end = invalid.time_start.clone();
+        assert_eq!(
+            validate_allocation_request(&invalid).unwrap_err(),
+            CorridorError::Time
+        );
+
+        let mut invalid = request.clone();
+        invalid.time_start = None;
+        assert_eq!(
+            validate_allocation_request(&invalid).unwrap_err(),
+            CorridorError::Time
+        );
+
+        let mut invalid = request.clone();
+        invalid.slice_seconds = 0;
+        assert_eq!(
+            validate_allocation_request(&invalid).unwrap_err(),
+            CorridorError::Slices
+        );
+
+        // One hour in one second slices
+        let mut invalid = request.clone();
+        invalid.slice_seconds = 1;
+        assert_eq!(
+            validate_allocation_request(&invalid).unwrap_err(),
+            CorridorError::Slices
+        );
+
+        let mut invalid = request;
+        invalid.identifier = "".to_string();
+        assert_eq!(
+            validate_allocation_request(&invalid).unwrap_err(),
+            CorridorError::Identifier
+        );
+    }
+
+    #[test]
+    fn ut_corridor_floor() {
+        use crate::grpc::server::grpc_server::Coordinates;
+
+        let vertices: Vec<Coordinates> = [
+            (52.3745, 4.9160),
+            (52.3745, 4.9170),
+            (52.3755, 4.9170),
+            (52.3745, 4.9160),
+        ]
+        .iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude: *latitude,
+            longitude: *longitude,
+        })
+        .collect();
+        let footprint = super::super::utils::polygon_from_vertices_z(&vertices, 0.0).unwrap();
+
+        let floor = corridor_floor(&footprint, &(80.0..120.0)).unwrap();
+        assert_eq!(floor.srid, Some(DEFAULT_SRID));
+        assert!(floor.rings[0].points.iter().all(|point| point.z == 80.0));
+
+        assert_eq!(
+            corridor_floor(&footprint, &(120.0..80.0)).unwrap_err(),
+            CorridorError::Dimensions
+        );
+        assert_eq!(
+            corridor_floor(&footprint, &(80.0..f64::NAN)).unwrap_err(),
+            CorridorError::Dimensions
+        );
+
+        let mut open = footprint.clone();
+        open.rings[0].points.pop();
+        assert_eq!(
+            corridor_floor(&open, &(80.0..120.0)).unwrap_err(),
+            CorridorError::Location
+        );
+
+        let mut invalid = footprint;
+        invalid.rings[0].points[1].y = 91.0;
+        assert_eq!(
+            corridor_floor(&invalid, &(80.0..120.0)).unwrap_err(),
+            CorridorError::Location
+        );
+    }
+
+    #[test]
+    fn ut_merge_corridor_conflict() {
+        let start = Utc::now();
+        let at = |seconds: i64| start + Duration::try_seconds(seconds).unwrap();
+        let conflict = |identifier: &str, from: i64, to: i64, flight_count: u32| CorridorConflict {
+            corridor_identifier: identifier.to_string(),
+            capacity: 1,
+            flight_count,
+            time_start: at(from),
+            time_end: at(to),
+        };
+
+        let mut conflicts = vec![];
+        merge_corridor_conflict(&mut conflicts, conflict("A", 0, 10, 1));
+        merge_corridor_conflict(&mut conflicts, conflict("B", 0, 10, 1));
+        merge_corridor_conflict(&mut conflicts, conflict("A", 10, 20, 2));
+
+        // Left the corridor, then entered again
+        merge_corridor_conflict(&mut conflicts, conflict("A", 30, 40, 1));
+
+        assert_eq!(
+            conflicts,
+            vec![
+                conflict("A", 0, 20, 2),
+                conflict("B", 0, 10, 1),
+                conflict("A", 30, 40, 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1040 @@
+//! GeoJSON and CSV exports
+//!
+//! Rows are read from the database as they arrive and packed into chunks
+//!  of newline-delimited GeoJSON Features or CSV records, so peak memory
+//!  is bounded by the chunk size rather than the number of exported rows.
+//!  Dropping the stream (e.g. when the client cancels) drops the row
+//!  stream and returns the connections to their pools.
+//!
+//! Aircraft are exported from each shard in turn, so their rows are only
+//!  ordered within a shard.
+//!
+//! Flights in a map viewport are returned as a single, capped
+//!  FeatureCollection instead.
+
+use super::aircraft::get_history_table_name;
+use super::aircraft::position_source;
+use super::flight::{get_flight_segments_table_name, get_flights_table_name, FlightError};
+use super::tags::TagFilter;
+use super::utils::{LAT_MAX, LAT_MIN, LON_MAX, LON_MIN};
+use super::{PostgisError, PsqlError, DEFAULT_SRID};
+use crate::grpc::server::grpc_server::{
+    CsvChunk, CsvDataset, ExportCsvRequest, GeoJsonChunk, GetFlightsRequest,
+};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Object;
+use futures::{Stream, StreamExt};
+use num_traits::FromPrimitive;
+use std::pin::Pin;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Row;
+
+/// Max features sent in one chunk
+pub const MAX_FEATURES_PER_CHUNK: usize = 500;
+
+/// Max size of the features of one chunk in bytes, well below the default
+///  gRPC message limit of 4 MiB
+pub const MAX_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Max features in a flight FeatureCollection
+pub const MAX_FLIGHT_FEATURES: usize = 1_000;
+
+/// Packs GeoJSON Feature lines into chunks
+#[derive(Debug, Default)]
+struct ChunkBuilder {
+    /// Sequence number of the chunk being built
+    sequence: u32,
+
+    /// Newline-delimited features of the chunk being built
+    features: String,
+
+    /// Number of features in the chunk being built
+    count: u32,
+}
+
+impl ChunkBuilder {
+    /// Takes the chunk being built and starts the next one
+    fn take(&mut self) -> GeoJsonChunk {
+        let chunk = GeoJsonChunk {
+            sequence: self.sequence,
+            features: std::mem::take(&mut self.features),
+            feature_count: self.count,
+        };
+
+        self.sequence += 1;
+        self.count = 0;
+        chunk
+    }
+
+    /// Adds a feature, returning the previous chunk if it is full
+    ///
+    /// A single feature larger than [`MAX_CHUNK_BYTES`] is sent in a chunk
+    ///  of its own.
+    fn push(&mut self, feature: &str) -> Option<GeoJsonChunk> {
+        let full = self.count > 0
+            && (self.count as usize >= MAX_FEATURES_PER_CHUNK
+                || self.features.len() + feature.len() + 1 > MAX_CHUNK_BYTES);
+
+        let chunk = full.then(|| self.take());
+
+        self.features.push_str(feature);
+        self.features.push('\n');
+        self.count += 1;
+
+        chunk
+    }
+
+    /// Takes the last chunk, if it has any features
+    fn finish(&mut self) -> Option<GeoJsonChunk> {
+        (self.count > 0).then(|| self.take())
+    }
+}
+
+/// Possible errors exporting a dataset
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExportError {
+    /// Unknown dataset
+    Dataset,
+
+    /// Invalid time window
+    Time,
+
+    /// Invalid spatial window
+    Location,
+
+    /// Could not get client
+    Client,
+
+    /// Could not run the export query
+    DBError,
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ExportError::Dataset => write!(f, "Unknown dataset provided."),
+            ExportError::Time => write!(f, "Invalid time window provided."),
+            ExportError::Location => write!(f, "Invalid spatial window provided."),
+            ExportError::Client => write!(f, "Could not get backend client."),
+            ExportError::DBError => write!(f, "Could not run the export query."),
+        }
+    }
+}
+
+/// Packs CSV records into chunks, the first chunk starts with the header
+#[derive(Debug)]
+struct CsvChunkBuilder {
+    /// Sequence number of the chunk being built
+    sequence: u32,
+
+    /// CRLF-terminated records of the chunk being built
+    data: String,
+
+    /// Number of records in the chunk being built, without the header
+    count: u32,
+}
+
+impl CsvChunkBuilder {
+    /// Starts the first chunk with the header record
+    fn new(header: &str) -> Self {
+        CsvChunkBuilder {
+            sequence: 0,
+            data: format!("{header}\r\n"),
+            count: 0,
+        }
+    }
+
+    /// Takes the chunk being built and starts the next one
+    fn take(&mut self) -> CsvChunk {
+        let chunk = CsvChunk {
+            sequence: self.sequence,
+            data: std::mem::take(&mut self.data),
+            row_count: self.count,
+        };
+
+        self.sequence += 1;
+        self.count = 0;
+        chunk
+    }
+
+    /// Adds a record, returning the previous chunk if it is full
+    ///
+    /// A single record larger than [`MAX_CHUNK_BYTES`] is sent in a chunk
+    ///  of its own.
+    fn push(&mut self, record: &str) -> Option<CsvChunk> {
+        let full = self.count > 0
+            && (self.count as usize >= MAX_FEATURES_PER_CHUNK
+                || self.data.len() + record.len() + 2 > MAX_CHUNK_BYTES);
+
+        let chunk = full.then(|| self.take());
+
+        self.data.push_str(record);
+        self.data.push_str("\r\n");
+        self.count += 1;
+
+        chunk
+    }
+
+    /// Takes the last chunk, if it has any data
+    ///
+    /// An export without rows still sends the header.
+    fn finish(&mut self) -> Option<CsvChunk> {
+        (!self.data.is_empty()).then(|| self.take())
+    }
+}
+
+/// Quotes a CSV field if needed (RFC 4180)
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Joins fields into a CSV record, without the line break
+///
+/// NULL values are written as empty fields.
+fn csv_record<'a>(fields: impl IntoIterator<Item = Option<&'a str>>) -> String {
+    fields
+        .into_iter()
+        .map(|field| csv_field(field.unwrap_or_default()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Rows of an export, read from one database after the other
+type ExportRows = Pin<Box<dyn Stream<Item = Result<Row, tokio_postgres::Error>> + Send>>;
+
+/// Runs a query on each client, the rows of a client are read once those
+///  of the previous one are
+async fn query_each(
+    clients: &[Object],
+    query: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> Result<ExportRows, tokio_postgres::Error> {
+    let mut streams = vec![];
+    for client in clients {
+        let stmt = client.prepare_cached(query).await?;
+        streams.push(client.query_raw(&stmt, params.iter().copied()).await?);
+    }
+
+    Ok(Box::pin(futures::stream::iter(streams).flatten()))
+}
+
+/// State of an export stream
+struct ExportState<B> {
+    /// Held until the export ends so the rows can still be read
+    _clients: Vec<Object>,
+
+    /// Rows not yet read
+    rows: ExportRows,
+
+    /// Chunk being built, none once the export has ended
+    builder: Option<B>,
+}
+
+/// Packs the `feature` column of the rows into chunks
+fn chunk_stream(
+    clients: Vec<Object>,
+    rows: ExportRows,
+) -> impl Stream<Item = Result<GeoJsonChunk, PostgisError>> {
+    let state = ExportState {
+        _clients: clients,
+        rows,
+        builder: Some(ChunkBuilder::default()),
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        let builder = state.builder.as_mut()?;
+
+        loop {
+            match state.rows.next().await {
+                Some(Ok(row)) => {
+                    let feature: String = match row.try_get("feature") {
+                        Ok(feature) => feature,
+                        Err(e) => {
+                            postgis_error!("(chunk_stream) could not get feature: {}", e);
+                            state.builder = None;
+                            return Some((Err(PostgisError::Psql(PsqlError::Execute)), state));
+                        }
+                    };
+
+                    if let Some(chunk) = builder.push(&feature) {
+                        return Some((Ok(chunk), state));
+                    }
+                }
+                Some(Err(e)) => {
+                    postgis_error!("(chunk_stream) could not read row: {}", e);
+                    state.builder = None;
+                    return Some((Err(PostgisError::Psql(PsqlError::Execute)), state));
+                }
+                None => {
+                    let chunk = builder.finish();
+                    state.builder = None;
+                    return chunk.map(|chunk| (Ok(chunk), state));
+                }
+            }
+        }
+    })
+}
+
+/// Packs the rows into chunks of CSV records, every column is read as
+///  nullable text
+fn csv_chunk_stream(
+    clients: Vec<Object>,
+    rows: ExportRows,
+    header: &str,
+) -> impl Stream<Item = Result<CsvChunk, PostgisError>> {
+    let state = ExportState {
+        _clients: clients,
+        rows,
+        builder: Some(CsvChunkBuilder::new(header)),
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        let builder = state.builder.as_mut()?;
+
+        loop {
+            match state.rows.next().await {
+                Some(Ok(row)) => {
+                    let fields = match (0..row.len())
+                        .map(|index| row.try_get::<_, Option<String>>(index))
+                        .collect::<Result<Vec<_>, _>>()
+                    {
+                        Ok(fields) => fields,
+                        Err(e) => {
+                            postgis_error!("(csv_chunk_stream) could not get field: {}", e);
+                            state.builder = None;
+                            return Some((Err(PostgisError::Export(ExportError::DBError)), state));
+                        }
+                    };
+
+                    let record = csv_record(fields.iter().map(|field| field.as_deref()));
+                    if let Some(chunk) = builder.push(&record) {
+                        return Some((Ok(chunk), state));
+                    }
+                }
+                Some(Err(e)) => {
+                    postgis_error!("(csv_chunk_stream) could not read row: {}", e);
+                    state.builder = None;
+                    return Some((Err(PostgisError::Export(ExportError::DBError)), state));
+                }
+                None => {
+                    let chunk = builder.finish();
+                    state.builder = None;
+                    return chunk.map(|chunk| (Ok(chunk), state));
+                }
+            }
+        }
+    })
+}
+
+/// Streams all aircraft with a known position as GeoJSON Features
+///
+/// With an `operator_id`, only that operator's aircraft are streamed.
+pub async fn aircraft_geojson_stream(
+    include_simulated: bool,
+    operator_id: Option<&str>,
+) -> Result<impl Stream<Item = Result<GeoJsonChunk, PostgisError>>, PostgisError> {
+    postgis_debug!("(aircraft_geojson_stream) entry.");
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(aircraft_geojson_stream) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(aircraft_geojson_stream) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    let mut clients = vec![client];
+    clients.extend(
+        super::shard::get_extra_shard_clients()
+            .await
+            .map_err(|_| PostgisError::Psql(PsqlError::Client))?,
+    );
+
+    let query = format!(
+        r#"SELECT json_build_object(
+                'type', 'Feature',
+                'id', "identifier",
+                'geometry', ST_AsGeoJSON("geom")::json,
+                'properties', json_build_object(
+                    'identifier', "identifier",
+                    'aircraft_type', "aircraft_type",
+                    'op_status', "op_status",
+                    'simulated', "simulated",
+                    'velocity_horizontal_ground_mps', "velocity_horizontal_ground_mps",
+                    'velocity_vertical_mps', "velocity_vertical_mps",
+                    'track_angle_degrees', "track_angle_degrees",
+                    'last_position_update', "last_position_update",
+                    'operator_id', "operator_id"
+                )
+            )::TEXT AS "feature"
+            FROM {table_name}
+            WHERE "geom" IS NOT NULL
+                AND ("simulated" = FALSE OR $1)
+                AND ($2::VARCHAR IS NULL OR "operator_id" = $2)
+            ORDER BY "identifier";"#,
+        table_name = position_source(),
+    );
+
+    let rows = query_each(&clients, &query, &[&include_simulated, &operator_id])
+        .await
+        .map_err(|e| {
+            postgis_error!("(aircraft_geojson_stream) could not execute query: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    Ok(chunk_stream(clients, rows))
+}
+
+/// Wraps features in a GeoJSON FeatureCollection, keeping at most
+///  [`MAX_FLIGHT_FEATURES`]
+fn feature_collection(mut features: Vec<serde_json::Value>) -> serde_json::Value {
+    if features.len() > MAX_FLIGHT_FEATURES {
+        postgis_warn!(
+            "(feature_collection) {} features exceed the max of {}, truncating.",
+            features.len(),
+            MAX_FLIGHT_FEATURES
+        );
+        features.truncate(MAX_FLIGHT_FEATURES);
+    }
+
+    serde_json::json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+/// Validates the viewport, time range and filters of a flight export
+fn validate_flights_window(
+    request: &GetFlightsRequest,
+) -> Result<(DateTime<Utc>, DateTime<Utc>, TagFilter), FlightError> {
+    let (Some(time_start), Some(time_end)) = (request.time_start.clone(), request.time_end.clone())
+    else {
+        postgis_error!("(validate_flights_window) time_start and time_end are required.");
+        return Err(FlightError::Time);
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end < time_start {
+        postgis_error!("(validate_flights_window) time_end is before time_start.");
+        return Err(FlightError::Time);
+    }
+
+    let longitudes = LON_MIN..=LON_MAX;
+    let latitudes = LAT_MIN..=LAT_MAX;
+    if !longitudes.contains(&request.window_min_x)
+        || !longitudes.contains(&request.window_max_x)
+        || !latitudes.contains(&request.window_min_y)
+        || !latitudes.contains(&request.window_max_y)
+        || request.window_min_x > request.window_max_x
+        || request.window_min_y > request.window_max_y
+    {
+        postgis_error!(
+            "(validate_flights_window) invalid window: ({}, {}) to ({}, {})",
+            request.window_min_x,
+            request.window_min_y,
+            request.window_max_x,
+            request.window_max_y
+        );
+        return Err(FlightError::Location);
+    }
+
+    if let Some(ref operator_id) = request.operator_id {
+        if let Err(e) = super::utils::check_label(operator_id) {
+            postgis_error!(
+                "(validate_flights_window) invalid operator_id {}: {}",
+                operator_id,
+                e
+            );
+            return Err(FlightError::Label);
+        }
+    }
+
+    let tag_filter = TagFilter::try_from(request.tag_filter.clone()).map_err(|e| {
+        postgis_error!("(validate_flights_window) invalid tag filter: {}", e);
+        FlightError::Label
+    })?;
+
+    Ok((time_start, time_end, tag_filter))
+}
+
+/// Gets the flights in a viewport and time range as a GeoJSON
+///  FeatureCollection
+///
+/// Each flight is a Feature with its path as a LineString. The window, time
+///  range, operator and tag filters of the request are used, `order_by` is
+///  ignored. At most [`MAX_FLIGHT_FEATURES`] flights are returned.
+pub async fn get_flights_geojson(
+    request: &GetFlightsRequest,
+    pool: &deadpool_postgres::Pool,
+) -> Result<serde_json::Value, PostgisError> {
+    postgis_debug!("(get_flights_geojson) entry.");
+    let (time_start, time_end, tag_filter) =
+        validate_flights_window(request).map_err(PostgisError::FlightPath)?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_flights_geojson) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    // One more than the cap so truncation can be logged
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT json_build_object(
+                'type', 'Feature',
+                'id', "flight_identifier",
+                'geometry', ST_AsGeoJSON("geom")::json,
+                'properties', json_build_object(
+                    'identifier', "flight_identifier",
+                    'aircraft_identifier', "aircraft_identifier",
+                    'aircraft_type', "aircraft_type",
+                    'time_start', "time_start",
+                    'time_end', "time_end",
+                    'simulated', "simulated"
+                )
+            )::TEXT AS "feature"
+            FROM {table_name}
+            WHERE "geom" IS NOT NULL
+                AND "deleted_at" IS NULL
+                AND "isa" && ST_MakeEnvelope($1, $2, $3, $4, {DEFAULT_SRID})
+                AND ST_Intersects(
+                    ST_Force2D("geom"),
+                    ST_MakeEnvelope($1, $2, $3, $4, {DEFAULT_SRID})
+                )
+                AND "time_end" >= $5
+                AND "time_start" <= $6
+                AND ($7::VARCHAR IS NULL OR "operator_id" = $7)
+                AND {tag_condition}
+            ORDER BY "flight_identifier"
+            LIMIT {limit};"#,
+            table_name = get_flights_table_name(),
+            tag_condition = tag_filter.condition(r#""tags""#, 8),
+            limit = MAX_FLIGHT_FEATURES + 1,
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_flights_geojson) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let features = client
+        .query(
+            &stmt,
+            &[
+                &request.window_min_x,
+                &request.window_min_y,
+                &request.window_max_x,
+                &request.window_max_y,
+                &time_start,
+                &time_end,
+                &request.operator_id,
+                &tag_filter.tags,
+            ],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_flights_geojson) could not execute query: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            let feature: String = row.try_get("feature").map_err(|e| {
+                postgis_error!("(get_flights_geojson) could not get feature: {}", e);
+                PostgisError::FlightPath(FlightError::DBError)
+            })?;
+
+            serde_json::from_str(&feature).map_err(|e| {
+                postgis_error!("(get_flights_geojson) could not parse feature: {}", e);
+                PostgisError::FlightPath(FlightError::DBError)
+            })
+        })
+        .collect::<Result<Vec<serde_json::Value>, PostgisError>>()?;
+
+    postgis_debug!("(get_flights_geojson) found {} flights.", features.len());
+    Ok(feature_collection(features))
+}
+
+/// Formats a timestamp column as RFC 3339 text in UTC
+fn csv_timestamp(column: &str) -> String {
+    format!(r#"to_char({column} AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"Z"')"#)
+}
+
+/// Gets the header and query of a CSV dataset
+///
+/// The query takes the window ($1 to $4) and the time range ($5, $6) and
+///  selects every column as text, geometries as WKT.
+fn csv_query(dataset: CsvDataset) -> (String, String) {
+    let envelope = format!("ST_MakeEnvelope($1, $2, $3, $4, {DEFAULT_SRID})");
+    let (columns, from) = match dataset {
+        CsvDataset::Flights => (
+            vec![
+                ("flight_identifier", r#""flight_identifier""#.to_string()),
+                (
+                    "aircraft_identifier",
+                    r#""aircraft_identifier""#.to_string(),
+                ),
+                ("aircraft_type", r#""aircraft_type"::TEXT"#.to_string()),
+                ("simulated", r#""simulated"::TEXT"#.to_string()),
+                ("operator_id", r#""operator_id""#.to_string()),
+                ("time_start", csv_timestamp(r#""time_start""#)),
+                ("time_end", csv_timestamp(r#""time_end""#)),
+                (
+                    "destination_identifier",
+                    r#""destination_identifier""#.to_string(),
+                ),
+                ("tags", r#"array_to_string("tags", ';')"#.to_string()),
+                ("scenario_id", r#""scenario_id""#.to_string()),
+                ("deleted_at", csv_timestamp(r#""deleted_at""#)),
+                ("geom_wkt", r#"ST_AsText("geom")"#.to_string()),
+            ],
+            format!(
+                r#"FROM {table_name}
+                WHERE "isa" && {envelope}
+                    AND ST_Intersects(ST_Force2D("isa"), {envelope})
+                    AND "time_end" >= $5
+                    AND "time_start" <= $6
+                ORDER BY "flight_identifier""#,
+                table_name = get_flights_table_name(),
+            ),
+        ),
+        CsvDataset::FlightSegments => (
+            vec![
+                ("flight_identifier", r#""flight_identifier""#.to_string()),
+                ("time_start", csv_timestamp(r#""time_start""#)),
+                ("time_end", csv_timestamp(r#""time_end""#)),
+                ("geom_wkt", r#"ST_AsText("geom")"#.to_string()),
+            ],
+            format!(
+                r#"FROM {table_name}
+                WHERE ST_Intersects(ST_Force2D("geom"), {envelope})
+                    AND "time_end" >= $5
+                    AND "time_start" <= $6
+                ORDER BY "flight_identifier", "time_start""#,
+                table_name = get_flight_segments_table_name(),
+            ),
+        ),
+        CsvDataset::AircraftHistory => (
+            vec![
+                ("identifier", r#""identifier""#.to_string()),
+                ("timestamp_network", csv_timestamp(r#""timestamp_network""#)),
+                ("timestamp_asset", csv_timestamp(r#""timestamp_asset""#)),
+                (
+                    "velocity_horizontal_ground_mps",
+                    r#""velocity_horizontal_ground_mps"::TEXT"#.to_string(),
+                ),
+                (
+                    "velocity_horizontal_air_mps",
+                    r#""velocity_horizontal_air_mps"::TEXT"#.to_string(),
+                ),
+                (
+                    "velocity_vertical_mps",
+                    r#""velocity_vertical_mps"::TEXT"#.to_string(),
+                ),
+                (
+                    "track_angle_degrees",
+                    r#""track_angle_degrees"::TEXT"#.to_string(),
+                ),
+                (
+                    "horizontal_accuracy_meters",
+                    r#""horizontal_accuracy_meters"::TEXT"#.to_string(),
+                ),
+                (
+                    "vertical_accuracy_meters",
+                    r#""vertical_accuracy_meters"::TEXT"#.to_string(),
+                ),
+                ("outlier", r#""outlier"::TEXT"#.to_string()),
+                ("geom_wkt", r#"ST_AsText("geom")"#.to_string()),
+            ],
+            format!(
+                r#"FROM {table_name}
+                WHERE ST_Intersects(ST_Force2D("geom"), {envelope})
+                    AND "timestamp_network" >= $5
+                    AND "timestamp_network" <= $6
+                ORDER BY "identifier", "timestamp_network""#,
+                table_name = get_history_table_name(),
+            ),
+        ),
+    };
+
+    let header = csv_record(columns.iter().map(|(name, _)| Some(*name)));
+    let select = columns
+        .iter()
+        .map(|(_, expression)| expression.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    (header, format!("SELECT {select} {from};"))
+}
+
+/// Validates the dataset, time range and window of a CSV export
+fn validate_csv_request(
+    request: &ExportCsvRequest,
+) -> Result<(CsvDataset, DateTime<Utc>, DateTime<Utc>), ExportError> {
+    let Some(dataset) = FromPrimitive::from_i32(request.dataset) else {
+        postgis_error!(
+            "(validate_csv_request) invalid dataset: {}",
+            request.dataset
+        );
+        return Err(ExportError::Dataset);
+    };
+
+    let (Some(time_start), Some(time_end)) = (request.time_start.clone(), request.time_end.clone())
+    else {
+        postgis_error!("(validate_csv_request) time_start and time_end are required.");
+        return Err(ExportError::Time);
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end < time_start {
+        postgis_error!("(validate_csv_request) time_end is before time_start.");
+        return Err(ExportError::Time);
+    }
+
+    let longitudes = LON_MIN..=LON_MAX;
+    let latitudes = LAT_MIN..=LAT_MAX;
+    if !longitudes.contains(&request.window_min_x)
+        || !longitudes.contains(&request.window_max_x)
+        || !latitudes.contains(&request.window_min_y)
+        || !latitudes.contains(&request.window_max_y)
+        || request.window_min_x > request.window_max_x
+        || request.window_min_y > request.window_max_y
+    {
+        postgis_error!(
+            "(validate_csv_request) invalid window: ({}, {}) to ({}, {})",
+            request.window_min_x,
+            request.window_min_y,
+            request.window_max_x,
+            request.window_max_y
+        );
+        return Err(ExportError::Location);
+    }
+
+    Ok((dataset, time_start, time_end))
+}
+
+/// Streams the rows of a dataset in a window and time range as CSV
+///
+/// Flights and segments overlapping the time range are exported, aircraft
+///  history rows are exported if their network timestamp is in range.
+///  Soft-deleted flights are included with their deletion time.
+pub async fn csv_stream(
+    request: &ExportCsvRequest,
+    pool: &deadpool_postgres::Pool,
+) -> Result<impl Stream<Item = Result<CsvChunk, PostgisError>>, PostgisError> {
+    postgis_debug!("(csv_stream) entry.");
+    let (dataset, time_start, time_end) =
+        validate_csv_request(request).map_err(PostgisError::Export)?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(csv_stream) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Export(ExportError::Client)
+    })?;
+
+    // The history of an aircraft is on its own shard
+    let mut clients = vec![client];
+    if dataset == CsvDataset::AircraftHistory {
+        clients.extend(
+            super::shard::get_extra_shard_clients()
+                .await
+                .map_err(|_| PostgisError::Export(ExportError::Client))?,
+        );
+    }
+
+    let (header, query) = csv_query(dataset);
+    let params: [&(dyn ToSql + Sync); 6] = [
+        &request.window_min_x,
+        &request.window_min_y,
+        &request.window_max_x,
+        &request.window_max_y,
+        &time_start,
+        &time_end,
+    ];
+    let rows = query_each(&clients, &query, &params).await.map_err(|e| {
+        postgis_error!("(csv_stream) could not execute query: {}", e);
+        PostgisError::Export(ExportError::DBError)
+    })?;
+
+    Ok(csv_chunk_stream(clients, rows, &header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(index: usize) -> String {
+        serde_json::json!({
+            "type": "Feature",
+            "id": format!("AIRCRAFT-{index}"),
+            "geometry": {
+                "type": "Point",
+                "coordinates": [4.9160036, 52.3745905, 100.0]
+            },
+            "properties": { "identifier": format!("AIRCRAFT-{index}") }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn ut_chunk_builder() {
+        let total = 50_000;
+        let mut builder = ChunkBuilder::default();
+        let mut chunks = vec![];
+        for index in 0..total {
+            chunks.extend(builder.push(&feature(index)));
+        }
+        chunks.extend(builder.finish());
+        assert!(builder.finish().is_none());
+
+        assert_eq!(chunks.len(), total / MAX_FEATURES_PER_CHUNK);
+
+        let mut count = 0;
+        for (sequence, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.sequence as usize, sequence);
+            assert!(chunk.feature_count as usize <= MAX_FEATURES_PER_CHUNK);
+            assert!(chunk.features.len() <= MAX_CHUNK_BYTES);
+
+            // Chunk boundaries fall between complete features
+            let lines = chunk.features.lines().collect::<Vec<_>>();
+            assert_eq!(lines.len(), chunk.feature_count as usize);
+            for line in lines {
+                let value: serde_json::Value = serde_json::from_str(line).unwrap();
+                assert_eq!(value["type"], "Feature");
+            }
+
+            count += chunk.feature_count as usize;
+        }
+
+        assert_eq!(count, total);
+    }
+
+    #[test]
+    fn ut_chunk_builder_size_limit() {
+        let large = format!(r#"{{"padding": "{}"}}"#, "x".repeat(MAX_CHUNK_BYTES / 3));
+        let mut builder = ChunkBuilder::default();
+
+        // Two features fit, the third starts a new chunk
+        assert!(builder.push(&large).is_none());
+        assert!(builder.push(&large).is_none());
+        let chunk = builder.push(&large).unwrap();
+        assert_eq!(chunk.feature_count, 2);
+        assert!(chunk.features.len() <= MAX_CHUNK_BYTES);
+
+        // Oversized features are sent alone
+        let oversized = "x".repeat(MAX_CHUNK_BYTES + 1);
+        let chunk = builder.push(&oversized).unwrap();
+        assert_eq!(chunk.feature_count, 1);
+        let chunk = builder.finish().unwrap();
+        assert_eq!(chunk.feature_count, 1);
+        assert_eq!(chunk.sequence, 2);
+    }
+
+    #[test]
+    fn ut_feature_collection() {
+        let collection = feature_collection(vec![]);
+        assert_eq!(collection["type"], "FeatureCollection");
+        assert_eq!(collection["features"].as_array().unwrap().len(), 0);
+
+        let features = (0..MAX_FLIGHT_FEATURES + 1)
+            .map(|index| serde_json::from_str(&feature(index)).unwrap())
+            .collect();
+        let collection = feature_collection(features);
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), MAX_FLIGHT_FEATURES);
+        assert_eq!(features[0]["id"], "AIRCRAFT-0");
+    }
+
+    fn flights_request() -> GetFlightsRequest {
+        let time_start = Utc::now();
+        GetFlightsRequest {
+            window_min_x: 4.9,
+            window_min_y: 52.3,
+            window_max_x: 5.0,
+            window_max_y: 52.4,
+            time_start: Some(time_start.into()),
+            time_end: Some((time_start + chrono::Duration::try_hours(1).unwrap()).into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ut_validate_flights_window() {
+        assert!(validate_flights_window(&flights_request()).is_ok());
+
+        let invalid = GetFlightsRequest {
+            window_min_x: 5.1,
+            ..flights_request()
+        };
+        assert_eq!(
+            validate_flights_window(&invalid).unwrap_err(),
+            FlightError::Location
+        );
+
+        let invalid = GetFlightsRequest {
+            window_max_y: f64::NAN,
+            ..flights_request()
+        };
+        assert_eq!(
+            validate_flights_window(&invalid).unwrap_err(),
+            FlightError::Location
+        );
+
+        let invalid = GetFlightsRequest {
+            time_end: None,
+            ..flights_request()
+        };
+        assert_eq!(
+            validate_flights_window(&invalid).unwrap_err(),
+            FlightError::Time
+        );
+
+        let invalid = GetFlightsRequest {
+            operator_id: Some("'Operator'".to_string()),
+            ..flights_request()
+        };
+        assert_eq!(
+            validate_flights_window(&invalid).unwrap_err(),
+            FlightError::Label
+        );
+
+        let invalid = GetFlightsRequest {
+            tag_filter: Some(crate::grpc::server::grpc_server::TagFilter {
+                tags: vec!["vip;".to_string()],
+                mode: 0,
+            }),
+            ..flights_request()
+        };
+        assert_eq!(
+            validate_flights_window(&invalid).unwrap_err(),
+            FlightError::Label
+        );
+    }
+
+    #[test]
+    fn ut_csv_field() {
+        assert_eq!(csv_field("AIRCRAFT-1"), "AIRCRAFT-1");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), r#""a,b""#);
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("line\r\nbreak"), "\"line\r\nbreak\"");
+    }
+
+    #[test]
+    fn ut_csv_record() {
+        let record = csv_record([Some("FLIGHT,1"), None, Some("vip;cargo"), Some("")]);
+        assert_eq!(record, r#""FLIGHT,1",,vip;cargo,"#);
+    }
+
+    #[test]
+    fn ut_csv_chunk_builder() {
+        // An export without rows still sends the header
+        let mut builder = CsvChunkBuilder::new("a,b");
+        let chunk = builder.finish().unwrap();
+        assert_eq!(chunk.data, "a,b\r\n");
+        assert_eq!(chunk.row_count, 0);
+        assert!(builder.finish().is_none());
+
+        let total = MAX_FEATURES_PER_CHUNK + 1;
+        let mut builder = CsvChunkBuilder::new("a,b");
+        let mut chunks = vec![];
+        for index in 0..total {
+            chunks.extend(builder.push(&format!("{index},x")));
+        }
+        chunks.extend(builder.finish());
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].sequence, 0);
+        assert_eq!(chunks[0].row_count as usize, MAX_FEATURES_PER_CHUNK);
+        assert!(chunks[0].data.starts_with("a,b\r\n0,x\r\n"));
+        assert_eq!(chunks[1].sequence, 1);
+        assert_eq!(chunks[1].row_count, 1);
+        assert_eq!(chunks[1].data, format!("{},x\r\n", total - 1));
+    }
+
+    #[test]
+    fn ut_csv_query() {
+        let (header, query) = csv_query(CsvDataset::FlightSegments);
+        assert_eq!(header, "flight_identifier,time_start,time_end,geom_wkt");
+        assert!(query.contains("ST_AsText"));
+        assert!(query.contains(get_flight_segments_table_name()));
+    }
+
+    fn csv_request() -> ExportCsvRequest {
+        let time_start = Utc::now();
+        ExportCsvRequest {
+            dataset: CsvDataset::Flights as i32,
+            time_start: Some(time_start.into()),
+            time_end: Some((time_start + chrono::Duration::try_hours(1).unwrap()).into()),
+            window_min_x: 4.9,
+            window_min_y: 52.3,
+            window_max_x: 5.0,
+            window_max_y: 52.4,
+        }
+    }
+
+    #[test]
+    fn ut_validate_csv_request() {
+        assert!(validate_csv_request(&csv_request()).is_ok());
+
+        let invalid = ExportCsvRequest {
+            dataset: 99,
+            ..csv_request()
+        };
+        assert_eq!(
+            validate_csv_request(&invalid).unwrap_err(),
+            ExportError::Dataset
+        );
+
+        let request = csv_request();
+        let invalid = ExportCsvRequest {
+            time_start: request.time_end.clone(),
+            time_end: request.time_start.clone(),
+            ..csv_request()
+        };
+        assert_eq!(
+            validate_csv_request(&invalid).unwrap_err(),
+            ExportError::Time
+        );
+
+        let invalid = ExportCsvRequest {
+            window_max_y: 91.0,
+            ..csv_request()
+        };
+        assert_eq!(
+            validate_csv_request(&invalid).unwrap_err(),
+            ExportError::Location
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_aircraft_geojson_stream_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_aircraft_geojson_stream_client_failure) start");
+
+        let Err(result) = aircraft_geojson_stream(false, None).await else {
+            panic!("expected a client error");
+        };
+        assert_eq!(result, PostgisError::Psql(PsqlError::Client));
+
+        ut_info!("(ut_aircraft_geojson_stream_client_failure) success");
+    }
+}
@@ -1,24 +1,182 @@
 //! This module contains functions for updating aircraft flight paths in the PostGIS database.
+//!
+//! Lifecycle steps of flights are persisted in the event backlog (see
+//!  [`super::backlog`]) in the same transaction, and published on the
+//!  flight event channel streamed by `streamFlightEvents` once committed.
 
-use super::{psql_transaction, PostgisError, DEFAULT_SRID, PSQL_SCHEMA};
+use super::backlog::{self, EventKind, PersistedEvent};
+use super::{psql_transaction, DbErrorKind, PostgisError, DEFAULT_SRID, PSQL_SCHEMA};
+use crate::cache::applied::{record_flight_applied, FlightApplied};
+use crate::cache::{Consumer, Processor};
 use crate::grpc::server::grpc_server::{
-    AircraftState, Flight, GetFlightsRequest, PointZ as GrpcPointZ, TimePosition,
-    UpdateFlightPathRequest,
+    AircraftState, Flight, FlightConflict as GrpcFlightConflict, FlightEvent, FlightEventType,
+    FlightOrder, FlightSegment as GrpcFlightSegment, GetFlightConflictsRequest,
+    GetFlightConflictsResponse, GetFlightSegmentsRequest, GetFlightSegmentsResponse,
+    GetFlightsRequest, GetFlightsResponse, PathAlignment, PathSegment, PointZ as GrpcPointZ,
+    SegmentizePathRequest, SegmentizePathResponse, TimePosition, UpdateFlightPathRequest,
+    VelocitySample,
 };
-use crate::postgis::utils::StringError;
-use crate::types::AircraftType;
+use crate::postgis::geometry_input::{flight_path_from_input, GeometryInputError};
+use crate::postgis::tags::TagFilter;
+use crate::postgis::utils::{Segment, StringError, LAT_MAX, LAT_MIN, LON_MAX, LON_MIN};
 use crate::types::OperationalStatus;
-use chrono::{DateTime, Utc};
+use crate::types::{AircraftType, FlightPathMessage};
+use chrono::{DateTime, Duration, Utc};
 use deadpool_postgres::Object;
 use num_traits::FromPrimitive;
+use once_cell::sync::Lazy;
 use postgis::ewkb::{LineStringT, Point, PointZ};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use tokio::sync::broadcast;
+use tonic::async_trait;
 
-/// Allowed characters in a identifier
-pub const FLIGHT_IDENTIFIER_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,255}$";
+/// Allowed characters in a identifier, no longer than the `VARCHAR(20)`
+///  flight identifier columns
+pub const FLIGHT_IDENTIFIER_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,20}$";
 
 /// Max length of each flight segment in meters
 pub const MAX_FLIGHT_SEGMENT_LENGTH_METERS: f32 = 40.0;
 
+/// Max number of conflicts reported per checked segment
+pub const MAX_FLIGHT_CONFLICTS: i64 = 10;
+
+/// Max separation distance accepted by [`get_flight_conflicts`]
+pub const MAX_CONFLICT_DISTANCE_METERS: f32 = 1_000.0;
+
+/// Max segment length accepted by [`segmentize_path`]
+pub const MAX_SEGMENTIZE_LENGTH_METERS: f32 = 10_000.0;
+
+/// Max segments returned by one [`get_flight_segments`] call
+pub const MAX_FLIGHT_SEGMENTS_LIMIT: u32 = 1_000;
+
+/// Max velocity samples per aircraft returned by [`get_flights`]
+pub const MAX_VELOCITY_SAMPLES: u32 = 10;
+
+/// Time that must be left before the deadline of a [`get_flights`] call to
+///  look up (or keep looking up) aircraft data for the flights
+pub const MIN_ENRICHMENT_REMAINING_MS: u64 = 250;
+
+/// Max horizontal distance of an aircraft from its planned path before
+///  it's considered off the path
+pub const MAX_PATH_LATERAL_DEVIATION_METERS: f64 = 50.0;
+
+/// Max altitude difference of an aircraft from its planned path before
+///  it's considered off the path
+pub const MAX_PATH_VERTICAL_DEVIATION_METERS: f64 = 30.0;
+
+/// Max aircraft flying the same flight (formation or swarm)
+pub const MAX_FLIGHT_AIRCRAFT: usize = 50;
+
+/// Max duration of an airspace reservation
+pub const MAX_RESERVATION_SECS: u32 = 3600;
+
+/// Reservations confirmed since startup
+pub static RESERVATIONS_CONFIRMED: AtomicU64 = AtomicU64::new(0);
+
+/// Reservations removed after expiring since startup
+pub static RESERVATIONS_EXPIRED: AtomicU64 = AtomicU64::new(0);
+
+/// Capacity of the flight event channel, slow subscribers miss older events
+const FLIGHT_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Flight event channel
+pub static FLIGHT_EVENTS: Lazy<broadcast::Sender<FlightEvent>> =
+    Lazy::new(|| broadcast::channel(FLIGHT_EVENT_CHANNEL_CAPACITY).0);
+
+/// Unix time in milliseconds of the last committed flight update, 0 if none
+///  since startup
+pub static LAST_FLIGHT_UPDATE_MS: AtomicI64 = AtomicI64::new(0);
+
+impl PersistedEvent for FlightEvent {
+    const KIND: EventKind = EventKind::FlightEvent;
+
+    fn event_id(&self) -> u64 {
+        self.event_id
+    }
+
+    fn set_event_id(&mut self, event_id: u64) {
+        self.event_id = event_id;
+    }
+}
+
+/// Persists lifecycle steps of flights in the event backlog within the
+///  transaction making them, see [`backlog::persist`]
+///
+/// `flights` are the flight identifiers with their operators.
+///
+/// Send the events to subscribers once the transaction commits.
+async fn persist_flight_events(
+    transaction: &deadpool_postgres::Transaction<'_>,
+    event_type: FlightEventType,
+    flights: Vec<(String, Option<String>)>,
+) -> Result<backlog::Outbox<FlightEvent>, PostgisError> {
+    let timestamp = crate::clock::now();
+    let events = flights
+        .into_iter()
+        .map(|(flight_identifier, operator_id)| FlightEvent {
+            event_type: event_type as i32,
+            flight_identifier,
+            timestamp: Some(timestamp.into()),
+            event_id: 0,
+            operator_id,
+        })
+        .collect();
+
+    backlog::persist(transaction, events).await
+}
+
+/// Persists a lifecycle step of a stored flight along with its operator,
+///  see [`persist_flight_events`]
+async fn persist_flight_event(
+    transaction: &deadpool_postgres::Transaction<'_>,
+    event_type: FlightEventType,
+    flight_identifier: &str,
+) -> Result<backlog::Outbox<FlightEvent>, PostgisError> {
+    let stmt = format!(
+        r#"SELECT "operator_id" FROM {table_name} WHERE "flight_identifier" = $1;"#,
+        table_name = get_flights_table_name()
+    );
+
+    let operator_id: Option<String> = transaction
+        .query_opt(&stmt, &[&flight_identifier])
+        .await
+        .and_then(|row| row.map(|row| row.try_get("operator_id")).transpose())
+        .map_err(|e| {
+            postgis_error!(
+                "(persist_flight_event) could not get flight operator: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .flatten();
+
+    persist_flight_events(
+        transaction,
+        event_type,
+        vec![(flight_identifier.to_string(), operator_id)],
+    )
+    .await
+}
+
+/// Stream of the flight lifecycle events committed after subscribing
+///
+/// With a `last_event_id`, the persisted events after it are sent first,
+///  see [`backlog::event_stream`]. With an `operator_id`, only events of
+///  that operator's flights are streamed.
+pub fn flight_event_stream(
+    last_event_id: Option<u64>,
+    operator_id: Option<String>,
+) -> impl futures::Stream<Item = FlightEvent> {
+    futures::StreamExt::filter(
+        backlog::event_stream(&FLIGHT_EVENTS, last_event_id),
+        move |event| {
+            let matches = operator_id.is_none() || event.operator_id == operator_id;
+            futures::future::ready(matches)
+        },
+    )
+}
+
 /// Possible errors with aircraft requests
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum FlightError {
@@ -45,6 +203,37 @@ pub enum FlightError {
 
     /// Segmentize Error
     Segments,
+
+    /// Flight is already assigned to a different aircraft
+    AircraftMismatch,
+
+    /// Flight belongs to another operator than the operator claim
+    Operator,
+
+    /// No matching flight found
+    NotFound,
+
+    /// Invalid page size or offset
+    Limit,
+
+    /// Flight is soft-deleted and can't be re-created until restored or purged
+    Deleted,
+
+    /// Statement cancelled by the statement timeout
+    Timeout,
+
+    /// A point of the path is outside of the service area
+    ServiceArea,
+
+    /// Classified database error
+    Database(DbErrorKind),
+
+    /// A string field is invalid, with the name of the field and the rule
+    ///  that failed
+    InvalidString(&'static str, StringError),
+
+    /// Invalid WKT or WKB path, or more than one path provided
+    Geometry(GeometryInputError),
 }
 
 impl std::fmt::Display for FlightError {
@@ -58,21 +247,57 @@ impl std::fmt::Display for FlightError {
             FlightError::Client => write!(f, "Could not get backend client."),
             FlightError::DBError => write!(f, "Unknown backend error."),
             FlightError::Segments => write!(f, "Could not segmentize path."),
+            FlightError::AircraftMismatch => {
+                write!(f, "Flight is assigned to a different aircraft.")
+            }
+            FlightError::Operator => write!(f, "Flight belongs to another operator."),
+            FlightError::NotFound => write!(f, "No matching flight found."),
+            FlightError::Limit => write!(f, "Invalid limit or offset provided."),
+            FlightError::Deleted => {
+                write!(f, "Flight is deleted, restore it or wait for the purge.")
+            }
+            FlightError::Timeout => write!(f, "Backend statement timed out."),
+            FlightError::ServiceArea => write!(f, "Path outside of the service area."),
+            FlightError::Database(kind) => write!(f, "Backend error: {}.", kind),
+            FlightError::InvalidString(field, e) => write!(f, "Invalid {field} provided: {e}"),
+            FlightError::Geometry(e) => write!(f, "{e}"),
         }
     }
 }
 
+/// Maps a database error to [`FlightError::Database`], or to
+///  [`FlightError::DBError`] if it has no specific kind
+fn db_error(e: &tokio_postgres::Error) -> FlightError {
+    match super::classify_db_error(e) {
+        DbErrorKind::Other => FlightError::DBError,
+        DbErrorKind::Timeout => FlightError::Timeout,
+        kind => FlightError::Database(kind),
+    }
+}
+
 /// Gets the name of the flights table
-fn get_flights_table_name() -> &'static str {
+pub(super) fn get_flights_table_name() -> &'static str {
     static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."flights""#,);
     FULL_NAME
 }
 /// Gets the name of the flight segments table
-fn get_flight_segments_table_name() -> &'static str {
+pub(super) fn get_flight_segments_table_name() -> &'static str {
     static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."flight_segments""#,);
     FULL_NAME
 }
 
+/// Gets the name of the flight rebinds table
+pub(super) fn get_flight_rebinds_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."flight_rebinds""#,);
+    FULL_NAME
+}
+
+/// Gets the name of the table of aircraft flying a flight
+pub(super) fn get_flight_aircraft_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."flight_aircraft""#,);
+    FULL_NAME
+}
+
 /// Verifies that a identifier is valid
 pub fn check_flight_identifier(identifier: &str) -> Result<(), StringError> {
     super::utils::check_string(identifier, FLIGHT_IDENTIFIER_REGEX)
@@ -80,10 +305,17 @@ pub fn check_flight_identifier(identifier: &str) -> Result<(), StringError> {
 
 /// Initializes the PostGIS database for aircraft.
 pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table and enum declarations for flights
+pub(super) fn psql_init_statements() -> Vec<String> {
     // Create Aircraft Table
     let enum_name = "aircrafttype";
-    let statements = vec![
-        // super::psql_enum_declaration::<AircraftType>(enum_name), // should already exist
+    vec![
+        // Also declared by aircraft, so that flights don't depend on it
+        //  being initialized first
+        super::psql_enum_declaration::<AircraftType>(enum_name),
         format!(
             r#"CREATE TABLE IF NOT EXISTS {table_name} (
                 "flight_identifier" VARCHAR(20) UNIQUE PRIMARY KEY NOT NULL,
@@ -93,11 +325,20 @@ pub async fn psql_init() -> Result<(), PostgisError> {
                 "geom" GEOMETRY(LINESTRINGZ, {DEFAULT_SRID}), -- full path
                 "isa" GEOMETRY NOT NULL, -- envelope
                 "time_start" TIMESTAMPTZ,
-                "time_end" TIMESTAMPTZ
+                "time_end" TIMESTAMPTZ,
+                "operator_id" VARCHAR(255)
             );"#,
             table_name = get_flights_table_name(),
             aircraft_type = AircraftType::Undeclared.to_string()
         ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "operator_id" VARCHAR(255);"#,
+            table_name = get_flights_table_name(),
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "flights_operator_id_idx" ON {table_name} ("operator_id");"#,
+            table_name = get_flights_table_name(),
+        ),
         format!(
             r#"CREATE TABLE IF NOT EXISTS {table_name} (
                 "flight_identifier" VARCHAR(20) NOT NULL,
@@ -108,6 +349,15 @@ pub async fn psql_init() -> Result<(), PostgisError> {
             );"#,
             table_name = get_flight_segments_table_name()
         ),
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "flight_identifier" VARCHAR(20) NOT NULL,
+                "previous_aircraft_identifier" VARCHAR(20) NOT NULL,
+                "aircraft_identifier" VARCHAR(20),
+                "timestamp_rebind" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );"#,
+            table_name = get_flight_rebinds_table_name()
+        ),
         format!(
             r#"CREATE INDEX IF NOT EXISTS "flights_geom_idx" ON {table_name} USING GIST ("isa");"#,
             table_name = get_flights_table_name()
@@ -116,9 +366,142 @@ pub async fn psql_init() -> Result<(), PostgisError> {
             r#"CREATE INDEX IF NOT EXISTS "flight_segments_geom_idx" ON {table_name} USING GIST (ST_Transform("geom", 4978));"#,
             table_name = get_flight_segments_table_name()
         ),
-    ];
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "deleted_at" TIMESTAMPTZ;"#,
+            table_name = get_flights_table_name()
+        ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "status_reason" VARCHAR({MAX_STATUS_REASON_LENGTH});"#,
+            table_name = get_flights_table_name()
+        ),
+        // All aircraft flying a flight, the first is also in the flights table
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "flight_identifier" VARCHAR(20) NOT NULL,
+                "aircraft_identifier" VARCHAR(20) NOT NULL,
+                PRIMARY KEY ("flight_identifier", "aircraft_identifier")
+            );"#,
+            table_name = get_flight_aircraft_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "flight_aircraft_aircraft_identifier_idx" ON {table_name} ("aircraft_identifier");"#,
+            table_name = get_flight_aircraft_table_name()
+        ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "tags" TEXT[] NOT NULL DEFAULT '{{}}';"#,
+            table_name = get_flights_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "flights_tags_idx" ON {table_name} USING GIN ("tags");"#,
+            table_name = get_flights_table_name()
+        ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "destination_identifier" VARCHAR(255);"#,
+            table_name = get_flights_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "flights_destination_idx" ON {table_name} ("destination_identifier", "time_end");"#,
+            table_name = get_flights_table_name()
+        ),
+        // Reserved flights have an expiry, planned flights don't
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "reserved_until" TIMESTAMPTZ;"#,
+            table_name = get_flights_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "flights_reserved_until_idx" ON {table_name} ("reserved_until") WHERE "reserved_until" IS NOT NULL;"#,
+            table_name = get_flights_table_name()
+        ),
+        // Simulation run of a simulated flight
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "scenario_id" VARCHAR(255);"#,
+            table_name = get_flights_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "flights_scenario_id_idx" ON {table_name} ("scenario_id") WHERE "scenario_id" IS NOT NULL;"#,
+            table_name = get_flights_table_name()
+        ),
+        // 3D path length, NULL for flights stored before it was recorded
+        //  until recomputed
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "length_meters" FLOAT8;"#,
+            table_name = get_flights_table_name()
+        ),
+    ]
+}
+
+/// Gets the aircraft flying a flight, without duplicates
+///
+/// The first is the `aircraft_identifier` of the request, or the first of
+///  `aircraft_identifiers` if not provided. It's the aircraft stored in
+///  the flights table, the others are swarm members.
+fn flight_members(item: &UpdateFlightPathRequest) -> Result<Vec<String>, FlightError> {
+    let mut members: Vec<String> = vec![];
+    for identifier in item
+        .aircraft_identifier
+        .iter()
+        .chain(item.aircraft_identifiers.iter())
+    {
+        if let Err(e) = super::aircraft::check_identifier(identifier) {
+            postgis_error!(
+                "(flight_members) invalid aircraft identifier {}: {}",
+                identifier,
+                e
+            );
+            return Err(FlightError::InvalidString("aircraft_identifier", e));
+        }
+
+        if !members.contains(identifier) {
+            members.push(identifier.clone());
+        }
+    }
+
+    if members.len() > MAX_FLIGHT_AIRCRAFT {
+        postgis_error!(
+            "(flight_members) too many aircraft: {}, max {}.",
+            members.len(),
+            MAX_FLIGHT_AIRCRAFT
+        );
+        return Err(FlightError::AircraftId);
+    }
+
+    Ok(members)
+}
+
+/// Replaces the WKT or WKB path of a flight update with the path points
+///
+/// The geometry can't be combined with path points or with an SRID other
+///  than [`DEFAULT_SRID`].
+fn resolve_path_geometry(
+    mut flight: UpdateFlightPathRequest,
+) -> Result<UpdateFlightPathRequest, FlightError> {
+    let path = flight_path_from_input(flight.geom_wkt.as_deref(), flight.geom_ewkb.as_deref())
+        .map_err(|e| {
+            postgis_error!(
+                "(resolve_path_geometry) invalid path geometry for flight {:?}: {}",
+                flight.flight_identifier,
+                e
+            );
+            FlightError::Geometry(e)
+        })?;
+
+    let Some(path) = path else {
+        return Ok(flight);
+    };
 
-    psql_transaction(statements).await
+    if !flight.path.is_empty() || flight.srid.is_some_and(|srid| srid != DEFAULT_SRID) {
+        postgis_error!(
+            "(resolve_path_geometry) flight {:?} has both a path geometry and path points or an SRID.",
+            flight.flight_identifier
+        );
+        return Err(FlightError::Geometry(GeometryInputError::Conflict));
+    }
+
+    flight.path = path;
+    flight.srid = None;
+    flight.geom_wkt = None;
+    flight.geom_ewkb = None;
+    Ok(flight)
 }
 
 /// Validates the provided aircraft identification.
@@ -135,16 +518,337 @@ fn validate_flight_path(item: &UpdateFlightPathRequest) -> Result<(), PostgisErr
             e
         );
 
+        return Err(PostgisError::FlightPath(FlightError::InvalidString(
+            "flight_identifier",
+            e,
+        )));
+    }
+
+    if let Some(ref operator_id) = item.operator_id {
+        if let Err(e) = super::utils::check_label(operator_id) {
+            postgis_error!(
+                "(validate_flight_path) invalid operator_id {}: {}",
+                operator_id,
+                e
+            );
+
+            return Err(PostgisError::FlightPath(FlightError::InvalidString(
+                "operator_id",
+                e,
+            )));
+        }
+    }
+
+    if let Err(e) = super::tags::validate_tags(&item.tags) {
+        postgis_error!("(validate_flight_path) invalid tags {:?}: {}", item.tags, e);
         return Err(PostgisError::FlightPath(FlightError::Label));
     }
 
+    if let Some(ref destination) = item.destination_identifier {
+        if let Err(e) = super::utils::check_string(destination, super::vertiport::IDENTIFIER_REGEX)
+        {
+            postgis_error!(
+                "(validate_flight_path) invalid destination_identifier {}: {}",
+                destination,
+                e
+            );
+
+            return Err(PostgisError::FlightPath(FlightError::InvalidString(
+                "destination_identifier",
+                e,
+            )));
+        }
+    }
+
+    if let Some(ref scenario_id) = item.scenario_id {
+        if let Err(e) = super::utils::check_label(scenario_id) {
+            postgis_error!(
+                "(validate_flight_path) invalid scenario_id {}: {}",
+                scenario_id,
+                e
+            );
+
+            return Err(PostgisError::FlightPath(FlightError::InvalidString(
+                "scenario_id",
+                e,
+            )));
+        }
+
+        // Tearing down a scenario must never remove a real flight
+        if !item.simulated {
+            postgis_error!(
+                "(validate_flight_path) scenario_id {} provided for a flight that isn't simulated.",
+                scenario_id
+            );
+
+            return Err(PostgisError::FlightPath(FlightError::Label));
+        }
+    }
+
+    if let Some(reservation_secs) = item.reservation_secs {
+        if reservation_secs == 0 || reservation_secs > MAX_RESERVATION_SECS {
+            postgis_error!(
+                "(validate_flight_path) reservation of {}s, expected 1 to {}s.",
+                reservation_secs,
+                MAX_RESERVATION_SECS
+            );
+
+            return Err(PostgisError::FlightPath(FlightError::Time));
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects paths that can't form a line
+///
+/// An empty path would otherwise store a flight with no geometry, which
+///  never shows up in envelope or intersection queries.
+fn validate_path_length(path: &[GrpcPointZ]) -> Result<(), FlightError> {
+    if path.len() < MIN_FLIGHT_PATH_POINTS {
+        postgis_error!(
+            "(validate_path_length) path has {} points, at least {} are required.",
+            path.len(),
+            MIN_FLIGHT_PATH_POINTS
+        );
+        return Err(FlightError::Location);
+    }
+
+    Ok(())
+}
+
+/// Validates the schedule of a flight path update
+///
+/// The end must be after the start and, unless `max_duration_secs` is 0,
+///  the flight can't last longer than `max_duration_secs`. Flights that
+///  have already ended are accepted with a warning unless `historical`.
+fn validate_flight_schedule(
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    now: DateTime<Utc>,
+    max_duration_secs: u64,
+    historical: bool,
+) -> Result<(), FlightError> {
+    if time_end <= time_start {
+        postgis_error!(
+            "(validate_flight_schedule) end time {} is not after start time {}.",
+            time_end,
+            time_start
+        );
+        return Err(FlightError::Time);
+    }
+
+    let duration_secs = (time_end - time_start).num_seconds();
+    if max_duration_secs > 0 && duration_secs as u64 > max_duration_secs {
+        postgis_error!(
+            "(validate_flight_schedule) flight duration {}s exceeds the maximum of {}s.",
+            duration_secs,
+            max_duration_secs
+        );
+        return Err(FlightError::Time);
+    }
+
+    if time_end < now && !historical {
+        postgis_warn!(
+            "(validate_flight_schedule) flight ended in the past ({}), set historical for replays.",
+            time_end
+        );
+    }
+
     Ok(())
 }
 
+/// The stored aircraft, operator, path and schedule of a flight
+#[derive(Debug, Clone, PartialEq)]
+struct StoredPath {
+    aircraft_identifier: String,
+    operator_id: Option<String>,
+    points: Vec<PointZ>,
+    time_start: Option<DateTime<Utc>>,
+    time_end: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Returns true if the stored flight has the same path and schedule as the
+///  incoming update, in which case the segments do not need to be regenerated.
+fn path_unchanged(
+    stored: &Option<StoredPath>,
+    points: &[PointZ],
+    time_start: &DateTime<Utc>,
+    time_end: &DateTime<Utc>,
+) -> bool {
+    let Some(stored) = stored else {
+        return false;
+    };
+
+    stored.time_start.as_ref() == Some(time_start)
+        && stored.time_end.as_ref() == Some(time_end)
+        && stored.points.len() == points.len()
+        && stored
+            .points
+            .iter()
+            .zip(points)
+            .all(|(a, b)| a.x == b.x && a.y == b.y && a.z == b.z)
+}
+
+/// Rejects updates to a soft-deleted flight
+///
+/// The flight must be restored first, or its identifier can be reused
+///  once the tombstone is purged.
+fn check_not_deleted(stored: &Option<StoredPath>) -> Result<(), FlightError> {
+    match stored {
+        Some(StoredPath {
+            deleted_at: Some(deleted_at),
+            ..
+        }) => {
+            postgis_error!("(check_not_deleted) flight was deleted at {}.", deleted_at);
+            Err(FlightError::Deleted)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a flight update carrying an operator claim only touches the
+///  claiming operator's flights
+///
+/// The claimed operator is the only one the update can attribute the flight
+///  to. A stored flight without an operator isn't anyone's.
+fn check_operator(
+    stored: &Option<StoredPath>,
+    operator_id: &Option<String>,
+    claim: Option<&str>,
+) -> Result<(), FlightError> {
+    let Some(claim) = claim else {
+        return Ok(());
+    };
+
+    if operator_id
+        .as_deref()
+        .is_some_and(|operator_id| operator_id != claim)
+    {
+        postgis_error!(
+            "(check_operator) operator {} can't attribute a flight to {:?}.",
+            claim,
+            operator_id
+        );
+        return Err(FlightError::Operator);
+    }
+
+    match stored {
+        Some(stored) if stored.operator_id.as_deref() != Some(claim) => {
+            postgis_error!(
+                "(check_operator) flight belongs to operator {:?}, not {}.",
+                stored.operator_id,
+                claim
+            );
+            Err(FlightError::Operator)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks if an update would bind a stored flight to a different aircraft
+///
+/// Returns true if the flight is being rebound (only allowed with
+///  `allow_rebind`), false if this is a new flight or the same aircraft.
+fn check_rebind(
+    stored: &Option<StoredPath>,
+    aircraft_identifier: &Option<String>,
+    allow_rebind: bool,
+) -> Result<bool, FlightError> {
+    let Some(stored) = stored else {
+        return Ok(false);
+    };
+
+    if aircraft_identifier.as_deref() == Some(stored.aircraft_identifier.as_str()) {
+        return Ok(false);
+    }
+
+    if !allow_rebind {
+        postgis_error!(
+            "(check_rebind) flight is assigned to aircraft {}, not {:?}.",
+            stored.aircraft_identifier,
+            aircraft_identifier
+        );
+        return Err(FlightError::AircraftMismatch);
+    }
+
+    Ok(true)
+}
+
+/// First key of the advisory locks held while updating a flight, the
+///  second key is the hash of the flight identifier
+const FLIGHT_UPDATE_LOCK_KEY: i32 = 0x666c_6967; // "flig"
+
 /// Pulls queued flight path messages from Redis Queue (from svc-scheduler)
-pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), PostgisError> {
+///
+/// Flights longer than `max_duration_secs` are rejected (0 disables the limit).
+///
+/// A dry run (`flight.dry_run`) validates and writes the flight, then rolls back.
+///
+/// The update is all-or-nothing: a failure at any step leaves the
+///  previously stored flight untouched. It's retried once on fresh
+///  connections after a primary switchover.
+pub async fn update_flight_path(
+    flight: UpdateFlightPathRequest,
+    max_duration_secs: u64,
+) -> Result<(), PostgisError> {
+    update_flight_path_for(flight, max_duration_secs, None).await
+}
+
+/// Updates a flight path as [`update_flight_path`] does, on behalf of the
+///  operator claimed by the caller, if any
+///
+/// With a claim, only flights of that operator are updated and new flights
+///  are attributed to it.
+pub async fn update_flight_path_for(
+    flight: UpdateFlightPathRequest,
+    max_duration_secs: u64,
+    operator_claim: Option<&str>,
+) -> Result<(), PostgisError> {
+    super::pool::retry_after_failover("update_flight_path", || {
+        update_flight_path_once(flight.clone(), max_duration_secs, operator_claim)
+    })
+    .await
+}
+
+/// Gets the stored flights a flight path would conflict with, using the
+///  same probe as [`validate_flight_comprehensive`]
+///
+/// Reported by dry runs of [`update_flight_path_for`], which only roll
+///  back the write.
+pub async fn probe_flight_conflicts(
+    flight: UpdateFlightPathRequest,
+) -> Result<Vec<String>, PostgisError> {
+    postgis_debug!("(probe_flight_conflicts) entry.");
+    let flight = resolve_path_geometry(flight).map_err(PostgisError::FlightPath)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(probe_flight_conflicts) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let conflicts = validate_flight_comprehensive(&flight, pool)
+        .await?
+        .into_iter()
+        .filter_map(|issue| match issue {
+            FlightIssue::Conflict(conflict) => Some(conflict.flight_identifier),
+            _ => None,
+        })
+        .collect();
+
+    Ok(conflicts)
+}
+
+/// Validates and writes a flight path once, see [`update_flight_path_for`]
+async fn update_flight_path_once(
+    flight: UpdateFlightPathRequest,
+    max_duration_secs: u64,
+    operator_claim: Option<&str>,
+) -> Result<(), PostgisError> {
     postgis_debug!("(update_flight_path) entry.");
 
+    let mut flight = resolve_path_geometry(flight).map_err(PostgisError::FlightPath)?;
     validate_flight_path(&flight).map_err(|e| {
         postgis_error!(
             "(update_flight_path) could not validate id for flight id {:?}: {:?}",
@@ -155,8 +859,16 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
         e
     })?;
 
-    let Some(timestamp_start) = flight.timestamp_start else {
-        postgis_error!("(update_flight_path) no start time provided.");
+    let members = flight_members(&flight).map_err(PostgisError::FlightPath)?;
+    let Some(lead) = members.first() else {
+        postgis_error!("(update_flight_path) no aircraft identifier provided.");
+        return Err(PostgisError::FlightPath(FlightError::AircraftId));
+    };
+
+    let aircraft_identifier = Some(lead.clone());
+
+    let Some(timestamp_start) = flight.timestamp_start else {
+        postgis_error!("(update_flight_path) no start time provided.");
         return Err(PostgisError::FlightPath(FlightError::Time));
     };
 
@@ -167,6 +879,14 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
 
     let timestamp_start: DateTime<Utc> = timestamp_start.into();
     let timestamp_end: DateTime<Utc> = timestamp_end.into();
+    validate_flight_schedule(
+        timestamp_start,
+        timestamp_end,
+        crate::clock::now(),
+        max_duration_secs,
+        flight.historical,
+    )
+    .map_err(PostgisError::FlightPath)?;
 
     let Some(aircraft_type): Option<AircraftType> = FromPrimitive::from_i32(flight.aircraft_type)
     else {
@@ -174,6 +894,8 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
         return Err(PostgisError::FlightPath(FlightError::AircraftType));
     };
 
+    validate_path_length(&flight.path).map_err(PostgisError::FlightPath)?;
+
     let flights_insertion_stmt: String = format!(
         r#"INSERT INTO {table_name} (
             "flight_identifier",
@@ -183,25 +905,84 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
             "time_start",
             "time_end",
             "geom",
-            "isa"
+            "isa",
+            "operator_id",
+            "tags",
+            "destination_identifier",
+            "reserved_until",
+            "scenario_id",
+            "length_meters"
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, ST_Envelope($7), $8, $9, $10,
+            $11, $12,
+            {length_expression}
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, ST_Envelope($7))
         ON CONFLICT ("flight_identifier") DO UPDATE
             SET "aircraft_identifier" = EXCLUDED."aircraft_identifier",
+                -- a planned flight can't become a reservation again, a
+                --  reservation filed again without reservation_secs is planned
+                "reserved_until" = CASE
+                    WHEN {table_name}."reserved_until" IS NULL THEN NULL
+                    ELSE EXCLUDED."reserved_until"
+                END,
+                "tags" = EXCLUDED."tags",
+                "operator_id" = COALESCE(EXCLUDED."operator_id", {table_name}."operator_id"),
+                "destination_identifier" = COALESCE(
+                    EXCLUDED."destination_identifier",
+                    {table_name}."destination_identifier"
+                ),
                 "aircraft_type" = EXCLUDED."aircraft_type",
                 "simulated" = EXCLUDED."simulated",
+                "scenario_id" = EXCLUDED."scenario_id",
                 "geom" = EXCLUDED."geom",
                 "isa" = EXCLUDED."isa",
+                "length_meters" = EXCLUDED."length_meters",
                 "time_start" = EXCLUDED."time_start",
                 "time_end" = EXCLUDED."time_end";"#,
+        table_name = get_flights_table_name(),
+        length_expression = flight_length_expression("$7"),
+    );
+
+    // `FOR UPDATE` only locks a stored flight, concurrent first updates of
+    //  a flight are serialized by this lock until the transaction ends
+    let flight_lock_stmt = "SELECT pg_advisory_xact_lock($1, hashtext($2));";
+
+    let stored_path_stmt = format!(
+        r#"SELECT "aircraft_identifier", "operator_id", "geom", "time_start", "time_end", "deleted_at"
+        FROM {table_name}
+        WHERE "flight_identifier" = $1
+        FOR UPDATE;"#,
         table_name = get_flights_table_name()
     );
 
+    let rebind_insertion_stmt = format!(
+        r#"INSERT INTO {table_name} (
+            "flight_identifier",
+            "previous_aircraft_identifier",
+            "aircraft_identifier"
+        ) VALUES ( $1, $2, $3 );"#,
+        table_name = get_flight_rebinds_table_name()
+    );
+
     let segments_deletion_stmt = format!(
         r#"DELETE FROM {table_name} WHERE "flight_identifier" = $1;"#,
         table_name = get_flight_segments_table_name()
     );
 
+    let members_deletion_stmt = format!(
+        r#"DELETE FROM {table_name} WHERE "flight_identifier" = $1;"#,
+        table_name = get_flight_aircraft_table_name()
+    );
+
+    let member_insertion_stmt = format!(
+        r#"INSERT INTO {table_name} (
+            "flight_identifier",
+            "aircraft_identifier"
+        ) VALUES ( $1, $2 );"#,
+        table_name = get_flight_aircraft_table_name()
+    );
+
     let segment_insertion_stmt = format!(
         r#"INSERT INTO {table_name} (
             "flight_identifier",
@@ -212,6 +993,50 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
         table_name = get_flight_segments_table_name()
     );
 
+    let points = flight
+        .path
+        .clone()
+        .into_iter()
+        .map(PointZ::try_from)
+        .collect::<Result<Vec<PointZ>, _>>()
+        .map_err(|_| {
+            postgis_error!("(update_flight_path) could not convert path to Vec<PointZ>.");
+            PostgisError::FlightPath(FlightError::Location)
+        })?;
+
+    // Paths submitted in another CRS are stored in DEFAULT_SRID, quantized
+    //  like paths submitted in DEFAULT_SRID
+    let points = match flight.srid {
+        Some(srid) if srid != DEFAULT_SRID => {
+            super::utils::transform_points(points, srid, DEFAULT_SRID)
+                .await
+                .map_err(|e| {
+                    postgis_error!(
+                        "(update_flight_path) could not transform path from SRID {}: {}",
+                        srid,
+                        e
+                    );
+                    PostgisError::FlightPath(FlightError::Location)
+                })?
+                .into_iter()
+                .map(super::utils::quantize_pointz)
+                .collect()
+        }
+        _ => points,
+    };
+
+    if let Err(index) =
+        super::service_area::check_service_area(points.iter().map(|point| (point.x, point.y)))
+    {
+        postgis_error!(
+            "(update_flight_path) point {} of flight {:?} is outside of the service area: {:?}",
+            index,
+            flight.flight_identifier,
+            points[index]
+        );
+        return Err(PostgisError::FlightPath(FlightError::ServiceArea));
+    }
+
     let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
         postgis_error!("(update_flight_path) could not get psql pool.");
         return Err(PostgisError::FlightPath(FlightError::DBError));
@@ -222,58 +1047,125 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
             "(update_flight_path) could not get client from psql connection pool: {}",
             e
         );
+        super::classify_pool_error(&e);
         PostgisError::FlightPath(FlightError::Client)
     })?;
 
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    // Every write below goes through this transaction. On an error (or if
+    //  this future is dropped) the transaction is rolled back, leaving no
+    //  flight, member, rebind or segment rows behind.
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!("(update_flight_path) could not create transaction: {}", e);
+        super::classify_db_error(&e);
         PostgisError::FlightPath(FlightError::Client)
     })?;
 
-    let points = flight
-        .path
-        .clone()
-        .into_iter()
-        .map(PointZ::try_from)
-        .collect::<Result<Vec<PointZ>, _>>()
-        .map_err(|_| {
-            postgis_error!("(update_flight_path) could not convert path to Vec<PointZ>.");
-            PostgisError::FlightPath(FlightError::Location)
-        })?;
-
-    // Subdivide the path into segments by length
     let geom = LineStringT {
         points: points.clone(),
         srid: Some(DEFAULT_SRID),
     };
 
-    postgis_debug!("(update_flight_path) segmentizing path.");
+    transaction
+        .execute(
+            flight_lock_stmt,
+            &[&FLIGHT_UPDATE_LOCK_KEY, &flight.flight_identifier],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_flight_path) could not execute transaction to lock flight: {}",
+                e
+            );
+            PostgisError::FlightPath(db_error(&e))
+        })?;
 
-    let segments = super::utils::segmentize(
-        points,
-        timestamp_start,
-        timestamp_end,
-        MAX_FLIGHT_SEGMENT_LENGTH_METERS,
-    )
-    .await
-    .map_err(|e| {
-        postgis_error!("(update_flight_path) could not segmentize path: {}", e);
-        PostgisError::FlightPath(FlightError::Segments)
-    })?;
+    let stored = transaction
+        .query_opt(&stored_path_stmt, &[&flight.flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_flight_path) could not execute transaction to get stored path: {}",
+                e
+            );
+            PostgisError::FlightPath(db_error(&e))
+        })?
+        .map(|row| -> Result<StoredPath, tokio_postgres::error::Error> {
+            let geom: Option<LineStringT<PointZ>> = row.try_get("geom")?;
+            Ok(StoredPath {
+                aircraft_identifier: row.try_get("aircraft_identifier")?,
+                operator_id: row.try_get("operator_id")?,
+                points: geom.map(|g| g.points).unwrap_or_default(),
+                time_start: row.try_get("time_start")?,
+                time_end: row.try_get("time_end")?,
+                deleted_at: row.try_get("deleted_at")?,
+            })
+        })
+        .transpose()
+        .map_err(|e| {
+            postgis_error!("(update_flight_path) could not parse stored path: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
 
-    // postgis_debug!("(update_flight_path) found segments: {:?}", segments);
+    check_not_deleted(&stored).map_err(PostgisError::FlightPath)?;
+    check_operator(&stored, &flight.operator_id, operator_claim)
+        .map_err(PostgisError::FlightPath)?;
+    if let Some(claim) = operator_claim {
+        flight.operator_id = Some(claim.to_string());
+    }
+
+    let rebind = check_rebind(&stored, &aircraft_identifier, flight.allow_rebind)
+        .map_err(PostgisError::FlightPath)?;
+
+    if let (true, Some(stored)) = (rebind, &stored) {
+        postgis_warn!(
+            "(update_flight_path) rebinding flight {:?} from aircraft {} to {:?}.",
+            flight.flight_identifier,
+            stored.aircraft_identifier,
+            aircraft_identifier
+        );
+
+        transaction
+            .execute(
+                &rebind_insertion_stmt,
+                &[
+                    &flight.flight_identifier,
+                    &stored.aircraft_identifier,
+                    &aircraft_identifier,
+                ],
+            )
+            .await
+            .map_err(|e| {
+                postgis_error!(
+                    "(update_flight_path) could not execute transaction to record rebind: {}",
+                    e
+                );
+                PostgisError::FlightPath(db_error(&e))
+            })?;
+    }
+
+    // Metadata-only updates keep the existing segments
+    let regenerate_segments = !path_unchanged(&stored, &points, &timestamp_start, &timestamp_end);
 
     transaction
         .execute(
             &flights_insertion_stmt,
             &[
                 &flight.flight_identifier,
-                &flight.aircraft_identifier,
+                &aircraft_identifier,
                 &aircraft_type,
                 &flight.simulated,
                 &timestamp_start,
                 &timestamp_end,
                 &geom,
+                &flight.operator_id,
+                &flight.tags,
+                &flight.destination_identifier,
+                &flight.reservation_secs.map(|secs| {
+                    crate::clock::now() + Duration::try_seconds(secs as i64).unwrap_or_default()
+                }),
+                &flight.scenario_id,
             ],
         )
         .await
@@ -282,9 +1174,69 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
                 "(update_flight_path) could not execute transaction to insert flight: {}",
                 e
             );
-            PostgisError::FlightPath(FlightError::DBError)
+            PostgisError::FlightPath(db_error(&e))
+        })?;
+
+    // The members are replaced along with the flight
+    transaction
+        .execute(&members_deletion_stmt, &[&flight.flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(update_flight_path) could not execute transaction to delete members: {}",
+                e
+            );
+            PostgisError::FlightPath(db_error(&e))
         })?;
 
+    for member in &members {
+        transaction
+            .execute(&member_insertion_stmt, &[&flight.flight_identifier, member])
+            .await
+            .map_err(|e| {
+                postgis_error!(
+                    "(update_flight_path) could not execute transaction to insert member: {}",
+                    e
+                );
+                PostgisError::FlightPath(db_error(&e))
+            })?;
+    }
+
+    if !regenerate_segments {
+        postgis_debug!("(update_flight_path) path unchanged, skipping segments.");
+        commit_flight_update(transaction, &flight).await?;
+        postgis_info!(
+            flight = flight.flight_identifier.as_deref().unwrap_or_default(),
+            dry_run = flight.dry_run;
+            "(update_flight_path) success, dry run: {}.", flight.dry_run
+        );
+        return Ok(());
+    }
+
+    // Subdivide the path into segments by length
+    //  (read-only, on its own connection)
+    postgis_debug!("(update_flight_path) segmentizing path.");
+
+    let segments = super::utils::segmentize(
+        points,
+        timestamp_start,
+        timestamp_end,
+        MAX_FLIGHT_SEGMENT_LENGTH_METERS,
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(update_flight_path) could not segmentize path: {}", e);
+        PostgisError::FlightPath(FlightError::Segments)
+    })?;
+
+    // postgis_debug!("(update_flight_path) found segments: {:?}", segments);
+
+    // (flight_identifier, time_start) is the segments primary key
+    if !unique_segment_starts(&segments) {
+        postgis_error!("(update_flight_path) segments with duplicate start times.");
+        return Err(PostgisError::FlightPath(FlightError::Segments));
+    }
+
     transaction
         .execute(&segments_deletion_stmt, &[&flight.flight_identifier])
         .await
@@ -293,7 +1245,7 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
                 "(update_flight_path) could not execute transaction to delete segments: {}",
                 e
             );
-            PostgisError::FlightPath(FlightError::DBError)
+            PostgisError::FlightPath(db_error(&e))
         })?;
 
     for segment in segments {
@@ -313,308 +1265,5305 @@ pub async fn update_flight_path(flight: UpdateFlightPathRequest) -> Result<(), P
                     "(update_flight_path) could not execute transaction to insert segment: {}",
                     e
                 );
-                PostgisError::FlightPath(FlightError::DBError)
+                PostgisError::FlightPath(db_error(&e))
             })?;
     }
 
-    transaction.commit().await.map_err(|e| {
-        postgis_error!("(update_flight_path) could not commit transaction: {}", e);
-        PostgisError::FlightPath(FlightError::DBError)
-    })?;
-
-    postgis_info!("(update_flight_path) success.");
+    commit_flight_update(transaction, &flight).await?;
+    postgis_info!(
+        flight = flight.flight_identifier.as_deref().unwrap_or_default(),
+        dry_run = flight.dry_run;
+        "(update_flight_path) success, dry run: {}.", flight.dry_run
+    );
     Ok(())
 }
 
-/// Prepares a statement that checks zone intersections with the provided geometry
-pub async fn get_flight_intersection_stmt(
-    client: &Object,
-) -> Result<tokio_postgres::Statement, PostgisError> {
-    let result = client
-        .prepare_cached(&format!(
-            r#"WITH "segments" AS (
-                SELECT
-                    "flight_identifier",
-                    "geom",
-                    "time_start",
-                    "time_end"
-                FROM {segments_table_name}
-                WHERE
-                    ("time_start" <= $4 OR "time_start" IS NULL) -- easy checks first
-                    AND ("time_end" >= $3 OR "time_end" IS NULL)
-                    AND ST_3DDWithin(
-                        ST_Transform("geom", 4978),
-                        ST_Transform($1, 4978),
-                        $2 -- meters
-                    )
-            ) SELECT
-                "flight_identifier",
-                "aircraft_identifier",
-                "geom",
-                "time_start",
-                "time_end"
-            FROM {flights_table_name}
-            WHERE "flight_identifier" IN (SELECT "flight_identifier" FROM "segments")
-                AND "simulated" = FALSE
-            LIMIT 1;
-        "#,
-            segments_table_name = get_flight_segments_table_name(),
-            flights_table_name = get_flights_table_name(),
-        ))
-        .await;
-
-    match result {
-        Ok(stmt) => Ok(stmt),
-        Err(e) => {
-            postgis_error!(
-                "(get_flight_intersection_stmt) could not prepare cached statement: {}",
-                e
-            );
-            Err(PostgisError::FlightPath(FlightError::DBError))
+/// Commits a flight update along with its filed event, or rolls it back
+///  for a dry run
+async fn commit_flight_update(
+    transaction: deadpool_postgres::Transaction<'_>,
+    flight: &UpdateFlightPathRequest,
+) -> Result<(), PostgisError> {
+    let outbox = match flight.dry_run {
+        true => None,
+        false => {
+            let flight_identifier = flight.flight_identifier.as_deref().unwrap_or_default();
+            Some(
+                persist_flight_event(&transaction, FlightEventType::Filed, flight_identifier)
+                    .await?,
+            )
         }
+    };
+
+    super::commit_or_rollback(transaction, flight.dry_run)
+        .await
+        .map_err(|e| {
+            postgis_error!("(update_flight_path) could not commit transaction: {}", e);
+            PostgisError::FlightPath(db_error(&e))
+        })?;
+
+    if let Some(outbox) = outbox {
+        outbox.send(&FLIGHT_EVENTS);
     }
+
+    record_flight_update(flight.dry_run);
+    Ok(())
 }
 
-/// Get flights and their aircraft that intersect with the provided geometry
-///  and time range.
-pub async fn get_flights(request: GetFlightsRequest) -> Result<Vec<Flight>, FlightError> {
-    postgis_debug!("(get_flights) entry.");
+/// Keeps the aircraft row preferred for a flight among the rows found on
+///  every shard: the flight's own aircraft first, then by identifier, as
+///  the single-shard query orders them
+fn preferred_aircraft_row(
+    rows: Vec<tokio_postgres::Row>,
+    aircraft_id: &Option<String>,
+) -> Vec<tokio_postgres::Row> {
+    let mut rows: Vec<(Option<String>, tokio_postgres::Row)> = rows
+        .into_iter()
+        .map(|row| (row.try_get("identifier").ok(), row))
+        .collect();
 
-    let Some(time_start) = request.time_start else {
-        postgis_error!("(get_flights) time_start is required.");
-        return Err(FlightError::Time);
-    };
+    rows.sort_by_key(|(identifier, _)| preferred_aircraft_key(identifier, aircraft_id));
+    rows.into_iter().take(1).map(|(_, row)| row).collect()
+}
 
-    let Some(time_end) = request.time_end else {
-        postgis_error!("(get_flights) time_end is required.");
-        return Err(FlightError::Time);
-    };
+/// Gets the aircraft rows of flights on the extra shards: their lead and
+///  member aircraft and aircraft reporting them as their session
+///
+/// Flights are those matching `flights_condition` on the primary, rows
+///  matching `aircraft_condition` are added. Both conditions take
+///  `params`. Without extra shards nothing is read.
+async fn get_flight_shard_rows(
+    client: &deadpool_postgres::Client,
+    flights_condition: &str,
+    aircraft_condition: Option<&str>,
+    params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+) -> Result<super::shard::ShardRows, FlightError> {
+    let table_name = super::aircraft::position_source();
+    if !super::shard::is_sharded() {
+        return Ok(super::shard::ShardRows::none(table_name));
+    }
 
-    let time_start: DateTime<Utc> = time_start.into();
-    let time_end: DateTime<Utc> = time_end.into();
-    let linestring = LineStringT {
-        points: vec![
-            Point {
-                x: request.window_min_x,
-                y: request.window_min_y,
-                srid: Some(DEFAULT_SRID),
-            },
-            Point {
-                x: request.window_max_x,
-                y: request.window_max_y,
-                srid: Some(DEFAULT_SRID),
-            },
-        ],
-        srid: Some(DEFAULT_SRID),
-    };
+    let stmt = format!(
+        r#"SELECT
+                "flights"."flight_identifier",
+                "flights"."aircraft_identifier",
+                "members"."aircraft_identifier" AS "member_identifier"
+            FROM {flights_table_name} AS "flights"
+            LEFT JOIN {members_table_name} AS "members"
+                ON "members"."flight_identifier" = "flights"."flight_identifier"
+            WHERE {flights_condition};"#,
+        flights_table_name = get_flights_table_name(),
+        members_table_name = get_flight_aircraft_table_name(),
+    );
 
-    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
-        postgis_error!("(get_flights) could not get psql pool.");
+    let rows = super::query_cached(client, &stmt, params)
+        .await
+        .and_then(|rows| {
+            rows.iter()
+                .map(|row| {
+                    let flight: String = row.try_get("flight_identifier")?;
+                    let aircraft: Option<String> = row.try_get("aircraft_identifier")?;
+                    let member: Option<String> = row.try_get("member_identifier")?;
+                    Ok((flight, aircraft, member))
+                })
+                .collect::<Result<Vec<_>, tokio_postgres::error::Error>>()
+        })
+        .map_err(|e| {
+            postgis_error!("(get_flight_shard_rows) could not get flights: {}", e);
+            FlightError::DBError
+        })?;
 
-        return Err(FlightError::Client);
-    };
+    let mut flights: Vec<String> = vec![];
+    let mut aircraft: Vec<String> = vec![];
+    for (flight, lead, member) in rows {
+        flights.push(flight);
+        aircraft.extend(lead);
+        aircraft.extend(member);
+    }
 
-    let client = pool.get().await.map_err(|e| {
-        postgis_error!(
-            "(get_flights) could not get client from psql connection pool: {}",
-            e
-        );
-        FlightError::Client
-    })?;
+    flights.sort();
+    flights.dedup();
+    aircraft.sort();
+    aircraft.dedup();
 
-    let session_id_str = "flight_identifier";
-    let aircraft_id_str = "aircraft_identifier";
-    let aircraft_type_str = "aircraft_type";
-    let simulated_str = "simulated";
-    let stmt = client
-        .prepare_cached(&format!(
-            r#"
-            SELECT 
-                "flights"."flight_identifier" as "{session_id_str}",
-                "aircraft"."identifier" as "{aircraft_id_str}",
-                "aircraft"."aircraft_type" as "{aircraft_type_str}",
-                "aircraft"."simulated" as "{simulated_str}"
-            FROM {aircraft_table_name} as "aircraft"
-            LEFT JOIN {flights_table_name} as "flights"
-                ON (
-                    "flights"."aircraft_identifier" = "aircraft"."identifier"
-                    OR "flights"."flight_identifier" = "aircraft"."session_id"
-                )
-            WHERE 
-                (
-                    -- get grounded aircraft without a scheduled flight
-                    ST_Intersects(ST_Envelope($1), "aircraft"."geom")
-                    AND "aircraft"."last_position_update" >= $2
-                    AND "aircraft"."last_position_update" <= $3
-                ) OR (
-                    -- flights that intersect this window
-                    "flights"."geom" IS NOT NULL
-                    AND ST_Intersects(ST_Envelope($1), "flights"."geom")
-                    AND "flights"."time_end" >= $2
-                    AND "flights"."time_start" <= $3
-                );
-            "#,
-            flights_table_name = get_flights_table_name(),
-            aircraft_table_name = super::aircraft::get_table_name(),
-        ))
+    // The aircraft condition takes the leading parameters
+    let mut shard_params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![];
+    let mut condition = String::new();
+    if let Some(aircraft_condition) = aircraft_condition {
+        shard_params.extend(params);
+        condition = format!("({aircraft_condition}) OR ");
+    }
+
+    condition += &format!(
+        r#""identifier" = ANY(${}::VARCHAR[]) OR "session_id" = ANY(${}::VARCHAR[])"#,
+        shard_params.len() + 1,
+        shard_params.len() + 2
+    );
+    shard_params.push(&aircraft);
+    shard_params.push(&flights);
+
+    super::shard::ShardRows::fetch(client, table_name, &condition, &shard_params)
         .await
         .map_err(|e| {
-            postgis_error!("(get_flights) could not prepare cached statement: {}", e);
-            FlightError::DBError
+            postgis_error!("(get_flight_shard_rows) could not get shard rows: {}", e);
+            match e {
+                super::shard::ShardError::Client => FlightError::Client,
+                super::shard::ShardError::DBError => FlightError::DBError,
+            }
+        })
+}
+
+/// Sort key of an aircraft row: the flight's own aircraft first, then by
+///  identifier with unknown identifiers last
+fn preferred_aircraft_key(
+    identifier: &Option<String>,
+    aircraft_id: &Option<String>,
+) -> (bool, bool, Option<String>) {
+    (
+        identifier.is_none() || identifier != aircraft_id,
+        identifier.is_none(),
+        identifier.clone(),
+    )
+}
+
+/// Records the time of a committed flight update, dry runs don't count
+fn record_flight_update(dry_run: bool) {
+    if !dry_run {
+        LAST_FLIGHT_UPDATE_MS.store(crate::clock::now().timestamp_millis(), Ordering::Relaxed);
+    }
+}
+
+/// Converts a queued flight path to an update request
+fn flight_path_request(message: FlightPathMessage) -> UpdateFlightPathRequest {
+    UpdateFlightPathRequest {
+        flight_identifier: message.flight_identifier,
+        aircraft_identifier: message.aircraft_identifier,
+        simulated: message.simulated,
+        aircraft_type: message.aircraft_type as i32,
+        path: message
+            .path
+            .into_iter()
+            .map(|p| GrpcPointZ {
+                latitude: p.latitude,
+                longitude: p.longitude,
+                altitude_meters: p.altitude_meters as f32,
+            })
+            .collect(),
+        timestamp_start: Some(message.timestamp_start.into()),
+        timestamp_end: Some(message.timestamp_end.into()),
+        allow_rebind: message.allow_rebind,
+        operator_id: message.operator_id,
+        srid: None,
+        historical: false,
+        dry_run: false,
+        aircraft_identifiers: vec![],
+        tags: vec![],
+        destination_identifier: message.destination_identifier,
+        reservation_secs: None,
+        scenario_id: message.scenario_id,
+        geom_wkt: None,
+        geom_ewkb: None,
+    }
+}
+
+#[async_trait]
+impl Processor<FlightPathMessage> for Consumer {
+    async fn process(&mut self, items: Vec<FlightPathMessage>) -> Result<(), ()> {
+        // Flights are applied one at a time so each outcome can be recorded
+        for message in items {
+            let correlation_id = message.correlation_id.clone();
+            let result =
+                update_flight_path(flight_path_request(message), self.max_flight_duration_secs)
+                    .await;
+
+            let Some(correlation_id) = correlation_id else {
+                continue;
+            };
+
+            let applied = FlightApplied {
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            };
+
+            let _ = record_flight_applied(&correlation_id, &applied).await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true if no two segments start at the same time
+fn unique_segment_starts(segments: &[Segment]) -> bool {
+    let mut starts = segments.iter().map(|s| s.time_start).collect::<Vec<_>>();
+    starts.sort_unstable();
+    starts.windows(2).all(|pair| pair[0] != pair[1])
+}
+
+/// Validates a segmentize request, returning the path and time window
+fn validate_segmentize_request(
+    request: SegmentizePathRequest,
+) -> Result<(Vec<PointZ>, DateTime<Utc>, DateTime<Utc>), FlightError> {
+    if !request.segment_length_meters.is_finite()
+        || request.segment_length_meters <= 0.0
+        || request.segment_length_meters > MAX_SEGMENTIZE_LENGTH_METERS
+    {
+        postgis_error!(
+            "(validate_segmentize_request) invalid segment length: {}",
+            request.segment_length_meters
+        );
+        return Err(FlightError::Segments);
+    }
+
+    let (Some(time_start), Some(time_end)) = (request.time_start, request.time_end) else {
+        postgis_error!("(validate_segmentize_request) time_start and time_end are required.");
+        return Err(FlightError::Time);
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if (time_end - time_start).num_seconds() < 1 {
+        postgis_error!(
+            "(validate_segmentize_request) time_end must be at least 1s after time_start."
+        );
+        return Err(FlightError::Time);
+    }
+
+    let points = request
+        .path
+        .into_iter()
+        .map(PointZ::try_from)
+        .collect::<Result<Vec<PointZ>, _>>()
+        .map_err(|_| {
+            postgis_error!("(validate_segmentize_request) could not convert path to Vec<PointZ>.");
+            FlightError::Location
         })?;
 
-    let result = client
-        .query(&stmt, &[&linestring, &time_start, &time_end])
+    // A path needs at least two distinct points to have a length
+    let Some(first) = points.first() else {
+        postgis_error!("(validate_segmentize_request) empty path.");
+        return Err(FlightError::Location);
+    };
+
+    if points
+        .iter()
+        .all(|p| p.x == first.x && p.y == first.y && p.z == first.z)
+    {
+        postgis_error!("(validate_segmentize_request) degenerate path (zero length).");
+        return Err(FlightError::Location);
+    }
+
+    Ok((points, time_start, time_end))
+}
+
+/// Converts a computed segment to its gRPC representation
+fn path_segment_from(segment: Segment) -> Result<PathSegment, FlightError> {
+    let (Some(start), Some(end)) = (segment.geom.points.first(), segment.geom.points.last()) else {
+        postgis_error!("(path_segment_from) segment has no points.");
+        return Err(FlightError::Segments);
+    };
+
+    Ok(PathSegment {
+        start: Some(GrpcPointZ::from(*start)),
+        end: Some(GrpcPointZ::from(*end)),
+        time_start: Some(segment.time_start.into()),
+        time_end: Some(segment.time_end.into()),
+    })
+}
+
+/// Previews how a path would be segmented, without storing anything
+pub async fn segmentize_path(
+    request: SegmentizePathRequest,
+) -> Result<SegmentizePathResponse, PostgisError> {
+    postgis_debug!("(segmentize_path) entry.");
+
+    let segment_length_meters = request.segment_length_meters;
+    let (points, time_start, time_end) =
+        validate_segmentize_request(request).map_err(PostgisError::FlightPath)?;
+
+    let segments = super::utils::segmentize(points, time_start, time_end, segment_length_meters)
         .await
         .map_err(|e| {
-            postgis_error!("(get_flights) could not execute transaction: {}", e);
-            FlightError::DBError
-        })?;
+            postgis_error!("(segmentize_path) could not segmentize path: {}", e);
+            PostgisError::FlightPath(FlightError::Segments)
+        })?
+        .into_iter()
+        .map(path_segment_from)
+        .collect::<Result<Vec<PathSegment>, FlightError>>()
+        .map_err(PostgisError::FlightPath)?;
 
-    let mut flights = result
-        .iter()
-        .map(|row| {
-            let session_id: Option<String> = row.try_get(session_id_str)?;
-            let aircraft_id: Option<String> = row.try_get(aircraft_id_str)?;
-            let aircraft_type: AircraftType = row.try_get(aircraft_type_str)?;
-            let simulated: bool = row.try_get(simulated_str)?;
+    Ok(SegmentizePathResponse { segments })
+}
 
-            Ok(Flight {
-                session_id,
-                aircraft_id,
-                simulated,
-                positions: vec![],
-                state: None,
-                aircraft_type: aircraft_type as i32,
-            })
+/// A stored flight segment that comes too close to a checked segment
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightConflict {
+    /// The conflicting flight
+    pub flight_identifier: String,
+
+    /// The aircraft assigned to the conflicting flight
+    pub aircraft_identifier: String,
+
+    /// The conflicting segment of the stored flight
+    pub segment: LineStringT<PointZ>,
+
+    /// The point on the stored segment closest to the checked segment
+    pub closest_point: PointZ,
+
+    /// Minimum 3D distance between the two segments in meters
+    pub distance_meters: f64,
+
+    /// Start of the overlapping time interval
+    pub time_start: Option<DateTime<Utc>>,
+
+    /// End of the overlapping time interval
+    pub time_end: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<tokio_postgres::Row> for FlightConflict {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(FlightConflict {
+            flight_identifier: row.try_get("flight_identifier")?,
+            aircraft_identifier: row.try_get("aircraft_identifier")?,
+            segment: row.try_get("geom")?,
+            closest_point: row.try_get("closest_point")?,
+            distance_meters: row.try_get("distance_meters")?,
+            time_start: row.try_get("time_start")?,
+            time_end: row.try_get("time_end")?,
         })
-        .collect::<Result<Vec<Flight>, tokio_postgres::error::Error>>()
-        .map_err(|e| {
-            postgis_error!("(get_flights) could not get flight data: {}", e);
-            FlightError::DBError
-        })?;
+    }
+}
 
-    postgis_debug!("(get_flights) found {} flights.", flights.len());
+impl From<FlightConflict> for GrpcFlightConflict {
+    fn from(conflict: FlightConflict) -> Self {
+        GrpcFlightConflict {
+            flight_identifier: conflict.flight_identifier,
+            aircraft_identifier: conflict.aircraft_identifier,
+            segment: conflict
+                .segment
+                .points
+                .into_iter()
+                .map(GrpcPointZ::from)
+                .collect(),
+            closest_point: Some(GrpcPointZ::from(conflict.closest_point)),
+            distance_meters: conflict.distance_meters,
+            time_start: conflict.time_start.map(Into::into),
+            time_end: conflict.time_end.map(Into::into),
+        }
+    }
+}
 
-    // TODO(R5): Change this to use Redis 60s telemetry storage to acquire
-    //  telemetry information
-    let stmt = client
+/// Prepares a statement that finds stored flight segments within a distance
+///  ($2, meters) of the provided segment ($1) during a time range ($3 to $4)
+///  for flights matching a tag filter (tags in $5)
+///
+/// The distance is the 3D separation in meters, see [`get_intersecting_flight`].
+///  Reservations unexpired at $6, the service clock's current time, are
+///  checked like planned flights.
+pub async fn get_flight_intersection_stmt(
+    client: &Object,
+    tag_filter: &TagFilter,
+) -> Result<tokio_postgres::Statement, PostgisError> {
+    let result = client
         .prepare_cached(&format!(
-            r#"SELECT
-                    "identifier",
-                    "session_id",
+            r#"WITH "segments" AS (
+                SELECT
+                    "flight_identifier",
                     "geom",
-                    "velocity_horizontal_ground_mps",
-                    "velocity_vertical_mps",
-                    "track_angle_degrees",
-                    "last_position_update",
-                    "op_status"
-                FROM {table_name} 
+                    "time_start",
+                    "time_end"
+                FROM {segments_table_name}
                 WHERE
-                    "session_id" = $1 
-                    OR "identifier" = $2 
-                LIMIT 1;
+                    ("time_start" <= $4 OR "time_start" IS NULL) -- easy checks first
+                    AND ("time_end" >= $3 OR "time_end" IS NULL)
+                    AND ST_3DDWithin(
+                        ST_Transform("geom", 4978),
+                        ST_Transform($1, 4978),
+                        $2 -- meters
+                    )
+            ) SELECT
+                "segments"."flight_identifier",
+                "flights"."aircraft_identifier",
+                "segments"."geom",
+                ST_Transform(
+                    ST_3DClosestPoint(
+                        ST_Transform("segments"."geom", 4978),
+                        ST_Transform($1, 4978)
+                    ),
+                    {DEFAULT_SRID}
+                ) AS "closest_point",
+                ST_3DDistance(
+                    ST_Transform("segments"."geom", 4978),
+                    ST_Transform($1, 4978)
+                ) AS "distance_meters",
+                GREATEST("segments"."time_start", $3) AS "time_start",
+                LEAST("segments"."time_end", $4) AS "time_end"
+            FROM "segments"
+            JOIN {flights_table_name} AS "flights"
+                ON "flights"."flight_identifier" = "segments"."flight_identifier"
+            WHERE "flights"."simulated" = FALSE
+                AND "flights"."deleted_at" IS NULL
+                -- unexpired reservations, as of the service clock
+                AND ("flights"."reserved_until" IS NULL OR "flights"."reserved_until" > $6)
+                AND {tag_condition}
+            ORDER BY "distance_meters" ASC
+            LIMIT {MAX_FLIGHT_CONFLICTS};
         "#,
-            table_name = super::aircraft::get_table_name(),
+            segments_table_name = get_flight_segments_table_name(),
+            flights_table_name = get_flights_table_name(),
+            tag_condition = tag_filter.condition(r#""flights"."tags""#, 5),
+        ))
+        .await;
+
+    match result {
+        Ok(stmt) => Ok(stmt),
+        Err(e) => {
+            postgis_error!(
+                "(get_flight_intersection_stmt) could not prepare cached statement: {}",
+                e
+            );
+            Err(PostgisError::FlightPath(FlightError::DBError))
+        }
+    }
+}
+
+/// Prepares a statement that finds, for each segment of a path, the stored
+///  flight segments within a distance ($2, meters) of it during its time
+///  range, for flights matching a tag filter (tags in $5)
+///
+/// The segments are given as arrays of geometries ($1) and start ($3) and
+///  end ($4) times, so the whole path is checked in one query. Each segment
+///  reports up to [`MAX_FLIGHT_CONFLICTS`] conflicts, as with
+///  [`get_flight_intersection_stmt`]. Reservations unexpired at $6 count.
+async fn get_path_intersection_stmt(
+    client: &Object,
+    tag_filter: &TagFilter,
+) -> Result<tokio_postgres::Statement, PostgisError> {
+    client
+        .prepare_cached(&format!(
+            r#"SELECT "conflicts".*
+            FROM UNNEST($1::GEOMETRY[], $3::TIMESTAMPTZ[], $4::TIMESTAMPTZ[])
+                AS "checked"("geom", "time_start", "time_end")
+            CROSS JOIN LATERAL (
+                SELECT
+                    "segments"."flight_identifier",
+                    "flights"."aircraft_identifier",
+                    "segments"."geom",
+                    ST_Transform(
+                        ST_3DClosestPoint(
+                            ST_Transform("segments"."geom", 4978),
+                            ST_Transform("checked"."geom", 4978)
+                        ),
+                        {DEFAULT_SRID}
+                    ) AS "closest_point",
+                    ST_3DDistance(
+                        ST_Transform("segments"."geom", 4978),
+                        ST_Transform("checked"."geom", 4978)
+                    ) AS "distance_meters",
+                    GREATEST("segments"."time_start", "checked"."time_start") AS "time_start",
+                    LEAST("segments"."time_end", "checked"."time_end") AS "time_end"
+                FROM {segments_table_name} AS "segments"
+                JOIN {flights_table_name} AS "flights"
+                    ON "flights"."flight_identifier" = "segments"."flight_identifier"
+                WHERE
+                    ("segments"."time_start" <= "checked"."time_end" OR "segments"."time_start" IS NULL)
+                    AND ("segments"."time_end" >= "checked"."time_start" OR "segments"."time_end" IS NULL)
+                    AND ST_3DDWithin(
+                        ST_Transform("segments"."geom", 4978),
+                        ST_Transform("checked"."geom", 4978),
+                        $2 -- meters
+                    )
+                    AND "flights"."simulated" = FALSE
+                    AND "flights"."deleted_at" IS NULL
+                    AND ("flights"."reserved_until" IS NULL OR "flights"."reserved_until" > $6)
+                    AND {tag_condition}
+                ORDER BY "distance_meters" ASC
+                LIMIT {MAX_FLIGHT_CONFLICTS}
+            ) AS "conflicts";"#,
+            segments_table_name = get_flight_segments_table_name(),
+            flights_table_name = get_flights_table_name(),
+            tag_condition = tag_filter.condition(r#""flights"."tags""#, 5),
         ))
         .await
         .map_err(|e| {
-            postgis_error!("(get_flights) could not prepare cached statement: {}", e);
-            FlightError::DBError
-        })?;
+            postgis_error!(
+                "(get_path_intersection_stmt) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })
+}
 
-    fn process_row(
-        row: tokio_postgres::Row,
-        flight: &mut Flight,
-    ) -> Result<(), tokio_postgres::error::Error> {
-        let identifier: Option<String> = row.try_get("identifier")?;
-        let session_id: Option<String> = row.try_get("session_id")?;
-        let geom: PointZ = row.try_get("geom")?;
-        let velocity_horizontal_ground_mps: f32 = row.try_get("velocity_horizontal_ground_mps")?;
-        let velocity_vertical_mps: f32 = row.try_get("velocity_vertical_mps")?;
-        let track_angle_degrees: f32 = row.try_get("track_angle_degrees")?;
-        let last_position_update: DateTime<Utc> = row.try_get("last_position_update")?;
-        let status: OperationalStatus = row.try_get("op_status")?;
-
-        flight.session_id = session_id;
-        flight.aircraft_id = identifier;
-        flight.positions.push(TimePosition {
-            position: Some(GrpcPointZ {
-                latitude: geom.y,
-                longitude: geom.x,
-                altitude_meters: geom.z as f32,
-            }),
-            timestamp: Some(last_position_update.into()),
-        });
+/// Gets the stored flight segments that come within `distance_meters` of
+///  the provided path during its time window
+///
+/// Corridors the path would take beyond their capacity are reported as
+///  corridor conflicts, see [`super::corridor`].
+pub async fn get_flight_conflicts(
+    request: GetFlightConflictsRequest,
+) -> Result<GetFlightConflictsResponse, PostgisError> {
+    postgis_debug!("(get_flight_conflicts) entry.");
 
-        let state = AircraftState {
-            timestamp: Some(last_position_update.into()),
-            ground_speed_mps: velocity_horizontal_ground_mps,
-            vertical_speed_mps: velocity_vertical_mps,
-            track_angle_degrees,
-            position: Some(GrpcPointZ {
-                latitude: geom.y,
-                longitude: geom.x,
-                altitude_meters: geom.z as f32,
-            }),
-            status: status as i32,
-        };
+    let distance_meters = request.distance_meters;
+    if !distance_meters.is_finite()
+        || distance_meters <= 0.0
+        || distance_meters > MAX_CONFLICT_DISTANCE_METERS
+    {
+        postgis_error!(
+            "(get_flight_conflicts) invalid distance: {}",
+            distance_meters
+        );
+        return Err(PostgisError::FlightPath(FlightError::Location));
+    }
 
-        flight.state = Some(state);
+    let tag_filter = TagFilter::try_from(request.tag_filter).map_err(|e| {
+        postgis_error!("(get_flight_conflicts) invalid tag filter: {}", e);
+        PostgisError::FlightPath(FlightError::Label)
+    })?;
 
-        Ok(())
-    }
+    let (points, time_start, time_end) = validate_segmentize_request(SegmentizePathRequest {
+        path: request.path,
+        time_start: request.time_start,
+        time_end: request.time_end,
+        segment_length_meters: MAX_FLIGHT_SEGMENT_LENGTH_METERS,
+    })
+    .map_err(PostgisError::FlightPath)?;
 
-    let mut result: Vec<Flight> = vec![];
-    for flight in &mut flights {
-        let rows = match client
-            .query(&stmt, &[&flight.session_id, &flight.aircraft_id])
+    let segments = super::utils::segmentize(
+        points,
+        time_start,
+        time_end,
+        MAX_FLIGHT_SEGMENT_LENGTH_METERS,
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(get_flight_conflicts) could not segmentize path: {}", e);
+        PostgisError::FlightPath(FlightError::Segments)
+    })?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_flight_conflicts) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_flight_conflicts) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let stmt = get_flight_intersection_stmt(&client, &tag_filter).await?;
+    let distance_meters = distance_meters as f64;
+    let now = crate::clock::now();
+    let mut conflicts: Vec<GrpcFlightConflict> = vec![];
+    for segment in &segments {
+        let rows = client
+            .query(
+                &stmt,
+                &[
+                    &segment.geom,
+                    &distance_meters,
+                    &segment.time_start,
+                    &segment.time_end,
+                    &tag_filter.tags,
+                    &now,
+                ],
+            )
             .await
-        {
-            Ok(rows) => rows,
-            Err(e) => {
-                postgis_error!("(get_flights) could not execute transaction: {}", e);
-                continue;
-            }
-        };
+            .map_err(|e| {
+                postgis_error!("(get_flight_conflicts) could not execute query: {}", e);
+                PostgisError::FlightPath(FlightError::DBError)
+            })?;
 
         for row in rows {
-            let mut f = flight.clone();
-            if let Err(e) = process_row(row, &mut f) {
-                postgis_error!("(get_flights) could not get position data: {}", e);
-                continue;
-            }
+            let conflict = FlightConflict::try_from(row).map_err(|e| {
+                postgis_error!("(get_flight_conflicts) could not get conflict data: {}", e);
+                PostgisError::FlightPath(FlightError::DBError)
+            })?;
 
-            result.push(f);
+            conflicts.push(conflict.into());
         }
     }
 
-    Ok(result)
+    let corridor_conflicts = super::corridor::get_corridor_conflicts(&client, &segments).await?;
+
+    postgis_debug!(
+        "(get_flight_conflicts) found {} conflicts and {} corridor conflicts.",
+        conflicts.len(),
+        corridor_conflicts.len()
+    );
+    Ok(GetFlightConflictsResponse {
+        conflicts,
+        corridor_conflicts,
+    })
 }
 
-#[cfg(test)]
+/// Validates the separation distance of an intersection check
+fn validate_intersection_distance(distance_meters: f64) -> Result<(), FlightError> {
+    if !distance_meters.is_finite()
+        || distance_meters <= 0.0
+        || distance_meters > MAX_CONFLICT_DISTANCE_METERS as f64
+    {
+        postgis_error!(
+            "(validate_intersection_distance) invalid distance: {}",
+            distance_meters
+        );
+        return Err(FlightError::Location);
+    }
+
+    Ok(())
+}
+
+/// Gets the closest stored flight that comes within `distance_meters` of
+///  a point during a time range
+///
+/// The distance is the 3D separation in meters between the point and the
+///  flight segments, measured in the earth-centered (SRID 4978) frame so
+///  altitude differences count as much as horizontal ones. It must be
+///  positive and at most [`MAX_CONFLICT_DISTANCE_METERS`].
+///
+/// Flights with a [`super::tags::CONFLICT_EXCLUDED_TAGS`] tag are ignored.
+pub async fn get_intersecting_flight(
+    point: PointZ,
+    distance_meters: f64,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Option<FlightConflict>, PostgisError> {
+    postgis_debug!("(get_intersecting_flight) entry.");
+
+    validate_intersection_distance(distance_meters).map_err(PostgisError::FlightPath)?;
+    if time_end < time_start {
+        postgis_error!(
+            "(get_intersecting_flight) end time {} is before start time {}.",
+            time_end,
+            time_start
+        );
+        return Err(PostgisError::FlightPath(FlightError::Time));
+    }
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_intersecting_flight) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let tag_filter = super::tags::conflict_tag_filter();
+    let stmt = get_flight_intersection_stmt(&client, &tag_filter).await?;
+    let row = client
+        .query(
+            &stmt,
+            &[
+                &point,
+                &distance_meters,
+                &time_start,
+                &time_end,
+                &tag_filter.tags,
+                &crate::clock::now(),
+            ],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_intersecting_flight) could not execute query: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .into_iter()
+        .next();
+
+    // Rows are ordered by distance, the first is the closest flight
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    FlightConflict::try_from(row).map(Some).map_err(|e| {
+        postgis_error!("(get_intersecting_flight) could not get flight data: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })
+}
+
+/// A flight segment with its end points in both the default and the
+///  earth-centered (SRID 4978) frame
+#[derive(Debug, Clone, PartialEq)]
+struct TimedSegment {
+    /// Start of the segment in [`DEFAULT_SRID`]
+    start: PointZ,
+
+    /// End of the segment in [`DEFAULT_SRID`]
+    end: PointZ,
+
+    /// Start of the segment in meters, earth-centered
+    start_ecef: PointZ,
+
+    /// End of the segment in meters, earth-centered
+    end_ecef: PointZ,
+
+    /// Time the aircraft is at the start of the segment
+    time_start: DateTime<Utc>,
+
+    /// Time the aircraft is at the end of the segment
+    time_end: DateTime<Utc>,
+}
+
+impl TimedSegment {
+    /// Fraction of the segment covered at `time`, assuming constant speed
+    fn fraction_at(&self, time: DateTime<Utc>) -> f64 {
+        let duration = (self.time_end - self.time_start).num_milliseconds();
+        if duration <= 0 {
+            return 0.0;
+        }
+
+        ((time - self.time_start).num_milliseconds() as f64 / duration as f64).clamp(0.0, 1.0)
+    }
+}
+
+/// Linear interpolation between two points
+fn lerp(a: &PointZ, b: &PointZ, fraction: f64) -> PointZ {
+    PointZ::new(
+        a.x + (b.x - a.x) * fraction,
+        a.y + (b.y - a.y) * fraction,
+        a.z + (b.z - a.z) * fraction,
+        a.srid,
+    )
+}
+
+/// The closest point of approach (CPA) of two flights
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosestApproach {
+    /// Minimum 3D separation of the two aircraft in meters
+    pub distance_meters: f64,
+
+    /// Time the minimum separation occurs
+    pub time: DateTime<Utc>,
+
+    /// Position of the first flight's aircraft at that time
+    pub position_a: PointZ,
+
+    /// Position of the second flight's aircraft at that time
+    pub position_b: PointZ,
+}
+
+/// Closest approach of two aircraft flying along `a` and `b` at the
+///  same time, if their time windows overlap
+///
+/// Both aircraft move at constant velocity during the overlap, so their
+///  separation is minimized in closed form.
+fn segment_closest_approach(a: &TimedSegment, b: &TimedSegment) -> Option<ClosestApproach> {
+    let start = a.time_start.max(b.time_start);
+    let end = a.time_end.min(b.time_end);
+    if start > end {
+        return None;
+    }
+
+    let (a0, a1) = (a.fraction_at(start), a.fraction_at(end));
+    let (b0, b1) = (b.fraction_at(start), b.fraction_at(end));
+
+    // Separation vector at the start and end of the overlap
+    let position = |s: &TimedSegment, fraction: f64| lerp(&s.start_ecef, &s.end_ecef, fraction);
+    let (pa0, pa1) = (position(a, a0), position(a, a1));
+    let (pb0, pb1) = (position(b, b0), position(b, b1));
+    let d0 = [pa0.x - pb0.x, pa0.y - pb0.y, pa0.z - pb0.z];
+    let d1 = [pa1.x - pb1.x, pa1.y - pb1.y, pa1.z - pb1.z];
+    let dv = [d1[0] - d0[0], d1[1] - d0[1], d1[2] - d0[2]];
+
+    let dot = |u: &[f64; 3], v: &[f64; 3]| u[0] * v[0] + u[1] * v[1] + u[2] * v[2];
+    let dv_squared = dot(&dv, &dv);
+    let s = if dv_squared > 0.0 {
+        (-dot(&d0, &dv) / dv_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let d = [d0[0] + dv[0] * s, d0[1] + dv[1] * s, d0[2] + dv[2] * s];
+    let overlap_ms = (end - start).num_milliseconds() as f64;
+    let time = start + Duration::try_milliseconds((overlap_ms * s).round() as i64)?;
+
+    Some(ClosestApproach {
+        distance_meters: dot(&d, &d).sqrt(),
+        time,
+        position_a: lerp(&a.start, &a.end, a.fraction_at(time)),
+        position_b: lerp(&b.start, &b.end, b.fraction_at(time)),
+    })
+}
+
+/// Closest approach of two flights given their segments ordered by time
+///
+/// Returns None if no segments overlap in time.
+fn closest_approach(a: &[TimedSegment], b: &[TimedSegment]) -> Option<ClosestApproach> {
+    let mut closest: Option<ClosestApproach> = None;
+    let (mut i, mut j) = (0, 0);
+
+    // Sweep both flights in time order, each pair of segments that
+    //  overlap in time is visited once
+    while i < a.len() && j < b.len() {
+        if let Some(approach) = segment_closest_approach(&a[i], &b[j]) {
+            if closest
+                .as_ref()
+                .map_or(true, |c| approach.distance_meters < c.distance_meters)
+            {
+                closest = Some(approach);
+            }
+        }
+
+        if a[i].time_end <= b[j].time_end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    closest
+}
+
+/// Gets the segments of a flight in time order
+async fn get_timed_segments(
+    client: &Object,
+    flight_identifier: &str,
+) -> Result<Vec<TimedSegment>, PostgisError> {
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT
+                ST_StartPoint("segments"."geom") AS "start",
+                ST_EndPoint("segments"."geom") AS "end",
+                ST_Transform(ST_StartPoint("segments"."geom"), 4978) AS "start_ecef",
+                ST_Transform(ST_EndPoint("segments"."geom"), 4978) AS "end_ecef",
+                "segments"."time_start",
+                "segments"."time_end"
+            FROM {table_name} AS "segments"
+            JOIN {flights_table_name} AS "flights"
+                ON "flights"."flight_identifier" = "segments"."flight_identifier"
+            WHERE "segments"."flight_identifier" = $1
+                AND "flights"."deleted_at" IS NULL
+                AND "segments"."time_start" IS NOT NULL
+                AND "segments"."time_end" IS NOT NULL
+            ORDER BY "segments"."time_start";"#,
+            table_name = get_flight_segments_table_name(),
+            flights_table_name = get_flights_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_timed_segments) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    client
+        .query(&stmt, &[&flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_timed_segments) could not execute query: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            Ok(TimedSegment {
+                start: row.try_get("start")?,
+                end: row.try_get("end")?,
+                start_ecef: row.try_get("start_ecef")?,
+                end_ecef: row.try_get("end_ecef")?,
+                time_start: row.try_get("time_start")?,
+                time_end: row.try_get("time_end")?,
+            })
+        })
+        .collect::<Result<Vec<TimedSegment>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_timed_segments) could not get segment data: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })
+}
+
+/// Gets the closest point of approach (CPA) of two stored flights
+///
+/// The aircraft are assumed to fly each segment at constant speed. The
+///  separation is the 3D distance in meters in the earth-centered (SRID
+///  4978) frame. Returns Ok(None) if the flights are never airborne at
+///  the same time.
+pub async fn closest_point_of_approach(
+    flight_a: &str,
+    flight_b: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Option<ClosestApproach>, PostgisError> {
+    postgis_debug!("(closest_point_of_approach) entry, flights: '{flight_a}', '{flight_b}'.");
+
+    for identifier in [flight_a, flight_b] {
+        check_flight_identifier(identifier).map_err(|e| {
+            postgis_error!(
+                "(closest_point_of_approach) invalid flight identifier {}: {}",
+                identifier,
+                e
+            );
+            PostgisError::FlightPath(FlightError::Label)
+        })?;
+    }
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(closest_point_of_approach) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let segments_a = get_timed_segments(&client, flight_a).await?;
+    let segments_b = get_timed_segments(&client, flight_b).await?;
+    for (identifier, segments) in [(flight_a, &segments_a), (flight_b, &segments_b)] {
+        if segments.is_empty() {
+            postgis_error!(
+                "(closest_point_of_approach) no segments found for flight '{}'.",
+                identifier
+            );
+            return Err(PostgisError::FlightPath(FlightError::NotFound));
+        }
+    }
+
+    Ok(closest_approach(&segments_a, &segments_b))
+}
+
+/// Minimum number of points in a flight path
+pub const MIN_FLIGHT_PATH_POINTS: usize = 2;
+
+/// Minimum separation from other flights in meters
+pub const FLIGHT_SEPARATION_METERS: f64 = 10.0;
+
+/// Lowest altitude a flight path may use in meters
+pub const MIN_FLIGHT_ALTITUDE_METERS: f64 = 0.0;
+
+/// Highest altitude a flight path may use in meters
+pub const MAX_FLIGHT_ALTITUDE_METERS: f64 = 1_000.0;
+
+/// A problem found when validating a proposed flight
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlightIssue {
+    /// Missing or invalid flight identifier
+    FlightIdentifier,
+
+    /// Missing or invalid aircraft identifier
+    AircraftIdentifier,
+
+    /// Invalid operator identifier
+    OperatorIdentifier,
+
+    /// Invalid aircraft type
+    AircraftType,
+
+    /// Missing start or end time, or end not after start
+    Time,
+
+    /// Invalid SRID
+    Srid,
+
+    /// Fewer than two points, or a point outside the valid range
+    Geometry,
+
+    /// A point outside the allowed altitude corridor
+    Altitude {
+        /// Index of the point in the path
+        index: usize,
+
+        /// Altitude of the point in meters
+        altitude_meters: f64,
+    },
+
+    /// The path crosses an active zone
+    Zone {
+        /// The zone identifier
+        identifier: String,
+    },
+
+    /// The path comes too close to another flight
+    Conflict(FlightConflict),
+}
+
+/// Checks everything about a proposed flight that doesn't need the database
+///
+/// Returns the issues found and, if the path and times are usable, the
+///  path (in the request SRID) and time window for the airspace checks.
+#[allow(clippy::type_complexity)]
+fn validate_flight_static(
+    flight: &UpdateFlightPathRequest,
+) -> (
+    Vec<FlightIssue>,
+    Option<(Vec<PointZ>, DateTime<Utc>, DateTime<Utc>)>,
+) {
+    let mut issues = vec![];
+
+    match flight.flight_identifier {
+        Some(ref identifier) if check_flight_identifier(identifier).is_ok() => (),
+        _ => issues.push(FlightIssue::FlightIdentifier),
+    }
+
+    match flight_members(flight) {
+        Ok(members) if !members.is_empty() => (),
+        _ => issues.push(FlightIssue::AircraftIdentifier),
+    }
+
+    if let Some(ref operator_id) = flight.operator_id {
+        if super::utils::check_label(operator_id).is_err() {
+            issues.push(FlightIssue::OperatorIdentifier);
+        }
+    }
+
+    let aircraft_type: Option<AircraftType> = FromPrimitive::from_i32(flight.aircraft_type);
+    if aircraft_type.is_none() {
+        issues.push(FlightIssue::AircraftType);
+    }
+
+    let times = match (flight.timestamp_start.clone(), flight.timestamp_end.clone()) {
+        (Some(start), Some(end)) => {
+            let start: DateTime<Utc> = start.into();
+            let end: DateTime<Utc> = end.into();
+            (end > start).then_some((start, end))
+        }
+        _ => None,
+    };
+
+    if times.is_none() {
+        issues.push(FlightIssue::Time);
+    }
+
+    let srid = flight.srid.unwrap_or(DEFAULT_SRID);
+    let srid_valid = super::utils::check_srid(srid).is_ok();
+    if !srid_valid {
+        issues.push(FlightIssue::Srid);
+    }
+
+    // Latitude and longitude ranges only apply to the default SRID
+    let points = flight
+        .path
+        .iter()
+        .map(|p| {
+            PointZ::new(
+                p.longitude,
+                p.latitude,
+                p.altitude_meters as f64,
+                Some(srid),
+            )
+        })
+        .collect::<Vec<PointZ>>();
+
+    let in_range = |p: &PointZ| {
+        p.x.is_finite()
+            && p.y.is_finite()
+            && p.z.is_finite()
+            && (srid != DEFAULT_SRID
+                || ((LON_MIN..=LON_MAX).contains(&p.x) && (LAT_MIN..=LAT_MAX).contains(&p.y)))
+    };
+
+    let geometry_valid = points.len() >= MIN_FLIGHT_PATH_POINTS && points.iter().all(in_range);
+    if !geometry_valid {
+        issues.push(FlightIssue::Geometry);
+    }
+
+    let usable = match times {
+        Some((start, end)) if geometry_valid && srid_valid => Some((points, start, end)),
+        _ => None,
+    };
+
+    (issues, usable)
+}
+
+/// Lists the points of a path outside the allowed altitude corridor
+fn altitude_issues(points: &[PointZ]) -> Vec<FlightIssue> {
+    points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !(MIN_FLIGHT_ALTITUDE_METERS..=MAX_FLIGHT_ALTITUDE_METERS).contains(&p.z))
+        .map(|(index, p)| FlightIssue::Altitude {
+            index,
+            altitude_meters: p.z,
+        })
+        .collect()
+}
+
+/// Validates a proposed flight against the current airspace without
+///  storing anything
+///
+/// All checks are run and every issue found is returned, rather than
+///  stopping at the first. Airspace checks (zones and separation) are
+///  skipped if the path or times are unusable. Zones containing the first
+///  or last point (the departure and arrival ports) are not reported.
+///  Flights with a [`super::tags::CONFLICT_EXCLUDED_TAGS`] tag are not
+///  checked for separation.
+///
+/// An empty list means the flight passed all checks.
+pub async fn validate_flight_comprehensive(
+    flight: &UpdateFlightPathRequest,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<FlightIssue>, PostgisError> {
+    postgis_debug!("(validate_flight_comprehensive) entry.");
+
+    let (mut issues, usable) = validate_flight_static(flight);
+    let Some((points, time_start, time_end)) = usable else {
+        return Ok(issues);
+    };
+
+    let srid = flight.srid.unwrap_or(DEFAULT_SRID);
+    let points = if srid != DEFAULT_SRID {
+        super::utils::transform_points(points, srid, DEFAULT_SRID).await?
+    } else {
+        points
+    };
+
+    issues.extend(altitude_issues(&points));
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(validate_flight_comprehensive) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let zone_stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT "identifier"
+            FROM {table_name}
+            WHERE
+                ST_3DIntersects("geom", $1::GEOMETRY(LINESTRINGZ, {DEFAULT_SRID}))
+                AND ("time_start" <= $3 OR "time_start" IS NULL)
+                AND ("time_end" >= $2 OR "time_end" IS NULL)
+                AND NOT ST_Intersects(ST_Force2D("geom"), ST_Force2D($4::GEOMETRY(POINTZ, {DEFAULT_SRID})))
+                AND NOT ST_Intersects(ST_Force2D("geom"), ST_Force2D($5::GEOMETRY(POINTZ, {DEFAULT_SRID})))
+                AND "deleted_at" IS NULL
+            ORDER BY "identifier";"#,
+            table_name = super::zone::get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(validate_flight_comprehensive) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let (Some(first), Some(last)) = (points.first().copied(), points.last().copied()) else {
+        return Ok(issues);
+    };
+
+    let geom = LineStringT {
+        points: points.clone(),
+        srid: Some(DEFAULT_SRID),
+    };
+
+    let zones = client
+        .query(&zone_stmt, &[&geom, &time_start, &time_end, &first, &last])
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(validate_flight_comprehensive) could not execute zone query: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    for row in zones {
+        let identifier: String = row.try_get("identifier").map_err(|e| {
+            postgis_error!(
+                "(validate_flight_comprehensive) could not get zone identifier: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+        issues.push(FlightIssue::Zone { identifier });
+    }
+
+    let segments = super::utils::segmentize(
+        points,
+        time_start,
+        time_end,
+        MAX_FLIGHT_SEGMENT_LENGTH_METERS,
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!(
+            "(validate_flight_comprehensive) could not segmentize path: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Segments)
+    })?;
+
+    let tag_filter = super::tags::conflict_tag_filter();
+    let flights_stmt = get_path_intersection_stmt(&client, &tag_filter).await?;
+    let mut geoms = Vec::with_capacity(segments.len());
+    let mut starts = Vec::with_capacity(segments.len());
+    let mut ends = Vec::with_capacity(segments.len());
+    for segment in segments {
+        geoms.push(segment.geom);
+        starts.push(segment.time_start);
+        ends.push(segment.time_end);
+    }
+
+    // All segments are checked at once
+    let rows = client
+        .query(
+            &flights_stmt,
+            &[
+                &geoms,
+                &FLIGHT_SEPARATION_METERS,
+                &starts,
+                &ends,
+                &tag_filter.tags,
+                &crate::clock::now(),
+            ],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(validate_flight_comprehensive) could not execute conflict query: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let mut conflicts: Vec<FlightConflict> = vec![];
+    for row in rows {
+        let conflict = FlightConflict::try_from(row).map_err(|e| {
+            postgis_error!(
+                "(validate_flight_comprehensive) could not get conflict data: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+        // Re-validating a stored flight shouldn't conflict with itself
+        if flight.flight_identifier.as_ref() == Some(&conflict.flight_identifier) {
+            continue;
+        }
+
+        // Report each conflicting flight once, at its closest approach
+        match conflicts
+            .iter_mut()
+            .find(|c| c.flight_identifier == conflict.flight_identifier)
+        {
+            Some(existing) if existing.distance_meters > conflict.distance_meters => {
+                *existing = conflict
+            }
+            Some(_) => (),
+            None => conflicts.push(conflict),
+        }
+    }
+
+    issues.extend(conflicts.into_iter().map(FlightIssue::Conflict));
+
+    postgis_debug!(
+        "(validate_flight_comprehensive) found {} issues.",
+        issues.len()
+    );
+    Ok(issues)
+}
+
+/// ORDER BY clause of [`get_flights`] for the requested ordering
+///
+/// Every ordering ends with the flight identifier and aircraft identifier
+///  so that ties are always broken the same way.
+fn order_by_clause(order_by: FlightOrder) -> &'static str {
+    match order_by {
+        FlightOrder::FlightIdentifier => {
+            r#""flight_identifier" ASC NULLS LAST, "aircraft_identifier" ASC NULLS LAST"#
+        }
+        FlightOrder::TimeStart => {
+            r#""time_start" ASC NULLS LAST, "flight_identifier" ASC NULLS LAST, "aircraft_identifier" ASC NULLS LAST"#
+        }
+        FlightOrder::Distance => {
+            r#""distance_meters" ASC NULLS LAST, "flight_identifier" ASC NULLS LAST, "aircraft_identifier" ASC NULLS LAST"#
+        }
+    }
+}
+
+/// Get flights and their aircraft that intersect with the provided geometry
+///  and time range.
+pub async fn get_flights(request: GetFlightsRequest) -> Result<Vec<Flight>, FlightError> {
+    get_flights_until(request, None)
+        .await
+        .map(|response| response.flights)
+}
+
+/// If too little time is left before the deadline to look up aircraft data
+fn deadline_near(deadline: Option<std::time::Instant>, now: std::time::Instant) -> bool {
+    deadline.is_some_and(|deadline| {
+        deadline.saturating_duration_since(now)
+            < std::time::Duration::from_millis(MIN_ENRICHMENT_REMAINING_MS)
+    })
+}
+
+/// Get flights and their aircraft that intersect with the provided geometry
+///  and time range, leaving out aircraft data when the deadline draws near.
+///
+/// The flights themselves (identifiers, aircraft type and time bounds) are
+///  found in one query. Aircraft state, positions and velocity samples need
+///  one lookup per flight, which are skipped when `skeleton_only` is set or
+///  less than [`MIN_ENRICHMENT_REMAINING_MS`] is left before `deadline`.
+///  The response is then marked `partial`.
+pub async fn get_flights_until(
+    request: GetFlightsRequest,
+    deadline: Option<std::time::Instant>,
+) -> Result<GetFlightsResponse, FlightError> {
+    postgis_debug!("(get_flights) entry.");
+    let start = std::time::Instant::now();
+
+    let Some(time_start) = request.time_start else {
+        postgis_error!("(get_flights) time_start is required.");
+        return Err(FlightError::Time);
+    };
+
+    let Some(time_end) = request.time_end else {
+        postgis_error!("(get_flights) time_end is required.");
+        return Err(FlightError::Time);
+    };
+
+    if let Some(ref operator_id) = request.operator_id {
+        if let Err(e) = super::utils::check_label(operator_id) {
+            postgis_error!("(get_flights) invalid operator_id {}: {}", operator_id, e);
+            return Err(FlightError::Label);
+        }
+    }
+
+    let tag_filter = TagFilter::try_from(request.tag_filter.clone()).map_err(|e| {
+        postgis_error!("(get_flights) invalid tag filter: {}", e);
+        FlightError::Label
+    })?;
+
+    if request.velocity_samples > MAX_VELOCITY_SAMPLES {
+        postgis_error!(
+            "(get_flights) {} velocity samples requested, at most {}.",
+            request.velocity_samples,
+            MAX_VELOCITY_SAMPLES
+        );
+        return Err(FlightError::Limit);
+    }
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    let linestring = LineStringT {
+        points: vec![
+            Point {
+                x: request.window_min_x,
+                y: request.window_min_y,
+                srid: Some(DEFAULT_SRID),
+            },
+            Point {
+                x: request.window_max_x,
+                y: request.window_max_y,
+                srid: Some(DEFAULT_SRID),
+            },
+        ],
+        srid: Some(DEFAULT_SRID),
+    };
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_flights) could not get psql pool.");
+
+        return Err(FlightError::Client);
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_flights) could not get client from psql connection pool: {}",
+            e
+        );
+        FlightError::Client
+    })?;
+
+    let Some(order_by): Option<FlightOrder> = FromPrimitive::from_i32(request.order_by) else {
+        postgis_error!("(get_flights) invalid order_by: {}", request.order_by);
+        return Err(FlightError::Label);
+    };
+
+    // Aircraft on other shards in the window or flying a flight in it
+    let shard_rows = get_flight_shard_rows(
+        &client,
+        r#""flights"."geom" IS NOT NULL
+            AND "flights"."deleted_at" IS NULL
+            AND ST_Intersects(ST_Envelope($1), "flights"."geom")
+            AND "flights"."time_end" >= $2
+            AND "flights"."time_start" <= $3"#,
+        Some(
+            r#"ST_Intersects(ST_Envelope($1), "geom")
+            AND "last_position_update" >= $2
+            AND "last_position_update" <= $3"#,
+        ),
+        &[&linestring, &time_start, &time_end],
+    )
+    .await?;
+
+    let session_id_str = "flight_identifier";
+    let aircraft_id_str = "aircraft_identifier";
+    let aircraft_type_str = "aircraft_type";
+    let simulated_str = "simulated";
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"
+            SELECT * FROM (
+            SELECT 
+                "flights"."flight_identifier" as "{session_id_str}",
+                "aircraft"."identifier" as "{aircraft_id_str}",
+                "aircraft"."aircraft_type" as "{aircraft_type_str}",
+                "aircraft"."simulated" as "{simulated_str}",
+                "flights"."time_start" as "time_start",
+                "flights"."time_end" as "time_end",
+                "checks"."conflict_count" as "conflict_count",
+                "checks"."checked_at" as "conflict_checked_at",
+                ST_Distance(
+                    ST_Centroid(ST_Envelope($1))::GEOGRAPHY,
+                    ST_Force2D("aircraft"."geom")::GEOGRAPHY
+                ) as "distance_meters"
+            FROM {aircraft_table_name} as "aircraft"
+            LEFT JOIN {flights_table_name} as "flights"
+                ON (
+                    "flights"."aircraft_identifier" = "aircraft"."identifier"
+                    OR "flights"."flight_identifier" = "aircraft"."session_id"
+                    OR EXISTS (
+                        SELECT 1 FROM {members_table_name} as "members"
+                        WHERE "members"."flight_identifier" = "flights"."flight_identifier"
+                            AND "members"."aircraft_identifier" = "aircraft"."identifier"
+                    )
+                ) AND "flights"."deleted_at" IS NULL
+            LEFT JOIN {checks_table_name} as "checks"
+                ON "checks"."flight_identifier" = "flights"."flight_identifier"
+            WHERE 
+                (
+                    (
+                        -- get grounded aircraft without a scheduled flight
+                        ST_Intersects(ST_Envelope($1), "aircraft"."geom")
+                        AND "aircraft"."last_position_update" >= $2
+                        AND "aircraft"."last_position_update" <= $3
+                    ) OR (
+                        -- flights that intersect this window
+                        "flights"."geom" IS NOT NULL
+                        AND ST_Intersects(ST_Envelope($1), "flights"."geom")
+                        AND "flights"."time_end" >= $2
+                        AND "flights"."time_start" <= $3
+                    )
+                ) AND (
+                    -- the flight's operator, the aircraft's without a flight
+                    $4::VARCHAR IS NULL
+                    OR "flights"."operator_id" = $4
+                    OR (
+                        "flights"."flight_identifier" IS NULL
+                        AND "aircraft"."operator_id" = $4
+                    )
+                )
+                -- aircraft without a flight are untagged
+                AND {aircraft_tag_condition}
+            UNION
+            SELECT
+                "flights"."flight_identifier" as "{session_id_str}",
+                COALESCE(
+                    "members"."aircraft_identifier",
+                    "flights"."aircraft_identifier"
+                ) as "{aircraft_id_str}",
+                "flights"."aircraft_type" as "{aircraft_type_str}",
+                "flights"."simulated" as "{simulated_str}",
+                "flights"."time_start" as "time_start",
+                "flights"."time_end" as "time_end",
+                "checks"."conflict_count" as "conflict_count",
+                "checks"."checked_at" as "conflict_checked_at",
+                ST_Distance(
+                    ST_Centroid(ST_Envelope($1))::GEOGRAPHY,
+                    ST_Force2D(ST_StartPoint("flights"."geom"))::GEOGRAPHY
+                ) as "distance_meters"
+            FROM {flights_table_name} as "flights"
+            -- one row per aircraft of a swarm flight
+            LEFT JOIN {members_table_name} as "members"
+                ON "members"."flight_identifier" = "flights"."flight_identifier"
+            LEFT JOIN {checks_table_name} as "checks"
+                ON "checks"."flight_identifier" = "flights"."flight_identifier"
+            WHERE
+                -- scheduled flights whose aircraft has not reported yet
+                "flights"."geom" IS NOT NULL
+                AND "flights"."deleted_at" IS NULL
+                AND ST_Intersects(ST_Envelope($1), "flights"."geom")
+                AND "flights"."time_end" >= $2
+                AND "flights"."time_start" <= $3
+                AND ($4::VARCHAR IS NULL OR "flights"."operator_id" = $4)
+                AND {flight_tag_condition}
+                AND NOT EXISTS (
+                    SELECT 1 FROM {aircraft_table_name} as "aircraft"
+                    WHERE "aircraft"."identifier" = COALESCE(
+                            "members"."aircraft_identifier",
+                            "flights"."aircraft_identifier"
+                        )
+                        -- an aircraft reporting the session stands in for
+                        --  the first aircraft only
+                        OR (
+                            "aircraft"."session_id" = "flights"."flight_identifier"
+                            AND COALESCE(
+                                "members"."aircraft_identifier",
+                                "flights"."aircraft_identifier"
+                            ) = "flights"."aircraft_identifier"
+                        )
+                )
+            ) as "results"
+            ORDER BY {order_by_clause};
+            "#,
+            flights_table_name = get_flights_table_name(),
+            members_table_name = get_flight_aircraft_table_name(),
+            aircraft_table_name = shard_rows.source(6),
+            checks_table_name = super::conflict_check::get_table_name(),
+            order_by_clause = order_by_clause(order_by),
+            aircraft_tag_condition = tag_filter.condition(r#"COALESCE("flights"."tags", '{}')"#, 5),
+            flight_tag_condition = tag_filter.condition(r#""flights"."tags""#, 5),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_flights) could not prepare cached statement: {}", e);
+            FlightError::DBError
+        })?;
+
+    let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![
+        &linestring,
+        &time_start,
+        &time_end,
+        &request.operator_id,
+        &tag_filter.tags,
+    ];
+    params.extend(shard_rows.param());
+
+    let result = client.query(&stmt, &params).await.map_err(|e| {
+        postgis_error!("(get_flights) could not execute transaction: {}", e);
+        match DbErrorKind::from(&e) {
+            DbErrorKind::Timeout => FlightError::Timeout,
+            _ => FlightError::DBError,
+        }
+    })?;
+
+    let flights = result
+        .iter()
+        .map(|row| {
+            let session_id: Option<String> = row.try_get(session_id_str)?;
+            let aircraft_id: Option<String> = row.try_get(aircraft_id_str)?;
+            let aircraft_type: AircraftType = row.try_get(aircraft_type_str)?;
+            let simulated: bool = row.try_get(simulated_str)?;
+            let time_start: Option<DateTime<Utc>> = row.try_get("time_start")?;
+            let time_end: Option<DateTime<Utc>> = row.try_get("time_end")?;
+            let conflict_count: Option<i32> = row.try_get("conflict_count")?;
+            let conflict_checked_at: Option<DateTime<Utc>> = row.try_get("conflict_checked_at")?;
+
+            Ok(Flight {
+                session_id,
+                aircraft_id,
+                simulated,
+                positions: vec![],
+                state: None,
+                aircraft_type: aircraft_type as i32,
+                velocity_samples: vec![],
+                time_start: time_start.map(Into::into),
+                time_end: time_end.map(Into::into),
+                path_alignment: None,
+                conflict_check: super::conflict_check::conflict_check(
+                    conflict_count,
+                    conflict_checked_at,
+                ),
+            })
+        })
+        .collect::<Result<Vec<Flight>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_flights) could not get flight data: {}", e);
+            FlightError::DBError
+        })?;
+
+    postgis_debug!("(get_flights) found {} flights.", flights.len());
+
+    if request.skeleton_only {
+        postgis_debug!(
+            count = flights.len(),
+            duration_ms = start.elapsed().as_millis();
+            "(get_flights) success (skeleton only)."
+        );
+
+        return Ok(GetFlightsResponse {
+            flights,
+            partial: true,
+        });
+    }
+
+    // TODO(R5): Change this to use Redis 60s telemetry storage to acquire
+    //  telemetry information
+    let stmt = format!(
+        r#"SELECT
+                    "identifier",
+                    "session_id",
+                    "geom",
+                    "velocity_horizontal_ground_mps",
+                    "velocity_vertical_mps",
+                    "track_angle_degrees",
+                    "last_position_update",
+                    "horizontal_accuracy_meters",
+                    "vertical_accuracy_meters",
+                    "op_status"
+                FROM {table_name} 
+                WHERE
+                    "session_id" = $1 
+                    OR "identifier" = $2 
+                -- another aircraft may report the session, prefer this one
+                ORDER BY ("identifier" = $2) DESC NULLS LAST, "identifier"
+                LIMIT 1;
+        "#,
+        table_name = super::aircraft::position_source(),
+    );
+
+    fn process_row(
+        row: tokio_postgres::Row,
+        flight: &mut Flight,
+    ) -> Result<(), tokio_postgres::error::Error> {
+        AircraftRow::try_from(row)?.apply(flight);
+        Ok(())
+    }
+
+    // Aircraft on other shards are looked up on each of them
+    let shard_clients = super::shard::get_extra_shard_clients()
+        .await
+        .map_err(|_| FlightError::Client)?;
+
+    // Expanded in query order so the result order is preserved
+    let mut result: Vec<Flight> = vec![];
+    for flight in &flights {
+        if deadline_near(deadline, std::time::Instant::now()) {
+            postgis_warn!(
+                "(get_flights) deadline near after {} ms, returning {} flights without aircraft data.",
+                start.elapsed().as_millis(),
+                flights.len()
+            );
+
+            return Ok(GetFlightsResponse {
+                flights,
+                partial: true,
+            });
+        }
+
+        let mut rows = vec![];
+        for client in std::iter::once(&client).chain(shard_clients.iter()) {
+            match super::query_cached(client, &stmt, &[&flight.session_id, &flight.aircraft_id])
+                .await
+            {
+                Ok(shard_rows) => rows.extend(shard_rows),
+                Err(e) => {
+                    postgis_error!("(get_flights) could not execute transaction: {}", e);
+                    return Err(FlightError::DBError);
+                }
+            };
+        }
+
+        if !shard_clients.is_empty() {
+            rows = preferred_aircraft_row(rows, &flight.aircraft_id);
+        }
+
+        result.extend(expand_flight(flight, rows, process_row));
+    }
+
+    let mut partial = false;
+    if request.velocity_samples > 0 && deadline_near(deadline, std::time::Instant::now()) {
+        postgis_warn!(
+            "(get_flights) deadline near after {} ms, returning flights without velocity samples.",
+            start.elapsed().as_millis()
+        );
+        partial = true;
+    } else if request.velocity_samples > 0 {
+        // The history of an aircraft is on its own shard
+        let mut samples: HashMap<String, Vec<VelocitySample>> = HashMap::new();
+        for client in std::iter::once(&client).chain(shard_clients.iter()) {
+            let shard_samples = get_velocity_samples(client, &result, request.velocity_samples)
+                .await
+                .map_err(|e| {
+                    postgis_error!("(get_flights) could not get velocity samples: {}", e);
+                    FlightError::DBError
+                })?;
+
+            for (identifier, aircraft_samples) in shard_samples {
+                samples.entry(identifier).or_insert(aircraft_samples);
+            }
+        }
+
+        attach_velocity_samples(&mut result, samples);
+    }
+
+    if request.path_alignment && deadline_near(deadline, std::time::Instant::now()) {
+        postgis_warn!(
+            "(get_flights) deadline near after {} ms, returning flights without path alignment.",
+            start.elapsed().as_millis()
+        );
+        partial = true;
+    } else if request.path_alignment {
+        let alignments = get_path_alignments(&client, &result).await.map_err(|e| {
+            postgis_error!("(get_flights) could not get path alignment: {}", e);
+            FlightError::DBError
+        })?;
+
+        for (index, alignment) in alignments {
+            if let Some(flight) = result.get_mut(index) {
+                flight.path_alignment = Some(alignment);
+            }
+        }
+    }
+
+    postgis_debug!(
+        count = result.len(),
+        duration_ms = start.elapsed().as_millis();
+        "(get_flights) success."
+    );
+
+    Ok(GetFlightsResponse {
+        flights: result,
+        partial,
+    })
+}
+
+/// Aircraft data attached to a flight in [`get_flights`]
+///
+/// Aircraft that have only been identified (no telemetry yet) have
+///  no position or velocity.
+#[derive(Debug, Clone)]
+struct AircraftRow {
+    identifier: Option<String>,
+    session_id: Option<String>,
+    geom: Option<PointZ>,
+    velocity_horizontal_ground_mps: Option<f32>,
+    velocity_vertical_mps: Option<f32>,
+    track_angle_degrees: Option<f32>,
+    last_position_update: Option<DateTime<Utc>>,
+    horizontal_accuracy_meters: Option<f32>,
+    vertical_accuracy_meters: Option<f32>,
+    status: OperationalStatus,
+}
+
+impl TryFrom<tokio_postgres::Row> for AircraftRow {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(AircraftRow {
+            identifier: row.try_get("identifier")?,
+            session_id: row.try_get("session_id")?,
+            geom: row.try_get("geom")?,
+            velocity_horizontal_ground_mps: row.try_get("velocity_horizontal_ground_mps")?,
+            velocity_vertical_mps: row.try_get("velocity_vertical_mps")?,
+            track_angle_degrees: row.try_get("track_angle_degrees")?,
+            last_position_update: row.try_get("last_position_update")?,
+            horizontal_accuracy_meters: row.try_get("horizontal_accuracy_meters")?,
+            vertical_accuracy_meters: row.try_get("vertical_accuracy_meters")?,
+            status: row.try_get("op_status")?,
+        })
+    }
+}
+
+impl AircraftRow {
+    /// Adds the aircraft identification and, if known, its position and state
+    fn apply(self, flight: &mut Flight) {
+        flight.session_id = self.session_id.clone();
+        flight.aircraft_id = self.identifier.clone();
+
+        let Some(state) = self.into_state() else {
+            return;
+        };
+
+        flight.positions.push(TimePosition {
+            position: state.position.clone(),
+            timestamp: state.timestamp.clone(),
+            bucket_seconds: 0,
+        });
+
+        flight.state = Some(state);
+    }
+
+    /// The current state of the aircraft, if it has reported a position
+    fn into_state(self) -> Option<AircraftState> {
+        let (Some(geom), Some(last_position_update)) = (self.geom, self.last_position_update)
+        else {
+            return None;
+        };
+
+        Some(AircraftState {
+            timestamp: Some(last_position_update.into()),
+            ground_speed_mps: self.velocity_horizontal_ground_mps.unwrap_or_default(),
+            vertical_speed_mps: self.velocity_vertical_mps.unwrap_or_default(),
+            track_angle_degrees: self.track_angle_degrees.unwrap_or_default(),
+            horizontal_accuracy_meters: self.horizontal_accuracy_meters,
+            vertical_accuracy_meters: self.vertical_accuracy_meters,
+            position: Some(GrpcPointZ {
+                latitude: geom.y,
+                longitude: geom.x,
+                altitude_meters: geom.z as f32,
+            }),
+            status: self.status as i32,
+        })
+    }
+}
+
+/// Gets the last `count` velocity samples of the aircraft of the provided
+///  flights, oldest first
+///
+/// Samples come from the telemetry history, positions flagged as outliers
+///  and rows without a velocity are skipped. Aircraft with fewer samples
+///  get what's available.
+async fn get_velocity_samples(
+    client: &deadpool_postgres::Client,
+    flights: &[Flight],
+    count: u32,
+) -> Result<HashMap<String, Vec<VelocitySample>>, tokio_postgres::error::Error> {
+    let mut identifiers: Vec<String> = flights
+        .iter()
+        .filter_map(|flight| flight.aircraft_id.clone())
+        .collect();
+    identifiers.sort();
+    identifiers.dedup();
+
+    if identifiers.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // Newest samples of each aircraft, then reordered oldest first
+    let stmt = format!(
+        r#"SELECT
+                "aircraft"."identifier",
+                "samples"."timestamp_network",
+                "samples"."velocity_horizontal_ground_mps",
+                "samples"."velocity_vertical_mps",
+                "samples"."track_angle_degrees"
+            FROM UNNEST($1::VARCHAR[]) AS "aircraft"("identifier")
+            CROSS JOIN LATERAL (
+                SELECT
+                    "timestamp_network",
+                    "velocity_horizontal_ground_mps",
+                    "velocity_vertical_mps",
+                    "track_angle_degrees"
+                FROM {table_name}
+                WHERE "identifier" = "aircraft"."identifier"
+                    AND "velocity_horizontal_ground_mps" IS NOT NULL
+                    AND NOT "outlier"
+                ORDER BY "timestamp_network" DESC
+                LIMIT $2
+            ) AS "samples"
+            ORDER BY "aircraft"."identifier", "samples"."timestamp_network" ASC;"#,
+        table_name = super::aircraft::get_history_table_name(),
+    );
+
+    let mut samples: HashMap<String, Vec<VelocitySample>> = HashMap::new();
+    for row in super::query_cached(client, &stmt, &[&identifiers, &(count as i64)]).await? {
+        let identifier: String = row.try_get("identifier")?;
+        let timestamp: DateTime<Utc> = row.try_get("timestamp_network")?;
+        let vertical_speed_mps: Option<f32> = row.try_get("velocity_vertical_mps")?;
+        let track_angle_degrees: Option<f32> = row.try_get("track_angle_degrees")?;
+
+        samples.entry(identifier).or_default().push(VelocitySample {
+            timestamp: Some(timestamp.into()),
+            ground_speed_mps: row.try_get("velocity_horizontal_ground_mps")?,
+            vertical_speed_mps: vertical_speed_mps.unwrap_or_default(),
+            track_angle_degrees: track_angle_degrees.unwrap_or_default(),
+        });
+    }
+
+    Ok(samples)
+}
+
+/// Adds the velocity samples of each flight's aircraft
+///
+/// Swarm members each get their own samples.
+fn attach_velocity_samples(flights: &mut [Flight], samples: HashMap<String, Vec<VelocitySample>>) {
+    for flight in flights.iter_mut() {
+        if let Some(aircraft_samples) = flight
+            .aircraft_id
+            .as_ref()
+            .and_then(|identifier| samples.get(identifier))
+        {
+            flight.velocity_samples = aircraft_samples.clone();
+        }
+    }
+}
+
+/// Builds the [`PathAlignment`] of an aircraft from its location along
+///  the path
+///
+/// The segment index is clamped to the segments of the path, as the end
+///  point of the path is located at the start of a segment that doesn't
+///  exist. Aircraft further from the path than
+///  [`MAX_PATH_LATERAL_DEVIATION_METERS`] or
+///  [`MAX_PATH_VERTICAL_DEVIATION_METERS`] are off the path, and are
+///  aligned to the closest point of the path regardless.
+fn path_alignment(
+    segment_index: i64,
+    segment_count: i64,
+    lateral_deviation_meters: f64,
+    vertical_deviation_meters: f64,
+    fraction_complete: f64,
+) -> PathAlignment {
+    let segment_index = segment_index.clamp(0, (segment_count - 1).max(0));
+    let off_path = lateral_deviation_meters > MAX_PATH_LATERAL_DEVIATION_METERS
+        || vertical_deviation_meters.abs() > MAX_PATH_VERTICAL_DEVIATION_METERS;
+
+    PathAlignment {
+        segment_index: segment_index as u32,
+        lateral_deviation_meters,
+        vertical_deviation_meters,
+        fraction_complete: fraction_complete.clamp(0.0, 1.0),
+        off_path,
+    }
+}
+
+/// Gets the alignment of each flight's aircraft with the planned path of
+///  the flight, keyed by the index of the flight in `flights`
+///
+/// The current position is projected onto the closest point of the path,
+///  as in [`flight_progress`]. Flights without a reported position or
+///  without a stored path get no alignment.
+async fn get_path_alignments(
+    client: &deadpool_postgres::Client,
+    flights: &[Flight],
+) -> Result<Vec<(usize, PathAlignment)>, tokio_postgres::error::Error> {
+    let mut indices: Vec<i64> = vec![];
+    let mut identifiers: Vec<String> = vec![];
+    let (mut xs, mut ys, mut zs): (Vec<f64>, Vec<f64>, Vec<f64>) = (vec![], vec![], vec![]);
+    for (index, flight) in flights.iter().enumerate() {
+        let (Some(session_id), Some(position)) = (
+            flight.session_id.as_ref(),
+            flight.state.as_ref().and_then(|state| state.position),
+        ) else {
+            continue;
+        };
+
+        indices.push(index as i64);
+        identifiers.push(session_id.clone());
+        xs.push(position.longitude);
+        ys.push(position.latitude);
+        zs.push(position.altitude_meters as f64);
+    }
+
+    if indices.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let stmt = format!(
+        r#"SELECT
+                "input"."index",
+                "located"."fraction",
+                (
+                    SELECT COUNT(*) FROM ST_DumpPoints("flights"."geom") AS "vertices"
+                    WHERE ST_LineLocatePoint(
+                        ST_Force2D("flights"."geom"),
+                        ST_Force2D("vertices"."geom")
+                    ) <= "located"."fraction"
+                ) - 1 AS "segment_index",
+                ST_NPoints("flights"."geom") - 1 AS "segment_count",
+                ST_Distance(
+                    ST_Force2D("located"."planned")::GEOGRAPHY,
+                    ST_Force2D("input"."geom")::GEOGRAPHY
+                ) AS "lateral_deviation_meters",
+                ST_Z("input"."geom") - ST_Z("located"."planned") AS "vertical_deviation_meters"
+            FROM (
+                SELECT
+                    "index",
+                    "flight_identifier",
+                    ST_SetSRID(ST_MakePoint("x", "y", "z"), {DEFAULT_SRID}) AS "geom"
+                FROM UNNEST($1::BIGINT[], $2::VARCHAR[], $3::FLOAT8[], $4::FLOAT8[], $5::FLOAT8[])
+                    AS "input"("index", "flight_identifier", "x", "y", "z")
+            ) AS "input"
+            JOIN {table_name} AS "flights"
+                ON "flights"."flight_identifier" = "input"."flight_identifier"
+                AND "flights"."geom" IS NOT NULL
+                AND "flights"."deleted_at" IS NULL
+            CROSS JOIN LATERAL (
+                SELECT
+                    "fraction",
+                    ST_LineInterpolatePoint("flights"."geom", "fraction") AS "planned"
+                FROM ST_LineLocatePoint(
+                    ST_Force2D("flights"."geom"),
+                    ST_Force2D("input"."geom")
+                ) AS "fraction"
+            ) AS "located";"#,
+        table_name = get_flights_table_name(),
+    );
+
+    super::query_cached(client, &stmt, &[&indices, &identifiers, &xs, &ys, &zs])
+        .await?
+        .into_iter()
+        .map(|row| {
+            let index: i64 = row.try_get("index")?;
+            let segment_count: i32 = row.try_get("segment_count")?;
+            let alignment = path_alignment(
+                row.try_get("segment_index")?,
+                segment_count as i64,
+                row.try_get("lateral_deviation_meters")?,
+                row.try_get("vertical_deviation_meters")?,
+                row.try_get("fraction")?,
+            );
+
+            Ok((index as usize, alignment))
+        })
+        .collect()
+}
+
+/// Produces one [`Flight`] per aircraft row found for the provided flight.
+///
+/// A flight with no matching aircraft rows (e.g. the aircraft has not
+///  reported any telemetry yet) is still returned with no positions
+///  and no state, so that callers don't assume the flight doesn't exist.
+fn expand_flight<R, E, F>(flight: &Flight, rows: Vec<R>, mut process: F) -> Vec<Flight>
+where
+    E: std::fmt::Display,
+    F: FnMut(R, &mut Flight) -> Result<(), E>,
+{
+    if rows.is_empty() {
+        postgis_debug!(
+            "(expand_flight) no aircraft data for flight {:?}.",
+            flight.session_id
+        );
+
+        return vec![flight.clone()];
+    }
+
+    let mut result: Vec<Flight> = vec![];
+    for row in rows {
+        let mut f = flight.clone();
+        if let Err(e) = process(row, &mut f) {
+            postgis_error!("(expand_flight) could not get position data: {}", e);
+            continue;
+        }
+
+        result.push(f);
+    }
+
+    result
+}
+
+/// Gets the current state of the aircraft flying each of the provided
+///  flights in a single query
+///
+/// A flight's aircraft is its lead aircraft or, failing that, the aircraft
+///  reporting the flight as its session. Flights whose aircraft has not
+///  reported a position are omitted from the result.
+pub async fn get_states_for_flights(
+    flight_identifiers: Vec<String>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<HashMap<String, AircraftState>, PostgisError> {
+    postgis_debug!(
+        "(get_states_for_flights) entry, count: {}.",
+        flight_identifiers.len()
+    );
+
+    for identifier in &flight_identifiers {
+        check_flight_identifier(identifier).map_err(|e| {
+            postgis_error!(
+                "(get_states_for_flights) invalid flight identifier {}: {}",
+                identifier,
+                e
+            );
+            PostgisError::FlightPath(FlightError::Label)
+        })?;
+    }
+
+    if flight_identifiers.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_states_for_flights) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let shard_rows = get_flight_shard_rows(
+        &client,
+        r#""flights"."flight_identifier" = ANY($1::VARCHAR[])"#,
+        None,
+        &[&flight_identifiers],
+    )
+    .await
+    .map_err(PostgisError::FlightPath)?;
+
+    let stmt = format!(
+        r#"SELECT DISTINCT ON ("requested"."flight_identifier")
+                    "requested"."flight_identifier" as "flight_identifier",
+                    "aircraft"."identifier",
+                    "aircraft"."session_id",
+                    "aircraft"."geom",
+                    "aircraft"."velocity_horizontal_ground_mps",
+                    "aircraft"."velocity_vertical_mps",
+                    "aircraft"."track_angle_degrees",
+                    "aircraft"."last_position_update",
+                    "aircraft"."horizontal_accuracy_meters",
+                    "aircraft"."vertical_accuracy_meters",
+                    "aircraft"."op_status"
+                FROM UNNEST($1::VARCHAR[]) as "requested"("flight_identifier")
+                LEFT JOIN {flights_table_name} as "flights"
+                    ON "flights"."flight_identifier" = "requested"."flight_identifier"
+                    AND "flights"."deleted_at" IS NULL
+                JOIN {aircraft_table_name} as "aircraft"
+                    ON "aircraft"."identifier" = "flights"."aircraft_identifier"
+                    OR "aircraft"."session_id" = "requested"."flight_identifier"
+                WHERE "aircraft"."geom" IS NOT NULL
+                    AND "aircraft"."last_position_update" IS NOT NULL
+                -- prefer the lead aircraft over another reporting the session
+                ORDER BY
+                    "requested"."flight_identifier",
+                    ("aircraft"."identifier" = "flights"."aircraft_identifier") DESC NULLS LAST,
+                    "aircraft"."identifier";
+            "#,
+        flights_table_name = get_flights_table_name(),
+        aircraft_table_name = shard_rows.source(2),
+    );
+
+    let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![&flight_identifiers];
+    params.extend(shard_rows.param());
+
+    let rows = super::query_cached(&client, &stmt, &params)
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_states_for_flights) could not execute query: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let mut states: HashMap<String, AircraftState> = HashMap::new();
+    for row in rows {
+        let flight_identifier: String = row.try_get("flight_identifier").map_err(|e| {
+            postgis_error!(
+                "(get_states_for_flights) could not get flight identifier: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+        let aircraft = AircraftRow::try_from(row).map_err(|e| {
+            postgis_error!(
+                "(get_states_for_flights) could not get aircraft data: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+        if let Some(state) = aircraft.into_state() {
+            states.insert(flight_identifier, state);
+        }
+    }
+
+    postgis_debug!("(get_states_for_flights) found {} states.", states.len());
+    Ok(states)
+}
+
+/// A flight scheduled to arrive at a vertiport
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertiportArrival {
+    /// The unique identifier of the flight
+    pub flight_identifier: String,
+
+    /// The planned arrival time of the flight
+    pub time_end: DateTime<Utc>,
+}
+
+/// Validates the vertiport identifier and time window of an arrivals query
+fn validate_arrivals_request(
+    vertiport_identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Result<(), FlightError> {
+    if let Err(e) =
+        super::utils::check_string(vertiport_identifier, super::vertiport::IDENTIFIER_REGEX)
+    {
+        postgis_error!(
+            "(validate_arrivals_request) invalid vertiport identifier {}: {}",
+            vertiport_identifier,
+            e
+        );
+        return Err(FlightError::Label);
+    }
+
+    if time_end < time_start {
+        postgis_error!("(validate_arrivals_request) time_end is before time_start.");
+        return Err(FlightError::Time);
+    }
+
+    Ok(())
+}
+
+/// Gets the flights scheduled to arrive at a vertiport within a time window
+///
+/// A flight arrives at the vertiport if the vertiport is its destination
+///  and its `time_end` falls within the window (inclusive). Flights are
+///  ordered by arrival time, then identifier.
+pub async fn vertiport_arrivals(
+    vertiport_identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<VertiportArrival>, PostgisError> {
+    postgis_debug!(
+        "(vertiport_arrivals) entry, vertiport: {}.",
+        vertiport_identifier
+    );
+
+    validate_arrivals_request(vertiport_identifier, time_start, time_end)
+        .map_err(PostgisError::FlightPath)?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(vertiport_arrivals) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT "flight_identifier", "time_end"
+            FROM {table_name}
+            WHERE "destination_identifier" = $1
+                AND "time_end" >= $2
+                AND "time_end" <= $3
+                AND "deleted_at" IS NULL
+            ORDER BY "time_end", "flight_identifier";
+        "#,
+        table_name = get_flights_table_name(),
+    );
+
+    let arrivals = super::query_cached(
+        &client,
+        &stmt,
+        &[&vertiport_identifier, &time_start, &time_end],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(vertiport_arrivals) could not execute query: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?
+    .into_iter()
+    .map(|row| {
+        Ok(VertiportArrival {
+            flight_identifier: row.try_get("flight_identifier")?,
+            time_end: row.try_get("time_end")?,
+        })
+    })
+    .collect::<Result<Vec<VertiportArrival>, tokio_postgres::error::Error>>()
+    .map_err(|e| {
+        postgis_error!("(vertiport_arrivals) could not get arrival data: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    postgis_debug!("(vertiport_arrivals) found {} arrivals.", arrivals.len());
+    Ok(arrivals)
+}
+
+/// Progress of an aircraft along its flight path
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FlightProgress {
+    /// Distance left to travel along the flight path in meters
+    pub distance_remaining_meters: f64,
+
+    /// Fraction of the flight path already travelled (0.0 to 1.0)
+    pub fraction_complete: f64,
+
+    /// The planned end time of the flight
+    pub time_end: Option<DateTime<Utc>>,
+
+    /// The current ground speed of the aircraft, if known
+    pub ground_speed_mps: Option<f32>,
+}
+
+impl TryFrom<tokio_postgres::Row> for FlightProgress {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        Ok(FlightProgress {
+            distance_remaining_meters: row.try_get("distance_remaining_meters")?,
+            fraction_complete: row.try_get("fraction")?,
+            time_end: row.try_get("time_end")?,
+            ground_speed_mps: row.try_get("velocity_horizontal_ground_mps")?,
+        })
+    }
+}
+
+/// Gets the progress of a flight along its stored path given
+///  the current position of the aircraft.
+///
+/// The current position is projected onto the closest point of the path.
+pub async fn flight_progress(
+    flight_identifier: &str,
+    current_point: PointZ,
+) -> Result<FlightProgress, PostgisError> {
+    postgis_debug!("(flight_progress) entry, flight: '{flight_identifier}'.");
+    check_flight_identifier(flight_identifier).map_err(|e| {
+        postgis_error!(
+            "(flight_progress) invalid flight identifier {}: {}",
+            flight_identifier,
+            e
+        );
+        PostgisError::FlightPath(FlightError::Label)
+    })?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(flight_progress) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(flight_progress) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let shard_rows = get_flight_shard_rows(
+        &client,
+        r#""flights"."flight_identifier" = $1"#,
+        None,
+        &[&flight_identifier],
+    )
+    .await
+    .map_err(PostgisError::FlightPath)?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"WITH "located" AS (
+                SELECT
+                    "flights"."geom",
+                    "flights"."time_end",
+                    ST_LineLocatePoint(
+                        ST_Force2D("flights"."geom"),
+                        ST_Force2D($2::GEOMETRY(POINTZ, {DEFAULT_SRID}))
+                    ) AS "fraction",
+                    "aircraft"."velocity_horizontal_ground_mps"
+                FROM {flights_table_name} AS "flights"
+                LEFT JOIN {aircraft_table_name} AS "aircraft"
+                    ON "aircraft"."identifier" = "flights"."aircraft_identifier"
+                WHERE "flights"."flight_identifier" = $1
+                    AND "flights"."geom" IS NOT NULL
+                    AND "flights"."deleted_at" IS NULL
+                LIMIT 1
+            ) SELECT
+                ST_Length(
+                    ST_LineSubstring("geom", "fraction", 1)::GEOGRAPHY
+                ) AS "distance_remaining_meters",
+                "fraction",
+                "time_end",
+                "velocity_horizontal_ground_mps"
+            FROM "located";"#,
+            flights_table_name = get_flights_table_name(),
+            aircraft_table_name = shard_rows.source(3),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(flight_progress) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+        vec![&flight_identifier, &current_point];
+    params.extend(shard_rows.param());
+
+    let row = client
+        .query_opt(&stmt, &params)
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(flight_progress) could not get path for flight '{flight_identifier}': {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .ok_or_else(|| {
+            postgis_error!("(flight_progress) no path found for flight '{flight_identifier}'.");
+            PostgisError::FlightPath(FlightError::NotFound)
+        })?;
+
+    let progress = FlightProgress::try_from(row).map_err(|e| {
+        postgis_error!("(flight_progress) could not get progress data: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    postgis_debug!("(flight_progress) progress: {:?}", progress);
+    Ok(progress)
+}
+
+/// Gets a single flight with the result of its last conflict check
+///
+/// The state and position of the flight's aircraft are attached if it has
+///  reported a position, see [`get_states_for_flights`].
+pub async fn get_flight(flight_identifier: &str) -> Result<Flight, PostgisError> {
+    postgis_debug!("(get_flight) entry, flight: '{flight_identifier}'.");
+    check_flight_identifier(flight_identifier).map_err(|e| {
+        postgis_error!(
+            "(get_flight) invalid flight identifier {}: {}",
+            flight_identifier,
+            e
+        );
+        PostgisError::FlightPath(FlightError::Label)
+    })?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_flight) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_flight) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT
+                "flights"."aircraft_identifier",
+                "flights"."aircraft_type",
+                "flights"."simulated",
+                "flights"."time_start",
+                "flights"."time_end",
+                "checks"."conflict_count",
+                "checks"."checked_at" AS "conflict_checked_at"
+            FROM {flights_table_name} AS "flights"
+            LEFT JOIN {checks_table_name} AS "checks"
+                ON "checks"."flight_identifier" = "flights"."flight_identifier"
+            WHERE "flights"."flight_identifier" = $1
+                AND "flights"."deleted_at" IS NULL;"#,
+            flights_table_name = get_flights_table_name(),
+            checks_table_name = super::conflict_check::get_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_flight) could not prepare cached statement: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let row = client
+        .query_opt(&stmt, &[&flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_flight) could not get flight '{flight_identifier}': {}",
+                e
+            );
+            PostgisError::FlightPath(db_error(&e))
+        })?
+        .ok_or_else(|| {
+            postgis_error!("(get_flight) no flight found for '{flight_identifier}'.");
+            PostgisError::FlightPath(FlightError::NotFound)
+        })?;
+
+    let parse = || -> Result<Flight, tokio_postgres::error::Error> {
+        let aircraft_type: AircraftType = row.try_get("aircraft_type")?;
+        let time_start: Option<DateTime<Utc>> = row.try_get("time_start")?;
+        let time_end: Option<DateTime<Utc>> = row.try_get("time_end")?;
+
+        Ok(Flight {
+            session_id: Some(flight_identifier.to_string()),
+            aircraft_id: row.try_get("aircraft_identifier")?,
+            simulated: row.try_get("simulated")?,
+            positions: vec![],
+            state: None,
+            aircraft_type: aircraft_type as i32,
+            velocity_samples: vec![],
+            time_start: time_start.map(Into::into),
+            time_end: time_end.map(Into::into),
+            path_alignment: None,
+            conflict_check: super::conflict_check::conflict_check(
+                row.try_get("conflict_count")?,
+                row.try_get("conflict_checked_at")?,
+            ),
+        })
+    };
+
+    let mut flight = parse().map_err(|e| {
+        postgis_error!("(get_flight) could not get flight data: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    if let Some(state) = get_states_for_flights(vec![flight_identifier.to_string()], pool)
+        .await?
+        .remove(flight_identifier)
+    {
+        flight.positions.push(TimePosition {
+            position: state.position.clone(),
+            timestamp: state.timestamp.clone(),
+            bucket_seconds: 0,
+        });
+
+        flight.state = Some(state);
+    }
+
+    postgis_debug!("(get_flight) success.");
+    Ok(flight)
+}
+
+/// Estimates the time of arrival given the remaining distance and ground speed
+///
+/// If the ground speed is zero or unknown, the planned end time of the flight
+///  is used instead.
+fn estimate_arrival(
+    now: DateTime<Utc>,
+    distance_remaining_meters: f64,
+    ground_speed_mps: Option<f32>,
+    time_end: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>, PostgisError> {
+    match ground_speed_mps {
+        Some(speed) if speed.is_finite() && speed > 0.0 => {
+            let seconds = distance_remaining_meters / speed as f64;
+            let Some(delta) = Duration::try_milliseconds((seconds * 1000.0) as i64) else {
+                postgis_error!("(estimate_arrival) could not create time delta from {seconds}s.");
+                return Err(PostgisError::FlightPath(FlightError::Time));
+            };
+
+            Ok(now + delta)
+        }
+        _ => time_end.ok_or_else(|| {
+            postgis_error!("(estimate_arrival) no ground speed or end time available.");
+            PostgisError::FlightPath(FlightError::Time)
+        }),
+    }
+}
+
+/// Computes the estimated time of arrival of a flight at its destination
+///  from the remaining distance along the stored path and the current
+///  ground speed of the aircraft.
+pub async fn flight_eta(
+    flight_identifier: &str,
+    current_point: PointZ,
+) -> Result<DateTime<Utc>, PostgisError> {
+    postgis_debug!("(flight_eta) entry, flight: '{flight_identifier}'.");
+    let progress = flight_progress(flight_identifier, current_point).await?;
+
+    estimate_arrival(
+        crate::clock::now(),
+        progress.distance_remaining_meters,
+        progress.ground_speed_mps,
+        progress.time_end,
+    )
+}
+
+/// Validates a [`GetFlightSegmentsRequest`], returning the limit and offset
+fn validate_flight_segments_request(
+    request: &GetFlightSegmentsRequest,
+) -> Result<(i64, i64), FlightError> {
+    check_flight_identifier(&request.flight_identifier).map_err(|e| {
+        postgis_error!(
+            "(validate_flight_segments_request) invalid flight identifier {}: {}",
+            request.flight_identifier,
+            e
+        );
+        FlightError::Label
+    })?;
+
+    let limit = request.limit.unwrap_or(MAX_FLIGHT_SEGMENTS_LIMIT);
+    if limit == 0 || limit > MAX_FLIGHT_SEGMENTS_LIMIT {
+        postgis_error!(
+            "(validate_flight_segments_request) limit must be between 1 and {}: {}",
+            MAX_FLIGHT_SEGMENTS_LIMIT,
+            limit
+        );
+        return Err(FlightError::Limit);
+    }
+
+    Ok((limit as i64, request.offset.unwrap_or(0) as i64))
+}
+
+/// Gets the stored segments of a flight, ordered along the path
+///
+/// Returns [`FlightError::NotFound`] if the flight has no segments.
+pub async fn get_flight_segments(
+    request: GetFlightSegmentsRequest,
+) -> Result<GetFlightSegmentsResponse, PostgisError> {
+    postgis_debug!(
+        "(get_flight_segments) entry, flight: '{}'.",
+        request.flight_identifier
+    );
+
+    let (limit, offset) =
+        validate_flight_segments_request(&request).map_err(PostgisError::FlightPath)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_flight_segments) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_flight_segments) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT "segments"."geom", "segments"."time_start", "segments"."time_end"
+            FROM {table_name} AS "segments"
+            JOIN {flights_table_name} AS "flights"
+                ON "flights"."flight_identifier" = "segments"."flight_identifier"
+            WHERE "segments"."flight_identifier" = $1
+                AND "flights"."deleted_at" IS NULL
+            ORDER BY "segments"."time_start"
+            LIMIT $2 OFFSET $3;"#,
+            table_name = get_flight_segments_table_name(),
+            flights_table_name = get_flights_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_flight_segments) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let segments = client
+        .query(&stmt, &[&request.flight_identifier, &limit, &offset])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_flight_segments) could not execute query: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let geom: Option<LineStringT<PointZ>> = row.try_get("geom")?;
+            let time_start: Option<DateTime<Utc>> = row.try_get("time_start")?;
+            let time_end: Option<DateTime<Utc>> = row.try_get("time_end")?;
+
+            Ok(GrpcFlightSegment {
+                index: (offset + i as i64) as u32,
+                points: geom
+                    .map(|g| g.points.into_iter().map(GrpcPointZ::from).collect())
+                    .unwrap_or_default(),
+                time_start: time_start.map(Into::into),
+                time_end: time_end.map(Into::into),
+            })
+        })
+        .collect::<Result<Vec<GrpcFlightSegment>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_flight_segments) could not get segment data: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    // An offset past the end of an existing flight still yields no rows
+    if segments.is_empty() {
+        postgis_error!(
+            "(get_flight_segments) no segments found for flight '{}'.",
+            request.flight_identifier
+        );
+        return Err(PostgisError::FlightPath(FlightError::NotFound));
+    }
+
+    Ok(GetFlightSegmentsResponse { segments })
+}
+
+/// Default tolerance of [`verify_flight_segments`] in meters
+pub const SEGMENT_TOLERANCE_METERS: f64 = 1.0;
+
+/// A difference between the stored path of a flight and its segments
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentDiscrepancy {
+    /// The flight has a path but no segments
+    NoSegments,
+
+    /// A segment has fewer than two points
+    EmptySegment {
+        /// Index of the segment in time order
+        index: usize,
+    },
+
+    /// The first segment doesn't start at the start of the path
+    Start {
+        /// Distance between the two points
+        distance_meters: f64,
+    },
+
+    /// The last segment doesn't end at the end of the path
+    End {
+        /// Distance between the two points
+        distance_meters: f64,
+    },
+
+    /// A segment doesn't start where the previous one ended
+    Gap {
+        /// Index of the segment in time order
+        index: usize,
+
+        /// Distance from the end of the previous segment
+        distance_meters: f64,
+    },
+
+    /// The segments don't add up to the length of the path
+    Length {
+        /// Length of the stored path
+        path_meters: f64,
+
+        /// Total length of the segments
+        segments_meters: f64,
+    },
+}
+
+/// Length of a path in meters
+fn path_length_meters(points: &[PointZ]) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| super::utils::geodesic_distance_meters(&pair[0], &pair[1]))
+        .sum()
+}
+
+/// Compares a path with the segments derived from it, in time order
+///
+/// Distances and lengths that differ by more than `tolerance_meters` are
+///  reported.
+fn segment_discrepancies(
+    path: &[PointZ],
+    segments: &[Vec<PointZ>],
+    tolerance_meters: f64,
+) -> Vec<SegmentDiscrepancy> {
+    if segments.is_empty() {
+        return vec![SegmentDiscrepancy::NoSegments];
+    }
+
+    let mut discrepancies: Vec<SegmentDiscrepancy> = segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| segment.len() < 2)
+        .map(|(index, _)| SegmentDiscrepancy::EmptySegment { index })
+        .collect();
+
+    // Endpoints and gaps are only compared between complete segments
+    let complete: Vec<(usize, &Vec<PointZ>)> = segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| segment.len() >= 2)
+        .collect();
+
+    let distance = super::utils::geodesic_distance_meters;
+    if let (Some(first), Some(last), Some(start), Some(end)) = (
+        complete.first().and_then(|(_, segment)| segment.first()),
+        complete.last().and_then(|(_, segment)| segment.last()),
+        path.first(),
+        path.last(),
+    ) {
+        let distance_meters = distance(start, first);
+        if distance_meters > tolerance_meters {
+            discrepancies.push(SegmentDiscrepancy::Start { distance_meters });
+        }
+
+        let distance_meters = distance(end, last);
+        if distance_meters > tolerance_meters {
+            discrepancies.push(SegmentDiscrepancy::End { distance_meters });
+        }
+    }
+
+    for pair in complete.windows(2) {
+        let ((_, previous), (index, segment)) = (pair[0], pair[1]);
+        let distance_meters = distance(&previous[previous.len() - 1], &segment[0]);
+        if distance_meters > tolerance_meters {
+            discrepancies.push(SegmentDiscrepancy::Gap {
+                index,
+                distance_meters,
+            });
+        }
+    }
+
+    let path_meters = path_length_meters(path);
+    let segments_meters: f64 = segments
+        .iter()
+        .map(|segment| path_length_meters(segment))
+        .sum();
+    if (path_meters - segments_meters).abs() > tolerance_meters {
+        discrepancies.push(SegmentDiscrepancy::Length {
+            path_meters,
+            segments_meters,
+        });
+    }
+
+    discrepancies
+}
+
+/// Checks that the stored segments of a flight still match its path
+///
+/// The path and its segments are written separately and could drift
+///  apart. The segments are joined in time order and their endpoints,
+///  continuity and total length compared to the stored path. Returns the
+///  discrepancies beyond `tolerance_meters`, none if consistent.
+///
+/// This is a data-integrity diagnostic, nothing is modified.
+pub async fn verify_flight_segments(
+    flight_identifier: &str,
+    tolerance_meters: f64,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<SegmentDiscrepancy>, PostgisError> {
+    postgis_debug!("(verify_flight_segments) entry, flight: '{flight_identifier}'.");
+    check_flight_identifier(flight_identifier).map_err(|e| {
+        postgis_error!(
+            "(verify_flight_segments) invalid flight identifier {}: {}",
+            flight_identifier,
+            e
+        );
+        PostgisError::FlightPath(FlightError::Label)
+    })?;
+
+    if !tolerance_meters.is_finite() || tolerance_meters < 0.0 {
+        postgis_error!(
+            "(verify_flight_segments) invalid tolerance: {}",
+            tolerance_meters
+        );
+        return Err(PostgisError::FlightPath(FlightError::Location));
+    }
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(verify_flight_segments) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let path_stmt = format!(
+        r#"SELECT "geom" FROM {table_name}
+            WHERE "flight_identifier" = $1
+                AND "geom" IS NOT NULL
+                AND "deleted_at" IS NULL;"#,
+        table_name = get_flights_table_name(),
+    );
+
+    let segments_stmt = format!(
+        r#"SELECT "geom" FROM {table_name}
+            WHERE "flight_identifier" = $1
+            ORDER BY "time_start";"#,
+        table_name = get_flight_segments_table_name(),
+    );
+
+    // Both reads see the same snapshot
+    let transaction = client
+        .build_transaction()
+        .isolation_level(tokio_postgres::IsolationLevel::RepeatableRead)
+        .read_only(true)
+        .start()
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(verify_flight_segments) could not create transaction: {}",
+                e
+            );
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let path: LineStringT<PointZ> = transaction
+        .query_opt(&path_stmt, &[&flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(verify_flight_segments) could not get path: {}", e);
+            PostgisError::FlightPath(db_error(&e))
+        })?
+        .ok_or_else(|| {
+            postgis_error!(
+                "(verify_flight_segments) no path found for flight '{flight_identifier}'."
+            );
+            PostgisError::FlightPath(FlightError::NotFound)
+        })?
+        .try_get("geom")
+        .map_err(|e| {
+            postgis_error!("(verify_flight_segments) could not parse path: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let segments = transaction
+        .query(&segments_stmt, &[&flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(verify_flight_segments) could not get segments: {}", e);
+            PostgisError::FlightPath(db_error(&e))
+        })?
+        .into_iter()
+        .map(|row| {
+            let geom: Option<LineStringT<PointZ>> = row.try_get("geom")?;
+            Ok(geom.map(|g| g.points).unwrap_or_default())
+        })
+        .collect::<Result<Vec<Vec<PointZ>>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(verify_flight_segments) could not parse segments: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let discrepancies = segment_discrepancies(&path.points, &segments, tolerance_meters);
+    if discrepancies.is_empty() {
+        postgis_debug!(
+            "(verify_flight_segments) {} segments of flight '{}' match its path.",
+            segments.len(),
+            flight_identifier
+        );
+    } else {
+        postgis_warn!(
+            "(verify_flight_segments) segments of flight '{}' don't match its path: {:?}",
+            flight_identifier,
+            discrepancies
+        );
+    }
+
+    Ok(discrepancies)
+}
+
+/// Max length of the reason a flight was cancelled
+pub const MAX_STATUS_REASON_LENGTH: usize = 255;
+
+/// Validates the reason for a flight status change
+///
+/// A missing reason is allowed but logged, as it leaves a gap in the audit
+///  trail.
+fn validate_status_reason(reason: Option<&str>) -> Result<(), FlightError> {
+    let Some(reason) = reason.filter(|r| !r.trim().is_empty()) else {
+        postgis_warn!("(validate_status_reason) no reason provided for the status change.");
+        return Ok(());
+    };
+
+    if reason.chars().count() > MAX_STATUS_REASON_LENGTH {
+        postgis_error!(
+            "(validate_status_reason) reason exceeds {} characters.",
+            MAX_STATUS_REASON_LENGTH
+        );
+        return Err(FlightError::Label);
+    }
+
+    if reason.chars().any(char::is_control) {
+        postgis_error!("(validate_status_reason) reason contains control characters.");
+        return Err(FlightError::Label);
+    }
+
+    Ok(())
+}
+
+/// Checks that a flight, if it exists, belongs to the claimed operator
+///
+/// Used ahead of deletions, restorations and confirmations on behalf of an
+///  operator. A flight without an operator isn't anyone's.
+pub async fn check_flight_operator(
+    flight_identifier: &str,
+    operator_claim: &str,
+) -> Result<(), PostgisError> {
+    postgis_debug!("(check_flight_operator) entry, flight: '{flight_identifier}'.");
+    let stmt = format!(
+        r#"SELECT 1 FROM {table_name}
+        WHERE "flight_identifier" = $1
+            AND "operator_id" IS DISTINCT FROM $2::VARCHAR;"#,
+        table_name = get_flights_table_name()
+    );
+
+    let operator_claim = operator_claim.to_string();
+    match execute_flight_stmt(
+        "check_flight_operator",
+        &stmt,
+        flight_identifier,
+        &[&operator_claim],
+        None,
+    )
+    .await?
+    {
+        0 => Ok(()),
+        _ => {
+            postgis_error!(
+                "(check_flight_operator) flight '{flight_identifier}' doesn't belong to operator {operator_claim}."
+            );
+            Err(PostgisError::FlightPath(FlightError::Operator))
+        }
+    }
+}
+
+/// Soft-deletes (cancels) a flight, it can be restored with [`restore_flight`]
+///
+/// The segments are kept so a restored flight is checked for conflicts
+///  again immediately. The reason is stored with the flight until it's
+///  restored.
+pub async fn delete_flight(
+    flight_identifier: &str,
+    reason: Option<&str>,
+) -> Result<(), PostgisError> {
+    postgis_debug!(flight = flight_identifier; "(delete_flight) entry, flight: '{flight_identifier}'.");
+    validate_status_reason(reason).map_err(PostgisError::FlightPath)?;
+
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "deleted_at" = $3, "status_reason" = $2
+        WHERE "flight_identifier" = $1 AND "deleted_at" IS NULL;"#,
+        table_name = get_flights_table_name()
+    );
+
+    let reason = reason.filter(|r| !r.trim().is_empty()).map(str::to_string);
+    match execute_flight_stmt(
+        "delete_flight",
+        &stmt,
+        flight_identifier,
+        &[&reason, &crate::clock::now()],
+        Some(FlightEventType::Deleted),
+    )
+    .await?
+    {
+        0 => Err(PostgisError::FlightPath(FlightError::NotFound)),
+        _ => Ok(()),
+    }
+}
+
+/// Restores a flight deleted less than `undo_window_secs` ago
+pub async fn restore_flight(
+    flight_identifier: &str,
+    undo_window_secs: u64,
+) -> Result<(), PostgisError> {
+    postgis_debug!("(restore_flight) entry, flight: '{flight_identifier}'.");
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "deleted_at" = NULL, "status_reason" = NULL
+        WHERE "flight_identifier" = $1
+            AND "deleted_at" >= $3 - make_interval(secs => $2::FLOAT8);"#,
+        table_name = get_flights_table_name()
+    );
+
+    match execute_flight_stmt(
+        "restore_flight",
+        &stmt,
+        flight_identifier,
+        &[&(undo_window_secs as f64), &crate::clock::now()],
+        Some(FlightEventType::Restored),
+    )
+    .await?
+    {
+        0 => {
+            postgis_warn!(
+                "(restore_flight) no flight '{flight_identifier}' deleted within the undo window."
+            );
+            Err(PostgisError::FlightPath(FlightError::NotFound))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// SQL expression of the 3D length in meters of a path, measured in the
+///  earth-centered (SRID 4978) frame
+fn flight_length_expression(geom: &str) -> String {
+    format!("ST_3DLength(ST_Transform({geom}, 4978))")
+}
+
+/// Recomputes the stored path length of a flight, deleted or not
+///
+/// For flights stored before the length was recorded or with a stale
+///  length. Returns the new length, `None` for a flight without a path.
+pub async fn recompute_flight_length(
+    flight_identifier: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Option<f64>, PostgisError> {
+    postgis_debug!("(recompute_flight_length) entry, flight: '{flight_identifier}'.");
+    check_flight_identifier(flight_identifier).map_err(|e| {
+        postgis_error!(
+            "(recompute_flight_length) invalid flight identifier {}: {}",
+            flight_identifier,
+            e
+        );
+        PostgisError::FlightPath(FlightError::InvalidString("flight_identifier", e))
+    })?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(recompute_flight_length) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "length_meters" = {length_expression}
+        WHERE "flight_identifier" = $1
+        RETURNING "length_meters";"#,
+        table_name = get_flights_table_name(),
+        length_expression = flight_length_expression(r#""geom""#),
+    );
+
+    let rows = super::query_cached(&client, &stmt, &[&flight_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(recompute_flight_length) could not execute query: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let Some(row) = rows.first() else {
+        postgis_warn!("(recompute_flight_length) no flight '{flight_identifier}'.");
+        return Err(PostgisError::FlightPath(FlightError::NotFound));
+    };
+
+    row.try_get("length_meters").map_err(|e| {
+        postgis_error!("(recompute_flight_length) could not get length: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })
+}
+
+/// Recomputes the stored path length of every flight whose length is
+///  missing or differs, to backfill flights stored before it was recorded
+///
+/// Returns the number of flights updated.
+pub async fn recompute_all_flight_lengths(
+    pool: &deadpool_postgres::Pool,
+) -> Result<u64, PostgisError> {
+    postgis_debug!("(recompute_all_flight_lengths) entry.");
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(recompute_all_flight_lengths) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "length_meters" = {length_expression}
+        WHERE "geom" IS NOT NULL
+            AND "length_meters" IS DISTINCT FROM {length_expression};"#,
+        table_name = get_flights_table_name(),
+        length_expression = flight_length_expression(r#""geom""#),
+    );
+
+    let updated = client.execute(stmt.as_str(), &[]).await.map_err(|e| {
+        postgis_error!(
+            "(recompute_all_flight_lengths) could not execute statement: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    postgis_info!(
+        "(recompute_all_flight_lengths) updated the length of {} flights.",
+        updated
+    );
+    Ok(updated)
+}
+
+/// Confirms a reserved flight before its reservation expires, it's then
+///  planned and no longer removed on expiry
+///
+/// Fails with [`FlightError::NotFound`] if the flight has no unexpired
+///  reservation, including when it was already confirmed.
+pub async fn confirm_flight(flight_identifier: &str) -> Result<(), PostgisError> {
+    postgis_debug!("(confirm_flight) entry, flight: '{flight_identifier}'.");
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "reserved_until" = NULL
+        WHERE "flight_identifier" = $1
+            AND "deleted_at" IS NULL
+            AND "reserved_until" > $2;"#,
+        table_name = get_flights_table_name()
+    );
+
+    let now = crate::clock::now();
+    match execute_flight_stmt(
+        "confirm_flight",
+        &stmt,
+        flight_identifier,
+        &[&now],
+        Some(FlightEventType::Confirmed),
+    )
+    .await?
+    {
+        0 => {
+            postgis_warn!(
+                "(confirm_flight) no unexpired reservation for flight '{flight_identifier}'."
+            );
+            Err(PostgisError::FlightPath(FlightError::NotFound))
+        }
+        _ => {
+            RESERVATIONS_CONFIRMED.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+}
+
+/// Executes a statement on a single flight, returning the number of rows affected
+///
+/// `$1` is the flight identifier and `$2` onwards the extra `params`. If
+///  the flight was affected, the `event_type` event is persisted in the
+///  same transaction and sent once committed.
+async fn execute_flight_stmt(
+    caller: &str,
+    stmt: &str,
+    flight_identifier: &str,
+    params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    event_type: Option<FlightEventType>,
+) -> Result<u64, PostgisError> {
+    check_flight_identifier(flight_identifier).map_err(|e| {
+        postgis_error!(
+            "({caller}) invalid flight identifier {}: {}",
+            flight_identifier,
+            e
+        );
+        PostgisError::FlightPath(FlightError::Label)
+    })?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("({caller}) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "({caller}) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("({caller}) could not create transaction: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    let stmt = transaction.prepare_cached(stmt).await.map_err(|e| {
+        postgis_error!("({caller}) could not prepare cached statement: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    let mut stmt_params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = vec![&flight_identifier];
+    stmt_params.extend_from_slice(params);
+
+    let affected = transaction
+        .execute(&stmt, &stmt_params)
+        .await
+        .map_err(|e| {
+            postgis_error!("({caller}) could not execute statement: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let outbox = match (affected, event_type) {
+        (0, _) | (_, None) => None,
+        (_, Some(event_type)) => {
+            Some(persist_flight_event(&transaction, event_type, flight_identifier).await?)
+        }
+    };
+
+    transaction.commit().await.map_err(|e| {
+        postgis_error!("({caller}) could not commit transaction: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    if let Some(outbox) = outbox {
+        outbox.send(&FLIGHT_EVENTS);
+    }
+
+    Ok(affected)
+}
+
+/// Permanently removes flights deleted more than `retention_secs` ago,
+///  along with their segments and rebind records
+///
+/// Returns the number of flights removed.
+pub async fn purge_flights(retention_secs: u64) -> Result<u64, PostgisError> {
+    postgis_debug!("(purge_flights) entry.");
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(purge_flights) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let retention = retention_secs as f64;
+    let purged = remove_flights(
+        "purge_flights",
+        r#""deleted_at" < $2 - make_interval(secs => $1::FLOAT8)"#,
+        &[&retention, &crate::clock::now()],
+        None,
+        pool,
+    )
+    .await?
+    .len() as u64;
+
+    postgis_debug!("(purge_flights) purged {} flights.", purged);
+    Ok(purged)
+}
+
+/// Permanently removes reserved flights whose reservation expired without
+///  being confirmed, along with their segments
+///
+/// Returns the number of reservations removed.
+pub async fn expire_reservations() -> Result<u64, PostgisError> {
+    postgis_debug!("(expire_reservations) entry.");
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(expire_reservations) could not get psql pool.");
+        return Err(PostgisError::FlightPath(FlightError::Client));
+    };
+
+    let expired = remove_flights(
+        "expire_reservations",
+        r#""reserved_until" <= $1"#,
+        &[&crate::clock::now()],
+        Some(FlightEventType::Expired),
+        pool,
+    )
+    .await?;
+
+    let expired = expired.len() as u64;
+    RESERVATIONS_EXPIRED.fetch_add(expired, Ordering::Relaxed);
+    postgis_debug!("(expire_reservations) removed {} reservations.", expired);
+    Ok(expired)
+}
+
+/// A flight of a simulation run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioFlight {
+    /// The unique identifier of the flight
+    pub flight_identifier: String,
+
+    /// The aircraft flying the flight
+    pub aircraft_identifier: String,
+
+    /// The planned start time of the flight
+    pub time_start: Option<DateTime<Utc>>,
+
+    /// The planned end time of the flight
+    pub time_end: Option<DateTime<Utc>>,
+}
+
+/// Validates the identifier of a simulation run
+fn validate_scenario_id(caller: &str, scenario_id: &str) -> Result<(), PostgisError> {
+    super::utils::check_label(scenario_id).map_err(|e| {
+        postgis_error!("({caller}) invalid scenario_id {}: {}", scenario_id, e);
+        PostgisError::FlightPath(FlightError::Label)
+    })
+}
+
+/// Gets the flights of a simulation run, ordered by start time then
+///  identifier
+///
+/// Deleted flights aren't included. An unknown scenario has no flights.
+pub async fn get_scenario_flights(
+    scenario_id: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<ScenarioFlight>, PostgisError> {
+    postgis_debug!("(get_scenario_flights) entry, scenario: '{scenario_id}'.");
+    validate_scenario_id("get_scenario_flights", scenario_id)?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_scenario_flights) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT "flight_identifier", "aircraft_identifier", "time_start", "time_end"
+            FROM {table_name}
+            WHERE "scenario_id" = $1
+                AND "deleted_at" IS NULL
+            ORDER BY "time_start", "flight_identifier";
+        "#,
+        table_name = get_flights_table_name(),
+    );
+
+    let flights = super::query_cached(&client, &stmt, &[&scenario_id])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_scenario_flights) could not execute query: {}", e);
+            PostgisError::FlightPath(db_error(&e))
+        })?
+        .into_iter()
+        .map(|row| {
+            Ok(ScenarioFlight {
+                flight_identifier: row.try_get("flight_identifier")?,
+                aircraft_identifier: row.try_get("aircraft_identifier")?,
+                time_start: row.try_get("time_start")?,
+                time_end: row.try_get("time_end")?,
+            })
+        })
+        .collect::<Result<Vec<ScenarioFlight>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_scenario_flights) could not get flight data: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    postgis_debug!("(get_scenario_flights) found {} flights.", flights.len());
+    Ok(flights)
+}
+
+/// Permanently removes the flights of a simulation run, deleted or not,
+///  along with their segments, rebind records and members
+///
+/// Returns the number of flights removed, 0 for an unknown scenario.
+pub async fn remove_scenario(
+    scenario_id: &str,
+    pool: &deadpool_postgres::Pool,
+) -> Result<u64, PostgisError> {
+    postgis_debug!("(remove_scenario) entry, scenario: '{scenario_id}'.");
+    validate_scenario_id("remove_scenario", scenario_id)?;
+
+    // Only simulated flights can have a scenario, checked again to be safe
+    let removed = remove_flights(
+        "remove_scenario",
+        r#""scenario_id" = $1 AND "simulated" = TRUE"#,
+        &[&scenario_id],
+        None,
+        pool,
+    )
+    .await?
+    .len() as u64;
+
+    postgis_info!(
+        "(remove_scenario) removed {} flights of scenario '{scenario_id}'.",
+        removed
+    );
+    Ok(removed)
+}
+
+/// Permanently removes the flights matching `condition` in a single
+///  transaction, along with their segments, rebind records, members and
+///  conflict checks
+///
+/// The `event_type` event of each removed flight is persisted in the same
+///  transaction and sent once committed.
+///
+/// Returns the identifiers of the flights removed.
+async fn remove_flights(
+    caller: &str,
+    condition: &str,
+    params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    event_type: Option<FlightEventType>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<String>, PostgisError> {
+    let statements = [
+        format!(
+            r#"DELETE FROM {table_name} WHERE "flight_identifier" IN (
+                SELECT "flight_identifier" FROM {flights_table_name}
+                WHERE {condition}
+            );"#,
+            table_name = get_flight_segments_table_name(),
+            flights_table_name = get_flights_table_name(),
+        ),
+        format!(
+            r#"DELETE FROM {table_name} WHERE "flight_identifier" IN (
+                SELECT "flight_identifier" FROM {flights_table_name}
+                WHERE {condition}
+            );"#,
+            table_name = get_flight_rebinds_table_name(),
+            flights_table_name = get_flights_table_name(),
+        ),
+        format!(
+            r#"DELETE FROM {table_name} WHERE "flight_identifier" IN (
+                SELECT "flight_identifier" FROM {flights_table_name}
+                WHERE {condition}
+            );"#,
+            table_name = get_flight_aircraft_table_name(),
+            flights_table_name = get_flights_table_name(),
+        ),
+        format!(
+            r#"DELETE FROM {table_name} WHERE "flight_identifier" IN (
+                SELECT "flight_identifier" FROM {flights_table_name}
+                WHERE {condition}
+            );"#,
+            table_name = super::conflict_check::get_table_name(),
+            flights_table_name = get_flights_table_name(),
+        ),
+    ];
+
+    let flights_deletion_stmt = format!(
+        r#"DELETE FROM {table_name} WHERE {condition}
+        RETURNING "flight_identifier", "operator_id";"#,
+        table_name = get_flights_table_name(),
+    );
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "({caller}) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::FlightPath(FlightError::Client)
+    })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("({caller}) could not create transaction: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    for stmt in &statements {
+        transaction
+            .execute(stmt.as_str(), params)
+            .await
+            .map_err(|e| {
+                postgis_error!("({caller}) could not execute statement: {}", e);
+                PostgisError::FlightPath(FlightError::DBError)
+            })?;
+    }
+
+    // The flights are deleted last, once nothing references them
+    let removed = transaction
+        .query(flights_deletion_stmt.as_str(), params)
+        .await
+        .map_err(|e| {
+            postgis_error!("({caller}) could not execute statement: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?
+        .iter()
+        .map(|row| {
+            Ok((
+                row.try_get("flight_identifier")?,
+                row.try_get("operator_id")?,
+            ))
+        })
+        .collect::<Result<Vec<(String, Option<String>)>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("({caller}) could not get removed flights: {}", e);
+            PostgisError::FlightPath(FlightError::DBError)
+        })?;
+
+    let flight_identifiers: Vec<String> = removed.iter().map(|(id, _)| id.clone()).collect();
+    let outbox = match event_type {
+        Some(event_type) if !removed.is_empty() => {
+            Some(persist_flight_events(&transaction, event_type, removed).await?)
+        }
+        _ => None,
+    };
+
+    transaction.commit().await.map_err(|e| {
+        postgis_error!("({caller}) could not commit transaction: {}", e);
+        PostgisError::FlightPath(FlightError::DBError)
+    })?;
+
+    if let Some(outbox) = outbox {
+        outbox.send(&FLIGHT_EVENTS);
+    }
+
+    Ok(flight_identifiers)
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Utc};
 
     #[tokio::test]
-    async fn ut_client_failure() {
+    async fn ut_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_client_failure) start");
+
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("test".to_string()),
+            aircraft_identifier: Some("test".to_string()),
+            aircraft_type: AircraftType::Aeroplane as i32,
+            simulated: false,
+            timestamp_start: Some(Utc::now().into()),
+            timestamp_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            path: vec![
+                GrpcPointZ {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.3749819,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+            ],
+            allow_rebind: false,
+            operator_id: None,
+            srid: None,
+            historical: false,
+            dry_run: false,
+            aircraft_identifiers: vec![],
+            tags: vec![],
+            destination_identifier: None,
+            reservation_secs: None,
+            scenario_id: None,
+            geom_wkt: None,
+            geom_ewkb: None,
+        };
+
+        let result = update_flight_path(item, 43_200).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::DBError));
+
+        ut_info!("(ut_client_failure) success");
+    }
+
+    #[tokio::test]
+    async fn ut_invalid_operator_id() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_invalid_operator_id) start");
+
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("test".to_string()),
+            aircraft_identifier: Some("test".to_string()),
+            aircraft_type: AircraftType::Aeroplane as i32,
+            simulated: false,
+            timestamp_start: Some(Utc::now().into()),
+            timestamp_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            path: vec![],
+            allow_rebind: false,
+            operator_id: Some("Operator;".to_string()),
+            srid: None,
+            historical: false,
+            dry_run: false,
+            aircraft_identifiers: vec![],
+            tags: vec![],
+            destination_identifier: None,
+            reservation_secs: None,
+            scenario_id: None,
+            geom_wkt: None,
+            geom_ewkb: None,
+        };
+
+        let result = update_flight_path(item, 43_200).await.unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::FlightPath(FlightError::InvalidString(
+                "operator_id",
+                StringError::Character {
+                    character: ';',
+                    index: 8
+                }
+            ))
+        );
+
+        let request = GetFlightsRequest {
+            window_min_x: 4.915,
+            window_min_y: 52.374,
+            window_max_x: 4.917,
+            window_max_y: 52.376,
+            time_start: Some(Utc::now().into()),
+            time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            operator_id: Some("'Operator'".to_string()),
+            order_by: FlightOrder::FlightIdentifier as i32,
+            tag_filter: None,
+            velocity_samples: 0,
+            skeleton_only: false,
+            path_alignment: false,
+        };
+
+        let result = get_flights(request.clone()).await.unwrap_err();
+        assert_eq!(result, FlightError::Label);
+
+        // Valid filter reaches the database
+        let request = GetFlightsRequest {
+            operator_id: Some("Operator".to_string()),
+            ..request
+        };
+
+        let result = get_flights(request).await.unwrap_err();
+        assert_eq!(result, FlightError::Client);
+
+        ut_info!("(ut_invalid_operator_id) success");
+    }
+
+    #[test]
+    fn ut_validate_flight_schedule() {
+        let now = Utc::now();
+        let hour = Duration::try_hours(1).unwrap();
+        let max_duration_secs = 12 * 3600;
+
+        assert!(validate_flight_schedule(now, now + hour, now, max_duration_secs, false).is_ok());
+
+        // End before or at start
+        assert_eq!(
+            validate_flight_schedule(now + hour, now, now, max_duration_secs, false).unwrap_err(),
+            FlightError::Time
+        );
+        assert_eq!(
+            validate_flight_schedule(now, now, now, max_duration_secs, false).unwrap_err(),
+            FlightError::Time
+        );
+
+        // Longer than the max duration
+        let days = Duration::try_days(180).unwrap();
+        assert_eq!(
+            validate_flight_schedule(now, now + days, now, max_duration_secs, false).unwrap_err(),
+            FlightError::Time
+        );
+        assert_eq!(
+            validate_flight_schedule(now, now + hour * 13, now, max_duration_secs, false)
+                .unwrap_err(),
+            FlightError::Time
+        );
+        assert!(
+            validate_flight_schedule(now, now + hour * 12, now, max_duration_secs, false).is_ok()
+        );
+
+        // No limit
+        assert!(validate_flight_schedule(now, now + days, now, 0, false).is_ok());
+
+        // Past flights are only warned about
+        assert!(validate_flight_schedule(
+            now - hour * 2,
+            now - hour,
+            now,
+            max_duration_secs,
+            false
+        )
+        .is_ok());
+        assert!(
+            validate_flight_schedule(now - hour * 2, now - hour, now, max_duration_secs, true)
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_update_flight_path_invalid_time() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_update_flight_path_invalid_time) start");
+
+        let time_start = Utc::now();
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("test".to_string()),
+            aircraft_identifier: Some("test".to_string()),
+            aircraft_type: AircraftType::Aeroplane as i32,
+            simulated: false,
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start - Duration::try_hours(1).unwrap()).into()),
+            path: vec![],
+            allow_rebind: false,
+            operator_id: None,
+            srid: None,
+            historical: false,
+            dry_run: false,
+            aircraft_identifiers: vec![],
+            tags: vec![],
+            destination_identifier: None,
+            reservation_secs: None,
+            scenario_id: None,
+            geom_wkt: None,
+            geom_ewkb: None,
+        };
+
+        let result = update_flight_path(item.clone(), 43_200).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Time));
+
+        let item = UpdateFlightPathRequest {
+            timestamp_end: Some((time_start + Duration::try_days(180).unwrap()).into()),
+            ..item
+        };
+
+        let result = update_flight_path(item.clone(), 43_200).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Time));
+
+        // Invalid reservations are rejected before the schedule is checked
+        for reservation_secs in [0, MAX_RESERVATION_SECS + 1] {
+            let item = UpdateFlightPathRequest {
+                timestamp_end: Some((time_start + Duration::try_hours(1).unwrap()).into()),
+                reservation_secs: Some(reservation_secs),
+                ..item.clone()
+            };
+
+            let result = update_flight_path(item, 43_200).await.unwrap_err();
+            assert_eq!(result, PostgisError::FlightPath(FlightError::Time));
+        }
+
+        ut_info!("(ut_update_flight_path_invalid_time) success");
+    }
+
+    #[tokio::test]
+    async fn ut_update_flight_path_empty_path() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_update_flight_path_empty_path) start");
+
+        let time_start = Utc::now();
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("test".to_string()),
+            aircraft_identifier: Some("test".to_string()),
+            aircraft_type: AircraftType::Aeroplane as i32,
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_hours(1).unwrap()).into()),
+            ..Default::default()
+        };
+
+        // Rejected before reaching the database
+        let result = update_flight_path(item.clone(), 43_200).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Location));
+
+        let item = UpdateFlightPathRequest {
+            path: vec![GrpcPointZ {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            }],
+            ..item
+        };
+        let result = update_flight_path(item, 43_200).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Location));
+
+        ut_info!("(ut_update_flight_path_empty_path) success");
+    }
+
+    #[test]
+    fn ut_resolve_path_geometry() {
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("test".to_string()),
+            geom_wkt: Some(
+                "LINESTRING Z (4.9160036 52.3745905 100, 4.9160036 52.3749819 120)".to_string(),
+            ),
+            ..Default::default()
+        };
+
+        let resolved = resolve_path_geometry(item.clone()).unwrap();
+        assert_eq!(
+            resolved.path,
+            vec![
+                GrpcPointZ {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.3749819,
+                    longitude: 4.9160036,
+                    altitude_meters: 120.0,
+                },
+            ]
+        );
+        assert_eq!(resolved.geom_wkt, None);
+
+        // Without a geometry the request is untouched
+        let plain = UpdateFlightPathRequest {
+            geom_wkt: None,
+            ..resolved.clone()
+        };
+        assert_eq!(resolve_path_geometry(plain.clone()).unwrap(), plain);
+
+        let conflicts = [
+            UpdateFlightPathRequest {
+                path: resolved.path.clone(),
+                ..item.clone()
+            },
+            UpdateFlightPathRequest {
+                srid: Some(28992),
+                ..item.clone()
+            },
+            UpdateFlightPathRequest {
+                geom_ewkb: Some(vec![]),
+                ..item.clone()
+            },
+        ];
+
+        for conflict in conflicts {
+            assert_eq!(
+                resolve_path_geometry(conflict).unwrap_err(),
+                FlightError::Geometry(GeometryInputError::Conflict)
+            );
+        }
+
+        // Same SRID as the geometry
+        let item = UpdateFlightPathRequest {
+            srid: Some(DEFAULT_SRID),
+            ..item
+        };
+        assert!(resolve_path_geometry(item).is_ok());
+    }
+
+    #[test]
+    fn ut_path_unchanged() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_hours(1).unwrap();
+        let points = vec![
+            PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID)),
+            PointZ::new(4.9160036, 52.3749819, 120.0, Some(DEFAULT_SRID)),
+        ];
+
+        let stored = Some(StoredPath {
+            aircraft_identifier: "aircraft".to_string(),
+            operator_id: None,
+            points: points.clone(),
+            time_start: Some(time_start),
+            time_end: Some(time_end),
+            deleted_at: None,
+        });
+
+        // Metadata-only update
+        assert!(path_unchanged(&stored, &points, &time_start, &time_end));
+
+        // New flight
+        assert!(!path_unchanged(&None, &points, &time_start, &time_end));
+
+        // Different altitude
+        let mut changed = points.clone();
+        changed[1].z = 130.0;
+        assert!(!path_unchanged(&stored, &changed, &time_start, &time_end));
+
+        // Different length
+        assert!(!path_unchanged(
+            &stored,
+            &points[..1],
+            &time_start,
+            &time_end
+        ));
+
+        // Rescheduled flight needs new segment timestamps
+        let later = time_end + Duration::try_minutes(5).unwrap();
+        assert!(!path_unchanged(&stored, &points, &time_start, &later));
+    }
+
+    #[test]
+    fn ut_check_not_deleted() {
+        let mut stored = StoredPath {
+            aircraft_identifier: "aircraft".to_string(),
+            operator_id: None,
+            points: vec![],
+            time_start: None,
+            time_end: None,
+            deleted_at: None,
+        };
+
+        check_not_deleted(&None).unwrap();
+        check_not_deleted(&Some(stored.clone())).unwrap();
+
+        // Re-creating a deleted flight is blocked
+        stored.deleted_at = Some(Utc::now());
+        let result = check_not_deleted(&Some(stored)).unwrap_err();
+        assert_eq!(result, FlightError::Deleted);
+    }
+
+    #[test]
+    fn ut_validate_status_reason() {
+        assert!(validate_status_reason(Some("Weather")).is_ok());
+        assert!(validate_status_reason(Some(&"a".repeat(MAX_STATUS_REASON_LENGTH))).is_ok());
+
+        // Allowed, but logged
+        assert!(validate_status_reason(None).is_ok());
+        assert!(validate_status_reason(Some(" ")).is_ok());
+
+        assert_eq!(
+            validate_status_reason(Some(&"a".repeat(MAX_STATUS_REASON_LENGTH + 1))).unwrap_err(),
+            FlightError::Label
+        );
+        assert_eq!(
+            validate_status_reason(Some("Weather\n")).unwrap_err(),
+            FlightError::Label
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_update_flight_path_long_identifier() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_update_flight_path_long_identifier) start");
+
+        let time_start = Utc::now();
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("F".repeat(21)),
+            aircraft_identifier: Some("A".repeat(20)),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_hours(1).unwrap()).into()),
+            path: vec![
+                GrpcPointZ {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.3749819,
+                    longitude: 4.9156925,
+                    altitude_meters: 120.0,
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Rejected before reaching the database (no pool in unit tests)
+        let result = update_flight_path(item.clone(), 43_200).await.unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::FlightPath(FlightError::InvalidString(
+                "flight_identifier",
+                StringError::TooLong {
+                    length: 21,
+                    max: 20
+                }
+            ))
+        );
+
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("F".repeat(20)),
+            aircraft_identifier: Some("A".repeat(21)),
+            ..item
+        };
+
+        let result = update_flight_path(item.clone(), 43_200).await.unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::FlightPath(FlightError::InvalidString(
+                "aircraft_identifier",
+                StringError::TooLong {
+                    length: 21,
+                    max: 20
+                }
+            ))
+        );
+
+        // Identifiers at the column length reach the database
+        let item = UpdateFlightPathRequest {
+            aircraft_identifier: Some("A".repeat(20)),
+            ..item
+        };
+
+        let result = update_flight_path(item, 43_200).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::DBError));
+
+        ut_info!("(ut_update_flight_path_long_identifier) success");
+    }
+
+    #[test]
+    fn ut_validate_flight_path_scenario() {
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("test".to_string()),
+            simulated: true,
+            scenario_id: Some("scenario-1".to_string()),
+            ..Default::default()
+        };
+        validate_flight_path(&item).unwrap();
+
+        let invalid = UpdateFlightPathRequest {
+            scenario_id: Some("scenario;".to_string()),
+            ..item.clone()
+        };
+        assert_eq!(
+            validate_flight_path(&invalid).unwrap_err(),
+            PostgisError::FlightPath(FlightError::InvalidString(
+                "scenario_id",
+                StringError::Character {
+                    character: ';',
+                    index: 8
+                }
+            ))
+        );
+
+        // Only simulated flights belong to a scenario
+        let real = UpdateFlightPathRequest {
+            simulated: false,
+            ..item
+        };
+        assert_eq!(
+            validate_flight_path(&real).unwrap_err(),
+            PostgisError::FlightPath(FlightError::Label)
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_soft_delete_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_soft_delete_client_failure) start");
+
+        let result = delete_flight("flight;", None).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        let result = restore_flight("flight;", 60).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        let result = delete_flight("flight", Some("Weather")).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        let result = restore_flight("flight", 60).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        let result = purge_flights(60).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        let result = confirm_flight("flight;").await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        let result = confirm_flight("flight").await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        let result = expire_reservations().await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        ut_info!("(ut_soft_delete_client_failure) success");
+    }
+
+    #[test]
+    fn ut_check_rebind() {
+        let stored = Some(StoredPath {
+            aircraft_identifier: "aircraft".to_string(),
+            operator_id: None,
+            points: vec![],
+            time_start: None,
+            time_end: None,
+            deleted_at: None,
+        });
+
+        // New flight
+        let result = check_rebind(&None, &Some("other".to_string()), false).unwrap();
+        assert!(!result);
+
+        // Same aircraft is a no-op
+        let result = check_rebind(&stored, &Some("aircraft".to_string()), false).unwrap();
+        assert!(!result);
+
+        // Different aircraft rejected
+        let result = check_rebind(&stored, &Some("other".to_string()), false).unwrap_err();
+        assert_eq!(result, FlightError::AircraftMismatch);
+
+        let result = check_rebind(&stored, &None, false).unwrap_err();
+        assert_eq!(result, FlightError::AircraftMismatch);
+
+        // Different aircraft allowed with allow_rebind
+        let result = check_rebind(&stored, &Some("other".to_string()), true).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn ut_check_operator() {
+        let owned = Some(StoredPath {
+            aircraft_identifier: "aircraft".to_string(),
+            operator_id: Some("operator".to_string()),
+            points: vec![],
+            time_start: None,
+            time_end: None,
+            deleted_at: None,
+        });
+        let unowned = owned.clone().map(|stored| StoredPath {
+            operator_id: None,
+            ..stored
+        });
+        let other = Some("other".to_string());
+
+        // Without a claim anything goes
+        check_operator(&owned, &other, None).unwrap();
+        check_operator(&unowned, &None, None).unwrap();
+
+        // New flights and the claimant's own flights
+        check_operator(&None, &None, Some("operator")).unwrap();
+        check_operator(&owned, &None, Some("operator")).unwrap();
+        check_operator(&owned, &Some("operator".to_string()), Some("operator")).unwrap();
+
+        // Flights of others, unowned flights and attributions to others
+        let result = check_operator(&owned, &None, Some("other")).unwrap_err();
+        assert_eq!(result, FlightError::Operator);
+
+        let result = check_operator(&unowned, &None, Some("operator")).unwrap_err();
+        assert_eq!(result, FlightError::Operator);
+
+        let result = check_operator(&None, &other, Some("operator")).unwrap_err();
+        assert_eq!(result, FlightError::Operator);
+    }
+
+    fn segmentize_request() -> SegmentizePathRequest {
+        SegmentizePathRequest {
+            path: vec![
+                GrpcPointZ {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.3749819,
+                    longitude: 4.9156925,
+                    altitude_meters: 120.0,
+                },
+            ],
+            time_start: Some(Utc::now().into()),
+            time_end: Some((Utc::now() + Duration::try_minutes(1).unwrap()).into()),
+            segment_length_meters: MAX_FLIGHT_SEGMENT_LENGTH_METERS,
+        }
+    }
+
+    #[test]
+    fn ut_unique_segment_starts() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_seconds(10).unwrap();
+        let point = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let segment = |time_start, time_end| Segment {
+            geom: LineStringT {
+                points: vec![point, point],
+                srid: Some(DEFAULT_SRID),
+            },
+            time_start,
+            time_end,
+        };
+
+        let segments = vec![
+            segment(time_start, time_start),
+            segment(time_start, time_end),
+        ];
+        assert!(!unique_segment_starts(&segments));
+
+        let segments = crate::postgis::utils::merge_zero_duration_segments(segments);
+        assert!(unique_segment_starts(&segments));
+        assert!(unique_segment_starts(&[]));
+    }
+
+    #[test]
+    fn ut_validate_segmentize_request() {
+        let (points, _, _) = validate_segmentize_request(segmentize_request()).unwrap();
+        assert_eq!(points.len(), 2);
+
+        let mut request = segmentize_request();
+        request.segment_length_meters = 0.0;
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Segments);
+
+        let mut request = segmentize_request();
+        request.segment_length_meters = f32::NAN;
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Segments);
+
+        let mut request = segmentize_request();
+        request.time_end = request.time_start.clone();
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Time);
+
+        let mut request = segmentize_request();
+        request.path = vec![];
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Location);
+
+        // Degenerate path
+        let mut request = segmentize_request();
+        request.path[1] = request.path[0].clone();
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Location);
+
+        // Non-finite coordinates
+        let mut request = segmentize_request();
+        request.path[1].latitude = f64::NAN;
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Location);
+
+        let mut request = segmentize_request();
+        request.path[1].altitude_meters = f32::INFINITY;
+        let result = validate_segmentize_request(request).unwrap_err();
+        assert_eq!(result, FlightError::Location);
+    }
+
+    #[test]
+    fn ut_path_segment_from() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_seconds(2).unwrap();
+        let start = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let end = PointZ::new(4.9156925, 52.3749819, 120.0, Some(DEFAULT_SRID));
+        let segment = Segment {
+            geom: LineStringT {
+                points: vec![start, end],
+                srid: Some(DEFAULT_SRID),
+            },
+            time_start,
+            time_end,
+        };
+
+        let result = path_segment_from(segment).unwrap();
+        assert_eq!(result.start, Some(GrpcPointZ::from(start)));
+        assert_eq!(result.end, Some(GrpcPointZ::from(end)));
+        assert_eq!(result.time_start, Some(time_start.into()));
+        assert_eq!(result.time_end, Some(time_end.into()));
+
+        let segment = Segment {
+            geom: LineStringT {
+                points: vec![],
+                srid: Some(DEFAULT_SRID),
+            },
+            time_start,
+            time_end,
+        };
+        let result = path_segment_from(segment).unwrap_err();
+        assert_eq!(result, FlightError::Segments);
+    }
+
+    #[tokio::test]
+    async fn ut_segmentize_path_client_failure() {
         crate::get_log_handle().await;
-        ut_info!("(ut_client_failure) start");
+        ut_info!("(ut_segmentize_path_client_failure) start");
+
+        let result = segmentize_path(segmentize_request()).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Segments));
+
+        ut_info!("(ut_segmentize_path_client_failure) success");
+    }
+
+    #[test]
+    fn ut_aircraft_row_apply() {
+        let flight = Flight {
+            session_id: Some("flight".to_string()),
+            aircraft_id: Some("aircraft".to_string()),
+            simulated: false,
+            positions: vec![],
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            state: None,
+            velocity_samples: vec![],
+            time_start: None,
+            time_end: None,
+            path_alignment: None,
+            conflict_check: None,
+        };
+
+        // Identified aircraft without telemetry is not an error
+        let row = AircraftRow {
+            identifier: Some("aircraft".to_string()),
+            session_id: Some("flight".to_string()),
+            geom: None,
+            velocity_horizontal_ground_mps: None,
+            velocity_vertical_mps: None,
+            track_angle_degrees: None,
+            last_position_update: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+            status: OperationalStatus::Undeclared,
+        };
+
+        let mut result = flight.clone();
+        row.clone().apply(&mut result);
+        assert!(result.positions.is_empty());
+        assert!(result.state.is_none());
+        assert_eq!(result.aircraft_id, Some("aircraft".to_string()));
+
+        // Aircraft with position
+        let now = Utc::now();
+        let row = AircraftRow {
+            geom: Some(PointZ::new(
+                4.9160036,
+                52.3745905,
+                100.0,
+                Some(DEFAULT_SRID),
+            )),
+            velocity_horizontal_ground_mps: Some(10.0),
+            last_position_update: Some(now),
+            horizontal_accuracy_meters: Some(7.5),
+            ..row
+        };
+
+        let mut result = flight.clone();
+        row.apply(&mut result);
+        assert_eq!(result.positions.len(), 1);
+        let state = result.state.unwrap();
+        assert_eq!(state.ground_speed_mps, 10.0);
+        assert_eq!(state.vertical_speed_mps, 0.0);
+        assert_eq!(state.timestamp, Some(now.into()));
+        assert_eq!(state.horizontal_accuracy_meters, Some(7.5));
+        assert_eq!(state.vertical_accuracy_meters, None);
+    }
+
+    #[tokio::test]
+    async fn ut_flight_progress_invalid_identifier() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_flight_progress_invalid_identifier) start");
+
+        let point = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let result = flight_progress("flight;", point).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        // Valid identifier without a database is a client error, not NotFound
+        let result = flight_progress("flight", point).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        ut_info!("(ut_flight_progress_invalid_identifier) success");
+    }
+
+    /// Points every 0.001 degrees of longitude, about 68 m at this latitude
+    fn line(from: usize, to: usize) -> Vec<PointZ> {
+        (from..=to)
+            .map(|i| PointZ::new(4.9 + i as f64 * 0.001, 52.37, 100.0, Some(DEFAULT_SRID)))
+            .collect()
+    }
+
+    #[test]
+    fn ut_segment_discrepancies() {
+        let path = line(0, 4);
+        let segments: Vec<Vec<PointZ>> = (0..4).map(|i| line(i, i + 1)).collect();
+        assert!(segment_discrepancies(&path, &segments, SEGMENT_TOLERANCE_METERS).is_empty());
+
+        assert_eq!(
+            segment_discrepancies(&path, &[], SEGMENT_TOLERANCE_METERS),
+            vec![SegmentDiscrepancy::NoSegments]
+        );
+
+        // A missing segment leaves a gap and a shorter total length
+        let mut missing = segments.clone();
+        missing.remove(2);
+        let discrepancies = segment_discrepancies(&path, &missing, SEGMENT_TOLERANCE_METERS);
+        assert_eq!(discrepancies.len(), 2);
+        assert!(matches!(
+            discrepancies[0],
+            SegmentDiscrepancy::Gap { index: 2, distance_meters } if distance_meters > 60.0
+        ));
+        assert!(matches!(
+            discrepancies[1],
+            SegmentDiscrepancy::Length { path_meters, segments_meters }
+                if path_meters > segments_meters
+        ));
+
+        // Truncated ends
+        let discrepancies = segment_discrepancies(&path, &segments[1..3], SEGMENT_TOLERANCE_METERS);
+        assert!(matches!(discrepancies[0], SegmentDiscrepancy::Start { .. }));
+        assert!(matches!(discrepancies[1], SegmentDiscrepancy::End { .. }));
+
+        // Empty segments are reported, the others still compared
+        let mut empty = segments.clone();
+        empty[1] = vec![];
+        let discrepancies = segment_discrepancies(&path, &empty, SEGMENT_TOLERANCE_METERS);
+        assert_eq!(
+            discrepancies[0],
+            SegmentDiscrepancy::EmptySegment { index: 1 }
+        );
+        assert!(matches!(
+            discrepancies[1],
+            SegmentDiscrepancy::Gap { index: 2, .. }
+        ));
+
+        // Within the tolerance
+        let mut shifted = segments;
+        shifted[3][1].z += 0.5;
+        assert!(segment_discrepancies(&path, &shifted, SEGMENT_TOLERANCE_METERS).is_empty());
+    }
+
+    #[tokio::test]
+    async fn ut_get_flight_segments_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_flight_segments_invalid) start");
+
+        let request = GetFlightSegmentsRequest {
+            flight_identifier: "flight".to_string(),
+            limit: None,
+            offset: Some(10),
+        };
+
+        assert_eq!(
+            validate_flight_segments_request(&request).unwrap(),
+            (MAX_FLIGHT_SEGMENTS_LIMIT as i64, 10)
+        );
+
+        let mut invalid = request.clone();
+        invalid.flight_identifier = "flight;".to_string();
+        let result = get_flight_segments(invalid).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        let mut invalid = request.clone();
+        invalid.limit = Some(0);
+        let result = get_flight_segments(invalid).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Limit));
+
+        let mut invalid = request.clone();
+        invalid.limit = Some(MAX_FLIGHT_SEGMENTS_LIMIT + 1);
+        let result = get_flight_segments(invalid).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Limit));
+
+        // Valid request without a database is a client error, not NotFound
+        let result = get_flight_segments(request).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        ut_info!("(ut_get_flight_segments_invalid) success");
+    }
+
+    #[test]
+    fn ut_flight_conflict_to_grpc() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_seconds(5).unwrap();
+        let start = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let end = PointZ::new(4.9156925, 52.3749819, 120.0, Some(DEFAULT_SRID));
+        let conflict = FlightConflict {
+            flight_identifier: "flight".to_string(),
+            aircraft_identifier: "aircraft".to_string(),
+            segment: LineStringT {
+                points: vec![start, end],
+                srid: Some(DEFAULT_SRID),
+            },
+            closest_point: end,
+            distance_meters: 3.5,
+            time_start: Some(time_start),
+            time_end: Some(time_end),
+        };
+
+        let result = GrpcFlightConflict::from(conflict);
+        assert_eq!(result.flight_identifier, "flight");
+        assert_eq!(result.aircraft_identifier, "aircraft");
+        assert_eq!(
+            result.segment,
+            vec![GrpcPointZ::from(start), GrpcPointZ::from(end)]
+        );
+        assert_eq!(result.closest_point, Some(GrpcPointZ::from(end)));
+        assert_eq!(result.distance_meters, 3.5);
+        assert_eq!(result.time_start, Some(time_start.into()));
+        assert_eq!(result.time_end, Some(time_end.into()));
+    }
+
+    #[tokio::test]
+    async fn ut_get_flight_conflicts_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_flight_conflicts_invalid) start");
+
+        let valid = segmentize_request();
+        let request = GetFlightConflictsRequest {
+            path: valid.path,
+            time_start: valid.time_start,
+            time_end: valid.time_end,
+            distance_meters: 0.0,
+            tag_filter: None,
+        };
+
+        let result = get_flight_conflicts(request.clone()).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Location));
+
+        let mut request = GetFlightConflictsRequest {
+            distance_meters: 10.0,
+            ..request
+        };
+        request.time_end = request.time_start.clone();
+        let result = get_flight_conflicts(request).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Time));
+
+        ut_info!("(ut_get_flight_conflicts_invalid) success");
+    }
 
+    #[test]
+    fn ut_validate_flight_comprehensive_static() {
+        // Violates several rules at once, all of them are reported
         let item = UpdateFlightPathRequest {
-            flight_identifier: Some("test".to_string()),
-            aircraft_identifier: Some("test".to_string()),
-            aircraft_type: AircraftType::Aeroplane as i32,
+            flight_identifier: Some("flight;".to_string()),
+            aircraft_identifier: None,
+            aircraft_type: -1,
             simulated: false,
-            timestamp_start: Some(Utc::now().into()),
-            timestamp_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
-            path: vec![],
+            timestamp_start: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            timestamp_end: Some(Utc::now().into()),
+            path: vec![GrpcPointZ {
+                latitude: 91.0,
+                longitude: 4.91,
+                altitude_meters: 100.0,
+            }],
+            allow_rebind: false,
+            operator_id: Some("Operator;".to_string()),
+            srid: Some(0),
+            historical: false,
+            dry_run: false,
+            aircraft_identifiers: vec![],
+            tags: vec![],
+            destination_identifier: None,
+            reservation_secs: None,
+            scenario_id: None,
+            geom_wkt: None,
+            geom_ewkb: None,
         };
 
-        let result = update_flight_path(item).await.unwrap_err();
-        assert_eq!(result, PostgisError::FlightPath(FlightError::DBError));
+        let (issues, usable) = validate_flight_static(&item);
+        assert!(usable.is_none());
+        assert_eq!(
+            issues,
+            vec![
+                FlightIssue::FlightIdentifier,
+                FlightIssue::AircraftIdentifier,
+                FlightIssue::OperatorIdentifier,
+                FlightIssue::AircraftType,
+                FlightIssue::Time,
+                FlightIssue::Srid,
+                FlightIssue::Geometry,
+            ]
+        );
 
-        ut_info!("(ut_client_failure) success");
+        // Valid flight, usable for the airspace checks
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_hours(1).unwrap();
+        let item = UpdateFlightPathRequest {
+            flight_identifier: Some("flight".to_string()),
+            aircraft_identifier: Some("aircraft".to_string()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            path: vec![
+                GrpcPointZ {
+                    latitude: 52.37,
+                    longitude: 4.91,
+                    altitude_meters: 100.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.38,
+                    longitude: 4.92,
+                    altitude_meters: 2_000.0,
+                },
+                GrpcPointZ {
+                    latitude: 52.39,
+                    longitude: 4.93,
+                    altitude_meters: -10.0,
+                },
+            ],
+            operator_id: None,
+            srid: None,
+            historical: false,
+            dry_run: false,
+            aircraft_identifiers: vec![],
+            ..item
+        };
+
+        let (issues, usable) = validate_flight_static(&item);
+        assert!(issues.is_empty());
+        let (points, start, end) = usable.unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!((start, end), (time_start, time_end));
+
+        // Out of the altitude corridor
+        assert_eq!(
+            altitude_issues(&points),
+            vec![
+                FlightIssue::Altitude {
+                    index: 1,
+                    altitude_meters: 2_000.0
+                },
+                FlightIssue::Altitude {
+                    index: 2,
+                    altitude_meters: -10.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ut_order_by_clause() {
+        for order_by in [
+            FlightOrder::FlightIdentifier,
+            FlightOrder::TimeStart,
+            FlightOrder::Distance,
+        ] {
+            let clause = order_by_clause(order_by);
+            assert!(clause.ends_with(
+                r#""flight_identifier" ASC NULLS LAST, "aircraft_identifier" ASC NULLS LAST"#
+            ));
+        }
+
+        assert!(order_by_clause(FlightOrder::TimeStart).starts_with(r#""time_start""#));
+        assert!(order_by_clause(FlightOrder::Distance).starts_with(r#""distance_meters""#));
+    }
+
+    #[test]
+    fn ut_expand_flights_deterministic() {
+        use prost::Message;
+
+        let flights: Vec<Flight> = ["b", "a", "c"]
+            .iter()
+            .map(|id| Flight {
+                session_id: Some(id.to_string()),
+                aircraft_id: Some(format!("aircraft_{id}")),
+                simulated: false,
+                positions: vec![],
+                aircraft_type: AircraftType::Rotorcraft as i32,
+                state: None,
+                velocity_samples: vec![],
+                time_start: None,
+                time_end: None,
+                path_alignment: None,
+                conflict_check: None,
+            })
+            .collect();
+
+        let expand = || {
+            flights
+                .iter()
+                .flat_map(|flight| {
+                    let rows = vec![GrpcPointZ {
+                        latitude: 52.37,
+                        longitude: 4.91,
+                        altitude_meters: 100.0,
+                    }];
+
+                    expand_flight(flight, rows, |row, f| {
+                        f.positions.push(TimePosition {
+                            position: Some(row),
+                            timestamp: None,
+                            bucket_seconds: 0,
+                        });
+
+                        Ok::<(), String>(())
+                    })
+                })
+                .map(|flight| flight.encode_to_vec())
+                .collect::<Vec<Vec<u8>>>()
+        };
+
+        // Expansion keeps the query order and is byte-identical between calls
+        let first = expand();
+        assert_eq!(first, expand());
+
+        let order = first
+            .iter()
+            .map(|bytes| {
+                Flight::decode(bytes.as_slice())
+                    .unwrap()
+                    .session_id
+                    .unwrap()
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(order, vec!["b", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn ut_get_flights_invalid_order() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_flights_invalid_order) start");
+
+        let request = GetFlightsRequest {
+            window_min_x: 4.915,
+            window_min_y: 52.374,
+            window_max_x: 4.917,
+            window_max_y: 52.376,
+            time_start: Some(Utc::now().into()),
+            time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            operator_id: None,
+            order_by: -1,
+            tag_filter: None,
+            velocity_samples: 0,
+            skeleton_only: false,
+            path_alignment: false,
+        };
+
+        let result = get_flights(request).await.unwrap_err();
+        assert_eq!(result, FlightError::Label);
+
+        ut_info!("(ut_get_flights_invalid_order) success");
+    }
+
+    #[tokio::test]
+    async fn ut_get_flights_invalid_velocity_samples() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_flights_invalid_velocity_samples) start");
+
+        let request = GetFlightsRequest {
+            window_min_x: 4.915,
+            window_min_y: 52.374,
+            window_max_x: 4.917,
+            window_max_y: 52.376,
+            time_start: Some(Utc::now().into()),
+            time_end: Some((Utc::now() + Duration::try_hours(1).unwrap()).into()),
+            velocity_samples: MAX_VELOCITY_SAMPLES + 1,
+            ..Default::default()
+        };
+
+        let result = get_flights(request.clone()).await.unwrap_err();
+        assert_eq!(result, FlightError::Limit);
+
+        // The maximum reaches the database
+        let request = GetFlightsRequest {
+            velocity_samples: MAX_VELOCITY_SAMPLES,
+            ..request
+        };
+
+        let result = get_flights(request).await.unwrap_err();
+        assert_eq!(result, FlightError::Client);
+
+        ut_info!("(ut_get_flights_invalid_velocity_samples) success");
+    }
+
+    #[test]
+    fn ut_deadline_near() {
+        let now = std::time::Instant::now();
+        let margin = std::time::Duration::from_millis(MIN_ENRICHMENT_REMAINING_MS);
+
+        assert!(!deadline_near(None, now));
+        assert!(!deadline_near(Some(now + margin), now));
+        assert!(deadline_near(Some(now + margin / 2), now));
+        assert!(deadline_near(Some(now), now));
+
+        // Already passed
+        assert!(deadline_near(Some(now), now + margin));
+    }
+
+    #[test]
+    fn ut_preferred_aircraft_key() {
+        let own = Some("N12345".to_string());
+        let mut identifiers = vec![
+            None,
+            Some("N99999".to_string()),
+            own.clone(),
+            Some("N00001".to_string()),
+        ];
+
+        identifiers.sort_by_key(|identifier| preferred_aircraft_key(identifier, &own));
+        assert_eq!(
+            identifiers,
+            vec![
+                own.clone(),
+                Some("N00001".to_string()),
+                Some("N99999".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn ut_attach_velocity_samples() {
+        let sample = |ground_speed_mps: f32| VelocitySample {
+            timestamp: Some(Utc::now().into()),
+            ground_speed_mps,
+            vertical_speed_mps: 0.0,
+            track_angle_degrees: 90.0,
+        };
+
+        let flight = |aircraft_id: Option<&str>| Flight {
+            session_id: Some("flight".to_string()),
+            aircraft_id: aircraft_id.map(str::to_string),
+            simulated: false,
+            positions: vec![],
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            state: None,
+            velocity_samples: vec![],
+            time_start: None,
+            time_end: None,
+            path_alignment: None,
+            conflict_check: None,
+        };
+
+        let mut flights = vec![
+            flight(Some("lead")),
+            flight(Some("member")),
+            flight(Some("silent")),
+            flight(None),
+        ];
+
+        let samples = HashMap::from([
+            ("lead".to_string(), vec![sample(1.0), sample(2.0)]),
+            ("member".to_string(), vec![sample(3.0)]),
+        ]);
+
+        attach_velocity_samples(&mut flights, samples);
+        let speeds = |flight: &Flight| {
+            flight
+                .velocity_samples
+                .iter()
+                .map(|sample| sample.ground_speed_mps)
+                .collect::<Vec<f32>>()
+        };
+
+        assert_eq!(speeds(&flights[0]), vec![1.0, 2.0]);
+        assert_eq!(speeds(&flights[1]), vec![3.0]);
+        assert!(flights[2].velocity_samples.is_empty());
+        assert!(flights[3].velocity_samples.is_empty());
+    }
+
+    #[test]
+    fn ut_expand_flight_no_aircraft_rows() {
+        let flight = Flight {
+            session_id: Some("flight".to_string()),
+            aircraft_id: Some("unknown_aircraft".to_string()),
+            simulated: false,
+            positions: vec![],
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            state: None,
+            velocity_samples: vec![],
+            time_start: None,
+            time_end: None,
+            path_alignment: None,
+            conflict_check: None,
+        };
+
+        let rows: Vec<GrpcPointZ> = vec![];
+        let result = expand_flight(&flight, rows, |_, _| Ok::<(), String>(()));
+        assert_eq!(result, vec![flight.clone()]);
+        assert!(result[0].positions.is_empty());
+        assert!(result[0].state.is_none());
+
+        let rows = vec![GrpcPointZ {
+            latitude: 52.37,
+            longitude: 4.91,
+            altitude_meters: 100.0,
+        }];
+
+        let result = expand_flight(&flight, rows, |row, f| {
+            f.positions.push(TimePosition {
+                position: Some(row),
+                timestamp: None,
+                bucket_seconds: 0,
+            });
+
+            Ok::<(), String>(())
+        });
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].positions.len(), 1);
+    }
+
+    #[test]
+    fn ut_estimate_arrival() {
+        let now = Utc::now();
+        let time_end = now + Duration::try_hours(1).unwrap();
+
+        // 1000 meters at 10 m/s
+        let eta = estimate_arrival(now, 1000.0, Some(10.0), Some(time_end)).unwrap();
+        assert_eq!(eta, now + Duration::try_seconds(100).unwrap());
+
+        // Zero or unknown speed falls back to the planned end time
+        let eta = estimate_arrival(now, 1000.0, Some(0.0), Some(time_end)).unwrap();
+        assert_eq!(eta, time_end);
+
+        let eta = estimate_arrival(now, 1000.0, None, Some(time_end)).unwrap();
+        assert_eq!(eta, time_end);
+
+        let result = estimate_arrival(now, 1000.0, None, None).unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Time));
+    }
+
+    #[test]
+    fn ut_path_alignment() {
+        let alignment = path_alignment(1, 2, 2.0, -1.5, 0.75);
+        assert_eq!(alignment.segment_index, 1);
+        assert_eq!(alignment.fraction_complete, 0.75);
+        assert!(!alignment.off_path);
+
+        // The end point of the path is in the last segment
+        let alignment = path_alignment(2, 2, 0.0, 0.0, 1.0);
+        assert_eq!(alignment.segment_index, 1);
+
+        // Off the path horizontally or vertically
+        let alignment = path_alignment(0, 2, MAX_PATH_LATERAL_DEVIATION_METERS + 1.0, 0.0, 0.1);
+        assert!(alignment.off_path);
+        assert_eq!(alignment.segment_index, 0);
+
+        let alignment = path_alignment(0, 2, 0.0, -(MAX_PATH_VERTICAL_DEVIATION_METERS + 1.0), 0.1);
+        assert!(alignment.off_path);
+    }
+
+    #[tokio::test]
+    async fn ut_flight_eta_invalid_identifier() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_flight_eta_invalid_identifier) start");
+
+        let point = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let result = flight_eta("NULL", point).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        let result = flight_eta("flight", point).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        ut_info!("(ut_flight_eta_invalid_identifier) success");
+    }
+
+    #[test]
+    fn ut_flight_path_request() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_hours(1).unwrap();
+        let message = FlightPathMessage {
+            correlation_id: Some("abc-123".to_string()),
+            flight_identifier: Some("FLIGHT-1".to_string()),
+            aircraft_identifier: Some("AIRCRAFT-1".to_string()),
+            simulated: true,
+            aircraft_type: AircraftType::Rotorcraft,
+            path: vec![crate::types::Position {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            }],
+            timestamp_start: time_start,
+            timestamp_end: time_end,
+            allow_rebind: false,
+            operator_id: None,
+            destination_identifier: Some("VERTIPORT-1".to_string()),
+            scenario_id: Some("SCENARIO-1".to_string()),
+            timestamp_enqueued: None,
+        };
+
+        let request = flight_path_request(message);
+        assert_eq!(request.flight_identifier, Some("FLIGHT-1".to_string()));
+        assert_eq!(request.aircraft_identifier, Some("AIRCRAFT-1".to_string()));
+        assert!(request.simulated);
+        assert_eq!(request.aircraft_type, AircraftType::Rotorcraft as i32);
+        assert_eq!(request.path.len(), 1);
+        assert_eq!(request.path[0].latitude, 52.3745905);
+        assert_eq!(request.path[0].altitude_meters, 100.0);
+        assert_eq!(request.timestamp_start, Some(time_start.into()));
+        assert_eq!(request.timestamp_end, Some(time_end.into()));
+        assert_eq!(
+            request.destination_identifier,
+            Some("VERTIPORT-1".to_string())
+        );
+        assert_eq!(request.scenario_id, Some("SCENARIO-1".to_string()));
+        assert!(!request.historical);
+    }
+
+    #[test]
+    fn ut_validate_arrivals_request() {
+        let time_start = Utc::now();
+        let time_end = time_start + Duration::try_hours(1).unwrap();
+        assert!(validate_arrivals_request("VERTIPORT-1", time_start, time_end).is_ok());
+        assert!(validate_arrivals_request("VERTIPORT-1", time_start, time_start).is_ok());
+
+        assert_eq!(
+            validate_arrivals_request("'VERTIPORT-1'", time_start, time_end).unwrap_err(),
+            FlightError::Label
+        );
+        assert_eq!(
+            validate_arrivals_request("VERTIPORT-1", time_end, time_start).unwrap_err(),
+            FlightError::Time
+        );
+    }
+
+    #[test]
+    fn ut_validate_intersection_distance() {
+        assert!(validate_intersection_distance(10.0).is_ok());
+        assert!(validate_intersection_distance(MAX_CONFLICT_DISTANCE_METERS as f64).is_ok());
+
+        for distance in [0.0, -1.0, f64::NAN, f64::INFINITY, 1_000.1] {
+            assert_eq!(
+                validate_intersection_distance(distance).unwrap_err(),
+                FlightError::Location
+            );
+        }
+    }
+
+    #[test]
+    fn ut_check_flight_identifier() {
+        assert!(check_flight_identifier("FLIGHT-1.a_b").is_ok());
+        assert_eq!(
+            check_flight_identifier("").unwrap_err(),
+            StringError::TooShort { length: 0, min: 1 }
+        );
+        assert_eq!(
+            check_flight_identifier(&"F".repeat(21)).unwrap_err(),
+            StringError::TooLong {
+                length: 21,
+                max: 20
+            }
+        );
+        assert_eq!(
+            check_flight_identifier("FLIGHT 1").unwrap_err(),
+            StringError::Character {
+                character: ' ',
+                index: 6
+            }
+        );
+        assert_eq!(
+            check_flight_identifier("flight-null").unwrap_err(),
+            StringError::ContainsForbidden
+        );
+
+        let e = FlightError::InvalidString(
+            "flight_identifier",
+            StringError::TooLong {
+                length: 21,
+                max: 20,
+            },
+        );
+        assert_eq!(
+            e.to_string(),
+            "Invalid flight_identifier provided: String is 21 characters long, at most 20 allowed."
+        );
+    }
+
+    #[test]
+    fn ut_flight_members() {
+        let mut item = UpdateFlightPathRequest {
+            aircraft_identifier: Some("lead".to_string()),
+            aircraft_identifiers: vec![
+                "wing-1".to_string(),
+                "lead".to_string(),
+                "wing-2".to_string(),
+                "wing-1".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        // lead first, duplicates removed
+        assert_eq!(
+            flight_members(&item).unwrap(),
+            vec!["lead", "wing-1", "wing-2"]
+        );
+
+        // the first listed aircraft leads when none is given
+        item.aircraft_identifier = None;
+        assert_eq!(
+            flight_members(&item).unwrap(),
+            vec!["wing-1", "lead", "wing-2"]
+        );
+
+        item.aircraft_identifiers = vec![];
+        assert!(flight_members(&item).unwrap().is_empty());
+
+        item.aircraft_identifiers = vec!["wing-1".to_string(), "a".repeat(1000)];
+        assert_eq!(
+            flight_members(&item).unwrap_err(),
+            FlightError::InvalidString(
+                "aircraft_identifier",
+                StringError::TooLong {
+                    length: 1000,
+                    max: 20
+                }
+            )
+        );
+
+        item.aircraft_identifiers = (0..=MAX_FLIGHT_AIRCRAFT)
+            .map(|i| format!("wing-{i}"))
+            .collect();
+        assert_eq!(flight_members(&item).unwrap_err(), FlightError::AircraftId);
+
+        item.aircraft_identifiers.pop();
+        assert_eq!(flight_members(&item).unwrap().len(), MAX_FLIGHT_AIRCRAFT);
+    }
+
+    #[tokio::test]
+    async fn ut_get_intersecting_flight_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_intersecting_flight_invalid) start");
+
+        let pool = deadpool_postgres::Config::new()
+            .create_pool(None, tokio_postgres::NoTls)
+            .unwrap();
+        let point = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let now = Utc::now();
+
+        let result = get_intersecting_flight(point, 0.0, now, now, &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Location));
+
+        let earlier = now - Duration::try_seconds(1).unwrap();
+        let result = get_intersecting_flight(point, 10.0, now, earlier, &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Time));
+
+        ut_info!("(ut_get_intersecting_flight_invalid) success");
+    }
+
+    #[tokio::test]
+    async fn ut_get_states_for_flights_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_get_states_for_flights_invalid) start");
+
+        let pool = deadpool_postgres::Config::new()
+            .create_pool(None, tokio_postgres::NoTls)
+            .unwrap();
+
+        // No query is made for an empty list
+        let states = get_states_for_flights(vec![], &pool).await.unwrap();
+        assert!(states.is_empty());
+
+        let result = get_states_for_flights(vec!["valid".to_string(), "".to_string()], &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        ut_info!("(ut_get_states_for_flights_invalid) success");
+    }
+
+    #[tokio::test]
+    async fn ut_recompute_flight_length_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_recompute_flight_length_invalid) start");
+
+        let pool = deadpool_postgres::Config::new()
+            .create_pool(None, tokio_postgres::NoTls)
+            .unwrap();
+
+        for identifier in ["", "flight;", "an_identifier_too_long"] {
+            let result = recompute_flight_length(identifier, &pool)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                result,
+                PostgisError::FlightPath(FlightError::InvalidString("flight_identifier", _))
+            ));
+        }
+
+        // Valid identifiers reach the database
+        let result = recompute_flight_length("flight", &pool).await.unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        ut_info!("(ut_recompute_flight_length_invalid) success");
+    }
+
+    /// A straight segment flown from `start` to `end` (local meters) during
+    ///  the given seconds after `t0`
+    fn timed_segment(
+        t0: DateTime<Utc>,
+        start: (f64, f64, f64),
+        end: (f64, f64, f64),
+        seconds: (i64, i64),
+    ) -> TimedSegment {
+        let start = PointZ::new(start.0, start.1, start.2, Some(4978));
+        let end = PointZ::new(end.0, end.1, end.2, Some(4978));
+        TimedSegment {
+            start,
+            end,
+            start_ecef: start,
+            end_ecef: end,
+            time_start: t0 + Duration::try_seconds(seconds.0).unwrap(),
+            time_end: t0 + Duration::try_seconds(seconds.1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn ut_closest_approach_crossing() {
+        let t0 = Utc::now();
+
+        // Eastbound and northbound through the origin at the same time
+        let a = vec![timed_segment(t0, (-100., 0., 0.), (100., 0., 0.), (0, 100))];
+        let b = vec![timed_segment(
+            t0,
+            (0., -100., 10.),
+            (0., 100., 10.),
+            (0, 100),
+        )];
+        let approach = closest_approach(&a, &b).unwrap();
+        assert!((approach.distance_meters - 10.0).abs() < 1e-9);
+        assert_eq!(approach.time, t0 + Duration::try_seconds(50).unwrap());
+        assert_eq!(approach.position_a, PointZ::new(0., 0., 0., Some(4978)));
+        assert_eq!(approach.position_b, PointZ::new(0., 0., 10., Some(4978)));
+
+        // The second aircraft is 10 seconds late, both fly at 2 m/s
+        let b = vec![timed_segment(
+            t0,
+            (0., -100., 0.),
+            (0., 100., 0.),
+            (10, 110),
+        )];
+        let approach = closest_approach(&a, &b).unwrap();
+        assert!((approach.distance_meters - 200f64.sqrt()).abs() < 1e-9);
+        assert_eq!(approach.time, t0 + Duration::try_seconds(55).unwrap());
+        assert!((approach.position_a.x - 10.0).abs() < 1e-9);
+        assert!((approach.position_b.y + 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ut_closest_approach_sweep() {
+        let t0 = Utc::now();
+
+        // Same crossing with the paths split into several segments
+        let a = vec![
+            timed_segment(t0, (-100., 0., 0.), (-50., 0., 0.), (0, 25)),
+            timed_segment(t0, (-50., 0., 0.), (20., 0., 0.), (25, 60)),
+            timed_segment(t0, (20., 0., 0.), (100., 0., 0.), (60, 100)),
+        ];
+        let b = vec![
+            timed_segment(t0, (0., -100., 0.), (0., 10., 0.), (0, 55)),
+            timed_segment(t0, (0., 10., 0.), (0., 100., 0.), (55, 100)),
+        ];
+        let approach = closest_approach(&a, &b).unwrap();
+        assert!(approach.distance_meters < 1e-6);
+        assert_eq!(approach.time, t0 + Duration::try_seconds(50).unwrap());
+    }
+
+    #[test]
+    fn ut_closest_approach_no_overlap() {
+        let t0 = Utc::now();
+        let a = vec![timed_segment(t0, (-100., 0., 0.), (100., 0., 0.), (0, 100))];
+        let b = vec![timed_segment(
+            t0,
+            (0., -100., 0.),
+            (0., 100., 0.),
+            (101, 200),
+        )];
+        assert!(closest_approach(&a, &b).is_none());
+        assert!(closest_approach(&a, &[]).is_none());
+
+        // Parallel flights keep a constant separation
+        let b = vec![timed_segment(
+            t0,
+            (-100., 30., 0.),
+            (100., 30., 0.),
+            (0, 100),
+        )];
+        let approach = closest_approach(&a, &b).unwrap();
+        assert!((approach.distance_meters - 30.0).abs() < 1e-9);
+        assert_eq!(approach.time, t0);
+    }
+
+    #[tokio::test]
+    async fn ut_closest_point_of_approach_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_closest_point_of_approach_invalid) start");
+
+        let pool = deadpool_postgres::Config::new()
+            .create_pool(None, tokio_postgres::NoTls)
+            .unwrap();
+
+        let result = closest_point_of_approach("flight;", "flight", &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Label));
+
+        let result = closest_point_of_approach("flight-a", "flight-b", &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::FlightPath(FlightError::Client));
+
+        ut_info!("(ut_closest_point_of_approach_invalid) success");
     }
 }
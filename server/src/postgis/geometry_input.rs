@@ -0,0 +1,804 @@
+//! Explicit geometry inputs for flight paths and zones
+//!
+//! Flight paths and zones can be submitted as (E)WKT or (E)WKB instead of
+//!  point lists. The geometry is parsed into the point list it stands for,
+//!  so that both forms go through the same validation and storage.
+//!
+//! The SRID must be [`DEFAULT_SRID`] or absent and the geometry type must
+//!  match the target: `LINESTRING Z` for flight paths, `POLYGON` or
+//!  `MULTIPOLYGON` for zones. Zone footprints have a single exterior ring
+//!  and their Z ordinates are ignored, the altitudes of the zone apply.
+//!
+//! Errors carry the byte offset into the WKT string or the WKB bytes where
+//!  parsing failed.
+
+use super::utils::validate_pointz;
+use super::wkt::{WktError, MAX_WKT_LENGTH};
+use super::DEFAULT_SRID;
+use crate::grpc::server::grpc_server::{Coordinates, PointZ as GrpcPointZ};
+use postgis::ewkb::PointZ;
+
+/// EWKB flag of geometries with Z ordinates
+const EWKB_Z_FLAG: u32 = 0x8000_0000;
+
+/// EWKB flag of geometries with M ordinates
+const EWKB_M_FLAG: u32 = 0x4000_0000;
+
+/// EWKB flag of geometries with an SRID
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// WKB type code of a line string
+const WKB_LINESTRING: u32 = 2;
+
+/// WKB type code of a polygon
+const WKB_POLYGON: u32 = 3;
+
+/// WKB type code of a multipolygon
+const WKB_MULTIPOLYGON: u32 = 6;
+
+/// Possible errors with geometry inputs
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GeometryInputError {
+    /// More than one of the point list, WKT and WKB were provided
+    Conflict,
+
+    /// Invalid WKT, with the byte offset where parsing failed
+    Wkt(WktError, usize),
+
+    /// Invalid WKB, with the byte offset where parsing failed
+    Ewkb(WktError, usize),
+}
+
+impl std::fmt::Display for GeometryInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GeometryInputError::Conflict => {
+                write!(f, "Provide only one of the point list, WKT or WKB.")
+            }
+            GeometryInputError::Wkt(kind, offset) => {
+                write!(f, "Invalid WKT at byte {offset}: {kind}")
+            }
+            GeometryInputError::Ewkb(kind, offset) => {
+                write!(f, "Invalid WKB at byte {offset}: {kind}")
+            }
+        }
+    }
+}
+
+/// What a geometry input describes
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Target {
+    /// A flight path, `LINESTRING Z`
+    Path,
+
+    /// A zone footprint, `POLYGON` or `MULTIPOLYGON`
+    Zone,
+}
+
+/// Checks the SRID of a geometry input
+fn check_input_srid(srid: i32) -> Result<(), WktError> {
+    if srid != DEFAULT_SRID {
+        postgis_error!(
+            "(check_input_srid) geometry inputs must use SRID {}, found {}.",
+            DEFAULT_SRID,
+            srid
+        );
+        return Err(WktError::Srid);
+    }
+
+    Ok(())
+}
+
+/// Builds a point from its ordinates, checking the bounds of the longitude
+///  and latitude
+fn input_point(x: f64, y: f64, z: Option<f64>) -> Result<PointZ, WktError> {
+    let point = PointZ::new(x, y, z.unwrap_or(0.0), Some(DEFAULT_SRID));
+    validate_pointz(&point).map_err(|_| WktError::OutOfBounds)?;
+    Ok(point)
+}
+
+/// Reads a WKT string, keeping track of the byte offset
+struct WktReader<'a> {
+    text: &'a str,
+    offset: usize,
+    target: Target,
+    has_z: bool,
+    dimensions: Option<usize>,
+}
+
+impl<'a> WktReader<'a> {
+    /// An error at the current offset
+    fn error(&self, kind: WktError) -> GeometryInputError {
+        GeometryInputError::Wkt(kind, self.offset)
+    }
+
+    /// Moves past whitespace
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    /// Gets the next character after whitespace, without consuming it
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.text[self.offset..].chars().next()
+    }
+
+    /// Consumes the expected character
+    fn consume(&mut self, expected: char) -> Result<(), GeometryInputError> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(WktError::Syntax));
+        }
+
+        self.offset += expected.len_utf8();
+        Ok(())
+    }
+
+    /// Consumes a run of characters that aren't whitespace or delimiters
+    fn token(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.text[self.offset..];
+        let length = rest
+            .find(|c: char| c.is_whitespace() || "(),;=".contains(c))
+            .unwrap_or(rest.len());
+
+        self.offset += length;
+        &rest[..length]
+    }
+
+    /// Consumes an optional `SRID=<srid>;` prefix
+    fn srid(&mut self) -> Result<(), GeometryInputError> {
+        self.skip_whitespace();
+        let rest = &self.text[self.offset..];
+        if !rest
+            .get(..5)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("SRID="))
+        {
+            return Ok(());
+        }
+
+        self.offset += 5;
+        let start = self.offset;
+        let srid = self
+            .token()
+            .parse::<i32>()
+            .map_err(|_| GeometryInputError::Wkt(WktError::Srid, start))?;
+
+        check_input_srid(srid).map_err(|kind| GeometryInputError::Wkt(kind, start))?;
+        self.consume(';')
+    }
+
+    /// Consumes the geometry keyword and dimension, returns the keyword
+    fn keyword(&mut self) -> Result<String, GeometryInputError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        let keyword = self.token().to_ascii_uppercase();
+        let expected = match (self.target, keyword.as_str()) {
+            (Target::Path, "LINESTRING") => true,
+            (Target::Zone, "POLYGON" | "MULTIPOLYGON") => true,
+            (_, "") => return Err(GeometryInputError::Wkt(WktError::Syntax, start)),
+            _ => false,
+        };
+
+        if !expected {
+            postgis_error!("(keyword) unexpected WKT geometry type: {}", keyword);
+            return Err(GeometryInputError::Wkt(WktError::GeometryType, start));
+        }
+
+        // Dimension and EMPTY, in that order
+        while self.peek().is_some_and(char::is_alphabetic) {
+            let start = self.offset;
+            match self.token().to_ascii_uppercase().as_str() {
+                "Z" if !self.has_z => self.has_z = true,
+                "M" | "ZM" => return Err(GeometryInputError::Wkt(WktError::Dimension, start)),
+                "EMPTY" => return Err(GeometryInputError::Wkt(WktError::PointCount, start)),
+                _ => return Err(GeometryInputError::Wkt(WktError::Syntax, start)),
+            }
+        }
+
+        Ok(keyword)
+    }
+
+    /// Consumes an `x y [z]` coordinate
+    fn coordinate(&mut self) -> Result<PointZ, GeometryInputError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        let mut ordinates = vec![];
+        while !matches!(self.peek(), None | Some(',') | Some(')')) {
+            let position = self.offset;
+            let value = self
+                .token()
+                .parse::<f64>()
+                .map_err(|_| GeometryInputError::Wkt(WktError::Syntax, position))?;
+
+            ordinates.push(value);
+        }
+
+        let dimensions = *self.dimensions.get_or_insert(ordinates.len());
+        let valid = match ordinates.len() {
+            3 => true,
+            2 => !self.has_z && self.target == Target::Zone,
+            _ => false,
+        };
+
+        if !valid || ordinates.len() != dimensions {
+            return Err(GeometryInputError::Wkt(WktError::Dimension, start));
+        }
+
+        input_point(ordinates[0], ordinates[1], ordinates.get(2).copied())
+            .map_err(|kind| GeometryInputError::Wkt(kind, start))
+    }
+
+    /// Consumes a parenthesized list of coordinates
+    fn points(&mut self) -> Result<Vec<PointZ>, GeometryInputError> {
+        self.consume('(')?;
+        let mut points = vec![self.coordinate()?];
+        while self.peek() == Some(',') {
+            self.offset += 1;
+            points.push(self.coordinate()?);
+        }
+
+        self.consume(')')?;
+        Ok(points)
+    }
+
+    /// Consumes a polygon body, returns its exterior ring
+    fn polygon(&mut self) -> Result<Vec<PointZ>, GeometryInputError> {
+        self.consume('(')?;
+        let ring = self.points()?;
+        if self.peek() == Some(',') {
+            self.offset += 1;
+            self.skip_whitespace();
+            postgis_error!("(polygon) zones can't have interior rings.");
+            return Err(self.error(WktError::Unsupported));
+        }
+
+        self.consume(')')?;
+        Ok(ring)
+    }
+
+    /// Reads the whole geometry, returns the path or the exterior ring
+    fn read(&mut self) -> Result<Vec<PointZ>, GeometryInputError> {
+        self.srid()?;
+        let keyword = self.keyword()?;
+        let start = self.offset;
+        let points = match keyword.as_str() {
+            "LINESTRING" => self.points()?,
+            "POLYGON" => self.polygon()?,
+            _ => {
+                self.consume('(')?;
+                let ring = self.polygon()?;
+                if self.peek() == Some(',') {
+                    self.offset += 1;
+                    self.skip_whitespace();
+                    postgis_error!("(read) zones must be a single polygon.");
+                    return Err(self.error(WktError::Unsupported));
+                }
+
+                self.consume(')')?;
+                ring
+            }
+        };
+
+        if self.target == Target::Path && !self.has_z {
+            return Err(GeometryInputError::Wkt(WktError::Dimension, start));
+        }
+
+        if self.target == Target::Path && points.len() < 2 {
+            return Err(GeometryInputError::Wkt(WktError::PointCount, start));
+        }
+
+        if self.peek().is_some() {
+            return Err(self.error(WktError::Syntax));
+        }
+
+        Ok(points)
+    }
+}
+
+/// Reads (E)WKB bytes, keeping track of the byte offset
+struct EwkbReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    little_endian: bool,
+}
+
+impl EwkbReader<'_> {
+    /// An error at the given offset
+    fn error(kind: WktError, offset: usize) -> GeometryInputError {
+        GeometryInputError::Ewkb(kind, offset)
+    }
+
+    /// Consumes the next `N` bytes
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], GeometryInputError> {
+        let Some(bytes) = self.bytes.get(self.offset..self.offset + N) else {
+            return Err(Self::error(WktError::Syntax, self.offset));
+        };
+
+        self.offset += N;
+        Ok(bytes.try_into().unwrap_or([0; N]))
+    }
+
+    /// Consumes an unsigned 32-bit integer
+    fn u32(&mut self) -> Result<u32, GeometryInputError> {
+        let bytes = self.take::<4>()?;
+        Ok(match self.little_endian {
+            true => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        })
+    }
+
+    /// Consumes a double
+    fn f64(&mut self) -> Result<f64, GeometryInputError> {
+        let bytes = self.take::<8>()?;
+        Ok(match self.little_endian {
+            true => f64::from_le_bytes(bytes),
+            false => f64::from_be_bytes(bytes),
+        })
+    }
+
+    /// Consumes an item count, checking that the remaining bytes can hold
+    ///  that many items of the given size
+    fn count(&mut self, item_size: usize) -> Result<usize, GeometryInputError> {
+        let start = self.offset;
+        let count = self.u32()? as usize;
+        let remaining = self.bytes.len() - self.offset;
+        if count.saturating_mul(item_size) > remaining {
+            return Err(Self::error(WktError::Syntax, start));
+        }
+
+        Ok(count)
+    }
+
+    /// Consumes a geometry header, returns the WKB type code, whether the
+    ///  coordinates have Z ordinates and the offset of the type
+    fn header(&mut self) -> Result<(u32, bool, usize), GeometryInputError> {
+        let start = self.offset;
+        self.little_endian = match self.take::<1>()? {
+            [0] => false,
+            [1] => true,
+            _ => return Err(Self::error(WktError::Syntax, start)),
+        };
+
+        let type_offset = self.offset;
+        let raw = self.u32()?;
+
+        // ISO WKB adds 1000 (Z), 2000 (M) or 3000 (ZM) to the type code
+        let code = raw & 0x0FFF_FFFF;
+        let (geometry_type, iso_dimension) = (code % 1000, code / 1000);
+        if raw & EWKB_M_FLAG != 0 || iso_dimension > 1 {
+            return Err(Self::error(WktError::Dimension, type_offset));
+        }
+
+        if raw & EWKB_SRID_FLAG != 0 {
+            let srid_offset = self.offset;
+            let srid = self.u32()? as i32;
+
+            // An SRID of 0 is unknown, as if absent
+            if srid != 0 {
+                check_input_srid(srid).map_err(|kind| Self::error(kind, srid_offset))?;
+            }
+        }
+
+        let has_z = raw & EWKB_Z_FLAG != 0 || iso_dimension == 1;
+        Ok((geometry_type, has_z, type_offset))
+    }
+
+    /// Consumes a list of points
+    fn points(&mut self, has_z: bool) -> Result<Vec<PointZ>, GeometryInputError> {
+        let size = if has_z { 24 } else { 16 };
+        let count = self.count(size)?;
+        (0..count)
+            .map(|_| {
+                let start = self.offset;
+                let x = self.f64()?;
+                let y = self.f64()?;
+                let z = if has_z { Some(self.f64()?) } else { None };
+                input_point(x, y, z).map_err(|kind| Self::error(kind, start))
+            })
+            .collect()
+    }
+
+    /// Consumes a polygon body, returns its exterior ring
+    fn polygon(&mut self, has_z: bool) -> Result<Vec<PointZ>, GeometryInputError> {
+        let start = self.offset;
+        match self.count(4)? {
+            0 => Err(Self::error(WktError::PointCount, start)),
+            1 => self.points(has_z),
+            _ => {
+                postgis_error!("(polygon) zones can't have interior rings.");
+                Err(Self::error(WktError::Unsupported, start))
+            }
+        }
+    }
+
+    /// Reads the whole geometry, returns the path or the exterior ring
+    fn read(&mut self, target: Target) -> Result<Vec<PointZ>, GeometryInputError> {
+        let (geometry_type, has_z, type_offset) = self.header()?;
+        let points = match (target, geometry_type) {
+            (Target::Path, WKB_LINESTRING) => {
+                if !has_z {
+                    return Err(Self::error(WktError::Dimension, type_offset));
+                }
+
+                let start = self.offset;
+                let points = self.points(has_z)?;
+                if points.len() < 2 {
+                    return Err(Self::error(WktError::PointCount, start));
+                }
+
+                points
+            }
+            (Target::Zone, WKB_POLYGON) => self.polygon(has_z)?,
+            (Target::Zone, WKB_MULTIPOLYGON) => {
+                let start = self.offset;
+                match self.count(9)? {
+                    0 => return Err(Self::error(WktError::PointCount, start)),
+                    1 => (),
+                    _ => {
+                        postgis_error!("(read) zones must be a single polygon.");
+                        return Err(Self::error(WktError::Unsupported, start));
+                    }
+                }
+
+                let (geometry_type, has_z, type_offset) = self.header()?;
+                if geometry_type != WKB_POLYGON {
+                    return Err(Self::error(WktError::GeometryType, type_offset));
+                }
+
+                self.polygon(has_z)?
+            }
+            _ => {
+                postgis_error!("(read) unexpected WKB geometry type: {}", geometry_type);
+                return Err(Self::error(WktError::GeometryType, type_offset));
+            }
+        };
+
+        if self.offset != self.bytes.len() {
+            return Err(Self::error(WktError::Syntax, self.offset));
+        }
+
+        Ok(points)
+    }
+}
+
+/// Parses whichever geometry input is provided
+///
+/// Returns `None` if neither is.
+fn parse_input(
+    wkt: Option<&str>,
+    ewkb: Option<&[u8]>,
+    target: Target,
+) -> Result<Option<Vec<PointZ>>, GeometryInputError> {
+    match (wkt, ewkb) {
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => Err(GeometryInputError::Conflict),
+        (Some(text), None) => {
+            if text.len() > MAX_WKT_LENGTH {
+                postgis_error!("(parse_input) WKT too long: {} bytes.", text.len());
+                return Err(GeometryInputError::Wkt(WktError::Syntax, MAX_WKT_LENGTH));
+            }
+
+            WktReader {
+                text,
+                offset: 0,
+                target,
+                has_z: false,
+                dimensions: None,
+            }
+            .read()
+            .map(Some)
+        }
+        (None, Some(bytes)) => {
+            if bytes.len() > MAX_WKT_LENGTH {
+                postgis_error!("(parse_input) WKB too long: {} bytes.", bytes.len());
+                return Err(GeometryInputError::Ewkb(WktError::Syntax, MAX_WKT_LENGTH));
+            }
+
+            EwkbReader {
+                bytes,
+                offset: 0,
+                little_endian: true,
+            }
+            .read(target)
+            .map(Some)
+        }
+    }
+}
+
+/// Gets the path points of a flight update from its geometry fields
+///
+/// Returns `None` if neither `geom_wkt` nor `geom_ewkb` is provided.
+pub fn flight_path_from_input(
+    wkt: Option<&str>,
+    ewkb: Option<&[u8]>,
+) -> Result<Option<Vec<GrpcPointZ>>, GeometryInputError> {
+    let points = parse_input(wkt, ewkb, Target::Path)?;
+    Ok(points.map(|points| {
+        points
+            .into_iter()
+            .map(|p| GrpcPointZ {
+                longitude: p.x,
+                latitude: p.y,
+                altitude_meters: p.z as f32,
+            })
+            .collect()
+    }))
+}
+
+/// Gets the vertices of a zone from its geometry fields
+///
+/// Returns `None` if neither `geom_wkt` nor `geom_ewkb` is provided.
+pub fn zone_vertices_from_input(
+    wkt: Option<&str>,
+    ewkb: Option<&[u8]>,
+) -> Result<Option<Vec<Coordinates>>, GeometryInputError> {
+    let points = parse_input(wkt, ewkb, Target::Zone)?;
+    Ok(points.map(|points| {
+        points
+            .into_iter()
+            .map(|p| Coordinates {
+                longitude: p.x,
+                latitude: p.y,
+            })
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Little endian EWKB of a line string with Z and an SRID
+    fn linestring_ewkb(srid: Option<u32>, points: &[(f64, f64, f64)]) -> Vec<u8> {
+        let mut flags = WKB_LINESTRING | EWKB_Z_FLAG;
+        if srid.is_some() {
+            flags |= EWKB_SRID_FLAG;
+        }
+
+        let mut bytes = vec![1];
+        bytes.extend(flags.to_le_bytes());
+        if let Some(srid) = srid {
+            bytes.extend(srid.to_le_bytes());
+        }
+
+        bytes.extend((points.len() as u32).to_le_bytes());
+        for (x, y, z) in points {
+            bytes.extend(x.to_le_bytes());
+            bytes.extend(y.to_le_bytes());
+            bytes.extend(z.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Big endian WKB of a 2D polygon with a single ring
+    fn polygon_wkb(ring: &[(f64, f64)]) -> Vec<u8> {
+        let mut bytes = vec![0];
+        bytes.extend(WKB_POLYGON.to_be_bytes());
+        bytes.extend(1u32.to_be_bytes());
+        bytes.extend((ring.len() as u32).to_be_bytes());
+        for (x, y) in ring {
+            bytes.extend(x.to_be_bytes());
+            bytes.extend(y.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    const RING: [(f64, f64); 4] = [(4.90, 52.37), (4.95, 52.40), (5.00, 52.37), (4.90, 52.37)];
+
+    #[test]
+    fn ut_flight_path_from_input() {
+        assert_eq!(flight_path_from_input(None, None).unwrap(), None);
+
+        let from_wkt = flight_path_from_input(
+            Some("SRID=4326;LINESTRING Z (4.90 52.37 80, 4.95 52.40 90)"),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+
+        let ewkb = linestring_ewkb(Some(4326), &[(4.90, 52.37, 80.0), (4.95, 52.40, 90.0)]);
+        let from_ewkb = flight_path_from_input(None, Some(&ewkb)).unwrap().unwrap();
+
+        assert_eq!(from_wkt, from_ewkb);
+        assert_eq!(from_wkt.len(), 2);
+        assert_eq!(from_wkt[1].longitude, 4.95);
+        assert_eq!(from_wkt[1].latitude, 52.40);
+        assert_eq!(from_wkt[1].altitude_meters, 90.0);
+
+        // No SRID, or an unknown SRID in the EWKB
+        assert!(flight_path_from_input(Some("linestring z(4.9 52.3 1,5 52.3 1)"), None).is_ok());
+        let ewkb = linestring_ewkb(Some(0), &[(4.9, 52.3, 1.0), (5.0, 52.3, 1.0)]);
+        assert!(flight_path_from_input(None, Some(&ewkb)).is_ok());
+
+        assert_eq!(
+            flight_path_from_input(Some("LINESTRING Z (4.9 52.3 1, 5 52.3 1)"), Some(&ewkb))
+                .unwrap_err(),
+            GeometryInputError::Conflict
+        );
+    }
+
+    #[test]
+    fn ut_flight_path_from_wkt_invalid() {
+        let cases = [
+            ("", WktError::Syntax, 0),
+            (
+                "SRID=28992;LINESTRING Z (4.9 52.3 1, 5 52.3 1)",
+                WktError::Srid,
+                5,
+            ),
+            (
+                "SRID=abc;LINESTRING Z (4.9 52.3 1, 5 52.3 1)",
+                WktError::Srid,
+                5,
+            ),
+            (
+                "POLYGON Z ((4.9 52.3 1, 5 52.3 1))",
+                WktError::GeometryType,
+                0,
+            ),
+            ("LINESTRING (4.9 52.3, 5 52.3)", WktError::Dimension, 12),
+            ("LINESTRING (4.9 52.3 1, 5 52.3 1)", WktError::Dimension, 11),
+            (
+                "LINESTRING M (4.9 52.3 1, 5 52.3 1)",
+                WktError::Dimension,
+                11,
+            ),
+            ("LINESTRING Z EMPTY", WktError::PointCount, 13),
+            ("LINESTRING Z (4.9 52.3 1)", WktError::PointCount, 13),
+            ("LINESTRING Z (4.9 52.3 1, 5 52.3 x)", WktError::Syntax, 33),
+            (
+                "LINESTRING Z (4.9 52.3 1, 5 95 1)",
+                WktError::OutOfBounds,
+                26,
+            ),
+            (
+                "LINESTRING Z (4.9 52.3 1 5 52.3 1)",
+                WktError::Dimension,
+                14,
+            ),
+            ("LINESTRING Z (4.9 52.3 1, 5 52.3 1", WktError::Syntax, 34),
+            (
+                "LINESTRING Z (4.9 52.3 1, 5 52.3 1) x",
+                WktError::Syntax,
+                36,
+            ),
+        ];
+
+        for (wkt, kind, offset) in cases {
+            assert_eq!(
+                flight_path_from_input(Some(wkt), None).unwrap_err(),
+                GeometryInputError::Wkt(kind, offset),
+                "{wkt}"
+            );
+        }
+    }
+
+    #[test]
+    fn ut_flight_path_from_ewkb_invalid() {
+        let valid = linestring_ewkb(Some(4326), &[(4.9, 52.3, 1.0), (5.0, 52.3, 1.0)]);
+
+        // Truncated, the points announced don't fit
+        let truncated = &valid[..valid.len() - 3];
+        assert_eq!(
+            flight_path_from_input(None, Some(truncated)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::Syntax, 9)
+        );
+
+        // Truncated in the type
+        assert_eq!(
+            flight_path_from_input(None, Some(&valid[..3])).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::Syntax, 1)
+        );
+
+        // Trailing bytes
+        let mut trailing = valid.clone();
+        trailing.push(0);
+        assert_eq!(
+            flight_path_from_input(None, Some(&trailing)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::Syntax, valid.len())
+        );
+
+        // Invalid byte order
+        let mut order = valid.clone();
+        order[0] = 2;
+        assert_eq!(
+            flight_path_from_input(None, Some(&order)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::Syntax, 0)
+        );
+
+        let srid = linestring_ewkb(Some(28992), &[(4.9, 52.3, 1.0), (5.0, 52.3, 1.0)]);
+        assert_eq!(
+            flight_path_from_input(None, Some(&srid)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::Srid, 5)
+        );
+
+        let bounds = linestring_ewkb(None, &[(4.9, 52.3, 1.0), (5.0, 95.0, 1.0)]);
+        assert_eq!(
+            flight_path_from_input(None, Some(&bounds)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::OutOfBounds, 33)
+        );
+
+        let single = linestring_ewkb(None, &[(4.9, 52.3, 1.0)]);
+        assert_eq!(
+            flight_path_from_input(None, Some(&single)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::PointCount, 5)
+        );
+
+        assert_eq!(
+            flight_path_from_input(None, Some(&polygon_wkb(&RING))).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::GeometryType, 1)
+        );
+
+        // More points announced than provided
+        let mut count = linestring_ewkb(None, &[(4.9, 52.3, 1.0), (5.0, 52.3, 1.0)]);
+        count[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            flight_path_from_input(None, Some(&count)).unwrap_err(),
+            GeometryInputError::Ewkb(WktError::Syntax, 5)
+        );
+    }
+
+    #[test]
+    fn ut_zone_vertices_from_input() {
+        let expected: Vec<Coordinates> = RING
+            .iter()
+            .map(|(longitude, latitude)| Coordinates {
+                latitude: *latitude,
+                longitude: *longitude,
+            })
+            .collect();
+
+        let inputs = [
+            "POLYGON ((4.90 52.37, 4.95 52.40, 5.00 52.37, 4.90 52.37))",
+            "SRID=4326;POLYGON Z ((4.90 52.37 5, 4.95 52.40 5, 5.00 52.37 5, 4.90 52.37 5))",
+            "MULTIPOLYGON (((4.90 52.37, 4.95 52.40, 5.00 52.37, 4.90 52.37)))",
+        ];
+
+        for wkt in inputs {
+            assert_eq!(
+                zone_vertices_from_input(Some(wkt), None).unwrap(),
+                Some(expected.clone()),
+                "{wkt}"
+            );
+        }
+
+        let wkb = polygon_wkb(&RING);
+        assert_eq!(
+            zone_vertices_from_input(None, Some(&wkb)).unwrap(),
+            Some(expected.clone())
+        );
+
+        // The same polygon wrapped in a little endian multipolygon
+        let mut multi = vec![1];
+        multi.extend(WKB_MULTIPOLYGON.to_le_bytes());
+        multi.extend(1u32.to_le_bytes());
+        multi.extend(&wkb);
+        assert_eq!(
+            zone_vertices_from_input(None, Some(&multi)).unwrap(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn ut_zone_vertices_from_input_invalid() {
+        let cases = [
+            ("LINESTRING Z (4.9 52.3 1, 5 52.3 1)", WktError::GeometryType, 0),
+            ("POLYGON ((4.9 52.3, 5 52.3, 5 52.4, 4.9 52.3), (4.95 52.35, 4.96 52.35, 4.96 52.36, 4.95 52.35))", WktError::Unsupported, 47),
+            ("MULTIPOLYGON (((4.9 52.3, 5 52.3, 5 52.4, 4.9 52.3)), ((6 52.3, 7 52.3, 7 52.4, 6 52.3)))", WktError::Unsupported, 54),
+            ("POLYGON ((4.9 52.3, 5 52.3 1, 5 52.4, 4.9 52.3))", WktError::Dimension, 20),
+            ("POLYGON Z ((4.9 52.3, 5 52.3, 5 52.4, 4.9 52.3))", WktError::Dimension, 12),
+            ("POLYGON (4.9 52.3, 5 52.3, 5 52.4, 4.9 52.3)", WktError::Syntax, 9),
+        ];
+
+        for (wkt, kind, offset) in cases {
+            assert_eq!(
+                zone_vertices_from_input(Some(wkt), None).unwrap_err(),
+                GeometryInputError::Wkt(kind, offset),
+                "{wkt}"
+            );
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! Bounded in-memory queue guarding the aircraft update path.
+//!
+//! Updates are keyed by aircraft and coalesced latest-wins: a newer update
+//!  replaces the pending one of the same aircraft instead of being appended,
+//!  so a lagging database costs at most one pending update per aircraft.
+//! Once the queue holds `capacity` aircraft, updates of other aircraft are
+//!  shed. Callers are never blocked by the database.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Max number of updates written at once by an ingest writer
+pub const INGEST_BATCH_SIZE: usize = 1000;
+
+/// Time an idle ingest writer waits for updates before checking for a
+///  shutdown
+const INGEST_IDLE_MS: u64 = 500;
+
+/// First wait of an ingest writer after a failed write, doubled on each
+///  consecutive failure
+const INGEST_RETRY_MIN_MS: u64 = 100;
+
+/// Longest wait of an ingest writer after a failed write
+const INGEST_RETRY_MAX_MS: u64 = 5_000;
+
+/// Updates waiting in the ingest queue
+pub static INGEST_QUEUE_DEPTH: AtomicU64 = AtomicU64::new(0);
+
+/// Updates that replaced a pending update of the same aircraft since startup
+pub static INGEST_COALESCED: AtomicU64 = AtomicU64::new(0);
+
+/// Updates dropped because the ingest queue was full since startup
+pub static INGEST_SHED: AtomicU64 = AtomicU64::new(0);
+
+/// What happened to an update pushed to the queue
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Enqueued {
+    /// Queued for writing
+    Queued,
+
+    /// Replaced the pending update of the same aircraft
+    Coalesced,
+
+    /// Dropped, the queue is full
+    Shed,
+}
+
+/// Counts of what happened to a set of updates
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct IngestOutcome {
+    /// Updates queued or written
+    pub accepted: u32,
+
+    /// Updates that replaced a pending update of the same aircraft
+    pub coalesced: u32,
+
+    /// Updates dropped because the queue was full
+    pub shed: u32,
+}
+
+impl IngestOutcome {
+    /// Adds an update to the counts
+    pub fn add(&mut self, enqueued: Enqueued) {
+        match enqueued {
+            Enqueued::Queued => self.accepted += 1,
+            Enqueued::Coalesced => self.coalesced += 1,
+            Enqueued::Shed => self.shed += 1,
+        }
+    }
+}
+
+/// A pending update and when its aircraft entered the queue
+#[derive(Debug)]
+pub struct Pending<V> {
+    /// The latest update
+    pub value: V,
+
+    /// When the aircraft entered the queue, kept when coalescing so the
+    ///  writer lag includes the time replaced updates waited
+    pub enqueued: Instant,
+}
+
+#[derive(Debug)]
+struct State<K, V> {
+    pending: HashMap<K, Pending<V>>,
+    order: VecDeque<K>,
+}
+
+/// Bounded queue of the latest update per key
+#[derive(Debug)]
+pub struct IngestQueue<K, V> {
+    capacity: usize,
+    state: Mutex<State<K, V>>,
+    notify: Notify,
+}
+
+impl<K, V> IngestQueue<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Creates a queue holding at most `capacity` keys
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(State {
+                pending: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Number of pending updates
+    pub fn len(&self) -> usize {
+        self.lock().order.len()
+    }
+
+    /// If no update is pending
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Time the oldest pending update has been waiting, zero if none is
+    ///
+    /// Keeps growing while the writers are stuck, unlike the wait of the
+    ///  updates they last wrote.
+    pub fn oldest_age(&self) -> Duration {
+        let state = self.lock();
+        state
+            .order
+            .front()
+            .and_then(|key| state.pending.get(key))
+            .map(|pending| pending.enqueued.elapsed())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State<K, V>> {
+        // A writer panicking mid-update leaves the state consistent,
+        //  every operation completes before releasing the lock
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Pushes the latest update of a key
+    pub fn push(&self, key: K, value: V) -> Enqueued {
+        let result = {
+            let mut state = self.lock();
+            if let Some(pending) = state.pending.get_mut(&key) {
+                pending.value = value;
+                Enqueued::Coalesced
+            } else if state.order.len() >= self.capacity {
+                Enqueued::Shed
+            } else {
+                state.pending.insert(
+                    key.clone(),
+                    Pending {
+                        value,
+                        enqueued: Instant::now(),
+                    },
+                );
+                state.order.push_back(key);
+                Enqueued::Queued
+            }
+        };
+
+        match result {
+            Enqueued::Queued => {
+                INGEST_QUEUE_DEPTH.fetch_add(1, Ordering::Relaxed);
+                self.notify.notify_one();
+            }
+            Enqueued::Coalesced => {
+                INGEST_COALESCED.fetch_add(1, Ordering::Relaxed);
+            }
+            Enqueued::Shed => {
+                INGEST_SHED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        result
+    }
+
+    /// Pushes the latest update of each item's key, logging what was shed
+    pub fn push_all(&self, caller: &str, items: Vec<V>, key: impl Fn(&V) -> K) -> IngestOutcome {
+        let mut outcome = IngestOutcome::default();
+        for item in items {
+            outcome.add(self.push(key(&item), item));
+        }
+
+        if outcome.shed > 0 {
+            postgis_warn!(
+                "({caller}) ingest queue full, shed {} updates.",
+                outcome.shed
+            );
+        }
+
+        outcome
+    }
+
+    /// Puts back updates that could not be written
+    ///
+    /// Keys updated in the meantime keep their newer update, the others
+    ///  go to the front of the queue as long as there is room.
+    pub fn requeue(&self, items: Vec<(K, Pending<V>)>) {
+        let mut state = self.lock();
+        let mut requeued = 0;
+        let mut shed = 0;
+        for (key, pending) in items.into_iter().rev() {
+            if state.pending.contains_key(&key) {
+                continue;
+            }
+
+            if state.order.len() >= self.capacity {
+                shed += 1;
+                continue;
+            }
+
+            state.pending.insert(key.clone(), pending);
+            state.order.push_front(key);
+            requeued += 1;
+        }
+
+        INGEST_QUEUE_DEPTH.fetch_add(requeued, Ordering::Relaxed);
+        INGEST_SHED.fetch_add(shed, Ordering::Relaxed);
+    }
+
+    /// Takes up to `max` of the oldest pending updates
+    pub fn pop_batch(&self, max: usize) -> Vec<(K, Pending<V>)> {
+        let mut state = self.lock();
+        let count = max.min(state.order.len());
+        let keys: Vec<K> = state.order.drain(..count).collect();
+        let batch: Vec<(K, Pending<V>)> = keys
+            .into_iter()
+            .filter_map(|key| state.pending.remove(&key).map(|pending| (key, pending)))
+            .collect();
+
+        INGEST_QUEUE_DEPTH.fetch_sub(batch.len() as u64, Ordering::Relaxed);
+        batch
+    }
+
+    /// Waits until an update is queued
+    pub async fn notified(&self) {
+        self.notify.notified().await
+    }
+}
+
+/// Writes queued updates with `write` until shutdown
+///
+/// Updates of a failed write are put back unless newer ones arrived, so
+///  the writer recovers once the database does. Pending updates are
+///  still written after shutdown begins, until a write fails.
+pub async fn write_queued<K, V, E, F, Fut>(caller: &str, queue: &IngestQueue<K, V>, write: F)
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    E: Display,
+    F: Fn(Vec<V>) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    let mut retry_ms = INGEST_RETRY_MIN_MS;
+    loop {
+        let shutting_down = crate::shutdown::is_shutting_down();
+        let batch = queue.pop_batch(INGEST_BATCH_SIZE);
+        if batch.is_empty() {
+            if shutting_down {
+                break;
+            }
+
+            let idle = Duration::from_millis(INGEST_IDLE_MS);
+            let _ = tokio::time::timeout(idle, queue.notified()).await;
+            continue;
+        }
+
+        let values = batch
+            .iter()
+            .map(|(_, pending)| pending.value.clone())
+            .collect();
+
+        match write(values).await {
+            Ok(_) => retry_ms = INGEST_RETRY_MIN_MS,
+            Err(e) if shutting_down => {
+                postgis_error!(
+                    "({caller}) could not write {} queued updates during shutdown: {e}",
+                    batch.len()
+                );
+                break;
+            }
+            Err(e) => {
+                postgis_warn!(
+                    "({caller}) could not write {} queued updates, retrying in {retry_ms}ms: {e}",
+                    batch.len()
+                );
+
+                queue.requeue(batch);
+                tokio::time::sleep(Duration::from_millis(retry_ms)).await;
+                retry_ms = (retry_ms * 2).min(INGEST_RETRY_MAX_MS);
+            }
+        }
+    }
+
+    postgis_info!("({caller}) ingest writer stopped for shutdown.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_ingest_queue_coalesces() {
+        let queue = IngestQueue::new(2);
+        assert_eq!(queue.push(1, "a"), Enqueued::Queued);
+        assert_eq!(queue.push(2, "b"), Enqueued::Queued);
+        assert_eq!(queue.push(1, "c"), Enqueued::Coalesced);
+        assert_eq!(queue.len(), 2);
+
+        // Full, other keys are shed but pending keys still coalesce
+        assert_eq!(queue.push(3, "d"), Enqueued::Shed);
+        assert_eq!(queue.push(2, "e"), Enqueued::Coalesced);
+        assert_eq!(queue.len(), 2);
+
+        // Oldest key first with its latest value
+        let batch = queue.pop_batch(1);
+        assert_eq!(batch.len(), 1);
+        assert_eq!((batch[0].0, batch[0].1.value), (1, "c"));
+
+        let batch = queue.pop_batch(10);
+        assert_eq!(batch.len(), 1);
+        assert_eq!((batch[0].0, batch[0].1.value), (2, "e"));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn ut_ingest_queue_bounded() {
+        let queue = IngestQueue::new(100);
+        let mut outcome = IngestOutcome::default();
+        for i in 0..10_000u32 {
+            outcome.add(queue.push(i % 250, i));
+        }
+
+        assert_eq!(queue.len(), 100);
+        assert_eq!(
+            outcome,
+            IngestOutcome {
+                accepted: 100,
+                coalesced: 3_900,
+                shed: 6_000,
+            }
+        );
+    }
+
+    #[test]
+    fn ut_ingest_queue_oldest_age() {
+        let queue = IngestQueue::new(2);
+        assert_eq!(queue.oldest_age(), Duration::ZERO);
+
+        queue.push(1, "a");
+        std::thread::sleep(Duration::from_millis(20));
+        queue.push(2, "b");
+
+        // Coalescing keeps the time the aircraft entered the queue
+        queue.push(1, "c");
+        assert!(queue.oldest_age() >= Duration::from_millis(20));
+
+        // A failed write puts the oldest update back in front
+        let batch = queue.pop_batch(1);
+        assert!(queue.oldest_age() < Duration::from_millis(20));
+        queue.requeue(batch);
+        assert!(queue.oldest_age() >= Duration::from_millis(20));
+
+        queue.pop_batch(2);
+        assert_eq!(queue.oldest_age(), Duration::ZERO);
+    }
+
+    #[test]
+    fn ut_ingest_queue_requeue() {
+        let queue = IngestQueue::new(2);
+        queue.push(1, "a");
+        queue.push(2, "b");
+        let batch = queue.pop_batch(2);
+
+        // Key 2 was updated while the batch was being written
+        queue.push(2, "c");
+        queue.requeue(batch);
+
+        let batch = queue.pop_batch(2);
+        let values: Vec<(u32, &str)> = batch.iter().map(|(k, p)| (*k, p.value)).collect();
+        assert_eq!(values, vec![(1, "a"), (2, "c")]);
+
+        // No room for the failed batch, it is shed
+        queue.push(3, "d");
+        queue.push(4, "e");
+        queue.requeue(batch);
+        assert_eq!(queue.len(), 2);
+    }
+}
@@ -0,0 +1,195 @@
+//! Periodic maintenance of frequently updated PostGIS tables.
+//!
+//! Tables with high update churn (e.g. aircraft positions) bloat quickly
+//!  and their planner statistics go stale, which degrades spatial queries.
+//! Old aircraft positions are moved to the downsampled archive.
+//!
+//! Soft-deleted zones and flights are purged once past retention, along
+//!  with flight reservations that expired without confirmation. Purges
+//!  run on their own interval, since deleted identifiers can't be reused
+//!  until purged.
+
+use super::{PostgisError, PsqlError};
+use std::sync::atomic::Ordering;
+
+/// Generates the maintenance statements for the hot tables
+///
+/// `VACUUM` can't be run inside a transaction block, so each statement
+///  is executed on its own.
+pub fn maintenance_statements(vacuum: bool) -> Vec<String> {
+    let command = match vacuum {
+        true => "VACUUM (ANALYZE)",
+        false => "ANALYZE",
+    };
+
+    [
+        super::aircraft::get_table_name(),
+        super::flight::get_flight_segments_table_name(),
+    ]
+    .iter()
+    .map(|table_name| format!("{command} {table_name};"))
+    .collect()
+}
+
+/// Runs maintenance statements on the hot tables.
+///
+/// This is best-effort: a failing statement is logged and the
+///  remaining statements are still attempted.
+pub async fn run_maintenance(vacuum: bool) -> Result<(), PostgisError> {
+    postgis_debug!("(run_maintenance) entry.");
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(run_maintenance) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Connection));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(run_maintenance) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    // A vacuum of a large table may outlast the statement timeout
+    if let Err(e) = client.batch_execute("SET statement_timeout = 0;").await {
+        postgis_warn!("(run_maintenance) could not disable statement timeout: {e}");
+    }
+
+    let mut result = Ok(());
+    for stmt in maintenance_statements(vacuum) {
+        match client.batch_execute(&stmt).await {
+            Ok(_) => postgis_info!("(run_maintenance) executed '{stmt}'."),
+            Err(e) => {
+                postgis_warn!("(run_maintenance) failed to execute '{stmt}': {e}");
+                result = Err(PostgisError::Psql(PsqlError::Execute));
+            }
+        }
+    }
+
+    // Back to the timeout of the connection options for the next user
+    if let Err(e) = client.batch_execute("RESET statement_timeout;").await {
+        postgis_warn!("(run_maintenance) could not restore statement timeout: {e}");
+    }
+
+    result
+}
+
+/// Permanently removes zones and flights soft-deleted more than
+///  `retention_secs` ago, and expired flight reservations
+///
+/// All purges are attempted even if one fails.
+pub async fn purge_deleted(retention_secs: u64) -> Result<(), PostgisError> {
+    postgis_debug!("(purge_deleted) entry.");
+    let zones = super::zone::purge_zones(retention_secs)
+        .await
+        .map_err(PostgisError::Zone);
+    let flights = super::flight::purge_flights(retention_secs).await;
+    let reservations = super::flight::expire_reservations().await;
+
+    if let (Ok(zones), Ok(flights)) = (&zones, &flights) {
+        postgis_info!("(purge_deleted) purged {zones} zones and {flights} flights.");
+    }
+
+    if let Ok(reservations) = &reservations {
+        postgis_info!(
+            "(purge_deleted) removed {reservations} expired reservations (since startup: {} confirmed, {} expired).",
+            super::flight::RESERVATIONS_CONFIRMED.load(Ordering::Relaxed),
+            super::flight::RESERVATIONS_EXPIRED.load(Ordering::Relaxed)
+        );
+    }
+
+    zones?;
+    flights?;
+    reservations?;
+    Ok(())
+}
+
+/// Starts a loop purging soft-deleted rows older than `retention_secs`
+///  every `interval_secs`, see [`purge_deleted`]
+pub async fn begin_purge(interval_secs: u64, retention_secs: u64) {
+    postgis_info!(
+        "(begin_purge) purging rows deleted over {retention_secs}s ago every {interval_secs}s."
+    );
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+
+    // The first tick completes immediately, skip it so purges don't run
+    //  during server startup
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        if let Err(e) = purge_deleted(retention_secs).await {
+            postgis_warn!("(begin_purge) purge of deleted rows incomplete: {e}");
+        }
+    }
+}
+
+/// Starts a loop running maintenance on the hot tables every `interval_secs`,
+///  archiving the aircraft history first
+pub async fn begin(interval_secs: u64, vacuum: bool, archive: super::archive::ArchivePolicy) {
+    postgis_info!(
+        "(begin) starting database maintenance every {interval_secs}s (vacuum: {vacuum})."
+    );
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+
+    // The first tick completes immediately, skip it so maintenance
+    //  doesn't run during server startup
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        if let Err(e) = super::archive::archive_history(archive).await {
+            postgis_warn!("(begin) archival of the aircraft history incomplete: {e}");
+        }
+
+        if let Err(e) = run_maintenance(vacuum).await {
+            postgis_warn!("(begin) database maintenance incomplete: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_maintenance_statements() {
+        let statements = maintenance_statements(false);
+        assert_eq!(
+            statements,
+            vec![
+                r#"ANALYZE "arrow"."aircraft";"#.to_string(),
+                r#"ANALYZE "arrow"."flight_segments";"#.to_string(),
+            ]
+        );
+
+        let statements = maintenance_statements(true);
+        assert_eq!(
+            statements,
+            vec![
+                r#"VACUUM (ANALYZE) "arrow"."aircraft";"#.to_string(),
+                r#"VACUUM (ANALYZE) "arrow"."flight_segments";"#.to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_run_maintenance_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_run_maintenance_client_failure) start");
+
+        let result = run_maintenance(true).await.unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Connection));
+
+        let result = purge_deleted(60).await.unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::Zone(crate::postgis::zone::ZoneError::Client)
+        );
+
+        ut_info!("(ut_run_maintenance_client_failure) success");
+    }
+}
@@ -6,12 +6,34 @@ use strum::IntoEnumIterator;
 pub mod macros;
 // pub mod nearest;
 pub mod aircraft;
+pub mod archive;
+pub mod backlog;
 pub mod best_path;
+pub mod compliance;
+pub mod conflict_check;
+pub mod corridor;
+pub mod export;
 pub mod flight;
+pub mod geometry_input;
+pub mod ingest;
+pub mod maintenance;
+pub mod nearby;
+pub mod occupancy;
 pub mod pool;
+pub mod replay;
+pub mod route;
+pub mod service_area;
+pub mod shard;
+pub mod subscription;
+pub mod tags;
+pub mod telemetry;
+pub mod throughput;
+pub mod tile;
 pub mod utils;
 pub mod vertiport;
 pub mod waypoint;
+pub mod what_if;
+pub mod wkt;
 pub mod zone;
 
 pub use once_cell::sync::OnceCell;
@@ -19,6 +41,19 @@ pub use once_cell::sync::OnceCell;
 /// Global pool for PostgreSQL connections
 pub static DEADPOOL_POSTGIS: OnceCell<deadpool_postgres::Pool> = OnceCell::new();
 
+/// Dedicated pool for aircraft telemetry updates, so that ingest never
+///  waits behind long-running reads on [`DEADPOOL_POSTGIS`]
+pub static DEADPOOL_POSTGIS_TELEMETRY: OnceCell<deadpool_postgres::Pool> = OnceCell::new();
+
+/// Gets the pool for aircraft telemetry updates
+///
+/// Falls back to the shared pool if no telemetry pool is configured.
+pub fn get_telemetry_pool() -> Option<&'static deadpool_postgres::Pool> {
+    DEADPOOL_POSTGIS_TELEMETRY
+        .get()
+        .or_else(|| DEADPOOL_POSTGIS.get())
+}
+
 /// PostgreSQL schema for all tables
 pub const PSQL_SCHEMA: &str = "arrow";
 
@@ -49,6 +84,39 @@ pub enum PostgisError {
 
     /// FlightPath Error
     FlightPath(flight::FlightError),
+
+    /// Tile Error
+    Tile(tile::TileError),
+
+    /// Binary Telemetry Error
+    Telemetry(telemetry::TelemetryError),
+
+    /// Vertiport Throughput Error
+    Throughput(throughput::ThroughputError),
+
+    /// Altitude Occupancy Error
+    Occupancy(occupancy::OccupancyError),
+
+    /// What-If Error
+    WhatIf(what_if::WhatIfError),
+
+    /// Route Error
+    Route(route::RouteError),
+
+    /// Export Error
+    Export(export::ExportError),
+
+    /// Nearby Nodes Error
+    Nearby(nearby::NearbyError),
+
+    /// Event Backlog Error
+    Backlog(backlog::BacklogError),
+
+    /// Corridor Error
+    Corridor(corridor::CorridorError),
+
+    /// History Replay Error
+    Replay(replay::ReplayError),
 }
 
 impl std::error::Error for PostgisError {
@@ -67,6 +135,17 @@ impl std::fmt::Display for PostgisError {
             PostgisError::Zone(e) => write!(f, "Zone Error: {}", e),
             PostgisError::BestPath(e) => write!(f, "BestPath Error: {}", e),
             PostgisError::FlightPath(e) => write!(f, "FlightPath Error: {}", e),
+            PostgisError::Tile(e) => write!(f, "Tile Error: {}", e),
+            PostgisError::Telemetry(e) => write!(f, "Telemetry Error: {}", e),
+            PostgisError::Throughput(e) => write!(f, "Throughput Error: {}", e),
+            PostgisError::Occupancy(e) => write!(f, "Occupancy Error: {}", e),
+            PostgisError::WhatIf(e) => write!(f, "What-If Error: {}", e),
+            PostgisError::Route(e) => write!(f, "Route Error: {}", e),
+            PostgisError::Export(e) => write!(f, "Export Error: {}", e),
+            PostgisError::Nearby(e) => write!(f, "Nearby Nodes Error: {}", e),
+            PostgisError::Backlog(e) => write!(f, "Event Backlog Error: {}", e),
+            PostgisError::Corridor(e) => write!(f, "Corridor Error: {}", e),
+            PostgisError::Replay(e) => write!(f, "History Replay Error: {}", e),
         }
     }
 }
@@ -88,6 +167,18 @@ pub enum PsqlError {
 
     /// Error on commit
     Commit,
+
+    /// Invalid or unknown spatial reference identifier
+    Srid,
+
+    /// Timed out waiting for the initialization lock
+    LockTimeout,
+
+    /// Required tables or columns are missing
+    Verification,
+
+    /// Coordinates don't survive a round trip through the database
+    CoordinateOrder,
 }
 
 impl std::fmt::Display for PsqlError {
@@ -98,6 +189,15 @@ impl std::fmt::Display for PsqlError {
             PsqlError::Execute => write!(f, "Error on execution"),
             PsqlError::Rollback => write!(f, "Error on rollback"),
             PsqlError::Commit => write!(f, "Error on commit"),
+            PsqlError::Srid => write!(f, "Invalid or unknown SRID"),
+            PsqlError::LockTimeout => write!(f, "Timed out waiting for the initialization lock"),
+            PsqlError::Verification => write!(f, "Required tables or columns are missing"),
+            PsqlError::CoordinateOrder => {
+                write!(
+                    f,
+                    "Coordinates changed in a round trip, latitude and longitude may be swapped"
+                )
+            }
         }
     }
 }
@@ -108,7 +208,142 @@ impl std::error::Error for PsqlError {
     }
 }
 
-/// Executes a transaction with multiple statements on the provided pool
+/// Classification of a database error by its SQLSTATE
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DbErrorKind {
+    /// A unique or primary key constraint was violated
+    AlreadyExists,
+
+    /// A foreign key constraint was violated
+    ForeignKeyViolation,
+
+    /// The transaction conflicted with a concurrent one and may be retried
+    SerializationFailure,
+
+    /// The connection to the database was lost or refused
+    Connection,
+
+    /// The statement was cancelled by the statement timeout
+    Timeout,
+
+    /// A write was attempted on a read-only connection, such as a former
+    ///  primary that is now a standby
+    ReadOnly,
+
+    /// Any other database error
+    Other,
+}
+
+impl std::fmt::Display for DbErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbErrorKind::AlreadyExists => write!(f, "Already exists"),
+            DbErrorKind::ForeignKeyViolation => write!(f, "Foreign key violation"),
+            DbErrorKind::SerializationFailure => write!(f, "Serialization failure"),
+            DbErrorKind::Connection => write!(f, "Connection Error"),
+            DbErrorKind::Timeout => write!(f, "Statement timeout"),
+            DbErrorKind::ReadOnly => write!(f, "Read-only transaction"),
+            DbErrorKind::Other => write!(f, "Other Error"),
+        }
+    }
+}
+
+impl DbErrorKind {
+    /// Classifies a SQLSTATE code
+    pub fn from_sqlstate(code: &tokio_postgres::error::SqlState) -> Self {
+        use tokio_postgres::error::SqlState;
+
+        // Class 08 is connection exceptions
+        if code.code().starts_with("08") {
+            return DbErrorKind::Connection;
+        }
+
+        match *code {
+            SqlState::UNIQUE_VIOLATION => DbErrorKind::AlreadyExists,
+            SqlState::FOREIGN_KEY_VIOLATION => DbErrorKind::ForeignKeyViolation,
+            SqlState::T_R_SERIALIZATION_FAILURE | SqlState::T_R_DEADLOCK_DETECTED => {
+                DbErrorKind::SerializationFailure
+            }
+            SqlState::QUERY_CANCELED => DbErrorKind::Timeout,
+            SqlState::READ_ONLY_SQL_TRANSACTION => DbErrorKind::ReadOnly,
+            SqlState::ADMIN_SHUTDOWN
+            | SqlState::CRASH_SHUTDOWN
+            | SqlState::CANNOT_CONNECT_NOW
+            | SqlState::TOO_MANY_CONNECTIONS => DbErrorKind::Connection,
+            _ => DbErrorKind::Other,
+        }
+    }
+}
+
+impl From<&tokio_postgres::Error> for DbErrorKind {
+    fn from(e: &tokio_postgres::Error) -> Self {
+        match e.code() {
+            Some(code) => DbErrorKind::from_sqlstate(code),
+            None if e.is_closed() || is_connection_refused(e) => DbErrorKind::Connection,
+            None => DbErrorKind::Other,
+        }
+    }
+}
+
+/// Returns true if a connection attempt was refused, e.g. while the
+///  database is restarting or its address moves to a new primary
+fn is_connection_refused(e: &tokio_postgres::Error) -> bool {
+    std::error::Error::source(e)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::ConnectionRefused)
+}
+
+/// Returns true if a SQLSTATE suggests the database is no longer (or not
+///  yet) the writable primary
+///
+/// Unlike [`DbErrorKind::Connection`], running out of connections is not
+///  a switchover.
+fn is_failover_sqlstate(code: &tokio_postgres::error::SqlState) -> bool {
+    use tokio_postgres::error::SqlState;
+
+    code.code().starts_with("08")
+        || matches!(
+            *code,
+            SqlState::READ_ONLY_SQL_TRANSACTION
+                | SqlState::ADMIN_SHUTDOWN
+                | SqlState::CRASH_SHUTDOWN
+                | SqlState::CANNOT_CONNECT_NOW
+        )
+}
+
+/// Returns true if a database error suggests a primary switchover
+pub fn is_failover_error(e: &tokio_postgres::Error) -> bool {
+    match e.code() {
+        Some(code) => is_failover_sqlstate(code),
+        None => e.is_closed() || is_connection_refused(e),
+    }
+}
+
+/// Classifies a database error, recycling the pools if it suggests a
+///  primary switchover
+///
+/// Established connections keep talking to the old primary, fresh ones
+///  follow the database address to the new one. See
+///  [`pool::recycle_pools`].
+pub(crate) fn classify_db_error(e: &tokio_postgres::Error) -> DbErrorKind {
+    if is_failover_error(e) {
+        postgis_warn!("(classify_db_error) suspected primary switchover: {}", e);
+        pool::recycle_pools();
+    }
+
+    DbErrorKind::from(e)
+}
+
+/// Classifies an error getting a client from a pool, see
+///  [`classify_db_error`]
+pub(crate) fn classify_pool_error(e: &deadpool_postgres::PoolError) -> Option<DbErrorKind> {
+    match e {
+        deadpool_postgres::PoolError::Backend(e) => Some(classify_db_error(e)),
+        _ => None,
+    }
+}
+
+/// Executes a transaction with multiple statements on the shared pool
 ///  with rollback if any of the statements fail to execute.
 pub async fn psql_transaction(statements: Vec<String>) -> Result<(), PostgisError> {
     let Some(pool) = DEADPOOL_POSTGIS.get() else {
@@ -116,22 +351,71 @@ pub async fn psql_transaction(statements: Vec<String>) -> Result<(), PostgisErro
         return Err(PostgisError::Psql(PsqlError::Connection));
     };
 
+    psql_transaction_on(pool, statements).await
+}
+
+/// Executes a transaction with multiple statements on the provided pool
+///  with rollback if any of the statements fail to execute.
+///
+/// Retried once on fresh connections after a primary switchover.
+pub async fn psql_transaction_on(
+    pool: &deadpool_postgres::Pool,
+    statements: Vec<String>,
+) -> Result<(), PostgisError> {
+    pool::retry_after_failover("psql_transaction", || {
+        psql_transaction_once(pool, statements.clone())
+    })
+    .await
+}
+
+/// Executes a transaction with multiple statements once, see
+///  [`psql_transaction_on`]
+async fn psql_transaction_once(
+    pool: &deadpool_postgres::Pool,
+    statements: Vec<String>,
+) -> Result<(), PostgisError> {
     let mut client = pool.get().await.map_err(|e| {
         postgis_error!(
             "(psql_transaction) could not get client from psql connection pool: {}",
             e
         );
+        classify_pool_error(&e);
         PostgisError::Psql(PsqlError::Client)
     })?;
 
+    psql_transaction_client(&mut client, statements).await
+}
+
+/// Executes a transaction with multiple statements on the provided client,
+///  see [`psql_transaction_on`]
+async fn psql_transaction_client(
+    client: &mut deadpool_postgres::Client,
+    statements: Vec<String>,
+) -> Result<(), PostgisError> {
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!("(psql_transaction) could not create transaction: {}", e);
+        classify_db_error(&e);
         PostgisError::Psql(PsqlError::Client)
     })?;
 
+    // Migrations on large tables may outlast the statement timeout
+    transaction
+        .batch_execute("SET LOCAL statement_timeout = 0;")
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(psql_transaction) could not disable statement timeout: {}",
+                e
+            );
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
     for stmt in statements.into_iter() {
         if let Err(e) = transaction.execute(&stmt, &[]).await {
             postgis_error!("(psql_transaction) Failed to execute statement '{stmt}': {e}");
+            classify_db_error(&e);
 
             transaction.rollback().await.map_err(|e| {
                 postgis_error!("(psql_transaction) Failed to rollback transaction: {}", e);
@@ -144,12 +428,84 @@ pub async fn psql_transaction(statements: Vec<String>) -> Result<(), PostgisErro
 
     transaction.commit().await.map_err(|e| {
         postgis_error!("(psql_transaction) Failed to commit transaction: {}", e);
+        classify_db_error(&e);
         PostgisError::Psql(PsqlError::Commit)
     })?;
 
     Ok(())
 }
 
+/// Commits a transaction, or rolls it back if this is a dry run
+///
+/// Dry runs execute the same statements as real updates, so they fail on
+///  the same constraints; only the final commit is skipped.
+pub(crate) async fn commit_or_rollback(
+    transaction: deadpool_postgres::Transaction<'_>,
+    dry_run: bool,
+) -> Result<(), tokio_postgres::Error> {
+    if dry_run {
+        transaction.rollback().await
+    } else {
+        transaction.commit().await
+    }
+}
+
+/// Returns true if a database error reports a stale cached plan
+///
+/// PostgreSQL refuses to run a prepared statement whose result columns
+///  changed type since it was prepared (e.g. after a migration).
+fn is_stale_plan(code: &tokio_postgres::error::SqlState, message: &str) -> bool {
+    *code == tokio_postgres::error::SqlState::FEATURE_NOT_SUPPORTED
+        && message.contains("cached plan must not change result type")
+}
+
+/// Returns true if a query failed because of a stale cached plan
+pub(crate) fn is_stale_plan_error(e: &tokio_postgres::Error) -> bool {
+    e.as_db_error()
+        .map(|db| is_stale_plan(db.code(), db.message()))
+        .unwrap_or(false)
+}
+
+/// Gets every configured pool: the shared pool, the telemetry pool and
+///  the extra aircraft shard pools
+pub(crate) fn get_all_pools() -> impl Iterator<Item = &'static deadpool_postgres::Pool> {
+    [DEADPOOL_POSTGIS.get(), DEADPOOL_POSTGIS_TELEMETRY.get()]
+        .into_iter()
+        .flatten()
+        .chain(shard::get_extra_shard_pools())
+}
+
+/// Drops the cached statements of every pooled connection
+///
+/// Statements are prepared again against the current schema on next use.
+///  Called after migrations, and when a stale plan is detected.
+pub fn clear_statement_caches() {
+    for pool in get_all_pools() {
+        pool.manager().statement_caches.clear();
+    }
+}
+
+/// Runs a cached query, preparing it again and retrying once if the
+///  cached plan is stale
+pub(crate) async fn query_cached(
+    client: &deadpool_postgres::Client,
+    stmt: &str,
+    params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+) -> Result<Vec<tokio_postgres::Row>, tokio_postgres::Error> {
+    let prepared = client.prepare_cached(stmt).await?;
+    match client.query(&prepared, params).await {
+        Err(e) if is_stale_plan_error(&e) => {
+            postgis_warn!("(query_cached) stale cached plan, clearing statement caches.");
+            client.statement_cache.clear();
+            clear_statement_caches();
+
+            let prepared = client.prepare_cached(stmt).await?;
+            client.query(&prepared, params).await
+        }
+        result => result,
+    }
+}
+
 /// Generates a PostgreSQL enum declaration from a Rust enum
 pub fn psql_enum_declaration<T>(enum_name: &str) -> String
 where
@@ -160,12 +516,16 @@ where
         .collect::<Vec<String>>()
         .join(", ");
 
+    // A concurrent declaration may create the enum between the check and
+    //  the creation
     let declaration = format!(
         "DO $$
     BEGIN
         IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = '{enum_name}') THEN
             CREATE TYPE {enum_name} as ENUM ({fields});
         END IF;
+    EXCEPTION
+        WHEN duplicate_object THEN NULL;
     END $$;"
     );
     postgis_info!("(psql_enum_declaration) {}.", declaration);
@@ -173,13 +533,769 @@ where
     declaration
 }
 
+/// Advisory lock key held while initializing the database, so that only
+///  one replica applies migrations at a time
+const PSQL_INIT_LOCK_KEY: i64 = 0x7376_632d_6769_73; // "svc-gis"
+
+/// Interval between attempts to take the initialization lock
+const PSQL_INIT_LOCK_RETRY_MS: u64 = 500;
+
+/// Version of the schema applied by [`psql_init`]
+///
+/// The declarations of each module are idempotent, this only records
+///  which level a replica brought the database to. Increase it whenever
+///  a declaration changes.
+pub const PSQL_SCHEMA_VERSION: i32 = 6;
+
+/// Gets the name of the table recording applied schema versions
+fn get_schema_version_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."schema_version""#,);
+    FULL_NAME
+}
+
+/// Tables and columns that must exist after initialization
+const REQUIRED_COLUMNS: &[(&str, &[&str])] = &[
+    (
+        "zones",
+        &[
+            "id",
+            "identifier",
+            "zone_type",
+            "geom",
+            "altitude_meters_min",
+            "altitude_meters_max",
+            "time_start",
+            "time_end",
+            "last_updated",
+            "source",
+            "external_reference",
+            "deleted_at",
+            "tags",
+        ],
+    ),
+    (
+        "vertiports",
+        &["identifier", "label", "zone_id", "geom", "altitude_meters"],
+    ),
+    (
+        "aircraft",
+        &["identifier", "session_id", "geom", "operator_id"],
+    ),
+    (
+        "aircraft_history",
+        &[
+            "identifier",
+            "geom",
+            "velocity_horizontal_ground_mps",
+            "velocity_horizontal_air_mps",
+            "velocity_vertical_mps",
+            "track_angle_degrees",
+            "timestamp_network",
+            "timestamp_asset",
+        ],
+    ),
+    (
+        "aircraft_history_archive",
+        &[
+            "identifier",
+            "bucket_start",
+            "bucket_seconds",
+            "first_geom",
+            "first_timestamp",
+            "last_geom",
+            "last_timestamp",
+            "altitude_meters_min",
+            "altitude_meters_max",
+            "velocity_horizontal_ground_mps_avg",
+            "point_count",
+        ],
+    ),
+    (
+        "aircraft_status_history",
+        &["identifier", "op_status", "previous_status", "timestamp"],
+    ),
+    ("waypoints", &["identifier", "geog"]),
+    (
+        "flights",
+        &[
+            "flight_identifier",
+            "aircraft_identifier",
+            "geom",
+            "isa",
+            "time_start",
+            "time_end",
+            "operator_id",
+            "throughput_recorded",
+            "deleted_at",
+            "status_reason",
+            "tags",
+            "destination_identifier",
+            "reserved_until",
+            "length_meters",
+        ],
+    ),
+    (
+        "flight_segments",
+        &["flight_identifier", "geom", "time_start", "time_end"],
+    ),
+    (
+        "flight_rebinds",
+        &[
+            "flight_identifier",
+            "aircraft_identifier",
+            "timestamp_rebind",
+        ],
+    ),
+    (
+        "flight_aircraft",
+        &["flight_identifier", "aircraft_identifier"],
+    ),
+    (
+        "flight_conflict_checks",
+        &["flight_identifier", "conflict_count", "checked_at"],
+    ),
+    ("telemetry_identifiers", &["index", "identifier"]),
+    (
+        "vertiport_throughput",
+        &["vertiport_identifier", "hour", "departures", "arrivals"],
+    ),
+    ("event_backlog", &["id", "kind", "payload", "created_at"]),
+    (
+        "aircraft_type_history",
+        &["identifier", "aircraft_type", "previous_type", "timestamp"],
+    ),
+    (
+        "corridors",
+        &[
+            "identifier",
+            "geom",
+            "width_meters",
+            "height_meters",
+            "capacity",
+            "last_updated",
+        ],
+    ),
+];
+
+/// Typed geometry columns that must have [`DEFAULT_SRID`] and a Z dimension
+const REQUIRED_GEOMETRY_COLUMNS: &[(&str, &str, &str)] = &[
+    ("zones", "geom", "POLYHEDRALSURFACE"),
+    ("aircraft", "geom", "POINT"),
+    ("aircraft_history", "geom", "POINT"),
+    ("flights", "geom", "LINESTRING"),
+    ("flight_segments", "geom", "LINESTRING"),
+    ("aircraft_history_archive", "first_geom", "POINT"),
+    ("aircraft_history_archive", "last_geom", "POINT"),
+    ("corridors", "geom", "LINESTRING"),
+];
+
+/// A geometry column as reported by the PostGIS `geometry_columns` view
+#[derive(Debug, Clone, PartialEq)]
+struct GeometryColumn {
+    table: String,
+    column: String,
+    geometry_type: String,
+    srid: i32,
+    coord_dimension: i32,
+}
+
+/// Lists the required geometry columns that are missing or don't have
+///  the expected type, SRID or Z dimension
+fn geometry_column_issues(columns: &[GeometryColumn]) -> Vec<String> {
+    REQUIRED_GEOMETRY_COLUMNS
+        .iter()
+        .filter_map(|(table, column, geometry_type)| {
+            let Some(found) = columns
+                .iter()
+                .find(|c| c.table == *table && c.column == *column)
+            else {
+                return Some(format!("{table}.{column} is missing"));
+            };
+
+            if found.srid != DEFAULT_SRID {
+                return Some(format!(
+                    "{table}.{column} has SRID {}, expected {DEFAULT_SRID}",
+                    found.srid
+                ));
+            }
+
+            if found.coord_dimension != 3 {
+                return Some(format!(
+                    "{table}.{column} has {} dimensions, expected 3",
+                    found.coord_dimension
+                ));
+            }
+
+            if !found.geometry_type.eq_ignore_ascii_case(geometry_type) {
+                return Some(format!(
+                    "{table}.{column} has type {}, expected {geometry_type}",
+                    found.geometry_type
+                ));
+            }
+
+            None
+        })
+        .collect()
+}
+
+/// Confirms that the geometry columns have the expected SRID and Z dimension
+async fn psql_verify_geometry(client: &deadpool_postgres::Client) -> Result<(), PostgisError> {
+    let columns = client
+        .query(
+            r#"SELECT
+                "f_table_name"::TEXT AS "table",
+                "f_geometry_column"::TEXT AS "column",
+                "type"::TEXT AS "geometry_type",
+                "srid",
+                "coord_dimension"
+            FROM geometry_columns
+            WHERE "f_table_schema" = $1;"#,
+            &[&PSQL_SCHEMA],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(psql_verify_geometry) could not execute query: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?
+        .into_iter()
+        .map(|row| {
+            Ok(GeometryColumn {
+                table: row.try_get("table")?,
+                column: row.try_get("column")?,
+                geometry_type: row.try_get("geometry_type")?,
+                srid: row.try_get("srid")?,
+                coord_dimension: row.try_get("coord_dimension")?,
+            })
+        })
+        .collect::<Result<Vec<GeometryColumn>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(psql_verify_geometry) could not get column data: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let issues = geometry_column_issues(&columns);
+    if !issues.is_empty() {
+        postgis_error!(
+            "(psql_verify_geometry) geometry columns don't match: {}",
+            issues.join(", ")
+        );
+        return Err(PostgisError::Psql(PsqlError::Verification));
+    }
+
+    postgis_info!("(psql_verify_geometry) all geometry columns match.");
+    Ok(())
+}
+
+/// Confirms that a known point survives the conversion to a PostGIS point,
+///  a round trip through the database and the conversion back
+///
+/// PostGIS points are (x = longitude, y = latitude), the gRPC types name
+///  the fields instead, so a swap would place every aircraft and flight
+///  in the wrong hemisphere.
+async fn psql_verify_coordinate_order(
+    client: &deadpool_postgres::Client,
+) -> Result<(), PostgisError> {
+    let original = utils::COORDINATE_CHECK_POINT;
+    let point = postgis::ewkb::PointZ::try_from(original.clone()).map_err(|_| {
+        postgis_error!("(psql_verify_coordinate_order) could not convert the check point.");
+        PostgisError::Psql(PsqlError::CoordinateOrder)
+    })?;
+
+    // ST_X also catches swaps that cancel out between the two conversions
+    let row = client
+        .query_one(
+            r#"SELECT $1::GEOMETRY AS "geom", ST_X($1::GEOMETRY) AS "longitude";"#,
+            &[&point],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(psql_verify_coordinate_order) could not execute query: {}",
+                e
+            );
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let stored: postgis::ewkb::PointZ = row.try_get("geom").map_err(|e| {
+        postgis_error!("(psql_verify_coordinate_order) could not get point: {}", e);
+        PostgisError::Psql(PsqlError::Execute)
+    })?;
+
+    let longitude: f64 = row.try_get("longitude").map_err(|e| {
+        postgis_error!(
+            "(psql_verify_coordinate_order) could not get longitude: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Execute)
+    })?;
+
+    let round_trip = crate::grpc::server::grpc_server::PointZ::from(stored);
+    if let Some(issue) = utils::coordinate_order_issue(&original, &round_trip) {
+        postgis_error!("(psql_verify_coordinate_order) {}", issue);
+        return Err(PostgisError::Psql(PsqlError::CoordinateOrder));
+    }
+
+    let x_position = crate::grpc::server::grpc_server::PointZ {
+        longitude,
+        ..original.clone()
+    };
+    if utils::coordinate_order_issue(&original, &x_position).is_some() {
+        postgis_error!(
+            "(psql_verify_coordinate_order) PostGIS reads x = {}, expected the longitude {}.",
+            longitude,
+            original.longitude
+        );
+        return Err(PostgisError::Psql(PsqlError::CoordinateOrder));
+    }
+
+    postgis_info!("(psql_verify_coordinate_order) coordinates survive a round trip.");
+    Ok(())
+}
+
+/// Takes the initialization advisory lock on the given client, retrying
+///  until the timeout elapses
+///
+/// The lock is held by the client's session until released or the
+///  connection closes.
+async fn psql_init_lock(
+    client: &deadpool_postgres::Client,
+    timeout_secs: u64,
+) -> Result<(), PostgisError> {
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(timeout_secs);
+
+    loop {
+        let locked: bool = client
+            .query_one("SELECT pg_try_advisory_lock($1);", &[&PSQL_INIT_LOCK_KEY])
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|e| {
+                postgis_error!("(psql_init_lock) could not request lock: {}", e);
+                PostgisError::Psql(PsqlError::Execute)
+            })?;
+
+        if locked {
+            postgis_info!("(psql_init_lock) acquired initialization lock.");
+            return Ok(());
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            postgis_error!(
+                "(psql_init_lock) timed out after {}s waiting for initialization lock.",
+                timeout_secs
+            );
+            return Err(PostgisError::Psql(PsqlError::LockTimeout));
+        }
+
+        postgis_info!("(psql_init_lock) waiting for another replica to finish initialization.");
+        tokio::time::sleep(std::time::Duration::from_millis(PSQL_INIT_LOCK_RETRY_MS)).await;
+    }
+}
+
+/// Releases the initialization advisory lock
+async fn psql_init_unlock(client: &deadpool_postgres::Client) -> Result<(), PostgisError> {
+    client
+        .execute("SELECT pg_advisory_unlock($1);", &[&PSQL_INIT_LOCK_KEY])
+        .await
+        .map_err(|e| {
+            postgis_error!("(psql_init_unlock) could not release lock: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    postgis_info!("(psql_init_unlock) released initialization lock.");
+    Ok(())
+}
+
+/// Confirms that the required tables and columns exist
+pub async fn psql_verify(client: &deadpool_postgres::Client) -> Result<(), PostgisError> {
+    let stmt = client
+        .prepare_cached(
+            r#"SELECT "column_name"::TEXT FROM information_schema.columns
+            WHERE "table_schema" = $1 AND "table_name" = $2;"#,
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(psql_verify) could not prepare cached statement: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let mut missing: Vec<String> = vec![];
+    for (table, columns) in REQUIRED_COLUMNS {
+        let existing = client
+            .query(&stmt, &[&PSQL_SCHEMA, table])
+            .await
+            .map_err(|e| {
+                postgis_error!("(psql_verify) could not execute query: {}", e);
+                PostgisError::Psql(PsqlError::Execute)
+            })?
+            .into_iter()
+            .map(|row| row.try_get::<_, String>(0))
+            .collect::<Result<Vec<String>, tokio_postgres::error::Error>>()
+            .map_err(|e| {
+                postgis_error!("(psql_verify) could not get column data: {}", e);
+                PostgisError::Psql(PsqlError::Execute)
+            })?;
+
+        missing.extend(
+            columns
+                .iter()
+                .filter(|c| !existing.iter().any(|e| e == *c))
+                .map(|c| format!("{table}.{c}")),
+        );
+    }
+
+    if !missing.is_empty() {
+        postgis_error!("(psql_verify) missing columns: {}", missing.join(", "));
+        return Err(PostgisError::Psql(PsqlError::Verification));
+    }
+
+    postgis_info!("(psql_verify) all required tables and columns exist.");
+    Ok(())
+}
+
+/// Records that [`PSQL_SCHEMA_VERSION`] was applied, by which service version
+async fn psql_record_schema_version(
+    client: &deadpool_postgres::Client,
+) -> Result<(), PostgisError> {
+    let statements = [
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "version" INTEGER PRIMARY KEY,
+                "service_version" TEXT NOT NULL,
+                "applied_at" TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );"#,
+            table_name = get_schema_version_table_name(),
+        ),
+        format!(
+            r#"INSERT INTO {table_name} ("version", "service_version")
+                VALUES ({PSQL_SCHEMA_VERSION}, '{service_version}')
+                ON CONFLICT ("version") DO NOTHING;"#,
+            table_name = get_schema_version_table_name(),
+            service_version = env!("CARGO_PKG_VERSION"),
+        ),
+    ];
+
+    for statement in statements {
+        client.execute(&statement, &[]).await.map_err(|e| {
+            postgis_error!(
+                "(psql_record_schema_version) could not execute statement: {}",
+                e
+            );
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+    }
+
+    postgis_info!(
+        "(psql_record_schema_version) schema version {} recorded.",
+        PSQL_SCHEMA_VERSION
+    );
+    Ok(())
+}
+
+/// Gets the highest schema version applied to the database
+///
+/// Returns `None` if no replica recorded a version yet.
+pub async fn get_schema_version() -> Result<Option<i32>, PostgisError> {
+    let Some(pool) = DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_schema_version) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Connection));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_schema_version) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    // The table only exists once a replica completed initialization
+    let exists: bool = client
+        .query_one(
+            "SELECT to_regclass($1) IS NOT NULL;",
+            &[&get_schema_version_table_name()],
+        )
+        .await
+        .and_then(|row| row.try_get(0))
+        .map_err(|e| {
+            postgis_error!("(get_schema_version) could not execute query: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    if !exists {
+        return Ok(None);
+    }
+
+    let stmt = format!(
+        r#"SELECT MAX("version") FROM {table_name};"#,
+        table_name = get_schema_version_table_name(),
+    );
+
+    client
+        .query_one(&stmt, &[])
+        .await
+        .and_then(|row| row.try_get(0))
+        .map_err(|e| {
+            postgis_error!("(get_schema_version) could not execute query: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })
+}
+
+/// Table and enum declarations of each module, in dependency order
+///
+/// Vertiports reference zones. Every module declares the enums it uses,
+///  so the aircraft type enum is declared by aircraft and flights alike.
+fn psql_init_modules() -> Vec<(&'static str, Vec<String>)> {
+    vec![
+        ("zone", zone::psql_init_statements()),
+        ("vertiport", vertiport::psql_init_statements()),
+        ("aircraft", aircraft::psql_init_statements()),
+        ("archive", archive::psql_init_statements()),
+        ("waypoint", waypoint::psql_init_statements()),
+        ("flight", flight::psql_init_statements()),
+        ("conflict_check", conflict_check::psql_init_statements()),
+        ("telemetry", telemetry::psql_init_statements()),
+        ("throughput", throughput::psql_init_statements()),
+        ("backlog", backlog::psql_init_statements()),
+        ("corridor", corridor::psql_init_statements()),
+    ]
+}
+
+/// Applies the table and enum declarations of each module on the provided
+///  pool, in dependency order
+///
+/// Each module is applied in its own transaction: a module that fails
+///  leaves the ones before it in place and nothing of its own. Every
+///  declaration is idempotent, so running this again on an initialized
+///  database changes nothing.
+pub async fn init_all(pool: &deadpool_postgres::Pool) -> Result<(), PostgisError> {
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(init_all) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    init_all_on(&mut client).await
+}
+
+/// Applies the table and enum declarations of each module on the provided
+///  client, see [`init_all`]
+async fn init_all_on(client: &mut deadpool_postgres::Client) -> Result<(), PostgisError> {
+    for (module, statements) in psql_init_modules() {
+        postgis_debug!("(init_all) initializing {module}.");
+        psql_transaction_client(client, statements)
+            .await
+            .map_err(|e| {
+                postgis_error!("(init_all) could not initialize {module}: {}", e);
+                e
+            })?;
+    }
+
+    Ok(())
+}
+
 /// Initializes the PostgreSQL database with the required tables and enums
-pub async fn psql_init() -> Result<(), Box<dyn std::error::Error>> {
-    zone::psql_init().await?;
-    vertiport::psql_init().await?;
-    aircraft::psql_init().await?;
-    waypoint::psql_init().await?;
-    flight::psql_init().await?;
+///
+/// Replicas starting at the same time take turns through an advisory
+///  lock, waiting at most `lock_timeout_secs`. The schema, including the
+///  SRID and dimension of geometry columns, is verified before returning.
+pub async fn psql_init(lock_timeout_secs: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(pool) = DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(psql_init) could not get psql pool.");
+        return Err(Box::new(PostgisError::Psql(PsqlError::Connection)));
+    };
+
+    // The migrations run on the client holding the session lock, so that
+    //  a pool of a single connection (or one saturated at startup) isn't
+    //  waited on while the lock is held
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(psql_init) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    psql_init_lock(&client, lock_timeout_secs).await?;
+    let result = async {
+        init_all_on(&mut client).await?;
+        psql_verify(&client).await?;
+        psql_verify_geometry(&client).await?;
+        psql_verify_coordinate_order(&client).await?;
+        psql_record_schema_version(&client).await?;
+        Ok::<(), PostgisError>(())
+    }
+    .await;
+
+    // Release even on failure so other replicas can retry
+    psql_init_unlock(&client).await?;
+    result?;
+
+    shard::psql_init_shards().await?;
+
+    // Statements prepared before the migrations may no longer match
+    clear_statement_caches();
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ut_psql_init_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_psql_init_client_failure) start");
+
+        let error = psql_init(1).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            PostgisError::Psql(PsqlError::Connection).to_string()
+        );
+
+        ut_info!("(ut_psql_init_client_failure) success");
+    }
+
+    fn geometry_columns() -> Vec<GeometryColumn> {
+        REQUIRED_GEOMETRY_COLUMNS
+            .iter()
+            .map(|(table, column, geometry_type)| GeometryColumn {
+                table: table.to_string(),
+                column: column.to_string(),
+                geometry_type: geometry_type.to_string(),
+                srid: DEFAULT_SRID,
+                coord_dimension: 3,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ut_geometry_column_issues() {
+        let columns = geometry_columns();
+        assert!(geometry_column_issues(&columns).is_empty());
+
+        // SRID drift
+        let mut mismatched = columns.clone();
+        mismatched[1].srid = 0;
+        let issues = geometry_column_issues(&mismatched);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("aircraft.geom has SRID 0"));
+
+        // 2D column
+        let mut mismatched = columns.clone();
+        mismatched[3].coord_dimension = 2;
+        let issues = geometry_column_issues(&mismatched);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("flights.geom has 2 dimensions"));
+
+        // Wrong type
+        let mut mismatched = columns.clone();
+        mismatched[2].geometry_type = "LINESTRING".to_string();
+        assert_eq!(geometry_column_issues(&mismatched).len(), 1);
+
+        // Missing column
+        let issues = geometry_column_issues(&columns[1..]);
+        assert_eq!(issues, vec!["zones.geom is missing".to_string()]);
+    }
+
+    #[test]
+    fn ut_required_columns_unique() {
+        for (i, (table, columns)) in REQUIRED_COLUMNS.iter().enumerate() {
+            assert!(!REQUIRED_COLUMNS[i + 1..].iter().any(|(t, _)| t == table));
+            for (j, column) in columns.iter().enumerate() {
+                assert!(!columns[j + 1..].contains(column));
+            }
+        }
+    }
+
+    #[test]
+    fn ut_psql_init_modules_order() {
+        let modules: Vec<&str> = psql_init_modules()
+            .iter()
+            .map(|(module, _)| *module)
+            .collect();
+        let position = |module: &str| modules.iter().position(|m| *m == module).unwrap();
+
+        assert!(position("zone") < position("vertiport"));
+        for (module, statements) in psql_init_modules() {
+            assert!(!statements.is_empty(), "{module}");
+        }
+
+        // Flights declare the enum themselves
+        let (_, statements) = psql_init_modules()
+            .into_iter()
+            .find(|(module, _)| *module == "flight")
+            .unwrap();
+        assert!(statements[0].contains("CREATE TYPE aircrafttype"));
+        assert!(statements[0].contains("duplicate_object"));
+    }
+
+    #[test]
+    fn ut_is_stale_plan() {
+        use tokio_postgres::error::SqlState;
+
+        assert!(is_stale_plan(
+            &SqlState::FEATURE_NOT_SUPPORTED,
+            "cached plan must not change result type"
+        ));
+        assert!(!is_stale_plan(
+            &SqlState::FEATURE_NOT_SUPPORTED,
+            "some other unsupported feature"
+        ));
+        assert!(!is_stale_plan(
+            &SqlState::UNDEFINED_COLUMN,
+            "cached plan must not change result type"
+        ));
+    }
+
+    #[test]
+    fn ut_db_error_kind_from_sqlstate() {
+        use tokio_postgres::error::SqlState;
+
+        let cases = [
+            (SqlState::UNIQUE_VIOLATION, DbErrorKind::AlreadyExists),
+            (
+                SqlState::FOREIGN_KEY_VIOLATION,
+                DbErrorKind::ForeignKeyViolation,
+            ),
+            (
+                SqlState::T_R_SERIALIZATION_FAILURE,
+                DbErrorKind::SerializationFailure,
+            ),
+            (
+                SqlState::T_R_DEADLOCK_DETECTED,
+                DbErrorKind::SerializationFailure,
+            ),
+            (SqlState::CONNECTION_FAILURE, DbErrorKind::Connection),
+            (SqlState::CONNECTION_EXCEPTION, DbErrorKind::Connection),
+            (SqlState::ADMIN_SHUTDOWN, DbErrorKind::Connection),
+            (SqlState::TOO_MANY_CONNECTIONS, DbErrorKind::Connection),
+            (SqlState::QUERY_CANCELED, DbErrorKind::Timeout),
+            (SqlState::READ_ONLY_SQL_TRANSACTION, DbErrorKind::ReadOnly),
+            (SqlState::NOT_NULL_VIOLATION, DbErrorKind::Other),
+            (SqlState::RAISE_EXCEPTION, DbErrorKind::Other),
+            (SqlState::from_code("XX999"), DbErrorKind::Other),
+        ];
+
+        for (code, kind) in cases {
+            assert_eq!(DbErrorKind::from_sqlstate(&code), kind, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn ut_is_failover_sqlstate() {
+        use tokio_postgres::error::SqlState;
+
+        assert!(is_failover_sqlstate(&SqlState::READ_ONLY_SQL_TRANSACTION));
+        assert!(is_failover_sqlstate(&SqlState::CONNECTION_FAILURE));
+        assert!(is_failover_sqlstate(&SqlState::ADMIN_SHUTDOWN));
+        assert!(is_failover_sqlstate(&SqlState::CANNOT_CONNECT_NOW));
+
+        // Not a switchover
+        assert!(!is_failover_sqlstate(&SqlState::TOO_MANY_CONNECTIONS));
+        assert!(!is_failover_sqlstate(&SqlState::QUERY_CANCELED));
+        assert!(!is_failover_sqlstate(&SqlState::UNIQUE_VIOLATION));
+    }
+}
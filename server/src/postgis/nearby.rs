@@ -0,0 +1,295 @@
+//! Vertiports, waypoints and aircraft near a point
+//!
+//! Builds a local airspace picture in one query instead of one per node
+//!  type.
+
+use super::PostgisError;
+use crate::grpc::server::grpc_server::{Coordinates, NodeType};
+use num_traits::FromPrimitive;
+use postgis::ewkb::PointZ;
+
+/// Max search radius
+pub const MAX_NEARBY_RADIUS_METERS: f64 = 50_000.0;
+
+/// Possible errors getting nearby nodes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NearbyError {
+    /// Invalid center point
+    Location,
+
+    /// Radius not positive or above [`MAX_NEARBY_RADIUS_METERS`]
+    Radius,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for NearbyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NearbyError::Location => write!(f, "Invalid location provided."),
+            NearbyError::Radius => write!(
+                f,
+                "The radius must be positive and at most {MAX_NEARBY_RADIUS_METERS} meters."
+            ),
+            NearbyError::Client => write!(f, "Could not get backend client."),
+            NearbyError::DBError => write!(f, "Database error."),
+        }
+    }
+}
+
+/// A node near a point
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearbyNode {
+    /// Type of the node
+    pub node_type: NodeType,
+
+    /// Identifier of the node
+    pub identifier: String,
+
+    /// Position of the node, the centroid at the declared altitude for
+    ///  vertiports and at ground level for waypoints
+    pub geom: PointZ,
+
+    /// Distance from the point, ignoring altitude
+    pub distance_meters: f64,
+}
+
+/// Checks the radius of a nearby query
+fn validate_radius(radius_meters: f64) -> Result<(), NearbyError> {
+    if !radius_meters.is_finite()
+        || radius_meters <= 0.0
+        || radius_meters > MAX_NEARBY_RADIUS_METERS
+    {
+        postgis_error!("(validate_radius) invalid radius: {}", radius_meters);
+        return Err(NearbyError::Radius);
+    }
+
+    Ok(())
+}
+
+/// Gets the vertiports, waypoints and aircraft within a radius of a point,
+///  nearest first
+///
+/// Aircraft without a known position are skipped.
+pub async fn get_nodes_near(
+    point: &Coordinates,
+    radius_meters: f64,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<NearbyNode>, PostgisError> {
+    postgis_debug!("(get_nodes_near) entry.");
+    let center = super::utils::point_from_vertex(point).map_err(|e| {
+        postgis_error!("(get_nodes_near) invalid point: {}", e);
+        PostgisError::Nearby(NearbyError::Location)
+    })?;
+    validate_radius(radius_meters).map_err(PostgisError::Nearby)?;
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_nodes_near) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Nearby(NearbyError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT "node_type", "identifier", "geom", "distance_meters"
+            FROM (
+                SELECT
+                    {vertiport}::INTEGER AS "node_type",
+                    "identifier",
+                    ST_Force3DZ(ST_Centroid("geom"), COALESCE("altitude_meters", 0)) AS "geom",
+                    ST_Distance(ST_Centroid("geom")::GEOGRAPHY, $1::GEOGRAPHY, false) AS "distance_meters"
+                FROM {vertiports_table_name}
+                WHERE "geom" IS NOT NULL
+                    AND ST_DWithin(ST_Centroid("geom")::GEOGRAPHY, $1::GEOGRAPHY, $2, false)
+                UNION ALL
+                SELECT
+                    {waypoint}::INTEGER,
+                    "identifier",
+                    ST_Force3DZ("geog"::GEOMETRY),
+                    ST_Distance("geog", $1::GEOGRAPHY, false)
+                FROM {waypoints_table_name}
+                WHERE ST_DWithin("geog", $1::GEOGRAPHY, $2, false)
+                UNION ALL
+                SELECT
+                    {aircraft}::INTEGER,
+                    "identifier",
+                    "geom",
+                    ST_Distance("geom"::GEOGRAPHY, $1::GEOGRAPHY, false)
+                FROM {aircraft_table_name}
+                WHERE "geom" IS NOT NULL
+                    AND ST_DWithin("geom"::GEOGRAPHY, $1::GEOGRAPHY, $2, false)
+            ) AS "nodes"
+            ORDER BY "distance_meters", "node_type", "identifier";"#,
+            vertiport = NodeType::Vertiport as i32,
+            waypoint = NodeType::Waypoint as i32,
+            aircraft = NodeType::Aircraft as i32,
+            vertiports_table_name = super::vertiport::get_table_name(),
+            waypoints_table_name = super::waypoint::get_table_name(),
+            aircraft_table_name = super::aircraft::position_source(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_nodes_near) could not prepare cached statement: {}", e);
+            PostgisError::Nearby(NearbyError::DBError)
+        })?;
+
+    let mut nodes = client
+        .query(&stmt, &[&center, &radius_meters])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_nodes_near) could not execute query: {}", e);
+            PostgisError::Nearby(NearbyError::DBError)
+        })?
+        .iter()
+        .map(node_from_row)
+        .collect::<Result<Vec<_>, PostgisError>>()?;
+
+    // Aircraft on other shards are merged in by distance
+    if super::shard::is_sharded() {
+        for pool in super::shard::get_extra_shard_pools() {
+            nodes.extend(get_shard_aircraft_near(&center, radius_meters, pool).await?);
+        }
+
+        nodes = merge_nodes(nodes);
+    }
+
+    postgis_debug!("(get_nodes_near) found {} nodes.", nodes.len());
+    Ok(nodes)
+}
+
+/// Reads a nearby node
+fn node_from_row(row: &tokio_postgres::Row) -> Result<NearbyNode, PostgisError> {
+    let node_type: i32 = row.try_get("node_type").map_err(|e| {
+        postgis_error!("(node_from_row) could not get node_type: {}", e);
+        PostgisError::Nearby(NearbyError::DBError)
+    })?;
+
+    let Some(node_type) = FromPrimitive::from_i32(node_type) else {
+        postgis_error!("(node_from_row) unknown node_type: {}", node_type);
+        return Err(PostgisError::Nearby(NearbyError::DBError));
+    };
+
+    Ok(NearbyNode {
+        node_type,
+        identifier: row.try_get("identifier").map_err(|e| {
+            postgis_error!("(node_from_row) could not get identifier: {}", e);
+            PostgisError::Nearby(NearbyError::DBError)
+        })?,
+        geom: row.try_get("geom").map_err(|e| {
+            postgis_error!("(node_from_row) could not get geom: {}", e);
+            PostgisError::Nearby(NearbyError::DBError)
+        })?,
+        distance_meters: row.try_get("distance_meters").map_err(|e| {
+            postgis_error!("(node_from_row) could not get distance: {}", e);
+            PostgisError::Nearby(NearbyError::DBError)
+        })?,
+    })
+}
+
+/// Orders nodes found on several shards as a single query would, an
+///  aircraft found on more than one shard is kept once, at its nearest
+fn merge_nodes(mut nodes: Vec<NearbyNode>) -> Vec<NearbyNode> {
+    nodes.sort_by(|a, b| {
+        a.distance_meters
+            .total_cmp(&b.distance_meters)
+            .then((a.node_type as i32).cmp(&(b.node_type as i32)))
+            .then(a.identifier.cmp(&b.identifier))
+    });
+
+    let mut seen = std::collections::HashSet::new();
+    nodes.retain(|node| seen.insert((node.node_type as i32, node.identifier.clone())));
+    nodes
+}
+
+/// Gets the aircraft within a radius of a point on an aircraft shard
+async fn get_shard_aircraft_near(
+    center: &PointZ,
+    radius_meters: f64,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<NearbyNode>, PostgisError> {
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_shard_aircraft_near) could not get client from shard connection pool: {}",
+            e
+        );
+        PostgisError::Nearby(NearbyError::Client)
+    })?;
+
+    let stmt = format!(
+        r#"SELECT
+                {aircraft}::INTEGER AS "node_type",
+                "identifier",
+                "geom",
+                ST_Distance("geom"::GEOGRAPHY, $1::GEOGRAPHY, false) AS "distance_meters"
+            FROM {aircraft_table_name}
+            WHERE "geom" IS NOT NULL
+                AND ST_DWithin("geom"::GEOGRAPHY, $1::GEOGRAPHY, $2, false);"#,
+        aircraft = NodeType::Aircraft as i32,
+        aircraft_table_name = super::aircraft::position_source(),
+    );
+
+    super::query_cached(&client, &stmt, &[center, &radius_meters])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_shard_aircraft_near) could not execute query: {}", e);
+            PostgisError::Nearby(NearbyError::DBError)
+        })?
+        .iter()
+        .map(node_from_row)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_merge_nodes() {
+        let node = |node_type: NodeType, identifier: &str, distance_meters: f64| NearbyNode {
+            node_type,
+            identifier: identifier.to_string(),
+            geom: PointZ::new(4.9, 52.3, 0.0, Some(super::super::DEFAULT_SRID)),
+            distance_meters,
+        };
+
+        let nodes = merge_nodes(vec![
+            node(NodeType::Vertiport, "VP-1", 300.0),
+            node(NodeType::Aircraft, "N12345", 100.0),
+            node(NodeType::Waypoint, "WP-1", 100.0),
+            node(NodeType::Aircraft, "N12345", 150.0),
+            node(NodeType::Aircraft, "N00001", 100.0),
+        ]);
+
+        assert_eq!(
+            nodes,
+            vec![
+                node(NodeType::Waypoint, "WP-1", 100.0),
+                node(NodeType::Aircraft, "N00001", 100.0),
+                node(NodeType::Aircraft, "N12345", 100.0),
+                node(NodeType::Vertiport, "VP-1", 300.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn ut_validate_radius() {
+        assert!(validate_radius(1.0).is_ok());
+        assert!(validate_radius(MAX_NEARBY_RADIUS_METERS).is_ok());
+
+        for radius in [
+            0.0,
+            -1.0,
+            f64::NAN,
+            f64::INFINITY,
+            MAX_NEARBY_RADIUS_METERS + 1.0,
+        ] {
+            assert_eq!(validate_radius(radius).unwrap_err(), NearbyError::Radius);
+        }
+    }
+}
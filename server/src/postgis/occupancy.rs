@@ -0,0 +1,412 @@
+//! This module contains functions for reporting how many flight segments
+//!  and aircraft occupy each altitude band of an area.
+//!
+//! A flight segment occupies every band its altitude range overlaps during
+//!  the time window. Aircraft are counted at their last reported position.
+
+use super::shard::ShardRows;
+use super::PostgisError;
+use crate::grpc::server::grpc_server::{
+    AltitudeBand, GetAltitudeOccupancyRequest, GetAltitudeOccupancyResponse,
+};
+use chrono::{DateTime, Duration, Utc};
+use postgis::ewkb::PolygonZ;
+use tokio_postgres::types::ToSql;
+
+/// Max number of altitude bands in a single query
+pub const MAX_ALTITUDE_BANDS: i32 = 100;
+
+/// Max time range of an occupancy query
+pub const MAX_OCCUPANCY_RANGE_HOURS: i64 = 24;
+
+/// Aircraft that haven't reported a position for longer are not counted
+pub const OCCUPANCY_MAX_POSITION_AGE_SECS: i64 = 60;
+
+/// Possible errors with altitude occupancy
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OccupancyError {
+    /// Invalid area
+    Location,
+
+    /// Invalid altitude range or band height
+    Altitude,
+
+    /// Invalid time range
+    Time,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for OccupancyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OccupancyError::Location => write!(f, "Invalid location provided."),
+            OccupancyError::Altitude => write!(f, "Invalid altitude bands provided."),
+            OccupancyError::Time => write!(f, "Invalid time range provided."),
+            OccupancyError::Client => write!(f, "Could not get backend client."),
+            OccupancyError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// A validated occupancy request
+#[derive(Debug, Clone)]
+struct OccupancyQuery {
+    area: PolygonZ,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    altitude_meters_min: f64,
+    band_height_meters: f64,
+    band_count: i32,
+}
+
+/// Validates an occupancy request
+///
+/// The highest band is extended to a whole band height if the altitude
+///  range isn't a multiple of it.
+fn validate_occupancy_request(
+    request: &GetAltitudeOccupancyRequest,
+) -> Result<OccupancyQuery, OccupancyError> {
+    let area = super::utils::polygon_from_vertices_z(&request.vertices, 0.0).map_err(|e| {
+        postgis_error!("(validate_occupancy_request) invalid area: {}", e);
+        OccupancyError::Location
+    })?;
+
+    let (Some(time_start), Some(time_end)) = (request.time_start.clone(), request.time_end.clone())
+    else {
+        postgis_error!("(validate_occupancy_request) time_start and time_end are required.");
+        return Err(OccupancyError::Time);
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end <= time_start {
+        postgis_error!("(validate_occupancy_request) time_end must be after time_start.");
+        return Err(OccupancyError::Time);
+    }
+
+    let Some(max_range) = Duration::try_hours(MAX_OCCUPANCY_RANGE_HOURS) else {
+        postgis_error!("(validate_occupancy_request) could not get max time range.");
+        return Err(OccupancyError::Time);
+    };
+
+    if time_end - time_start > max_range {
+        postgis_error!(
+            "(validate_occupancy_request) time range exceeds {} hours.",
+            MAX_OCCUPANCY_RANGE_HOURS
+        );
+        return Err(OccupancyError::Time);
+    }
+
+    let altitude_meters_min = request.altitude_meters_min as f64;
+    let altitude_meters_max = request.altitude_meters_max as f64;
+    let band_height_meters = request.band_height_meters as f64;
+    if !altitude_meters_min.is_finite()
+        || !altitude_meters_max.is_finite()
+        || altitude_meters_max <= altitude_meters_min
+    {
+        postgis_error!(
+            "(validate_occupancy_request) invalid altitude range: {} to {}",
+            altitude_meters_min,
+            altitude_meters_max
+        );
+        return Err(OccupancyError::Altitude);
+    }
+
+    if !band_height_meters.is_finite() || band_height_meters <= 0.0 {
+        postgis_error!(
+            "(validate_occupancy_request) invalid band height: {}",
+            band_height_meters
+        );
+        return Err(OccupancyError::Altitude);
+    }
+
+    let band_count = ((altitude_meters_max - altitude_meters_min) / band_height_meters).ceil();
+    if band_count > MAX_ALTITUDE_BANDS as f64 {
+        postgis_error!(
+            "(validate_occupancy_request) {} bands exceeds the max of {}.",
+            band_count,
+            MAX_ALTITUDE_BANDS
+        );
+        return Err(OccupancyError::Altitude);
+    }
+
+    Ok(OccupancyQuery {
+        area,
+        time_start,
+        time_end,
+        altitude_meters_min,
+        band_height_meters,
+        band_count: band_count as i32,
+    })
+}
+
+/// Gets the number of flight segments and aircraft in each altitude band
+///  of an area during a time window
+///
+/// Every band is returned, from lowest to highest, even if empty.
+pub async fn get_altitude_occupancy(
+    request: GetAltitudeOccupancyRequest,
+) -> Result<GetAltitudeOccupancyResponse, PostgisError> {
+    postgis_debug!("(get_altitude_occupancy) entry.");
+    let query = validate_occupancy_request(&request).map_err(PostgisError::Occupancy)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_altitude_occupancy) could not get psql pool.");
+        return Err(PostgisError::Occupancy(OccupancyError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_altitude_occupancy) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Occupancy(OccupancyError::Client)
+    })?;
+
+    // Recent aircraft on other shards within the area
+    let updated_since = crate::clock::now()
+        - Duration::try_seconds(OCCUPANCY_MAX_POSITION_AGE_SECS).unwrap_or_default();
+    let shard_rows = ShardRows::fetch(
+        &client,
+        super::aircraft::position_source(),
+        r#""geom" IS NOT NULL
+            AND ST_Intersects(ST_Force2D("geom"), ST_Force2D($1))
+            AND "last_position_update" >= $2"#,
+        &[&query.area, &updated_since],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(get_altitude_occupancy) could not get shard rows: {}", e);
+        PostgisError::Occupancy(OccupancyError::DBError)
+    })?;
+
+    // Flights are narrowed down by their envelope ("isa") index first
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"WITH "bands" AS (
+                SELECT
+                    "index",
+                    $4::FLOAT8 + "index" * $5::FLOAT8 AS "bottom",
+                    $4::FLOAT8 + ("index" + 1) * $5::FLOAT8 AS "top"
+                FROM generate_series(0, $6::INTEGER - 1) AS "index"
+            ), "segments" AS (
+                SELECT
+                    ST_ZMin("segments"."geom") AS "z_min",
+                    ST_ZMax("segments"."geom") AS "z_max"
+                FROM {segments_table_name} AS "segments"
+                JOIN {flights_table_name} AS "flights"
+                    ON "flights"."flight_identifier" = "segments"."flight_identifier"
+                WHERE
+                    "flights"."deleted_at" IS NULL
+                    AND "flights"."isa" && ST_Force2D($1)
+                    AND ST_Intersects(ST_Force2D("segments"."geom"), ST_Force2D($1))
+                    AND "segments"."time_end" >= $2
+                    AND "segments"."time_start" <= $3
+            ), "aircraft" AS (
+                SELECT ST_Z("geom") AS "z"
+                FROM {aircraft_table_name} AS "aircraft"
+                WHERE
+                    "geom" IS NOT NULL
+                    AND ST_Intersects(ST_Force2D("geom"), ST_Force2D($1))
+                    AND "last_position_update" >= $7
+            )
+            SELECT
+                "bands"."bottom",
+                "bands"."top",
+                (
+                    SELECT COUNT(*) FROM "segments"
+                    WHERE "segments"."z_min" < "bands"."top"
+                        AND "segments"."z_max" >= "bands"."bottom"
+                ) AS "segment_count",
+                (
+                    SELECT COUNT(*) FROM "aircraft"
+                    WHERE "aircraft"."z" >= "bands"."bottom"
+                        AND "aircraft"."z" < "bands"."top"
+                ) AS "aircraft_count"
+            FROM "bands"
+            ORDER BY "bands"."index";"#,
+            segments_table_name = super::flight::get_flight_segments_table_name(),
+            flights_table_name = super::flight::get_flights_table_name(),
+            aircraft_table_name = shard_rows.source(8),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_altitude_occupancy) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Occupancy(OccupancyError::DBError)
+        })?;
+
+    let mut params: Vec<&(dyn ToSql + Sync)> = vec![
+        &query.area,
+        &query.time_start,
+        &query.time_end,
+        &query.altitude_meters_min,
+        &query.band_height_meters,
+        &query.band_count,
+        &updated_since,
+    ];
+    params.extend(shard_rows.param());
+
+    let bands = client
+        .query(&stmt, &params)
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_altitude_occupancy) could not execute query: {}", e);
+            PostgisError::Occupancy(OccupancyError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            let bottom: f64 = row.try_get("bottom")?;
+            let top: f64 = row.try_get("top")?;
+            let segment_count: i64 = row.try_get("segment_count")?;
+            let aircraft_count: i64 = row.try_get("aircraft_count")?;
+
+            Ok(AltitudeBand {
+                altitude_meters_min: bottom as f32,
+                altitude_meters_max: top as f32,
+                segment_count: segment_count.max(0) as u64,
+                aircraft_count: aircraft_count.max(0) as u64,
+            })
+        })
+        .collect::<Result<Vec<AltitudeBand>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!(
+                "(get_altitude_occupancy) could not get occupancy data: {}",
+                e
+            );
+            PostgisError::Occupancy(OccupancyError::DBError)
+        })?;
+
+    Ok(GetAltitudeOccupancyResponse { bands })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::server::grpc_server::Coordinates;
+
+    fn request() -> GetAltitudeOccupancyRequest {
+        let time_start = Utc::now();
+        GetAltitudeOccupancyRequest {
+            vertices: [(0., 0.), (0., 1.), (1., 1.), (1., 0.), (0., 0.)]
+                .iter()
+                .map(|(dy, dx)| Coordinates {
+                    latitude: 52.3745905 + dy * 0.01,
+                    longitude: 4.9160036 + dx * 0.01,
+                })
+                .collect(),
+            time_start: Some(time_start.into()),
+            time_end: Some((time_start + Duration::try_minutes(20).unwrap()).into()),
+            altitude_meters_min: 0.0,
+            altitude_meters_max: 300.0,
+            band_height_meters: 30.0,
+        }
+    }
+
+    #[test]
+    fn ut_validate_occupancy_request() {
+        let query = validate_occupancy_request(&request()).unwrap();
+        assert_eq!(query.band_count, 10);
+
+        // A partial band at the top is counted as a whole band
+        let valid = GetAltitudeOccupancyRequest {
+            altitude_meters_max: 301.0,
+            ..request()
+        };
+        assert_eq!(validate_occupancy_request(&valid).unwrap().band_count, 11);
+
+        let valid = GetAltitudeOccupancyRequest {
+            band_height_meters: 3.0,
+            ..request()
+        };
+        assert_eq!(
+            validate_occupancy_request(&valid).unwrap().band_count,
+            MAX_ALTITUDE_BANDS
+        );
+
+        let invalid = GetAltitudeOccupancyRequest {
+            band_height_meters: 2.9,
+            ..request()
+        };
+        assert_eq!(
+            validate_occupancy_request(&invalid).unwrap_err(),
+            OccupancyError::Altitude
+        );
+
+        for band_height_meters in [0.0, -30.0, f32::NAN, f32::INFINITY] {
+            let invalid = GetAltitudeOccupancyRequest {
+                band_height_meters,
+                ..request()
+            };
+            assert_eq!(
+                validate_occupancy_request(&invalid).unwrap_err(),
+                OccupancyError::Altitude
+            );
+        }
+
+        let invalid = GetAltitudeOccupancyRequest {
+            altitude_meters_max: 0.0,
+            ..request()
+        };
+        assert_eq!(
+            validate_occupancy_request(&invalid).unwrap_err(),
+            OccupancyError::Altitude
+        );
+
+        let mut invalid = request();
+        invalid.vertices.pop();
+        assert_eq!(
+            validate_occupancy_request(&invalid).unwrap_err(),
+            OccupancyError::Location
+        );
+
+        let invalid = GetAltitudeOccupancyRequest {
+            time_end: request().time_start,
+            ..request()
+        };
+        assert_eq!(
+            validate_occupancy_request(&invalid).unwrap_err(),
+            OccupancyError::Time
+        );
+
+        let invalid = GetAltitudeOccupancyRequest {
+            time_start: None,
+            ..request()
+        };
+        assert_eq!(
+            validate_occupancy_request(&invalid).unwrap_err(),
+            OccupancyError::Time
+        );
+
+        let time_start = Utc::now();
+        let invalid = GetAltitudeOccupancyRequest {
+            time_start: Some(time_start.into()),
+            time_end: Some(
+                (time_start + Duration::try_hours(MAX_OCCUPANCY_RANGE_HOURS + 1).unwrap()).into(),
+            ),
+            ..request()
+        };
+        assert_eq!(
+            validate_occupancy_request(&invalid).unwrap_err(),
+            OccupancyError::Time
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_occupancy_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_occupancy_client_failure) start");
+
+        let result = get_altitude_occupancy(request()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Occupancy(OccupancyError::Client));
+
+        ut_info!("(ut_occupancy_client_failure) success");
+    }
+}
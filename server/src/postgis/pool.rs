@@ -1,13 +1,28 @@
 //! Secure connections to the PostGIS database
 //!
 
-use deadpool_postgres::{ManagerConfig, Pool, RecyclingMethod, Runtime};
+use deadpool_postgres::{
+    Hook, HookError, ManagerConfig, Metrics, Pool, PoolConfig, RecyclingMethod, Runtime,
+};
 use native_tls::{Certificate, Identity, TlsConnector};
 use postgres_native_tls::MakeTlsConnector;
 // use tokio_postgres::tls::MakeTlsConnect;
 
 use crate::config::Config;
 use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Pool recycles triggered by a suspected primary switchover since startup
+pub static FAILOVER_RECYCLES: AtomicU64 = AtomicU64::new(0);
+
+/// Min time between two failover recycles, so that a burst of failing
+///  operations recycles the pools once
+pub const MIN_FAILOVER_RECYCLE_INTERVAL_MS: u64 = 1_000;
+
+/// When the pools were last recycled after a suspected switchover
+static FAILOVER_RECYCLED_AT: RwLock<Option<Instant>> = RwLock::new(None);
 
 /// Creates a connection to the PostGIS database using SSL certificates
 pub fn create_pool(mut config: Config) -> Pool {
@@ -64,8 +79,24 @@ pub fn create_pool(mut config: Config) -> Pool {
             )
         });
 
+    config.pg.options =
+        with_statement_timeout(config.pg.options.take(), config.pg_statement_timeout_secs);
+
     let connector = MakeTlsConnector::new(connector);
-    let result = config.pg.create_pool(Some(Runtime::Tokio1), connector);
+    let builder = match config.pg.builder(connector) {
+        Ok(builder) => builder,
+        Err(e) => {
+            panic!("(create_pool) error creating pool: {}", e);
+        }
+    };
+
+    // Connections opened before a failover recycle may still talk to the
+    //  old primary, they're dropped instead of handed out again
+    let result = builder
+        .runtime(Runtime::Tokio1)
+        .post_recycle(Hook::sync_fn(|_, metrics| discard_stale(metrics)))
+        .build();
+
     match result {
         Ok(pool) => pool,
         Err(e) => {
@@ -73,3 +104,252 @@ pub fn create_pool(mut config: Config) -> Pool {
         }
     }
 }
+
+/// If the pools were recycled after a switchover at or after `instant`
+pub fn recycled_since(instant: Instant) -> bool {
+    FAILOVER_RECYCLED_AT
+        .read()
+        .map(|recycled_at| recycled_at.is_some_and(|recycled_at| recycled_at >= instant))
+        .unwrap_or(false)
+}
+
+/// Rejects pooled connections opened before the last failover recycle
+fn discard_stale(metrics: &Metrics) -> Result<(), HookError> {
+    if recycled_since(metrics.created) {
+        return Err(HookError::StaticMessage(
+            "connection opened before the last failover recycle",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Recycles every pool after a suspected primary switchover
+///
+/// Idle connections are dropped right away, connections in use are
+///  dropped when they're returned. New connections resolve the database
+///  address again and so reach the new primary. Returns false if the
+///  pools were already recycled less than
+///  [`MIN_FAILOVER_RECYCLE_INTERVAL_MS`] ago.
+pub fn recycle_pools() -> bool {
+    let now = Instant::now();
+    {
+        let Ok(mut recycled_at) = FAILOVER_RECYCLED_AT.write() else {
+            postgis_error!("(recycle_pools) could not lock recycle time.");
+            return false;
+        };
+
+        let interval = Duration::from_millis(MIN_FAILOVER_RECYCLE_INTERVAL_MS);
+        if recycled_at.is_some_and(|recycled_at| now.duration_since(recycled_at) < interval) {
+            return false;
+        }
+
+        *recycled_at = Some(now);
+    }
+
+    FAILOVER_RECYCLES.fetch_add(1, Ordering::Relaxed);
+    postgis_warn!("(recycle_pools) recycling database pools after a suspected failover.");
+
+    for pool in super::get_all_pools() {
+        pool.retain(|_, _| false);
+    }
+
+    true
+}
+
+/// Runs an operation, running it once more if it failed and the pools
+///  were recycled after a switchover in the meantime
+///
+/// The retry gets fresh connections. Operations must be safe to repeat
+///  after a failure, e.g. a single transaction that was rolled back.
+pub(crate) async fn retry_after_failover<T, E, F, Fut>(caller: &str, mut op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let start = Instant::now();
+    match op().await {
+        Err(_) if recycled_since(start) => {
+            postgis_warn!("({caller}) retrying once after a failover recycle.");
+            op().await
+        }
+        result => result,
+    }
+}
+
+/// Creates a dedicated pool for aircraft telemetry updates
+///
+/// Returns None if `pg_telemetry_pool_size` is 0, in which case telemetry
+///  shares the main pool.
+pub fn create_telemetry_pool(mut config: Config) -> Option<Pool> {
+    if config.pg_telemetry_pool_size == 0 {
+        return None;
+    }
+
+    config.pg.pool = Some(telemetry_pool_config(&config));
+    Some(create_pool(config))
+}
+
+/// Pool settings sized to `pg_telemetry_pool_size`, keeping the timeouts
+///  and queue mode configured for the main pool
+fn telemetry_pool_config(config: &Config) -> PoolConfig {
+    let mut pool = config.pg.pool.clone().unwrap_or_default();
+    pool.max_size = config.pg_telemetry_pool_size;
+    pool
+}
+
+/// Usage of a connection pool
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PoolGauges {
+    /// Max connections of the pool
+    pub max_size: usize,
+
+    /// Connections currently open
+    pub size: usize,
+
+    /// Open connections not in use
+    pub available: usize,
+
+    /// Callers waiting for a connection
+    pub waiting: usize,
+}
+
+impl From<deadpool_postgres::Status> for PoolGauges {
+    fn from(status: deadpool_postgres::Status) -> Self {
+        PoolGauges {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+            waiting: status.waiting,
+        }
+    }
+}
+
+/// Gets the usage of the shared pool and of the telemetry pool, if
+///  configured, by pool name
+pub fn pool_gauges() -> Vec<(&'static str, PoolGauges)> {
+    [
+        ("main", super::DEADPOOL_POSTGIS.get()),
+        ("telemetry", super::DEADPOOL_POSTGIS_TELEMETRY.get()),
+    ]
+    .into_iter()
+    .filter_map(|(name, pool)| Some((name, pool?.status().into())))
+    .collect()
+}
+
+/// Creates a pool for each extra aircraft shard database
+///
+/// Shards share the server, credentials and settings of the primary
+///  database, only the database name differs. Returns no pools if
+///  `pg_aircraft_shards` is unset.
+pub fn create_shard_pools(config: Config) -> Vec<Pool> {
+    shard_dbnames(config.pg_aircraft_shards.as_deref())
+        .into_iter()
+        .map(|dbname| {
+            let mut config = config.clone();
+            config.pg.dbname = Some(dbname);
+            if config.pg_telemetry_pool_size > 0 {
+                config.pg.pool = Some(telemetry_pool_config(&config));
+            }
+
+            create_pool(config)
+        })
+        .collect()
+}
+
+/// Splits the comma separated shard database names
+fn shard_dbnames(shards: Option<&str>) -> Vec<String> {
+    shards
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|dbname| !dbname.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Adds the statement timeout to the connection options
+///
+/// The timeout is set when a connection is opened, so it applies to every
+///  pooled connection. Statements running longer are cancelled by the
+///  database and fail with `QUERY_CANCELED`.
+fn with_statement_timeout(options: Option<String>, timeout_secs: u64) -> Option<String> {
+    if timeout_secs == 0 {
+        return options;
+    }
+
+    let timeout = format!("-c statement_timeout={}", timeout_secs.saturating_mul(1000));
+    match options {
+        Some(options) if !options.trim().is_empty() => Some(format!("{options} {timeout}")),
+        _ => Some(timeout),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_shard_dbnames() {
+        assert!(shard_dbnames(None).is_empty());
+        assert!(shard_dbnames(Some(" , ")).is_empty());
+        assert_eq!(
+            shard_dbnames(Some("gis_shard_1, gis_shard_2,")),
+            vec!["gis_shard_1".to_string(), "gis_shard_2".to_string()]
+        );
+    }
+
+    #[test]
+    fn ut_telemetry_pool_config() {
+        let mut config = Config::default();
+        config.pg_telemetry_pool_size = 2;
+        config.pg.pool = Some(PoolConfig {
+            max_size: 16,
+            timeouts: deadpool_postgres::Timeouts::wait_millis(500),
+            ..Default::default()
+        });
+
+        // Only the size differs from the main pool
+        let pool = telemetry_pool_config(&config);
+        assert_eq!(pool.max_size, 2);
+        assert_eq!(pool.timeouts.wait, Some(Duration::from_millis(500)));
+
+        config.pg.pool = None;
+        assert_eq!(telemetry_pool_config(&config).max_size, 2);
+    }
+
+    #[test]
+    fn ut_recycle_pools() {
+        let before = Instant::now();
+        let recycles = FAILOVER_RECYCLES.load(Ordering::Relaxed);
+
+        // No pools configured, only the recycle time is recorded
+        assert!(recycle_pools());
+        assert!(recycled_since(before));
+        assert_eq!(FAILOVER_RECYCLES.load(Ordering::Relaxed), recycles + 1);
+
+        // A burst of failures recycles once
+        assert!(!recycle_pools());
+        assert_eq!(FAILOVER_RECYCLES.load(Ordering::Relaxed), recycles + 1);
+        assert!(!recycled_since(Instant::now()));
+    }
+
+    #[test]
+    fn ut_with_statement_timeout() {
+        assert_eq!(
+            with_statement_timeout(None, 30),
+            Some("-c statement_timeout=30000".to_string())
+        );
+        assert_eq!(
+            with_statement_timeout(Some("-c search_path=arrow".to_string()), 1),
+            Some("-c search_path=arrow -c statement_timeout=1000".to_string())
+        );
+
+        // Disabled
+        assert_eq!(with_statement_timeout(None, 0), None);
+        assert_eq!(
+            with_statement_timeout(Some("-c search_path=arrow".to_string()), 0),
+            Some("-c search_path=arrow".to_string())
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! Replays recorded aircraft history into the live aircraft tables
+//!
+//! Meant for tests and map demos driven by recorded data. The telemetry
+//!  snapshots of an aircraft are re-applied as telemetry updates, spaced by
+//!  their original interval divided by a speed factor. Each update is
+//!  stamped with the time it's replayed at, so that it's current.
+
+use super::aircraft::{self, AircraftError, TelemetrySnapshot};
+use super::PostgisError;
+use crate::types::{AircraftTelemetry, Position};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Max time acceleration of a replay
+pub const MAX_SPEED_FACTOR: f64 = 10_000.0;
+
+/// Possible errors with replays
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReplayError {
+    /// Speed factor not positive or above [`MAX_SPEED_FACTOR`]
+    SpeedFactor,
+
+    /// The replay task panicked or was cancelled
+    Task,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReplayError::SpeedFactor => write!(
+                f,
+                "Speed factor must be positive and at most {MAX_SPEED_FACTOR}."
+            ),
+            ReplayError::Task => write!(f, "Replay task failed."),
+        }
+    }
+}
+
+/// A running replay
+///
+/// Dropping the handle stops the replay.
+#[derive(Debug)]
+pub struct ReplayHandle {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<Result<u64, PostgisError>>,
+}
+
+impl ReplayHandle {
+    /// Stops the replay, returns the number of updates applied
+    pub async fn stop(self) -> Result<u64, PostgisError> {
+        let ReplayHandle { stop, task } = self;
+
+        // Fails if the replay is already over
+        let _ = stop.send(());
+        join_replay(task).await
+    }
+
+    /// Waits until every snapshot is replayed, returns the number of
+    ///  updates applied
+    pub async fn finish(self) -> Result<u64, PostgisError> {
+        let ReplayHandle { stop, task } = self;
+        let result = join_replay(task).await;
+        drop(stop);
+        result
+    }
+
+    /// If the replay is over
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// Waits for a replay task
+async fn join_replay(task: JoinHandle<Result<u64, PostgisError>>) -> Result<u64, PostgisError> {
+    task.await.map_err(|e| {
+        postgis_error!("(join_replay) replay task failed: {}", e);
+        PostgisError::Replay(ReplayError::Task)
+    })?
+}
+
+/// Validates a speed factor
+fn check_speed_factor(speed_factor: f64) -> Result<(), ReplayError> {
+    if !speed_factor.is_finite() || speed_factor <= 0.0 || speed_factor > MAX_SPEED_FACTOR {
+        return Err(ReplayError::SpeedFactor);
+    }
+
+    Ok(())
+}
+
+/// Gets when each snapshot is replayed, from the start of the replay
+fn replay_offsets(timestamps: &[DateTime<Utc>], speed_factor: f64) -> Vec<Duration> {
+    let Some(first) = timestamps.first() else {
+        return vec![];
+    };
+
+    timestamps
+        .iter()
+        .map(|timestamp| {
+            (*timestamp - *first)
+                .to_std()
+                .unwrap_or_default()
+                .div_f64(speed_factor)
+        })
+        .collect()
+}
+
+/// Gets the telemetry update replaying a snapshot
+///
+/// Snapshots written by position-only updates are replayed with zero
+///  velocities.
+fn replayed_telemetry(snapshot: &TelemetrySnapshot, now: DateTime<Utc>) -> AircraftTelemetry {
+    AircraftTelemetry {
+        identifier: snapshot.identifier.clone(),
+        position: Position {
+            longitude: snapshot.geom.x,
+            latitude: snapshot.geom.y,
+            altitude_meters: snapshot.geom.z,
+        },
+        velocity_horizontal_ground_mps: snapshot.velocity_horizontal_ground_mps.unwrap_or(0.0),
+        velocity_horizontal_air_mps: snapshot.velocity_horizontal_air_mps,
+        velocity_vertical_mps: snapshot.velocity_vertical_mps.unwrap_or(0.0),
+        track_angle_degrees: snapshot.track_angle_degrees.unwrap_or(0.0),
+        timestamp_network: now,
+        timestamp_asset: None,
+    }
+}
+
+/// Replays the history of an aircraft between two times into the live
+///  aircraft tables
+///
+/// Updates are spaced by their original interval divided by `speed_factor`:
+///  2.0 replays twice as fast as recorded. The pool must be the shard
+///  holding the aircraft, it's read from and written to. Returns once the
+///  history is read, the replay continues in the background until it's
+///  done or stopped through the handle.
+///
+/// Replays at most [`aircraft::MAX_TELEMETRY_HISTORY_ROWS`] snapshots.
+pub async fn replay_history(
+    identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    speed_factor: f64,
+    pool: &deadpool_postgres::Pool,
+) -> Result<ReplayHandle, PostgisError> {
+    postgis_debug!("(replay_history) entry, aircraft: '{identifier}'.");
+    check_speed_factor(speed_factor).map_err(|e| {
+        postgis_error!(
+            "(replay_history) invalid speed factor {}: {}",
+            speed_factor,
+            e
+        );
+        PostgisError::Replay(e)
+    })?;
+
+    aircraft::check_identifier(identifier).map_err(|e| {
+        postgis_error!("(replay_history) invalid identifier {}: {}", identifier, e);
+        PostgisError::Aircraft(AircraftError::Identifier)
+    })?;
+
+    if time_end <= time_start {
+        postgis_error!("(replay_history) time_end must be after time_start.");
+        return Err(PostgisError::Aircraft(AircraftError::Time));
+    }
+
+    let snapshots =
+        aircraft::get_telemetry_history_on(pool, identifier, time_start, time_end).await?;
+
+    postgis_info!(
+        "(replay_history) replaying {} snapshots of aircraft {} at {}x.",
+        snapshots.len(),
+        identifier,
+        speed_factor
+    );
+
+    let (stop, stop_rx) = oneshot::channel();
+    let task = tokio::spawn(run_replay(pool.clone(), snapshots, speed_factor, stop_rx));
+    Ok(ReplayHandle { stop, task })
+}
+
+/// Applies the snapshots on schedule until done or stopped
+async fn run_replay(
+    pool: deadpool_postgres::Pool,
+    snapshots: Vec<TelemetrySnapshot>,
+    speed_factor: f64,
+    mut stop: oneshot::Receiver<()>,
+) -> Result<u64, PostgisError> {
+    let timestamps: Vec<DateTime<Utc>> = snapshots.iter().map(|s| s.timestamp_network).collect();
+    let offsets = replay_offsets(&timestamps, speed_factor);
+    let start = Instant::now();
+    let mut applied: u64 = 0;
+
+    for (snapshot, offset) in snapshots.iter().zip(offsets) {
+        tokio::select! {
+            biased;
+            _ = &mut stop => {
+                postgis_info!("(run_replay) stopped after {} updates.", applied);
+                return Ok(applied);
+            }
+            _ = tokio::time::sleep_until(start + offset) => (),
+        }
+
+        let now = crate::clock::now();
+        let item = replayed_telemetry(snapshot, now);
+        if let Err(e) = aircraft::validate_telemetry_message(&item, &now) {
+            postgis_warn!(
+                "(run_replay) skipping snapshot of {} from {}: {}",
+                snapshot.identifier,
+                snapshot.timestamp_network,
+                e
+            );
+            continue;
+        }
+
+        aircraft::update_aircraft_telemetry_on(&pool, vec![item], false).await?;
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use postgis::ewkb::PointZ;
+
+    #[test]
+    fn ut_check_speed_factor() {
+        assert!(check_speed_factor(1.0).is_ok());
+        assert!(check_speed_factor(0.5).is_ok());
+        assert!(check_speed_factor(MAX_SPEED_FACTOR).is_ok());
+
+        for speed_factor in [0.0, -1.0, f64::NAN, f64::INFINITY, MAX_SPEED_FACTOR + 1.0] {
+            assert_eq!(
+                check_speed_factor(speed_factor).unwrap_err(),
+                ReplayError::SpeedFactor
+            );
+        }
+    }
+
+    #[test]
+    fn ut_replay_offsets() {
+        assert!(replay_offsets(&[], 2.0).is_empty());
+
+        let start = Utc::now();
+        let timestamps = [
+            start,
+            start + chrono::Duration::try_seconds(2).unwrap(),
+            start + chrono::Duration::try_seconds(10).unwrap(),
+        ];
+
+        assert_eq!(
+            replay_offsets(&timestamps, 2.0),
+            vec![
+                Duration::ZERO,
+                Duration::from_secs(1),
+                Duration::from_secs(5)
+            ]
+        );
+
+        // Slower than recorded
+        assert_eq!(replay_offsets(&timestamps, 0.5)[1], Duration::from_secs(4));
+    }
+
+    #[test]
+    fn ut_replayed_telemetry() {
+        let now = Utc::now();
+        let snapshot = TelemetrySnapshot {
+            identifier: "N12345".to_string(),
+            geom: PointZ::new(4.9160036, 52.3745905, 100.0, Some(4326)),
+            velocity_horizontal_ground_mps: None,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: Some(-1.5),
+            track_angle_degrees: None,
+            timestamp_network: now - chrono::Duration::try_days(1).unwrap(),
+            timestamp_asset: Some(now - chrono::Duration::try_days(1).unwrap()),
+        };
+
+        let item = replayed_telemetry(&snapshot, now);
+        assert_eq!(item.identifier, snapshot.identifier);
+        assert_eq!(item.position.longitude, 4.9160036);
+        assert_eq!(item.position.latitude, 52.3745905);
+        assert_eq!(item.position.altitude_meters, 100.0);
+        assert_eq!(item.velocity_horizontal_ground_mps, 0.0);
+        assert_eq!(item.velocity_vertical_mps, -1.5);
+        assert_eq!(item.timestamp_network, now);
+        assert_eq!(item.timestamp_asset, None);
+    }
+}
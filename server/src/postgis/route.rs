@@ -0,0 +1,328 @@
+//! This module contains functions for building a flight path from an
+//!  ordered list of nodes.
+//!
+//! Each pair of consecutive nodes is a leg routed with [`best_path`], the
+//!  legs are joined into a single path and stored like any other flight
+//!  with [`update_flight_path`].
+
+use super::best_path::{best_path, PathError};
+use super::flight::update_flight_path;
+use super::PostgisError;
+use crate::grpc::server::grpc_server::{
+    BestPathRequest, BuildFlightPathRequest, BuildFlightPathResponse, NodeRef, NodeType,
+    Path as GrpcPath, PointZ as GrpcPointZ, UpdateFlightPathRequest,
+};
+use chrono::{DateTime, Utc};
+use num_traits::FromPrimitive;
+
+/// Max number of nodes in a request, each leg is a separate routing
+pub const MAX_ROUTE_NODES: usize = 20;
+
+/// Possible errors building a flight path
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RouteError {
+    /// Fewer than two or more than [`MAX_ROUTE_NODES`] nodes
+    Nodes,
+
+    /// The node at this index is of an unsupported type or doesn't exist
+    Node(usize),
+
+    /// Routing failed on the leg starting at this node index
+    Leg(usize, PathError),
+
+    /// Invalid time window
+    Time,
+
+    /// Could not get client
+    Client,
+}
+
+impl std::fmt::Display for RouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RouteError::Nodes => {
+                write!(f, "Between 2 and {MAX_ROUTE_NODES} nodes must be provided.")
+            }
+            RouteError::Node(index) => write!(f, "Invalid or unknown node at index {index}."),
+            RouteError::Leg(index, e) => write!(
+                f,
+                "Could not route leg {index} (node {index} to node {}): {e}",
+                index + 1
+            ),
+            RouteError::Time => write!(f, "Invalid time window provided."),
+            RouteError::Client => write!(f, "Could not get backend client."),
+        }
+    }
+}
+
+/// Checks the node types of a route
+///
+/// [`best_path`] routes from a vertiport or an aircraft to a vertiport,
+///  so an aircraft may only be the first node and every other node must
+///  be a vertiport.
+fn validate_nodes(nodes: &[NodeRef]) -> Result<Vec<NodeType>, RouteError> {
+    if nodes.len() < 2 || nodes.len() > MAX_ROUTE_NODES {
+        postgis_error!("(validate_nodes) invalid number of nodes: {}", nodes.len());
+        return Err(RouteError::Nodes);
+    }
+
+    nodes
+        .iter()
+        .enumerate()
+        .map(
+            |(index, node)| match FromPrimitive::from_i32(node.node_type) {
+                Some(NodeType::Vertiport) => Ok(NodeType::Vertiport),
+                Some(NodeType::Aircraft) if index == 0 => Ok(NodeType::Aircraft),
+                node_type => {
+                    postgis_error!(
+                        "(validate_nodes) unsupported node type at index {index}: {:?}",
+                        node_type
+                    );
+                    Err(RouteError::Node(index))
+                }
+            },
+        )
+        .collect()
+}
+
+/// Checks that each node exists
+async fn check_nodes_exist(nodes: &[NodeRef], types: &[NodeType]) -> Result<(), RouteError> {
+    for (index, (node, node_type)) in nodes.iter().zip(types).enumerate() {
+        let result = match node_type {
+            NodeType::Aircraft => super::aircraft::get_aircraft_pointz(&node.identifier).await,
+            _ => super::vertiport::get_vertiport_centroidz(&node.identifier).await,
+        };
+
+        match result {
+            Ok(_) => (),
+            Err(PostgisError::Aircraft(super::aircraft::AircraftError::Client))
+            | Err(PostgisError::Vertiport(super::vertiport::VertiportError::Client)) => {
+                return Err(RouteError::Client)
+            }
+            Err(e) => {
+                postgis_error!(
+                    "(check_nodes_exist) node {index} '{}' not found: {}",
+                    node.identifier,
+                    e
+                );
+                return Err(RouteError::Node(index));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Joins the paths of consecutive legs into a single path
+///
+/// Each leg starts where the previous one ended, so the first point of
+///  every leg after the first is dropped.
+fn join_legs(legs: &[GrpcPath]) -> Vec<GrpcPointZ> {
+    legs.iter()
+        .enumerate()
+        .flat_map(|(index, leg)| {
+            leg.path
+                .iter()
+                .skip(usize::from(index > 0))
+                .filter_map(|node| node.geom.clone())
+        })
+        .collect()
+}
+
+/// Routes between consecutive nodes and stores the joined path as a flight
+///
+/// Each leg departs as early as possible after the arrival of the previous
+///  leg, and the last leg must arrive by `time_end`. If a leg can't be
+///  routed the error reports which one, nothing is stored.
+///
+/// Flights longer than `max_duration_secs` are rejected (0 disables the limit).
+#[cfg(not(tarpaulin_include))]
+pub async fn build_flight_path(
+    request: BuildFlightPathRequest,
+    max_duration_secs: u64,
+) -> Result<BuildFlightPathResponse, PostgisError> {
+    postgis_debug!("(build_flight_path) entry, nodes: {:?}", request.nodes);
+
+    let types = validate_nodes(&request.nodes).map_err(PostgisError::Route)?;
+
+    let (Some(time_start), Some(time_end)) = (request.time_start, request.time_end) else {
+        postgis_error!("(build_flight_path) time_start and time_end are required.");
+        return Err(PostgisError::Route(RouteError::Time));
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end <= time_start {
+        postgis_error!("(build_flight_path) time_end is not after time_start.");
+        return Err(PostgisError::Route(RouteError::Time));
+    }
+
+    check_nodes_exist(&request.nodes, &types)
+        .await
+        .map_err(PostgisError::Route)?;
+
+    let mut legs: Vec<GrpcPath> = vec![];
+    let mut departure = time_start;
+    for (index, pair) in request.nodes.windows(2).enumerate() {
+        let leg_request = BestPathRequest {
+            origin_identifier: pair[0].identifier.clone(),
+            target_identifier: pair[1].identifier.clone(),
+            origin_type: types[index] as i32,
+            target_type: types[index + 1] as i32,
+            time_start: Some(departure.into()),
+            time_end: Some(time_end.into()),
+            limit: 1,
+            soft_window: true,
+            cruise_velocity_mps: request.cruise_velocity_mps,
+            ..Default::default()
+        };
+
+        let leg = match best_path(leg_request, false).await {
+            Ok(paths) => paths.into_iter().next(),
+            Err(PostgisError::BestPath(e)) => {
+                postgis_error!("(build_flight_path) could not route leg {index}: {}", e);
+                return Err(PostgisError::Route(RouteError::Leg(index, e)));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let Some(leg) = leg else {
+            postgis_error!("(build_flight_path) no path found for leg {index}.");
+            return Err(PostgisError::Route(RouteError::Leg(
+                index,
+                PathError::NoPath,
+            )));
+        };
+
+        let Some(arrival) = leg.time_arrival.clone() else {
+            postgis_error!("(build_flight_path) leg {index} has no arrival time.");
+            return Err(PostgisError::Route(RouteError::Leg(
+                index,
+                PathError::Internal,
+            )));
+        };
+
+        departure = arrival.into();
+        legs.push(leg);
+    }
+
+    let time_departure = legs.first().and_then(|leg| leg.time_departure.clone());
+    let time_arrival = legs.last().and_then(|leg| leg.time_arrival.clone());
+    let distance_meters = legs.iter().map(|leg| leg.distance_meters).sum();
+    let path = join_legs(&legs);
+
+    let flight = UpdateFlightPathRequest {
+        flight_identifier: Some(request.flight_identifier),
+        aircraft_identifier: Some(request.aircraft_identifier),
+        aircraft_type: request.aircraft_type,
+        path: path.clone(),
+        timestamp_start: time_departure.clone(),
+        timestamp_end: time_arrival.clone(),
+        ..Default::default()
+    };
+
+    update_flight_path(flight, max_duration_secs).await?;
+
+    postgis_debug!(
+        "(build_flight_path) success, legs: {}, points: {}.",
+        legs.len(),
+        path.len()
+    );
+
+    Ok(BuildFlightPathResponse {
+        path,
+        distance_meters,
+        time_departure,
+        time_arrival,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::server::grpc_server::PathNode as GrpcPathNode;
+
+    fn node(node_type: NodeType, identifier: &str) -> NodeRef {
+        NodeRef {
+            node_type: node_type as i32,
+            identifier: identifier.to_string(),
+        }
+    }
+
+    fn leg(points: &[(f64, f64)]) -> GrpcPath {
+        GrpcPath {
+            path: points
+                .iter()
+                .enumerate()
+                .map(|(index, (longitude, latitude))| GrpcPathNode {
+                    index: index as i32,
+                    geom: Some(GrpcPointZ {
+                        longitude: *longitude,
+                        latitude: *latitude,
+                        altitude_meters: 0.0,
+                    }),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ut_validate_nodes() {
+        let nodes = vec![
+            node(NodeType::Aircraft, "Mantis"),
+            node(NodeType::Vertiport, "vertiport-a"),
+            node(NodeType::Vertiport, "vertiport-b"),
+        ];
+        assert_eq!(
+            validate_nodes(&nodes).unwrap(),
+            vec![NodeType::Aircraft, NodeType::Vertiport, NodeType::Vertiport]
+        );
+
+        // Too few nodes
+        assert_eq!(validate_nodes(&nodes[..1]).unwrap_err(), RouteError::Nodes);
+
+        // Aircraft only as the first node
+        let mut invalid = nodes.clone();
+        invalid[1] = node(NodeType::Aircraft, "Mantis");
+        assert_eq!(validate_nodes(&invalid).unwrap_err(), RouteError::Node(1));
+
+        // Waypoints can't be routed to
+        let mut invalid = nodes.clone();
+        invalid[2] = node(NodeType::Waypoint, "waypoint-a");
+        assert_eq!(validate_nodes(&invalid).unwrap_err(), RouteError::Node(2));
+
+        let mut invalid = nodes;
+        invalid[0].node_type = 42;
+        assert_eq!(validate_nodes(&invalid).unwrap_err(), RouteError::Node(0));
+    }
+
+    #[test]
+    fn ut_join_legs() {
+        let legs = vec![
+            leg(&[(4.0, 52.0), (4.1, 52.1), (4.2, 52.2)]),
+            leg(&[(4.2, 52.2), (4.3, 52.3)]),
+        ];
+
+        let points: Vec<(f64, f64)> = join_legs(&legs)
+            .iter()
+            .map(|point| (point.longitude, point.latitude))
+            .collect();
+
+        assert_eq!(
+            points,
+            vec![(4.0, 52.0), (4.1, 52.1), (4.2, 52.2), (4.3, 52.3)]
+        );
+        assert!(join_legs(&[]).is_empty());
+    }
+
+    #[test]
+    fn ut_route_error_display() {
+        let e = RouteError::Leg(1, PathError::NoPath);
+        assert_eq!(
+            e.to_string(),
+            "Could not route leg 1 (node 1 to node 2): No path was found."
+        );
+    }
+}
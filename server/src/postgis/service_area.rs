@@ -0,0 +1,257 @@
+//! This module contains the operating region of the service.
+//!
+//! When a service area is configured, aircraft positions, flight paths and
+//!  zones with a point further than the buffer outside of it are rejected.
+//!  Without one nothing is checked.
+
+use super::utils::{LAT_MAX, LAT_MIN, LON_MAX, LON_MIN};
+use geo::Contains;
+use once_cell::sync::OnceCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Geometries outside of this area are rejected, if set
+pub static SERVICE_AREA: OnceCell<geo::Polygon<f64>> = OnceCell::new();
+
+/// Distance outside of [`SERVICE_AREA`] still accepted, 0 if unset
+pub static SERVICE_AREA_BUFFER_METERS: OnceCell<f64> = OnceCell::new();
+
+/// Positions, flight paths and zones rejected for being outside of the
+///  service area since startup
+pub static OUT_OF_AREA_REJECTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Mean earth radius in meters
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// Possible errors with service areas
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ServiceAreaError {
+    /// A vertex isn't a valid `longitude latitude` pair
+    Vertex,
+
+    /// Fewer than 3 vertices
+    Vertices,
+}
+
+impl std::fmt::Display for ServiceAreaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ServiceAreaError::Vertex => write!(f, "Invalid vertex provided."),
+            ServiceAreaError::Vertices => write!(f, "At least 3 vertices are required."),
+        }
+    }
+}
+
+/// Parses a service area from comma-separated `longitude latitude` vertices
+///
+/// The polygon is closed automatically if the last vertex differs from
+///  the first, e.g. `"4.8 52.3, 5.0 52.3, 5.0 52.4, 4.8 52.4"`.
+pub fn parse_service_area(area: &str) -> Result<geo::Polygon<f64>, ServiceAreaError> {
+    let vertices = area
+        .split(',')
+        .map(|vertex| {
+            let values = vertex
+                .split_whitespace()
+                .map(|v| v.parse::<f64>().ok())
+                .collect::<Option<Vec<f64>>>()?;
+
+            match values[..] {
+                [longitude, latitude] => Some(geo::coord! { x: longitude, y: latitude }),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<geo::Coord<f64>>>>()
+        .ok_or_else(|| {
+            postgis_error!("(parse_service_area) invalid vertex in service area: {area}");
+            ServiceAreaError::Vertex
+        })?;
+
+    area_from_vertices(vertices)
+}
+
+/// Builds an area from `(longitude, latitude)` vertices, closed
+///  automatically
+pub fn area_from_vertices(
+    vertices: Vec<geo::Coord<f64>>,
+) -> Result<geo::Polygon<f64>, ServiceAreaError> {
+    if let Some(vertex) = vertices.iter().find(|vertex| {
+        !(LON_MIN..=LON_MAX).contains(&vertex.x) || !(LAT_MIN..=LAT_MAX).contains(&vertex.y)
+    }) {
+        postgis_error!("(area_from_vertices) vertex out of range: {vertex:?}");
+        return Err(ServiceAreaError::Vertex);
+    }
+
+    let polygon = geo::Polygon::new(geo::LineString::from(vertices), vec![]);
+
+    // Closed ring of a triangle at least
+    if polygon.exterior().0.len() < 4 {
+        postgis_error!("(area_from_vertices) area needs at least 3 vertices.");
+        return Err(ServiceAreaError::Vertices);
+    }
+
+    Ok(polygon)
+}
+
+/// Distance in meters from a point to the area, 0 if inside
+///
+/// Uses a local equirectangular projection around the point, accurate
+///  enough for buffers of a few kilometers.
+fn distance_to_area_meters(area: &geo::Polygon<f64>, longitude: f64, latitude: f64) -> f64 {
+    if area.contains(&geo::point! { x: longitude, y: latitude }) {
+        return 0.0;
+    }
+
+    let scale_x = latitude.to_radians().cos() * EARTH_RADIUS_METERS;
+    let project = |coord: geo::Coord<f64>| {
+        (
+            (coord.x - longitude).to_radians() * scale_x,
+            (coord.y - latitude).to_radians() * EARTH_RADIUS_METERS,
+        )
+    };
+
+    area.exterior()
+        .lines()
+        .map(|line| {
+            let (ax, ay) = project(line.start);
+            let (bx, by) = project(line.end);
+            let (dx, dy) = (bx - ax, by - ay);
+
+            // Closest point of the edge to the origin (the point)
+            let length_squared = dx * dx + dy * dy;
+            let t = if length_squared > 0.0 {
+                (-(ax * dx + ay * dy) / length_squared).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            (ax + t * dx).hypot(ay + t * dy)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Checks if a point is within `buffer_meters` of the area, always true
+///  without an area
+pub fn in_area(
+    area: Option<&geo::Polygon<f64>>,
+    buffer_meters: f64,
+    longitude: f64,
+    latitude: f64,
+) -> bool {
+    let Some(area) = area else {
+        return true;
+    };
+
+    distance_to_area_meters(area, longitude, latitude) <= buffer_meters
+}
+
+/// Index of the first `(longitude, latitude)` point outside of the area
+///  and its buffer, if any
+fn first_outside<I>(
+    area: Option<&geo::Polygon<f64>>,
+    buffer_meters: f64,
+    points: I,
+) -> Option<usize>
+where
+    I: IntoIterator<Item = (f64, f64)>,
+{
+    points
+        .into_iter()
+        .position(|(longitude, latitude)| !in_area(area, buffer_meters, longitude, latitude))
+}
+
+/// Checks `(longitude, latitude)` points against the configured service
+///  area and buffer
+///
+/// Returns the index of the first point outside, the rejection is counted
+///  in [`OUT_OF_AREA_REJECTIONS`].
+pub fn check_service_area<I>(points: I) -> Result<(), usize>
+where
+    I: IntoIterator<Item = (f64, f64)>,
+{
+    let buffer_meters = SERVICE_AREA_BUFFER_METERS.get().copied().unwrap_or(0.0);
+    match first_outside(SERVICE_AREA.get(), buffer_meters, points) {
+        Some(index) => {
+            OUT_OF_AREA_REJECTIONS.fetch_add(1, Ordering::Relaxed);
+            Err(index)
+        }
+        None => Ok(()),
+    }
+}
+
+/// The configured service area as WKT, if any
+pub fn service_area_wkt() -> Option<String> {
+    let area = SERVICE_AREA.get()?;
+    let vertices = area
+        .exterior()
+        .coords()
+        .map(|coord| format!("{} {}", coord.x, coord.y))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Some(format!("POLYGON(({vertices}))"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amsterdam() -> geo::Polygon<f64> {
+        parse_service_area("4.8 52.3, 5.0 52.3, 5.0 52.4, 4.8 52.4").unwrap()
+    }
+
+    #[test]
+    fn ut_parse_service_area() {
+        assert_eq!(amsterdam().exterior().0.len(), 5);
+
+        // Explicitly closed ring is accepted as well
+        let closed = parse_service_area("4.8 52.3, 5.0 52.3, 5.0 52.4, 4.8 52.3").unwrap();
+        assert_eq!(closed.exterior().0.len(), 4);
+
+        assert_eq!(
+            parse_service_area("4.8 52.3, 5.0 52.3").unwrap_err(),
+            ServiceAreaError::Vertices
+        );
+
+        for invalid in [
+            "",
+            "4.8 52.3, 5.0, 5.0 52.4",
+            "4.8 52.3, 5.0 52.3, 5.0 52.4 10.0",
+            "4.8 52.3, 5.0 52.3, 181.0 52.4",
+            "4.8 52.3, 5.0 52.3, east north",
+        ] {
+            assert_eq!(
+                parse_service_area(invalid).unwrap_err(),
+                ServiceAreaError::Vertex
+            );
+        }
+    }
+
+    #[test]
+    fn ut_in_area() {
+        let area = amsterdam();
+
+        assert!(in_area(Some(&area), 0.0, 4.9160036, 52.3745905));
+
+        // Mid-Atlantic typo
+        assert!(!in_area(Some(&area), 0.0, -34.9160036, 52.3745905));
+        assert!(!in_area(Some(&area), 10_000.0, -34.9160036, 52.3745905));
+
+        // No restriction by default
+        assert!(in_area(None, 0.0, -34.9160036, 52.3745905));
+
+        // About 680 m east of the area
+        let (longitude, latitude) = (5.01, 52.35);
+        assert!(!in_area(Some(&area), 0.0, longitude, latitude));
+        assert!(!in_area(Some(&area), 600.0, longitude, latitude));
+        assert!(in_area(Some(&area), 750.0, longitude, latitude));
+    }
+
+    #[test]
+    fn ut_first_outside() {
+        let area = amsterdam();
+        let points = [(4.85, 52.35), (4.95, 52.35), (-34.95, 52.35), (4.9, 52.3)];
+
+        assert_eq!(first_outside(Some(&area), 0.0, points), Some(2));
+        assert_eq!(first_outside(Some(&area), 0.0, points[..2].to_vec()), None);
+        assert_eq!(first_outside(None, 0.0, points), None);
+    }
+}
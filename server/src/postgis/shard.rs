@@ -0,0 +1,331 @@
+//! This module spreads aircraft over several databases by identifier.
+//!
+//! Aircraft tables take most of the writes, so they can be sharded while
+//!  flights, zones and routing stay on the primary database. Each aircraft
+//!  lives on the shard picked by a hash of its identifier. The primary is
+//!  always the first shard: without extra shards every aircraft stays on
+//!  the primary, as if sharding didn't exist.
+//!
+//! Reads joining aircraft with tables of the primary, like flights, carry
+//!  the matching aircraft rows of the extra shards over to the primary
+//!  with [`ShardRows`] so the join happens in a single query.
+
+use super::PostgisError;
+use deadpool_postgres::{Client, Pool};
+use once_cell::sync::OnceCell;
+use tokio_postgres::types::ToSql;
+
+/// Pools of the aircraft shards after the primary, if configured
+pub static DEADPOOL_POSTGIS_SHARDS: OnceCell<Vec<Pool>> = OnceCell::new();
+
+/// Possible errors reading the extra shards
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShardError {
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for ShardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ShardError::Client => write!(f, "Could not get backend client."),
+            ShardError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// FNV-1a offset basis
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// FNV-1a prime
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Hashes an identifier with 64-bit FNV-1a
+///
+/// Stable across builds and platforms, unlike the std hasher, so that
+///  every replica picks the same shard.
+pub fn identifier_hash(identifier: &str) -> u64 {
+    identifier.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Gets the index of the shard holding an aircraft
+pub fn shard_index(identifier: &str, shard_count: usize) -> usize {
+    if shard_count <= 1 {
+        return 0;
+    }
+
+    (identifier_hash(identifier) % shard_count as u64) as usize
+}
+
+/// Gets the pools of the aircraft shards after the primary
+pub fn get_extra_shard_pools() -> impl Iterator<Item = &'static Pool> {
+    DEADPOOL_POSTGIS_SHARDS.get().into_iter().flatten()
+}
+
+/// If aircraft are spread over more than the primary database
+pub fn is_sharded() -> bool {
+    get_extra_shard_pools().next().is_some()
+}
+
+/// Gets every aircraft shard, starting with the given pool of the primary
+///
+/// Writes go through the telemetry pool of the primary and reads through
+///  the shared pool, so the caller picks the primary pool. Returns no
+///  pools without a primary pool.
+pub fn get_shard_pools(primary: Option<&'static Pool>) -> Vec<&'static Pool> {
+    let Some(primary) = primary else {
+        return vec![];
+    };
+
+    std::iter::once(primary)
+        .chain(get_extra_shard_pools())
+        .collect()
+}
+
+/// Gets the pool of the shard holding an aircraft
+pub fn get_shard_pool(primary: Option<&'static Pool>, identifier: &str) -> Option<&'static Pool> {
+    let pools = get_shard_pools(primary);
+    pools.get(shard_index(identifier, pools.len())).copied()
+}
+
+/// Gets a client of each extra shard
+pub async fn get_extra_shard_clients() -> Result<Vec<deadpool_postgres::Object>, ShardError> {
+    let mut clients = vec![];
+    for pool in get_extra_shard_pools() {
+        clients.push(pool.get().await.map_err(|e| {
+            postgis_error!(
+                "(get_extra_shard_clients) could not get client from shard connection pool: {}",
+                e
+            );
+            ShardError::Client
+        })?);
+    }
+
+    Ok(clients)
+}
+
+/// Rows of an aircraft table on the extra shards, read along with the
+///  table in a query on the primary
+///
+/// Rows are carried over as record literals of the table on the primary.
+///  Their columns are listed by name, a table migrated on the primary may
+///  order them differently than the same table created later on a shard.
+#[derive(Debug)]
+pub struct ShardRows {
+    /// The table the rows were read from
+    table_name: &'static str,
+
+    /// The rows, none without extra shards
+    rows: Option<Vec<String>>,
+}
+
+impl ShardRows {
+    /// No rows, the table is only read on the primary
+    pub fn none(table_name: &'static str) -> Self {
+        ShardRows {
+            table_name,
+            rows: None,
+        }
+    }
+
+    /// Gets the rows of the table matching `condition` on each extra shard
+    ///
+    /// The condition only sees the table, so it must let through every row
+    ///  the query on the primary may need. Without extra shards nothing is
+    ///  read.
+    pub async fn fetch(
+        primary: &Client,
+        table_name: &'static str,
+        condition: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Self, ShardError> {
+        let clients = get_extra_shard_clients().await?;
+        if clients.is_empty() {
+            return Ok(Self::none(table_name));
+        }
+
+        let columns_stmt = r#"SELECT string_agg(quote_ident("attname"), ', ' ORDER BY "attnum")
+            FROM "pg_catalog"."pg_attribute"
+            WHERE "attrelid" = $1::TEXT::REGCLASS
+                AND "attnum" > 0
+                AND NOT "attisdropped";"#;
+
+        let columns: String = super::query_cached(primary, columns_stmt, &[&table_name])
+            .await
+            .and_then(|rows| match rows.first() {
+                Some(row) => row.try_get(0),
+                None => Ok(String::new()),
+            })
+            .map_err(|e| {
+                postgis_error!(
+                    "(ShardRows::fetch) could not get columns of {}: {}",
+                    table_name,
+                    e
+                );
+                ShardError::DBError
+            })?;
+
+        let stmt = format!(r#"SELECT ROW({columns})::TEXT FROM {table_name} WHERE {condition};"#);
+        let mut rows = vec![];
+        for client in &clients {
+            for row in super::query_cached(client, &stmt, params)
+                .await
+                .map_err(|e| {
+                    postgis_error!("(ShardRows::fetch) could not execute query: {}", e);
+                    ShardError::DBError
+                })?
+            {
+                rows.push(row.try_get(0).map_err(|e| {
+                    postgis_error!("(ShardRows::fetch) could not get row: {}", e);
+                    ShardError::DBError
+                })?);
+            }
+        }
+
+        Ok(ShardRows {
+            table_name,
+            rows: Some(rows),
+        })
+    }
+
+    /// Gets what to select from in place of the table, the rows are passed
+    ///  as parameter `param` (see [`Self::param`])
+    ///
+    /// Needs an alias, like the table would with a subquery.
+    pub fn source(&self, param: usize) -> String {
+        match self.rows {
+            None => self.table_name.to_string(),
+            Some(_) => format!(
+                r#"(
+                    SELECT * FROM {table_name}
+                    UNION ALL
+                    SELECT ("rows"."row"::{table_name}).*
+                    FROM UNNEST(${param}::TEXT[]) AS "rows"("row")
+                )"#,
+                table_name = self.table_name
+            ),
+        }
+    }
+
+    /// Gets the parameter holding the rows, none without extra shards
+    pub fn param(&self) -> Option<&(dyn ToSql + Sync)> {
+        self.rows.as_ref().map(|rows| rows as &(dyn ToSql + Sync))
+    }
+}
+
+/// Groups items by the shard holding their aircraft, keeping their order
+///
+/// Shards without items are left out. Returns `None` without a primary
+///  pool.
+pub fn partition<T>(
+    primary: Option<&'static Pool>,
+    items: Vec<T>,
+    identifier: impl Fn(&T) -> &str,
+) -> Option<Vec<(&'static Pool, Vec<T>)>> {
+    let pools = get_shard_pools(primary);
+    if pools.is_empty() {
+        return None;
+    }
+
+    let groups = group_by_shard(items, pools.len(), identifier);
+    Some(
+        pools
+            .into_iter()
+            .zip(groups)
+            .filter(|(_, items)| !items.is_empty())
+            .collect(),
+    )
+}
+
+/// Splits items into one group per shard
+fn group_by_shard<T>(
+    items: Vec<T>,
+    shard_count: usize,
+    identifier: impl Fn(&T) -> &str,
+) -> Vec<Vec<T>> {
+    let mut groups: Vec<Vec<T>> = (0..shard_count.max(1)).map(|_| vec![]).collect();
+    for item in items {
+        let index = shard_index(identifier(&item), groups.len());
+        groups[index].push(item);
+    }
+
+    groups
+}
+
+/// Applies the aircraft and archive table declarations to the extra shards
+pub async fn psql_init_shards() -> Result<(), PostgisError> {
+    for pool in get_extra_shard_pools() {
+        let mut statements = super::aircraft::psql_init_statements();
+        statements.extend(super::archive::psql_init_statements());
+        super::psql_transaction_on(pool, statements).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_identifier_hash() {
+        // Reference values of 64-bit FNV-1a
+        assert_eq!(identifier_hash(""), 0xcbf29ce484222325);
+        assert_eq!(identifier_hash("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(identifier_hash("foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn ut_shard_index() {
+        assert_eq!(shard_index("N12345", 0), 0);
+        assert_eq!(shard_index("N12345", 1), 0);
+
+        let identifiers: Vec<String> = (0..1000).map(|index| format!("N{index}")).collect();
+        let groups = group_by_shard(identifiers.clone(), 2, |identifier| identifier.as_str());
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len() + groups[1].len(), identifiers.len());
+
+        // Both shards get a fair share
+        assert!(groups.iter().all(|group| group.len() > 400));
+
+        // Same shard every time
+        for (index, group) in groups.iter().enumerate() {
+            assert!(group
+                .iter()
+                .all(|identifier| shard_index(identifier, 2) == index));
+        }
+    }
+
+    #[test]
+    fn ut_partition_without_pool() {
+        assert!(partition(None, vec!["N12345"], |identifier| *identifier).is_none());
+        assert!(get_shard_pool(None, "N12345").is_none());
+        assert!(!is_sharded());
+    }
+
+    #[test]
+    fn ut_shard_rows_without_shards() {
+        let rows = ShardRows::none(r#""arrow"."aircraft""#);
+        assert_eq!(rows.source(4), r#""arrow"."aircraft""#);
+        assert!(rows.param().is_none());
+    }
+
+    #[test]
+    fn ut_shard_rows_source() {
+        let rows = ShardRows {
+            table_name: r#""arrow"."aircraft""#,
+            rows: Some(vec![]),
+        };
+
+        let source = rows.source(4);
+        assert!(source.contains(r#"SELECT * FROM "arrow"."aircraft""#));
+        assert!(source.contains(r#"("rows"."row"::"arrow"."aircraft").*"#));
+        assert!(source.contains("UNNEST($4::TEXT[])"));
+        assert!(rows.param().is_some());
+    }
+}
@@ -0,0 +1,496 @@
+//! This module contains region-of-interest subscriptions to aircraft
+//!  positions.
+//!
+//! A subscription tracks which aircraft are inside its region and turns
+//!  position updates into ENTER, UPDATE and LEAVE events. Region
+//!  membership uses the same polygon checks as the service area.
+//!
+//! Written aircraft positions are published on the position channel,
+//!  which is streamed by `streamAircraftPositions`. Quarantined positions
+//!  (see [`super::aircraft`]) are not published.
+
+use super::service_area::{area_from_vertices, in_area, ServiceAreaError};
+use crate::grpc::server::grpc_server::{
+    AircraftPositionEvent, Coordinates, PointZ, PositionEventType,
+};
+use crate::types::AircraftPosition;
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+/// Capacity of the position channel, slow subscribers miss older positions
+const POSITION_CHANNEL_CAPACITY: usize = 1024;
+
+/// Interval at which position streams check for shutdown
+const POSITION_STREAM_POLL_INTERVAL_MS: u64 = 1_000;
+
+/// Time the operator of an aircraft is remembered by a stream filtered by
+///  operator, so that ownership changes are picked up
+const OPERATOR_CACHE_SECS: i64 = 60;
+
+/// Written aircraft position channel
+pub static POSITIONS: Lazy<broadcast::Sender<AircraftPosition>> =
+    Lazy::new(|| broadcast::channel(POSITION_CHANNEL_CAPACITY).0);
+
+/// What a position update means for a subscription
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RegionEvent {
+    /// The aircraft entered the region
+    Enter,
+
+    /// The aircraft moved within the region
+    Update,
+
+    /// The aircraft left the region
+    Leave,
+}
+
+impl From<RegionEvent> for PositionEventType {
+    fn from(event: RegionEvent) -> Self {
+        match event {
+            RegionEvent::Enter => PositionEventType::Enter,
+            RegionEvent::Update => PositionEventType::Update,
+            RegionEvent::Leave => PositionEventType::Leave,
+        }
+    }
+}
+
+/// Options of a position subscription
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionOptions {
+    /// Only positions in this region are streamed, all positions if unset
+    pub region: Option<geo::Polygon<f64>>,
+
+    /// Only stream ENTER and LEAVE events
+    pub events_only: bool,
+
+    /// Min time between UPDATE events of an aircraft, every update if zero
+    pub decimation_secs: u32,
+
+    /// Only positions of this operator's aircraft are streamed, all
+    ///  aircraft if unset
+    pub operator_id: Option<String>,
+}
+
+impl SubscriptionOptions {
+    /// Gets the options of a subscription from the region vertices, all
+    ///  positions are streamed without vertices
+    pub fn try_new(
+        vertices: &[Coordinates],
+        events_only: bool,
+        decimation_secs: u32,
+    ) -> Result<Self, ServiceAreaError> {
+        let region = match vertices {
+            [] => None,
+            vertices => Some(area_from_vertices(
+                vertices
+                    .iter()
+                    .map(|vertex| geo::coord! { x: vertex.longitude, y: vertex.latitude })
+                    .collect(),
+            )?),
+        };
+
+        Ok(Self {
+            region,
+            events_only,
+            decimation_secs,
+            operator_id: None,
+        })
+    }
+}
+
+/// A position update with its meaning for the subscription
+#[derive(Debug, Clone)]
+pub struct PositionEvent {
+    /// The event type
+    pub event: RegionEvent,
+
+    /// The position that caused the event
+    pub position: AircraftPosition,
+}
+
+impl From<PositionEvent> for AircraftPositionEvent {
+    fn from(event: PositionEvent) -> Self {
+        let position = event.position;
+        AircraftPositionEvent {
+            event_type: PositionEventType::from(event.event) as i32,
+            identifier: position.identifier,
+            position: Some(PointZ {
+                latitude: position.position.latitude,
+                longitude: position.position.longitude,
+                altitude_meters: position.position.altitude_meters as f32,
+            }),
+            timestamp_network: Some(position.timestamp_network.into()),
+        }
+    }
+}
+
+/// Tracks the aircraft inside a subscription's region
+#[derive(Debug, Default)]
+pub struct RegionSubscription {
+    options: SubscriptionOptions,
+
+    /// Time of the last event sent for each aircraft inside the region
+    inside: HashMap<String, DateTime<Utc>>,
+}
+
+impl RegionSubscription {
+    /// Creates a subscription with no aircraft inside its region
+    pub fn new(options: SubscriptionOptions) -> Self {
+        Self {
+            options,
+            inside: HashMap::new(),
+        }
+    }
+
+    /// Number of aircraft inside the region
+    pub fn inside_count(&self) -> usize {
+        self.inside.len()
+    }
+
+    /// Updates the subscription with an aircraft position
+    ///
+    /// Returns the event to stream, if any. UPDATE events are dropped in
+    ///  events only mode and when sooner than the decimation interval
+    ///  after the previous event of the aircraft. ENTER and LEAVE events
+    ///  are never dropped.
+    pub fn update(&mut self, position: AircraftPosition) -> Option<PositionEvent> {
+        let timestamp = position.timestamp_network;
+        let inside = in_area(
+            self.options.region.as_ref(),
+            0.0,
+            position.position.longitude,
+            position.position.latitude,
+        );
+
+        let event = match (inside, self.inside.get(&position.identifier).copied()) {
+            (false, None) => return None,
+            (true, None) => {
+                self.inside.insert(position.identifier.clone(), timestamp);
+                RegionEvent::Enter
+            }
+            (false, Some(_)) => {
+                self.inside.remove(&position.identifier);
+                RegionEvent::Leave
+            }
+            (true, Some(last)) => {
+                if self.options.events_only {
+                    return None;
+                }
+
+                let interval = Duration::try_seconds(self.options.decimation_secs as i64)?;
+                if timestamp - last < interval {
+                    return None;
+                }
+
+                self.inside.insert(position.identifier.clone(), timestamp);
+                RegionEvent::Update
+            }
+        };
+
+        Some(PositionEvent { event, position })
+    }
+}
+
+/// Keeps the positions of an operator's aircraft
+///
+/// The operator of each aircraft is looked up once per
+///  [`OPERATOR_CACHE_SECS`].
+#[derive(Debug)]
+struct OperatorFilter {
+    operator_id: String,
+
+    /// If each aircraft belongs to the operator, and when that was looked up
+    known: HashMap<String, (bool, DateTime<Utc>)>,
+}
+
+impl OperatorFilter {
+    fn new(operator_id: String) -> Self {
+        Self {
+            operator_id,
+            known: HashMap::new(),
+        }
+    }
+
+    /// Checks if an aircraft belongs to the operator, aircraft whose
+    ///  operator can't be looked up are left out
+    async fn allows(&mut self, identifier: &str) -> bool {
+        let now = crate::clock::now();
+        let ttl = Duration::try_seconds(OPERATOR_CACHE_SECS).unwrap_or_default();
+        if let Some((allowed, checked)) = self.known.get(identifier) {
+            if now - *checked < ttl {
+                return *allowed;
+            }
+        }
+
+        match super::aircraft::get_aircraft_operator(identifier).await {
+            Ok(operator_id) => {
+                let allowed = operator_id.as_deref() == Some(self.operator_id.as_str());
+                self.known.insert(identifier.to_string(), (allowed, now));
+                allowed
+            }
+            Err(e) => {
+                postgis_warn!(
+                    "(OperatorFilter::allows) could not get operator of aircraft {identifier}: {e}"
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Publishes a written aircraft position to the position streams
+pub fn publish(position: &AircraftPosition) {
+    if POSITIONS.receiver_count() > 0 {
+        // Only fails without subscribers
+        let _ = POSITIONS.send(position.clone());
+    }
+}
+
+/// Stream of the events of the positions written after subscribing
+///
+/// Ends once shutdown begins so it doesn't hold up the gRPC server.
+pub fn position_stream(options: SubscriptionOptions) -> impl futures::Stream<Item = PositionEvent> {
+    let operators = options.operator_id.clone().map(OperatorFilter::new);
+    let state = (
+        POSITIONS.subscribe(),
+        RegionSubscription::new(options),
+        operators,
+    );
+    futures::stream::unfold(
+        state,
+        |(mut receiver, mut subscription, mut operators)| async move {
+            while !crate::shutdown::is_shutting_down() {
+                let poll = std::time::Duration::from_millis(POSITION_STREAM_POLL_INTERVAL_MS);
+                match tokio::time::timeout(poll, receiver.recv()).await {
+                    Ok(Ok(position)) => {
+                        if let Some(operators) = operators.as_mut() {
+                            if !operators.allows(&position.identifier).await {
+                                continue;
+                            }
+                        }
+
+                        if let Some(event) = subscription.update(position) {
+                            return Some((event, (receiver, subscription, operators)));
+                        }
+                    }
+                    Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                        postgis_warn!("(position_stream) subscriber missed {skipped} positions.");
+                    }
+                    Ok(Err(broadcast::error::RecvError::Closed)) => return None,
+                    Err(_) => (),
+                }
+            }
+
+            None
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgis::service_area::parse_service_area;
+    use crate::types::Position;
+    use futures::StreamExt;
+
+    /// Positions of an aircraft flying east once a second at `longitudes`
+    fn flight(identifier: &str, start: DateTime<Utc>, longitudes: &[f64]) -> Vec<AircraftPosition> {
+        longitudes
+            .iter()
+            .enumerate()
+            .map(|(i, longitude)| AircraftPosition {
+                identifier: identifier.to_string(),
+                position: Position {
+                    longitude: *longitude,
+                    latitude: 52.35,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network: start + Duration::try_seconds(i as i64).unwrap(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            })
+            .collect()
+    }
+
+    /// Crosses the region, 0.01 degrees east per second
+    fn crossing(identifier: &str, start: DateTime<Utc>) -> Vec<AircraftPosition> {
+        let longitudes: Vec<f64> = (0..40).map(|i| 4.755 + i as f64 * 0.01).collect();
+        flight(identifier, start, &longitudes)
+    }
+
+    fn region() -> geo::Polygon<f64> {
+        parse_service_area("4.8 52.3, 5.0 52.3, 5.0 52.4, 4.8 52.4").unwrap()
+    }
+
+    fn events(
+        subscription: &mut RegionSubscription,
+        positions: Vec<AircraftPosition>,
+    ) -> Vec<(RegionEvent, i64)> {
+        positions
+            .into_iter()
+            .filter_map(|position| subscription.update(position))
+            .map(|event| (event.event, event.position.timestamp_network.timestamp()))
+            .collect()
+    }
+
+    #[test]
+    fn ut_region_subscription_decimated() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut subscription = RegionSubscription::new(SubscriptionOptions {
+            region: Some(region()),
+            events_only: false,
+            decimation_secs: 5,
+            operator_id: None,
+        });
+
+        let events = events(&mut subscription, crossing("Mantis", start));
+        let offsets: Vec<(RegionEvent, i64)> = events
+            .into_iter()
+            .map(|(event, timestamp)| (event, timestamp - start.timestamp()))
+            .collect();
+
+        // Inside from 4.805 (5s) to 4.995 (24s), left at 5.005 (25s)
+        assert_eq!(
+            offsets,
+            vec![
+                (RegionEvent::Enter, 5),
+                (RegionEvent::Update, 10),
+                (RegionEvent::Update, 15),
+                (RegionEvent::Update, 20),
+                (RegionEvent::Leave, 25),
+            ]
+        );
+        assert_eq!(subscription.inside_count(), 0);
+    }
+
+    #[test]
+    fn ut_region_subscription_events_only() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut subscription = RegionSubscription::new(SubscriptionOptions {
+            region: Some(region()),
+            events_only: true,
+            decimation_secs: 0,
+            operator_id: None,
+        });
+
+        let mut positions = crossing("Mantis", start);
+        positions.extend(flight("Ghost", start, &[4.9, 4.91, 4.92]));
+        positions.sort_by_key(|position| position.timestamp_network);
+
+        let kinds: Vec<RegionEvent> = events(&mut subscription, positions)
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![RegionEvent::Enter, RegionEvent::Enter, RegionEvent::Leave]
+        );
+
+        // The second aircraft is still inside
+        assert_eq!(subscription.inside_count(), 1);
+    }
+
+    #[test]
+    fn ut_subscription_options_try_new() {
+        let vertex = |longitude, latitude| Coordinates {
+            latitude,
+            longitude,
+        };
+
+        let options = SubscriptionOptions::try_new(&[], true, 5).unwrap();
+        assert!(options.region.is_none());
+        assert!(options.events_only);
+        assert_eq!(options.decimation_secs, 5);
+
+        let vertices = [
+            vertex(4.8, 52.3),
+            vertex(5.0, 52.3),
+            vertex(5.0, 52.4),
+            vertex(4.8, 52.4),
+        ];
+        let options = SubscriptionOptions::try_new(&vertices, false, 0).unwrap();
+        assert_eq!(options.region, Some(region()));
+
+        assert_eq!(
+            SubscriptionOptions::try_new(&vertices[..2], false, 0).unwrap_err(),
+            ServiceAreaError::Vertices
+        );
+        assert_eq!(
+            SubscriptionOptions::try_new(
+                &[vertex(181.0, 52.3), vertex(5.0, 52.3), vertex(5.0, 52.4)],
+                false,
+                0
+            )
+            .unwrap_err(),
+            ServiceAreaError::Vertex
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_position_stream() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut stream = Box::pin(position_stream(SubscriptionOptions {
+            region: Some(region()),
+            events_only: true,
+            decimation_secs: 0,
+            operator_id: None,
+        }));
+
+        for position in crossing("Mantis", start) {
+            publish(&position);
+        }
+
+        let enter = stream.next().await.unwrap();
+        assert_eq!(enter.event, RegionEvent::Enter);
+        assert_eq!(
+            enter.position.timestamp_network.timestamp() - start.timestamp(),
+            5
+        );
+
+        let leave = AircraftPositionEvent::from(stream.next().await.unwrap());
+        assert_eq!(leave.event_type, PositionEventType::Leave as i32);
+        assert_eq!(leave.identifier, "Mantis");
+    }
+
+    #[tokio::test]
+    async fn ut_operator_filter() {
+        let now = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = crate::clock::freeze(now);
+        let mut filter = OperatorFilter::new("Operator-A".to_string());
+        filter.known.insert("Mantis".to_string(), (true, now));
+        filter.known.insert("Ghost".to_string(), (false, now));
+
+        // Remembered operators are used without a database
+        assert!(filter.allows("Mantis").await);
+        assert!(!filter.allows("Ghost").await);
+
+        // Once forgotten, aircraft whose operator can't be looked up are
+        //  left out
+        clock.advance(Duration::try_seconds(OPERATOR_CACHE_SECS).unwrap());
+        assert!(!filter.allows("Mantis").await);
+        assert!(!filter.allows("Unknown").await);
+    }
+
+    #[test]
+    fn ut_region_subscription_no_region() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut subscription = RegionSubscription::new(SubscriptionOptions::default());
+
+        let kinds: Vec<RegionEvent> = events(
+            &mut subscription,
+            flight("Mantis", start, &[0.0, 10.0, 20.0]),
+        )
+        .into_iter()
+        .map(|(event, _)| event)
+        .collect();
+
+        assert_eq!(
+            kinds,
+            vec![RegionEvent::Enter, RegionEvent::Update, RegionEvent::Update]
+        );
+    }
+}
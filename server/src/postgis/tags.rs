@@ -0,0 +1,229 @@
+//! This module contains helpers for the operational tags of zones and
+//!  flights ("exercise-redwing", "vip", "training", ...).
+//!
+//! Tags are stored in a `TEXT[]` column and matched with the GIN-indexable
+//!  array operators (`&&`, `@>`).
+
+use crate::grpc::server::grpc_server::{TagFilter as GrpcTagFilter, TagMatch};
+use num_traits::FromPrimitive;
+use once_cell::sync::OnceCell;
+
+/// Max tags on a zone or flight, and in a tag filter
+pub const MAX_TAGS: usize = 10;
+
+/// Regex for a single tag
+pub const TAG_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,255}$";
+
+/// Flights with any of these tags are left out of the internal conflict
+///  checks, e.g. exercise traffic (none if unset)
+pub static CONFLICT_EXCLUDED_TAGS: OnceCell<Vec<String>> = OnceCell::new();
+
+/// Possible errors with tags
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TagError {
+    /// More than [`MAX_TAGS`] tags
+    TooMany,
+
+    /// A tag doesn't match [`TAG_REGEX`]
+    Invalid,
+
+    /// Invalid match mode
+    Mode,
+}
+
+impl std::fmt::Display for TagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TagError::TooMany => write!(f, "Too many tags provided."),
+            TagError::Invalid => write!(f, "Invalid tag provided."),
+            TagError::Mode => write!(f, "Invalid tag match mode provided."),
+        }
+    }
+}
+
+/// Validates a list of tags
+pub fn validate_tags(tags: &[String]) -> Result<(), TagError> {
+    if tags.len() > MAX_TAGS {
+        postgis_error!(
+            "(validate_tags) {} tags provided, at most {} allowed.",
+            tags.len(),
+            MAX_TAGS
+        );
+        return Err(TagError::TooMany);
+    }
+
+    for tag in tags {
+        if let Err(e) = super::utils::check_string(tag, TAG_REGEX) {
+            postgis_error!("(validate_tags) invalid tag '{}': {}", tag, e);
+            return Err(TagError::Invalid);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses and validates comma separated tags
+pub fn parse_tags(tags: &str) -> Result<Vec<String>, TagError> {
+    let tags: Vec<String> = tags
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    validate_tags(&tags)?;
+    Ok(tags)
+}
+
+/// Filter of the flights considered by the internal conflict checks,
+///  leaving out the flights with a [`CONFLICT_EXCLUDED_TAGS`] tag
+pub fn conflict_tag_filter() -> TagFilter {
+    match CONFLICT_EXCLUDED_TAGS.get() {
+        Some(tags) if !tags.is_empty() => TagFilter {
+            tags: tags.clone(),
+            mode: TagMatch::Exclude,
+        },
+        _ => TagFilter::default(),
+    }
+}
+
+/// A validated tag filter
+///
+/// An empty list of tags matches everything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagFilter {
+    /// Tags to match
+    pub tags: Vec<String>,
+
+    /// How the tags are matched
+    pub mode: TagMatch,
+}
+
+impl Default for TagFilter {
+    fn default() -> Self {
+        TagFilter {
+            tags: vec![],
+            mode: TagMatch::Any,
+        }
+    }
+}
+
+impl TryFrom<Option<GrpcTagFilter>> for TagFilter {
+    type Error = TagError;
+
+    fn try_from(filter: Option<GrpcTagFilter>) -> Result<Self, Self::Error> {
+        let Some(filter) = filter else {
+            return Ok(TagFilter::default());
+        };
+
+        let Some(mode) = FromPrimitive::from_i32(filter.mode) else {
+            postgis_error!("(try_from GrpcTagFilter) invalid mode: {}", filter.mode);
+            return Err(TagError::Mode);
+        };
+
+        validate_tags(&filter.tags)?;
+        Ok(TagFilter {
+            tags: filter.tags,
+            mode,
+        })
+    }
+}
+
+impl TagFilter {
+    /// SQL condition matching the tags in `column` against this filter
+    ///
+    /// The tags are bound to the statement as parameter `$param`, which
+    ///  is always referenced so that the parameter list doesn't depend on
+    ///  the filter.
+    pub fn condition(&self, column: &str, param: usize) -> String {
+        match self.mode {
+            TagMatch::Any => {
+                format!(r#"(cardinality(${param}::TEXT[]) = 0 OR {column} && ${param}::TEXT[])"#)
+            }
+            TagMatch::All => format!(r#"{column} @> ${param}::TEXT[]"#),
+            TagMatch::Exclude => format!(r#"NOT ({column} && ${param}::TEXT[])"#),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_parse_tags() {
+        assert_eq!(parse_tags("").unwrap(), Vec::<String>::new());
+        assert_eq!(
+            parse_tags(" exercise-redwing, training ,").unwrap(),
+            vec!["exercise-redwing".to_string(), "training".to_string()]
+        );
+        assert_eq!(parse_tags("vip;").unwrap_err(), TagError::Invalid);
+    }
+
+    #[test]
+    fn ut_validate_tags() {
+        assert!(validate_tags(&[]).is_ok());
+        assert!(validate_tags(&["exercise-redwing".to_string(), "vip".to_string()]).is_ok());
+
+        let tags = (0..=MAX_TAGS)
+            .map(|i| format!("tag{i}"))
+            .collect::<Vec<_>>();
+        assert_eq!(validate_tags(&tags).unwrap_err(), TagError::TooMany);
+        assert!(validate_tags(&tags[..MAX_TAGS]).is_ok());
+
+        for tag in ["", "two words", "vip;", "null"] {
+            assert_eq!(
+                validate_tags(&[tag.to_string()]).unwrap_err(),
+                TagError::Invalid
+            );
+        }
+    }
+
+    #[test]
+    fn ut_tag_filter_try_from() {
+        assert_eq!(TagFilter::try_from(None).unwrap(), TagFilter::default());
+
+        let filter = TagFilter::try_from(Some(GrpcTagFilter {
+            tags: vec!["training".to_string()],
+            mode: TagMatch::Exclude as i32,
+        }))
+        .unwrap();
+        assert_eq!(filter.mode, TagMatch::Exclude);
+        assert_eq!(filter.tags, vec!["training".to_string()]);
+
+        let error = TagFilter::try_from(Some(GrpcTagFilter {
+            tags: vec![],
+            mode: 99,
+        }))
+        .unwrap_err();
+        assert_eq!(error, TagError::Mode);
+
+        let error = TagFilter::try_from(Some(GrpcTagFilter {
+            tags: vec!["vip'".to_string()],
+            mode: TagMatch::All as i32,
+        }))
+        .unwrap_err();
+        assert_eq!(error, TagError::Invalid);
+    }
+
+    #[test]
+    fn ut_tag_filter_condition() {
+        let filter = |mode| TagFilter {
+            tags: vec!["vip".to_string()],
+            mode,
+        };
+
+        assert_eq!(
+            filter(TagMatch::Any).condition(r#""flights"."tags""#, 5),
+            r#"(cardinality($5::TEXT[]) = 0 OR "flights"."tags" && $5::TEXT[])"#
+        );
+        assert_eq!(
+            filter(TagMatch::All).condition(r#""tags""#, 2),
+            r#""tags" @> $2::TEXT[]"#
+        );
+        assert_eq!(
+            filter(TagMatch::Exclude).condition(r#""tags""#, 2),
+            r#"NOT ("tags" && $2::TEXT[])"#
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! This module contains functions for ingesting compact binary aircraft
+//!  telemetry records.
+//!
+//! Each record has a fixed little-endian layout of [`RECORD_SIZE`] bytes:
+//!
+//! | Offset | Type | Field |
+//! | --- | --- | --- |
+//! | 0 | u32 | identifier index (see [`register_identifier`]) |
+//! | 4 | i32 | latitude in microdegrees |
+//! | 8 | i32 | longitude in microdegrees |
+//! | 12 | i16 | altitude in meters |
+//! | 14 | i16 | horizontal ground speed in cm/s |
+//! | 16 | i16 | vertical speed in cm/s |
+//! | 18 | u16 | track angle in centidegrees |
+//! | 20 | i64 | network timestamp in milliseconds since the UNIX epoch |
+
+use super::ingest::IngestOutcome;
+use super::{psql_transaction, PostgisError, PSQL_SCHEMA};
+use crate::types::{AircraftTelemetry, Position};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Size of a binary telemetry record in bytes
+pub const RECORD_SIZE: usize = 28;
+
+/// Max number of records in a single payload
+pub const MAX_RECORDS_PER_PAYLOAD: usize = 1000;
+
+/// Registered identifiers by index, filled as they're registered or
+///  looked up
+static IDENTIFIERS: Lazy<RwLock<HashMap<u32, String>>> = Lazy::new(Default::default);
+
+/// Possible errors with binary telemetry
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TelemetryError {
+    /// Malformed payload
+    Payload,
+
+    /// Invalid or unregistered identifier
+    Identifier,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for TelemetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TelemetryError::Payload => write!(f, "Malformed telemetry payload."),
+            TelemetryError::Identifier => write!(f, "Invalid identifier provided."),
+            TelemetryError::Client => write!(f, "Could not get backend client."),
+            TelemetryError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// Gets the name of the identifier index table
+pub(super) fn get_identifiers_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."telemetry_identifiers""#,);
+    FULL_NAME
+}
+
+/// Initializes the PostGIS database for binary telemetry.
+pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for binary telemetry
+pub(super) fn psql_init_statements() -> Vec<String> {
+    vec![format!(
+        r#"CREATE TABLE IF NOT EXISTS {table_name} (
+            "index" SERIAL PRIMARY KEY,
+            "identifier" VARCHAR(255) UNIQUE NOT NULL
+        );"#,
+        table_name = get_identifiers_table_name()
+    )]
+}
+
+/// A decoded binary telemetry record
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Record {
+    index: u32,
+    latitude_udeg: i32,
+    longitude_udeg: i32,
+    altitude_m: i16,
+    ground_speed_cmps: i16,
+    vertical_speed_cmps: i16,
+    track_angle_cdeg: u16,
+    timestamp_ms: i64,
+}
+
+impl Record {
+    /// Decodes a record from exactly [`RECORD_SIZE`] bytes
+    fn decode(bytes: &[u8; RECORD_SIZE]) -> Self {
+        let u32_at =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        let i16_at = |i: usize| i16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&bytes[20..28]);
+
+        Record {
+            index: u32_at(0),
+            latitude_udeg: u32_at(4) as i32,
+            longitude_udeg: u32_at(8) as i32,
+            altitude_m: i16_at(12),
+            ground_speed_cmps: i16_at(14),
+            vertical_speed_cmps: i16_at(16),
+            track_angle_cdeg: u16::from_le_bytes([bytes[18], bytes[19]]),
+            timestamp_ms: i64::from_le_bytes(timestamp),
+        }
+    }
+
+    /// Converts the record to the telemetry used by the protobuf path
+    fn into_telemetry(self, identifier: String) -> Option<AircraftTelemetry> {
+        let timestamp_network = DateTime::<Utc>::from_timestamp_millis(self.timestamp_ms)?;
+
+        Some(AircraftTelemetry {
+            identifier,
+            position: Position {
+                latitude: self.latitude_udeg as f64 / 1_000_000.0,
+                longitude: self.longitude_udeg as f64 / 1_000_000.0,
+                altitude_meters: self.altitude_m as f64,
+            },
+            velocity_horizontal_ground_mps: self.ground_speed_cmps as f32 / 100.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: self.vertical_speed_cmps as f32 / 100.0,
+            track_angle_degrees: self.track_angle_cdeg as f32 / 100.0,
+            timestamp_network,
+            timestamp_asset: None,
+        })
+    }
+}
+
+/// Splits a payload into records
+fn decode_payload(payload: &[u8]) -> Result<Vec<Record>, TelemetryError> {
+    if payload.is_empty() || payload.len() % RECORD_SIZE != 0 {
+        postgis_error!(
+            "(decode_payload) payload length {} is not a multiple of {}.",
+            payload.len(),
+            RECORD_SIZE
+        );
+        return Err(TelemetryError::Payload);
+    }
+
+    if payload.len() / RECORD_SIZE > MAX_RECORDS_PER_PAYLOAD {
+        postgis_error!(
+            "(decode_payload) too many records: {}",
+            payload.len() / RECORD_SIZE
+        );
+        return Err(TelemetryError::Payload);
+    }
+
+    payload
+        .chunks_exact(RECORD_SIZE)
+        .map(|chunk| {
+            let bytes: &[u8; RECORD_SIZE] = chunk.try_into().map_err(|_| {
+                postgis_error!("(decode_payload) could not read record.");
+                TelemetryError::Payload
+            })?;
+
+            Ok(Record::decode(bytes))
+        })
+        .collect()
+}
+
+/// Converts records to telemetry using the registered identifiers
+///
+/// Records with unregistered identifier indices are dropped.
+fn records_to_telemetry(
+    records: Vec<Record>,
+    identifiers: &HashMap<u32, String>,
+) -> Vec<AircraftTelemetry> {
+    records
+        .into_iter()
+        .filter_map(|record| {
+            let Some(identifier) = identifiers.get(&record.index) else {
+                postgis_warn!(
+                    "(records_to_telemetry) unregistered identifier index: {}",
+                    record.index
+                );
+                return None;
+            };
+
+            record.into_telemetry(identifier.clone())
+        })
+        .collect()
+}
+
+/// Registers an aircraft identifier for binary telemetry, returning its index
+///
+/// Registering the same identifier again returns the same index.
+pub async fn register_identifier(identifier: &str) -> Result<u32, PostgisError> {
+    postgis_debug!("(register_identifier) entry, identifier: '{identifier}'.");
+    super::aircraft::check_identifier(identifier).map_err(|e| {
+        postgis_error!(
+            "(register_identifier) invalid identifier {}: {}",
+            identifier,
+            e
+        );
+        PostgisError::Telemetry(TelemetryError::Identifier)
+    })?;
+
+    let Some(pool) = crate::postgis::get_telemetry_pool() else {
+        postgis_error!("(register_identifier) could not get psql pool.");
+        return Err(PostgisError::Telemetry(TelemetryError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(register_identifier) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Telemetry(TelemetryError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"INSERT INTO {table_name} ("identifier") VALUES ($1)
+            ON CONFLICT ("identifier") DO UPDATE SET "identifier" = EXCLUDED."identifier"
+            RETURNING "index";"#,
+            table_name = get_identifiers_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(register_identifier) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Telemetry(TelemetryError::DBError)
+        })?;
+
+    let index: i32 = client
+        .query_one(&stmt, &[&identifier])
+        .await
+        .and_then(|row| row.try_get("index"))
+        .map_err(|e| {
+            postgis_error!("(register_identifier) could not execute query: {}", e);
+            PostgisError::Telemetry(TelemetryError::DBError)
+        })?;
+
+    let index = index as u32;
+    IDENTIFIERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(index, identifier.to_string());
+
+    Ok(index)
+}
+
+/// Ingests a payload of binary telemetry records
+///
+/// Records are translated to [`AircraftTelemetry`] and stored with the
+///  same validation and upsert as the protobuf path, including its ingest
+///  guard (see [`super::aircraft::start_ingest_writers`]) and dry runs.
+///
+/// With an operator claim, the payload is rejected unless every record is
+///  of an aircraft of that operator (see
+///  [`super::aircraft::check_aircraft_operator`]).
+pub async fn ingest_binary(
+    payload: &[u8],
+    dry_run: bool,
+    operator_claim: Option<&str>,
+) -> Result<IngestOutcome, PostgisError> {
+    postgis_debug!("(ingest_binary) entry, {} bytes.", payload.len());
+    let records = decode_payload(payload).map_err(PostgisError::Telemetry)?;
+
+    let mut indices: Vec<u32> = records.iter().map(|r| r.index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+
+    let identifiers = get_identifiers(indices).await?;
+    let telemetry = records_to_telemetry(records, &identifiers);
+    if let Some(claim) = operator_claim {
+        let aircraft = telemetry.iter().map(|t| t.identifier.clone()).collect();
+        super::aircraft::check_aircraft_operator(aircraft, claim).await?;
+    }
+
+    super::aircraft::update_aircraft_telemetry(telemetry, dry_run).await
+}
+
+/// Gets the registered identifiers of indices
+///
+/// Indices are never reassigned, so known ones are served from memory and
+///  only unknown ones are looked up in the database.
+async fn get_identifiers(indices: Vec<u32>) -> Result<HashMap<u32, String>, PostgisError> {
+    let mut identifiers = HashMap::new();
+    let mut unknown: Vec<i32> = vec![];
+    {
+        let known = IDENTIFIERS.read().unwrap_or_else(|e| e.into_inner());
+        for index in indices {
+            match known.get(&index) {
+                Some(identifier) => {
+                    identifiers.insert(index, identifier.clone());
+                }
+                None => unknown.push(index as i32),
+            }
+        }
+    }
+
+    if unknown.is_empty() {
+        return Ok(identifiers);
+    }
+
+    let Some(pool) = crate::postgis::get_telemetry_pool() else {
+        postgis_error!("(get_identifiers) could not get psql pool.");
+        return Err(PostgisError::Telemetry(TelemetryError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_identifiers) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Telemetry(TelemetryError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT "index", "identifier" FROM {table_name} WHERE "index" = ANY($1);"#,
+            table_name = get_identifiers_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_identifiers) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Telemetry(TelemetryError::DBError)
+        })?;
+
+    let found = client
+        .query(&stmt, &[&unknown])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_identifiers) could not execute query: {}", e);
+            PostgisError::Telemetry(TelemetryError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            let index: i32 = row.try_get("index")?;
+            let identifier: String = row.try_get("identifier")?;
+            Ok((index as u32, identifier))
+        })
+        .collect::<Result<HashMap<u32, String>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(get_identifiers) could not get identifiers: {}", e);
+            PostgisError::Telemetry(TelemetryError::DBError)
+        })?;
+
+    IDENTIFIERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend(found.clone());
+
+    identifiers.extend(found);
+    Ok(identifiers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(record: &Record) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&record.index.to_le_bytes());
+        bytes.extend_from_slice(&record.latitude_udeg.to_le_bytes());
+        bytes.extend_from_slice(&record.longitude_udeg.to_le_bytes());
+        bytes.extend_from_slice(&record.altitude_m.to_le_bytes());
+        bytes.extend_from_slice(&record.ground_speed_cmps.to_le_bytes());
+        bytes.extend_from_slice(&record.vertical_speed_cmps.to_le_bytes());
+        bytes.extend_from_slice(&record.track_angle_cdeg.to_le_bytes());
+        bytes.extend_from_slice(&record.timestamp_ms.to_le_bytes());
+        bytes
+    }
+
+    fn record() -> Record {
+        Record {
+            index: 7,
+            latitude_udeg: 52_374_590,
+            longitude_udeg: -4_916_003,
+            altitude_m: 120,
+            ground_speed_cmps: 1250,
+            vertical_speed_cmps: -150,
+            track_angle_cdeg: 27_050,
+            timestamp_ms: 1_700_000_000_123,
+        }
+    }
+
+    #[test]
+    fn ut_decode_payload() {
+        let mut payload = encode(&record());
+        assert_eq!(payload.len(), RECORD_SIZE);
+
+        let second = Record {
+            index: 8,
+            ..record()
+        };
+        payload.extend(encode(&second));
+
+        let records = decode_payload(&payload).unwrap();
+        assert_eq!(records, vec![record(), second]);
+    }
+
+    #[test]
+    fn ut_decode_payload_invalid() {
+        assert_eq!(decode_payload(&[]).unwrap_err(), TelemetryError::Payload);
+
+        let payload = encode(&record());
+        let result = decode_payload(&payload[..RECORD_SIZE - 1]).unwrap_err();
+        assert_eq!(result, TelemetryError::Payload);
+
+        let payload = vec![0u8; RECORD_SIZE * (MAX_RECORDS_PER_PAYLOAD + 1)];
+        let result = decode_payload(&payload).unwrap_err();
+        assert_eq!(result, TelemetryError::Payload);
+    }
+
+    #[test]
+    fn ut_records_to_telemetry() {
+        let identifiers = HashMap::from([(7, "Aircraft".to_string())]);
+        let unregistered = Record {
+            index: 8,
+            ..record()
+        };
+
+        let result = records_to_telemetry(vec![record(), unregistered], &identifiers);
+        assert_eq!(result.len(), 1);
+
+        // Same logical data as the protobuf path
+        let telemetry = &result[0];
+        assert_eq!(telemetry.identifier, "Aircraft");
+        assert_eq!(telemetry.position.latitude, 52.37459);
+        assert_eq!(telemetry.position.longitude, -4.916003);
+        assert_eq!(telemetry.position.altitude_meters, 120.0);
+        assert_eq!(telemetry.velocity_horizontal_ground_mps, 12.5);
+        assert_eq!(telemetry.velocity_vertical_mps, -1.5);
+        assert_eq!(telemetry.track_angle_degrees, 270.5);
+        assert_eq!(
+            telemetry.timestamp_network.timestamp_millis(),
+            1_700_000_000_123
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_ingest_binary_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_ingest_binary_client_failure) start");
+
+        let result = ingest_binary(&[0u8; 3], false, None).await.unwrap_err();
+        assert_eq!(result, PostgisError::Telemetry(TelemetryError::Payload));
+
+        let result = ingest_binary(&encode(&record()), false, None)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Telemetry(TelemetryError::Client));
+
+        let result = register_identifier("Aircraft;").await.unwrap_err();
+        assert_eq!(result, PostgisError::Telemetry(TelemetryError::Identifier));
+
+        let result = register_identifier("Aircraft").await.unwrap_err();
+        assert_eq!(result, PostgisError::Telemetry(TelemetryError::Client));
+
+        ut_info!("(ut_ingest_binary_client_failure) success");
+    }
+}
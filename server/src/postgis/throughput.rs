@@ -0,0 +1,415 @@
+//! This module contains functions for recording and reporting hourly
+//!  flight throughput per vertiport.
+//!
+//! Completed flights are attributed to the nearest vertiport (within a snap
+//!  distance) of the first and last points of their path.
+
+use super::{psql_transaction, PostgisError, PSQL_SCHEMA};
+use crate::grpc::server::grpc_server::{
+    GetVertiportThroughputRequest, GetVertiportThroughputResponse, VertiportThroughput,
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Vertiport identifier recorded when no vertiport is within the snap distance
+pub const UNKNOWN_VERTIPORT: &str = "unknown";
+
+/// Max time range of a throughput query
+pub const MAX_THROUGHPUT_RANGE_DAYS: i64 = 366;
+
+/// Possible errors with vertiport throughput
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ThroughputError {
+    /// Invalid vertiport identifier
+    Identifier,
+
+    /// Invalid time range
+    Time,
+
+    /// Invalid snap distance
+    SnapDistance,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for ThroughputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ThroughputError::Identifier => write!(f, "Invalid identifier provided."),
+            ThroughputError::Time => write!(f, "Invalid time range provided."),
+            ThroughputError::SnapDistance => write!(f, "Invalid snap distance provided."),
+            ThroughputError::Client => write!(f, "Could not get backend client."),
+            ThroughputError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// Gets the name of the throughput table
+pub(super) fn get_table_name() -> &'static str {
+    static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."vertiport_throughput""#,);
+    FULL_NAME
+}
+
+/// Initializes the PostGIS database for vertiport throughput.
+pub async fn psql_init() -> Result<(), PostgisError> {
+    psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for vertiport throughput
+pub(super) fn psql_init_statements() -> Vec<String> {
+    vec![
+        format!(
+            r#"CREATE TABLE IF NOT EXISTS {table_name} (
+                "vertiport_identifier" VARCHAR(255) NOT NULL,
+                "hour" TIMESTAMPTZ NOT NULL,
+                "departures" BIGINT NOT NULL DEFAULT 0,
+                "arrivals" BIGINT NOT NULL DEFAULT 0,
+                PRIMARY KEY ("vertiport_identifier", "hour")
+            );"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "throughput_recorded" BOOLEAN NOT NULL DEFAULT FALSE;"#,
+            table_name = super::flight::get_flights_table_name(),
+        ),
+    ]
+}
+
+/// Records the throughput of flights that have completed since the last run
+///
+/// Each flight is only counted once. The first run records all completed
+///  flights already in the database (backfill). Returns the number of
+///  (vertiport, hour) rows updated.
+pub async fn record_throughput(snap_distance_meters: f64) -> Result<u64, PostgisError> {
+    postgis_debug!("(record_throughput) entry.");
+
+    if !snap_distance_meters.is_finite() || snap_distance_meters < 0.0 {
+        postgis_error!(
+            "(record_throughput) invalid snap distance: {}",
+            snap_distance_meters
+        );
+        return Err(PostgisError::Throughput(ThroughputError::SnapDistance));
+    }
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(record_throughput) could not get psql pool.");
+        return Err(PostgisError::Throughput(ThroughputError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(record_throughput) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Throughput(ThroughputError::Client)
+    })?;
+
+    // A single statement, so flights are marked and counted atomically
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"WITH "completed" AS (
+                UPDATE {flights_table_name}
+                SET "throughput_recorded" = TRUE
+                WHERE
+                    "throughput_recorded" = FALSE
+                    AND "simulated" = FALSE
+                    AND "deleted_at" IS NULL
+                    AND "geom" IS NOT NULL
+                    AND "time_start" IS NOT NULL
+                    AND "time_end" <= NOW()
+                RETURNING "geom", "time_start", "time_end"
+            ), "endpoints" AS (
+                SELECT
+                    ST_Force2D(ST_StartPoint("geom")) AS "point",
+                    date_trunc('hour', "time_start", 'UTC') AS "hour",
+                    1 AS "departures",
+                    0 AS "arrivals"
+                FROM "completed"
+                UNION ALL
+                SELECT
+                    ST_Force2D(ST_EndPoint("geom")) AS "point",
+                    date_trunc('hour', "time_end", 'UTC') AS "hour",
+                    0 AS "departures",
+                    1 AS "arrivals"
+                FROM "completed"
+            ), "snapped" AS (
+                SELECT
+                    COALESCE(
+                        (
+                            SELECT "vertiports"."identifier"
+                            FROM {vertiports_table_name} AS "vertiports"
+                            WHERE ST_DWithin(
+                                ST_Force2D("vertiports"."geom")::GEOGRAPHY,
+                                "endpoints"."point"::GEOGRAPHY,
+                                $1
+                            )
+                            ORDER BY
+                                ST_Distance(
+                                    ST_Force2D("vertiports"."geom")::GEOGRAPHY,
+                                    "endpoints"."point"::GEOGRAPHY
+                                ),
+                                "vertiports"."identifier"
+                            LIMIT 1
+                        ),
+                        '{UNKNOWN_VERTIPORT}'
+                    ) AS "vertiport_identifier",
+                    "hour",
+                    "departures",
+                    "arrivals"
+                FROM "endpoints"
+            )
+            INSERT INTO {table_name} ("vertiport_identifier", "hour", "departures", "arrivals")
+            SELECT "vertiport_identifier", "hour", SUM("departures"), SUM("arrivals")
+            FROM "snapped"
+            GROUP BY "vertiport_identifier", "hour"
+            ON CONFLICT ("vertiport_identifier", "hour") DO UPDATE
+                SET "departures" = {table_name}."departures" + EXCLUDED."departures",
+                    "arrivals" = {table_name}."arrivals" + EXCLUDED."arrivals";"#,
+            table_name = get_table_name(),
+            flights_table_name = super::flight::get_flights_table_name(),
+            vertiports_table_name = super::vertiport::get_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(record_throughput) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Throughput(ThroughputError::DBError)
+        })?;
+
+    let updated = client
+        .execute(&stmt, &[&snap_distance_meters])
+        .await
+        .map_err(|e| {
+            postgis_error!("(record_throughput) could not execute statement: {}", e);
+            PostgisError::Throughput(ThroughputError::DBError)
+        })?;
+
+    postgis_debug!("(record_throughput) updated {} rows.", updated);
+    Ok(updated)
+}
+
+/// Starts a loop recording completed flights every `interval_secs`
+///
+/// The first run happens immediately to backfill existing flights.
+pub async fn begin(interval_secs: u64, snap_distance_meters: f64) {
+    postgis_info!(
+        "(begin) recording vertiport throughput every {interval_secs}s (snap distance: {snap_distance_meters}m)."
+    );
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        if let Err(e) = record_throughput(snap_distance_meters).await {
+            postgis_warn!("(begin) could not record vertiport throughput: {e}");
+        }
+    }
+}
+
+/// Validates a throughput request, returning the time range
+fn validate_throughput_request(
+    request: &GetVertiportThroughputRequest,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), ThroughputError> {
+    if let Err(e) =
+        super::utils::check_string(&request.identifier, super::vertiport::IDENTIFIER_REGEX)
+    {
+        postgis_error!(
+            "(validate_throughput_request) invalid identifier {}: {}",
+            request.identifier,
+            e
+        );
+        return Err(ThroughputError::Identifier);
+    }
+
+    let (Some(time_start), Some(time_end)) = (request.time_start.clone(), request.time_end.clone())
+    else {
+        postgis_error!("(validate_throughput_request) time_start and time_end are required.");
+        return Err(ThroughputError::Time);
+    };
+
+    let time_start: DateTime<Utc> = time_start.into();
+    let time_end: DateTime<Utc> = time_end.into();
+    if time_end <= time_start {
+        postgis_error!("(validate_throughput_request) time_end must be after time_start.");
+        return Err(ThroughputError::Time);
+    }
+
+    let Some(max_range) = Duration::try_days(MAX_THROUGHPUT_RANGE_DAYS) else {
+        postgis_error!("(validate_throughput_request) could not get max time range.");
+        return Err(ThroughputError::Time);
+    };
+
+    if time_end - time_start > max_range {
+        postgis_error!(
+            "(validate_throughput_request) time range exceeds {} days.",
+            MAX_THROUGHPUT_RANGE_DAYS
+        );
+        return Err(ThroughputError::Time);
+    }
+
+    Ok((time_start, time_end))
+}
+
+/// Gets the hourly departures and arrivals of a vertiport
+///
+/// Hours without flights are omitted from the series.
+pub async fn get_vertiport_throughput(
+    request: GetVertiportThroughputRequest,
+) -> Result<GetVertiportThroughputResponse, PostgisError> {
+    postgis_debug!("(get_vertiport_throughput) entry.");
+    let (time_start, time_end) =
+        validate_throughput_request(&request).map_err(PostgisError::Throughput)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_vertiport_throughput) could not get psql pool.");
+        return Err(PostgisError::Throughput(ThroughputError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_vertiport_throughput) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Throughput(ThroughputError::Client)
+    })?;
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT "hour", "departures", "arrivals"
+            FROM {table_name}
+            WHERE
+                "vertiport_identifier" = $1
+                AND "hour" >= date_trunc('hour', $2::TIMESTAMPTZ, 'UTC')
+                AND "hour" < $3
+            ORDER BY "hour";"#,
+            table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(get_vertiport_throughput) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Throughput(ThroughputError::DBError)
+        })?;
+
+    let series = client
+        .query(&stmt, &[&request.identifier, &time_start, &time_end])
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_vertiport_throughput) could not execute query: {}", e);
+            PostgisError::Throughput(ThroughputError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            let hour: DateTime<Utc> = row.try_get("hour")?;
+            let departures: i64 = row.try_get("departures")?;
+            let arrivals: i64 = row.try_get("arrivals")?;
+
+            Ok(VertiportThroughput {
+                hour: Some(hour.into()),
+                departures: departures.max(0) as u64,
+                arrivals: arrivals.max(0) as u64,
+            })
+        })
+        .collect::<Result<Vec<VertiportThroughput>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!(
+                "(get_vertiport_throughput) could not get throughput data: {}",
+                e
+            );
+            PostgisError::Throughput(ThroughputError::DBError)
+        })?;
+
+    Ok(GetVertiportThroughputResponse { series })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> GetVertiportThroughputRequest {
+        let time_end = Utc::now();
+        GetVertiportThroughputRequest {
+            identifier: "vertiport".to_string(),
+            time_start: Some((time_end - Duration::try_days(1).unwrap()).into()),
+            time_end: Some(time_end.into()),
+        }
+    }
+
+    #[test]
+    fn ut_validate_throughput_request() {
+        assert!(validate_throughput_request(&request()).is_ok());
+
+        // The unknown vertiport can be queried
+        let valid = GetVertiportThroughputRequest {
+            identifier: UNKNOWN_VERTIPORT.to_string(),
+            ..request()
+        };
+        assert!(validate_throughput_request(&valid).is_ok());
+
+        let invalid = GetVertiportThroughputRequest {
+            identifier: "'vertiport'".to_string(),
+            ..request()
+        };
+        assert_eq!(
+            validate_throughput_request(&invalid).unwrap_err(),
+            ThroughputError::Identifier
+        );
+
+        let invalid = GetVertiportThroughputRequest {
+            time_start: request().time_end,
+            ..request()
+        };
+        assert_eq!(
+            validate_throughput_request(&invalid).unwrap_err(),
+            ThroughputError::Time
+        );
+
+        let invalid = GetVertiportThroughputRequest {
+            time_start: None,
+            ..request()
+        };
+        assert_eq!(
+            validate_throughput_request(&invalid).unwrap_err(),
+            ThroughputError::Time
+        );
+
+        let time_end = Utc::now();
+        let invalid = GetVertiportThroughputRequest {
+            time_start: Some(
+                (time_end - Duration::try_days(MAX_THROUGHPUT_RANGE_DAYS + 1).unwrap()).into(),
+            ),
+            time_end: Some(time_end.into()),
+            ..request()
+        };
+        assert_eq!(
+            validate_throughput_request(&invalid).unwrap_err(),
+            ThroughputError::Time
+        );
+    }
+
+    #[tokio::test]
+    async fn ut_throughput_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_throughput_client_failure) start");
+
+        let result = get_vertiport_throughput(request()).await.unwrap_err();
+        assert_eq!(result, PostgisError::Throughput(ThroughputError::Client));
+
+        let result = record_throughput(-1.0).await.unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::Throughput(ThroughputError::SnapDistance)
+        );
+
+        let result = record_throughput(100.0).await.unwrap_err();
+        assert_eq!(result, PostgisError::Throughput(ThroughputError::Client));
+
+        ut_info!("(ut_throughput_client_failure) success");
+    }
+}
@@ -0,0 +1,384 @@
+//! This module contains functions for producing Mapbox Vector Tiles (MVT)
+//!  of the contents of the PostGIS database for map rendering.
+
+use super::shard::ShardRows;
+use super::{PostgisError, DEFAULT_SRID};
+use crate::grpc::server::grpc_server::{GetTileRequest, TileLayer};
+use num_traits::FromPrimitive;
+use tokio_postgres::types::ToSql;
+
+/// Max zoom level for which tiles are produced
+pub const MAX_TILE_ZOOM: u32 = 22;
+
+/// Max number of features in each layer of a tile
+pub const MAX_TILE_FEATURES_PER_LAYER: i64 = 1000;
+
+/// The extent of a tile in tile coordinate space
+const TILE_EXTENT: i32 = 4096;
+
+/// Spatial Reference Identifier of the Web Mercator projection used by tiles
+const WEB_MERCATOR_SRID: i32 = 3857;
+
+/// Possible errors with tile requests
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TileError {
+    /// Invalid zoom level or tile coordinates
+    Coordinates,
+
+    /// Invalid or missing layers
+    Layers,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for TileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TileError::Coordinates => write!(f, "Invalid tile coordinates provided."),
+            TileError::Layers => write!(f, "Invalid tile layers provided."),
+            TileError::Client => write!(f, "Could not get backend client."),
+            TileError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// Helper struct for validating tile requests
+#[derive(Debug, PartialEq)]
+struct Tile {
+    z: i32,
+    x: i32,
+    y: i32,
+    layers: Vec<TileLayer>,
+}
+
+impl TryFrom<GetTileRequest> for Tile {
+    type Error = TileError;
+
+    fn try_from(request: GetTileRequest) -> Result<Self, Self::Error> {
+        if request.z > MAX_TILE_ZOOM {
+            postgis_error!(
+                "(try_from GetTileRequest) invalid zoom level: {}",
+                request.z
+            );
+            return Err(TileError::Coordinates);
+        }
+
+        // There are 2^z tiles along each axis at zoom level z
+        let tile_count: u32 = 1 << request.z;
+        if request.x >= tile_count || request.y >= tile_count {
+            postgis_error!(
+                "(try_from GetTileRequest) invalid tile coordinates for zoom {}: ({}, {})",
+                request.z,
+                request.x,
+                request.y
+            );
+            return Err(TileError::Coordinates);
+        }
+
+        if request.layers.is_empty() {
+            postgis_error!("(try_from GetTileRequest) no layers requested.");
+            return Err(TileError::Layers);
+        }
+
+        let mut layers: Vec<TileLayer> = vec![];
+        for layer in request.layers {
+            let Some(layer) = FromPrimitive::from_i32(layer) else {
+                postgis_error!("(try_from GetTileRequest) invalid layer: {}", layer);
+                return Err(TileError::Layers);
+            };
+
+            // Each layer is only added to the tile once
+            if !layers.contains(&layer) {
+                layers.push(layer);
+            }
+        }
+
+        Ok(Tile {
+            z: request.z as i32,
+            x: request.x as i32,
+            y: request.y as i32,
+            layers,
+        })
+    }
+}
+
+/// Footprint of a zone, the union of the faces of its extruded volume
+///  projected to 2D
+///
+/// Vertical faces project to zero area and are left out, the floor and
+///  ceiling both project to the footprint.
+const ZONE_FOOTPRINT: &str = r#"(
+    SELECT ST_Union(ST_Force2D("faces"."geom"))
+    FROM ST_Dump("t"."geom") AS "faces"
+    WHERE ST_Area(ST_Force2D("faces"."geom")) > 0
+)"#;
+
+/// Gets the layer name used in the tile for the provided layer
+pub fn get_layer_name(layer: TileLayer) -> &'static str {
+    match layer {
+        TileLayer::Aircraft => "aircraft",
+        TileLayer::Flights => "flights",
+        TileLayer::Zones => "zones",
+        TileLayer::Vertiports => "vertiports",
+    }
+}
+
+/// Generates the statement producing a single layer of a tile
+///
+/// $1, $2, $3 are the zoom level, x, and y of the tile. The aircraft
+///  layer reads aircraft from `aircraft_source` (see
+///  [`super::shard::ShardRows`]).
+fn get_layer_stmt(layer: TileLayer, aircraft_source: &str) -> String {
+    // Soft-deleted flights and zones are hidden
+    let (table_name, attributes, geom, filter) = match layer {
+        TileLayer::Aircraft => (
+            aircraft_source,
+            r#""identifier", "session_id", "aircraft_type"::TEXT, "op_status"::TEXT"#,
+            r#""t"."geom""#,
+            "",
+        ),
+        TileLayer::Flights => (
+            super::flight::get_flights_table_name(),
+            r#""flight_identifier", "aircraft_identifier", "aircraft_type"::TEXT, "time_start"::TEXT, "time_end"::TEXT"#,
+            r#""t"."geom""#,
+            r#"AND "t"."deleted_at" IS NULL"#,
+        ),
+        TileLayer::Zones => (
+            super::zone::get_table_name(),
+            r#""identifier", "zone_type"::TEXT, "altitude_meters_min", "altitude_meters_max""#,
+            ZONE_FOOTPRINT,
+            r#"AND "t"."deleted_at" IS NULL"#,
+        ),
+        TileLayer::Vertiports => (
+            super::vertiport::get_table_name(),
+            r#""identifier", "label", "altitude_meters""#,
+            r#""t"."geom""#,
+            "",
+        ),
+    };
+
+    format!(
+        r#"WITH "bounds" AS (
+            SELECT ST_TileEnvelope($1, $2, $3) AS "geom"
+        ), "features" AS (
+            SELECT
+                {attributes},
+                ST_AsMVTGeom(
+                    ST_Transform(ST_Force2D({geom}), {WEB_MERCATOR_SRID}),
+                    "bounds"."geom",
+                    {TILE_EXTENT}
+                ) AS "geom"
+            FROM {table_name} AS "t", "bounds"
+            WHERE
+                "t"."geom" IS NOT NULL
+                {filter}
+                AND ST_Intersects(
+                    "t"."geom",
+                    ST_Transform("bounds"."geom", {DEFAULT_SRID})
+                )
+            LIMIT {MAX_TILE_FEATURES_PER_LAYER}
+        ) SELECT ST_AsMVT("features", '{layer_name}', {TILE_EXTENT}, 'geom')
+        FROM "features"
+        WHERE "geom" IS NOT NULL;"#,
+        layer_name = get_layer_name(layer)
+    )
+}
+
+/// Produces a Mapbox Vector Tile containing the requested layers
+///  clipped to the tile envelope.
+pub async fn get_tile(request: GetTileRequest) -> Result<Vec<u8>, PostgisError> {
+    postgis_debug!("(get_tile) entry.");
+    let tile = Tile::try_from(request).map_err(PostgisError::Tile)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(get_tile) could not get psql pool.");
+        return Err(PostgisError::Tile(TileError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(get_tile) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Tile(TileError::Client)
+    })?;
+
+    // Aircraft on other shards within the tile
+    let shard_rows = if tile.layers.contains(&TileLayer::Aircraft) {
+        ShardRows::fetch(
+            &client,
+            super::aircraft::position_source(),
+            &format!(
+                r#""geom" IS NOT NULL
+                AND ST_Intersects("geom", ST_Transform(ST_TileEnvelope($1, $2, $3), {DEFAULT_SRID}))"#
+            ),
+            &[&tile.z, &tile.x, &tile.y],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(get_tile) could not get shard rows: {}", e);
+            PostgisError::Tile(TileError::DBError)
+        })?
+    } else {
+        ShardRows::none(super::aircraft::position_source())
+    };
+
+    // Layers encoded as MVT can be concatenated into a single tile
+    let mut result: Vec<u8> = vec![];
+    for layer in tile.layers {
+        let mut params: Vec<&(dyn ToSql + Sync)> = vec![&tile.z, &tile.x, &tile.y];
+        if layer == TileLayer::Aircraft {
+            params.extend(shard_rows.param());
+        }
+
+        let stmt = client
+            .prepare_cached(&get_layer_stmt(layer, &shard_rows.source(4)))
+            .await
+            .map_err(|e| {
+                postgis_error!("(get_tile) could not prepare cached statement: {}", e);
+                PostgisError::Tile(TileError::DBError)
+            })?;
+
+        // An empty layer may come back as NULL
+        let bytes: Option<Vec<u8>> = client
+            .query_one(&stmt, &params)
+            .await
+            .map_err(|e| {
+                postgis_error!("(get_tile) could not execute query: {}", e);
+                PostgisError::Tile(TileError::DBError)
+            })?
+            .try_get(0)
+            .map_err(|e| {
+                postgis_error!("(get_tile) could not get tile data: {}", e);
+                PostgisError::Tile(TileError::DBError)
+            })?;
+
+        result.extend(bytes.unwrap_or_default());
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_request_valid() {
+        let request = GetTileRequest {
+            z: 2,
+            x: 3,
+            y: 0,
+            layers: vec![
+                TileLayer::Aircraft as i32,
+                TileLayer::Zones as i32,
+                TileLayer::Aircraft as i32,
+            ],
+        };
+
+        let tile = Tile::try_from(request).unwrap();
+        assert_eq!(
+            tile,
+            Tile {
+                z: 2,
+                x: 3,
+                y: 0,
+                layers: vec![TileLayer::Aircraft, TileLayer::Zones],
+            }
+        );
+    }
+
+    #[test]
+    fn ut_request_invalid_coordinates() {
+        let request = GetTileRequest {
+            z: MAX_TILE_ZOOM + 1,
+            x: 0,
+            y: 0,
+            layers: vec![TileLayer::Aircraft as i32],
+        };
+
+        let result = Tile::try_from(request).unwrap_err();
+        assert_eq!(result, TileError::Coordinates);
+
+        for (x, y) in [(4, 0), (0, 4)] {
+            let request = GetTileRequest {
+                z: 2,
+                x,
+                y,
+                layers: vec![TileLayer::Aircraft as i32],
+            };
+
+            let result = Tile::try_from(request).unwrap_err();
+            assert_eq!(result, TileError::Coordinates);
+        }
+    }
+
+    #[test]
+    fn ut_request_invalid_layers() {
+        let request = GetTileRequest {
+            z: 0,
+            x: 0,
+            y: 0,
+            layers: vec![],
+        };
+
+        let result = Tile::try_from(request).unwrap_err();
+        assert_eq!(result, TileError::Layers);
+
+        let request = GetTileRequest {
+            z: 0,
+            x: 0,
+            y: 0,
+            layers: vec![TileLayer::Aircraft as i32, 100],
+        };
+
+        let result = Tile::try_from(request).unwrap_err();
+        assert_eq!(result, TileError::Layers);
+    }
+
+    #[test]
+    fn ut_layer_stmt() {
+        let aircraft = crate::postgis::aircraft::get_table_name();
+        for layer in [
+            TileLayer::Aircraft,
+            TileLayer::Flights,
+            TileLayer::Zones,
+            TileLayer::Vertiports,
+        ] {
+            let stmt = get_layer_stmt(layer, aircraft);
+            assert!(stmt.contains(&format!("'{}'", get_layer_name(layer))));
+            assert!(stmt.contains(&format!("LIMIT {MAX_TILE_FEATURES_PER_LAYER}")));
+        }
+
+        assert!(get_layer_stmt(TileLayer::Zones, aircraft).contains(r#""deleted_at" IS NULL"#));
+        assert!(get_layer_stmt(TileLayer::Flights, aircraft).contains(r#""deleted_at" IS NULL"#));
+        assert!(!get_layer_stmt(TileLayer::Aircraft, aircraft).contains("deleted_at"));
+        assert!(get_layer_stmt(TileLayer::Zones, aircraft).contains(ZONE_FOOTPRINT));
+
+        // Aircraft of other shards are read from the provided source
+        let stmt = get_layer_stmt(TileLayer::Aircraft, "(SELECT 1)");
+        assert!(stmt.contains(r#"FROM (SELECT 1) AS "t""#));
+    }
+
+    #[tokio::test]
+    async fn ut_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_client_failure) start");
+
+        let request = GetTileRequest {
+            z: 0,
+            x: 0,
+            y: 0,
+            layers: vec![TileLayer::Aircraft as i32],
+        };
+
+        let result = get_tile(request).await.unwrap_err();
+        assert_eq!(result, PostgisError::Tile(TileError::Client));
+
+        ut_info!("(ut_client_failure) success");
+    }
+}
@@ -6,11 +6,25 @@ use crate::grpc::server::grpc_server::{Coordinates, PointZ as GrpcPointZ};
 use crate::types::Position;
 use chrono::{DateTime, Duration, Utc};
 use deadpool_postgres::tokio_postgres::{types::ToSql, Row};
+use geo::algorithm::geodesic_distance::GeodesicDistance;
 use geo::algorithm::haversine_distance::HaversineDistance;
 use geo::point;
+use once_cell::sync::OnceCell;
 use postgis::ewkb::{LineStringT, LineStringZ, Point, PointZ, PolygonZ};
 use regex;
 
+/// Southernmost valid latitude in degrees
+pub const LAT_MIN: f64 = -90.0;
+
+/// Northernmost valid latitude in degrees
+pub const LAT_MAX: f64 = 90.0;
+
+/// Westernmost valid longitude in degrees
+pub const LON_MIN: f64 = -180.0;
+
+/// Easternmost valid longitude in degrees
+pub const LON_MAX: f64 = 180.0;
+
 /// A polygon must have at least three vertices (a triangle)
 /// A closed polygon has the first and last vertex equal
 /// Therefore, four vertices needed to indicate a closed triangular region
@@ -27,6 +41,9 @@ pub enum PolygonError {
 
     /// A vertex does not fit within the valid range of latitude and longitude
     OutOfBounds,
+
+    /// The ring crosses or touches itself
+    SelfIntersection,
 }
 
 impl std::fmt::Display for PolygonError {
@@ -38,6 +55,7 @@ impl std::fmt::Display for PolygonError {
                 "The first and last vertices do not match (open polygon)."
             ),
             PolygonError::OutOfBounds => write!(f, "One or more vertices are out of bounds."),
+            PolygonError::SelfIntersection => write!(f, "The polygon intersects itself."),
         }
     }
 }
@@ -66,6 +84,33 @@ pub enum StringError {
     /// Provided string contains invalid keywords
     ContainsForbidden,
 
+    /// Provided string is shorter than allowed (e.g. empty)
+    TooShort {
+        /// Length in characters
+        length: usize,
+
+        /// Min length in characters
+        min: usize,
+    },
+
+    /// Provided string is longer than allowed
+    TooLong {
+        /// Length in characters
+        length: usize,
+
+        /// Max length in characters
+        max: usize,
+    },
+
+    /// Provided string contains a character that isn't allowed
+    Character {
+        /// First offending character
+        character: char,
+
+        /// Index of the character, in characters
+        index: usize,
+    },
+
     /// Provided string doesn't match regex
     Mismatch,
 }
@@ -76,11 +121,42 @@ impl std::fmt::Display for StringError {
             StringError::Regex => write!(f, "Regex is invalid."),
             StringError::Mismatch => write!(f, "String does not match regex."),
             StringError::ContainsForbidden => write!(f, "String contains 'null'."),
+            StringError::TooShort { length: 0, .. } => write!(f, "String is empty."),
+            StringError::TooShort { length, min } => write!(
+                f,
+                "String is {length} characters long, at least {min} required."
+            ),
+            StringError::TooLong { length, max } => write!(
+                f,
+                "String is {length} characters long, at most {max} allowed."
+            ),
+            StringError::Character { character, index } => write!(
+                f,
+                "Character {character:?} at index {index} is not allowed."
+            ),
         }
     }
 }
 
+/// Splits a `^[class]{min,max}$` regex into the regex of a single
+///  character and the length bounds
+///
+/// Returns none for other regexes, which are only reported as a mismatch.
+fn string_rules(regex: &str) -> Option<(String, usize, usize)> {
+    let body = regex.strip_prefix('^')?.strip_suffix('$')?;
+    let (class, bounds) = body.rsplit_once('{')?;
+    if !class.starts_with('[') || !class.ends_with(']') {
+        return None;
+    }
+
+    let (min, max) = bounds.strip_suffix('}')?.split_once(',')?;
+    Some((format!("^{class}$"), min.parse().ok()?, max.parse().ok()?))
+}
+
 /// Check if a provided string argument is valid
+///
+/// For `^[class]{min,max}$` regexes the error tells which rule failed:
+///  the length, or the first character outside of the class.
 pub fn check_string(string: &str, regex: &str) -> Result<(), StringError> {
     let Ok(re) = regex::Regex::new(regex) else {
         return Err(StringError::Regex);
@@ -90,43 +166,212 @@ pub fn check_string(string: &str, regex: &str) -> Result<(), StringError> {
         return Err(StringError::ContainsForbidden);
     }
 
-    if !re.is_match(string) {
+    if re.is_match(string) {
+        return Ok(());
+    }
+
+    let Some((class, min, max)) = string_rules(regex) else {
         return Err(StringError::Mismatch);
+    };
+
+    let length = string.chars().count();
+    if length < min {
+        return Err(StringError::TooShort { length, min });
     }
 
-    Ok(())
+    if length > max {
+        return Err(StringError::TooLong { length, max });
+    }
+
+    let Ok(class) = regex::Regex::new(&class) else {
+        return Err(StringError::Mismatch);
+    };
+
+    let mut buffer = [0; 4];
+    string
+        .chars()
+        .enumerate()
+        .find(|(_, character)| !class.is_match(character.encode_utf8(&mut buffer)))
+        .map_or(Err(StringError::Mismatch), |(index, character)| {
+            Err(StringError::Character { character, index })
+        })
+}
+
+/// Allowed characters in a label, such as an operator or scenario
+pub const LABEL_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,255}$";
+
+/// Verifies that a label is valid
+///
+/// Labels are stored in `VARCHAR(255)` columns, unlike aircraft and
+///  flight identifiers.
+pub fn check_label(label: &str) -> Result<(), StringError> {
+    check_string(label, LABEL_REGEX)
 }
 
 /// Approximate the distance between these two points
 pub fn distance_meters(a: &PointZ, b: &PointZ) -> f32 {
+    haversine_distance_meters(a, b) as f32
+}
+
+/// Approximate the distance between these two points, in double precision
+///
+/// Use this when summing distances: in single precision the rounding error
+///  of each addition grows with the total and can reach meters over long
+///  paths.
+pub fn haversine_distance_meters(a: &PointZ, b: &PointZ) -> f64 {
     let p1 = point!(x: a.x, y: a.y);
     let p2 = point!(x: b.x, y: b.y);
 
     let distance_meters = p1.haversine_distance(&p2);
 
     // the Z coordinate is already in meters
-    (distance_meters.powf(2.) + (a.z - b.z).powf(2.)).sqrt() as f32
+    (distance_meters.powf(2.) + (a.z - b.z).powf(2.)).sqrt()
+}
+
+/// Rounding step of returned distances and altitudes in meters,
+///  [`DEFAULT_DISTANCE_RESOLUTION_METERS`] if unset
+pub static DISTANCE_RESOLUTION_METERS: OnceCell<f64> = OnceCell::new();
+
+/// Default rounding step of returned distances and altitudes
+///
+/// A single precision float keeps this step for values up to about
+///  500 km, beyond the longest flight.
+pub const DEFAULT_DISTANCE_RESOLUTION_METERS: f64 = 0.1;
+
+/// Rounds a distance or altitude in meters to a step, 0 keeps the value
+fn round_meters(meters: f64, step: f64) -> f32 {
+    if step > 0.0 {
+        ((meters / step).round() * step) as f32
+    } else {
+        meters as f32
+    }
+}
+
+/// Rounds a distance or altitude in meters to the configured
+///  [`DISTANCE_RESOLUTION_METERS`] for a response
+///
+/// Responses carry distances as single precision floats, this makes the
+///  precision of the conversion explicit.
+pub fn response_meters(meters: f64) -> f32 {
+    let step = DISTANCE_RESOLUTION_METERS
+        .get()
+        .copied()
+        .unwrap_or(DEFAULT_DISTANCE_RESOLUTION_METERS);
+
+    round_meters(meters, step)
+}
+
+/// Distance between these two points on the WGS84 ellipsoid
+///
+/// Slower than [`distance_meters`], which assumes a spherical earth.
+pub fn geodesic_distance_meters(a: &PointZ, b: &PointZ) -> f64 {
+    let p1 = point!(x: a.x, y: a.y);
+    let p2 = point!(x: b.x, y: b.y);
+
+    let distance_meters = p1.geodesic_distance(&p2);
+
+    // the Z coordinate is already in meters
+    (distance_meters.powi(2) + (a.z - b.z).powi(2)).sqrt()
 }
 
 /// Validate a PointZ
+///
+/// NaN fails every range comparison, so non-finite values are rejected
+///  explicitly.
 pub fn validate_pointz(point: &PointZ) -> Result<(), PolygonError> {
-    if point.x < -180.0 || point.x > 180.0 || point.y < -90.0 || point.y > 90.0 {
+    if !point.x.is_finite() || !point.y.is_finite() || !point.z.is_finite() {
+        return Err(PolygonError::OutOfBounds);
+    }
+
+    if point.x < LON_MIN || point.x > LON_MAX || point.y < LAT_MIN || point.y > LAT_MAX {
         return Err(PolygonError::OutOfBounds);
     }
 
     Ok(())
 }
 
+/// Precision of stored coordinates, [`Quantization::default`] if unset
+pub static QUANTIZATION: OnceCell<Quantization> = OnceCell::new();
+
+/// Rounding of coordinates before they are stored
+///
+/// Positions that only differ below the quantum are stored identically,
+///  so equality checks on stored geometries (e.g. unchanged flight paths)
+///  aren't defeated by sensor noise in the last decimals.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quantization {
+    /// Step of longitudes and latitudes in degrees, 0 to disable
+    pub degrees: f64,
+
+    /// Step of altitudes in meters, 0 to disable
+    pub meters: f64,
+}
+
+impl Default for Quantization {
+    fn default() -> Self {
+        // ~1 cm at the equator
+        Quantization {
+            degrees: 1e-7,
+            meters: 0.1,
+        }
+    }
+}
+
+impl Quantization {
+    /// Creates a quantization, steps must be finite and not negative
+    pub fn new(degrees: f64, meters: f64) -> Result<Self, ()> {
+        let valid = |step: f64| step.is_finite() && step >= 0.0;
+        if !valid(degrees) || !valid(meters) {
+            return Err(());
+        }
+
+        Ok(Quantization { degrees, meters })
+    }
+
+    /// Rounds a point to the quantization steps
+    pub fn apply(&self, point: PointZ) -> PointZ {
+        let round = |value: f64, step: f64| {
+            if step > 0.0 {
+                (value / step).round() * step
+            } else {
+                value
+            }
+        };
+
+        PointZ::new(
+            round(point.x, self.degrees),
+            round(point.y, self.degrees),
+            round(point.z, self.meters),
+            point.srid,
+        )
+    }
+}
+
+/// Rounds a point to the configured [`QUANTIZATION`]
+pub fn quantize_pointz(point: PointZ) -> PointZ {
+    QUANTIZATION.get().copied().unwrap_or_default().apply(point)
+}
+
+/// Converts a position to a PointZ without quantization
+pub fn raw_pointz(position: &Position) -> PointZ {
+    PointZ::new(
+        position.longitude,
+        position.latitude,
+        position.altitude_meters,
+        Some(DEFAULT_SRID),
+    )
+}
+
 impl TryFrom<Position> for PointZ {
     type Error = ();
 
     fn try_from(position: Position) -> Result<Self, Self::Error> {
-        Ok(PointZ::new(
-            position.longitude,
-            position.latitude,
-            position.altitude_meters,
-            Some(DEFAULT_SRID),
-        ))
+        let point = raw_pointz(&position);
+        if !point.x.is_finite() || !point.y.is_finite() || !point.z.is_finite() {
+            return Err(());
+        }
+
+        Ok(quantize_pointz(point))
     }
 }
 
@@ -134,13 +379,68 @@ impl TryFrom<GrpcPointZ> for PointZ {
     type Error = ();
 
     fn try_from(position: GrpcPointZ) -> Result<Self, Self::Error> {
-        Ok(PointZ::new(
+        if !position.longitude.is_finite()
+            || !position.latitude.is_finite()
+            || !position.altitude_meters.is_finite()
+        {
+            return Err(());
+        }
+
+        Ok(quantize_pointz(PointZ::new(
             position.longitude,
             position.latitude,
             position.altitude_meters as f64,
             Some(DEFAULT_SRID),
-        ))
+        )))
+    }
+}
+
+/// A point whose latitude and longitude can't be mistaken for each other
+///  (Amsterdam), used to check the coordinate order at startup
+pub const COORDINATE_CHECK_POINT: GrpcPointZ = GrpcPointZ {
+    latitude: 52.3745905,
+    longitude: 4.9160036,
+    altitude_meters: 100.0,
+};
+
+/// Describes how a point differs from the original after a round trip
+///  through conversions, `None` if it survived
+///
+/// Differences within the configured [`QUANTIZATION`] are accepted.
+pub fn coordinate_order_issue(original: &GrpcPointZ, round_trip: &GrpcPointZ) -> Option<String> {
+    let quantization = QUANTIZATION.get().copied().unwrap_or_default();
+    let degrees = quantization.degrees + 1e-9;
+    let meters = quantization.meters + 0.01;
+    let close = |a: f64, b: f64, tolerance: f64| (a - b).abs() <= tolerance;
+
+    if close(original.latitude, round_trip.latitude, degrees)
+        && close(original.longitude, round_trip.longitude, degrees)
+        && close(
+            original.altitude_meters as f64,
+            round_trip.altitude_meters as f64,
+            meters,
+        )
+    {
+        return None;
     }
+
+    let swapped = close(original.latitude, round_trip.longitude, degrees)
+        && close(original.longitude, round_trip.latitude, degrees);
+
+    Some(format!(
+        "expected (lat {}, lon {}, alt {}), got (lat {}, lon {}, alt {}){}",
+        original.latitude,
+        original.longitude,
+        original.altitude_meters,
+        round_trip.latitude,
+        round_trip.longitude,
+        round_trip.altitude_meters,
+        if swapped {
+            ": latitude and longitude are swapped"
+        } else {
+            ""
+        }
+    ))
 }
 
 /// Generate a PostGIS Polygon from a list of vertices
@@ -178,6 +478,11 @@ pub fn polygon_from_vertices_z(
         return Err(PolygonError::OutOfBounds);
     }
 
+    if let Some(intersection) = ring_self_intersection(vertices) {
+        postgis_warn!("(polygon_from_vertices_z) {}", intersection);
+        return Err(PolygonError::SelfIntersection);
+    }
+
     Ok(PolygonZ {
         rings: vec![LineStringT {
             points: vertices
@@ -195,14 +500,212 @@ pub fn polygon_from_vertices_z(
     })
 }
 
+/// Where a polygon ring crosses or touches itself
+#[derive(Debug, Clone, PartialEq)]
+pub enum RingIntersection {
+    /// A vertex repeats an earlier, non-adjacent vertex
+    RepeatedVertex {
+        /// Index of the earlier vertex
+        first: usize,
+
+        /// Index of the repeating vertex
+        repeat: usize,
+
+        /// The repeated location
+        location: Coordinates,
+    },
+
+    /// Two non-adjacent edges cross or overlap
+    Segments {
+        /// Index of the first vertex of the first edge
+        first: usize,
+
+        /// Index of the first vertex of the second edge
+        second: usize,
+
+        /// The first intersection point
+        location: Coordinates,
+    },
+}
+
+impl RingIntersection {
+    /// The location of the intersection
+    pub fn location(&self) -> &Coordinates {
+        match self {
+            RingIntersection::RepeatedVertex { location, .. } => location,
+            RingIntersection::Segments { location, .. } => location,
+        }
+    }
+}
+
+impl std::fmt::Display for RingIntersection {
+    /// Formatted like PostGIS `ST_IsValidReason`, with the location as
+    ///  `[longitude latitude]`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RingIntersection::RepeatedVertex {
+                first,
+                repeat,
+                location,
+            } => write!(
+                f,
+                "Ring Self-intersection[{} {}]: vertex {repeat} repeats vertex {first}",
+                location.longitude, location.latitude
+            ),
+            RingIntersection::Segments {
+                first,
+                second,
+                location,
+            } => write!(
+                f,
+                "Self-intersection[{} {}]: edge {first}-{} crosses edge {second}-{}",
+                location.longitude,
+                location.latitude,
+                first + 1,
+                second + 1
+            ),
+        }
+    }
+}
+
+/// Finds the first place where a closed ring crosses or touches itself
+///
+/// Consecutive duplicate vertices are allowed (as in PostGIS). Edges are
+///  treated as planar segments in longitude and latitude.
+pub fn ring_self_intersection(vertices: &[Coordinates]) -> Option<RingIntersection> {
+    // Vertices without consecutive duplicates and the closing vertex,
+    //  with their index in the request
+    let mut ring: Vec<(usize, &Coordinates)> = vec![];
+    for (index, vertex) in vertices
+        .iter()
+        .enumerate()
+        .take(vertices.len().saturating_sub(1))
+    {
+        if ring.last().map(|(_, last)| *last) != Some(vertex) {
+            ring.push((index, vertex));
+        }
+    }
+
+    if ring.len() > 1 && ring.first().map(|(_, v)| *v) == ring.last().map(|(_, v)| *v) {
+        ring.pop();
+    }
+
+    for (i, (first, vertex)) in ring.iter().enumerate() {
+        if let Some((repeat, _)) = ring[i + 1..].iter().find(|(_, other)| *other == *vertex) {
+            return Some(RingIntersection::RepeatedVertex {
+                first: *first,
+                repeat: *repeat,
+                location: (*vertex).clone(),
+            });
+        }
+    }
+
+    let n = ring.len();
+    let edge = |i: usize| (ring[i].1, ring[(i + 1) % n].1);
+    for i in 0..n {
+        // Adjacent edges share a vertex, the last edge is adjacent to the first
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+
+            if let Some(location) = segment_intersection(edge(i), edge(j)) {
+                return Some(RingIntersection::Segments {
+                    first: ring[i].0,
+                    second: ring[j].0,
+                    location,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Gets the first intersection point of two planar segments, if any
+fn segment_intersection(
+    (a, b): (&Coordinates, &Coordinates),
+    (c, d): (&Coordinates, &Coordinates),
+) -> Option<Coordinates> {
+    let cross = |o: &Coordinates, p: &Coordinates, q: &Coordinates| {
+        (p.longitude - o.longitude) * (q.latitude - o.latitude)
+            - (p.latitude - o.latitude) * (q.longitude - o.longitude)
+    };
+
+    // Whether q, collinear with o and p, lies within their bounding box
+    let within = |o: &Coordinates, p: &Coordinates, q: &Coordinates| {
+        q.longitude >= o.longitude.min(p.longitude)
+            && q.longitude <= o.longitude.max(p.longitude)
+            && q.latitude >= o.latitude.min(p.latitude)
+            && q.latitude <= o.latitude.max(p.latitude)
+    };
+
+    let d1 = cross(c, d, a);
+    let d2 = cross(c, d, b);
+    let d3 = cross(a, b, c);
+    let d4 = cross(a, b, d);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        let t = d1 / (d1 - d2);
+        return Some(Coordinates {
+            latitude: a.latitude + t * (b.latitude - a.latitude),
+            longitude: a.longitude + t * (b.longitude - a.longitude),
+        });
+    }
+
+    // Touching or overlapping segments
+    [(d1, c, d, a), (d2, c, d, b), (d3, a, b, c), (d4, a, b, d)]
+        .into_iter()
+        .find(|(side, o, p, q)| *side == 0.0 && within(o, p, q))
+        .map(|(_, _, _, q)| q.clone())
+}
+
+/// Describes why a polygon is invalid, in the terms of PostGIS
+///  `ST_IsValidReason`, `None` if it's valid
+pub fn polygon_validity_reason(vertices: &[Coordinates]) -> Option<String> {
+    if vertices.len() < MIN_NUM_POLYGON_VERTICES {
+        return Some(format!(
+            "Too few points in geometry component: {} vertices, at least {} required",
+            vertices.len(),
+            MIN_NUM_POLYGON_VERTICES
+        ));
+    }
+
+    if let (Some(first), Some(last)) = (vertices.first(), vertices.last()) {
+        if first != last {
+            return Some(format!(
+                "Ring not closed[{} {}]: first vertex differs from last vertex [{} {}]",
+                first.longitude, first.latitude, last.longitude, last.latitude
+            ));
+        }
+    }
+
+    if let Some((index, vertex)) = vertices
+        .iter()
+        .enumerate()
+        .find(|(_, vertex)| point_from_vertex(vertex).is_err())
+    {
+        return Some(format!(
+            "Vertex {index} out of bounds[{} {}]",
+            vertex.longitude, vertex.latitude
+        ));
+    }
+
+    ring_self_intersection(vertices).map(|intersection| intersection.to_string())
+}
+
 /// Generate a PostGis 'Point' from a vertex
 /// Each vertex must be within the valid range of latitude and longitude
 pub fn point_from_vertex(vertex: &Coordinates) -> Result<Point, PointError> {
     // Each coordinate must fit within the valid range of latitude and longitude
-    if vertex.latitude < -90.0
-        || vertex.latitude > 90.0
-        || vertex.longitude < -180.0
-        || vertex.longitude > 180.0
+    if !vertex.latitude.is_finite()
+        || !vertex.longitude.is_finite()
+        || vertex.latitude < LAT_MIN
+        || vertex.latitude > LAT_MAX
+        || vertex.longitude < LON_MIN
+        || vertex.longitude > LON_MAX
     {
         postgis_warn!("(point_from_vertex) vertex out of bounds: {:?}", vertex);
         return Err(PointError::OutOfBounds);
@@ -228,6 +731,27 @@ pub struct Segment {
     pub time_end: DateTime<Utc>,
 }
 
+/// Merges zero-duration segments (e.g. from coincident points) into the
+///  segment that follows them, so that every segment has a unique start time
+///
+/// A trailing zero-duration segment is kept, its start time is still unique.
+pub fn merge_zero_duration_segments(segments: Vec<Segment>) -> Vec<Segment> {
+    let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        match merged.last_mut() {
+            Some(last) if last.time_start == segment.time_start => {
+                last.geom
+                    .points
+                    .extend(segment.geom.points.into_iter().skip(1));
+                last.time_end = segment.time_end;
+            }
+            _ => merged.push(segment),
+        }
+    }
+
+    merged
+}
+
 #[derive(Debug)]
 struct ExpectedResult {
     // The index of the segment
@@ -263,6 +787,16 @@ pub async fn segmentize(
     timestamp_end: DateTime<Utc>,
     max_segment_len_meters: f32,
 ) -> Result<Vec<Segment>, PostgisError> {
+    // Negative durations would produce segments ending before they start
+    if timestamp_end <= timestamp_start {
+        postgis_error!(
+            "(segmentize) end time {} is not after start time {}.",
+            timestamp_end,
+            timestamp_start
+        );
+        return Err(PostgisError::FlightPath(super::flight::FlightError::Time));
+    }
+
     let geom = LineStringT {
         points,
         srid: Some(DEFAULT_SRID),
@@ -271,7 +805,7 @@ pub async fn segmentize(
     let stmt = "WITH segments AS (
         SELECT
             geom,
-            ST_3DLength(geom) AS distance_m
+            ST_3DLength(ST_Transform(geom, 4978)) AS distance_m -- meters, not degrees
         FROM ST_DumpSegments(
             (
                 SELECT ST_Segmentize(
@@ -326,6 +860,14 @@ pub async fn segmentize(
         .into_iter()
         .map(|r| {
             let segment_duration_ms = (r.distance_m / velocity_m_s) * 1000.;
+            if segment_duration_ms.is_nan() || segment_duration_ms < 0. {
+                postgis_error!(
+                    "(segmentize) invalid segment duration: {}ms",
+                    segment_duration_ms
+                );
+
+                return Err(PostgisError::Psql(PsqlError::Execute));
+            }
 
             let Some(time_delta) = Duration::try_milliseconds(segment_duration_ms as i64) else {
                 postgis_error!(
@@ -352,6 +894,8 @@ pub async fn segmentize(
             PostgisError::Psql(PsqlError::Execute)
         })?;
 
+    let results = merge_zero_duration_segments(results);
+
     // postgis_debug!(
     //     "(segmentize) found {} segments. craft velocity {} m/s.",
     //     results.len(),
@@ -361,11 +905,155 @@ pub async fn segmentize(
     Ok(results)
 }
 
+/// Largest SRID accepted by PostGIS
+pub const MAX_SRID: i32 = 998_999;
+
+/// Verifies that an SRID is within the range accepted by PostGIS
+pub fn check_srid(srid: i32) -> Result<(), PsqlError> {
+    if srid <= 0 || srid > MAX_SRID {
+        postgis_error!("(check_srid) SRID out of range: {}", srid);
+        return Err(PsqlError::Srid);
+    }
+
+    Ok(())
+}
+
+/// Transforms points from one spatial reference system to another
+///
+/// Both SRIDs must exist in `spatial_ref_sys`. The input points are
+///  interpreted in `source_srid` regardless of their own SRID.
+pub async fn transform_points(
+    points: Vec<PointZ>,
+    source_srid: i32,
+    target_srid: i32,
+) -> Result<Vec<PointZ>, PostgisError> {
+    check_srid(source_srid).map_err(PostgisError::Psql)?;
+    check_srid(target_srid).map_err(PostgisError::Psql)?;
+
+    if source_srid == target_srid {
+        return Ok(points
+            .into_iter()
+            .map(|p| PointZ {
+                srid: Some(target_srid),
+                ..p
+            })
+            .collect());
+    }
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(transform_points) could not get psql pool.");
+        return Err(PostgisError::Psql(PsqlError::Client));
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(transform_points) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::Psql(PsqlError::Client)
+    })?;
+
+    let srid_stmt = client
+        .prepare_cached(
+            r#"SELECT COUNT(*) AS "count" FROM "spatial_ref_sys" WHERE "srid" IN ($1, $2);"#,
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(transform_points) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let count: i64 = client
+        .query_one(&srid_stmt, &[&source_srid, &target_srid])
+        .await
+        .and_then(|row| row.try_get("count"))
+        .map_err(|e| {
+            postgis_error!("(transform_points) could not execute query: {}", e);
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    if count != 2 {
+        postgis_error!(
+            "(transform_points) unknown SRID: {} or {}",
+            source_srid,
+            target_srid
+        );
+        return Err(PostgisError::Psql(PsqlError::Srid));
+    }
+
+    let stmt = client
+        .prepare_cached(r#"SELECT ST_Transform(ST_SetSRID($1::GEOMETRY, $2), $3) AS "geom";"#)
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(transform_points) could not prepare cached statement: {}",
+                e
+            );
+            PostgisError::Psql(PsqlError::Execute)
+        })?;
+
+    let mut result: Vec<PointZ> = vec![];
+    for point in points {
+        let point: PointZ = client
+            .query_one(&stmt, &[&point, &source_srid, &target_srid])
+            .await
+            .and_then(|row| row.try_get("geom"))
+            .map_err(|e| {
+                postgis_error!("(transform_points) could not transform point: {}", e);
+                PostgisError::Psql(PsqlError::Execute)
+            })?;
+
+        result.push(point);
+    }
+
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
 
+    #[test]
+    fn ut_coordinate_order_round_trip() {
+        let point = PointZ::try_from(COORDINATE_CHECK_POINT).unwrap();
+        assert_eq!(point.x, COORDINATE_CHECK_POINT.longitude);
+        assert_eq!(point.y, COORDINATE_CHECK_POINT.latitude);
+
+        let round_trip = GrpcPointZ::from(point);
+        assert_eq!(
+            coordinate_order_issue(&COORDINATE_CHECK_POINT, &round_trip),
+            None
+        );
+    }
+
+    #[test]
+    fn ut_coordinate_order_issue() {
+        let swapped = GrpcPointZ {
+            latitude: COORDINATE_CHECK_POINT.longitude,
+            longitude: COORDINATE_CHECK_POINT.latitude,
+            ..COORDINATE_CHECK_POINT
+        };
+        let issue = coordinate_order_issue(&COORDINATE_CHECK_POINT, &swapped).unwrap();
+        assert!(issue.ends_with("latitude and longitude are swapped"));
+
+        let moved = GrpcPointZ {
+            latitude: COORDINATE_CHECK_POINT.latitude + 0.1,
+            ..COORDINATE_CHECK_POINT
+        };
+        let issue = coordinate_order_issue(&COORDINATE_CHECK_POINT, &moved).unwrap();
+        assert!(!issue.contains("swapped"));
+
+        let lowered = GrpcPointZ {
+            altitude_meters: 0.0,
+            ..COORDINATE_CHECK_POINT
+        };
+        assert!(coordinate_order_issue(&COORDINATE_CHECK_POINT, &lowered).is_some());
+    }
+
     #[test]
     fn ut_point_from_vertex() {
         let mut rng = thread_rng();
@@ -411,6 +1099,94 @@ mod tests {
         };
         let point = point_from_vertex(&vertex).unwrap_err();
         assert_eq!(point, PointError::OutOfBounds);
+
+        for (latitude, longitude) in [(f64::NAN, 0.0), (0.0, f64::INFINITY)] {
+            let vertex = Coordinates {
+                latitude,
+                longitude,
+            };
+            let point = point_from_vertex(&vertex).unwrap_err();
+            assert_eq!(point, PointError::OutOfBounds);
+        }
+    }
+
+    #[test]
+    fn ut_coordinate_bounds() {
+        let outside = 1e-9;
+        let within = [
+            (LAT_MIN, 0.0),
+            (LAT_MAX, 0.0),
+            (0.0, LON_MIN),
+            (0.0, LON_MAX),
+        ];
+        let beyond = [
+            (LAT_MIN - outside, 0.0),
+            (LAT_MAX + outside, 0.0),
+            (0.0, LON_MIN - outside),
+            (0.0, LON_MAX + outside),
+        ];
+
+        for (latitude, longitude) in within {
+            let point = PointZ::new(longitude, latitude, 100.0, Some(DEFAULT_SRID));
+            assert!(validate_pointz(&point).is_ok());
+
+            let vertex = Coordinates {
+                latitude,
+                longitude,
+            };
+            assert!(point_from_vertex(&vertex).is_ok());
+        }
+
+        for (latitude, longitude) in beyond {
+            let point = PointZ::new(longitude, latitude, 100.0, Some(DEFAULT_SRID));
+            assert_eq!(
+                validate_pointz(&point).unwrap_err(),
+                PolygonError::OutOfBounds
+            );
+
+            let vertex = Coordinates {
+                latitude,
+                longitude,
+            };
+            assert_eq!(
+                point_from_vertex(&vertex).unwrap_err(),
+                PointError::OutOfBounds
+            );
+        }
+    }
+
+    #[test]
+    fn ut_validate_pointz_non_finite() {
+        assert!(validate_pointz(&PointZ::new(4.9, 52.3, 100.0, Some(DEFAULT_SRID))).is_ok());
+
+        for (x, y, z) in [
+            (f64::NAN, 52.3, 100.0),
+            (4.9, f64::NAN, 100.0),
+            (4.9, 52.3, f64::NAN),
+            (f64::INFINITY, 52.3, 100.0),
+            (4.9, f64::NEG_INFINITY, 100.0),
+            (4.9, 52.3, f64::INFINITY),
+        ] {
+            let point = PointZ::new(x, y, z, Some(DEFAULT_SRID));
+            assert_eq!(
+                validate_pointz(&point).unwrap_err(),
+                PolygonError::OutOfBounds
+            );
+
+            let position = Position {
+                longitude: x,
+                latitude: y,
+                altitude_meters: z,
+            };
+            assert!(PointZ::try_from(position).is_err());
+
+            let grpc = GrpcPointZ {
+                longitude: x,
+                latitude: y,
+                altitude_meters: z as f32,
+            };
+            assert!(PointZ::try_from(grpc).is_err());
+        }
     }
 
     #[test]
@@ -487,6 +1263,91 @@ mod tests {
         assert_eq!(polygon, PolygonError::OutOfBounds);
     }
 
+    /// A bow-tie near Amsterdam, its edges 0-1 and 2-3 cross at
+    ///  (52.375, 4.915)
+    fn bow_tie() -> Vec<Coordinates> {
+        [
+            (52.374, 4.914),
+            (52.376, 4.916),
+            (52.376, 4.914),
+            (52.374, 4.916),
+            (52.374, 4.914),
+        ]
+        .iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude: *latitude,
+            longitude: *longitude,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn ut_ring_self_intersection() {
+        let vertices = bow_tie();
+        let Some(RingIntersection::Segments {
+            first,
+            second,
+            location,
+        }) = ring_self_intersection(&vertices)
+        else {
+            panic!("expected crossing edges");
+        };
+
+        assert_eq!((first, second), (0, 2));
+        assert!((location.latitude - 52.375).abs() < 1e-9);
+        assert!((location.longitude - 4.915).abs() < 1e-9);
+
+        let reason = polygon_validity_reason(&vertices).unwrap();
+        assert!(reason.starts_with("Self-intersection["));
+
+        let error = polygon_from_vertices_z(&vertices, 100.).unwrap_err();
+        assert_eq!(error, PolygonError::SelfIntersection);
+    }
+
+    #[test]
+    fn ut_ring_self_intersection_vertices() {
+        let vertex = |latitude: f64, longitude: f64| Coordinates {
+            latitude,
+            longitude,
+        };
+
+        // Consecutive duplicates are allowed
+        let vertices = vec![
+            vertex(52.374, 4.914),
+            vertex(52.376, 4.914),
+            vertex(52.376, 4.914),
+            vertex(52.376, 4.916),
+            vertex(52.374, 4.914),
+        ];
+        assert_eq!(ring_self_intersection(&vertices), None);
+        assert_eq!(polygon_validity_reason(&vertices), None);
+
+        // Two triangles touching at one vertex
+        let vertices = vec![
+            vertex(52.375, 4.915),
+            vertex(52.376, 4.914),
+            vertex(52.376, 4.916),
+            vertex(52.375, 4.915),
+            vertex(52.374, 4.916),
+            vertex(52.374, 4.914),
+            vertex(52.375, 4.915),
+        ];
+        assert_eq!(
+            ring_self_intersection(&vertices),
+            Some(RingIntersection::RepeatedVertex {
+                first: 0,
+                repeat: 3,
+                location: vertex(52.375, 4.915),
+            })
+        );
+
+        // Open rings are reported before intersections
+        let mut vertices = bow_tie();
+        vertices.pop();
+        let reason = polygon_validity_reason(&vertices).unwrap();
+        assert!(reason.starts_with("Ring not closed"));
+    }
+
     #[test]
     fn ut_check_string() {
         // Valid
@@ -499,14 +1360,27 @@ mod tests {
         let string = "tes";
         assert_eq!(
             check_string(string, regex).unwrap_err(),
-            StringError::Mismatch,
+            StringError::TooShort { length: 3, min: 4 },
         );
 
         // Invalid Length
         let string = "T".repeat(max_length + 1);
         assert_eq!(
             check_string(&string, regex).unwrap_err(),
-            StringError::Mismatch,
+            StringError::TooLong {
+                length: max_length + 1,
+                max: max_length
+            },
+        );
+
+        // Invalid Character
+        let string = "test!";
+        assert_eq!(
+            check_string(string, regex).unwrap_err(),
+            StringError::Character {
+                character: '!',
+                index: 4
+            },
         );
 
         // Breaks Regex
@@ -525,4 +1399,259 @@ mod tests {
             StringError::ContainsForbidden,
         );
     }
+
+    #[test]
+    fn ut_round_meters() {
+        assert_eq!(round_meters(1234.5678, 0.1), 1234.6);
+        assert_eq!(round_meters(1234.5678, 1.0), 1235.0);
+        assert_eq!(round_meters(1234.5678, 0.0), 1234.5678);
+        assert_eq!(response_meters(1234.5678), 1234.6);
+    }
+
+    #[test]
+    fn ut_summed_distance_precision() {
+        // ~280 km along the equator in 2000 segments of ~140 m
+        let points = (0..=2000)
+            .map(|i| PointZ::new(i as f64 * 0.00125, 0.0, 0.0, Some(DEFAULT_SRID)))
+            .collect::<Vec<_>>();
+        let total = haversine_distance_meters(&points[0], &points[2000]);
+
+        // Summing in single precision is off by meters
+        let single = points
+            .windows(2)
+            .map(|pair| distance_meters(&pair[0], &pair[1]))
+            .sum::<f32>();
+        assert!((single as f64 - total).abs() > 1.0);
+
+        // Summing in double precision keeps the returned resolution
+        let double = points
+            .windows(2)
+            .map(|pair| haversine_distance_meters(&pair[0], &pair[1]))
+            .sum::<f64>();
+        assert!((double - total).abs() < 1e-3);
+        assert!(
+            (response_meters(double) as f64 - total).abs() <= DEFAULT_DISTANCE_RESOLUTION_METERS
+        );
+    }
+
+    #[test]
+    fn ut_string_rules() {
+        assert_eq!(
+            string_rules(LABEL_REGEX).unwrap(),
+            (r"^[\-0-9A-Za-z_\.]$".to_string(), 1, 255)
+        );
+        assert!(string_rules(r"^[0-9A-Za-z_]+$").is_none());
+        assert!(string_rules(r"[0-9A-Za-z_]{3,20}").is_none());
+    }
+
+    #[test]
+    fn ut_check_string_error_display() {
+        assert_eq!(
+            StringError::TooShort { length: 0, min: 1 }.to_string(),
+            "String is empty."
+        );
+        assert_eq!(
+            StringError::TooLong {
+                length: 21,
+                max: 20
+            }
+            .to_string(),
+            "String is 21 characters long, at most 20 allowed."
+        );
+        assert_eq!(
+            StringError::Character {
+                character: ';',
+                index: 3
+            }
+            .to_string(),
+            "Character ';' at index 3 is not allowed."
+        );
+    }
+
+    #[test]
+    fn ut_merge_zero_duration_segments() {
+        let time_start = Utc::now();
+        let time_mid = time_start + Duration::try_seconds(10).unwrap();
+        let time_end = time_mid + Duration::try_seconds(10).unwrap();
+        let a = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+        let b = PointZ::new(4.9160036, 52.3745905, 120.0, Some(DEFAULT_SRID));
+        let c = PointZ::new(4.9161036, 52.3746905, 120.0, Some(DEFAULT_SRID));
+        let segment = |points: Vec<PointZ>, time_start, time_end| Segment {
+            geom: LineStringT {
+                points,
+                srid: Some(DEFAULT_SRID),
+            },
+            time_start,
+            time_end,
+        };
+
+        // Coincident points produce segments with the same start time
+        let segments = vec![
+            segment(vec![a, a], time_start, time_start),
+            segment(vec![a, b], time_start, time_mid),
+            segment(vec![b, b], time_mid, time_mid),
+            segment(vec![b, b], time_mid, time_mid),
+            segment(vec![b, c], time_mid, time_end),
+            segment(vec![c, c], time_end, time_end),
+        ];
+
+        let merged = merge_zero_duration_segments(segments);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].geom.points, vec![a, a, b]);
+        assert_eq!(
+            (merged[0].time_start, merged[0].time_end),
+            (time_start, time_mid)
+        );
+        assert_eq!(merged[1].geom.points, vec![b, b, b, c]);
+        assert_eq!(
+            (merged[1].time_start, merged[1].time_end),
+            (time_mid, time_end)
+        );
+        assert_eq!(
+            (merged[2].time_start, merged[2].time_end),
+            (time_end, time_end)
+        );
+
+        // Start times are unique
+        for pair in merged.windows(2) {
+            assert!(pair[0].time_start < pair[1].time_start);
+        }
+    }
+
+    #[test]
+    fn ut_distance_known_pair() {
+        // 10 km along the equator: a * Δλ on the WGS84 ellipsoid
+        let a = PointZ::new(0.0, 0.0, 0.0, Some(DEFAULT_SRID));
+        let b = PointZ::new(0.0898315284, 0.0, 0.0, Some(DEFAULT_SRID));
+
+        let geodesic = geodesic_distance_meters(&a, &b);
+        assert!((geodesic - 10_000.0).abs() < 10_000.0 * 0.0001);
+
+        let haversine = distance_meters(&a, &b) as f64;
+        assert!((haversine - 10_000.0).abs() < 10_000.0 * 0.005);
+
+        // Altitude difference is added in 3D
+        let c = PointZ::new(0.0898315284, 0.0, 100.0, Some(DEFAULT_SRID));
+        let geodesic_3d = geodesic_distance_meters(&a, &c);
+        assert!((geodesic_3d - (geodesic.powi(2) + 100.0_f64.powi(2)).sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ut_check_srid() {
+        assert!(check_srid(DEFAULT_SRID).is_ok());
+        assert!(check_srid(27700).is_ok());
+        assert_eq!(check_srid(0).unwrap_err(), PsqlError::Srid);
+        assert_eq!(check_srid(-4326).unwrap_err(), PsqlError::Srid);
+        assert_eq!(check_srid(MAX_SRID + 1).unwrap_err(), PsqlError::Srid);
+    }
+
+    #[tokio::test]
+    async fn ut_segmentize_invalid_time() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_segmentize_invalid_time) start");
+
+        let points = vec![
+            PointZ::new(4.91, 52.37, 100.0, Some(DEFAULT_SRID)),
+            PointZ::new(4.92, 52.38, 100.0, Some(DEFAULT_SRID)),
+        ];
+
+        let time_start = Utc::now();
+        let time_end = time_start - Duration::try_hours(1).unwrap();
+        let result = segmentize(points.clone(), time_start, time_end, 100.0)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::FlightPath(crate::postgis::flight::FlightError::Time)
+        );
+
+        let result = segmentize(points, time_start, time_start, 100.0)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            result,
+            PostgisError::FlightPath(crate::postgis::flight::FlightError::Time)
+        );
+
+        ut_info!("(ut_segmentize_invalid_time) success");
+    }
+
+    #[tokio::test]
+    async fn ut_transform_points() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_transform_points) start");
+
+        // British National Grid
+        let point = PointZ::new(530_000.0, 180_000.0, 10.0, Some(27700));
+
+        // Same SRID doesn't need the database
+        let result = transform_points(vec![point], 27700, 27700).await.unwrap();
+        assert_eq!(result, vec![point]);
+
+        let result = transform_points(vec![point], 0, DEFAULT_SRID)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Srid));
+
+        let result = transform_points(vec![point], 27700, DEFAULT_SRID)
+            .await
+            .unwrap_err();
+        assert_eq!(result, PostgisError::Psql(PsqlError::Client));
+
+        ut_info!("(ut_transform_points) success");
+    }
+
+    #[test]
+    fn ut_quantization() {
+        let quantization = Quantization::default();
+
+        // Telemetry noise in the 10th decimal and below 0.05 m is dropped
+        let a = quantization.apply(PointZ::new(
+            4.916003600012,
+            52.374590499987,
+            100.02,
+            Some(DEFAULT_SRID),
+        ));
+        let b = quantization.apply(PointZ::new(
+            4.916003599991,
+            52.374590500004,
+            99.98,
+            Some(DEFAULT_SRID),
+        ));
+        assert_eq!(a, b);
+        assert!((a.x - 4.9160036).abs() < 1e-12);
+        assert!((a.z - 100.0).abs() < 1e-9);
+
+        // Differences above the quantum are kept
+        let c = quantization.apply(PointZ::new(
+            4.9160037,
+            52.3745905,
+            100.0,
+            Some(DEFAULT_SRID),
+        ));
+        assert_ne!(a, c);
+
+        // Conversion helpers quantize the same way
+        let position = Position {
+            longitude: 4.916003600012,
+            latitude: 52.374590499987,
+            altitude_meters: 100.02,
+        };
+        let grpc = GrpcPointZ {
+            longitude: 4.916003599991,
+            latitude: 52.374590500004,
+            altitude_meters: 99.98,
+        };
+        assert_eq!(PointZ::try_from(position).unwrap(), a);
+        assert_eq!(PointZ::try_from(grpc).unwrap(), a);
+        assert_eq!(raw_pointz(&position).x, 4.916003600012);
+
+        // A zero step keeps the full precision
+        let full = Quantization::new(0.0, 0.0).unwrap();
+        let point = PointZ::new(4.916003600012, 52.3745905, 100.02, Some(DEFAULT_SRID));
+        assert_eq!(full.apply(point), point);
+
+        assert!(Quantization::new(-1e-7, 0.1).is_err());
+        assert!(Quantization::new(1e-7, f64::NAN).is_err());
+    }
 }
@@ -53,7 +53,7 @@ impl std::fmt::Display for VertiportError {
 }
 
 /// Gets the name of this module's table
-fn get_table_name() -> &'static str {
+pub(super) fn get_table_name() -> &'static str {
     static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."vertiports""#,);
     FULL_NAME
 }
@@ -122,8 +122,13 @@ impl TryFrom<RequestVertiport> for Vertiport {
 
 /// Initialize the vertiports table in the PostGIS database
 pub async fn psql_init() -> Result<(), PostgisError> {
+    super::psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for vertiports
+pub(super) fn psql_init_statements() -> Vec<String> {
     // Create Vertiport Table
-    let statements = vec![format!(
+    vec![format!(
         r#"CREATE TABLE IF NOT EXISTS {vertiports_table_name} (
             "identifier" VARCHAR(255) UNIQUE PRIMARY KEY NOT NULL,
             "label" VARCHAR(255) NOT NULL,
@@ -137,13 +142,16 @@ pub async fn psql_init() -> Result<(), PostgisError> {
         );"#,
         vertiports_table_name = get_table_name(),
         zones_table_name = super::zone::get_table_name(),
-    )];
-
-    super::psql_transaction(statements).await
+    )]
 }
 
 /// Update vertiports in the PostGIS database
-pub async fn update_vertiports(vertiports: Vec<RequestVertiport>) -> Result<(), VertiportError> {
+///
+/// A dry run validates and writes the vertiports, then rolls back.
+pub async fn update_vertiports(
+    vertiports: Vec<RequestVertiport>,
+    dry_run: bool,
+) -> Result<(), VertiportError> {
     postgis_debug!("(update_vertiports) entry.");
     if vertiports.is_empty() {
         return Err(VertiportError::NoVertiports);
@@ -168,6 +176,8 @@ pub async fn update_vertiports(vertiports: Vec<RequestVertiport>) -> Result<(),
         VertiportError::Client
     })?;
 
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!("(update_vertiports) could not create transaction: {}", e);
         VertiportError::DBError
@@ -199,7 +209,8 @@ pub async fn update_vertiports(vertiports: Vec<RequestVertiport>) -> Result<(),
                 ON CONFLICT ("identifier") DO UPDATE
                 SET
                     "geom" = EXCLUDED."geom",
-                    "zone_type" = EXCLUDED."zone_type"
+                    "zone_type" = EXCLUDED."zone_type",
+                    "deleted_at" = NULL -- the vertiport re-declares its zone
                 RETURNING "id"
             ) INSERT INTO {vertiports_table_name} (
                 "identifier",
@@ -256,9 +267,9 @@ pub async fn update_vertiports(vertiports: Vec<RequestVertiport>) -> Result<(),
             })?;
     }
 
-    match transaction.commit().await {
+    match super::commit_or_rollback(transaction, dry_run).await {
         Ok(_) => {
-            postgis_debug!("(update_vertiports) success.");
+            postgis_debug!("(update_vertiports) success, dry run: {dry_run}.");
             Ok(())
         }
         Err(e) => {
@@ -392,7 +403,7 @@ mod tests {
             })
             .collect();
 
-        let result = update_vertiports(vertiports).await.unwrap_err();
+        let result = update_vertiports(vertiports, false).await.unwrap_err();
         assert_eq!(result, VertiportError::Client);
     }
 
@@ -419,7 +430,7 @@ mod tests {
                 timestamp_network: Some(Utc::now().into()),
             }];
 
-            let result = update_vertiports(vertiports).await.unwrap_err();
+            let result = update_vertiports(vertiports, false).await.unwrap_err();
             assert_eq!(result, VertiportError::Identifier);
         }
     }
@@ -427,7 +438,7 @@ mod tests {
     #[tokio::test]
     async fn ut_vertiports_request_to_gis_invalid_no_nodes() {
         let vertiports: Vec<RequestVertiport> = vec![];
-        let result = update_vertiports(vertiports).await.unwrap_err();
+        let result = update_vertiports(vertiports, false).await.unwrap_err();
         assert_eq!(result, VertiportError::NoVertiports);
     }
 
@@ -453,7 +464,7 @@ mod tests {
                 ..Default::default()
             }];
 
-            let result = update_vertiports(vertiports).await.unwrap_err();
+            let result = update_vertiports(vertiports, false).await.unwrap_err();
             assert_eq!(result, VertiportError::Location);
         }
 
@@ -484,7 +495,7 @@ mod tests {
                 ..Default::default()
             }];
 
-            let result = update_vertiports(vertiports).await.unwrap_err();
+            let result = update_vertiports(vertiports, false).await.unwrap_err();
             assert_eq!(result, VertiportError::Location);
         }
     }
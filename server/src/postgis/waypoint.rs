@@ -40,7 +40,7 @@ impl std::fmt::Display for WaypointError {
 }
 
 /// Gets the name of this module's table
-fn get_table_name() -> &'static str {
+pub(super) fn get_table_name() -> &'static str {
     static FULL_NAME: &str = const_format::formatcp!(r#""{PSQL_SCHEMA}"."waypoints""#,);
     FULL_NAME
 }
@@ -93,8 +93,13 @@ impl TryFrom<RequestWaypoint> for Waypoint {
 
 /// Initialize the vertiports table in the PostGIS database
 pub async fn psql_init() -> Result<(), PostgisError> {
+    super::psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table declarations for waypoints
+pub(super) fn psql_init_statements() -> Vec<String> {
     // Create Aircraft Table
-    let statements = vec![
+    vec![
         format!(
             r#"CREATE TABLE IF NOT EXISTS {table_name} (
             "identifier" VARCHAR(255) UNIQUE NOT NULL,
@@ -106,9 +111,7 @@ pub async fn psql_init() -> Result<(), PostgisError> {
             r#"CREATE INDEX IF NOT EXISTS "waypoints_geog_idx" ON {table_name} USING GIST ("geog");"#,
             table_name = get_table_name()
         ),
-    ];
-
-    super::psql_transaction(statements).await
+    ]
 }
 
 /// Update waypoints in the PostGIS database
@@ -137,6 +140,8 @@ pub async fn update_waypoints(waypoints: Vec<RequestWaypoint>) -> Result<(), Way
         WaypointError::Client
     })?;
 
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
     let transaction = client.transaction().await.map_err(|e| {
         postgis_error!("(update_waypoints) could not create transaction: {}", e);
         WaypointError::DBError
@@ -0,0 +1,622 @@
+//! This module contains functions for evaluating a synthetic aircraft
+//!  state against the stored airspace.
+//!
+//! Dispatchers can ask which zones, flights and live traffic would concern
+//!  an aircraft at a given position and velocity without registering it.
+//!  The synthetic aircraft only exists as query parameters: the same zone
+//!  and flight intersection statements used for planning are run in a
+//!  read-only transaction, so nothing can persist.
+
+use super::flight::{FlightConflict, MAX_CONFLICT_DISTANCE_METERS};
+use super::shard::ShardRows;
+use super::tags::TagFilter;
+use super::utils::Segment;
+use super::{PostgisError, DEFAULT_SRID};
+use crate::grpc::server::grpc_server::{
+    FlightConflict as GrpcFlightConflict, NearbyAircraft, PointZ as GrpcPointZ, WhatIfRequest,
+    WhatIfResponse, WhatIfZoneHit, ZoneType,
+};
+use chrono::{DateTime, Duration, Utc};
+use geo::algorithm::haversine_destination::HaversineDestination;
+use geo::point;
+use postgis::ewkb::{LineStringT, PointZ};
+use tokio_postgres::types::ToSql;
+
+/// Time extrapolated from the state if the request doesn't set one
+pub const DEFAULT_WHAT_IF_LOOKAHEAD_SECS: u32 = 60;
+
+/// Max time evaluated ahead of the state
+pub const MAX_WHAT_IF_LOOKAHEAD_SECS: u32 = 600;
+
+/// Aircraft that haven't reported a position for longer are not traffic
+pub const TRAFFIC_MAX_POSITION_AGE_SECS: i64 = 60;
+
+/// Max number of live aircraft returned as traffic
+pub const MAX_TRAFFIC_AIRCRAFT: i64 = 50;
+
+/// Length of the path segments checked against zones and flights
+const WHAT_IF_SEGMENT_LENGTH_METERS: f32 = super::flight::MAX_FLIGHT_SEGMENT_LENGTH_METERS;
+
+/// Possible errors with what-if requests
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WhatIfError {
+    /// Invalid position or path
+    Location,
+
+    /// Invalid velocity
+    Velocity,
+
+    /// Invalid time or lookahead
+    Time,
+
+    /// Invalid separation distance
+    Distance,
+
+    /// Invalid tag filter
+    Label,
+
+    /// Could not segmentize the path
+    Segments,
+
+    /// Could not get client
+    Client,
+
+    /// DBError error
+    DBError,
+}
+
+impl std::fmt::Display for WhatIfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WhatIfError::Location => write!(f, "Invalid position or path provided."),
+            WhatIfError::Velocity => write!(f, "Invalid velocity provided."),
+            WhatIfError::Time => write!(f, "Invalid time or lookahead provided."),
+            WhatIfError::Distance => write!(f, "Invalid separation distance provided."),
+            WhatIfError::Label => write!(f, "Invalid tag filter provided."),
+            WhatIfError::Segments => write!(f, "Could not segmentize path."),
+            WhatIfError::Client => write!(f, "Could not get backend client."),
+            WhatIfError::DBError => write!(f, "Unknown backend error."),
+        }
+    }
+}
+
+/// A validated what-if request
+#[derive(Debug, Clone)]
+struct WhatIfQuery {
+    position: PointZ,
+    points: Vec<PointZ>,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    distance_meters: f64,
+    tag_filter: TagFilter,
+}
+
+/// Gets the position reached after `secs` at a constant velocity
+///
+/// The track angle is in degrees clockwise from true north, the vertical
+///  speed is positive up.
+pub fn extrapolate_position(
+    position: &PointZ,
+    ground_speed_mps: f64,
+    track_angle_degrees: f64,
+    vertical_speed_mps: f64,
+    secs: f64,
+) -> PointZ {
+    let destination = point!(x: position.x, y: position.y)
+        .haversine_destination(track_angle_degrees, ground_speed_mps * secs);
+
+    PointZ::new(
+        destination.x(),
+        destination.y(),
+        position.z + vertical_speed_mps * secs,
+        Some(DEFAULT_SRID),
+    )
+}
+
+/// Converts a requested point, rejecting non-finite and out of range values
+fn validated_point(point: GrpcPointZ) -> Result<PointZ, WhatIfError> {
+    let point = PointZ::try_from(point).map_err(|_| {
+        postgis_error!("(validated_point) point has non-finite coordinates.");
+        WhatIfError::Location
+    })?;
+
+    super::utils::validate_pointz(&point).map_err(|e| {
+        postgis_error!("(validated_point) invalid point {:?}: {}", point, e);
+        WhatIfError::Location
+    })?;
+
+    Ok(point)
+}
+
+/// Validates a what-if request
+///
+/// The evaluated path starts at the state's position. It continues along
+///  the candidate path if one is provided, otherwise the state is
+///  extrapolated at a constant velocity for the lookahead.
+fn validate_what_if_request(
+    request: &WhatIfRequest,
+    now: DateTime<Utc>,
+) -> Result<WhatIfQuery, WhatIfError> {
+    let Some(position) = request.position.clone() else {
+        postgis_error!("(validate_what_if_request) position is required.");
+        return Err(WhatIfError::Location);
+    };
+
+    let position = validated_point(position)?;
+
+    let ground_speed_mps = request.ground_speed_mps as f64;
+    let track_angle_degrees = request.track_angle_degrees as f64;
+    let vertical_speed_mps = request.vertical_speed_mps as f64;
+    if !ground_speed_mps.is_finite()
+        || ground_speed_mps < 0.0
+        || !track_angle_degrees.is_finite()
+        || !vertical_speed_mps.is_finite()
+    {
+        postgis_error!(
+            "(validate_what_if_request) invalid velocity: {} m/s at {} degrees, {} m/s vertical",
+            ground_speed_mps,
+            track_angle_degrees,
+            vertical_speed_mps
+        );
+        return Err(WhatIfError::Velocity);
+    }
+
+    let lookahead_secs = match request.lookahead_secs {
+        0 => DEFAULT_WHAT_IF_LOOKAHEAD_SECS,
+        secs if secs > MAX_WHAT_IF_LOOKAHEAD_SECS => {
+            postgis_error!(
+                "(validate_what_if_request) lookahead of {} seconds exceeds the max of {}.",
+                secs,
+                MAX_WHAT_IF_LOOKAHEAD_SECS
+            );
+            return Err(WhatIfError::Time);
+        }
+        secs => secs,
+    };
+
+    let time_start: DateTime<Utc> = request.timestamp.clone().map(Into::into).unwrap_or(now);
+
+    let Some(time_end) = Duration::try_seconds(lookahead_secs as i64)
+        .and_then(|lookahead| time_start.checked_add_signed(lookahead))
+    else {
+        postgis_error!("(validate_what_if_request) could not get the end of the lookahead.");
+        return Err(WhatIfError::Time);
+    };
+
+    let distance_meters = request.distance_meters;
+    if !distance_meters.is_finite()
+        || distance_meters <= 0.0
+        || distance_meters > MAX_CONFLICT_DISTANCE_METERS
+    {
+        postgis_error!(
+            "(validate_what_if_request) invalid distance: {}",
+            distance_meters
+        );
+        return Err(WhatIfError::Distance);
+    }
+
+    let tag_filter = TagFilter::try_from(request.tag_filter.clone()).map_err(|e| {
+        postgis_error!("(validate_what_if_request) invalid tag filter: {}", e);
+        WhatIfError::Label
+    })?;
+
+    let mut points = vec![position];
+    if request.path.is_empty() {
+        points.push(extrapolate_position(
+            &position,
+            ground_speed_mps,
+            track_angle_degrees,
+            vertical_speed_mps,
+            lookahead_secs as f64,
+        ));
+    } else {
+        for point in &request.path {
+            points.push(validated_point(point.clone())?);
+        }
+    }
+
+    Ok(WhatIfQuery {
+        position,
+        points,
+        time_start,
+        time_end,
+        distance_meters: distance_meters as f64,
+        tag_filter,
+    })
+}
+
+/// Reads a zone row of the zone intersection statement
+fn zone_hit(row: &tokio_postgres::Row) -> Result<WhatIfZoneHit, tokio_postgres::error::Error> {
+    let zone_type: ZoneType = row.try_get("zone_type")?;
+    Ok(WhatIfZoneHit {
+        identifier: row.try_get("identifier")?,
+        zone_type: zone_type as i32,
+    })
+}
+
+/// Splits the evaluated path into the segments checked against zones and
+///  flights
+///
+/// A hovering aircraft has no path to segmentize, its single segment is
+///  its position for the whole lookahead.
+async fn what_if_segments(query: &WhatIfQuery) -> Result<Vec<Segment>, WhatIfError> {
+    if query.points.windows(2).all(|pair| pair[0] == pair[1]) {
+        return Ok(vec![Segment {
+            geom: LineStringT {
+                points: vec![query.position, query.position],
+                srid: Some(DEFAULT_SRID),
+            },
+            time_start: query.time_start,
+            time_end: query.time_end,
+        }]);
+    }
+
+    super::utils::segmentize(
+        query.points.clone(),
+        query.time_start,
+        query.time_end,
+        WHAT_IF_SEGMENT_LENGTH_METERS,
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(what_if_segments) could not segmentize path: {}", e);
+        WhatIfError::Segments
+    })
+}
+
+/// Evaluates a synthetic aircraft state against stored zones, flights and
+///  live traffic
+///
+/// Zones are reported once, in the order the path enters them; the zone
+///  intersection statement reports one zone per path segment. Flights are
+///  reported once each, at their closest approach. Traffic is every live
+///  aircraft within `distance_meters` of the state's position, nearest
+///  first.
+pub async fn what_if(request: WhatIfRequest) -> Result<WhatIfResponse, PostgisError> {
+    postgis_debug!("(what_if) entry.");
+    let query =
+        validate_what_if_request(&request, crate::clock::now()).map_err(PostgisError::WhatIf)?;
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(what_if) could not get psql pool.");
+        return Err(PostgisError::WhatIf(WhatIfError::Client));
+    };
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(what_if) could not get client from psql connection pool: {}",
+            e
+        );
+        PostgisError::WhatIf(WhatIfError::Client)
+    })?;
+
+    let segments = what_if_segments(&query)
+        .await
+        .map_err(PostgisError::WhatIf)?;
+
+    let zone_stmt = super::zone::get_zone_intersection_stmt(&client, &query.tag_filter).await?;
+    let flights_stmt =
+        super::flight::get_flight_intersection_stmt(&client, &query.tag_filter).await?;
+    let now = crate::clock::now();
+
+    // Live aircraft on other shards within the distance
+    let updated_since =
+        now - Duration::try_seconds(TRAFFIC_MAX_POSITION_AGE_SECS).unwrap_or_default();
+    let shard_rows = ShardRows::fetch(
+        &client,
+        super::aircraft::position_source(),
+        &format!(
+            r#""geom" IS NOT NULL
+            AND "last_position_update" >= $3
+            AND ST_3DDWithin(
+                ST_Transform("geom", 4978),
+                ST_Transform($1::GEOMETRY(POINTZ, {DEFAULT_SRID}), 4978),
+                $2 -- meters
+            )"#
+        ),
+        &[&query.position, &query.distance_meters, &updated_since],
+    )
+    .await
+    .map_err(|e| {
+        postgis_error!("(what_if) could not get shard rows: {}", e);
+        PostgisError::WhatIf(WhatIfError::DBError)
+    })?;
+
+    let traffic_stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT
+                "identifier",
+                "geom",
+                ST_3DDistance(
+                    ST_Transform("geom", 4978),
+                    ST_Transform($1::GEOMETRY(POINTZ, {DEFAULT_SRID}), 4978)
+                ) AS "distance_meters"
+            FROM {table_name} AS "aircraft"
+            WHERE
+                "geom" IS NOT NULL
+                AND "last_position_update" >= $3
+                AND ST_3DDWithin(
+                    ST_Transform("geom", 4978),
+                    ST_Transform($1::GEOMETRY(POINTZ, {DEFAULT_SRID}), 4978),
+                    $2 -- meters
+                )
+            ORDER BY "distance_meters" ASC
+            LIMIT {MAX_TRAFFIC_AIRCRAFT};"#,
+            table_name = shard_rows.source(4),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(what_if) could not prepare cached statement: {}", e);
+            PostgisError::WhatIf(WhatIfError::DBError)
+        })?;
+
+    // Nothing is written, the transaction guards against it regardless
+    let transaction = client
+        .build_transaction()
+        .read_only(true)
+        .start()
+        .await
+        .map_err(|e| {
+            postgis_error!("(what_if) could not create transaction: {}", e);
+            PostgisError::WhatIf(WhatIfError::DBError)
+        })?;
+
+    let mut zones: Vec<WhatIfZoneHit> = vec![];
+    let mut conflicts: Vec<FlightConflict> = vec![];
+    for segment in &segments {
+        let rows = transaction
+            .query(
+                &zone_stmt,
+                &[
+                    &segment.geom,
+                    &segment.time_start,
+                    &segment.time_end,
+                    &"",
+                    &"",
+                    &query.tag_filter.tags,
+                ],
+            )
+            .await
+            .map_err(|e| {
+                postgis_error!("(what_if) could not execute zone query: {}", e);
+                PostgisError::WhatIf(WhatIfError::DBError)
+            })?;
+
+        for row in rows {
+            let hit = zone_hit(&row).map_err(|e| {
+                postgis_error!("(what_if) could not get zone data: {}", e);
+                PostgisError::WhatIf(WhatIfError::DBError)
+            })?;
+
+            if !zones.iter().any(|zone| zone.identifier == hit.identifier) {
+                zones.push(hit);
+            }
+        }
+
+        let rows = transaction
+            .query(
+                &flights_stmt,
+                &[
+                    &segment.geom,
+                    &query.distance_meters,
+                    &segment.time_start,
+                    &segment.time_end,
+                    &query.tag_filter.tags,
+                    &now,
+                ],
+            )
+            .await
+            .map_err(|e| {
+                postgis_error!("(what_if) could not execute conflict query: {}", e);
+                PostgisError::WhatIf(WhatIfError::DBError)
+            })?;
+
+        for row in rows {
+            let conflict = FlightConflict::try_from(row).map_err(|e| {
+                postgis_error!("(what_if) could not get conflict data: {}", e);
+                PostgisError::WhatIf(WhatIfError::DBError)
+            })?;
+
+            // Report each conflicting flight once, at its closest approach
+            match conflicts
+                .iter_mut()
+                .find(|c| c.flight_identifier == conflict.flight_identifier)
+            {
+                Some(existing) if existing.distance_meters > conflict.distance_meters => {
+                    *existing = conflict
+                }
+                Some(_) => (),
+                None => conflicts.push(conflict),
+            }
+        }
+    }
+
+    let mut params: Vec<&(dyn ToSql + Sync)> =
+        vec![&query.position, &query.distance_meters, &updated_since];
+    params.extend(shard_rows.param());
+
+    let traffic = transaction
+        .query(&traffic_stmt, &params)
+        .await
+        .map_err(|e| {
+            postgis_error!("(what_if) could not execute traffic query: {}", e);
+            PostgisError::WhatIf(WhatIfError::DBError)
+        })?
+        .into_iter()
+        .map(|row| {
+            let identifier: String = row.try_get("identifier")?;
+            let geom: PointZ = row.try_get("geom")?;
+            let distance_meters: f64 = row.try_get("distance_meters")?;
+
+            Ok(NearbyAircraft {
+                identifier,
+                position: Some(GrpcPointZ::from(geom)),
+                distance_meters,
+            })
+        })
+        .collect::<Result<Vec<NearbyAircraft>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(what_if) could not get traffic data: {}", e);
+            PostgisError::WhatIf(WhatIfError::DBError)
+        })?;
+
+    transaction.rollback().await.map_err(|e| {
+        postgis_error!("(what_if) could not end transaction: {}", e);
+        PostgisError::WhatIf(WhatIfError::DBError)
+    })?;
+
+    postgis_debug!(
+        "(what_if) found {} zones, {} conflicts and {} aircraft.",
+        zones.len(),
+        conflicts.len(),
+        traffic.len()
+    );
+
+    Ok(WhatIfResponse {
+        zones,
+        conflicts: conflicts
+            .into_iter()
+            .map(GrpcFlightConflict::from)
+            .collect(),
+        traffic,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgis::utils::distance_meters;
+
+    fn request() -> WhatIfRequest {
+        WhatIfRequest {
+            position: Some(GrpcPointZ {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            }),
+            ground_speed_mps: 20.0,
+            track_angle_degrees: 90.0,
+            vertical_speed_mps: 1.0,
+            distance_meters: 50.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ut_extrapolate_position() {
+        let position = PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID));
+
+        // Due east for a minute at 20 m/s, climbing 1 m/s
+        let destination = extrapolate_position(&position, 20.0, 90.0, 1.0, 60.0);
+        assert!(destination.x > position.x);
+        assert!((destination.y - position.y).abs() < 1e-4);
+        assert!((destination.z - 160.0).abs() < 1e-9);
+
+        let horizontal = distance_meters(
+            &position,
+            &PointZ::new(destination.x, destination.y, position.z, None),
+        );
+        assert!((horizontal - 1200.0).abs() < 5.0);
+
+        // Standing still
+        let destination = extrapolate_position(&position, 0.0, 0.0, 0.0, 60.0);
+        assert!((destination.x - position.x).abs() < 1e-9);
+        assert!((destination.y - position.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ut_validate_what_if_request() {
+        let now = Utc::now();
+        let query = validate_what_if_request(&request(), now).unwrap();
+        assert_eq!(query.points.len(), 2);
+        assert_eq!(query.time_start, now);
+        assert_eq!(
+            query.time_end - query.time_start,
+            Duration::try_seconds(DEFAULT_WHAT_IF_LOOKAHEAD_SECS as i64).unwrap()
+        );
+
+        // A candidate path replaces the extrapolation
+        let path = vec![
+            GrpcPointZ {
+                latitude: 52.3755905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            GrpcPointZ {
+                latitude: 52.3765905,
+                longitude: 4.9170036,
+                altitude_meters: 120.0,
+            },
+        ];
+        let valid = WhatIfRequest {
+            path: path.clone(),
+            lookahead_secs: 120,
+            ..request()
+        };
+        let query = validate_what_if_request(&valid, now).unwrap();
+        assert_eq!(query.points.len(), 3);
+        assert_eq!(query.points[0], query.position);
+        assert_eq!(
+            query.time_end - query.time_start,
+            Duration::try_seconds(120).unwrap()
+        );
+
+        let invalid = WhatIfRequest {
+            position: None,
+            ..request()
+        };
+        assert_eq!(
+            validate_what_if_request(&invalid, now).unwrap_err(),
+            WhatIfError::Location
+        );
+
+        let mut invalid = WhatIfRequest { path, ..request() };
+        invalid.path[1].latitude = 91.0;
+        assert_eq!(
+            validate_what_if_request(&invalid, now).unwrap_err(),
+            WhatIfError::Location
+        );
+
+        for ground_speed_mps in [-1.0, f32::NAN, f32::INFINITY] {
+            let invalid = WhatIfRequest {
+                ground_speed_mps,
+                ..request()
+            };
+            assert_eq!(
+                validate_what_if_request(&invalid, now).unwrap_err(),
+                WhatIfError::Velocity
+            );
+        }
+
+        let invalid = WhatIfRequest {
+            lookahead_secs: MAX_WHAT_IF_LOOKAHEAD_SECS + 1,
+            ..request()
+        };
+        assert_eq!(
+            validate_what_if_request(&invalid, now).unwrap_err(),
+            WhatIfError::Time
+        );
+
+        for distance_meters in [0.0, -1.0, MAX_CONFLICT_DISTANCE_METERS + 1.0, f32::NAN] {
+            let invalid = WhatIfRequest {
+                distance_meters,
+                ..request()
+            };
+            assert_eq!(
+                validate_what_if_request(&invalid, now).unwrap_err(),
+                WhatIfError::Distance
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn ut_what_if_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_what_if_client_failure) start");
+
+        let result = what_if(request()).await.unwrap_err();
+        assert_eq!(result, PostgisError::WhatIf(WhatIfError::Client));
+
+        ut_info!("(ut_what_if_client_failure) success");
+    }
+}
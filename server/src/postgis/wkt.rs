@@ -0,0 +1,335 @@
+//! Conversions from WKT/EWKT strings to PostGIS geometries
+//!
+//! Accepts `POINT Z (x y z)` and `LINESTRING Z (x y z, ...)`, optionally
+//!  prefixed with an EWKT `SRID=<srid>;`. Without a prefix the coordinates
+//!  are assumed to be in [`DEFAULT_SRID`](super::DEFAULT_SRID). Longitude
+//!  is the X axis and latitude the Y axis.
+
+use super::utils::{check_srid, validate_pointz};
+use super::DEFAULT_SRID;
+use crate::grpc::server::grpc_server::PointZ as GrpcPointZ;
+use crate::types::Position;
+use postgis::ewkb::{LineStringT, PointZ};
+
+/// Max length of a WKT string accepted
+pub const MAX_WKT_LENGTH: usize = 1_000_000;
+
+/// Possible errors parsing WKT
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WktError {
+    /// Malformed WKT
+    Syntax,
+
+    /// Unexpected geometry type
+    GeometryType,
+
+    /// Coordinates don't have exactly three ordinates
+    Dimension,
+
+    /// Invalid or unsupported SRID
+    Srid,
+
+    /// A coordinate is outside of the valid range
+    OutOfBounds,
+
+    /// Not enough points for the geometry
+    PointCount,
+
+    /// Valid geometry, but not supported by the target
+    Unsupported,
+}
+
+impl std::fmt::Display for WktError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WktError::Syntax => write!(f, "Malformed WKT provided."),
+            WktError::GeometryType => write!(f, "Unexpected WKT geometry type."),
+            WktError::Dimension => write!(f, "WKT coordinates must have X, Y and Z."),
+            WktError::Srid => write!(f, "Invalid SRID provided."),
+            WktError::OutOfBounds => write!(f, "One or more coordinates are out of bounds."),
+            WktError::PointCount => write!(f, "Not enough points provided."),
+            WktError::Unsupported => write!(f, "Geometry not supported for this target."),
+        }
+    }
+}
+
+/// Splits an optional EWKT `SRID=<srid>;` prefix from the geometry
+fn split_srid(wkt: &str) -> Result<(i32, &str), WktError> {
+    let wkt = wkt.trim();
+    let Some((prefix, geometry)) = wkt.split_once(';') else {
+        return Ok((DEFAULT_SRID, wkt));
+    };
+
+    let Some(srid) = prefix
+        .trim()
+        .strip_prefix("SRID=")
+        .or_else(|| prefix.trim().strip_prefix("srid="))
+    else {
+        return Err(WktError::Syntax);
+    };
+
+    let srid = srid.trim().parse::<i32>().map_err(|_| WktError::Srid)?;
+    check_srid(srid).map_err(|_| WktError::Srid)?;
+
+    Ok((srid, geometry.trim()))
+}
+
+/// Checks the keyword of a `KEYWORD [Z] (body)` geometry and returns the
+///  body between the outer parentheses
+fn split_geometry<'a>(geometry: &'a str, keyword: &str) -> Result<&'a str, WktError> {
+    let Some(open) = geometry.find('(') else {
+        return Err(WktError::Syntax);
+    };
+
+    let mut tag = geometry[..open].split_whitespace();
+    let Some(name) = tag.next() else {
+        return Err(WktError::Syntax);
+    };
+
+    if !name.eq_ignore_ascii_case(keyword) {
+        return Err(WktError::GeometryType);
+    }
+
+    match tag.next() {
+        None => (),
+        Some(dimension) if dimension.eq_ignore_ascii_case("Z") => (),
+        Some(_) => return Err(WktError::Dimension),
+    }
+
+    if tag.next().is_some() {
+        return Err(WktError::Syntax);
+    }
+
+    let Some(body) = geometry[open + 1..].trim_end().strip_suffix(')') else {
+        return Err(WktError::Syntax);
+    };
+
+    if body.trim().is_empty() || body.contains(['(', ')']) {
+        return Err(WktError::Syntax);
+    }
+
+    Ok(body)
+}
+
+/// Parses an `x y z` coordinate
+fn parse_coordinate(coordinate: &str, srid: i32) -> Result<PointZ, WktError> {
+    let ordinates = coordinate
+        .split_whitespace()
+        .map(|value| value.parse::<f64>().map_err(|_| WktError::Syntax))
+        .collect::<Result<Vec<f64>, WktError>>()?;
+
+    let [x, y, z] = ordinates[..] else {
+        return Err(WktError::Dimension);
+    };
+
+    if !x.is_finite() || !y.is_finite() || !z.is_finite() {
+        return Err(WktError::OutOfBounds);
+    }
+
+    let point = PointZ::new(x, y, z, Some(srid));
+
+    // Only geographic coordinates have known bounds
+    if srid == DEFAULT_SRID {
+        validate_pointz(&point).map_err(|_| WktError::OutOfBounds)?;
+    }
+
+    Ok(point)
+}
+
+/// Parses a `POINT Z (x y z)` WKT or EWKT string
+pub fn pointz_from_wkt(wkt: &str) -> Result<PointZ, WktError> {
+    if wkt.len() > MAX_WKT_LENGTH {
+        postgis_error!("(pointz_from_wkt) WKT too long: {} bytes.", wkt.len());
+        return Err(WktError::Syntax);
+    }
+
+    let (srid, geometry) = split_srid(wkt)?;
+    let body = split_geometry(geometry, "POINT")?;
+    parse_coordinate(body, srid).map_err(|e| {
+        postgis_error!("(pointz_from_wkt) could not parse '{}': {}", wkt, e);
+        e
+    })
+}
+
+/// Parses a `LINESTRING Z (x y z, ...)` WKT or EWKT string
+pub fn linestringz_from_wkt(wkt: &str) -> Result<LineStringT<PointZ>, WktError> {
+    if wkt.len() > MAX_WKT_LENGTH {
+        postgis_error!("(linestringz_from_wkt) WKT too long: {} bytes.", wkt.len());
+        return Err(WktError::Syntax);
+    }
+
+    let (srid, geometry) = split_srid(wkt)?;
+    let body = split_geometry(geometry, "LINESTRING")?;
+    let points = body
+        .split(',')
+        .map(|coordinate| parse_coordinate(coordinate, srid))
+        .collect::<Result<Vec<PointZ>, WktError>>()
+        .map_err(|e| {
+            postgis_error!("(linestringz_from_wkt) could not parse line string: {}", e);
+            e
+        })?;
+
+    if points.len() < 2 {
+        postgis_error!(
+            "(linestringz_from_wkt) line string needs at least two points, found {}.",
+            points.len()
+        );
+        return Err(WktError::PointCount);
+    }
+
+    Ok(LineStringT {
+        points,
+        srid: Some(srid),
+    })
+}
+
+/// Parses the position of an aircraft update from WKT
+///
+/// Aircraft positions are stored in [`DEFAULT_SRID`], other SRIDs are
+///  rejected.
+pub fn position_from_wkt(wkt: &str) -> Result<Position, WktError> {
+    let point = pointz_from_wkt(wkt)?;
+    if point.srid != Some(DEFAULT_SRID) {
+        postgis_error!(
+            "(position_from_wkt) aircraft positions must use SRID {}.",
+            DEFAULT_SRID
+        );
+        return Err(WktError::Srid);
+    }
+
+    Ok(Position {
+        longitude: point.x,
+        latitude: point.y,
+        altitude_meters: point.z,
+    })
+}
+
+/// Parses the path of a flight update from WKT
+///
+/// Returns the points and their SRID, for the `path` and `srid` fields of
+///  an `UpdateFlightPathRequest`.
+pub fn flight_path_from_wkt(wkt: &str) -> Result<(Vec<GrpcPointZ>, i32), WktError> {
+    let line = linestringz_from_wkt(wkt)?;
+    let srid = line.srid.unwrap_or(DEFAULT_SRID);
+    let path = line
+        .points
+        .into_iter()
+        .map(|p| GrpcPointZ {
+            longitude: p.x,
+            latitude: p.y,
+            altitude_meters: p.z as f32,
+        })
+        .collect();
+
+    Ok((path, srid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ut_pointz_from_wkt() {
+        let point = pointz_from_wkt("POINT Z (4.9160036 52.3745905 100)").unwrap();
+        assert_eq!(
+            point,
+            PointZ::new(4.9160036, 52.3745905, 100.0, Some(DEFAULT_SRID))
+        );
+
+        let point = pointz_from_wkt(" srid=4326; point z(4.9 52.3 -5.5) ").unwrap();
+        assert_eq!(point, PointZ::new(4.9, 52.3, -5.5, Some(DEFAULT_SRID)));
+
+        // Projected coordinates have no fixed bounds
+        let point = pointz_from_wkt("SRID=28992;POINT Z (121000 487000 10)").unwrap();
+        assert_eq!(point, PointZ::new(121000.0, 487000.0, 10.0, Some(28992)));
+
+        // Z keyword is optional when three ordinates are given
+        assert!(pointz_from_wkt("POINT (4.9 52.3 10)").is_ok());
+    }
+
+    #[test]
+    fn ut_pointz_from_wkt_invalid() {
+        let cases = [
+            ("", WktError::Syntax),
+            ("garbage", WktError::Syntax),
+            ("POINT Z 4.9 52.3 100", WktError::Syntax),
+            ("POINT Z (4.9 52.3 100", WktError::Syntax),
+            ("POINT Z (4.9 52.3 abc)", WktError::Syntax),
+            ("POINT Z ((4.9 52.3 100))", WktError::Syntax),
+            ("POINT Z (4.9 52.3 100) extra", WktError::Syntax),
+            (
+                "LINESTRING Z (4.9 52.3 100, 5.0 52.3 100)",
+                WktError::GeometryType,
+            ),
+            ("POINT (4.9 52.3)", WktError::Dimension),
+            ("POINT M (4.9 52.3 100)", WktError::Dimension),
+            ("POINT ZM (4.9 52.3 100 1)", WktError::Dimension),
+            ("POINT Z (181.0 52.3 100)", WktError::OutOfBounds),
+            ("POINT Z (4.9 -90.1 100)", WktError::OutOfBounds),
+            ("POINT Z (4.9 52.3 NaN)", WktError::OutOfBounds),
+            ("SRID=0;POINT Z (4.9 52.3 100)", WktError::Srid),
+            ("SRID=abc;POINT Z (4.9 52.3 100)", WktError::Srid),
+            ("SRID:4326;POINT Z (4.9 52.3 100)", WktError::Syntax),
+        ];
+
+        for (wkt, expected) in cases {
+            assert_eq!(pointz_from_wkt(wkt).unwrap_err(), expected, "{wkt}");
+        }
+    }
+
+    #[test]
+    fn ut_linestringz_from_wkt() {
+        let line =
+            linestringz_from_wkt("LINESTRING Z (4.90 52.37 80, 4.95 52.40 80, 5.00 52.37 80)")
+                .unwrap();
+        assert_eq!(line.srid, Some(DEFAULT_SRID));
+        assert_eq!(line.points.len(), 3);
+        assert_eq!(
+            line.points[1],
+            PointZ::new(4.95, 52.40, 80.0, Some(DEFAULT_SRID))
+        );
+
+        let cases = [
+            ("LINESTRING Z (4.9 52.3 100)", WktError::PointCount),
+            ("LINESTRING Z ()", WktError::Syntax),
+            (
+                "LINESTRING Z (4.9 52.3 100,, 5.0 52.3 100)",
+                WktError::Dimension,
+            ),
+            (
+                "LINESTRING Z (4.9 52.3 100, 5.0 95.0 100)",
+                WktError::OutOfBounds,
+            ),
+            ("POINT Z (4.9 52.3 100)", WktError::GeometryType),
+        ];
+
+        for (wkt, expected) in cases {
+            assert_eq!(linestringz_from_wkt(wkt).unwrap_err(), expected, "{wkt}");
+        }
+    }
+
+    #[test]
+    fn ut_position_from_wkt() {
+        let position = position_from_wkt("POINT Z (4.9160036 52.3745905 100)").unwrap();
+        assert_eq!(position.longitude, 4.9160036);
+        assert_eq!(position.latitude, 52.3745905);
+        assert_eq!(position.altitude_meters, 100.0);
+
+        assert_eq!(
+            position_from_wkt("SRID=28992;POINT Z (121000 487000 10)").unwrap_err(),
+            WktError::Srid
+        );
+    }
+
+    #[test]
+    fn ut_flight_path_from_wkt() {
+        let (path, srid) =
+            flight_path_from_wkt("SRID=28992;LINESTRING Z (121000 487000 10, 122000 487000 20)")
+                .unwrap();
+        assert_eq!(srid, 28992);
+        assert_eq!(path.len(), 2);
+        assert_eq!(path[0].longitude, 121000.0);
+        assert_eq!(path[0].latitude, 487000.0);
+        assert_eq!(path[1].altitude_meters, 20.0);
+    }
+}
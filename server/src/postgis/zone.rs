@@ -1,17 +1,89 @@
 //! This module contains functions for updating zones in the PostGIS database.
 //! Zones have various restrictions and can be permanent or temporary.
+//!
+//! Changes of zones are persisted in the event backlog (see
+//!  [`super::backlog`]) in the same transaction, and published on the zone
+//!  change channel streamed by `streamZoneChanges` once committed.
 
+use super::backlog::{self, EventKind, PersistedEvent};
+use super::geometry_input::{zone_vertices_from_input, GeometryInputError};
+use super::tags::TagFilter;
 use super::{PostgisError, DEFAULT_SRID, PSQL_SCHEMA};
 use crate::grpc::server::grpc_server;
 use chrono::{DateTime, Utc};
 use deadpool_postgres::Object;
 use grpc_server::Zone as RequestZone;
-use grpc_server::ZoneType;
+use grpc_server::{ZoneChangeType, ZoneChanged, ZoneType};
 use num_traits::FromPrimitive;
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
 
 /// Allowed characters in a identifier
 const IDENTIFIER_REGEX: &str = r"^[\-0-9A-Za-z_\.]{1,255}$";
 
+/// Allowed characters in an external reference (NOTAM ids contain slashes)
+const EXTERNAL_REFERENCE_REGEX: &str = r"^[\-0-9A-Za-z_\./]{1,255}$";
+
+/// Max position history rows scanned by [`zone_crossings`]
+pub const MAX_ZONE_CROSSING_SAMPLES: i64 = 100_000;
+
+/// Capacity of the zone change channel, slow subscribers miss older changes
+const ZONE_CHANGE_CHANNEL_CAPACITY: usize = 256;
+
+/// Zone change channel
+pub static ZONE_CHANGES: Lazy<broadcast::Sender<ZoneChanged>> =
+    Lazy::new(|| broadcast::channel(ZONE_CHANGE_CHANNEL_CAPACITY).0);
+
+impl PersistedEvent for ZoneChanged {
+    const KIND: EventKind = EventKind::ZoneChanged;
+
+    fn event_id(&self) -> u64 {
+        self.event_id
+    }
+
+    fn set_event_id(&mut self, event_id: u64) {
+        self.event_id = event_id;
+    }
+}
+
+/// Persists changes of zones in the event backlog within the transaction
+///  making them, see [`backlog::persist`]
+///
+/// Changes without zones are skipped. Send the changes to subscribers once
+///  the transaction commits.
+async fn persist_zone_changes(
+    transaction: &deadpool_postgres::Transaction<'_>,
+    changes: Vec<(ZoneChangeType, Vec<String>)>,
+) -> Result<backlog::Outbox<ZoneChanged>, ZoneError> {
+    let timestamp = crate::clock::now();
+    let changes = changes
+        .into_iter()
+        .filter(|(_, identifiers)| !identifiers.is_empty())
+        .map(|(change_type, identifiers)| ZoneChanged {
+            change_type: change_type as i32,
+            identifiers,
+            timestamp: Some(timestamp.into()),
+            event_id: 0,
+        })
+        .collect();
+
+    backlog::persist(transaction, changes).await.map_err(|e| {
+        postgis_error!(
+            "(persist_zone_changes) could not persist zone changes: {}",
+            e
+        );
+        ZoneError::DBError
+    })
+}
+
+/// Stream of the zone changes committed after subscribing
+///
+/// With a `last_event_id`, the persisted changes after it are sent first,
+///  see [`backlog::event_stream`].
+pub fn zone_change_stream(last_event_id: Option<u64>) -> impl futures::Stream<Item = ZoneChanged> {
+    backlog::event_stream(&ZONE_CHANGES, last_event_id)
+}
+
 #[derive(Clone, Debug)]
 /// Nodes that aircraft can fly between
 pub struct Zone {
@@ -35,6 +107,15 @@ pub struct Zone {
 
     /// The end time of the zone, if applicable
     pub time_end: Option<DateTime<Utc>>,
+
+    /// The authority that published the zone
+    pub source: Option<String>,
+
+    /// The external reference of the zone (NOTAM id, etc.)
+    pub external_reference: Option<String>,
+
+    /// Operational tags
+    pub tags: Vec<String>,
 }
 
 /// Possible conversion errors from the GRPC type to GIS type
@@ -63,6 +144,30 @@ pub enum ZoneError {
 
     /// Invalid zone type
     ZoneType,
+
+    /// Invalid source authority
+    Source,
+
+    /// Invalid external reference
+    ExternalReference,
+
+    /// Zone is soft-deleted and can't be re-created until restored or purged
+    Deleted,
+
+    /// No matching zone found
+    NotFound,
+
+    /// Invalid tags
+    Tags,
+
+    /// Statement cancelled by the statement timeout
+    Timeout,
+
+    /// One or more vertices are outside of the service area
+    ServiceArea,
+
+    /// Invalid WKT or WKB footprint, or more than one footprint provided
+    Geometry(GeometryInputError),
 }
 
 impl std::fmt::Display for ZoneError {
@@ -76,6 +181,14 @@ impl std::fmt::Display for ZoneError {
             ZoneError::DBError => write!(f, "Unknown backend error."),
             ZoneError::Identifier => write!(f, "Invalid identifier provided."),
             ZoneError::ZoneType => write!(f, "Invalid zone type provided."),
+            ZoneError::Source => write!(f, "Invalid source provided."),
+            ZoneError::ExternalReference => write!(f, "Invalid external reference provided."),
+            ZoneError::Deleted => write!(f, "Zone is deleted, restore it or wait for the purge."),
+            ZoneError::NotFound => write!(f, "No matching zone found."),
+            ZoneError::Tags => write!(f, "Invalid tags provided."),
+            ZoneError::Timeout => write!(f, "Backend statement timed out."),
+            ZoneError::ServiceArea => write!(f, "Zone outside of the service area."),
+            ZoneError::Geometry(e) => write!(f, "{e}"),
         }
     }
 }
@@ -84,6 +197,7 @@ impl TryFrom<RequestZone> for Zone {
     type Error = ZoneError;
 
     fn try_from(zone: RequestZone) -> Result<Self, Self::Error> {
+        let zone = resolve_zone_geometry(zone)?;
         if let Err(e) = super::utils::check_string(&zone.identifier, IDENTIFIER_REGEX) {
             postgis_error!(
                 "(try_from RequestZone) Invalid zone identifier: {}; {}",
@@ -118,6 +232,17 @@ impl TryFrom<RequestZone> for Zone {
                 }
             };
 
+        let vertices = zone.vertices.iter().map(|v| (v.longitude, v.latitude));
+        if let Err(index) = super::service_area::check_service_area(vertices) {
+            postgis_error!(
+                "(try_from RequestZone) vertex {} of zone {} is outside of the service area: {:?}",
+                index,
+                zone.identifier,
+                zone.vertices[index]
+            );
+            return Err(ZoneError::ServiceArea);
+        }
+
         let Some(zone_type) = FromPrimitive::from_i32(zone.zone_type) else {
             postgis_error!(
                 "(try_from RequestZone) Invalid zone type: {}",
@@ -126,6 +251,37 @@ impl TryFrom<RequestZone> for Zone {
             return Err(ZoneError::ZoneType);
         };
 
+        if let Some(ref source) = zone.source {
+            if let Err(e) = check_source(source) {
+                postgis_error!(
+                    "(try_from RequestZone) Invalid zone source: {}; {}",
+                    source,
+                    e
+                );
+                return Err(ZoneError::Source);
+            }
+        }
+
+        if let Some(ref reference) = zone.external_reference {
+            if let Err(e) = super::utils::check_string(reference, EXTERNAL_REFERENCE_REGEX) {
+                postgis_error!(
+                    "(try_from RequestZone) Invalid zone external reference: {}; {}",
+                    reference,
+                    e
+                );
+                return Err(ZoneError::ExternalReference);
+            }
+        }
+
+        if let Err(e) = super::tags::validate_tags(&zone.tags) {
+            postgis_error!(
+                "(try_from RequestZone) Invalid zone tags: {:?}; {}",
+                zone.tags,
+                e
+            );
+            return Err(ZoneError::Tags);
+        }
+
         Ok(Zone {
             identifier: zone.identifier,
             zone_type,
@@ -134,10 +290,50 @@ impl TryFrom<RequestZone> for Zone {
             altitude_meters_max: zone.altitude_meters_max,
             time_start,
             time_end,
+            source: zone.source,
+            external_reference: zone.external_reference,
+            tags: zone.tags,
         })
     }
 }
 
+/// Replaces the WKT or WKB footprint of a zone with its vertices
+///
+/// The footprint can't be combined with vertices.
+pub fn resolve_zone_geometry(mut zone: RequestZone) -> Result<RequestZone, ZoneError> {
+    let vertices = zone_vertices_from_input(zone.geom_wkt.as_deref(), zone.geom_ewkb.as_deref())
+        .map_err(|e| {
+            postgis_error!(
+                "(resolve_zone_geometry) invalid footprint for zone {}: {}",
+                zone.identifier,
+                e
+            );
+            ZoneError::Geometry(e)
+        })?;
+
+    let Some(vertices) = vertices else {
+        return Ok(zone);
+    };
+
+    if !zone.vertices.is_empty() {
+        postgis_error!(
+            "(resolve_zone_geometry) zone {} has both a footprint and vertices.",
+            zone.identifier
+        );
+        return Err(ZoneError::Geometry(GeometryInputError::Conflict));
+    }
+
+    zone.vertices = vertices;
+    zone.geom_wkt = None;
+    zone.geom_ewkb = None;
+    Ok(zone)
+}
+
+/// Verifies that a zone source is valid
+pub fn check_source(source: &str) -> Result<(), super::utils::StringError> {
+    super::utils::check_string(source, IDENTIFIER_REGEX)
+}
+
 /// Get the table name for the zones table
 /// pub(super) so that it can be used by the vertiports module
 pub(super) fn get_table_name() -> &'static str {
@@ -147,10 +343,15 @@ pub(super) fn get_table_name() -> &'static str {
 
 /// Initialize the vertiports table in the PostGIS database
 pub async fn psql_init() -> Result<(), PostgisError> {
+    super::psql_transaction(psql_init_statements()).await
+}
+
+/// Gets the table and enum declarations for zones
+pub(super) fn psql_init_statements() -> Vec<String> {
     // Create Aircraft Table
 
     let zonetype_str = "zonetype";
-    let statements = vec![
+    vec![
         super::psql_enum_declaration::<ZoneType>(zonetype_str),
         format!(
             r#"CREATE TABLE IF NOT EXISTS {table_name} (
@@ -170,42 +371,50 @@ pub async fn psql_init() -> Result<(), PostgisError> {
             r#"CREATE INDEX IF NOT EXISTS "zone_geom_idx" ON {table_name} USING GIST ("geom");"#,
             table_name = get_table_name()
         ),
-    ];
-
-    super::psql_transaction(statements).await
+        format!(
+            r#"ALTER TABLE {table_name}
+                ADD COLUMN IF NOT EXISTS "source" VARCHAR(255),
+                ADD COLUMN IF NOT EXISTS "external_reference" VARCHAR(255);"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "zone_source_idx" ON {table_name} ("source");"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "deleted_at" TIMESTAMPTZ;"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"ALTER TABLE {table_name} ADD COLUMN IF NOT EXISTS "tags" TEXT[] NOT NULL DEFAULT '{{}}';"#,
+            table_name = get_table_name()
+        ),
+        format!(
+            r#"CREATE INDEX IF NOT EXISTS "zone_tags_idx" ON {table_name} USING GIN ("tags");"#,
+            table_name = get_table_name()
+        ),
+    ]
 }
 
-/// Updates zones in the PostGIS database.
-pub async fn update_zones(zones: Vec<RequestZone>) -> Result<(), ZoneError> {
-    postgis_debug!("(update_zones) entry.");
-    if zones.is_empty() {
-        postgis_error!("(update_zones) no zones provided.");
-        return Err(ZoneError::NoZones);
-    }
-
-    let zones: Vec<Zone> = zones
-        .into_iter()
-        .map(Zone::try_from)
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
-        postgis_error!("(update_zones) could not get psql pool.");
-        return Err(ZoneError::Client);
-    };
-
-    let mut client = pool.get().await.map_err(|e| {
-        postgis_error!(
-            "(update_zones) could not get client from psql connection pool: {}",
-            e
-        );
-        ZoneError::Client
-    })?;
+/// Counts of zones written by an upsert
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+struct ZoneUpsertCounts {
+    /// Zones that didn't exist yet
+    created: u64,
 
-    let transaction = client.transaction().await.map_err(|e| {
-        postgis_error!("(update_zones) could not create transaction: {}", e);
-        ZoneError::DBError
-    })?;
+    /// Existing zones that were overwritten
+    updated: u64,
+}
 
+/// Inserts or overwrites zones within a transaction
+///
+/// Fails with [`ZoneError::Deleted`] if a zone is soft-deleted, it must
+///  be restored or purged first.
+async fn upsert_zones(
+    caller: &str,
+    transaction: &deadpool_postgres::Transaction<'_>,
+    zones: &[Zone],
+) -> Result<ZoneUpsertCounts, ZoneError> {
     let stmt = transaction
         .prepare_cached(&format!(
             r#"INSERT INTO {table_name} (
@@ -216,6 +425,9 @@ pub async fn update_zones(zones: Vec<RequestZone>) -> Result<(), ZoneError> {
             "altitude_meters_max",
             "time_start",
             "time_end",
+            "source",
+            "external_reference",
+            "tags",
             "last_updated"
         )
         VALUES (
@@ -226,6 +438,9 @@ pub async fn update_zones(zones: Vec<RequestZone>) -> Result<(), ZoneError> {
             $5,
             $6,
             $7,
+            $8,
+            $9,
+            $10,
             NOW()
         )
         ON CONFLICT ("identifier") DO UPDATE
@@ -233,19 +448,25 @@ pub async fn update_zones(zones: Vec<RequestZone>) -> Result<(), ZoneError> {
             "altitude_meters_min" = EXCLUDED."altitude_meters_min",
             "altitude_meters_max" = EXCLUDED."altitude_meters_max",
             "time_start" = EXCLUDED."time_start",
-            "time_end" = EXCLUDED."time_end";
+            "time_end" = EXCLUDED."time_end",
+            "source" = EXCLUDED."source",
+            "external_reference" = EXCLUDED."external_reference",
+            "tags" = EXCLUDED."tags"
+        WHERE {table_name}."deleted_at" IS NULL
+        RETURNING ("xmax" = 0) AS "inserted";
         "#,
             table_name = get_table_name(),
         ))
         .await
         .map_err(|e| {
-            postgis_error!("(update_zones) could not prepare cached statement: {}", e);
+            postgis_error!("({caller}) could not prepare cached statement: {}", e);
             ZoneError::DBError
         })?;
 
-    for zone in &zones {
-        transaction
-            .execute(
+    let mut counts = ZoneUpsertCounts::default();
+    for zone in zones {
+        let row = transaction
+            .query_opt(
                 &stmt,
                 &[
                     &zone.identifier,
@@ -255,147 +476,1086 @@ pub async fn update_zones(zones: Vec<RequestZone>) -> Result<(), ZoneError> {
                     &zone.altitude_meters_max,
                     &zone.time_start,
                     &zone.time_end,
+                    &zone.source,
+                    &zone.external_reference,
+                    &zone.tags,
                 ],
             )
             .await
             .map_err(|e| {
-                postgis_error!("(update_zones) could not execute transaction: {}", e);
-                ZoneError::DBError
+                postgis_error!("({caller}) could not execute transaction: {}", e);
+                match super::DbErrorKind::from(&e) {
+                    super::DbErrorKind::Timeout => ZoneError::Timeout,
+                    _ => ZoneError::DBError,
+                }
             })?;
-    }
-
-    match transaction.commit().await {
-        Ok(_) => {
-            postgis_debug!("(update_zones) success.");
-            Ok(())
-        }
-        Err(e) => {
-            postgis_error!("(update_zones) could not commit transaction: {}", e);
-            Err(ZoneError::DBError)
-        }
-    }
-}
-
-/// Prepares a statement that checks zone intersections with the provided geometry
-pub async fn get_zone_intersection_stmt(
-    client: &Object,
-) -> Result<tokio_postgres::Statement, PostgisError> {
-    let result = client
-        .prepare_cached(&format!(
-            r#"
-            SELECT
-                "identifier",
-                "geom",
-                "zone_type",
-                "altitude_meters_min",
-                "altitude_meters_max",
-                "time_start",
-                "time_end"
-            FROM {table_name}
-            WHERE
-                ST_3DIntersects("geom", $1::GEOMETRY(LINESTRINGZ, {DEFAULT_SRID}))
-                AND ("time_start" <= $3 OR "time_start" IS NULL)
-                AND ("time_end" >= $2 OR "time_end" IS NULL)
-                AND "identifier" NOT IN ($4, $5)
-            LIMIT 1;
-        "#,
-            table_name = get_table_name()
-        ))
-        .await;
 
-    match result {
-        Ok(stmt) => Ok(stmt),
-        Err(e) => {
+        // The conflict update is skipped for soft-deleted zones
+        let Some(row) = row else {
             postgis_error!(
-                "(get_zone_intersection_stmt) could not prepare cached statement: {}",
-                e
+                "({caller}) zone '{}' is deleted, restore it before updating.",
+                zone.identifier
             );
-            Err(PostgisError::Zone(ZoneError::DBError))
+            return Err(ZoneError::Deleted);
+        };
+
+        match row.try_get::<_, bool>("inserted") {
+            Ok(true) => counts.created += 1,
+            Ok(false) => counts.updated += 1,
+            Err(e) => {
+                postgis_error!("({caller}) could not parse upsert result: {}", e);
+                return Err(ZoneError::DBError);
+            }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::grpc::server::grpc_server::Coordinates;
-    use crate::postgis::utils;
+    Ok(counts)
+}
 
-    fn square(latitude: f64, longitude: f64) -> Vec<(f64, f64)> {
-        vec![
-            (latitude - 0.0001, longitude - 0.0001),
-            (latitude + 0.0001, longitude - 0.0001),
-            (latitude + 0.0001, longitude + 0.0001),
-            (latitude - 0.0001, longitude + 0.0001),
-            (latitude - 0.0001, longitude - 0.0001),
-        ]
+/// Updates zones in the PostGIS database.
+///
+/// A dry run validates and writes the zones, then rolls back.
+pub async fn update_zones(zones: Vec<RequestZone>, dry_run: bool) -> Result<(), ZoneError> {
+    postgis_debug!("(update_zones) entry.");
+    if zones.is_empty() {
+        postgis_error!("(update_zones) no zones provided.");
+        return Err(ZoneError::NoZones);
     }
 
-    #[test]
-    fn ut_request_valid() {
-        let nodes: Vec<(&str, Vec<(f64, f64)>, f32, f32)> = vec![
-            ("NFZ_A", square(52.3745905, 4.9160036), 102.0, 200.0),
-            ("NFZ_B", square(52.3749819, 4.9156925), 20.5, 120.0),
-            ("NFZ_C", square(52.3752144, 4.9153733), 22.0, 100.0),
-        ];
+    let zones: Vec<Zone> = zones
+        .into_iter()
+        .map(Zone::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
 
-        let zones: Vec<RequestZone> = nodes
-            .iter()
-            .map(
-                |(identifier, points, altitude_min, altitude_max)| RequestZone {
-                    identifier: identifier.to_string(),
-                    vertices: points
-                        .iter()
-                        .map(|(latitude, longitude)| Coordinates {
-                            latitude: *latitude,
-                            longitude: *longitude,
-                        })
-                        .collect(),
-                    altitude_meters_min: *altitude_min,
-                    altitude_meters_max: *altitude_max,
-                    ..Default::default()
-                },
-            )
-            .collect();
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(update_zones) could not get psql pool.");
+        return Err(ZoneError::Client);
+    };
 
-        let converted = zones
-            .clone()
-            .into_iter()
-            .map(Zone::try_from)
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(update_zones) could not get client from psql connection pool: {}",
+            e
+        );
+        ZoneError::Client
+    })?;
 
-        assert_eq!(zones.len(), converted.len());
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
 
-        for (i, nfz) in zones.iter().enumerate() {
-            assert_eq!(nfz.identifier, converted[i].identifier);
-            assert_eq!(
-                utils::polygon_from_vertices_z(&nfz.vertices, nfz.altitude_meters_min).unwrap(),
-                converted[i].geom
-            );
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("(update_zones) could not create transaction: {}", e);
+        ZoneError::DBError
+    })?;
+
+    upsert_zones("update_zones", &transaction, &zones).await?;
+
+    let outbox = match dry_run {
+        true => None,
+        false => {
+            let identifiers = zones.into_iter().map(|zone| zone.identifier).collect();
+            let changes = vec![(ZoneChangeType::Updated, identifiers)];
+            Some(persist_zone_changes(&transaction, changes).await?)
         }
-    }
+    };
 
-    #[tokio::test]
-    async fn ut_client_failure() {
-        let nodes: Vec<(&str, Vec<(f64, f64)>)> = vec![("NFZ", square(52.3745905, 4.9160036))];
-        let zone: Vec<RequestZone> = nodes
-            .iter()
-            .map(|(identifier, points)| RequestZone {
-                identifier: identifier.to_string(),
-                vertices: points
-                    .iter()
-                    .map(|(latitude, longitude)| Coordinates {
-                        latitude: *latitude,
-                        longitude: *longitude,
-                    })
-                    .collect(),
-                ..Default::default()
-            })
-            .collect();
+    if let Err(e) = super::commit_or_rollback(transaction, dry_run).await {
+        postgis_error!("(update_zones) could not commit transaction: {}", e);
+        return Err(ZoneError::DBError);
+    }
 
-        let result = update_zones(zone).await.unwrap_err();
-        assert_eq!(result, ZoneError::Client);
+    if let Some(outbox) = outbox {
+        outbox.send(&ZONE_CHANGES);
+    }
+
+    postgis_debug!("(update_zones) success, dry run: {dry_run}.");
+    Ok(())
+}
+
+/// Why the geometry of a zone is invalid
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneGeometryIssue {
+    /// The identifier of the zone
+    pub identifier: String,
+
+    /// The reason the geometry is invalid, as from PostGIS `ST_IsValidReason`
+    pub reason: String,
+
+    /// The offending location, if known
+    pub location: Option<grpc_server::Coordinates>,
+}
+
+impl std::fmt::Display for ZoneGeometryIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "zone '{}': {}", self.identifier, self.reason)
+    }
+}
+
+/// Explains why the geometry of each invalid zone is rejected
+///
+/// The vertices are checked here first (closed ring, bounds, repeated
+///  vertices and crossing edges). Only if they all pass are the polygons
+///  checked with PostGIS `ST_IsValidDetail`, which catches what the local
+///  checks don't.
+pub async fn zone_geometry_issues(
+    zones: &[RequestZone],
+) -> Result<Vec<ZoneGeometryIssue>, ZoneError> {
+    postgis_debug!("(zone_geometry_issues) entry.");
+    let issues: Vec<ZoneGeometryIssue> = zones
+        .iter()
+        .filter_map(|zone| {
+            let reason = super::utils::polygon_validity_reason(&zone.vertices)?;
+            let location = super::utils::ring_self_intersection(&zone.vertices)
+                .map(|intersection| intersection.location().clone());
+
+            Some(ZoneGeometryIssue {
+                identifier: zone.identifier.clone(),
+                reason,
+                location,
+            })
+        })
+        .collect();
+
+    if !issues.is_empty() {
+        return Ok(issues);
+    }
+
+    let polygons = zones
+        .iter()
+        .filter_map(|zone| {
+            super::utils::polygon_from_vertices_z(&zone.vertices, zone.altitude_meters_min)
+                .ok()
+                .map(|polygon| (zone.identifier.clone(), polygon))
+        })
+        .collect::<Vec<_>>();
+
+    postgis_geometry_issues(&polygons).await
+}
+
+/// A row of PostGIS `ST_IsValidDetail`
+struct ValidityDetail {
+    valid: bool,
+    reason: Option<String>,
+    location: Option<(f64, f64)>,
+}
+
+impl TryFrom<tokio_postgres::Row> for ValidityDetail {
+    type Error = tokio_postgres::error::Error;
+
+    fn try_from(row: tokio_postgres::Row) -> Result<Self, Self::Error> {
+        let longitude: Option<f64> = row.try_get("longitude")?;
+        let latitude: Option<f64> = row.try_get("latitude")?;
+        Ok(ValidityDetail {
+            valid: row.try_get("valid")?,
+            reason: row.try_get("reason")?,
+            location: longitude.zip(latitude),
+        })
+    }
+}
+
+/// Checks polygons with PostGIS `ST_IsValidDetail`
+pub async fn postgis_geometry_issues(
+    polygons: &[(String, postgis::ewkb::PolygonZ)],
+) -> Result<Vec<ZoneGeometryIssue>, ZoneError> {
+    if polygons.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(postgis_geometry_issues) could not get psql pool.");
+        return Err(ZoneError::Client);
+    };
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(postgis_geometry_issues) could not get client from psql connection pool: {}",
+            e
+        );
+        ZoneError::Client
+    })?;
+
+    let stmt = client
+        .prepare_cached(
+            r#"SELECT
+                "valid",
+                "reason",
+                ST_X("location") AS "longitude",
+                ST_Y("location") AS "latitude"
+            FROM ST_IsValidDetail($1::GEOMETRY);"#,
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(postgis_geometry_issues) could not prepare cached statement: {}",
+                e
+            );
+            ZoneError::DBError
+        })?;
+
+    let mut issues = vec![];
+    for (identifier, polygon) in polygons {
+        let row = client.query_one(&stmt, &[polygon]).await.map_err(|e| {
+            postgis_error!("(postgis_geometry_issues) could not execute query: {}", e);
+            ZoneError::DBError
+        })?;
+
+        let detail = ValidityDetail::try_from(row).map_err(|e| {
+            postgis_error!("(postgis_geometry_issues) could not get validity: {}", e);
+            ZoneError::DBError
+        })?;
+
+        if detail.valid {
+            continue;
+        }
+
+        let reason = detail
+            .reason
+            .unwrap_or_else(|| "Invalid geometry".to_string());
+        let (reason, location) = match detail.location {
+            Some((longitude, latitude)) => (
+                format!("{reason}[{longitude} {latitude}]"),
+                Some(grpc_server::Coordinates {
+                    latitude,
+                    longitude,
+                }),
+            ),
+            None => (reason, None),
+        };
+
+        postgis_warn!(
+            "(postgis_geometry_issues) invalid zone '{}': {}",
+            identifier,
+            reason
+        );
+        issues.push(ZoneGeometryIssue {
+            identifier: identifier.clone(),
+            reason,
+            location,
+        });
+    }
+
+    Ok(issues)
+}
+
+/// Executes a statement on zones, returning the number of rows affected
+///
+/// If rows were affected, the `change` is persisted in the same
+///  transaction and sent once committed.
+async fn execute_zone_stmt(
+    caller: &str,
+    stmt: &str,
+    params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    change: Option<(ZoneChangeType, &str)>,
+) -> Result<u64, ZoneError> {
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("({caller}) could not get psql pool.");
+        return Err(ZoneError::Client);
+    };
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "({caller}) could not get client from psql connection pool: {}",
+            e
+        );
+        ZoneError::Client
+    })?;
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("({caller}) could not create transaction: {}", e);
+        ZoneError::DBError
+    })?;
+
+    let stmt = transaction.prepare_cached(stmt).await.map_err(|e| {
+        postgis_error!("({caller}) could not prepare cached statement: {}", e);
+        ZoneError::DBError
+    })?;
+
+    let affected = transaction.execute(&stmt, params).await.map_err(|e| {
+        postgis_error!("({caller}) could not execute statement: {}", e);
+        ZoneError::DBError
+    })?;
+
+    let outbox = match (affected, change) {
+        (0, _) | (_, None) => None,
+        (_, Some((change_type, identifier))) => {
+            let changes = vec![(change_type, vec![identifier.to_string()])];
+            Some(persist_zone_changes(&transaction, changes).await?)
+        }
+    };
+
+    transaction.commit().await.map_err(|e| {
+        postgis_error!("({caller}) could not commit transaction: {}", e);
+        ZoneError::DBError
+    })?;
+
+    if let Some(outbox) = outbox {
+        outbox.send(&ZONE_CHANGES);
+    }
+
+    Ok(affected)
+}
+
+/// Soft-deletes a zone, it can be restored with [`restore_zone`]
+pub async fn delete_zone(identifier: &str) -> Result<(), ZoneError> {
+    postgis_debug!("(delete_zone) entry, zone: '{identifier}'.");
+    if let Err(e) = super::utils::check_string(identifier, IDENTIFIER_REGEX) {
+        postgis_error!("(delete_zone) invalid identifier {}: {}", identifier, e);
+        return Err(ZoneError::Identifier);
+    }
+
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "deleted_at" = $2
+        WHERE "identifier" = $1 AND "deleted_at" IS NULL;"#,
+        table_name = get_table_name()
+    );
+
+    let change = Some((ZoneChangeType::Deleted, identifier));
+    let now = crate::clock::now();
+    match execute_zone_stmt("delete_zone", &stmt, &[&identifier, &now], change).await? {
+        0 => Err(ZoneError::NotFound),
+        _ => Ok(()),
+    }
+}
+
+/// Restores a zone deleted less than `undo_window_secs` ago
+pub async fn restore_zone(identifier: &str, undo_window_secs: u64) -> Result<(), ZoneError> {
+    postgis_debug!("(restore_zone) entry, zone: '{identifier}'.");
+    if let Err(e) = super::utils::check_string(identifier, IDENTIFIER_REGEX) {
+        postgis_error!("(restore_zone) invalid identifier {}: {}", identifier, e);
+        return Err(ZoneError::Identifier);
+    }
+
+    let stmt = format!(
+        r#"UPDATE {table_name} SET "deleted_at" = NULL
+        WHERE "identifier" = $1
+            AND "deleted_at" >= $3 - make_interval(secs => $2::FLOAT8);"#,
+        table_name = get_table_name()
+    );
+
+    let window = undo_window_secs as f64;
+    let change = Some((ZoneChangeType::Restored, identifier));
+    let now = crate::clock::now();
+    match execute_zone_stmt("restore_zone", &stmt, &[&identifier, &window, &now], change).await? {
+        0 => {
+            postgis_warn!("(restore_zone) no zone '{identifier}' deleted within the undo window.");
+            Err(ZoneError::NotFound)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Permanently removes zones deleted more than `retention_secs` ago,
+///  returning the number removed
+///
+/// Zones still referenced by a vertiport are kept.
+pub async fn purge_zones(retention_secs: u64) -> Result<u64, ZoneError> {
+    postgis_debug!("(purge_zones) entry.");
+    let stmt = format!(
+        r#"DELETE FROM {table_name} AS "zones"
+        WHERE "zones"."deleted_at" < $2 - make_interval(secs => $1::FLOAT8)
+            AND NOT EXISTS (
+                SELECT 1 FROM {vertiports_table_name} AS "vertiports"
+                WHERE "vertiports"."zone_id" = "zones"."id"
+            );"#,
+        table_name = get_table_name(),
+        vertiports_table_name = super::vertiport::get_table_name(),
+    );
+
+    let retention = retention_secs as f64;
+    let purged = execute_zone_stmt(
+        "purge_zones",
+        &stmt,
+        &[&retention, &crate::clock::now()],
+        None,
+    )
+    .await?;
+    postgis_debug!("(purge_zones) purged {} zones.", purged);
+    Ok(purged)
+}
+
+/// Soft-deletes all zones published by a source, returning the number removed
+///
+/// Used when an authority retracts a publication.
+pub async fn remove_zones_by_source(source: &str) -> Result<u64, ZoneError> {
+    postgis_debug!("(remove_zones_by_source) entry, source: '{source}'.");
+    if let Err(e) = check_source(source) {
+        postgis_error!("(remove_zones_by_source) invalid source {}: {}", source, e);
+        return Err(ZoneError::Source);
+    }
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(remove_zones_by_source) could not get psql pool.");
+        return Err(ZoneError::Client);
+    };
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(remove_zones_by_source) could not get client from psql connection pool: {}",
+            e
+        );
+        ZoneError::Client
+    })?;
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!(
+            "(remove_zones_by_source) could not create transaction: {}",
+            e
+        );
+        ZoneError::DBError
+    })?;
+
+    let stmt = transaction
+        .prepare_cached(&format!(
+            r#"UPDATE {table_name} SET "deleted_at" = $2
+            WHERE "source" = $1 AND "deleted_at" IS NULL
+            RETURNING "identifier";"#,
+            table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(remove_zones_by_source) could not prepare cached statement: {}",
+                e
+            );
+            ZoneError::DBError
+        })?;
+
+    let identifiers: Vec<String> = transaction
+        .query(&stmt, &[&source, &crate::clock::now()])
+        .await
+        .map_err(|e| {
+            postgis_error!(
+                "(remove_zones_by_source) could not execute statement: {}",
+                e
+            );
+            ZoneError::DBError
+        })?
+        .iter()
+        .map(|row| row.try_get("identifier"))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| {
+            postgis_error!("(remove_zones_by_source) could not get identifiers: {}", e);
+            ZoneError::DBError
+        })?;
+
+    let removed = identifiers.len() as u64;
+    let outbox =
+        persist_zone_changes(&transaction, vec![(ZoneChangeType::Deleted, identifiers)]).await?;
+
+    transaction.commit().await.map_err(|e| {
+        postgis_error!(
+            "(remove_zones_by_source) could not commit transaction: {}",
+            e
+        );
+        ZoneError::DBError
+    })?;
+
+    outbox.send(&ZONE_CHANGES);
+    postgis_debug!("(remove_zones_by_source) removed {} zones.", removed);
+    Ok(removed)
+}
+
+/// Counts of zones changed by [`replace_zones`]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct ZoneReplacement {
+    /// Zones that didn't exist yet
+    pub created: u64,
+
+    /// Existing zones that were overwritten
+    pub updated: u64,
+
+    /// Zones of the source missing from the new set, soft-deleted
+    pub removed: u64,
+}
+
+/// Replaces all zones published by a source with a new set
+///
+/// Zones of the source missing from the set are soft-deleted and the
+///  others are upserted in a single transaction, so readers see either
+///  the previous or the new publication. Zones without a source are
+///  assigned to `source`, zones of another source are rejected.
+///
+/// An empty set removes all zones of the source. A dry run validates and
+///  writes the zones, then rolls back.
+pub async fn replace_zones(
+    source: &str,
+    zones: Vec<RequestZone>,
+    dry_run: bool,
+) -> Result<ZoneReplacement, ZoneError> {
+    postgis_debug!(
+        "(replace_zones) entry, source: '{source}', zones: {}.",
+        zones.len()
+    );
+
+    if let Err(e) = check_source(source) {
+        postgis_error!("(replace_zones) invalid source {}: {}", source, e);
+        return Err(ZoneError::Source);
+    }
+
+    let mut zones: Vec<Zone> = zones
+        .into_iter()
+        .map(Zone::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for zone in zones.iter_mut() {
+        match zone.source.as_deref() {
+            None => zone.source = Some(source.to_string()),
+            Some(zone_source) if zone_source == source => (),
+            Some(zone_source) => {
+                postgis_error!(
+                    "(replace_zones) zone '{}' is published by '{zone_source}', not '{source}'.",
+                    zone.identifier
+                );
+                return Err(ZoneError::Source);
+            }
+        }
+    }
+
+    let identifiers: Vec<String> = zones.iter().map(|zone| zone.identifier.clone()).collect();
+
+    let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() else {
+        postgis_error!("(replace_zones) could not get psql pool.");
+        return Err(ZoneError::Client);
+    };
+
+    let mut client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(replace_zones) could not get client from psql connection pool: {}",
+            e
+        );
+        ZoneError::Client
+    })?;
+
+    let _in_flight = crate::shutdown::IN_FLIGHT.track();
+
+    let transaction = client.transaction().await.map_err(|e| {
+        postgis_error!("(replace_zones) could not create transaction: {}", e);
+        ZoneError::DBError
+    })?;
+
+    let stmt = transaction
+        .prepare_cached(&format!(
+            r#"UPDATE {table_name} SET "deleted_at" = $3
+            WHERE "source" = $1
+                AND "deleted_at" IS NULL
+                AND NOT ("identifier" = ANY($2::TEXT[]))
+            RETURNING "identifier";"#,
+            table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(replace_zones) could not prepare cached statement: {}", e);
+            ZoneError::DBError
+        })?;
+
+    let removed = transaction
+        .query(&stmt, &[&source, &identifiers, &crate::clock::now()])
+        .await
+        .map_err(|e| {
+            postgis_error!("(replace_zones) could not remove zones: {}", e);
+            match super::DbErrorKind::from(&e) {
+                super::DbErrorKind::Timeout => ZoneError::Timeout,
+                _ => ZoneError::DBError,
+            }
+        })?
+        .iter()
+        .map(|row| row.try_get("identifier"))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| {
+            postgis_error!("(replace_zones) could not get removed zones: {}", e);
+            ZoneError::DBError
+        })?;
+
+    let counts = upsert_zones("replace_zones", &transaction, &zones).await?;
+    let replacement = ZoneReplacement {
+        created: counts.created,
+        updated: counts.updated,
+        removed: removed.len() as u64,
+    };
+
+    let outbox = match dry_run {
+        true => None,
+        false => {
+            let changes = vec![
+                (ZoneChangeType::Updated, identifiers),
+                (ZoneChangeType::Deleted, removed),
+            ];
+            Some(persist_zone_changes(&transaction, changes).await?)
+        }
+    };
+
+    if let Err(e) = super::commit_or_rollback(transaction, dry_run).await {
+        postgis_error!("(replace_zones) could not commit transaction: {}", e);
+        return Err(ZoneError::DBError);
+    }
+
+    if let Some(outbox) = outbox {
+        outbox.send(&ZONE_CHANGES);
+    }
+
+    postgis_debug!(
+        "(replace_zones) success, dry run: {dry_run}: {:?}.",
+        replacement
+    );
+
+    Ok(replacement)
+}
+
+/// Direction of a zone boundary crossing
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CrossingKind {
+    /// Aircraft entered the zone
+    Entry,
+
+    /// Aircraft left the zone
+    Exit,
+}
+
+/// An aircraft crossing the boundary of a zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneCrossing {
+    /// The aircraft identifier
+    pub aircraft_identifier: String,
+
+    /// Entry or exit
+    pub kind: CrossingKind,
+
+    /// Time of the first position on the new side of the boundary
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A recorded aircraft position and if it was inside the zone
+#[derive(Debug, Clone, PartialEq)]
+struct ZoneSample {
+    aircraft_identifier: String,
+    timestamp: DateTime<Utc>,
+    inside: bool,
+}
+
+/// Finds the boundary crossings in samples ordered by aircraft and time
+///
+/// The first sample of each aircraft only sets its initial side, an
+///  aircraft already inside at the start of the range has no entry.
+fn crossings_from_samples(samples: &[ZoneSample]) -> Vec<ZoneCrossing> {
+    samples
+        .windows(2)
+        .filter(|pair| {
+            pair[0].aircraft_identifier == pair[1].aircraft_identifier
+                && pair[0].inside != pair[1].inside
+        })
+        .map(|pair| ZoneCrossing {
+            aircraft_identifier: pair[1].aircraft_identifier.clone(),
+            kind: match pair[1].inside {
+                true => CrossingKind::Entry,
+                false => CrossingKind::Exit,
+            },
+            timestamp: pair[1].timestamp,
+        })
+        .collect()
+}
+
+/// Gets the aircraft that entered or left a zone between two times
+///
+/// Uses the aircraft position history. A position is inside the zone if
+///  it is within the zone footprint and altitude band while the zone is
+///  active. Crossings are ordered by aircraft, then time. Deleted zones
+///  can still be audited.
+pub async fn zone_crossings(
+    zone_identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    pool: &deadpool_postgres::Pool,
+) -> Result<Vec<ZoneCrossing>, ZoneError> {
+    postgis_debug!("(zone_crossings) entry, zone: '{zone_identifier}'.");
+    if let Err(e) = super::utils::check_string(zone_identifier, IDENTIFIER_REGEX) {
+        postgis_error!(
+            "(zone_crossings) invalid identifier {}: {}",
+            zone_identifier,
+            e
+        );
+        return Err(ZoneError::Identifier);
+    }
+
+    if time_end <= time_start {
+        postgis_error!("(zone_crossings) time_end must be after time_start.");
+        return Err(ZoneError::TimeOrder);
+    }
+
+    let client = pool.get().await.map_err(|e| {
+        postgis_error!(
+            "(zone_crossings) could not get client from psql connection pool: {}",
+            e
+        );
+        ZoneError::Client
+    })?;
+
+    let zone_stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT 1 FROM {table_name} WHERE "identifier" = $1;"#,
+            table_name = get_table_name()
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(zone_crossings) could not prepare cached statement: {}", e);
+            ZoneError::DBError
+        })?;
+
+    let zone = client
+        .query_opt(&zone_stmt, &[&zone_identifier])
+        .await
+        .map_err(|e| {
+            postgis_error!("(zone_crossings) could not execute statement: {}", e);
+            ZoneError::DBError
+        })?;
+
+    if zone.is_none() {
+        postgis_warn!("(zone_crossings) no zone '{zone_identifier}'.");
+        return Err(ZoneError::NotFound);
+    }
+
+    let stmt = client
+        .prepare_cached(&format!(
+            r#"SELECT
+                "history"."identifier",
+                "history"."timestamp_network",
+                (
+                    ST_Intersects(ST_Force2D("zones"."geom"), ST_Force2D("history"."geom"))
+                    AND ST_Z("history"."geom") >= "zones"."altitude_meters_min"
+                    AND ST_Z("history"."geom") <= "zones"."altitude_meters_max"
+                    AND ("zones"."time_start" IS NULL OR "zones"."time_start" <= "history"."timestamp_network")
+                    AND ("zones"."time_end" IS NULL OR "zones"."time_end" >= "history"."timestamp_network")
+                ) AS "inside"
+            FROM {history_table_name} AS "history"
+            JOIN {table_name} AS "zones" ON "zones"."identifier" = $1
+            WHERE "history"."timestamp_network" >= $2
+                AND "history"."timestamp_network" <= $3
+                AND NOT "history"."outlier"
+            ORDER BY "history"."identifier", "history"."timestamp_network"
+            LIMIT $4;"#,
+            table_name = get_table_name(),
+            history_table_name = super::aircraft::get_history_table_name(),
+        ))
+        .await
+        .map_err(|e| {
+            postgis_error!("(zone_crossings) could not prepare cached statement: {}", e);
+            ZoneError::DBError
+        })?;
+
+    let rows = client
+        .query(
+            &stmt,
+            &[
+                &zone_identifier,
+                &time_start,
+                &time_end,
+                &MAX_ZONE_CROSSING_SAMPLES,
+            ],
+        )
+        .await
+        .map_err(|e| {
+            postgis_error!("(zone_crossings) could not execute statement: {}", e);
+            ZoneError::DBError
+        })?;
+
+    if rows.len() as i64 >= MAX_ZONE_CROSSING_SAMPLES {
+        postgis_warn!(
+            "(zone_crossings) reached the limit of {} positions, narrow the time range.",
+            MAX_ZONE_CROSSING_SAMPLES
+        );
+    }
+
+    let samples = rows
+        .into_iter()
+        .map(|row| {
+            Ok(ZoneSample {
+                aircraft_identifier: row.try_get("identifier")?,
+                timestamp: row.try_get("timestamp_network")?,
+                inside: row.try_get("inside")?,
+            })
+        })
+        .collect::<Result<Vec<ZoneSample>, tokio_postgres::error::Error>>()
+        .map_err(|e| {
+            postgis_error!("(zone_crossings) could not parse row: {}", e);
+            ZoneError::DBError
+        })?;
+
+    let crossings = crossings_from_samples(&samples);
+    postgis_debug!(
+        "(zone_crossings) found {} crossings, zone: '{zone_identifier}'.",
+        crossings.len()
+    );
+
+    Ok(crossings)
+}
+
+/// Prepares a statement that checks zone intersections with the provided geometry
+///
+/// Only zones matching the tag filter (tags in $6) are checked.
+pub async fn get_zone_intersection_stmt(
+    client: &Object,
+    tag_filter: &TagFilter,
+) -> Result<tokio_postgres::Statement, PostgisError> {
+    let result = client
+        .prepare_cached(&format!(
+            r#"
+            SELECT
+                "identifier",
+                "geom",
+                "zone_type",
+                "altitude_meters_min",
+                "altitude_meters_max",
+                "time_start",
+                "time_end"
+            FROM {table_name}
+            WHERE
+                ST_3DIntersects("geom", $1::GEOMETRY(LINESTRINGZ, {DEFAULT_SRID}))
+                AND ("time_start" <= $3 OR "time_start" IS NULL)
+                AND ("time_end" >= $2 OR "time_end" IS NULL)
+                AND "identifier" NOT IN ($4, $5)
+                AND "deleted_at" IS NULL
+                AND {tag_condition}
+            LIMIT 1;
+        "#,
+            table_name = get_table_name(),
+            tag_condition = tag_filter.condition(r#""tags""#, 6),
+        ))
+        .await;
+
+    match result {
+        Ok(stmt) => Ok(stmt),
+        Err(e) => {
+            postgis_error!(
+                "(get_zone_intersection_stmt) could not prepare cached statement: {}",
+                e
+            );
+            Err(PostgisError::Zone(ZoneError::DBError))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::server::grpc_server::Coordinates;
+    use crate::postgis::utils;
+    use crate::postgis::wkt::WktError;
+
+    fn square(latitude: f64, longitude: f64) -> Vec<(f64, f64)> {
+        vec![
+            (latitude - 0.0001, longitude - 0.0001),
+            (latitude + 0.0001, longitude - 0.0001),
+            (latitude + 0.0001, longitude + 0.0001),
+            (latitude - 0.0001, longitude + 0.0001),
+            (latitude - 0.0001, longitude - 0.0001),
+        ]
+    }
+
+    #[test]
+    fn ut_crossings_from_samples() {
+        let start = Utc::now();
+        let sample = |identifier: &str, secs: i64, inside: bool| ZoneSample {
+            aircraft_identifier: identifier.to_string(),
+            timestamp: start + chrono::Duration::try_seconds(secs).unwrap(),
+            inside,
+        };
+
+        // A1 flies through the zone, A2 starts inside and leaves,
+        //  A3 never enters
+        let samples = vec![
+            sample("A1", 0, false),
+            sample("A1", 10, false),
+            sample("A1", 20, true),
+            sample("A1", 30, true),
+            sample("A1", 40, false),
+            sample("A2", 0, true),
+            sample("A2", 10, false),
+            sample("A3", 0, false),
+            sample("A3", 10, false),
+        ];
+
+        let crossings = crossings_from_samples(&samples);
+        assert_eq!(
+            crossings,
+            vec![
+                ZoneCrossing {
+                    aircraft_identifier: "A1".to_string(),
+                    kind: CrossingKind::Entry,
+                    timestamp: samples[2].timestamp,
+                },
+                ZoneCrossing {
+                    aircraft_identifier: "A1".to_string(),
+                    kind: CrossingKind::Exit,
+                    timestamp: samples[4].timestamp,
+                },
+                ZoneCrossing {
+                    aircraft_identifier: "A2".to_string(),
+                    kind: CrossingKind::Exit,
+                    timestamp: samples[6].timestamp,
+                },
+            ]
+        );
+
+        // The change of aircraft between A2 (outside) and A3 is not a crossing
+        let samples = vec![sample("A2", 0, true), sample("A3", 0, false)];
+        assert!(crossings_from_samples(&samples).is_empty());
+        assert!(crossings_from_samples(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn ut_zone_crossings_invalid() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_zone_crossings_invalid) start");
+
+        // Never connects, validation fails first
+        let pool = deadpool_postgres::Config::new()
+            .create_pool(None, tokio_postgres::NoTls)
+            .unwrap();
+
+        let time_start = Utc::now();
+        let time_end = time_start + chrono::Duration::try_hours(1).unwrap();
+
+        let result = zone_crossings("zone;", time_start, time_end, &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, ZoneError::Identifier);
+
+        let result = zone_crossings("zone", time_end, time_start, &pool)
+            .await
+            .unwrap_err();
+        assert_eq!(result, ZoneError::TimeOrder);
+
+        ut_info!("(ut_zone_crossings_invalid) success");
+    }
+
+    #[tokio::test]
+    async fn ut_soft_delete_client_failure() {
+        crate::get_log_handle().await;
+        ut_info!("(ut_soft_delete_client_failure) start");
+
+        let result = delete_zone("NFZ;").await.unwrap_err();
+        assert_eq!(result, ZoneError::Identifier);
+
+        let result = restore_zone("NFZ;", 60).await.unwrap_err();
+        assert_eq!(result, ZoneError::Identifier);
+
+        let result = delete_zone("NFZ").await.unwrap_err();
+        assert_eq!(result, ZoneError::Client);
+
+        let result = restore_zone("NFZ", 60).await.unwrap_err();
+        assert_eq!(result, ZoneError::Client);
+
+        let result = purge_zones(60).await.unwrap_err();
+        assert_eq!(result, ZoneError::Client);
+
+        ut_info!("(ut_soft_delete_client_failure) success");
+    }
+
+    #[test]
+    fn ut_request_valid() {
+        let nodes: Vec<(&str, Vec<(f64, f64)>, f32, f32)> = vec![
+            ("NFZ_A", square(52.3745905, 4.9160036), 102.0, 200.0),
+            ("NFZ_B", square(52.3749819, 4.9156925), 20.5, 120.0),
+            ("NFZ_C", square(52.3752144, 4.9153733), 22.0, 100.0),
+        ];
+
+        let zones: Vec<RequestZone> = nodes
+            .iter()
+            .map(
+                |(identifier, points, altitude_min, altitude_max)| RequestZone {
+                    identifier: identifier.to_string(),
+                    vertices: points
+                        .iter()
+                        .map(|(latitude, longitude)| Coordinates {
+                            latitude: *latitude,
+                            longitude: *longitude,
+                        })
+                        .collect(),
+                    altitude_meters_min: *altitude_min,
+                    altitude_meters_max: *altitude_max,
+                    ..Default::default()
+                },
+            )
+            .collect();
+
+        let converted = zones
+            .clone()
+            .into_iter()
+            .map(Zone::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(zones.len(), converted.len());
+
+        for (i, nfz) in zones.iter().enumerate() {
+            assert_eq!(nfz.identifier, converted[i].identifier);
+            assert_eq!(
+                utils::polygon_from_vertices_z(&nfz.vertices, nfz.altitude_meters_min).unwrap(),
+                converted[i].geom
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn ut_zone_geometry_issues_local() {
+        let vertex = |latitude: f64, longitude: f64| Coordinates {
+            latitude,
+            longitude,
+        };
+
+        let valid = RequestZone {
+            identifier: "NFZ_VALID".to_string(),
+            vertices: square(52.3745905, 4.9160036)
+                .into_iter()
+                .map(|(latitude, longitude)| vertex(latitude, longitude))
+                .collect(),
+            ..Default::default()
+        };
+
+        let bow_tie = RequestZone {
+            identifier: "NFZ_BOW_TIE".to_string(),
+            vertices: vec![
+                vertex(52.374, 4.914),
+                vertex(52.376, 4.916),
+                vertex(52.376, 4.914),
+                vertex(52.374, 4.916),
+                vertex(52.374, 4.914),
+            ],
+            ..Default::default()
+        };
+
+        // Local issues are reported without a database
+        let issues = zone_geometry_issues(&[valid, bow_tie]).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].identifier, "NFZ_BOW_TIE");
+        assert!(issues[0].reason.contains("Self-intersection"));
+        assert!(issues[0]
+            .to_string()
+            .starts_with("zone 'NFZ_BOW_TIE': Self-intersection["));
+
+        let location = issues[0].location.clone().unwrap();
+        assert!((location.latitude - 52.375).abs() < 1e-9);
+        assert!((location.longitude - 4.915).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn ut_client_failure() {
+        let nodes: Vec<(&str, Vec<(f64, f64)>)> = vec![("NFZ", square(52.3745905, 4.9160036))];
+        let zone: Vec<RequestZone> = nodes
+            .iter()
+            .map(|(identifier, points)| RequestZone {
+                identifier: identifier.to_string(),
+                vertices: points
+                    .iter()
+                    .map(|(latitude, longitude)| Coordinates {
+                        latitude: *latitude,
+                        longitude: *longitude,
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .collect();
+
+        let result = update_zones(zone, false).await.unwrap_err();
+        assert_eq!(result, ZoneError::Client);
     }
 
     #[tokio::test]
@@ -419,15 +1579,154 @@ mod tests {
                 ..Default::default()
             }];
 
-            let result = update_zones(zones).await.unwrap_err();
+            let result = update_zones(zones, false).await.unwrap_err();
             assert_eq!(result, ZoneError::Identifier);
         }
     }
 
+    #[test]
+    fn ut_zone_request_provenance() {
+        let zone = RequestZone {
+            identifier: "Nofly_zone".to_string(),
+            vertices: square(52.3745905, 4.9160036)
+                .iter()
+                .map(|(latitude, longitude)| Coordinates {
+                    latitude: *latitude,
+                    longitude: *longitude,
+                })
+                .collect(),
+            source: Some("LVNL".to_string()),
+            external_reference: Some("A1234/24".to_string()),
+            ..Default::default()
+        };
+
+        let converted = Zone::try_from(zone.clone()).unwrap();
+        assert_eq!(converted.source, Some("LVNL".to_string()));
+        assert_eq!(converted.external_reference, Some("A1234/24".to_string()));
+
+        let invalid = RequestZone {
+            source: Some("'LVNL'".to_string()),
+            ..zone.clone()
+        };
+        assert_eq!(Zone::try_from(invalid).unwrap_err(), ZoneError::Source);
+
+        let invalid = RequestZone {
+            external_reference: Some("A1234/24;".to_string()),
+            ..zone
+        };
+        assert_eq!(
+            Zone::try_from(invalid).unwrap_err(),
+            ZoneError::ExternalReference
+        );
+    }
+
+    #[test]
+    fn ut_resolve_zone_geometry() {
+        let vertices: Vec<Coordinates> = square(52.3745905, 4.9160036)
+            .iter()
+            .map(|(latitude, longitude)| Coordinates {
+                latitude: *latitude,
+                longitude: *longitude,
+            })
+            .collect();
+
+        let wkt = format!(
+            "POLYGON (({}))",
+            vertices
+                .iter()
+                .map(|v| format!("{} {}", v.longitude, v.latitude))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let zone = RequestZone {
+            identifier: "Nofly_zone".to_string(),
+            geom_wkt: Some(wkt),
+            ..Default::default()
+        };
+
+        let resolved = resolve_zone_geometry(zone.clone()).unwrap();
+        assert_eq!(resolved.vertices, vertices);
+        assert_eq!(resolved.geom_wkt, None);
+        assert!(Zone::try_from(zone.clone()).is_ok());
+
+        // Without a geometry the zone is untouched
+        let plain = RequestZone {
+            vertices: vertices.clone(),
+            geom_wkt: None,
+            ..zone.clone()
+        };
+        assert_eq!(resolve_zone_geometry(plain.clone()).unwrap(), plain);
+
+        let invalid = RequestZone {
+            vertices,
+            ..zone.clone()
+        };
+        assert_eq!(
+            Zone::try_from(invalid).unwrap_err(),
+            ZoneError::Geometry(GeometryInputError::Conflict)
+        );
+
+        let invalid = RequestZone {
+            geom_wkt: Some("POINT (4.9 52.3)".to_string()),
+            ..zone
+        };
+        assert_eq!(
+            Zone::try_from(invalid).unwrap_err(),
+            ZoneError::Geometry(GeometryInputError::Wkt(WktError::GeometryType, 0))
+        );
+    }
+
+    #[test]
+    fn ut_zone_request_tags() {
+        let zone = RequestZone {
+            identifier: "Nofly_zone".to_string(),
+            vertices: square(52.3745905, 4.9160036)
+                .iter()
+                .map(|(latitude, longitude)| Coordinates {
+                    latitude: *latitude,
+                    longitude: *longitude,
+                })
+                .collect(),
+            tags: vec!["exercise-redwing".to_string()],
+            ..Default::default()
+        };
+
+        let converted = Zone::try_from(zone.clone()).unwrap();
+        assert_eq!(converted.tags, vec!["exercise-redwing".to_string()]);
+
+        let invalid = RequestZone {
+            tags: vec!["exercise redwing".to_string()],
+            ..zone.clone()
+        };
+        assert_eq!(Zone::try_from(invalid).unwrap_err(), ZoneError::Tags);
+
+        let invalid = RequestZone {
+            tags: (0..=crate::postgis::tags::MAX_TAGS)
+                .map(|i| format!("tag{i}"))
+                .collect(),
+            ..zone
+        };
+        assert_eq!(Zone::try_from(invalid).unwrap_err(), ZoneError::Tags);
+    }
+
+    #[tokio::test]
+    async fn ut_remove_zones_by_source_client_failure() {
+        let result = remove_zones_by_source("'LVNL'").await.unwrap_err();
+        assert_eq!(result, ZoneError::Source);
+
+        let result = remove_zones_by_source("LVNL").await.unwrap_err();
+        assert_eq!(result, ZoneError::Client);
+    }
+
     #[tokio::test]
     async fn ut_zone_request_to_gis_invalid_no_nodes() {
         let zones: Vec<RequestZone> = vec![];
-        let result = update_zones(zones).await.unwrap_err();
+        let result = update_zones(zones, false).await.unwrap_err();
+        assert_eq!(result, ZoneError::NoZones);
+
+        // Dry runs are validated the same way
+        let result = update_zones(vec![], true).await.unwrap_err();
         assert_eq!(result, ZoneError::NoZones);
     }
 
@@ -453,7 +1752,7 @@ mod tests {
                 ..Default::default()
             }];
 
-            let result = update_zones(zones).await.unwrap_err();
+            let result = update_zones(zones, false).await.unwrap_err();
             assert_eq!(result, ZoneError::Location);
         }
 
@@ -484,7 +1783,7 @@ mod tests {
                 ..Default::default()
             }];
 
-            let result = update_zones(zones).await.unwrap_err();
+            let result = update_zones(zones, false).await.unwrap_err();
             assert_eq!(result, ZoneError::Location);
         }
     }
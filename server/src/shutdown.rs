@@ -0,0 +1,175 @@
+//! Graceful shutdown
+//!
+//! Once shutdown begins the gRPC server stops accepting requests and the
+//!  Redis consumers stop popping messages. Database transactions already
+//!  running are given a grace period to finish before the pools are closed.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Interval between checks for remaining in-flight transactions
+const DRAIN_POLL_INTERVAL_MS: u64 = 50;
+
+/// Set once shutdown has begun
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Database transactions currently running
+pub static IN_FLIGHT: InFlight = InFlight::new();
+
+/// Marks the service as shutting down
+pub fn begin() {
+    SHUTTING_DOWN.store(true, Ordering::SeqCst);
+}
+
+/// Returns true once shutdown has begun, new work should not be started
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Counter of in-flight work
+#[derive(Debug, Default)]
+pub struct InFlight {
+    count: AtomicUsize,
+}
+
+/// Counts as in-flight work until dropped
+#[derive(Debug)]
+pub struct InFlightGuard<'a> {
+    tracker: &'a InFlight,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl InFlight {
+    /// Create a new counter with no work in flight
+    pub const fn new() -> Self {
+        InFlight {
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Tracks work until the returned guard is dropped
+    pub fn track(&self) -> InFlightGuard<'_> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard { tracker: self }
+    }
+
+    /// Number of tracked work items still running
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Waits for all tracked work to finish
+    ///
+    /// Returns false if work is still running after the grace period.
+    pub async fn drain(&self, grace_period: Duration) -> bool {
+        let poll = async {
+            while self.count() > 0 {
+                tokio::time::sleep(Duration::from_millis(DRAIN_POLL_INTERVAL_MS)).await;
+            }
+        };
+
+        tokio::time::timeout(grace_period, poll).await.is_ok()
+    }
+}
+
+/// Stops new work, waits for in-flight transactions and closes the
+///  PostgreSQL pools
+#[cfg(not(tarpaulin_include))]
+pub async fn shutdown(grace_period_secs: u64) {
+    begin();
+
+    let remaining = IN_FLIGHT.count();
+    if remaining > 0 {
+        log::info!(
+            "(shutdown) waiting up to {}s for {} in-flight transactions.",
+            grace_period_secs,
+            remaining
+        );
+    }
+
+    if !IN_FLIGHT
+        .drain(Duration::from_secs(grace_period_secs))
+        .await
+    {
+        log::warn!(
+            "(shutdown) {} transactions still running after the grace period.",
+            IN_FLIGHT.count()
+        );
+    }
+
+    if let Some(pool) = crate::postgis::DEADPOOL_POSTGIS.get() {
+        pool.close();
+    }
+
+    if let Some(pool) = crate::postgis::DEADPOOL_POSTGIS_TELEMETRY.get() {
+        pool.close();
+    }
+
+    for pool in crate::postgis::shard::get_extra_shard_pools() {
+        pool.close();
+    }
+
+    log::info!("(shutdown) database pools closed.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ut_drain_completes_slow_transaction() {
+        static TRACKER: InFlight = InFlight::new();
+        static COMPLETED: AtomicBool = AtomicBool::new(false);
+
+        // Slow transaction started before shutdown
+        let guard = TRACKER.track();
+        let handle = tokio::spawn(async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            COMPLETED.store(true, Ordering::SeqCst);
+        });
+
+        assert_eq!(TRACKER.count(), 1);
+        assert!(TRACKER.drain(Duration::from_secs(5)).await);
+        assert!(COMPLETED.load(Ordering::SeqCst));
+        assert_eq!(TRACKER.count(), 0);
+
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn ut_drain_grace_period_exceeded() {
+        static TRACKER: InFlight = InFlight::new();
+
+        let guard = TRACKER.track();
+        let handle = tokio::spawn(async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        });
+
+        assert!(!TRACKER.drain(Duration::from_millis(100)).await);
+        assert_eq!(TRACKER.count(), 1);
+
+        handle.await.unwrap();
+        assert_eq!(TRACKER.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn ut_drain_idle() {
+        let tracker = InFlight::new();
+        assert!(tracker.drain(Duration::from_millis(10)).await);
+
+        {
+            let _guard = tracker.track();
+            let _guard2 = tracker.track();
+            assert_eq!(tracker.count(), 2);
+        }
+
+        assert_eq!(tracker.count(), 0);
+    }
+}
@@ -0,0 +1,221 @@
+//! Aircraft spread over two shards against a live database
+//!
+//! The extra shard is a database of its own, provisioned like the primary
+//!  (with the schema and the PostGIS extension) and named in
+//!  `PG_AIRCRAFT_SHARDS`. Each aircraft is only stored on its shard, so
+//!  reads joining aircraft with flights of the primary must fan out.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, GetFlightsRequest, NodeType, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::{aircraft, compliance, flight, nearby, shard, PSQL_SCHEMA};
+use svc_gis::types::{AircraftTelemetry, AircraftType, Position};
+
+const LATITUDE: f64 = 52.4045905;
+const LONGITUDE: f64 = 4.9160036;
+
+fn telemetry(identifier: &str, longitude: f64) -> AircraftTelemetry {
+    AircraftTelemetry {
+        identifier: identifier.to_string(),
+        position: Position {
+            latitude: LATITUDE,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        velocity_horizontal_ground_mps: 10.0,
+        velocity_horizontal_air_mps: None,
+        velocity_vertical_mps: 0.0,
+        track_angle_degrees: 90.0,
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+    }
+}
+
+/// Counts the rows of an aircraft in the aircraft table of a database
+async fn count(pool: &deadpool_postgres::Pool, identifier: &str) -> i64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."aircraft" WHERE "identifier" = $1;"#),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count aircraft")
+        .get(0)
+}
+
+/// Aircraft on either shard are written to their shard only, and found
+///  exactly once by the reads joining them with flights of the primary
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`), with one extra shard database in `PG_AIRCRAFT_SHARDS`:
+///  `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_aircraft_shards() {
+    let config = svc_gis::Config::try_from_env().expect("could not load config");
+    let shards = svc_gis::postgis::pool::create_shard_pools(config.clone());
+    assert_eq!(shards.len(), 1, "PG_AIRCRAFT_SHARDS must name one database");
+    let shard_pool = shards[0].clone();
+    shard::DEADPOOL_POSTGIS_SHARDS
+        .set(shards)
+        .expect("could not set shard pools");
+
+    let (config, pool) = common::setup_with(config).await;
+    assert!(shard::is_sharded());
+
+    // One aircraft on the primary, a flying and a loose one on the shard
+    let suffix = common::unique_suffix();
+    let identifiers: Vec<String> = [0, 1, 1]
+        .iter()
+        .enumerate()
+        .map(|(index, shard_index)| {
+            (0..)
+                .map(|attempt| format!("s{index}{attempt}-{suffix}"))
+                .find(|identifier| shard::shard_index(identifier, 2) == *shard_index)
+                .unwrap()
+        })
+        .collect();
+    let longitudes = [LONGITUDE, LONGITUDE + 0.001, LONGITUDE + 0.0015];
+
+    aircraft::update_aircraft_telemetry(
+        identifiers
+            .iter()
+            .zip(longitudes)
+            .map(|(identifier, longitude)| telemetry(identifier, longitude))
+            .collect(),
+        false,
+    )
+    .await
+    .expect("telemetry update failed");
+
+    for ((identifier, longitude), shard_index) in identifiers.iter().zip(longitudes).zip([0, 1, 1])
+    {
+        let geom = aircraft::get_aircraft_pointz(identifier)
+            .await
+            .expect("could not get aircraft position");
+        assert!((geom.x - longitude).abs() < 1e-6);
+
+        // Stored on its own shard only
+        let counts = [
+            count(&pool, identifier).await,
+            count(&shard_pool, identifier).await,
+        ];
+        let mut expected = [0, 0];
+        expected[shard_index] = 1;
+        assert_eq!(counts, expected, "{identifier}");
+    }
+
+    // Nearest aircraft
+    let nodes = nearby::get_nodes_near(
+        &Coordinates {
+            latitude: LATITUDE,
+            longitude: LONGITUDE,
+        },
+        500.0,
+        &pool,
+    )
+    .await
+    .expect("could not get nearby nodes");
+    for identifier in &identifiers {
+        assert_eq!(
+            nodes
+                .iter()
+                .filter(
+                    |node| node.node_type == NodeType::Aircraft && &node.identifier == identifier
+                )
+                .count(),
+            1
+        );
+    }
+
+    // A flight of the primary flown by an aircraft of the shard
+    let now = Utc::now();
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifiers[1].clone()),
+            aircraft_identifier: Some(identifiers[1].clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: [LONGITUDE, LONGITUDE + 0.002]
+                .iter()
+                .map(|longitude| PointZ {
+                    latitude: LATITUDE,
+                    longitude: *longitude,
+                    altitude_meters: 100.0,
+                })
+                .collect(),
+            timestamp_start: Some((now - Duration::try_minutes(5).unwrap()).into()),
+            timestamp_end: Some((now + Duration::try_minutes(25).unwrap()).into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    let response = flight::get_flights_until(
+        GetFlightsRequest {
+            window_min_x: LONGITUDE - 0.001,
+            window_min_y: LATITUDE - 0.001,
+            window_max_x: LONGITUDE + 0.003,
+            window_max_y: LATITUDE + 0.001,
+            time_start: Some((now - Duration::try_minutes(1).unwrap()).into()),
+            time_end: Some((now + Duration::try_minutes(1).unwrap()).into()),
+            ..Default::default()
+        },
+        None,
+    )
+    .await
+    .expect("could not get flights");
+
+    let flights: Vec<_> = response
+        .flights
+        .iter()
+        .filter(|flight| flight.session_id.as_deref() == Some(identifiers[1].as_str()))
+        .collect();
+    assert_eq!(flights.len(), 1);
+    assert!(flights[0].state.is_some());
+
+    // Aircraft without a flight in the window, on either shard
+    for identifier in [&identifiers[0], &identifiers[2]] {
+        assert_eq!(
+            response
+                .flights
+                .iter()
+                .filter(|flight| flight.aircraft_id.as_ref() == Some(identifier))
+                .count(),
+            1,
+            "{identifier}"
+        );
+    }
+
+    let states = flight::get_states_for_flights(vec![identifiers[1].clone()], &pool)
+        .await
+        .expect("could not get states");
+    assert!(states.contains_key(&identifiers[1]));
+
+    // Flying without a flight, the aircraft of the flight is bound
+    let unbound = compliance::find_unbound_aircraft(Utc::now(), true)
+        .await
+        .expect("could not find unbound aircraft");
+    let unbound = |identifier: &String| {
+        unbound
+            .iter()
+            .any(|aircraft| &aircraft.identifier == identifier)
+    };
+    assert!(unbound(&identifiers[0]));
+    assert!(!unbound(&identifiers[1]));
+    assert!(unbound(&identifiers[2]));
+
+    assert!(aircraft::get_latest_position_update()
+        .await
+        .expect("could not get latest position update")
+        .is_some());
+
+    flight::delete_flight(&identifiers[1], None)
+        .await
+        .expect("could not delete flight");
+}
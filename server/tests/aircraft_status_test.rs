@@ -0,0 +1,68 @@
+//! Batched aircraft status updates and their history against a live database
+
+mod common;
+
+use svc_gis::postgis::aircraft;
+use svc_gis::types::OperationalStatus;
+
+/// A batch sets the status of every aircraft in one statement and records
+///  only actual changes in the history
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_aircraft_status_batch() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let first = format!("as-{suffix}-1");
+    let second = format!("as-{suffix}-2");
+
+    let changes = aircraft::update_aircraft_status_batch(
+        vec![
+            (first.clone(), OperationalStatus::Ground),
+            (second.clone(), OperationalStatus::Ground),
+        ],
+        &pool,
+    )
+    .await
+    .expect("could not update statuses");
+    assert_eq!(changes, 2);
+
+    // Only the first aircraft changes
+    let changes = aircraft::update_aircraft_status_batch(
+        vec![
+            (first.clone(), OperationalStatus::Airborne),
+            (second.clone(), OperationalStatus::Ground),
+        ],
+        &pool,
+    )
+    .await
+    .expect("could not update statuses");
+    assert_eq!(changes, 1);
+
+    let history = aircraft::get_status_history(&first, &pool)
+        .await
+        .expect("could not get history");
+    assert_eq!(history.len(), 2);
+    assert!(history[0].previous_status.is_none());
+    assert_eq!(
+        history[0].status.to_string(),
+        OperationalStatus::Ground.to_string()
+    );
+    assert_eq!(
+        history[1].previous_status.map(|status| status.to_string()),
+        Some(OperationalStatus::Ground.to_string())
+    );
+    assert_eq!(
+        history[1].status.to_string(),
+        OperationalStatus::Airborne.to_string()
+    );
+    assert!(history[0].timestamp <= history[1].timestamp);
+
+    let history = aircraft::get_status_history(&second, &pool)
+        .await
+        .expect("could not get history");
+    assert_eq!(history.len(), 1);
+}
@@ -0,0 +1,89 @@
+//! Downsampling of aircraft tracks against a live database
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use svc_gis::grpc::server::grpc_server::GetAircraftTrackRequest;
+use svc_gis::postgis::{aircraft, PSQL_SCHEMA};
+
+/// Gets the timestamps of the track of an aircraft between two times
+async fn track(identifier: &str, time_start: i64, time_end: i64, resolution: u32) -> Vec<i64> {
+    let response = aircraft::get_aircraft_track(GetAircraftTrackRequest {
+        identifier: identifier.to_string(),
+        time_start: Some(
+            DateTime::<Utc>::from_timestamp(time_start, 0)
+                .unwrap()
+                .into(),
+        ),
+        time_end: Some(DateTime::<Utc>::from_timestamp(time_end, 0).unwrap().into()),
+        resolution_seconds: resolution,
+        ..Default::default()
+    })
+    .await
+    .expect("could not get track");
+
+    assert_eq!(response.point_count as usize, response.positions.len());
+    response
+        .positions
+        .iter()
+        .map(|position| {
+            let timestamp: DateTime<Utc> = position.timestamp.clone().unwrap().into();
+            timestamp.timestamp()
+        })
+        .collect()
+}
+
+/// Keeps the first point of each bucket aligned to the epoch and the last
+///  point of the track
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_downsample_track() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("tr-{}", common::unique_suffix());
+    let timestamps: Vec<i64> = vec![1000, 1001, 1005, 1010, 1011, 1019, 1021, 1022];
+
+    let client = pool.get().await.expect("could not get client");
+    for timestamp in &timestamps {
+        client
+            .execute(
+                &format!(
+                    r#"INSERT INTO "{PSQL_SCHEMA}"."aircraft_history" (
+                        "identifier", "geom", "timestamp_network", "velocity_horizontal_ground_mps"
+                    ) VALUES (
+                        $1,
+                        ST_SetSRID(ST_MakePoint(4.9160036, 52.3745905, 100), 4326),
+                        to_timestamp($2::FLOAT8),
+                        10.0
+                    );"#
+                ),
+                &[&identifier, &(*timestamp as f64)],
+            )
+            .await
+            .expect("could not insert history");
+    }
+
+    // Raw data when resolution is 0
+    assert_eq!(track(&identifier, 1000, 1022, 0).await, timestamps);
+
+    // Buckets aligned to the epoch: [1000, 1010), [1010, 1020), [1020, 1030)
+    assert_eq!(
+        track(&identifier, 1000, 1022, 10).await,
+        vec![1000, 1010, 1021, 1022]
+    );
+
+    // First and last points always preserved
+    assert_eq!(track(&identifier, 1000, 1022, 3600).await, vec![1000, 1022]);
+
+    // Boundaries don't shift when the window starts later
+    assert_eq!(
+        track(&identifier, 1005, 1022, 10).await,
+        vec![1005, 1010, 1021, 1022]
+    );
+
+    // No points, no buckets
+    assert!(track(&identifier, 2000, 3000, 10).await.is_empty());
+}
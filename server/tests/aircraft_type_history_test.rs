@@ -0,0 +1,76 @@
+//! Aircraft type change history against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::postgis::aircraft;
+use svc_gis::types::{AircraftId, AircraftType};
+
+fn identification(identifier: &str, aircraft_type: AircraftType, seconds_ago: i64) -> AircraftId {
+    AircraftId {
+        identifier: Some(identifier.to_string()),
+        session_id: None,
+        aircraft_type,
+        operator_id: None,
+        timestamp_network: Utc::now() - Duration::try_seconds(seconds_ago).unwrap(),
+        timestamp_asset: None,
+        force_type: false,
+    }
+}
+
+/// Identifying an aircraft with a different type records a change, with
+///  the same type it records nothing
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_aircraft_type_history() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("th{}", common::unique_suffix());
+
+    // Registering isn't a change
+    aircraft::update_aircraft_id(
+        vec![identification(&identifier, AircraftType::Rotorcraft, 30)],
+        false,
+    )
+    .await
+    .expect("could not identify aircraft");
+
+    // Same type
+    aircraft::update_aircraft_id(
+        vec![identification(&identifier, AircraftType::Rotorcraft, 20)],
+        false,
+    )
+    .await
+    .expect("could not identify aircraft");
+
+    let history = aircraft::get_type_history(&identifier, &pool)
+        .await
+        .expect("could not get history");
+    assert!(history.is_empty());
+
+    // Reclassified
+    let reclassification = identification(&identifier, AircraftType::Aeroplane, 10);
+    aircraft::update_aircraft_id(vec![reclassification.clone()], false)
+        .await
+        .expect("could not identify aircraft");
+
+    let history = aircraft::get_type_history(&identifier, &pool)
+        .await
+        .expect("could not get history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(
+        history[0].previous_type.to_string(),
+        AircraftType::Rotorcraft.to_string()
+    );
+    assert_eq!(
+        history[0].aircraft_type.to_string(),
+        AircraftType::Aeroplane.to_string()
+    );
+    assert_eq!(
+        history[0].timestamp.timestamp_micros(),
+        reclassification.timestamp_network.timestamp_micros()
+    );
+}
@@ -0,0 +1,99 @@
+//! Undeclared and incompatible aircraft type changes against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use std::sync::atomic::Ordering;
+use svc_gis::postgis::aircraft::{self, TYPE_TRANSITIONS_REJECTED, UNDECLARED_TYPES_IGNORED};
+use svc_gis::types::{AircraftId, AircraftType};
+
+fn identification(
+    identifier: &str,
+    aircraft_type: AircraftType,
+    seconds_ago: i64,
+    force_type: bool,
+) -> AircraftId {
+    AircraftId {
+        identifier: Some(identifier.to_string()),
+        session_id: None,
+        aircraft_type,
+        operator_id: None,
+        timestamp_network: Utc::now() - Duration::try_seconds(seconds_ago).unwrap(),
+        timestamp_asset: None,
+        force_type,
+    }
+}
+
+/// An undeclared type keeps the declared one and an incompatible type is
+///  skipped, both are applied when forced
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_aircraft_type_transition() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("tt{}", common::unique_suffix());
+    let identify = |aircraft_type, seconds_ago, force_type| {
+        aircraft::update_aircraft_id(
+            vec![identification(
+                &identifier,
+                aircraft_type,
+                seconds_ago,
+                force_type,
+            )],
+            false,
+        )
+    };
+    let types = || async {
+        aircraft::get_type_history(&identifier, &pool)
+            .await
+            .expect("could not get history")
+            .iter()
+            .map(|change| {
+                (
+                    change.previous_type.to_string(),
+                    change.aircraft_type.to_string(),
+                )
+            })
+            .collect::<Vec<(String, String)>>()
+    };
+
+    identify(AircraftType::Rotorcraft, 50, false)
+        .await
+        .expect("could not identify aircraft");
+
+    // Misconfigured feed, the type stays
+    let ignored = UNDECLARED_TYPES_IGNORED.load(Ordering::Relaxed);
+    identify(AircraftType::Undeclared, 40, false)
+        .await
+        .expect("could not identify aircraft");
+    assert!(types().await.is_empty());
+    assert!(UNDECLARED_TYPES_IGNORED.load(Ordering::Relaxed) > ignored);
+
+    // Reused identifier, skipped
+    let rejected = TYPE_TRANSITIONS_REJECTED.load(Ordering::Relaxed);
+    identify(AircraftType::Aeroplane, 30, false)
+        .await
+        .expect("could not identify aircraft");
+    assert!(types().await.is_empty());
+    assert!(TYPE_TRANSITIONS_REJECTED.load(Ordering::Relaxed) > rejected);
+
+    // Forced
+    identify(AircraftType::Aeroplane, 20, true)
+        .await
+        .expect("could not identify aircraft");
+    identify(AircraftType::Undeclared, 10, true)
+        .await
+        .expect("could not identify aircraft");
+
+    let pair = |from: AircraftType, to: AircraftType| (from.to_string(), to.to_string());
+    assert_eq!(
+        types().await,
+        vec![
+            pair(AircraftType::Rotorcraft, AircraftType::Aeroplane),
+            pair(AircraftType::Aeroplane, AircraftType::Undeclared),
+        ]
+    );
+}
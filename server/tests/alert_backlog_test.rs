@@ -0,0 +1,78 @@
+//! Persisted compliance alerts against a live database
+
+mod common;
+
+use futures::{Stream, StreamExt};
+use svc_gis::grpc::server::grpc_server::ComplianceAlert;
+use svc_gis::postgis::backlog::{self, EventKind};
+use svc_gis::postgis::compliance;
+
+/// Time to wait for an alert on the stream
+const STREAM_TIMEOUT_MS: u64 = 5_000;
+
+/// Gets the next alert of a stream
+async fn next(stream: &mut (impl Stream<Item = ComplianceAlert> + Unpin)) -> ComplianceAlert {
+    tokio::time::timeout(
+        std::time::Duration::from_millis(STREAM_TIMEOUT_MS),
+        stream.next(),
+    )
+    .await
+    .expect("no alert received")
+    .expect("stream ended")
+}
+
+/// A subscriber reconnecting with its last event id receives the alerts it
+///  missed from the backlog, then live alerts, each once
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_alert_backlog_resume() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let alert = |label: &str| ComplianceAlert {
+        aircraft_identifier: format!("{label}-{suffix}"),
+        unbound_seconds: 60,
+        ..Default::default()
+    };
+
+    // Published while the subscriber was disconnected
+    let mut published = compliance::ALERTS.subscribe();
+    compliance::publish_alert(alert("seen")).await;
+    compliance::publish_alert(alert("missed-1")).await;
+    compliance::publish_alert(alert("missed-2")).await;
+
+    let mut ids = vec![];
+    for _ in 0..3 {
+        let alert = published.recv().await.expect("alert not published");
+        ids.push(alert.event_id);
+    }
+    assert!(ids[0] > 0 && ids[1] > ids[0] && ids[2] > ids[1]);
+
+    let events = backlog::get_events_after(EventKind::ComplianceAlert, ids[0], 2, &pool)
+        .await
+        .expect("could not read backlog");
+    assert_eq!(
+        events.iter().map(|event| event.id).collect::<Vec<u64>>(),
+        ids[1..].to_vec()
+    );
+
+    // Reconnects after the first alert
+    let mut stream = Box::pin(compliance::alert_stream(Some(ids[0])));
+
+    let received = next(&mut stream).await;
+    assert_eq!(received.aircraft_identifier, format!("missed-1-{suffix}"));
+    assert_eq!(received.event_id, ids[1]);
+
+    let received = next(&mut stream).await;
+    assert_eq!(received.aircraft_identifier, format!("missed-2-{suffix}"));
+    assert_eq!(received.event_id, ids[2]);
+
+    // Then live alerts
+    compliance::publish_alert(alert("live")).await;
+    let received = next(&mut stream).await;
+    assert_eq!(received.aircraft_identifier, format!("live-{suffix}"));
+    assert!(received.event_id > ids[2]);
+}
@@ -0,0 +1,84 @@
+//! Altitude band occupancy against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, GetAltitudeOccupancyRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::{flight, occupancy};
+use svc_gis::types::AircraftType;
+
+/// A level flight through the area occupies only the band of its altitude
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_altitude_occupancy() {
+    let (config, _) = common::setup().await;
+
+    // An area far from other tests so their flights aren't counted
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (-45.0 + (suffix % 1000) as f64 * 1e-3, 170.0);
+    let time_start = Utc::now() + Duration::try_hours(2).unwrap();
+    let time_end = time_start + Duration::try_minutes(20).unwrap();
+
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(format!("ao-{suffix}")),
+        aircraft_identifier: Some(format!("ao-{suffix}")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.0005,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some(time_end.into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let request = GetAltitudeOccupancyRequest {
+        vertices: [(-1., -1.), (-1., 2.), (1., 2.), (1., -1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: latitude + dy * 0.0005,
+                longitude: longitude + dx * 0.0005,
+            })
+            .collect(),
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 300.0,
+        band_height_meters: 30.0,
+    };
+
+    let bands = occupancy::get_altitude_occupancy(request)
+        .await
+        .expect("could not get occupancy")
+        .bands;
+
+    assert_eq!(bands.len(), 10);
+    for (i, band) in bands.iter().enumerate() {
+        assert_eq!(band.altitude_meters_min, i as f32 * 30.0);
+        assert_eq!(band.aircraft_count, 0);
+
+        // The path may be split into several segments
+        if i == 3 {
+            assert!(band.segment_count >= 1);
+        } else {
+            assert_eq!(band.segment_count, 0, "band {i}");
+        }
+    }
+}
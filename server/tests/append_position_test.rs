@@ -0,0 +1,120 @@
+//! Append-only aircraft position writes against a live database
+//!
+//! Kept in its own test binary so that the write mode can be set without
+//!  affecting other tests.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{Coordinates, GetFlightsRequest, NodeType};
+use svc_gis::postgis::aircraft::{self, PositionWriteMode, POSITION_WRITE_MODE};
+use svc_gis::postgis::{flight, nearby, PSQL_SCHEMA};
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Number of position updates sent for the aircraft
+const UPDATE_COUNT: usize = 5;
+
+const LATITUDE: f64 = 52.3745905;
+const LONGITUDE: f64 = 4.9160036;
+
+/// Every update is kept in the history and the latest position is read
+///  from the view, by the position lookup as by the readers of all aircraft
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_append_position_mode() {
+    POSITION_WRITE_MODE
+        .set(PositionWriteMode::Append)
+        .expect("could not set write mode");
+
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("ap{}", common::unique_suffix());
+    let start = Utc::now() - Duration::try_minutes(1).unwrap();
+    for i in 0..UPDATE_COUNT {
+        let item = AircraftPosition {
+            identifier: identifier.clone(),
+            position: Position {
+                latitude: LATITUDE + i as f64 * 1e-4,
+                longitude: LONGITUDE,
+                altitude_meters: 100.0 + i as f64,
+            },
+            timestamp_network: start + Duration::try_seconds(i as i64).unwrap(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        };
+
+        aircraft::update_aircraft_position(vec![item], false)
+            .await
+            .expect("position update failed");
+    }
+
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."aircraft_history" WHERE "identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count history rows");
+    let count: i64 = row.get(0);
+    assert_eq!(count, UPDATE_COUNT as i64);
+
+    // The aircraft is registered without a position
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT "geom" IS NULL FROM "{PSQL_SCHEMA}"."aircraft" WHERE "identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("aircraft not registered");
+    assert!(row.get::<_, bool>(0));
+
+    let point = aircraft::get_aircraft_pointz(&identifier)
+        .await
+        .expect("could not get latest position");
+    assert!((point.y - (LATITUDE + (UPDATE_COUNT - 1) as f64 * 1e-4)).abs() < 1e-9);
+    assert!((point.z - (100.0 + (UPDATE_COUNT - 1) as f64)).abs() < 1e-9);
+
+    let nodes = nearby::get_nodes_near(
+        &Coordinates {
+            latitude: LATITUDE,
+            longitude: LONGITUDE,
+        },
+        1_000.0,
+        &pool,
+    )
+    .await
+    .expect("could not get nearby nodes");
+    let node = nodes
+        .iter()
+        .find(|node| node.node_type == NodeType::Aircraft && node.identifier == identifier)
+        .expect("aircraft not nearby");
+    assert!((node.geom.y - point.y).abs() < 1e-9);
+
+    let flights = flight::get_flights(GetFlightsRequest {
+        window_min_x: LONGITUDE - 0.01,
+        window_min_y: LATITUDE - 0.01,
+        window_max_x: LONGITUDE + 0.01,
+        window_max_y: LATITUDE + 0.01,
+        time_start: Some((start - Duration::try_minutes(1).unwrap()).into()),
+        time_end: Some(Utc::now().into()),
+        ..Default::default()
+    })
+    .await
+    .expect("could not get flights");
+    let state = flights
+        .iter()
+        .find(|flight| flight.aircraft_id.as_ref() == Some(&identifier))
+        .and_then(|flight| flight.state.clone())
+        .expect("aircraft state not found");
+    let position = state.position.expect("no position");
+    assert!((position.latitude - point.y).abs() < 1e-9);
+}
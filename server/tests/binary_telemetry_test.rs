@@ -0,0 +1,105 @@
+//! Binary telemetry ingestion against a live database
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use svc_gis::postgis::{aircraft, telemetry, PSQL_SCHEMA};
+use svc_gis::types::{AircraftTelemetry, Position};
+
+const LATITUDE_UDEG: i32 = 52_374_590;
+const LONGITUDE_UDEG: i32 = 4_916_003;
+const ALTITUDE_M: i16 = 120;
+const GROUND_SPEED_CMPS: i16 = 1234;
+const VERTICAL_SPEED_CMPS: i16 = -150;
+const TRACK_ANGLE_CDEG: u16 = 27_050;
+
+/// Encodes a record in the documented layout of [`telemetry`]
+fn encode(index: u32, timestamp_ms: i64) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(telemetry::RECORD_SIZE);
+    bytes.extend_from_slice(&index.to_le_bytes());
+    bytes.extend_from_slice(&LATITUDE_UDEG.to_le_bytes());
+    bytes.extend_from_slice(&LONGITUDE_UDEG.to_le_bytes());
+    bytes.extend_from_slice(&ALTITUDE_M.to_le_bytes());
+    bytes.extend_from_slice(&GROUND_SPEED_CMPS.to_le_bytes());
+    bytes.extend_from_slice(&VERTICAL_SPEED_CMPS.to_le_bytes());
+    bytes.extend_from_slice(&TRACK_ANGLE_CDEG.to_le_bytes());
+    bytes.extend_from_slice(&timestamp_ms.to_le_bytes());
+    bytes
+}
+
+/// Gets the rows of an aircraft in a table as JSON, without the identifier
+async fn rows(pool: &deadpool_postgres::Pool, table: &str, identifier: &str) -> Vec<String> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query(
+            &format!(
+                r#"SELECT (to_jsonb("t") - 'identifier')::TEXT
+                FROM "{PSQL_SCHEMA}"."{table}" AS "t"
+                WHERE "identifier" = $1
+                ORDER BY 1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get rows")
+        .iter()
+        .map(|row| row.get(0))
+        .collect()
+}
+
+/// A binary record is stored exactly as the same telemetry submitted
+///  through the protobuf path
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_binary_telemetry_round_trip() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let binary = format!("bt{suffix}b");
+    let protobuf = format!("bt{suffix}p");
+
+    // Binary timestamps have a millisecond resolution
+    let timestamp_network =
+        DateTime::<Utc>::from_timestamp_millis(Utc::now().timestamp_millis()).unwrap();
+
+    let index = telemetry::register_identifier(&binary)
+        .await
+        .expect("could not register identifier");
+    let outcome = telemetry::ingest_binary(
+        &encode(index, timestamp_network.timestamp_millis()),
+        false,
+        None,
+    )
+    .await
+    .expect("could not ingest binary telemetry");
+    assert_eq!(outcome.accepted, 1);
+
+    aircraft::update_aircraft_telemetry(
+        vec![AircraftTelemetry {
+            identifier: protobuf.clone(),
+            position: Position {
+                latitude: LATITUDE_UDEG as f64 / 1_000_000.0,
+                longitude: LONGITUDE_UDEG as f64 / 1_000_000.0,
+                altitude_meters: ALTITUDE_M as f64,
+            },
+            velocity_horizontal_ground_mps: 12.34,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: -1.5,
+            track_angle_degrees: 270.5,
+            timestamp_network,
+            timestamp_asset: None,
+        }],
+        false,
+    )
+    .await
+    .expect("could not update telemetry");
+
+    for table in ["aircraft", "aircraft_history"] {
+        let binary_rows = rows(&pool, table, &binary).await;
+        assert_eq!(binary_rows.len(), 1, "{table}");
+        assert_eq!(binary_rows, rows(&pool, table, &protobuf).await, "{table}");
+    }
+}
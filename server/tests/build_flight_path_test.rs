@@ -0,0 +1,165 @@
+//! Flight paths built from a sequence of nodes against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    BuildFlightPathRequest, Coordinates, NodeRef, NodeType, Vertiport,
+};
+use svc_gis::postgis::route::{self, RouteError};
+use svc_gis::postgis::{vertiport, PostgisError, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Latitude of the vertiports
+const LATITUDE: f64 = 52.3745905;
+
+/// Longitude of the first vertiport, the others are further east
+const LONGITUDE: f64 = 4.9160036;
+
+/// Longitude between consecutive vertiports, about 1.4 km
+const SPACING_DEGREES: f64 = 0.02;
+
+/// A small square vertiport
+fn square(identifier: &str, longitude: f64) -> Vertiport {
+    let offset = 0.0001;
+    Vertiport {
+        identifier: identifier.to_string(),
+        vertices: vec![
+            (LATITUDE - offset, longitude - offset),
+            (LATITUDE + offset, longitude - offset),
+            (LATITUDE + offset, longitude + offset),
+            (LATITUDE - offset, longitude + offset),
+            (LATITUDE - offset, longitude - offset),
+        ]
+        .into_iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude,
+            longitude,
+        })
+        .collect(),
+        altitude_meters: 0.0,
+        label: None,
+        timestamp_network: Some(Utc::now().into()),
+    }
+}
+
+fn node(identifier: &str) -> NodeRef {
+    NodeRef {
+        node_type: NodeType::Vertiport as i32,
+        identifier: identifier.to_string(),
+    }
+}
+
+/// Chains two legs into one stored flight, a leg that can't be routed is
+///  reported and nothing is stored
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_build_flight_path() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let vertiports: Vec<String> = (0..3).map(|i| format!("bf-{suffix}-{i}")).collect();
+    vertiport::update_vertiports(
+        vertiports
+            .iter()
+            .enumerate()
+            .map(|(i, identifier)| square(identifier, LONGITUDE + SPACING_DEGREES * i as f64))
+            .collect(),
+        false,
+    )
+    .await
+    .expect("could not add vertiports");
+
+    let time_start = Utc::now() + Duration::try_minutes(1).unwrap();
+    let request = BuildFlightPathRequest {
+        flight_identifier: format!("bf-{suffix}"),
+        aircraft_identifier: format!("bf-{suffix}-ac"),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        nodes: vertiports
+            .iter()
+            .map(|identifier| node(identifier))
+            .collect(),
+        time_start: Some(time_start.into()),
+        time_end: Some((time_start + Duration::try_hours(1).unwrap()).into()),
+        ..Default::default()
+    };
+
+    let response = route::build_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("could not build flight path");
+
+    // Starts and ends at the outer vertiports, passes the middle one once
+    let longitudes: Vec<f64> = response.path.iter().map(|p| p.longitude).collect();
+    let near = |longitude: f64, i: usize| {
+        (longitude - (LONGITUDE + SPACING_DEGREES * i as f64)).abs() < 1e-6
+    };
+    assert!(near(longitudes[0], 0));
+    assert!(near(*longitudes.last().unwrap(), 2));
+    assert_eq!(longitudes.iter().filter(|l| near(**l, 1)).count(), 1);
+    assert!(response.distance_meters > 2.0 * 1_300.0);
+
+    let time_departure: DateTime<Utc> = response.time_departure.clone().unwrap().into();
+    let time_arrival: DateTime<Utc> = response.time_arrival.clone().unwrap().into();
+    assert!(time_departure >= time_start);
+    assert!(time_arrival > time_departure);
+
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT ST_NPoints("geom"), "time_start", "time_end"
+                FROM "{PSQL_SCHEMA}"."flights"
+                WHERE "flight_identifier" = $1;"#
+            ),
+            &[&request.flight_identifier],
+        )
+        .await
+        .expect("could not get stored flight");
+
+    let (points, stored_start, stored_end): (i32, DateTime<Utc>, DateTime<Utc>) =
+        (row.get(0), row.get(1), row.get(2));
+    assert_eq!(points as usize, response.path.len());
+    assert_eq!(
+        stored_start.timestamp_millis(),
+        time_departure.timestamp_millis()
+    );
+    assert_eq!(
+        stored_end.timestamp_millis(),
+        time_arrival.timestamp_millis()
+    );
+
+    // The window fits the first leg but not the second
+    let request = BuildFlightPathRequest {
+        flight_identifier: format!("bf-{suffix}-x"),
+        time_end: Some((time_start + Duration::try_seconds(100).unwrap()).into()),
+        ..request
+    };
+
+    let e = route::build_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .unwrap_err();
+    assert!(matches!(e, PostgisError::Route(RouteError::Leg(1, _))));
+
+    let stored = client
+        .query_opt(
+            &format!(r#"SELECT 1 FROM "{PSQL_SCHEMA}"."flights" WHERE "flight_identifier" = $1;"#),
+            &[&request.flight_identifier],
+        )
+        .await
+        .expect("could not query flights");
+    assert!(stored.is_none());
+
+    // Unknown vertiports are rejected before routing
+    let mut nodes = request.nodes.clone();
+    nodes[2] = node(&format!("bf-{suffix}-missing"));
+    let e = route::build_flight_path(
+        BuildFlightPathRequest { nodes, ..request },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(e, PostgisError::Route(RouteError::Node(2)));
+}
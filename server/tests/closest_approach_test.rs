@@ -0,0 +1,110 @@
+//! Closest point of approach of two stored flights against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3745905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f32 = 100.0;
+
+/// Stores a straight flight between two points
+async fn store_flight(
+    identifier: &str,
+    path: [(f64, f64); 2],
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    max_duration_secs: u64,
+) {
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.to_string()),
+            aircraft_identifier: Some(identifier.to_string()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: path
+                .iter()
+                .map(|(latitude, longitude)| PointZ {
+                    latitude: *latitude,
+                    longitude: *longitude,
+                    altitude_meters: ALTITUDE,
+                })
+                .collect(),
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        max_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+}
+
+/// An eastbound and a northbound flight crossing the same point halfway
+///  through the same time window meet there, a later flight never does
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_closest_point_of_approach() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (east, north, later) = (
+        format!("cpa-e-{suffix}"),
+        format!("cpa-n-{suffix}"),
+        format!("cpa-l-{suffix}"),
+    );
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    let max_duration_secs = config.max_flight_duration_secs;
+
+    store_flight(
+        &east,
+        [(LATITUDE, LONGITUDE - 0.01), (LATITUDE, LONGITUDE + 0.01)],
+        time_start,
+        time_end,
+        max_duration_secs,
+    )
+    .await;
+
+    store_flight(
+        &north,
+        [(LATITUDE - 0.01, LONGITUDE), (LATITUDE + 0.01, LONGITUDE)],
+        time_start,
+        time_end,
+        max_duration_secs,
+    )
+    .await;
+
+    let approach = flight::closest_point_of_approach(&east, &north, &pool)
+        .await
+        .expect("query failed")
+        .expect("flights overlap in time");
+
+    // Segment times are rounded to milliseconds, both aircraft fly ~2-4 m/s
+    let crossing = time_start + Duration::try_minutes(5).unwrap();
+    assert!((approach.time - crossing).num_seconds().abs() <= 1);
+    assert!(approach.distance_meters < 10.0);
+    assert!((approach.position_a.x - LONGITUDE).abs() < 1e-4);
+    assert!((approach.position_b.y - LATITUDE).abs() < 1e-4);
+
+    // Starts after the eastbound flight has landed
+    store_flight(
+        &later,
+        [(LATITUDE - 0.01, LONGITUDE), (LATITUDE + 0.01, LONGITUDE)],
+        time_end + Duration::try_minutes(1).unwrap(),
+        time_end + Duration::try_minutes(11).unwrap(),
+        max_duration_secs,
+    )
+    .await;
+
+    let approach = flight::closest_point_of_approach(&east, &later, &pool)
+        .await
+        .expect("query failed");
+    assert!(approach.is_none());
+}
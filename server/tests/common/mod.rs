@@ -0,0 +1,36 @@
+//! Setup shared by the integration tests
+//!
+//! Each test file is its own test binary, with its own global database
+//!  pool set up here.
+
+#![allow(dead_code)]
+
+/// Loads the config from the environment, then sets up the database as
+///  [`setup_with`] does
+pub async fn setup() -> (svc_gis::Config, deadpool_postgres::Pool) {
+    let config = svc_gis::Config::try_from_env().expect("could not load config");
+    setup_with(config).await
+}
+
+/// Sets the global database pool for `config` and initializes the
+///  database
+///
+/// The pool is set by the first test of the binary to run and shared by
+///  the ones after it, so tests sharing a binary need `--test-threads=1`.
+pub async fn setup_with(config: svc_gis::Config) -> (svc_gis::Config, deadpool_postgres::Pool) {
+    let pool = svc_gis::postgis::DEADPOOL_POSTGIS
+        .get_or_init(|| svc_gis::postgis::pool::create_pool(config.clone()))
+        .clone();
+
+    svc_gis::postgis::psql_init(config.psql_init_lock_timeout_secs)
+        .await
+        .expect("psql_init failed");
+
+    (config, pool)
+}
+
+/// A number that differs between test runs, appended to identifiers so
+///  rows left behind by earlier runs don't collide
+pub fn unique_suffix() -> i64 {
+    chrono::Utc::now().timestamp_micros() % 1_000_000_000
+}
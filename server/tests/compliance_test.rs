@@ -0,0 +1,87 @@
+//! Alerts for airborne aircraft without a flight against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::compliance::{self, UnboundTracker, ALERTS};
+use svc_gis::types::{AircraftTelemetry, AircraftType, Position};
+
+fn airborne(identifier: &str) -> AircraftTelemetry {
+    AircraftTelemetry {
+        identifier: identifier.to_string(),
+        position: Position {
+            latitude: 52.3745905,
+            longitude: 4.9160036,
+            altitude_meters: 100.0,
+        },
+        velocity_horizontal_ground_mps: 20.0,
+        velocity_horizontal_air_mps: None,
+        velocity_vertical_mps: 0.0,
+        track_angle_degrees: 90.0,
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+    }
+}
+
+/// Only the airborne aircraft without a bound flight alerts
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_unbound_aircraft_alert() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let bound = format!("itb-{suffix}");
+    let unbound = format!("itu-{suffix}");
+
+    svc_gis::postgis::aircraft::update_aircraft_telemetry(
+        vec![airborne(&bound), airborne(&unbound)],
+        false,
+    )
+    .await
+    .expect("telemetry update failed");
+
+    let time_start = Utc::now() - Duration::try_minutes(5).unwrap();
+    let flight = UpdateFlightPathRequest {
+        flight_identifier: Some(format!("itf-{suffix}")),
+        aircraft_identifier: Some(bound.clone()),
+        simulated: false,
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude: 52.37,
+                longitude: 4.91,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: 52.38,
+                longitude: 4.92,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_hours(1).unwrap()).into()),
+        ..Default::default()
+    };
+
+    svc_gis::postgis::flight::update_flight_path(flight, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let mut receiver = ALERTS.subscribe();
+    let mut tracker = UnboundTracker::default();
+    compliance::check_unbound_aircraft(&mut tracker, Duration::zero(), false)
+        .await
+        .expect("check failed");
+
+    let mut alerted = vec![];
+    while let Ok(alert) = receiver.try_recv() {
+        alerted.push(alert.aircraft_identifier);
+    }
+
+    assert!(alerted.contains(&unbound));
+    assert!(!alerted.contains(&bound));
+}
@@ -0,0 +1,115 @@
+//! Concurrent first updates of a flight against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::{PostgisError, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Flights raced, each by two aircraft
+const RACES: usize = 10;
+
+/// Two aircraft racing to file a new flight: one wins, the other is
+///  rejected as a rebind instead of silently taking the flight over
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn it_concurrent_first_update() {
+    let (config, pool) = common::setup().await;
+
+    let prefix = format!("cu-{}", common::unique_suffix());
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+
+    for race in 0..RACES {
+        let identifier = format!("{prefix}-{race}");
+        let request = |aircraft: &str| UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(format!("{prefix}-{aircraft}")),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![
+                PointZ {
+                    latitude,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude,
+                    longitude: longitude + 0.01,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+            ..Default::default()
+        };
+
+        let max_duration_secs = config.max_flight_duration_secs;
+        let tasks = ["a", "b"].map(|aircraft| {
+            let request = request(aircraft);
+            tokio::spawn(
+                async move { flight::update_flight_path(request, max_duration_secs).await },
+            )
+        });
+
+        let mut results = vec![];
+        for task in tasks {
+            results.push(task.await.expect("task panicked"));
+        }
+
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .any(|result| result == &Err(PostgisError::FlightPath(FlightError::AircraftMismatch))));
+
+        // The flight and its members belong to the winner only
+        let client = pool.get().await.expect("could not get client");
+        let owner: String = client
+            .query_one(
+                &format!(
+                    r#"SELECT "aircraft_identifier" FROM "{PSQL_SCHEMA}"."flights"
+                    WHERE "flight_identifier" = $1;"#
+                ),
+                &[&identifier],
+            )
+            .await
+            .expect("could not get flight")
+            .get(0);
+
+        let members: Vec<String> = client
+            .query(
+                &format!(
+                    r#"SELECT "aircraft_identifier" FROM "{PSQL_SCHEMA}"."flight_aircraft"
+                    WHERE "flight_identifier" = $1;"#
+                ),
+                &[&identifier],
+            )
+            .await
+            .expect("could not get members")
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        assert_eq!(members, vec![owner]);
+
+        let rebinds: i64 = client
+            .query_one(
+                &format!(
+                    r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."flight_rebinds"
+                    WHERE "flight_identifier" = $1;"#
+                ),
+                &[&identifier],
+            )
+            .await
+            .expect("could not count rebinds")
+            .get(0);
+        assert_eq!(rebinds, 0);
+
+        flight::delete_flight(&identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+}
@@ -0,0 +1,178 @@
+//! Periodic conflict checks of upcoming flights against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use prost::Message;
+use svc_gis::grpc::server::grpc_server::{
+    ConflictCheck, FlightConflicted, GetFlightsRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::backlog::{self, EventKind, EVENT_BACKLOG_CAPACITY};
+use svc_gis::postgis::{conflict_check, flight};
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3145905;
+const LONGITUDE: f64 = 4.9160036;
+
+/// Files a straight flight between two points at 100 meters
+async fn file_flight(
+    identifier: &str,
+    from: (f64, f64),
+    to: (f64, f64),
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) {
+    let point = |(latitude, longitude)| PointZ {
+        latitude,
+        longitude,
+        altitude_meters: 100.0,
+    };
+
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.to_string()),
+            aircraft_identifier: Some(identifier.to_string()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![point(from), point(to)],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        0,
+    )
+    .await
+    .expect("flight update failed");
+}
+
+/// Gets the stored conflict check of a flight
+async fn stored_check(identifier: &str, request: &GetFlightsRequest) -> Option<ConflictCheck> {
+    flight::get_flights(request.clone())
+        .await
+        .expect("could not get flights")
+        .into_iter()
+        .find(|flight| flight.session_id.as_deref() == Some(identifier))
+        .expect("flight not found")
+        .conflict_check
+}
+
+/// Counts the conflicted events of a flight in the backlog
+async fn conflicted_events(identifier: &str, pool: &deadpool_postgres::Pool) -> usize {
+    backlog::get_events_after(EventKind::FlightConflicted, 0, EVENT_BACKLOG_CAPACITY, pool)
+        .await
+        .expect("could not read backlog")
+        .iter()
+        .filter_map(|event| FlightConflicted::decode(event.payload.as_slice()).ok())
+        .filter(|event| event.flight_identifier == identifier)
+        .count()
+}
+
+/// A clean flight becomes conflicted once a crossing flight is filed, which
+///  is reported once and streamed
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_conflict_check() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let clean = format!("cc-a-{suffix}");
+    let crossing = format!("cc-b-{suffix}");
+
+    let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+    let time_start = now + Duration::try_minutes(5).unwrap();
+    let time_end = now + Duration::try_minutes(15).unwrap();
+    let horizon = Duration::try_minutes(10).unwrap();
+
+    // West to east
+    file_flight(
+        &clean,
+        (LATITUDE, LONGITUDE - 0.002),
+        (LATITUDE, LONGITUDE + 0.002),
+        time_start,
+        time_end,
+    )
+    .await;
+
+    let request = GetFlightsRequest {
+        window_min_x: LONGITUDE - 0.01,
+        window_min_y: LATITUDE - 0.01,
+        window_max_x: LONGITUDE + 0.01,
+        window_max_y: LATITUDE + 0.01,
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        skeleton_only: true,
+        ..Default::default()
+    };
+
+    assert!(stored_check(&clean, &request).await.is_none());
+
+    conflict_check::check_upcoming_flights(horizon, 2)
+        .await
+        .expect("check failed");
+
+    let check = stored_check(&clean, &request)
+        .await
+        .expect("flight not checked");
+    assert!(!check.conflicted);
+    assert_eq!(check.conflict_count, 0);
+    assert!(check.checked_at.is_some());
+    assert_eq!(conflicted_events(&clean, &pool).await, 0);
+
+    let mut live = Box::pin(conflict_check::conflict_stream(None));
+
+    // South to north, crossing in the middle at the same time
+    file_flight(
+        &crossing,
+        (LATITUDE - 0.002, LONGITUDE),
+        (LATITUDE + 0.002, LONGITUDE),
+        time_start,
+        time_end,
+    )
+    .await;
+
+    conflict_check::check_upcoming_flights(horizon, 2)
+        .await
+        .expect("check failed");
+
+    let check = stored_check(&clean, &request)
+        .await
+        .expect("flight not checked");
+    assert!(check.conflicted);
+    assert_eq!(check.conflict_count, 1);
+    assert_eq!(conflicted_events(&clean, &pool).await, 1);
+
+    // Other flights may be found conflicting in the meantime
+    let streamed = loop {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), live.next())
+            .await
+            .expect("no event received")
+            .expect("stream ended");
+
+        if event.flight_identifier == clean {
+            break event;
+        }
+    };
+    assert!(streamed.event_id > 0);
+    assert_eq!(streamed.conflict_check, Some(check.clone()));
+
+    let single = flight::get_flight(&clean)
+        .await
+        .expect("could not get flight");
+    assert_eq!(single.conflict_check, Some(check));
+
+    // Still conflicted, not reported again
+    conflict_check::check_upcoming_flights(horizon, 2)
+        .await
+        .expect("check failed");
+    assert_eq!(conflicted_events(&clean, &pool).await, 1);
+
+    flight::delete_flight(&clean, None)
+        .await
+        .expect("could not delete flight");
+    flight::delete_flight(&crossing, None)
+        .await
+        .expect("could not delete flight");
+}
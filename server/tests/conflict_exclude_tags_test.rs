@@ -0,0 +1,113 @@
+//! Flights with an excluded tag left out of the internal conflict checks
+//!  against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, FlightIssue};
+use svc_gis::postgis::tags;
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3145905;
+const LONGITUDE: f64 = 4.9460036;
+
+/// A straight flight between two points at 100 meters
+fn flight_request(
+    identifier: &str,
+    from: (f64, f64),
+    to: (f64, f64),
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> UpdateFlightPathRequest {
+    let point = |(latitude, longitude)| PointZ {
+        latitude,
+        longitude,
+        altitude_meters: 100.0,
+    };
+
+    UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.to_string()),
+        aircraft_identifier: Some(identifier.to_string()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![point(from), point(to)],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some(time_end.into()),
+        ..Default::default()
+    }
+}
+
+/// Validation and the conflict probe of updates don't report flights
+///  with an excluded tag
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_conflict_exclude_tags() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let exercise_tag = format!("exercise-{suffix}");
+    tags::CONFLICT_EXCLUDED_TAGS
+        .set(vec![exercise_tag.clone()])
+        .expect("could not set excluded tags");
+
+    let proposed = format!("ce-a-{suffix}");
+    let real = format!("ce-b-{suffix}");
+    let exercise = format!("ce-c-{suffix}");
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    let request = flight_request(
+        &proposed,
+        (LATITUDE, LONGITUDE - 0.01),
+        (LATITUDE, LONGITUDE + 0.01),
+        time_start,
+        time_end,
+    );
+
+    // Both cross the proposed flight, one of them is an exercise
+    for (identifier, longitude, tags) in [
+        (&real, LONGITUDE - 0.005, vec![]),
+        (&exercise, LONGITUDE + 0.005, vec![exercise_tag.clone()]),
+    ] {
+        flight::update_flight_path(
+            UpdateFlightPathRequest {
+                tags,
+                ..flight_request(
+                    identifier,
+                    (LATITUDE - 0.002, longitude),
+                    (LATITUDE + 0.002, longitude),
+                    time_start,
+                    time_end,
+                )
+            },
+            0,
+        )
+        .await
+        .expect("flight update failed");
+    }
+
+    let conflicts: Vec<String> = flight::validate_flight_comprehensive(&request, &pool)
+        .await
+        .expect("validation failed")
+        .into_iter()
+        .filter_map(|issue| match issue {
+            FlightIssue::Conflict(conflict) => Some(conflict.flight_identifier),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(conflicts, vec![real.clone()]);
+
+    let probed = flight::probe_flight_conflicts(request)
+        .await
+        .expect("probe failed");
+    assert_eq!(probed, vec![real.clone()]);
+
+    for identifier in [&real, &exercise] {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+}
@@ -0,0 +1,169 @@
+//! Corridor allocation and corridor conflicts against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Corridor, CorridorConflict, GetCorridorAllocationRequest, GetFlightConflictsRequest, PointZ,
+    UpdateFlightPathRequest,
+};
+use svc_gis::postgis::{corridor, flight};
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3845905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f32 = 150.0;
+
+/// Points along the corridor centerline, west to east
+fn centerline() -> Vec<PointZ> {
+    [LONGITUDE - 0.005, LONGITUDE + 0.005]
+        .iter()
+        .map(|longitude| PointZ {
+            latitude: LATITUDE,
+            longitude: *longitude,
+            altitude_meters: ALTITUDE,
+        })
+        .collect()
+}
+
+/// Stores a flight following the corridor centerline
+async fn store_flight(
+    identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+    max_duration_secs: u64,
+) {
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.to_string()),
+            aircraft_identifier: Some(identifier.to_string()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: centerline(),
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        max_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+}
+
+/// Gets the conflicts of a path along the corridor with the corridor
+async fn corridor_conflicts(
+    identifier: &str,
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Vec<CorridorConflict> {
+    flight::get_flight_conflicts(GetFlightConflictsRequest {
+        path: centerline(),
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        distance_meters: 10.0,
+        tag_filter: None,
+    })
+    .await
+    .expect("could not get conflicts")
+    .corridor_conflicts
+    .into_iter()
+    .filter(|conflict| conflict.corridor_identifier == identifier)
+    .collect()
+}
+
+/// Two flights in a capacity-1 corridor fill it only while they overlap in
+///  time, and a path through the full corridor conflicts with it
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_corridor_allocation() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let identifier = format!("corridor-{suffix}");
+    let (first, later, overlapping) = (
+        format!("ca-f-{suffix}"),
+        format!("ca-l-{suffix}"),
+        format!("ca-o-{suffix}"),
+    );
+
+    corridor::update_corridors(vec![Corridor {
+        identifier: identifier.clone(),
+        centerline: centerline(),
+        width_meters: 40.0,
+        height_meters: 20.0,
+        capacity: 1,
+    }])
+    .await
+    .expect("could not update corridors");
+
+    let start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let at = |minutes: i64| start + Duration::try_minutes(minutes).unwrap();
+    let max_duration_secs = config.max_flight_duration_secs;
+
+    // One after the other
+    store_flight(&first, at(5), at(10), max_duration_secs).await;
+    store_flight(&later, at(20), at(25), max_duration_secs).await;
+
+    let request = GetCorridorAllocationRequest {
+        identifier: identifier.clone(),
+        time_start: Some(start.into()),
+        time_end: Some(at(30).into()),
+        slice_seconds: 300,
+    };
+
+    let allocation = corridor::get_corridor_allocation(request.clone())
+        .await
+        .expect("could not get allocation");
+    assert_eq!(allocation.capacity, 1);
+    assert_eq!(allocation.slices.len(), 6);
+    assert!(allocation.slices.iter().all(|slice| !slice.over_capacity));
+    assert_eq!(
+        allocation
+            .slices
+            .iter()
+            .map(|slice| slice.flight_count)
+            .max(),
+        Some(1)
+    );
+
+    // Between the two flights the corridor is free
+    assert!(corridor_conflicts(&identifier, at(13), at(18))
+        .await
+        .is_empty());
+
+    // While the first flight is in it the corridor is full
+    let conflicts = corridor_conflicts(&identifier, at(7), at(12)).await;
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].capacity, 1);
+    assert_eq!(conflicts[0].flight_count, 1);
+
+    // Stored anyway, the overlapping slices are over capacity
+    store_flight(&overlapping, at(7), at(12), max_duration_secs).await;
+
+    let allocation = corridor::get_corridor_allocation(request)
+        .await
+        .expect("could not get allocation");
+    let over_capacity: Vec<u32> = allocation
+        .slices
+        .iter()
+        .filter(|slice| slice.over_capacity)
+        .map(|slice| slice.flight_count)
+        .collect();
+    assert_eq!(over_capacity, vec![2]);
+
+    for identifier in [&first, &later, &overlapping] {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+
+    corridor::delete_corridor(&identifier)
+        .await
+        .expect("could not delete corridor");
+    assert_eq!(
+        corridor::delete_corridor(&identifier).await.unwrap_err(),
+        corridor::CorridorError::NotFound
+    );
+}
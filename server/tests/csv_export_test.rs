@@ -0,0 +1,129 @@
+//! CSV export of flights and their segments against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use futures::StreamExt;
+use svc_gis::grpc::server::grpc_server::{
+    CsvDataset, ExportCsvRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::{export, flight};
+use svc_gis::types::AircraftType;
+
+/// Number of flights in the window
+const FLIGHT_COUNT: usize = 3;
+
+/// Exports a dataset and joins the chunks
+async fn export_csv(request: &ExportCsvRequest, pool: &deadpool_postgres::Pool) -> (String, u32) {
+    let mut stream = Box::pin(
+        export::csv_stream(request, pool)
+            .await
+            .expect("could not start export"),
+    );
+
+    let (mut data, mut row_count) = (String::new(), 0);
+    let mut sequence = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.expect("could not export chunk");
+        assert_eq!(chunk.sequence, sequence);
+        data.push_str(&chunk.data);
+        row_count += chunk.row_count;
+        sequence += 1;
+    }
+
+    (data, row_count)
+}
+
+/// Flights and segments in the window are exported with a header, WKT
+///  geometries are quoted since they contain commas
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_export_csv() {
+    let (config, pool) = common::setup().await;
+
+    // A window far from other tests so their flights aren't included
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (-41.0 + (suffix % 1000) as f64 * 1e-3, 174.0);
+    let time_start = Utc::now() + Duration::try_hours(3).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    let mut identifiers = vec![];
+    for i in 0..FLIGHT_COUNT {
+        let identifier = format!("csv-{suffix}-{i}");
+        let request = UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            simulated: true,
+            path: vec![
+                PointZ {
+                    latitude: latitude + i as f64 * 1e-4,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude: latitude + i as f64 * 1e-4,
+                    longitude: longitude + 0.0005,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        };
+
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("flight update failed");
+        identifiers.push(identifier);
+    }
+
+    let request = ExportCsvRequest {
+        dataset: CsvDataset::Flights as i32,
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        window_min_x: longitude - 0.001,
+        window_min_y: latitude - 0.001,
+        window_max_x: longitude + 0.001,
+        window_max_y: latitude + 0.001,
+    };
+
+    let (data, row_count) = export_csv(&request, &pool).await;
+    assert_eq!(row_count as usize, FLIGHT_COUNT);
+
+    let records = data.split_terminator("\r\n").collect::<Vec<_>>();
+    assert_eq!(records.len(), FLIGHT_COUNT + 1);
+    assert!(records[0].starts_with("flight_identifier,aircraft_identifier,"));
+    assert!(records[0].ends_with(",geom_wkt"));
+    for (record, identifier) in records[1..].iter().zip(&identifiers) {
+        assert!(record.starts_with(&format!("{identifier},{identifier},Rotorcraft,true,")));
+        assert!(record.ends_with(")\""));
+        assert!(record.contains(",\"LINESTRING Z ("));
+    }
+
+    let request = ExportCsvRequest {
+        dataset: CsvDataset::FlightSegments as i32,
+        ..request
+    };
+
+    let (data, row_count) = export_csv(&request, &pool).await;
+    assert!(row_count as usize >= FLIGHT_COUNT);
+    assert!(data.starts_with("flight_identifier,time_start,time_end,geom_wkt\r\n"));
+    for identifier in &identifiers {
+        assert!(data.contains(&format!("\r\n{identifier},")));
+    }
+
+    // No rows still sends the header
+    let request = ExportCsvRequest {
+        time_start: Some((time_end + Duration::try_days(365).unwrap()).into()),
+        time_end: Some((time_end + Duration::try_days(366).unwrap()).into()),
+        ..request
+    };
+
+    let (data, row_count) = export_csv(&request, &pool).await;
+    assert_eq!(row_count, 0);
+    assert_eq!(data, "flight_identifier,time_start,time_end,geom_wkt\r\n");
+}
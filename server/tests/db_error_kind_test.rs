@@ -0,0 +1,86 @@
+//! Classification of database errors against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{flight, DbErrorKind, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Errors raised by real constraint violations are classified by kind
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_db_error_kind() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let flight_identifier = format!("dk-{suffix}");
+    let aircraft_identifier = format!("dk-{suffix}-ac");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(aircraft_identifier.clone()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let client = pool.get().await.expect("could not get client");
+
+    // Duplicate flight member
+    let e = client
+        .execute(
+            &format!(
+                r#"INSERT INTO "{PSQL_SCHEMA}"."flight_aircraft"
+                ("flight_identifier", "aircraft_identifier") VALUES ($1, $2);"#
+            ),
+            &[&flight_identifier, &aircraft_identifier],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(DbErrorKind::from(&e), DbErrorKind::AlreadyExists);
+
+    // Missing parent row
+    client
+        .batch_execute(
+            r#"CREATE TEMP TABLE "dk_parent" ("id" INTEGER PRIMARY KEY);
+            CREATE TEMP TABLE "dk_child" ("id" INTEGER REFERENCES "dk_parent" ("id"));"#,
+        )
+        .await
+        .expect("could not create tables");
+
+    let e = client
+        .execute(r#"INSERT INTO "dk_child" ("id") VALUES (1);"#, &[])
+        .await
+        .unwrap_err();
+    assert_eq!(DbErrorKind::from(&e), DbErrorKind::ForeignKeyViolation);
+
+    // Anything else
+    let e = client
+        .execute(r#"INSERT INTO "dk_parent" ("id") VALUES (NULL);"#, &[])
+        .await
+        .unwrap_err();
+    assert_eq!(DbErrorKind::from(&e), DbErrorKind::Other);
+}
@@ -0,0 +1,229 @@
+//! Dry-run updates against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, PointZ, UpdateFlightPathRequest, Zone, ZoneType,
+};
+use svc_gis::grpc::server::{RpcService, ServerImpl};
+use svc_gis::postgis::{aircraft, flight, zone, PSQL_SCHEMA};
+use svc_gis::types::{AircraftId, AircraftPosition, AircraftType, Position};
+use tonic::Request;
+
+/// Counts rows of `table` with `column` equal to `identifier`
+async fn count(pool: &deadpool_postgres::Pool, table: &str, column: &str, identifier: &str) -> i64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."{table}" WHERE "{column}" = $1;"#),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count rows")
+        .get(0)
+}
+
+/// Dry runs go through validation and the real statements, but leave no
+///  rows behind
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_dry_run_updates() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+
+    // Zones
+    let zone_identifier = format!("dr-zone-{suffix}");
+    let zones = vec![Zone {
+        identifier: zone_identifier.clone(),
+        zone_type: ZoneType::Restriction as i32,
+        vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: latitude + dy * 0.0001,
+                longitude: longitude + dx * 0.0001,
+            })
+            .collect(),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 100.0,
+        ..Default::default()
+    }];
+
+    zone::update_zones(zones.clone(), true)
+        .await
+        .expect("dry run zone update failed");
+    assert_eq!(
+        count(&pool, "zones", "identifier", &zone_identifier).await,
+        0
+    );
+
+    zone::update_zones(zones, false)
+        .await
+        .expect("zone update failed");
+    assert_eq!(
+        count(&pool, "zones", "identifier", &zone_identifier).await,
+        1
+    );
+
+    // Flight paths
+    let flight_identifier = format!("dr-{suffix}");
+    let time_start = Utc::now();
+    let mut request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(format!("dr-{suffix}")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        dry_run: true,
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("dry run flight update failed");
+    assert_eq!(
+        count(&pool, "flights", "flight_identifier", &flight_identifier).await,
+        0
+    );
+    assert_eq!(
+        count(
+            &pool,
+            "flight_segments",
+            "flight_identifier",
+            &flight_identifier
+        )
+        .await,
+        0
+    );
+
+    // Validation errors are reported as for a real update
+    let mut invalid = request.clone();
+    invalid.timestamp_end = invalid.timestamp_start.clone();
+    flight::update_flight_path(invalid, config.max_flight_duration_secs)
+        .await
+        .unwrap_err();
+
+    request.dry_run = false;
+    flight::update_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+    assert_eq!(
+        count(&pool, "flights", "flight_identifier", &flight_identifier).await,
+        1
+    );
+
+    // A dry run of a crossing flight reports the conflict probe
+    let crossing_identifier = format!("dr-{suffix}-crossing");
+    let crossing = UpdateFlightPathRequest {
+        flight_identifier: Some(crossing_identifier.clone()),
+        aircraft_identifier: Some(crossing_identifier.clone()),
+        path: vec![
+            PointZ {
+                latitude: latitude - 0.005,
+                longitude: longitude + 0.005,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: latitude + 0.005,
+                longitude: longitude + 0.005,
+                altitude_meters: 100.0,
+            },
+        ],
+        dry_run: true,
+        ..request
+    };
+
+    let response = ServerImpl::default()
+        .update_flight_path(Request::new(crossing))
+        .await
+        .expect("dry run flight update failed")
+        .into_inner();
+    assert!(response.dry_run);
+    assert!(!response.updated);
+    assert_eq!(response.identifiers, vec![crossing_identifier.clone()]);
+    assert!(response.conflict_identifiers.contains(&flight_identifier));
+    assert_eq!(
+        count(&pool, "flights", "flight_identifier", &crossing_identifier).await,
+        0
+    );
+
+    // Aircraft
+    let aircraft_identifier = format!("dra{suffix}");
+    let identification = AircraftId {
+        identifier: Some(aircraft_identifier.clone()),
+        session_id: None,
+        aircraft_type: AircraftType::Rotorcraft,
+        operator_id: None,
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+        force_type: false,
+    };
+    let position = AircraftPosition {
+        identifier: aircraft_identifier.clone(),
+        position: Position {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+        horizontal_accuracy_meters: None,
+        vertical_accuracy_meters: None,
+    };
+
+    aircraft::update_aircraft_id(vec![identification.clone()], true)
+        .await
+        .expect("dry run aircraft id update failed");
+    let outcome = aircraft::update_aircraft_position(vec![position.clone()], true)
+        .await
+        .expect("dry run position update failed");
+    assert_eq!(outcome.accepted, 1);
+    assert_eq!(
+        count(&pool, "aircraft", "identifier", &aircraft_identifier).await,
+        0
+    );
+    assert_eq!(
+        count(
+            &pool,
+            "aircraft_history",
+            "identifier",
+            &aircraft_identifier
+        )
+        .await,
+        0
+    );
+
+    aircraft::update_aircraft_id(vec![identification], false)
+        .await
+        .expect("aircraft id update failed");
+    aircraft::update_aircraft_position(vec![position], false)
+        .await
+        .expect("position update failed");
+    assert_eq!(
+        count(
+            &pool,
+            "aircraft_history",
+            "identifier",
+            &aircraft_identifier
+        )
+        .await,
+        1
+    );
+}
@@ -0,0 +1,92 @@
+//! Pool recycling after a primary switchover against a live database
+
+mod common;
+
+use chrono::Utc;
+use deadpool_postgres::PoolConfig;
+use std::sync::atomic::Ordering;
+use svc_gis::postgis::aircraft;
+use svc_gis::postgis::pool::FAILOVER_RECYCLES;
+use svc_gis::postgis::DbErrorKind;
+use svc_gis::types::{AircraftTelemetry, Position};
+
+/// A pooled connection that turned read-only, as a former primary does
+///  after a switchover, is recycled and the write succeeds on a fresh
+///  connection
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_failover_recycle() {
+    let mut config = svc_gis::Config::try_from_env().expect("could not load config");
+
+    // A single connection, so that the write gets the read-only one first,
+    //  which psql_init must give back. Waiting for it is bounded so that
+    //  the test fails rather than hangs if it doesn't.
+    let mut pool_config = PoolConfig::new(1);
+    pool_config.timeouts.wait = Some(std::time::Duration::from_secs(10));
+    config.pg.pool = Some(pool_config);
+
+    let (_, pool) = common::setup_with(config).await;
+
+    let suffix = common::unique_suffix();
+    let identifier = format!("fo-{suffix}");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+
+    // Simulated switchover: the established connection can't write anymore
+    {
+        let client = pool.get().await.expect("could not get client");
+        client
+            .batch_execute("SET SESSION default_transaction_read_only = on;")
+            .await
+            .expect("could not make the connection read-only");
+
+        let e = client
+            .execute(
+                &format!(r#"CREATE TEMPORARY TABLE "{identifier}" ("id" INTEGER);"#),
+                &[],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(DbErrorKind::from(&e), DbErrorKind::ReadOnly);
+        assert!(svc_gis::postgis::is_failover_error(&e));
+    }
+
+    let recycles = FAILOVER_RECYCLES.load(Ordering::Relaxed);
+    aircraft::update_aircraft_telemetry(
+        vec![AircraftTelemetry {
+            identifier: identifier.clone(),
+            position: Position {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            velocity_horizontal_ground_mps: 10.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: 0.0,
+            track_angle_degrees: 90.0,
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+        }],
+        false,
+    )
+    .await
+    .expect("telemetry update did not recover");
+
+    assert_eq!(FAILOVER_RECYCLES.load(Ordering::Relaxed), recycles + 1);
+
+    let geom = aircraft::get_aircraft_pointz(&identifier)
+        .await
+        .expect("aircraft not stored");
+    assert!((geom.y - latitude).abs() < 1e-6);
+    assert!((geom.x - longitude).abs() < 1e-6);
+
+    // The read-only connection was replaced
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one("SHOW default_transaction_read_only;", &[])
+        .await
+        .expect("could not get read-only setting");
+    assert_eq!(row.get::<_, String>(0), "off");
+}
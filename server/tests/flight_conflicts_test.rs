@@ -0,0 +1,135 @@
+//! Conflicts of a probed path with stored flights against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use postgis::ewkb::{LineStringT, PointZ};
+use svc_gis::grpc::server::grpc_server::{
+    GetFlightConflictsRequest, PointZ as GrpcPointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::{flight, utils, DEFAULT_SRID};
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3745905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f32 = 100.0;
+
+/// Max distance in meters between the reported closest point and the
+///  stored segment, as altitudes in responses are rounded to the distance
+///  resolution
+const ON_SEGMENT_TOLERANCE_METERS: f64 = utils::DEFAULT_DISTANCE_RESOLUTION_METERS;
+
+/// Gets a path between two `(latitude, longitude)` points
+fn path(from: (f64, f64), to: (f64, f64)) -> Vec<GrpcPointZ> {
+    [from, to]
+        .iter()
+        .map(|(latitude, longitude)| GrpcPointZ {
+            latitude: *latitude,
+            longitude: *longitude,
+            altitude_meters: ALTITUDE,
+        })
+        .collect()
+}
+
+/// Gets the 3D distance in meters between a point and a segment
+async fn distance_meters(
+    pool: &deadpool_postgres::Pool,
+    point: &PointZ,
+    segment: &LineStringT<PointZ>,
+) -> f64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            "SELECT ST_3DDistance(ST_Transform($1, 4978), ST_Transform($2, 4978));",
+            &[point, segment],
+        )
+        .await
+        .expect("could not get distance")
+        .get(0)
+}
+
+/// A probe crossing a stored flight reports the closest approach on the
+///  stored segment, at the crossing
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_conflict_closest_point() {
+    let (config, pool) = common::setup().await;
+
+    let identifier = format!("fc-{}", common::unique_suffix());
+    let time_start = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap()
+        + Duration::try_minutes(5).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    // West to east
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: path((LATITUDE, LONGITUDE - 0.002), (LATITUDE, LONGITUDE + 0.002)),
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    // South to north, crossing in the middle
+    let response = flight::get_flight_conflicts(GetFlightConflictsRequest {
+        path: path((LATITUDE - 0.002, LONGITUDE), (LATITUDE + 0.002, LONGITUDE)),
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        distance_meters: 20.0,
+        tag_filter: None,
+    })
+    .await
+    .expect("could not get conflicts");
+
+    let conflicts: Vec<_> = response
+        .conflicts
+        .into_iter()
+        .filter(|conflict| conflict.flight_identifier == identifier)
+        .collect();
+    assert!(!conflicts.is_empty());
+
+    for conflict in &conflicts {
+        let segment = LineStringT {
+            points: conflict
+                .segment
+                .iter()
+                .cloned()
+                .map(PointZ::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .expect("invalid segment"),
+            srid: Some(DEFAULT_SRID),
+        };
+        assert_eq!(segment.points.len(), 2);
+
+        let closest_point =
+            PointZ::try_from(conflict.closest_point.clone().expect("no closest point"))
+                .expect("invalid closest point");
+
+        assert!(
+            distance_meters(&pool, &closest_point, &segment).await < ON_SEGMENT_TOLERANCE_METERS
+        );
+        assert!(conflict.distance_meters <= 20.0);
+    }
+
+    // The closest of all conflicts is where the paths cross
+    let closest = conflicts
+        .iter()
+        .min_by(|a, b| a.distance_meters.total_cmp(&b.distance_meters))
+        .and_then(|conflict| conflict.closest_point.clone())
+        .unwrap();
+    assert!((closest.longitude - LONGITUDE).abs() < 1e-4);
+    assert!((closest.latitude - LATITUDE).abs() < 1e-4);
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
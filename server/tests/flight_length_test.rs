@@ -0,0 +1,126 @@
+//! Recomputing stored flight path lengths against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{flight, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3545905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f64 = 120.0;
+
+/// Gets the stored length of a flight
+async fn stored_length(pool: &deadpool_postgres::Pool, identifier: &str) -> Option<f64> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(
+                r#"SELECT "length_meters" FROM "{PSQL_SCHEMA}"."flights" WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get length")
+        .get("length_meters")
+}
+
+/// Clears the stored length of a flight, as for a flight stored before the
+///  length was recorded
+async fn clear_length(pool: &deadpool_postgres::Pool, identifier: &str) {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .execute(
+            &format!(
+                r#"UPDATE "{PSQL_SCHEMA}"."flights" SET "length_meters" = NULL WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not clear length");
+}
+
+/// A flight with a NULL length gets the length of its path back, one by
+///  one or in bulk
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_recompute_flight_length() {
+    let (config, pool) = common::setup().await;
+
+    let identifier = format!("fl-{}", common::unique_suffix());
+    let path: Vec<PointZ> = [LONGITUDE - 0.002, LONGITUDE + 0.002]
+        .iter()
+        .map(|longitude| PointZ {
+            latitude: LATITUDE,
+            longitude: *longitude,
+            altitude_meters: ALTITUDE as f32,
+        })
+        .collect();
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path,
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    let expected = svc_gis::postgis::utils::distance_meters(
+        &postgis::ewkb::PointZ::new(LONGITUDE - 0.002, LATITUDE, ALTITUDE, None),
+        &postgis::ewkb::PointZ::new(LONGITUDE + 0.002, LATITUDE, ALTITUDE, None),
+    ) as f64;
+
+    // Recorded when stored
+    let length = stored_length(&pool, &identifier)
+        .await
+        .expect("no length stored");
+    assert!((length - expected).abs() < 0.5, "{length} != {expected}");
+
+    clear_length(&pool, &identifier).await;
+    assert!(stored_length(&pool, &identifier).await.is_none());
+
+    let length = flight::recompute_flight_length(&identifier, &pool)
+        .await
+        .expect("could not recompute length")
+        .expect("no length computed");
+    assert!((length - expected).abs() < 0.5, "{length} != {expected}");
+    assert_eq!(stored_length(&pool, &identifier).await, Some(length));
+
+    // Backfill
+    clear_length(&pool, &identifier).await;
+    let updated = flight::recompute_all_flight_lengths(&pool)
+        .await
+        .expect("could not recompute lengths");
+    assert!(updated >= 1);
+    assert_eq!(stored_length(&pool, &identifier).await, Some(length));
+
+    // Up to date lengths are left alone
+    assert_eq!(
+        flight::recompute_all_flight_lengths(&pool)
+            .await
+            .expect("could not recompute lengths"),
+        0
+    );
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+    assert_eq!(
+        flight::recompute_flight_length("fl-unknown", &pool)
+            .await
+            .unwrap_err(),
+        svc_gis::postgis::PostgisError::FlightPath(flight::FlightError::NotFound)
+    );
+}
@@ -0,0 +1,101 @@
+//! Ordering of getFlights results against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    FlightOrder, GetFlightsRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3845905;
+const LONGITUDE: f64 = 4.9560036;
+
+/// Gets the identifiers of the flights starting with `prefix`, in the
+///  order returned
+async fn flight_order(request: &GetFlightsRequest, prefix: &str) -> Vec<String> {
+    flight::get_flights(request.clone())
+        .await
+        .expect("could not get flights")
+        .into_iter()
+        .filter_map(|flight| flight.session_id)
+        .filter(|identifier| identifier.starts_with(prefix))
+        .collect()
+}
+
+/// Flights tied on start time and distance are returned in the same order
+///  on every call, broken by flight identifier
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_flights_order_deterministic() {
+    common::setup().await;
+
+    let prefix = format!("fo-{}", common::unique_suffix());
+    let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+    let time_start = now + Duration::try_minutes(5).unwrap();
+    let time_end = now + Duration::try_minutes(15).unwrap();
+
+    // Same path and times, filed out of order
+    let identifiers: Vec<String> = ["c", "a", "d", "b"]
+        .iter()
+        .map(|suffix| format!("{prefix}-{suffix}"))
+        .collect();
+
+    for identifier in &identifiers {
+        flight::update_flight_path(
+            UpdateFlightPathRequest {
+                flight_identifier: Some(identifier.clone()),
+                aircraft_identifier: Some(identifier.clone()),
+                aircraft_type: AircraftType::Rotorcraft as i32,
+                path: [LONGITUDE - 0.002, LONGITUDE + 0.002]
+                    .iter()
+                    .map(|longitude| PointZ {
+                        latitude: LATITUDE,
+                        longitude: *longitude,
+                        altitude_meters: 100.0,
+                    })
+                    .collect(),
+                timestamp_start: Some(time_start.into()),
+                timestamp_end: Some(time_end.into()),
+                ..Default::default()
+            },
+            0,
+        )
+        .await
+        .expect("flight update failed");
+    }
+
+    let mut sorted = identifiers.clone();
+    sorted.sort();
+
+    for order_by in [
+        FlightOrder::FlightIdentifier,
+        FlightOrder::TimeStart,
+        FlightOrder::Distance,
+    ] {
+        let request = GetFlightsRequest {
+            window_min_x: LONGITUDE - 0.01,
+            window_min_y: LATITUDE - 0.01,
+            window_max_x: LONGITUDE + 0.01,
+            window_max_y: LATITUDE + 0.01,
+            time_start: Some(time_start.into()),
+            time_end: Some(time_end.into()),
+            order_by: order_by as i32,
+            ..Default::default()
+        };
+
+        let first = flight_order(&request, &prefix).await;
+        assert_eq!(first, sorted, "order {order_by:?}");
+        assert_eq!(flight_order(&request, &prefix).await, first);
+    }
+
+    for identifier in &identifiers {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+}
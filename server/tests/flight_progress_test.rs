@@ -0,0 +1,79 @@
+//! Flight progress lookups against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::PostgisError;
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3145905;
+const LONGITUDE: f64 = 4.9460036;
+
+/// A point on the flight path at 100 meters
+fn point(longitude: f64) -> PointZ {
+    PointZ {
+        latitude: LATITUDE,
+        longitude,
+        altitude_meters: 100.0,
+    }
+}
+
+/// Flights without a stored path are reported as not found rather than
+///  as a database fault
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_progress_not_found() {
+    let _ = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let stored = format!("fp-a-{suffix}");
+    let unknown = format!("fp-b-{suffix}");
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(stored.clone()),
+            aircraft_identifier: Some(stored.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![point(LONGITUDE - 0.01), point(LONGITUDE + 0.01)],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        0,
+    )
+    .await
+    .expect("flight update failed");
+
+    // The stored flight has no telemetry yet, it is still found
+    let progress = flight::flight_progress(&stored, point(LONGITUDE))
+        .await
+        .expect("could not get progress of stored flight");
+    assert!(progress.fraction_complete > 0.4 && progress.fraction_complete < 0.6);
+
+    let error = flight::flight_progress(&unknown, point(LONGITUDE))
+        .await
+        .expect_err("unknown flight has progress");
+    assert!(matches!(
+        error,
+        PostgisError::FlightPath(FlightError::NotFound)
+    ));
+
+    // Soft deleted flights no longer have progress
+    flight::delete_flight(&stored, None)
+        .await
+        .expect("could not delete flight");
+    let error = flight::flight_progress(&stored, point(LONGITUDE))
+        .await
+        .expect_err("deleted flight has progress");
+    assert!(matches!(
+        error,
+        PostgisError::FlightPath(FlightError::NotFound)
+    ));
+}
@@ -0,0 +1,244 @@
+//! Flight reservations and their confirmation against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::atomic::Ordering;
+use svc_gis::grpc::server::grpc_server::{
+    GetFlightConflictsRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::{PostgisError, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Identifiers of the flights conflicting with `path`
+async fn conflicting_flights(
+    path: &[PointZ],
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> Vec<String> {
+    flight::get_flight_conflicts(GetFlightConflictsRequest {
+        path: path.to_vec(),
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        distance_meters: 50.0,
+        tag_filter: None,
+    })
+    .await
+    .expect("could not get conflicts")
+    .conflicts
+    .into_iter()
+    .map(|conflict| conflict.flight_identifier)
+    .collect()
+}
+
+/// Counts the stored rows of a flight in `table`
+async fn count(pool: &deadpool_postgres::Pool, table: &str, identifier: &str) -> i64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(
+                r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."{table}" WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count rows")
+        .get(0)
+}
+
+/// Whether a flight is stored with a reservation
+async fn reserved(pool: &deadpool_postgres::Pool, identifier: &str) -> bool {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(
+                r#"SELECT "reserved_until" IS NOT NULL FROM "{PSQL_SCHEMA}"."flights"
+                WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get flight")
+        .get(0)
+}
+
+/// An unconfirmed reservation blocks the airspace until it expires, then
+///  is removed. A confirmed reservation is kept as a planned flight.
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_reservation() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    let reservation = |name: &str, longitude: f64, reservation_secs: u32| UpdateFlightPathRequest {
+        flight_identifier: Some(format!("rs-{suffix}-{name}")),
+        aircraft_identifier: Some(format!("rs-{suffix}-{name}-ac")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: latitude + 0.01,
+                longitude,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some(time_end.into()),
+        reservation_secs: Some(reservation_secs),
+        ..Default::default()
+    };
+
+    // Far enough apart that the two reservations don't conflict
+    let expiring = reservation("expiring", longitude, 1);
+    let confirmed = reservation("confirmed", longitude + 0.1, 60);
+    let expiring_identifier = expiring.flight_identifier.clone().unwrap();
+    let confirmed_identifier = confirmed.flight_identifier.clone().unwrap();
+
+    let confirmed_count = flight::RESERVATIONS_CONFIRMED.load(Ordering::Relaxed);
+
+    for request in [expiring.clone(), confirmed.clone()] {
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("flight update failed");
+    }
+
+    // Reservations block like planned flights
+    let conflicts = conflicting_flights(&expiring.path, time_start, time_end).await;
+    assert!(conflicts.contains(&expiring_identifier));
+
+    flight::confirm_flight(&confirmed_identifier)
+        .await
+        .expect("could not confirm flight");
+    assert!(flight::RESERVATIONS_CONFIRMED.load(Ordering::Relaxed) > confirmed_count);
+
+    // A confirmed flight has no reservation left to confirm
+    assert_eq!(
+        flight::confirm_flight(&confirmed_identifier)
+            .await
+            .unwrap_err(),
+        PostgisError::FlightPath(FlightError::NotFound)
+    );
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    // An expired reservation stops blocking before it's swept
+    let conflicts = conflicting_flights(&expiring.path, time_start, time_end).await;
+    assert!(!conflicts.contains(&expiring_identifier));
+
+    assert_eq!(
+        flight::confirm_flight(&expiring_identifier)
+            .await
+            .unwrap_err(),
+        PostgisError::FlightPath(FlightError::NotFound)
+    );
+
+    let expired = flight::expire_reservations()
+        .await
+        .expect("could not expire reservations");
+    assert!(expired >= 1);
+
+    for table in ["flights", "flight_aircraft", "flight_segments"] {
+        assert_eq!(
+            count(&pool, table, &expiring_identifier).await,
+            0,
+            "expired reservation left rows in {table}"
+        );
+    }
+
+    // The confirmed flight persists and keeps blocking
+    assert_eq!(count(&pool, "flights", &confirmed_identifier).await, 1);
+    let conflicts = conflicting_flights(&confirmed.path, time_start, time_end).await;
+    assert!(conflicts.contains(&confirmed_identifier));
+
+    // Updates of a planned flight don't turn it back into a reservation
+    flight::update_flight_path(confirmed, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    assert!(!reserved(&pool, &confirmed_identifier).await);
+}
+
+/// Reservations expire by the service clock, and filing a reservation again
+///  without `reservation_secs` makes it a planned flight
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_reservation_clock() {
+    let (config, pool) = common::setup().await;
+
+    let identifier = format!("rc-{}", common::unique_suffix());
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.clone()),
+        aircraft_identifier: Some(format!("{identifier}-ac")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: latitude + 0.01,
+                longitude,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some(time_end.into()),
+        reservation_secs: Some(60),
+        ..Default::default()
+    };
+
+    let clock = svc_gis::clock::freeze(time_start);
+    flight::update_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    // Expired by the service clock long before the system clock
+    clock.advance(Duration::try_minutes(2).unwrap());
+    let conflicts = conflicting_flights(&request.path, time_start, time_end).await;
+    assert!(!conflicts.contains(&identifier));
+    assert_eq!(
+        flight::confirm_flight(&identifier).await.unwrap_err(),
+        PostgisError::FlightPath(FlightError::NotFound)
+    );
+
+    // Still reserved as of the system clock
+    drop(clock);
+    let conflicts = conflicting_flights(&request.path, time_start, time_end).await;
+    assert!(conflicts.contains(&identifier));
+    assert!(reserved(&pool, &identifier).await);
+
+    // Filed again as a plain flight, not a renewed reservation
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            reservation_secs: None,
+            ..request
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+    assert!(!reserved(&pool, &identifier).await);
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
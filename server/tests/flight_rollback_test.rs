@@ -0,0 +1,194 @@
+//! Flight path updates failing part way through against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::{PostgisError, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Counts rows of `table` with `column` equal to `identifier`
+async fn count(pool: &deadpool_postgres::Pool, table: &str, column: &str, identifier: &str) -> i64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."{table}" WHERE "{column}" = $1;"#),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count rows")
+        .get(0)
+}
+
+/// Makes segment inserts for flights starting with `prefix` fail
+async fn inject_segment_failure(pool: &deadpool_postgres::Pool, prefix: &str) {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .batch_execute(&format!(
+            r#"CREATE OR REPLACE FUNCTION "{PSQL_SCHEMA}"."fail_segment_insert"()
+            RETURNS TRIGGER AS $$
+            BEGIN
+                RAISE EXCEPTION 'injected segment failure';
+            END;
+            $$ LANGUAGE plpgsql;
+
+            CREATE TRIGGER "fail_segment_insert_{prefix}"
+            BEFORE INSERT ON "{PSQL_SCHEMA}"."flight_segments"
+            FOR EACH ROW
+            WHEN (NEW."flight_identifier" LIKE '{prefix}%')
+            EXECUTE FUNCTION "{PSQL_SCHEMA}"."fail_segment_insert"();"#
+        ))
+        .await
+        .expect("could not create trigger");
+}
+
+/// Removes the trigger added by [`inject_segment_failure`]
+async fn remove_segment_failure(pool: &deadpool_postgres::Pool, prefix: &str) {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .batch_execute(&format!(
+            r#"DROP TRIGGER IF EXISTS "fail_segment_insert_{prefix}"
+            ON "{PSQL_SCHEMA}"."flight_segments";"#
+        ))
+        .await
+        .expect("could not drop trigger");
+}
+
+/// A failure while inserting segments rolls back the whole update, for
+///  new flights and for updates of stored flights
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_path_rollback() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let prefix = format!("fr-{suffix}");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+
+    let stored_identifier = format!("{prefix}-stored");
+    let stored = UpdateFlightPathRequest {
+        flight_identifier: Some(stored_identifier.clone()),
+        aircraft_identifier: Some(format!("{prefix}-a")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(stored.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let stored_segments = count(
+        &pool,
+        "flight_segments",
+        "flight_identifier",
+        &stored_identifier,
+    )
+    .await;
+    assert!(stored_segments > 0);
+
+    inject_segment_failure(&pool, &prefix).await;
+
+    // New flight
+    let new_identifier = format!("{prefix}-new");
+    let new = UpdateFlightPathRequest {
+        flight_identifier: Some(new_identifier.clone()),
+        aircraft_identifier: Some(format!("{prefix}-b")),
+        ..stored.clone()
+    };
+    let new_result = flight::update_flight_path(new, config.max_flight_duration_secs).await;
+
+    // Stored flight with a new path and a rebind
+    let mut update = stored.clone();
+    update.aircraft_identifier = Some(format!("{prefix}-c"));
+    update.allow_rebind = true;
+    update.path[1].longitude += 0.01;
+    let update_result = flight::update_flight_path(update, config.max_flight_duration_secs).await;
+
+    remove_segment_failure(&pool, &prefix).await;
+
+    assert_eq!(
+        new_result.unwrap_err(),
+        PostgisError::FlightPath(FlightError::DBError)
+    );
+    assert_eq!(
+        update_result.unwrap_err(),
+        PostgisError::FlightPath(FlightError::DBError)
+    );
+
+    // No orphan rows for the new flight
+    for table in ["flights", "flight_aircraft", "flight_segments"] {
+        assert_eq!(
+            count(&pool, table, "flight_identifier", &new_identifier).await,
+            0,
+            "orphan rows in {table}"
+        );
+    }
+
+    // The stored flight is untouched
+    assert_eq!(
+        count(
+            &pool,
+            "flight_rebinds",
+            "flight_identifier",
+            &stored_identifier
+        )
+        .await,
+        0
+    );
+    assert_eq!(
+        count(
+            &pool,
+            "flight_segments",
+            "flight_identifier",
+            &stored_identifier
+        )
+        .await,
+        stored_segments
+    );
+
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT "aircraft_identifier" FROM "{PSQL_SCHEMA}"."flights"
+                WHERE "flight_identifier" = $1;"#
+            ),
+            &[&stored_identifier],
+        )
+        .await
+        .expect("could not get stored flight");
+    let aircraft_identifier: String = row.get(0);
+    assert_eq!(aircraft_identifier, format!("{prefix}-a"));
+
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT "aircraft_identifier" FROM "{PSQL_SCHEMA}"."flight_aircraft"
+                WHERE "flight_identifier" = $1;"#
+            ),
+            &[&stored_identifier],
+        )
+        .await
+        .expect("could not get stored flight members");
+    let member: String = row.get(0);
+    assert_eq!(member, format!("{prefix}-a"));
+}
@@ -0,0 +1,90 @@
+//! Batched aircraft states for a list of flights against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{aircraft, flight};
+use svc_gis::types::{AircraftPosition, AircraftType, Position};
+
+/// Flights with a reporting aircraft get its state, the others are omitted
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_states_for_flights() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    let mut flight_identifiers = vec![];
+    for i in 0..3 {
+        let flight_identifier = format!("fs-{suffix}-{i}");
+        let request = UpdateFlightPathRequest {
+            flight_identifier: Some(flight_identifier.clone()),
+            aircraft_identifier: Some(format!("fs-{suffix}-ac{i}")),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![
+                PointZ {
+                    latitude: latitude + i as f64 * 0.01,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude: latitude + i as f64 * 0.01,
+                    longitude: longitude + 0.01,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        };
+
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("flight update failed");
+
+        flight_identifiers.push(flight_identifier);
+    }
+
+    // The last aircraft never reports
+    let positions = (0..2)
+        .map(|i| AircraftPosition {
+            identifier: format!("fs-{suffix}-ac{i}"),
+            position: Position {
+                latitude: latitude + i as f64 * 0.01,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        })
+        .collect();
+
+    aircraft::update_aircraft_position(positions, false)
+        .await
+        .expect("position update failed");
+
+    let mut requested = flight_identifiers.clone();
+    requested.push(format!("fs-{suffix}-unknown"));
+    let states = flight::get_states_for_flights(requested, &pool)
+        .await
+        .expect("could not get states");
+
+    assert_eq!(states.len(), 2);
+    for (i, flight_identifier) in flight_identifiers.iter().take(2).enumerate() {
+        let position = states
+            .get(flight_identifier)
+            .and_then(|state| state.position.clone())
+            .expect("no state for flight");
+        assert!((position.latitude - (latitude + i as f64 * 0.01)).abs() < 1e-6);
+    }
+    assert!(!states.contains_key(&flight_identifiers[2]));
+}
@@ -0,0 +1,75 @@
+//! Reason a flight was cancelled against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{flight, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Gets the stored status reason of a flight
+async fn status_reason(pool: &deadpool_postgres::Pool, flight_identifier: &str) -> Option<String> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(
+                r#"SELECT "status_reason" FROM "{PSQL_SCHEMA}"."flights" WHERE "flight_identifier" = $1;"#
+            ),
+            &[&flight_identifier],
+        )
+        .await
+        .expect("flight not found")
+        .get(0)
+}
+
+/// The reason is stored when a flight is cancelled and cleared on restore
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_status_reason() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let flight_identifier = format!("sr-{suffix}");
+    let time_start = Utc::now() + Duration::try_hours(1).unwrap();
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(format!("sr-{suffix}")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: 52.3745905,
+                longitude: 4.9260036,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+    assert_eq!(status_reason(&pool, &flight_identifier).await, None);
+
+    flight::delete_flight(&flight_identifier, Some("Weather"))
+        .await
+        .expect("could not delete flight");
+    assert_eq!(
+        status_reason(&pool, &flight_identifier).await.as_deref(),
+        Some("Weather")
+    );
+
+    flight::restore_flight(&flight_identifier, config.soft_delete_undo_window_secs)
+        .await
+        .expect("could not restore flight");
+    assert_eq!(status_reason(&pool, &flight_identifier).await, None);
+}
@@ -0,0 +1,103 @@
+//! Airspace checks of a proposed flight against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, FlightIssue};
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3245905;
+const LONGITUDE: f64 = 4.9360036;
+
+/// A straight flight between two points at 100 meters
+fn flight_request(
+    identifier: &str,
+    from: (f64, f64),
+    to: (f64, f64),
+    time_start: DateTime<Utc>,
+    time_end: DateTime<Utc>,
+) -> UpdateFlightPathRequest {
+    let point = |(latitude, longitude)| PointZ {
+        latitude,
+        longitude,
+        altitude_meters: 100.0,
+    };
+
+    UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.to_string()),
+        aircraft_identifier: Some(identifier.to_string()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![point(from), point(to)],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some(time_end.into()),
+        ..Default::default()
+    }
+}
+
+/// Flights crossing different segments of a long path are each reported
+///  once, and a stored flight doesn't conflict with itself
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_validate_flight_conflicts() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let long = format!("fv-a-{suffix}");
+    let west = format!("fv-b-{suffix}");
+    let east = format!("fv-c-{suffix}");
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    // West to east over about 1.4 km, many segments long
+    let request = flight_request(
+        &long,
+        (LATITUDE, LONGITUDE - 0.01),
+        (LATITUDE, LONGITUDE + 0.01),
+        time_start,
+        time_end,
+    );
+
+    // South to north across each end of the path
+    for (identifier, longitude) in [(&west, LONGITUDE - 0.008), (&east, LONGITUDE + 0.008)] {
+        flight::update_flight_path(
+            flight_request(
+                identifier,
+                (LATITUDE - 0.002, longitude),
+                (LATITUDE + 0.002, longitude),
+                time_start,
+                time_end,
+            ),
+            0,
+        )
+        .await
+        .expect("flight update failed");
+    }
+
+    flight::update_flight_path(request.clone(), 0)
+        .await
+        .expect("flight update failed");
+
+    let mut conflicts: Vec<String> = flight::validate_flight_comprehensive(&request, &pool)
+        .await
+        .expect("validation failed")
+        .into_iter()
+        .filter_map(|issue| match issue {
+            FlightIssue::Conflict(conflict) => Some(conflict.flight_identifier),
+            _ => None,
+        })
+        .collect();
+
+    conflicts.sort();
+    assert_eq!(conflicts, vec![west.clone(), east.clone()]);
+
+    for identifier in [&long, &west, &east] {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+}
@@ -0,0 +1,89 @@
+//! GeoJSON FeatureCollection of flights in a viewport against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{GetFlightsRequest, PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{export, flight};
+use svc_gis::types::AircraftType;
+
+/// Number of flights in the viewport
+const FLIGHT_COUNT: usize = 3;
+
+/// Every flight in the viewport is a LineString Feature with its properties
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_flights_geojson() {
+    let (config, pool) = common::setup().await;
+
+    // A viewport far from other tests so their flights aren't included
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (-40.0 + (suffix % 1000) as f64 * 1e-3, 175.0);
+    let time_start = Utc::now() + Duration::try_hours(3).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    let mut identifiers = vec![];
+    for i in 0..FLIGHT_COUNT {
+        let identifier = format!("gj-{suffix}-{i}");
+        let request = UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            simulated: true,
+            path: vec![
+                PointZ {
+                    latitude: latitude + i as f64 * 1e-4,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude: latitude + i as f64 * 1e-4,
+                    longitude: longitude + 0.0005,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        };
+
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("flight update failed");
+        identifiers.push(identifier);
+    }
+
+    let request = GetFlightsRequest {
+        window_min_x: longitude - 0.001,
+        window_min_y: latitude - 0.001,
+        window_max_x: longitude + 0.001,
+        window_max_y: latitude + 0.001,
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        ..Default::default()
+    };
+
+    let collection = export::get_flights_geojson(&request, &pool)
+        .await
+        .expect("could not export flights");
+
+    assert_eq!(collection["type"], "FeatureCollection");
+    let features = collection["features"]
+        .as_array()
+        .expect("features is not an array");
+    assert_eq!(features.len(), FLIGHT_COUNT);
+
+    for (feature, identifier) in features.iter().zip(&identifiers) {
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(feature["id"], identifier.as_str());
+        assert_eq!(feature["geometry"]["type"], "LineString");
+        assert_eq!(feature["properties"]["identifier"], identifier.as_str());
+        assert_eq!(feature["properties"]["aircraft_type"], "Rotorcraft");
+        assert_eq!(feature["properties"]["simulated"], true);
+        assert!(feature["properties"]["time_start"].is_string());
+        assert!(feature["properties"]["time_end"].is_string());
+    }
+}
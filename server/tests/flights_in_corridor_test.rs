@@ -0,0 +1,116 @@
+//! Flights inside of a corridor volume against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{Coordinates, PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::corridor::{self, CorridorFlight};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3945905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f32 = 100.0;
+
+/// A flight crossing the corridor and a flight passing north of it at the
+///  same altitude, only the first is in the corridor and only while flying
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flights_in_corridor() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let inside = format!("ci-{suffix}");
+    let outside = format!("co-{suffix}");
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    for (identifier, latitude) in [(&inside, LATITUDE), (&outside, LATITUDE + 0.01)] {
+        flight::update_flight_path(
+            UpdateFlightPathRequest {
+                flight_identifier: Some(identifier.clone()),
+                aircraft_identifier: Some(identifier.clone()),
+                aircraft_type: AircraftType::Rotorcraft as i32,
+                path: [LONGITUDE - 0.005, LONGITUDE + 0.005]
+                    .iter()
+                    .map(|longitude| PointZ {
+                        latitude,
+                        longitude: *longitude,
+                        altitude_meters: ALTITUDE,
+                    })
+                    .collect(),
+                timestamp_start: Some(time_start.into()),
+                timestamp_end: Some(time_end.into()),
+                ..Default::default()
+            },
+            config.max_flight_duration_secs,
+        )
+        .await
+        .expect("flight update failed");
+    }
+
+    let footprint = svc_gis::postgis::utils::polygon_from_vertices_z(
+        &[
+            (LATITUDE - 0.001, LONGITUDE - 0.001),
+            (LATITUDE - 0.001, LONGITUDE + 0.001),
+            (LATITUDE + 0.001, LONGITUDE + 0.001),
+            (LATITUDE + 0.001, LONGITUDE - 0.001),
+            (LATITUDE - 0.001, LONGITUDE - 0.001),
+        ]
+        .iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude: *latitude,
+            longitude: *longitude,
+        })
+        .collect::<Vec<_>>(),
+        0.0,
+    )
+    .expect("invalid footprint");
+
+    // The corridor is halfway along the path
+    let midway = time_start + Duration::try_minutes(5).unwrap();
+    let flights = corridor::flights_in_corridor(&footprint, midway, 50.0..150.0, &pool)
+        .await
+        .expect("could not get flights");
+    let flights: Vec<&CorridorFlight> = flights
+        .iter()
+        .filter(|flight| flight.flight_identifier.ends_with(&suffix.to_string()))
+        .collect();
+    assert_eq!(
+        flights,
+        vec![&CorridorFlight {
+            flight_identifier: inside.clone(),
+            aircraft_identifier: inside.clone(),
+        }]
+    );
+
+    // Below the altitude band
+    let flights = corridor::flights_in_corridor(&footprint, midway, 150.0..300.0, &pool)
+        .await
+        .expect("could not get flights");
+    assert!(!flights
+        .iter()
+        .any(|flight| flight.flight_identifier == inside));
+
+    // Not yet flying
+    let flights = corridor::flights_in_corridor(
+        &footprint,
+        time_start - Duration::try_minutes(1).unwrap(),
+        50.0..150.0,
+        &pool,
+    )
+    .await
+    .expect("could not get flights");
+    assert!(!flights
+        .iter()
+        .any(|flight| flight.flight_identifier == inside));
+
+    for identifier in [&inside, &outside] {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+}
@@ -0,0 +1,145 @@
+//! Skeleton and deadline-degraded getFlights responses against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Flight, GetFlightsRequest, GetFlightsResponse, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::{aircraft, flight};
+use svc_gis::types::{AircraftTelemetry, AircraftType, Position};
+
+const LATITUDE: f64 = 52.3645905;
+const LONGITUDE: f64 = 4.9160036;
+
+/// Flight plan start and end as returned in a flight
+fn bounds(flight: &Flight) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    (
+        flight.time_start.clone().map(Into::into),
+        flight.time_end.clone().map(Into::into),
+    )
+}
+
+/// A reporting aircraft on a flight plan gets its state unless only the
+///  skeleton is requested or the deadline is too close, the flight time
+///  bounds are returned either way
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_flights_skeleton() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let identifier = format!("sk-{suffix}");
+
+    // Whole seconds survive the round trip through the database unchanged
+    let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+    let (time_start, time_end) = (
+        now - Duration::try_minutes(5).unwrap(),
+        now + Duration::try_minutes(25).unwrap(),
+    );
+
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: [LONGITUDE - 0.002, LONGITUDE + 0.002]
+                .iter()
+                .map(|longitude| PointZ {
+                    latitude: LATITUDE,
+                    longitude: *longitude,
+                    altitude_meters: 100.0,
+                })
+                .collect(),
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    aircraft::update_aircraft_telemetry(
+        vec![AircraftTelemetry {
+            identifier: identifier.clone(),
+            position: Position {
+                latitude: LATITUDE,
+                longitude: LONGITUDE,
+                altitude_meters: 100.0,
+            },
+            velocity_horizontal_ground_mps: 10.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: 0.0,
+            track_angle_degrees: 90.0,
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+        }],
+        false,
+    )
+    .await
+    .expect("telemetry update failed");
+
+    let request = GetFlightsRequest {
+        window_min_x: LONGITUDE - 0.001,
+        window_min_y: LATITUDE - 0.001,
+        window_max_x: LONGITUDE + 0.001,
+        window_max_y: LATITUDE + 0.001,
+        time_start: Some(now.into()),
+        time_end: Some((now + Duration::try_minutes(1).unwrap()).into()),
+        velocity_samples: 1,
+        ..Default::default()
+    };
+
+    let find = |response: &GetFlightsResponse| {
+        response
+            .flights
+            .iter()
+            .find(|flight| flight.session_id.as_deref() == Some(identifier.as_str()))
+            .cloned()
+            .expect("flight not found")
+    };
+
+    // No deadline, fully enriched
+    let response = flight::get_flights_until(request.clone(), None)
+        .await
+        .expect("could not get flights");
+    assert!(!response.partial);
+    let full = find(&response);
+    assert!(full.state.is_some());
+    assert_eq!(full.velocity_samples.len(), 1);
+    assert_eq!(bounds(&full), (Some(time_start), Some(time_end)));
+
+    // Requested skeleton
+    let response = flight::get_flights_until(
+        GetFlightsRequest {
+            skeleton_only: true,
+            ..request.clone()
+        },
+        None,
+    )
+    .await
+    .expect("could not get flights");
+    assert!(response.partial);
+    let skeleton = find(&response);
+    assert!(skeleton.state.is_none());
+    assert!(skeleton.velocity_samples.is_empty());
+    assert_eq!(skeleton.aircraft_id, full.aircraft_id);
+    assert_eq!(bounds(&skeleton), (Some(time_start), Some(time_end)));
+
+    // Deadline already passed, degraded to the skeleton
+    let response = flight::get_flights_until(request, Some(std::time::Instant::now()))
+        .await
+        .expect("could not get flights");
+    assert!(response.partial);
+    let degraded = find(&response);
+    assert!(degraded.state.is_none());
+    assert_eq!(bounds(&degraded), (Some(time_start), Some(time_end)));
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
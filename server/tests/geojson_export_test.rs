@@ -0,0 +1,93 @@
+//! Streaming GeoJSON export of many aircraft against a live database
+
+mod common;
+
+use chrono::Utc;
+use futures::StreamExt;
+use std::collections::HashSet;
+use svc_gis::postgis::export::{self, MAX_CHUNK_BYTES, MAX_FEATURES_PER_CHUNK};
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Number of synthetic aircraft exported
+const AIRCRAFT_COUNT: usize = 50_000;
+
+/// Aircraft inserted per update
+const BATCH_SIZE: usize = 1_000;
+
+/// 50k aircraft are exported in bounded chunks of complete features, and
+///  dropping the stream early returns the connection to the pool
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_aircraft_geojson_stream() {
+    let (_, pool) = common::setup().await;
+
+    // Identifiers are limited to 20 characters
+    let prefix = format!("ge{}", Utc::now().timestamp_micros() % 1_000_000);
+    let identifiers = (0..AIRCRAFT_COUNT)
+        .map(|i| format!("{prefix}-{i}"))
+        .collect::<Vec<_>>();
+
+    for batch in identifiers.chunks(BATCH_SIZE) {
+        let items = batch
+            .iter()
+            .enumerate()
+            .map(|(i, identifier)| AircraftPosition {
+                identifier: identifier.clone(),
+                position: Position {
+                    latitude: 52.0 + i as f64 * 1e-4,
+                    longitude: 4.9,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            })
+            .collect();
+
+        svc_gis::postgis::aircraft::update_aircraft_position(items, false)
+            .await
+            .expect("position update failed");
+    }
+
+    let mut stream = Box::pin(
+        export::aircraft_geojson_stream(true, None)
+            .await
+            .expect("could not start export"),
+    );
+
+    let mut exported = HashSet::new();
+    let mut sequence = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.expect("export failed");
+        assert_eq!(chunk.sequence, sequence);
+        assert!(chunk.feature_count as usize <= MAX_FEATURES_PER_CHUNK);
+        assert!(chunk.features.len() <= MAX_CHUNK_BYTES);
+        sequence += 1;
+
+        for line in chunk.features.lines() {
+            let feature: serde_json::Value =
+                serde_json::from_str(line).expect("chunk line is not valid JSON");
+            assert_eq!(feature["type"], "Feature");
+            if let Some(id) = feature["id"].as_str() {
+                exported.insert(id.to_string());
+            }
+        }
+    }
+
+    assert!(identifiers.iter().all(|id| exported.contains(id)));
+
+    // Cancelling after the first chunk releases the connection
+    let available = pool.status().available;
+    let mut stream = Box::pin(
+        export::aircraft_geojson_stream(true, None)
+            .await
+            .expect("could not start export"),
+    );
+    assert!(stream.next().await.is_some());
+    drop(stream);
+    assert_eq!(pool.status().available, available);
+}
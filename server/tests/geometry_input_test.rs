@@ -0,0 +1,239 @@
+//! WKT and WKB geometry inputs against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use postgis::ewkb::{AsEwkbLineString, AsEwkbPolygon, EwkbWrite, LineStringT, PolygonT};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, PointZ, UpdateFlightPathRequest, Zone, ZoneType,
+};
+use svc_gis::postgis::{flight, zone, DEFAULT_SRID, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3345905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f32 = 110.0;
+
+/// Gets the stored geometry of a row as EWKB
+async fn stored_geometry(
+    pool: &deadpool_postgres::Pool,
+    table: &str,
+    column: &str,
+    identifier: &str,
+) -> Vec<u8> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(
+                r#"SELECT ST_AsEWKB("geom") FROM "{PSQL_SCHEMA}"."{table}" WHERE "{column}" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get geometry")
+        .get(0)
+}
+
+/// The same path stored from points, WKT and EWKB, and the same zone from
+///  vertices, WKT and WKB, are stored identically
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_geometry_input_round_trip() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+
+    // Flight paths
+    let path: Vec<PointZ> = [LONGITUDE - 0.002, LONGITUDE, LONGITUDE + 0.002]
+        .iter()
+        .map(|longitude| PointZ {
+            latitude: LATITUDE,
+            longitude: *longitude,
+            altitude_meters: ALTITUDE,
+        })
+        .collect();
+
+    let wkt = format!(
+        "SRID={DEFAULT_SRID};LINESTRING Z ({})",
+        path.iter()
+            .map(|p| format!("{} {} {}", p.longitude, p.latitude, p.altitude_meters))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let line = LineStringT {
+        points: path
+            .iter()
+            .map(|p| {
+                postgis::ewkb::PointZ::new(
+                    p.longitude,
+                    p.latitude,
+                    p.altitude_meters as f64,
+                    Some(DEFAULT_SRID),
+                )
+            })
+            .collect(),
+        srid: Some(DEFAULT_SRID),
+    };
+    let mut ewkb = vec![];
+    line.as_ewkb()
+        .write_ewkb(&mut ewkb)
+        .expect("could not write EWKB");
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let request = UpdateFlightPathRequest {
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    let flights = [
+        (
+            format!("gp-{suffix}"),
+            UpdateFlightPathRequest {
+                path: path.clone(),
+                ..request.clone()
+            },
+        ),
+        (
+            format!("gw-{suffix}"),
+            UpdateFlightPathRequest {
+                geom_wkt: Some(wkt),
+                ..request.clone()
+            },
+        ),
+        (
+            format!("ge-{suffix}"),
+            UpdateFlightPathRequest {
+                geom_ewkb: Some(ewkb),
+                ..request.clone()
+            },
+        ),
+    ];
+
+    for (identifier, request) in &flights {
+        flight::update_flight_path(
+            UpdateFlightPathRequest {
+                flight_identifier: Some(identifier.clone()),
+                aircraft_identifier: Some(identifier.clone()),
+                ..request.clone()
+            },
+            config.max_flight_duration_secs,
+        )
+        .await
+        .expect("flight update failed");
+    }
+
+    let expected = stored_geometry(&pool, "flights", "flight_identifier", &flights[0].0).await;
+    for (identifier, _) in &flights[1..] {
+        assert_eq!(
+            stored_geometry(&pool, "flights", "flight_identifier", identifier).await,
+            expected,
+            "{identifier}"
+        );
+    }
+
+    // Points and a geometry together
+    let error = flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(format!("gx-{suffix}")),
+            aircraft_identifier: Some(format!("gx-{suffix}")),
+            path,
+            geom_wkt: Some("LINESTRING Z (4.9 52.3 100, 5.0 52.3 100)".to_string()),
+            ..request
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("only one"), "{error}");
+
+    for (identifier, _) in &flights {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+
+    // Zones
+    let vertices: Vec<Coordinates> = [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+        .iter()
+        .map(|(dy, dx)| Coordinates {
+            latitude: LATITUDE + dy * 0.0001,
+            longitude: LONGITUDE + dx * 0.0001,
+        })
+        .collect();
+
+    let wkt = format!(
+        "MULTIPOLYGON ((({})))",
+        vertices
+            .iter()
+            .map(|v| format!("{} {}", v.longitude, v.latitude))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let polygon = PolygonT {
+        rings: vec![LineStringT {
+            points: vertices
+                .iter()
+                .map(|v| postgis::ewkb::Point::new(v.longitude, v.latitude, None))
+                .collect(),
+            srid: None,
+        }],
+        srid: None,
+    };
+    let mut wkb = vec![];
+    polygon
+        .as_ewkb()
+        .write_ewkb(&mut wkb)
+        .expect("could not write WKB");
+
+    let request = Zone {
+        zone_type: ZoneType::Restriction as i32,
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 100.0,
+        ..Default::default()
+    };
+
+    let zones = vec![
+        Zone {
+            identifier: format!("zv-{suffix}"),
+            vertices,
+            ..request.clone()
+        },
+        Zone {
+            identifier: format!("zw-{suffix}"),
+            geom_wkt: Some(wkt),
+            ..request.clone()
+        },
+        Zone {
+            identifier: format!("ze-{suffix}"),
+            geom_ewkb: Some(wkb),
+            ..request
+        },
+    ];
+
+    let identifiers: Vec<String> = zones.iter().map(|z| z.identifier.clone()).collect();
+    zone::update_zones(zones, false)
+        .await
+        .expect("zone update failed");
+
+    let expected = stored_geometry(&pool, "zones", "identifier", &identifiers[0]).await;
+    for identifier in &identifiers[1..] {
+        assert_eq!(
+            stored_geometry(&pool, "zones", "identifier", identifier).await,
+            expected,
+            "{identifier}"
+        );
+    }
+
+    for identifier in &identifiers {
+        zone::delete_zone(identifier)
+            .await
+            .expect("could not delete zone");
+    }
+}
@@ -0,0 +1,134 @@
+//! Aircraft history archival and track stitching against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::GetAircraftTrackRequest;
+use svc_gis::postgis::archive::{self, ArchivePolicy};
+use svc_gis::postgis::{aircraft, PSQL_SCHEMA};
+
+/// Seconds between the inserted positions
+const INTERVAL_SECS: i64 = 10;
+
+/// Inserted positions, two hours of history
+const POSITIONS: i64 = 720;
+
+/// Archives an hour of full-rate history to one minute buckets, the track
+///  continues from the last archived point to the first full-rate point
+///  without a gap or duplicate
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_history_archive() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("ha-{}", common::unique_suffix());
+    let now = Utc::now().timestamp();
+    let start = now - now.rem_euclid(60) - POSITIONS * INTERVAL_SECS;
+
+    // Altitude increases by a meter per position
+    let client = pool.get().await.expect("could not get client");
+    client
+        .execute(
+            &format!(
+                r#"INSERT INTO "{PSQL_SCHEMA}"."aircraft_history" (
+                    "identifier", "geom", "timestamp_network", "velocity_horizontal_ground_mps"
+                )
+                SELECT
+                    $1,
+                    ST_SetSRID(ST_MakePoint(4.9160036, 52.3745905, 100 + s), 4326),
+                    to_timestamp($2::FLOAT8 + s * $3::FLOAT8),
+                    10.0
+                FROM generate_series(0, $4::INTEGER - 1) AS s;"#
+            ),
+            &[
+                &identifier,
+                &(start as f64),
+                &(INTERVAL_SECS as f64),
+                &(POSITIONS as i32),
+            ],
+        )
+        .await
+        .expect("could not insert history");
+
+    let policy = ArchivePolicy {
+        hot_retention_secs: 3600,
+        archive_retention_secs: 0,
+        bucket_secs: 60,
+    };
+
+    let summary = archive::archive_history(policy)
+        .await
+        .expect("archival failed");
+    assert!(summary.archived > 0);
+
+    // Nothing left to archive, archived buckets aren't duplicated
+    archive::archive_history(policy)
+        .await
+        .expect("archival failed");
+
+    let track = aircraft::get_aircraft_track(GetAircraftTrackRequest {
+        identifier: identifier.clone(),
+        time_start: Some(DateTime::<Utc>::from_timestamp(start, 0).unwrap().into()),
+        time_end: Some((Utc::now() + Duration::try_minutes(1).unwrap()).into()),
+        ..Default::default()
+    })
+    .await
+    .expect("could not get track");
+
+    let points: Vec<(DateTime<Utc>, u32)> = track
+        .positions
+        .iter()
+        .map(|position| {
+            let timestamp: DateTime<Utc> = position.timestamp.clone().unwrap().into();
+            (timestamp, position.bucket_seconds)
+        })
+        .collect();
+
+    // No duplicates
+    assert!(points.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    // Archived points precede the full-rate points
+    let hot = points
+        .iter()
+        .position(|(_, bucket_seconds)| *bucket_seconds == 0)
+        .expect("no full-rate points");
+    assert!(hot > 0);
+    assert!(points[..hot]
+        .iter()
+        .all(|(_, bucket_seconds)| *bucket_seconds == 60));
+    assert!(points[hot..]
+        .iter()
+        .all(|(_, bucket_seconds)| *bucket_seconds == 0));
+
+    // No gap at the boundary, which is aligned to a bucket
+    let boundary = points[hot].0;
+    assert_eq!(boundary.timestamp().rem_euclid(60), 0);
+    assert_eq!((boundary - points[hot - 1].0).num_seconds(), INTERVAL_SECS);
+
+    // Each archived minute keeps its first and last position
+    let archived = (boundary.timestamp() - start) / INTERVAL_SECS;
+    assert!(summary.archived as i64 >= archived);
+    assert_eq!(hot as i64, archived / 3);
+    assert_eq!((points.len() - hot) as i64, POSITIONS - archived);
+    assert_eq!(track.point_count as usize, points.len());
+
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT "altitude_meters_min", "altitude_meters_max", "point_count"
+                FROM "{PSQL_SCHEMA}"."aircraft_history_archive"
+                WHERE "identifier" = $1
+                ORDER BY "bucket_start" ASC
+                LIMIT 1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get archived bucket");
+
+    let (min, max, count): (f64, f64, i32) = (row.get(0), row.get(1), row.get(2));
+    assert_eq!((min, max, count), (100.0, 105.0, 6));
+}
@@ -0,0 +1,59 @@
+//! Aircraft history extent against a live database
+
+mod common;
+
+use chrono::{Duration, DurationRound, Utc};
+use svc_gis::postgis::aircraft;
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Number of position updates sent for the aircraft
+const UPDATE_COUNT: i64 = 6;
+
+/// The extent spans the first and last stored positions, and is `None`
+///  for an aircraft without history
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_history_extent() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("he{}", common::unique_suffix());
+    let extent = aircraft::get_history_extent(&identifier, &pool)
+        .await
+        .expect("could not get extent");
+    assert!(extent.is_none());
+
+    // Positions every five minutes over the last half hour, out of order
+    let start = (Utc::now() - Duration::try_minutes(30).unwrap())
+        .duration_trunc(Duration::try_seconds(1).unwrap())
+        .unwrap();
+    let step = Duration::try_minutes(5).unwrap();
+    for i in (0..UPDATE_COUNT).rev() {
+        let item = AircraftPosition {
+            identifier: identifier.clone(),
+            position: Position {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: start + step * i as i32,
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        };
+
+        aircraft::update_aircraft_position(vec![item], false)
+            .await
+            .expect("position update failed");
+    }
+
+    let (first, last) = aircraft::get_history_extent(&identifier, &pool)
+        .await
+        .expect("could not get extent")
+        .expect("no extent for the aircraft");
+
+    assert_eq!(first, start);
+    assert_eq!(last, start + step * (UPDATE_COUNT - 1) as i32);
+}
@@ -0,0 +1,118 @@
+//! Accelerated replay of aircraft history against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::postgis::{aircraft, replay, PSQL_SCHEMA};
+use svc_gis::types::{AircraftTelemetry, Position};
+
+const LATITUDE: f64 = 52.3245905;
+const LONGITUDE: f64 = 4.9160036;
+const SNAPSHOTS: usize = 5;
+
+/// Longitudes of the history rows written after a time, oldest first
+async fn replayed_longitudes(
+    pool: &deadpool_postgres::Pool,
+    identifier: &str,
+    since: DateTime<Utc>,
+) -> Vec<f64> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query(
+            &format!(
+                r#"SELECT ST_X("geom") FROM "{PSQL_SCHEMA}"."aircraft_history"
+                WHERE "identifier" = $1 AND "timestamp_network" >= $2
+                ORDER BY "timestamp_network";"#
+            ),
+            &[&identifier, &since],
+        )
+        .await
+        .expect("could not get history")
+        .iter()
+        .map(|row| row.get(0))
+        .collect()
+}
+
+/// Recorded snapshots ten seconds apart are replayed twenty times faster,
+///  in order, and a replay stopped early applies fewer updates
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_replay_history() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("rp-{}", common::unique_suffix());
+    let recorded_start = Utc::now() - Duration::try_minutes(10).unwrap();
+    let longitudes: Vec<f64> = (0..SNAPSHOTS)
+        .map(|index| LONGITUDE + index as f64 * 0.0005)
+        .collect();
+
+    for (index, longitude) in longitudes.iter().enumerate() {
+        aircraft::update_aircraft_telemetry(
+            vec![AircraftTelemetry {
+                identifier: identifier.clone(),
+                position: Position {
+                    latitude: LATITUDE,
+                    longitude: *longitude,
+                    altitude_meters: 100.0,
+                },
+                velocity_horizontal_ground_mps: 5.0,
+                velocity_horizontal_air_mps: None,
+                velocity_vertical_mps: 0.0,
+                track_angle_degrees: 90.0,
+                timestamp_network: recorded_start
+                    + Duration::try_seconds(10 * index as i64).unwrap(),
+                timestamp_asset: None,
+            }],
+            false,
+        )
+        .await
+        .expect("telemetry update failed");
+    }
+
+    let recorded_end = recorded_start + Duration::try_minutes(1).unwrap();
+
+    // 40 seconds of history in two seconds
+    let replay_start = Utc::now();
+    let handle = replay::replay_history(&identifier, recorded_start, recorded_end, 20.0, &pool)
+        .await
+        .expect("could not start replay");
+
+    let applied = handle.finish().await.expect("replay failed");
+    let elapsed = Utc::now() - replay_start;
+    assert_eq!(applied, SNAPSHOTS as u64);
+    assert!(
+        elapsed >= Duration::try_milliseconds(1900).unwrap(),
+        "{elapsed}"
+    );
+    assert!(elapsed < Duration::try_seconds(10).unwrap(), "{elapsed}");
+
+    let replayed = replayed_longitudes(&pool, &identifier, replay_start).await;
+    assert_eq!(replayed.len(), SNAPSHOTS);
+    for (replayed, recorded) in replayed.iter().zip(&longitudes) {
+        assert!(
+            (replayed - recorded).abs() < 1e-6,
+            "{replayed} != {recorded}"
+        );
+    }
+
+    // At the recorded speed only the first snapshot is due right away
+    let handle = replay::replay_history(&identifier, recorded_start, recorded_end, 1.0, &pool)
+        .await
+        .expect("could not start replay");
+
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert!(!handle.is_finished());
+    let applied = handle.stop().await.expect("replay failed");
+    assert_eq!(applied, 1);
+
+    let error = replay::replay_history(&identifier, recorded_start, recorded_end, 0.0, &pool)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        svc_gis::postgis::PostgisError::Replay(replay::ReplayError::SpeedFactor)
+    );
+}
@@ -0,0 +1,144 @@
+//! Plausibility check of aircraft positions against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use std::sync::atomic::Ordering;
+use svc_gis::postgis::aircraft::{self, DEFAULT_RELOCATION_REPORTS, IMPLAUSIBLE_POSITIONS};
+use svc_gis::postgis::PSQL_SCHEMA;
+use svc_gis::types::{AircraftPosition, AircraftTelemetry, Position};
+
+/// A position `degrees_north` north of a reference point
+fn position(identifier: &str, degrees_north: f64, seconds: i64) -> AircraftPosition {
+    AircraftPosition {
+        identifier: identifier.to_string(),
+        position: Position {
+            latitude: 52.3745905 + degrees_north,
+            longitude: 4.9160036,
+            altitude_meters: 100.0,
+        },
+        timestamp_network: Utc::now() - Duration::try_minutes(1).unwrap()
+            + Duration::try_seconds(seconds).unwrap(),
+        timestamp_asset: None,
+        horizontal_accuracy_meters: None,
+        vertical_accuracy_meters: None,
+    }
+}
+
+/// Telemetry at a position `degrees_north` north of a reference point
+fn telemetry(identifier: &str, degrees_north: f64, seconds: i64) -> AircraftTelemetry {
+    let position = position(identifier, degrees_north, seconds);
+    AircraftTelemetry {
+        identifier: position.identifier,
+        position: position.position,
+        velocity_horizontal_ground_mps: 10.0,
+        velocity_horizontal_air_mps: None,
+        velocity_vertical_mps: 0.0,
+        track_angle_degrees: 0.0,
+        timestamp_network: position.timestamp_network,
+        timestamp_asset: None,
+    }
+}
+
+/// A jump of ~80 km in a second is quarantined, consistent reports at the
+///  new location are accepted once there are enough of them
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_implausible_position() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("ip{}", common::unique_suffix());
+    aircraft::update_aircraft_position(vec![position(&identifier, 0.0, 0)], false)
+        .await
+        .expect("position update failed");
+
+    // ~80 km north, one report short of a relocation
+    let relocation_reports = DEFAULT_RELOCATION_REPORTS as i64;
+    for i in 1..relocation_reports {
+        aircraft::update_aircraft_position(
+            vec![position(&identifier, 0.72 + i as f64 * 1e-5, i)],
+            false,
+        )
+        .await
+        .expect("position update failed");
+
+        let point = aircraft::get_aircraft_pointz(&identifier)
+            .await
+            .expect("could not get position");
+        assert!((point.y - 52.3745905).abs() < 1e-6);
+    }
+
+    assert!(IMPLAUSIBLE_POSITIONS.load(Ordering::Relaxed) >= (relocation_reports - 1) as u64);
+
+    let client = pool.get().await.expect("could not get client");
+    let outliers: i64 = client
+        .query_one(
+            &format!(
+                r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."aircraft_history"
+                WHERE "identifier" = $1 AND "outlier";"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count outliers")
+        .get(0);
+    assert_eq!(outliers, relocation_reports - 1);
+
+    // The next consistent report re-establishes the aircraft
+    let latitude = 0.72 + relocation_reports as f64 * 1e-5;
+    aircraft::update_aircraft_position(
+        vec![position(&identifier, latitude, relocation_reports)],
+        false,
+    )
+    .await
+    .expect("position update failed");
+
+    let point = aircraft::get_aircraft_pointz(&identifier)
+        .await
+        .expect("could not get position");
+    assert!((point.y - (52.3745905 + latitude)).abs() < 1e-6);
+}
+
+/// Telemetry is checked like positions: a jump of ~80 km in a second is
+///  only kept in the history, flagged as an outlier
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_implausible_telemetry() {
+    let (_, pool) = common::setup().await;
+
+    let identifier = format!("it{}", common::unique_suffix());
+    aircraft::update_aircraft_telemetry(vec![telemetry(&identifier, 0.0, 0)], false)
+        .await
+        .expect("telemetry update failed");
+
+    let implausible = IMPLAUSIBLE_POSITIONS.load(Ordering::Relaxed);
+    aircraft::update_aircraft_telemetry(vec![telemetry(&identifier, 0.72, 1)], false)
+        .await
+        .expect("telemetry update failed");
+    assert!(IMPLAUSIBLE_POSITIONS.load(Ordering::Relaxed) > implausible);
+
+    let point = aircraft::get_aircraft_pointz(&identifier)
+        .await
+        .expect("could not get position");
+    assert!((point.y - 52.3745905).abs() < 1e-6);
+
+    let client = pool.get().await.expect("could not get client");
+    let outliers: i64 = client
+        .query_one(
+            &format!(
+                r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."aircraft_history"
+                WHERE "identifier" = $1 AND "outlier";"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count outliers")
+        .get(0);
+    assert_eq!(outliers, 1);
+}
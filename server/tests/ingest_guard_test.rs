@@ -0,0 +1,174 @@
+//! Aircraft update ingest guard against a live database
+
+mod common;
+
+use chrono::Utc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use svc_gis::postgis::ingest::{INGEST_QUEUE_DEPTH, INGEST_SHED};
+use svc_gis::postgis::{aircraft, telemetry, PSQL_SCHEMA};
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Aircraft with pending records before the others are shed
+const CAPACITY: usize = 5;
+
+/// Encodes a binary telemetry record near Amsterdam
+fn encode(index: u32, altitude_m: i16) -> Vec<u8> {
+    let mut bytes = vec![];
+    bytes.extend_from_slice(&index.to_le_bytes());
+    bytes.extend_from_slice(&52_374_590i32.to_le_bytes());
+    bytes.extend_from_slice(&4_916_003i32.to_le_bytes());
+    bytes.extend_from_slice(&altitude_m.to_le_bytes());
+    bytes.extend_from_slice(&0i16.to_le_bytes());
+    bytes.extend_from_slice(&0i16.to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&Utc::now().timestamp_millis().to_le_bytes());
+    bytes
+}
+
+/// A position near Amsterdam
+fn position(identifier: &str, altitude_meters: f64) -> AircraftPosition {
+    AircraftPosition {
+        identifier: identifier.to_string(),
+        position: Position {
+            latitude: 52.3745905,
+            longitude: 4.9160036,
+            altitude_meters,
+        },
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+        horizontal_accuracy_meters: None,
+        vertical_accuracy_meters: None,
+    }
+}
+
+/// Gets the stored altitude of an aircraft
+async fn stored_altitude(pool: &deadpool_postgres::Pool, identifier: &str) -> Option<f64> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_opt(
+            &format!(
+                r#"SELECT ST_Z("geom") FROM "{PSQL_SCHEMA}"."aircraft" WHERE "identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get aircraft")
+        .map(|row| row.get(0))
+}
+
+/// While the aircraft table is locked ingestion of binary telemetry and
+///  positions doesn't block and the queues stay bounded, once released the
+///  writers catch up with the latest updates without a restart
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_ingest_guard() {
+    let (_, pool) = common::setup().await;
+
+    aircraft::start_ingest_writers(2, CAPACITY).expect("could not start writers");
+
+    let suffix = common::unique_suffix();
+    let mut aircraft = vec![];
+    for i in 0..(CAPACITY + 3) {
+        let identifier = format!("ig-{suffix}-{i}");
+        let index = telemetry::register_identifier(&identifier)
+            .await
+            .expect("could not register identifier");
+        aircraft.push((identifier, index));
+    }
+
+    let positioned = format!("ig-{suffix}-p");
+
+    // Pause the database for aircraft updates
+    let mut locker = pool.get().await.expect("could not get client");
+    let lock = locker
+        .transaction()
+        .await
+        .expect("could not start transaction");
+    lock.batch_execute(&format!(
+        r#"LOCK TABLE "{PSQL_SCHEMA}"."aircraft" IN ACCESS EXCLUSIVE MODE;"#
+    ))
+    .await
+    .expect("could not lock aircraft table");
+
+    let shed = INGEST_SHED.load(Ordering::Relaxed);
+    let rounds: i16 = 200;
+    let mut coalesced = 0;
+    for round in 0..rounds {
+        let payload: Vec<u8> = aircraft
+            .iter()
+            .flat_map(|(_, index)| encode(*index, 100 + round))
+            .collect();
+
+        let outcome = tokio::time::timeout(
+            Duration::from_secs(1),
+            telemetry::ingest_binary(&payload, false, None),
+        )
+        .await
+        .expect("ingestion blocked on the database")
+        .expect("ingestion failed");
+
+        coalesced += outcome.coalesced;
+
+        let altitude = (100 + round) as f64;
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            aircraft::update_aircraft_position(vec![position(&positioned, altitude)], false),
+        )
+        .await
+        .expect("position update blocked on the database")
+        .expect("position update failed");
+
+        // One queue each for positions and telemetry
+        assert!(INGEST_QUEUE_DEPTH.load(Ordering::Relaxed) <= 2 * CAPACITY as u64);
+
+        // Let the writers pick up records and block on the lock
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    assert!(coalesced > 0);
+    assert!(INGEST_SHED.load(Ordering::Relaxed) > shed);
+
+    // Pending since the writers got stuck, over a second ago
+    assert!(aircraft::ingest_writer_lag() >= Duration::from_millis(500));
+
+    // Resume the database
+    lock.rollback().await.expect("could not release lock");
+
+    let (identifier, _) = &aircraft[0];
+    let latest = (100 + rounds - 1) as f64;
+    let mut altitude = None;
+    for _ in 0..100 {
+        altitude = stored_altitude(&pool, identifier).await;
+        if altitude == Some(latest) && INGEST_QUEUE_DEPTH.load(Ordering::Relaxed) == 0 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(altitude, Some(latest));
+    assert_eq!(stored_altitude(&pool, &positioned).await, Some(latest));
+    assert_eq!(INGEST_QUEUE_DEPTH.load(Ordering::Relaxed), 0);
+    assert_eq!(aircraft::ingest_writer_lag(), Duration::ZERO);
+
+    // New records are written as usual
+    let (identifier, index) = &aircraft[CAPACITY + 2];
+    let outcome = telemetry::ingest_binary(&encode(*index, 42), false, None)
+        .await
+        .expect("ingestion failed");
+    assert_eq!(outcome.accepted, 1);
+
+    for _ in 0..100 {
+        if stored_altitude(&pool, identifier).await == Some(42.0) {
+            return;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("records were not written after the database resumed");
+}
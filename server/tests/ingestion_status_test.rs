@@ -0,0 +1,156 @@
+//! Ingestion queue status against a live Redis server
+
+mod common;
+
+use chrono::{Duration, Utc};
+use deadpool_redis::redis;
+use svc_gis::cache::status;
+use svc_gis::types::{
+    AircraftPosition, AircraftType, FlightPathMessage, Position, REDIS_KEY_AIRCRAFT_POSITION,
+    REDIS_KEY_FLIGHT_PATH,
+};
+
+/// Messages pushed to the queue
+const BACKLOG: u64 = 3;
+
+/// Age of the pushed messages
+const BACKLOG_AGE_SECS: i64 = 30;
+
+/// Sets up the Redis pool inspected for the status, once per test binary
+fn setup() -> deadpool_redis::Pool {
+    status::STATUS_POOL
+        .get_or_init(|| {
+            let config = svc_gis::Config::try_from_env().expect("could not load config");
+            config
+                .redis
+                .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+                .expect("could not create Redis pool")
+        })
+        .clone()
+}
+
+/// Gets the reported status of a queue
+async fn queue_status(queue: &str) -> (u64, Option<u64>) {
+    let response = status::get_ingestion_status()
+        .await
+        .expect("could not get ingestion status");
+
+    let queue = response
+        .queues
+        .into_iter()
+        .find(|status| status.queue == queue)
+        .expect("queue not reported");
+
+    (queue.depth, queue.oldest_age_ms)
+}
+
+/// Pushes messages at the head of a queue, where the oldest message is
+///  read, checks the reported status, then removes them
+async fn check_backlog(pool: &deadpool_redis::Pool, queue: &str, messages: &[Vec<u8>]) {
+    let mut connection = pool.get().await.expect("could not get connection");
+    let (depth, _) = queue_status(queue).await;
+
+    for message in messages {
+        let _: u64 = redis::cmd("LPUSH")
+            .arg(queue)
+            .arg(message)
+            .query_async(&mut connection)
+            .await
+            .expect("could not push message");
+    }
+
+    let (backlog_depth, oldest_age_ms) = queue_status(queue).await;
+    assert_eq!(backlog_depth, depth + messages.len() as u64);
+
+    let oldest_age_ms = oldest_age_ms.expect("no age of the oldest message");
+    assert!(oldest_age_ms >= (BACKLOG_AGE_SECS * 1000) as u64);
+
+    // Also kept for inspection
+    let gauge = status::queue_gauges()
+        .into_iter()
+        .find(|status| status.queue == queue)
+        .expect("queue gauge not kept");
+    assert_eq!(gauge.depth, backlog_depth);
+
+    for message in messages {
+        let _: u64 = redis::cmd("LREM")
+            .arg(queue)
+            .arg(1)
+            .arg(message)
+            .query_async(&mut connection)
+            .await
+            .expect("could not remove message");
+    }
+
+    assert_eq!(queue_status(queue).await.0, depth);
+}
+
+/// A backlog of queued messages is reflected in the reported depth and
+///  age of the oldest message
+///
+/// Requires a Redis server configured through the environment (see
+///  `.env.repo`), without consumers running: `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_ingestion_backlog() {
+    let pool = setup();
+
+    let suffix = common::unique_suffix();
+    let timestamp_network = Utc::now() - Duration::try_seconds(BACKLOG_AGE_SECS).unwrap();
+    let messages = (0..BACKLOG)
+        .map(|index| {
+            serde_json::to_vec(&AircraftPosition {
+                identifier: format!("is-{suffix}-{index}"),
+                position: Position {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network,
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            })
+            .expect("could not serialize position")
+        })
+        .collect::<Vec<_>>();
+
+    check_backlog(&pool, REDIS_KEY_AIRCRAFT_POSITION, &messages).await;
+}
+
+/// A backlog of queued flight paths, which have no network timestamp, is
+///  aged from the time they were queued
+///
+/// Requires a Redis server configured through the environment (see
+///  `.env.repo`), without consumers running: `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_path_backlog() {
+    let pool = setup();
+
+    let suffix = common::unique_suffix();
+    let timestamp_enqueued = Utc::now() - Duration::try_seconds(BACKLOG_AGE_SECS).unwrap();
+    let timestamp_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let messages = (0..BACKLOG)
+        .map(|index| {
+            serde_json::to_vec(&FlightPathMessage {
+                correlation_id: None,
+                flight_identifier: Some(format!("is-{suffix}-{index}")),
+                aircraft_identifier: Some(format!("is-{suffix}-{index}")),
+                simulated: false,
+                aircraft_type: AircraftType::Rotorcraft,
+                path: vec![],
+                timestamp_start,
+                timestamp_end: timestamp_start + Duration::try_minutes(10).unwrap(),
+                allow_rebind: false,
+                operator_id: None,
+                destination_identifier: None,
+                scenario_id: None,
+                timestamp_enqueued: Some(timestamp_enqueued),
+            })
+            .expect("could not serialize flight path")
+        })
+        .collect::<Vec<_>>();
+
+    check_backlog(&pool, REDIS_KEY_FLIGHT_PATH, &messages).await;
+}
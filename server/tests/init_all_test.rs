@@ -0,0 +1,85 @@
+//! Module initialization on a fresh database against a live server
+
+mod common;
+
+use svc_gis::postgis::PSQL_SCHEMA;
+
+/// Names of the tables of the service schema and of every enum, sorted
+async fn relations(client: &deadpool_postgres::Client) -> Vec<String> {
+    client
+        .query(
+            r#"SELECT "table_name"::TEXT AS "name" FROM information_schema.tables
+                WHERE "table_schema"::TEXT = $1
+            UNION
+            SELECT "typname"::TEXT AS "name" FROM pg_type
+                WHERE "typtype" = 'e'
+            ORDER BY "name";"#,
+            &[&PSQL_SCHEMA],
+        )
+        .await
+        .expect("could not list relations")
+        .iter()
+        .map(|row| row.get("name"))
+        .collect()
+}
+
+/// Initializing a fresh database succeeds, and running it again changes
+///  nothing
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`), with a role allowed to create databases and the PostGIS
+///  extension: `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_init_all() {
+    let config = svc_gis::Config::try_from_env().expect("could not load config");
+    let admin_pool = svc_gis::postgis::pool::create_pool(config.clone());
+    let admin = admin_pool.get().await.expect("could not get client");
+
+    let suffix = common::unique_suffix();
+    let dbname = format!("gis_init_{suffix}");
+    admin
+        .batch_execute(&format!(r#"CREATE DATABASE "{dbname}";"#))
+        .await
+        .expect("could not create database");
+
+    let mut fresh = config.clone();
+    fresh.pg.dbname = Some(dbname.clone());
+    let pool = svc_gis::postgis::pool::create_pool(fresh);
+
+    {
+        let client = pool.get().await.expect("could not get client");
+        client
+            .batch_execute(&format!(
+                r#"CREATE SCHEMA IF NOT EXISTS {PSQL_SCHEMA};
+                CREATE EXTENSION IF NOT EXISTS postgis SCHEMA {PSQL_SCHEMA};"#
+            ))
+            .await
+            .expect("could not prepare database");
+    }
+
+    svc_gis::postgis::init_all(&pool)
+        .await
+        .expect("init_all failed on a fresh database");
+
+    let client = pool.get().await.expect("could not get client");
+    let initialized = relations(&client).await;
+    for name in ["aircraft", "aircrafttype", "flights", "vertiports", "zones"] {
+        assert!(
+            initialized.contains(&name.to_string()),
+            "{name} missing: {initialized:?}"
+        );
+    }
+
+    svc_gis::postgis::init_all(&pool)
+        .await
+        .expect("init_all failed when re-run");
+    assert_eq!(relations(&client).await, initialized);
+
+    drop(client);
+    pool.close();
+    admin
+        .batch_execute(&format!(r#"DROP DATABASE "{dbname}" WITH (FORCE);"#))
+        .await
+        .expect("could not drop database");
+}
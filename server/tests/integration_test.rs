@@ -7,3 +7,34 @@
 // fn it_add_one() {
 //     assert_eq!(2, tmp_lib::add_one(1));
 // }
+
+/// Replicas racing through initialization must all succeed
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn it_concurrent_psql_init() {
+    let config = svc_gis::Config::try_from_env().expect("could not load config");
+    let pool = svc_gis::postgis::pool::create_pool(config.clone());
+    svc_gis::postgis::DEADPOOL_POSTGIS
+        .set(pool)
+        .expect("could not set pool");
+
+    let tasks = (0..4)
+        .map(|_| {
+            let timeout = config.psql_init_lock_timeout_secs;
+            tokio::spawn(async move {
+                svc_gis::postgis::psql_init(timeout)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for task in tasks {
+        task.await
+            .expect("task panicked")
+            .expect("psql_init failed");
+    }
+}
@@ -0,0 +1,90 @@
+//! Flight intersection with a point against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use postgis::ewkb::PointZ;
+use svc_gis::grpc::server::grpc_server::{PointZ as GrpcPointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+const LATITUDE: f64 = 52.3745905;
+const LONGITUDE: f64 = 4.9160036;
+const ALTITUDE: f64 = 100.0;
+
+/// A point near a flight segment matches the flight, a point further away
+///  than the distance doesn't
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_intersecting_flight() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let flight_identifier = format!("itf-{suffix}");
+
+    // Due east along a line of latitude
+    let time_start = Utc::now();
+    let time_end = time_start + Duration::try_hours(1).unwrap();
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(flight_identifier.clone()),
+            aircraft_identifier: Some(format!("it-{suffix}")),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![
+                GrpcPointZ {
+                    latitude: LATITUDE,
+                    longitude: LONGITUDE,
+                    altitude_meters: ALTITUDE as f32,
+                },
+                GrpcPointZ {
+                    latitude: LATITUDE,
+                    longitude: LONGITUDE + 0.001,
+                    altitude_meters: ALTITUDE as f32,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    // 5 meters above the path
+    let point = PointZ::new(
+        LONGITUDE + 0.0005,
+        LATITUDE,
+        ALTITUDE + 5.0,
+        Some(svc_gis::postgis::DEFAULT_SRID),
+    );
+
+    let conflict = flight::get_intersecting_flight(point, 10.0, time_start, time_end, &pool)
+        .await
+        .expect("intersection query failed")
+        .expect("no flight found within the distance");
+
+    assert_eq!(conflict.flight_identifier, flight_identifier);
+    assert!((conflict.distance_meters - 5.0).abs() < 0.5);
+
+    // Outside of the separation distance
+    let result = flight::get_intersecting_flight(point, 2.0, time_start, time_end, &pool)
+        .await
+        .expect("intersection query failed");
+    assert!(result.is_none());
+
+    // Outside of the flight's time window
+    let result = flight::get_intersecting_flight(
+        point,
+        10.0,
+        time_end + Duration::try_hours(1).unwrap(),
+        time_end + Duration::try_hours(2).unwrap(),
+        &pool,
+    )
+    .await
+    .expect("intersection query failed");
+    assert!(result.is_none());
+}
@@ -0,0 +1,214 @@
+//! Persisted zone changes and flight lifecycle events against a live
+//!  database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use futures::{Stream, StreamExt};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, FlightEvent, FlightEventType, PointZ, UpdateFlightPathRequest, Zone,
+    ZoneChangeType, ZoneChanged, ZoneType,
+};
+use svc_gis::postgis::{flight, zone};
+use svc_gis::types::AircraftType;
+
+/// Time to wait for an event on the stream
+const STREAM_TIMEOUT_MS: u64 = 5_000;
+
+/// Gets the next event of a stream matching `filter`, other tests may
+///  publish in the meantime
+async fn next<T>(stream: &mut (impl Stream<Item = T> + Unpin), filter: impl Fn(&T) -> bool) -> T {
+    loop {
+        let event = tokio::time::timeout(
+            std::time::Duration::from_millis(STREAM_TIMEOUT_MS),
+            stream.next(),
+        )
+        .await
+        .expect("no event received")
+        .expect("stream ended");
+
+        if filter(&event) {
+            return event;
+        }
+    }
+}
+
+fn zone(identifier: &str) -> Zone {
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    Zone {
+        identifier: identifier.to_string(),
+        zone_type: ZoneType::Restriction as i32,
+        vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: latitude + dy * 0.0001,
+                longitude: longitude + dx * 0.0001,
+            })
+            .collect(),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 100.0,
+        ..Default::default()
+    }
+}
+
+/// A subscriber reconnecting with its last event id receives the zone
+///  changes it missed, dry runs are not published
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_zone_change_events() {
+    common::setup().await;
+
+    let identifier = format!("zc-{}", common::unique_suffix());
+    let ours = |change: &ZoneChanged| change.identifiers.contains(&identifier);
+
+    let mut live = Box::pin(zone::zone_change_stream(None));
+    zone::update_zones(vec![zone(&identifier)], true)
+        .await
+        .expect("dry run failed");
+    zone::update_zones(vec![zone(&identifier)], false)
+        .await
+        .expect("could not update zone");
+
+    let updated = next(&mut live, ours).await;
+    assert_eq!(updated.change_type, ZoneChangeType::Updated as i32);
+    assert!(updated.event_id > 0);
+
+    // Missed while disconnected
+    zone::delete_zone(&identifier)
+        .await
+        .expect("could not delete zone");
+    zone::restore_zone(&identifier, 60)
+        .await
+        .expect("could not restore zone");
+
+    let mut resumed = Box::pin(zone::zone_change_stream(Some(updated.event_id)));
+    for expected in [ZoneChangeType::Deleted, ZoneChangeType::Restored] {
+        let change = next(&mut resumed, ours).await;
+        assert_eq!(change.change_type, expected as i32);
+        assert_eq!(change.identifiers, vec![identifier.clone()]);
+        assert!(change.event_id > updated.event_id);
+    }
+
+    zone::delete_zone(&identifier)
+        .await
+        .expect("could not delete zone");
+}
+
+/// A subscriber reconnecting with its last event id receives the flight
+///  lifecycle steps it missed
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_flight_lifecycle_events() {
+    let (config, _) = common::setup().await;
+
+    let identifier = format!("fe-{}", common::unique_suffix());
+    let ours = |event: &FlightEvent| event.flight_identifier == identifier;
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+
+    let mut live = Box::pin(flight::flight_event_stream(None));
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![
+                PointZ {
+                    latitude,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude,
+                    longitude: longitude + 0.01,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    let filed = next(&mut live, ours).await;
+    assert_eq!(filed.event_type, FlightEventType::Filed as i32);
+    assert!(filed.event_id > 0);
+
+    // Missed while disconnected
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+    flight::restore_flight(&identifier, 60)
+        .await
+        .expect("could not restore flight");
+
+    let mut resumed = Box::pin(flight::flight_event_stream(Some(filed.event_id)));
+    for expected in [FlightEventType::Deleted, FlightEventType::Restored] {
+        let event = next(&mut resumed, ours).await;
+        assert_eq!(event.event_type, expected as i32);
+        assert!(event.event_id > filed.event_id);
+    }
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
+
+/// Zone changes committed concurrently are streamed in order of their
+///  event id, live and when resuming, so a resumed subscriber misses none
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_concurrent_events_in_order() {
+    common::setup().await;
+
+    const ZONES: usize = 8;
+    let prefix = format!("zo-{}", common::unique_suffix());
+    let ours = |change: &ZoneChanged| {
+        change
+            .identifiers
+            .iter()
+            .any(|identifier| identifier.starts_with(&prefix))
+    };
+
+    let mut live = Box::pin(zone::zone_change_stream(None));
+    let updates = (0..ZONES).map(|i| {
+        let identifier = format!("{prefix}-{i}");
+        tokio::spawn(async move { zone::update_zones(vec![zone(&identifier)], false).await })
+    });
+
+    for update in futures::future::join_all(updates).await {
+        update
+            .expect("update task failed")
+            .expect("could not update zone");
+    }
+
+    let mut live_ids = vec![];
+    for _ in 0..ZONES {
+        live_ids.push(next(&mut live, ours).await.event_id);
+    }
+
+    assert!(live_ids.windows(2).all(|ids| ids[0] < ids[1]));
+
+    let mut resumed = Box::pin(zone::zone_change_stream(Some(live_ids[0])));
+    for expected in &live_ids[1..] {
+        assert_eq!(next(&mut resumed, ours).await.event_id, *expected);
+    }
+
+    for i in 0..ZONES {
+        zone::delete_zone(&format!("{prefix}-{i}"))
+            .await
+            .expect("could not delete zone");
+    }
+}
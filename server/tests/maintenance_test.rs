@@ -0,0 +1,91 @@
+//! Maintenance of the hot tables against a live database
+
+mod common;
+
+use svc_gis::postgis::maintenance;
+
+/// Hot tables, as `(schema, table)`
+const TABLES: [(&str, &str); 2] = [("arrow", "aircraft"), ("arrow", "flight_segments")];
+
+/// Gets the `(analyze_count, vacuum_count)` of a table from the statistics
+///  views, each including the runs of the autovacuum daemon
+async fn maintenance_counts(
+    client: &deadpool_postgres::Client,
+    (schema, table): (&str, &str),
+) -> (i64, i64) {
+    // Make statistics of the other sessions visible to this one
+    client
+        .batch_execute("SELECT pg_stat_clear_snapshot();")
+        .await
+        .expect("could not clear statistics snapshot");
+
+    let row = client
+        .query_one(
+            r#"SELECT
+                ("analyze_count" + "autoanalyze_count")::BIGINT,
+                ("vacuum_count" + "autovacuum_count")::BIGINT
+            FROM "pg_stat_user_tables"
+            WHERE "schemaname" = $1 AND "relname" = $2;"#,
+            &[&schema, &table],
+        )
+        .await
+        .expect("could not get table statistics");
+
+    (row.get(0), row.get(1))
+}
+
+/// Waits for the statistics of a table to reach the expected counts, as
+///  they're reported asynchronously by the backend that ran the statement
+async fn wait_for_counts(
+    client: &deadpool_postgres::Client,
+    table: (&str, &str),
+    expected: (i64, i64),
+) -> (i64, i64) {
+    let mut counts = maintenance_counts(client, table).await;
+    for _ in 0..20 {
+        if counts.0 >= expected.0 && counts.1 >= expected.1 {
+            break;
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        counts = maintenance_counts(client, table).await;
+    }
+
+    counts
+}
+
+/// Analyzes then vacuums the hot tables, both of which are reflected in the
+///  table statistics
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_run_maintenance() {
+    let (_, pool) = common::setup().await;
+    let client = pool.get().await.expect("could not get client");
+
+    let mut before = vec![];
+    for table in TABLES {
+        before.push(maintenance_counts(&client, table).await);
+    }
+
+    maintenance::run_maintenance(false)
+        .await
+        .expect("analyze failed");
+
+    for (table, (analyzed, vacuumed)) in TABLES.into_iter().zip(before.iter()) {
+        let counts = wait_for_counts(&client, table, (analyzed + 1, *vacuumed)).await;
+        assert!(counts.0 > *analyzed, "{table:?} not analyzed");
+    }
+
+    maintenance::run_maintenance(true)
+        .await
+        .expect("vacuum failed");
+
+    for (table, (analyzed, vacuumed)) in TABLES.into_iter().zip(before.iter()) {
+        let counts = wait_for_counts(&client, table, (analyzed + 2, vacuumed + 1)).await;
+        assert!(counts.0 > analyzed + 1, "{table:?} not analyzed");
+        assert!(counts.1 > *vacuumed, "{table:?} not vacuumed");
+    }
+}
@@ -0,0 +1,140 @@
+//! Max segments of a best path against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    BestPathRequest, Coordinates, NodeType, Vertiport, Waypoint, Zone, ZoneType,
+};
+use svc_gis::postgis::best_path::{self, PathError};
+use svc_gis::postgis::{vertiport, waypoint, zone, PostgisError};
+
+/// Latitude of the vertiports
+const LATITUDE: f64 = 52.4245905;
+
+/// Longitude halfway between the vertiports
+const LONGITUDE: f64 = 4.9160036;
+
+/// Longitude from the middle to each vertiport, about 700 m
+const VERTIPORT_OFFSET_DEGREES: f64 = 0.01;
+
+/// A small square vertiport
+fn square(identifier: &str, longitude: f64) -> Vertiport {
+    let offset = 0.0001;
+    Vertiport {
+        identifier: identifier.to_string(),
+        vertices: vec![
+            (LATITUDE - offset, longitude - offset),
+            (LATITUDE + offset, longitude - offset),
+            (LATITUDE + offset, longitude + offset),
+            (LATITUDE - offset, longitude + offset),
+            (LATITUDE - offset, longitude - offset),
+        ]
+        .into_iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude,
+            longitude,
+        })
+        .collect(),
+        altitude_meters: 0.0,
+        label: None,
+        timestamp_network: Some(Utc::now().into()),
+    }
+}
+
+/// A route blocked by a zone needs a detour through a waypoint (two
+///  segments), it isn't returned when only one segment is allowed
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_best_path_max_segments() {
+    common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (west, east) = (format!("ms-{suffix}-w"), format!("ms-{suffix}-e"));
+    vertiport::update_vertiports(
+        vec![
+            square(&west, LONGITUDE - VERTIPORT_OFFSET_DEGREES),
+            square(&east, LONGITUDE + VERTIPORT_OFFSET_DEGREES),
+        ],
+        false,
+    )
+    .await
+    .expect("could not add vertiports");
+
+    // Blocks the direct route at every flight level
+    zone::update_zones(
+        vec![Zone {
+            identifier: format!("ms-{suffix}-zone"),
+            zone_type: ZoneType::Restriction as i32,
+            vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+                .iter()
+                .map(|(dy, dx)| Coordinates {
+                    latitude: LATITUDE + dy * 0.003,
+                    longitude: LONGITUDE + dx * 0.003,
+                })
+                .collect(),
+            altitude_meters_min: 0.0,
+            altitude_meters_max: 500.0,
+            ..Default::default()
+        }],
+        false,
+    )
+    .await
+    .expect("zone update failed");
+
+    // North of the zone, both legs through it clear the zone
+    waypoint::update_waypoints(vec![Waypoint {
+        identifier: format!("ms-{suffix}-wp"),
+        location: Some(Coordinates {
+            latitude: LATITUDE + 0.006,
+            longitude: LONGITUDE,
+        }),
+    }])
+    .await
+    .expect("could not add waypoint");
+
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    let request = BestPathRequest {
+        origin_identifier: west.clone(),
+        target_identifier: east.clone(),
+        origin_type: NodeType::Vertiport as i32,
+        target_type: NodeType::Vertiport as i32,
+        time_start: Some(time_start.into()),
+        time_end: Some((time_start + Duration::try_minutes(30).unwrap()).into()),
+        limit: 1,
+        ..Default::default()
+    };
+
+    let paths = best_path::best_path(request.clone(), false)
+        .await
+        .expect("could not route");
+    let segments = paths.first().expect("no path found").path.len() - 1;
+    assert!(segments >= 2);
+
+    // Allowed as many segments as the detour needs
+    let paths = best_path::best_path(
+        BestPathRequest {
+            max_segments: Some(segments as u32),
+            ..request.clone()
+        },
+        false,
+    )
+    .await
+    .expect("could not route");
+    assert_eq!(paths[0].path.len() - 1, segments);
+
+    // Only the blocked direct route is allowed
+    let e = best_path::best_path(
+        BestPathRequest {
+            max_segments: Some(1),
+            ..request
+        },
+        false,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(e, PostgisError::BestPath(PathError::NoPath));
+}
@@ -0,0 +1,116 @@
+//! Metadata-only flight updates against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{flight, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Gets the physical location and inserting transaction of each segment
+///  of a flight, which both change when a segment is rewritten
+async fn segment_rows(pool: &deadpool_postgres::Pool, identifier: &str) -> Vec<(String, String)> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query(
+            &format!(
+                r#"SELECT "ctid"::TEXT, "xmin"::TEXT
+                FROM "{PSQL_SCHEMA}"."flight_segments"
+                WHERE "flight_identifier" = $1
+                ORDER BY "time_start" ASC;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get segments")
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect()
+}
+
+/// A metadata-only update leaves the segments untouched, a path change
+///  rewrites them
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_metadata_only_update() {
+    let (config, pool) = common::setup().await;
+
+    let identifier = format!("mu-{}", common::unique_suffix());
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.clone()),
+        aircraft_identifier: Some(identifier.clone()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let segments = segment_rows(&pool, &identifier).await;
+    assert!(!segments.is_empty());
+
+    let metadata = UpdateFlightPathRequest {
+        aircraft_type: AircraftType::Aeroplane as i32,
+        simulated: true,
+        ..request.clone()
+    };
+    flight::update_flight_path(metadata, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    assert_eq!(segment_rows(&pool, &identifier).await, segments);
+
+    // The flight row itself is updated
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT "aircraft_type"::TEXT, "simulated"
+                FROM "{PSQL_SCHEMA}"."flights"
+                WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get flight");
+    let (aircraft_type, simulated): (String, bool) = (row.get(0), row.get(1));
+    assert_eq!(aircraft_type, AircraftType::Aeroplane.to_string());
+    assert!(simulated);
+
+    // A new path regenerates all segments
+    let mut moved = request;
+    moved.path[1].latitude += 0.001;
+    flight::update_flight_path(moved, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let regenerated = segment_rows(&pool, &identifier).await;
+    assert!(!regenerated.is_empty());
+    assert!(regenerated
+        .iter()
+        .all(|(_, xmin)| segments.iter().all(|(_, previous)| previous != xmin)));
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
@@ -0,0 +1,141 @@
+//! Vertiports, waypoints and aircraft near a point against a live database
+
+mod common;
+
+use chrono::Utc;
+use svc_gis::grpc::server::grpc_server::{Coordinates, NodeType, Vertiport, Waypoint};
+use svc_gis::postgis::{aircraft, nearby, vertiport, waypoint};
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Search radius
+const RADIUS_METERS: f64 = 500.0;
+
+/// A small square vertiport centered on a point
+fn square(identifier: &str, latitude: f64, longitude: f64) -> Vertiport {
+    let offset = 0.0001;
+    Vertiport {
+        identifier: identifier.to_string(),
+        vertices: vec![
+            (latitude - offset, longitude - offset),
+            (latitude + offset, longitude - offset),
+            (latitude + offset, longitude + offset),
+            (latitude - offset, longitude + offset),
+            (latitude - offset, longitude - offset),
+        ]
+        .into_iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude,
+            longitude,
+        })
+        .collect(),
+        altitude_meters: 10.0,
+        label: None,
+        timestamp_network: Some(Utc::now().into()),
+    }
+}
+
+/// An aircraft position
+fn position(identifier: &str, latitude: f64, longitude: f64) -> AircraftPosition {
+    AircraftPosition {
+        identifier: identifier.to_string(),
+        position: Position {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+        horizontal_accuracy_meters: None,
+        vertical_accuracy_meters: None,
+    }
+}
+
+/// One node of each type inside the radius is returned nearest first, the
+///  ones outside are not
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_nodes_near() {
+    let (_, pool) = common::setup().await;
+
+    // A point far from other tests so their nodes aren't included
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (-30.0 + (suffix % 1000) as f64 * 1e-2, 150.0);
+
+    // About 110, 220 and 330 meters north inside the radius, 1.1 km outside
+    let near = |step: f64| latitude + step * 0.001;
+    let far = latitude + 0.01;
+
+    let vertiports = [format!("nn-{suffix}-vp"), format!("nn-{suffix}-vp-far")];
+    vertiport::update_vertiports(
+        vec![
+            square(&vertiports[0], near(1.0), longitude),
+            square(&vertiports[1], far, longitude),
+        ],
+        false,
+    )
+    .await
+    .expect("could not add vertiports");
+
+    let waypoints = [format!("nn-{suffix}-wp"), format!("nn-{suffix}-wp-far")];
+    waypoint::update_waypoints(vec![
+        Waypoint {
+            identifier: waypoints[0].clone(),
+            location: Some(Coordinates {
+                latitude: near(2.0),
+                longitude,
+            }),
+        },
+        Waypoint {
+            identifier: waypoints[1].clone(),
+            location: Some(Coordinates {
+                latitude: far,
+                longitude,
+            }),
+        },
+    ])
+    .await
+    .expect("could not add waypoints");
+
+    let aircraft = [format!("nn-{suffix}-ac"), format!("nn-{suffix}-ac-far")];
+    aircraft::update_aircraft_position(
+        vec![
+            position(&aircraft[0], near(3.0), longitude),
+            position(&aircraft[1], far, longitude),
+        ],
+        false,
+    )
+    .await
+    .expect("could not add aircraft");
+
+    let point = Coordinates {
+        latitude,
+        longitude,
+    };
+    let nodes = nearby::get_nodes_near(&point, RADIUS_METERS, &pool)
+        .await
+        .expect("could not get nearby nodes");
+
+    let found = nodes
+        .iter()
+        .map(|node| (node.node_type, node.identifier.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (NodeType::Vertiport, vertiports[0].as_str()),
+            (NodeType::Waypoint, waypoints[0].as_str()),
+            (NodeType::Aircraft, aircraft[0].as_str()),
+        ]
+    );
+
+    for (node, step) in nodes.iter().zip([1.0, 2.0, 3.0]) {
+        assert!((node.distance_meters - step * 111.0).abs() < 15.0);
+        assert!((node.geom.y - near(step)).abs() < 1e-4);
+    }
+    assert_eq!(nodes[0].geom.z, 10.0);
+    assert_eq!(nodes[1].geom.z, 0.0);
+    assert_eq!(nodes[2].geom.z, 100.0);
+}
@@ -0,0 +1,440 @@
+//! Operator filtering and enforcement of flights and aircraft against a
+//!  live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use futures::StreamExt;
+use svc_gis::grpc::server::grpc_server::{GetFlightsRequest, PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::aircraft::{self, AircraftError};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::subscription::{self, SubscriptionOptions};
+use svc_gis::postgis::{export, telemetry, PostgisError};
+use svc_gis::types::{AircraftId, AircraftPosition, AircraftType, Position};
+
+/// Identifies aircraft as an operator's
+async fn identify(identifiers: &[&String], operator_id: &str, operator_enforcement: bool) {
+    aircraft::update_aircraft_id_for(
+        identifiers
+            .iter()
+            .map(|identifier| AircraftId {
+                identifier: Some(identifier.to_string()),
+                session_id: None,
+                aircraft_type: AircraftType::Rotorcraft,
+                operator_id: Some(operator_id.to_string()),
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                force_type: false,
+            })
+            .collect(),
+        false,
+        operator_enforcement,
+    )
+    .await
+    .expect("could not identify aircraft");
+}
+
+/// Encodes a binary telemetry record, see [`telemetry`]
+fn encode(index: u32) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(telemetry::RECORD_SIZE);
+    bytes.extend_from_slice(&index.to_le_bytes());
+    bytes.extend_from_slice(&52_374_590i32.to_le_bytes());
+    bytes.extend_from_slice(&4_916_003i32.to_le_bytes());
+    bytes.extend_from_slice(&100i16.to_le_bytes());
+    bytes.extend_from_slice(&0i16.to_le_bytes());
+    bytes.extend_from_slice(&0i16.to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&Utc::now().timestamp_millis().to_le_bytes());
+    bytes
+}
+
+/// get_flights filtered by operator returns that operator's flights and
+///  idle aircraft, not another operator's flight on one of its aircraft
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_operator_filter() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let owner = format!("op-{suffix}-owner");
+    let charter = format!("op-{suffix}-charter");
+    let aircraft_identifier = format!("op-{suffix}-ac");
+    let idle_identifier = format!("op-{suffix}-idle");
+    let flight_identifier = format!("op-{suffix}-flight");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+
+    // Both aircraft belong to the owner, one is chartered for a flight of
+    //  another operator
+    aircraft::update_aircraft_id(
+        [
+            (&aircraft_identifier, Some(flight_identifier.clone())),
+            (&idle_identifier, None),
+        ]
+        .into_iter()
+        .map(|(identifier, session_id)| AircraftId {
+            identifier: Some(identifier.clone()),
+            session_id,
+            aircraft_type: AircraftType::Rotorcraft,
+            operator_id: Some(owner.clone()),
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            force_type: false,
+        })
+        .collect(),
+        false,
+    )
+    .await
+    .expect("could not identify aircraft");
+
+    aircraft::update_aircraft_position(
+        [&aircraft_identifier, &idle_identifier]
+            .into_iter()
+            .map(|identifier| AircraftPosition {
+                identifier: identifier.clone(),
+                position: Position {
+                    latitude,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            })
+            .collect(),
+        false,
+    )
+    .await
+    .expect("position update failed");
+
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(flight_identifier.clone()),
+            aircraft_identifier: Some(aircraft_identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            operator_id: Some(charter.clone()),
+            path: vec![
+                PointZ {
+                    latitude,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude,
+                    longitude: longitude + 0.01,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    let flights_of = |operator_id: &str| {
+        flight::get_flights(GetFlightsRequest {
+            window_min_x: longitude - 0.01,
+            window_min_y: latitude - 0.01,
+            window_max_x: longitude + 0.02,
+            window_max_y: latitude + 0.01,
+            time_start: Some(time_start.into()),
+            time_end: Some(time_end.into()),
+            operator_id: Some(operator_id.to_string()),
+            ..Default::default()
+        })
+    };
+
+    let owned = flights_of(&owner).await.expect("could not get flights");
+    assert!(owned
+        .iter()
+        .all(|flight| flight.session_id.as_ref() != Some(&flight_identifier)));
+    assert!(owned
+        .iter()
+        .any(|flight| flight.aircraft_id.as_ref() == Some(&idle_identifier)));
+
+    let chartered = flights_of(&charter).await.expect("could not get flights");
+    assert!(chartered
+        .iter()
+        .any(|flight| flight.session_id.as_ref() == Some(&flight_identifier)));
+    assert!(chartered
+        .iter()
+        .all(|flight| flight.aircraft_id.as_ref() != Some(&idle_identifier)));
+
+    flight::delete_flight(&flight_identifier, None)
+        .await
+        .expect("could not delete flight");
+}
+
+/// Updates carrying an operator claim are limited to that operator's
+///  flights, and new flights are attributed to it
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_operator_enforcement() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let operator = format!("oe-{suffix}-operator");
+    let other = format!("oe-{suffix}-other");
+    let identifier = format!("oe-{suffix}");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.clone()),
+        aircraft_identifier: Some(identifier.clone()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    // Attributing a new flight to someone else
+    let result = flight::update_flight_path_for(
+        UpdateFlightPathRequest {
+            operator_id: Some(other.clone()),
+            ..request.clone()
+        },
+        config.max_flight_duration_secs,
+        Some(&operator),
+    )
+    .await;
+    assert_eq!(result, Err(PostgisError::FlightPath(FlightError::Operator)));
+
+    // Attributed to the claimant without an operator in the request
+    flight::update_flight_path_for(
+        request.clone(),
+        config.max_flight_duration_secs,
+        Some(&operator),
+    )
+    .await
+    .expect("flight update failed");
+    flight::check_flight_operator(&identifier, &operator)
+        .await
+        .expect("flight not attributed to the claimant");
+
+    // Another operator can't touch it
+    let result = flight::update_flight_path_for(
+        request.clone(),
+        config.max_flight_duration_secs,
+        Some(&other),
+    )
+    .await;
+    assert_eq!(result, Err(PostgisError::FlightPath(FlightError::Operator)));
+    assert_eq!(
+        flight::check_flight_operator(&identifier, &other).await,
+        Err(PostgisError::FlightPath(FlightError::Operator))
+    );
+
+    // Unknown flights are anyone's to file
+    flight::check_flight_operator(&format!("{identifier}-unknown"), &other)
+        .await
+        .expect("unknown flight rejected");
+
+    // Without a claim, updates are not restricted
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
+
+/// Aircraft writes carrying an operator claim are limited to aircraft
+///  identified as that operator's, and enforced identifications can't move
+///  an aircraft to another operator
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_aircraft_operator_enforcement() {
+    common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let operator = format!("ae-{suffix}-operator");
+    let other = format!("ae-{suffix}-other");
+    let identifier = format!("ae-{suffix}");
+    let unknown = format!("ae-{suffix}-unknown");
+    identify(&[&identifier], &operator, true).await;
+
+    aircraft::check_aircraft_operator(vec![identifier.clone()], &operator)
+        .await
+        .expect("aircraft not attributed to its operator");
+    let foreign = Err(PostgisError::Aircraft(AircraftError::Operator));
+    assert_eq!(
+        aircraft::check_aircraft_operator(vec![identifier.clone()], &other).await,
+        foreign
+    );
+
+    // Unknown aircraft must be identified first
+    assert_eq!(
+        aircraft::check_aircraft_operator(vec![identifier.clone(), unknown.clone()], &operator)
+            .await,
+        foreign
+    );
+
+    // Binary telemetry is checked against the claim, not without one
+    let payload = encode(
+        telemetry::register_identifier(&identifier)
+            .await
+            .expect("could not register identifier"),
+    );
+    assert_eq!(
+        telemetry::ingest_binary(&payload, true, Some(&other)).await,
+        foreign
+    );
+    telemetry::ingest_binary(&payload, true, Some(&operator))
+        .await
+        .expect("telemetry of own aircraft rejected");
+    telemetry::ingest_binary(&payload, true, None)
+        .await
+        .expect("telemetry without claim rejected");
+
+    // An enforced identification keeps the aircraft with its operator
+    identify(&[&identifier], &other, true).await;
+    aircraft::check_aircraft_operator(vec![identifier.clone()], &operator)
+        .await
+        .expect("aircraft moved to another operator");
+
+    // Without enforcement, identifications are not restricted
+    identify(&[&identifier], &other, false).await;
+    aircraft::check_aircraft_operator(vec![identifier.clone()], &other)
+        .await
+        .expect("aircraft not moved to another operator");
+}
+
+/// Position, GeoJSON and flight event streams filtered by operator only
+///  stream that operator's aircraft and flights
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_stream_operator_filter() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let operator = format!("sf-{suffix}-operator");
+    let other = format!("sf-{suffix}-other");
+    let own = format!("sf-{suffix}-own");
+    let foreign = format!("sf-{suffix}-foreign");
+    identify(&[&own], &operator, false).await;
+    identify(&[&foreign], &other, false).await;
+
+    let timeout = std::time::Duration::from_secs(5);
+    let mut positions = Box::pin(subscription::position_stream(SubscriptionOptions {
+        operator_id: Some(operator.clone()),
+        ..Default::default()
+    }));
+    let mut events = Box::pin(flight::flight_event_stream(None, Some(operator.clone())));
+
+    // The foreign aircraft is written first, only the own one is streamed
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    for identifier in [&foreign, &own] {
+        aircraft::update_aircraft_position(
+            vec![AircraftPosition {
+                identifier: identifier.clone(),
+                position: Position {
+                    latitude,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                timestamp_network: Utc::now(),
+                timestamp_asset: None,
+                horizontal_accuracy_meters: None,
+                vertical_accuracy_meters: None,
+            }],
+            false,
+        )
+        .await
+        .expect("position update failed");
+    }
+
+    let event = tokio::time::timeout(timeout, positions.next())
+        .await
+        .expect("no position received")
+        .expect("stream ended");
+    assert_eq!(event.position.identifier, own);
+
+    let mut stream = Box::pin(
+        export::aircraft_geojson_stream(true, Some(&operator))
+            .await
+            .expect("could not start export"),
+    );
+    let mut exported = vec![];
+    while let Some(chunk) = stream.next().await {
+        for line in chunk.expect("export failed").features.lines() {
+            let feature: serde_json::Value =
+                serde_json::from_str(line).expect("chunk line is not valid JSON");
+            exported.push(feature["id"].as_str().unwrap_or_default().to_string());
+        }
+    }
+    assert_eq!(exported, vec![own.clone()]);
+
+    // Flights of both operators are filed, only the own one is streamed
+    let time_start = Utc::now();
+    for (identifier, operator_id) in [(&foreign, &other), (&own, &operator)] {
+        flight::update_flight_path(
+            UpdateFlightPathRequest {
+                flight_identifier: Some(identifier.clone()),
+                aircraft_identifier: Some(identifier.clone()),
+                aircraft_type: AircraftType::Rotorcraft as i32,
+                operator_id: Some(operator_id.clone()),
+                path: vec![
+                    PointZ {
+                        latitude,
+                        longitude,
+                        altitude_meters: 100.0,
+                    },
+                    PointZ {
+                        latitude,
+                        longitude: longitude + 0.01,
+                        altitude_meters: 100.0,
+                    },
+                ],
+                timestamp_start: Some(time_start.into()),
+                timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+                ..Default::default()
+            },
+            config.max_flight_duration_secs,
+        )
+        .await
+        .expect("flight update failed");
+    }
+
+    let event = tokio::time::timeout(timeout, events.next())
+        .await
+        .expect("no flight event received")
+        .expect("stream ended");
+    assert_eq!(event.flight_identifier, own);
+    assert_eq!(event.operator_id, Some(operator.clone()));
+
+    for identifier in [&foreign, &own] {
+        flight::delete_flight(identifier, None)
+            .await
+            .expect("could not delete flight");
+    }
+}
@@ -0,0 +1,143 @@
+//! Alignment of aircraft with their planned path in getFlights against a
+//!  live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Flight, GetFlightsRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::aircraft;
+use svc_gis::postgis::flight::{self, MAX_PATH_LATERAL_DEVIATION_METERS};
+use svc_gis::types::{AircraftTelemetry, AircraftType, Position};
+
+const LATITUDE: f64 = 52.3545905;
+const LONGITUDE: f64 = 4.9160036;
+
+/// Reports the position of an aircraft
+async fn report(identifier: &str, latitude: f64, longitude: f64, altitude_meters: f64) {
+    aircraft::update_aircraft_telemetry(
+        vec![AircraftTelemetry {
+            identifier: identifier.to_string(),
+            position: Position {
+                latitude,
+                longitude,
+                altitude_meters,
+            },
+            velocity_horizontal_ground_mps: 10.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: 0.0,
+            track_angle_degrees: 90.0,
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+        }],
+        false,
+    )
+    .await
+    .expect("telemetry update failed");
+}
+
+/// An aircraft midway along the second segment of a two-segment flight is
+///  in that segment, an aircraft far from the path is off the path but
+///  still aligned to its closest segment
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_path_alignment() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let identifier = format!("pa-{suffix}");
+
+    let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+
+    // Two segments of equal length, east then north, climbing in the second
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![
+                PointZ {
+                    latitude: LATITUDE,
+                    longitude: LONGITUDE,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude: LATITUDE,
+                    longitude: LONGITUDE + 0.002,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude: LATITUDE + 0.002,
+                    longitude: LONGITUDE + 0.002,
+                    altitude_meters: 120.0,
+                },
+            ],
+            timestamp_start: Some((now - Duration::try_minutes(5).unwrap()).into()),
+            timestamp_end: Some((now + Duration::try_minutes(25).unwrap()).into()),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    let request = GetFlightsRequest {
+        window_min_x: LONGITUDE - 0.01,
+        window_min_y: LATITUDE - 0.01,
+        window_max_x: LONGITUDE + 0.01,
+        window_max_y: LATITUDE + 0.01,
+        time_start: Some(now.into()),
+        time_end: Some((now + Duration::try_minutes(1).unwrap()).into()),
+        ..Default::default()
+    };
+
+    let find = |flights: &[Flight]| {
+        flights
+            .iter()
+            .find(|flight| flight.session_id.as_deref() == Some(identifier.as_str()))
+            .cloned()
+            .expect("flight not found")
+    };
+
+    // Midway along the second segment, 2 meters below the planned 110 meters
+    report(&identifier, LATITUDE + 0.001, LONGITUDE + 0.002, 108.0).await;
+
+    // None unless requested
+    let flights = flight::get_flights(request.clone())
+        .await
+        .expect("could not get flights");
+    assert!(find(&flights).path_alignment.is_none());
+
+    let request = GetFlightsRequest {
+        path_alignment: true,
+        ..request
+    };
+
+    let flights = flight::get_flights(request.clone())
+        .await
+        .expect("could not get flights");
+    let alignment = find(&flights).path_alignment.expect("no path alignment");
+    assert_eq!(alignment.segment_index, 1);
+    assert!(alignment.lateral_deviation_meters < 1.0);
+    assert!((alignment.vertical_deviation_meters + 2.0).abs() < 0.5);
+    assert!((alignment.fraction_complete - 0.75).abs() < 0.05);
+    assert!(!alignment.off_path);
+
+    // Far east of the first segment's end, the closest point is the corner
+    report(&identifier, LATITUDE, LONGITUDE + 0.004, 100.0).await;
+    let flights = flight::get_flights(request)
+        .await
+        .expect("could not get flights");
+    let alignment = find(&flights).path_alignment.expect("no path alignment");
+    assert!(alignment.off_path);
+    assert!(alignment.lateral_deviation_meters > MAX_PATH_LATERAL_DEVIATION_METERS);
+    assert!((alignment.fraction_complete - 0.5).abs() < 0.05);
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
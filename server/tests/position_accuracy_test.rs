@@ -0,0 +1,130 @@
+//! Accuracy of aircraft positions against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{aircraft, flight, PSQL_SCHEMA};
+use svc_gis::types::{AircraftPosition, AircraftType, Position};
+
+/// Reported accuracy is stored with the position and returned in the
+///  aircraft state, a later position without accuracy clears it
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_position_accuracy() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let flight_identifier = format!("pa-{suffix}");
+    let aircraft_identifier = format!("pa-{suffix}-ac");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(aircraft_identifier.clone()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let position = AircraftPosition {
+        identifier: aircraft_identifier.clone(),
+        position: Position {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        timestamp_network: Utc::now(),
+        timestamp_asset: None,
+        horizontal_accuracy_meters: Some(7.5),
+        vertical_accuracy_meters: Some(12.0),
+    };
+
+    aircraft::update_aircraft_position(vec![position.clone()], false)
+        .await
+        .expect("position update failed");
+
+    let states = flight::get_states_for_flights(vec![flight_identifier.clone()], &pool)
+        .await
+        .expect("could not get states");
+    let state = states.get(&flight_identifier).expect("no state for flight");
+    assert_eq!(state.horizontal_accuracy_meters, Some(7.5));
+    assert_eq!(state.vertical_accuracy_meters, Some(12.0));
+
+    // Also kept in the history
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT "horizontal_accuracy_meters", "vertical_accuracy_meters"
+                FROM "{PSQL_SCHEMA}"."aircraft_history"
+                WHERE "identifier" = $1;"#
+            ),
+            &[&aircraft_identifier],
+        )
+        .await
+        .expect("could not get history");
+    assert_eq!(row.get::<_, Option<f32>>(0), Some(7.5));
+    assert_eq!(row.get::<_, Option<f32>>(1), Some(12.0));
+
+    // A negative accuracy is rejected, the stored state is unchanged
+    aircraft::update_aircraft_position(
+        vec![AircraftPosition {
+            timestamp_network: Utc::now(),
+            horizontal_accuracy_meters: Some(-1.0),
+            ..position.clone()
+        }],
+        false,
+    )
+    .await
+    .expect("position update failed");
+
+    let states = flight::get_states_for_flights(vec![flight_identifier.clone()], &pool)
+        .await
+        .expect("could not get states");
+    assert_eq!(
+        states[&flight_identifier].horizontal_accuracy_meters,
+        Some(7.5)
+    );
+
+    // Sources that don't report accuracy don't inherit the previous one
+    aircraft::update_aircraft_position(
+        vec![AircraftPosition {
+            timestamp_network: Utc::now(),
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+            ..position
+        }],
+        false,
+    )
+    .await
+    .expect("position update failed");
+
+    let states = flight::get_states_for_flights(vec![flight_identifier.clone()], &pool)
+        .await
+        .expect("could not get states");
+    let state = &states[&flight_identifier];
+    assert_eq!(state.horizontal_accuracy_meters, None);
+    assert_eq!(state.vertical_accuracy_meters, None);
+}
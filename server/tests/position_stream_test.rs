@@ -0,0 +1,79 @@
+//! Region subscriptions of written aircraft positions against a live
+//!  database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use svc_gis::grpc::server::grpc_server::Coordinates;
+use svc_gis::postgis::aircraft;
+use svc_gis::postgis::subscription::{self, RegionEvent, SubscriptionOptions};
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Latitude of the region and the positions
+const LATITUDE: f64 = 52.35;
+
+/// Writes the position of an aircraft at a longitude, `seconds` after
+///  `start`
+async fn write(identifier: &str, longitude: f64, start: DateTime<Utc>, seconds: i64) {
+    aircraft::update_aircraft_position(
+        vec![AircraftPosition {
+            identifier: identifier.to_string(),
+            position: Position {
+                latitude: LATITUDE,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: start + Duration::try_seconds(seconds).unwrap(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        }],
+        false,
+    )
+    .await
+    .expect("position update failed");
+}
+
+/// An aircraft flying through a region is streamed as it enters and
+///  leaves, other aircraft are not
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_position_stream() {
+    common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let identifier = format!("pst-{suffix}");
+    let outside = format!("pst-{suffix}-out");
+
+    let vertices = [(52.34, 4.90), (52.34, 4.92), (52.36, 4.92), (52.36, 4.90)]
+        .iter()
+        .map(|(latitude, longitude)| Coordinates {
+            latitude: *latitude,
+            longitude: *longitude,
+        })
+        .collect::<Vec<_>>();
+    let options =
+        SubscriptionOptions::try_new(&vertices, true, 0).expect("could not create options");
+    let mut stream = Box::pin(subscription::position_stream(options));
+
+    // Far enough apart to be plausible, and not in the future
+    let start = Utc::now() - Duration::try_minutes(1).unwrap();
+    write(&outside, 4.80, start, 0).await;
+    write(&identifier, 4.89, start, 0).await;
+    write(&identifier, 4.91, start, 10).await;
+    write(&identifier, 4.91, start, 20).await;
+    write(&identifier, 4.93, start, 30).await;
+
+    for expected in [RegionEvent::Enter, RegionEvent::Leave] {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+            .await
+            .expect("no event received")
+            .expect("stream ended");
+        assert_eq!(event.event, expected);
+        assert_eq!(event.position.identifier, identifier);
+    }
+}
@@ -0,0 +1,125 @@
+//! Flight paths submitted in a projected CRS against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use postgis::ewkb::{LineStringT, PointZ};
+use svc_gis::grpc::server::grpc_server::{self, UpdateFlightPathRequest};
+use svc_gis::postgis::{flight, DEFAULT_SRID, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Spherical Mercator (EPSG:3857)
+const WEB_MERCATOR_SRID: i32 = 3857;
+
+/// Radius of the sphere of EPSG:3857
+const WEB_MERCATOR_RADIUS_METERS: f64 = 6_378_137.0;
+
+/// Max difference in degrees between a stored and an expected coordinate,
+///  besides quantization
+const TOLERANCE_DEGREES: f64 = 1e-8;
+
+/// Projects a `(latitude, longitude)` to EPSG:3857 `(x, y)`
+fn web_mercator(latitude: f64, longitude: f64) -> (f64, f64) {
+    let x = WEB_MERCATOR_RADIUS_METERS * longitude.to_radians();
+    let y = WEB_MERCATOR_RADIUS_METERS
+        * (std::f64::consts::FRAC_PI_4 + latitude.to_radians() / 2.0)
+            .tan()
+            .ln();
+    (x, y)
+}
+
+/// A path submitted in EPSG:3857 is stored in WGS84
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_projected_srid_path() {
+    let (config, pool) = common::setup().await;
+
+    let identifier = format!("ps-{}", common::unique_suffix());
+    let coordinates = [(52.3745905, 4.9160036), (52.3745905, 4.9260036)];
+    let time_start = Utc::now();
+
+    let path = coordinates
+        .iter()
+        .map(|(latitude, longitude)| {
+            let (x, y) = web_mercator(*latitude, *longitude);
+            grpc_server::PointZ {
+                latitude: y,
+                longitude: x,
+                altitude_meters: 100.0,
+            }
+        })
+        .collect();
+
+    flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(identifier.clone()),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path,
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+            srid: Some(WEB_MERCATOR_SRID),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight update failed");
+
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one(
+            &format!(
+                r#"SELECT ST_SRID("geom") AS "srid", "geom"
+                FROM "{PSQL_SCHEMA}"."flights"
+                WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not get flight");
+
+    let srid: i32 = row.get("srid");
+    assert_eq!(srid, DEFAULT_SRID);
+
+    // Transformed points are quantized like any other
+    let tolerance = config.coordinate_quantum_degrees + TOLERANCE_DEGREES;
+    let geom: LineStringT<PointZ> = row.get("geom");
+    assert_eq!(geom.points.len(), coordinates.len());
+    for (point, (latitude, longitude)) in geom.points.iter().zip(coordinates) {
+        assert!((point.y - latitude).abs() < tolerance);
+        assert!((point.x - longitude).abs() < tolerance);
+        assert!((point.z - 100.0).abs() <= config.altitude_quantum_meters);
+    }
+
+    // SRIDs missing from spatial_ref_sys are rejected
+    let result = flight::update_flight_path(
+        UpdateFlightPathRequest {
+            flight_identifier: Some(format!("{identifier}-unknown")),
+            aircraft_identifier: Some(identifier.clone()),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: coordinates
+                .iter()
+                .map(|(latitude, longitude)| grpc_server::PointZ {
+                    latitude: *latitude,
+                    longitude: *longitude,
+                    altitude_meters: 100.0,
+                })
+                .collect(),
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+            srid: Some(990_000),
+            ..Default::default()
+        },
+        config.max_flight_duration_secs,
+    )
+    .await;
+    assert!(result.is_err());
+
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
@@ -0,0 +1,70 @@
+//! Per-domain readiness against a live database
+
+mod common;
+
+use chrono::Utc;
+use svc_gis::grpc::server::grpc_server::{DomainStatus, Readiness};
+use svc_gis::health::{self, Thresholds, DOMAIN_AIRCRAFT_POSITIONS, DOMAIN_FLIGHT_UPDATES};
+use svc_gis::postgis::aircraft;
+use svc_gis::types::{AircraftTelemetry, Position};
+
+/// A fresh position keeps its domain fresh, an overdue flight update
+///  degrades the instance without making it unready
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_readiness_domains() {
+    common::setup().await;
+
+    health::THRESHOLDS
+        .set(Thresholds {
+            position_max_age_secs: 60,
+            flight_update_max_age_secs: 1,
+            ..Default::default()
+        })
+        .expect("could not set thresholds");
+    health::STARTED_AT
+        .set(Utc::now() - chrono::Duration::try_minutes(5).unwrap())
+        .expect("could not set startup time");
+
+    let identifier = format!("rd{}", common::unique_suffix());
+    aircraft::update_aircraft_telemetry(
+        vec![AircraftTelemetry {
+            identifier,
+            position: Position {
+                latitude: 52.3745905,
+                longitude: 4.9160036,
+                altitude_meters: 100.0,
+            },
+            velocity_horizontal_ground_mps: 10.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: 0.0,
+            track_angle_degrees: 90.0,
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+        }],
+        false,
+    )
+    .await
+    .expect("telemetry update failed");
+
+    let response = health::get_readiness().await;
+    let status = |domain: &str| {
+        response
+            .domains
+            .iter()
+            .find(|health| health.domain == domain)
+            .map(|health| health.status)
+            .expect("domain not reported")
+    };
+
+    assert!(response.ready);
+    assert_eq!(response.readiness, Readiness::Degraded as i32);
+    assert_eq!(
+        status(DOMAIN_AIRCRAFT_POSITIONS),
+        DomainStatus::Fresh as i32
+    );
+    assert_eq!(status(DOMAIN_FLIGHT_UPDATES), DomainStatus::Stale as i32);
+}
@@ -0,0 +1,127 @@
+//! Replacement of the zones of a source against a live database
+
+mod common;
+
+use chrono::Utc;
+use svc_gis::grpc::server::grpc_server::{Coordinates, Zone, ZoneType};
+use svc_gis::postgis::zone::{self, ZoneError, ZoneReplacement};
+use svc_gis::postgis::PSQL_SCHEMA;
+
+fn zone(identifier: &str) -> Zone {
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    Zone {
+        identifier: identifier.to_string(),
+        zone_type: ZoneType::Restriction as i32,
+        vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: latitude + dy * 0.0001,
+                longitude: longitude + dx * 0.0001,
+            })
+            .collect(),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 100.0,
+        ..Default::default()
+    }
+}
+
+/// Active zones of a source, sorted
+async fn active_zones(pool: &deadpool_postgres::Pool, source: &str) -> Vec<String> {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query(
+            &format!(
+                r#"SELECT "identifier" FROM "{PSQL_SCHEMA}"."zones"
+                WHERE "source" = $1 AND "deleted_at" IS NULL
+                ORDER BY "identifier";"#
+            ),
+            &[&source],
+        )
+        .await
+        .expect("could not get zones")
+        .iter()
+        .map(|row| row.get(0))
+        .collect()
+}
+
+/// A publication that drops one zone and adds another removes the dropped
+///  zone, creates the new one and overwrites the others
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_replace_zones() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = Utc::now().timestamp_micros();
+    let source = format!("rz-{suffix}");
+    let other_source = format!("rz-{suffix}-other");
+    let [a, b, c] = ["a", "b", "c"].map(|name| format!("rz-{suffix}-{name}"));
+
+    // A zone of another source is left alone
+    let mut other = zone(&format!("rz-{suffix}-other"));
+    other.source = Some(other_source.clone());
+    zone::update_zones(vec![other], false).await.unwrap();
+
+    let first = zone::replace_zones(&source, vec![zone(&a), zone(&b)], false)
+        .await
+        .expect("could not replace zones");
+    assert_eq!(
+        first,
+        ZoneReplacement {
+            created: 2,
+            updated: 0,
+            removed: 0,
+        }
+    );
+    assert_eq!(
+        active_zones(&pool, &source).await,
+        vec![a.clone(), b.clone()]
+    );
+
+    // The next publication drops b and adds c, a dry run changes nothing
+    let publication = vec![zone(&a), zone(&c)];
+    let expected = ZoneReplacement {
+        created: 1,
+        updated: 1,
+        removed: 1,
+    };
+
+    let dry_run = zone::replace_zones(&source, publication.clone(), true)
+        .await
+        .expect("could not replace zones");
+    assert_eq!(dry_run, expected);
+    assert_eq!(
+        active_zones(&pool, &source).await,
+        vec![a.clone(), b.clone()]
+    );
+
+    let second = zone::replace_zones(&source, publication, false)
+        .await
+        .expect("could not replace zones");
+    assert_eq!(second, expected);
+    assert_eq!(
+        active_zones(&pool, &source).await,
+        vec![a.clone(), c.clone()]
+    );
+    assert_eq!(active_zones(&pool, &other_source).await.len(), 1);
+
+    // A failing zone leaves the previous publication in place
+    let mut foreign = zone(&format!("rz-{suffix}-d"));
+    foreign.source = Some(other_source.clone());
+    assert_eq!(
+        zone::replace_zones(&source, vec![zone(&a), foreign], false)
+            .await
+            .unwrap_err(),
+        ZoneError::Source
+    );
+
+    assert_eq!(
+        zone::replace_zones(&source, vec![zone(&a), zone(&b)], false)
+            .await
+            .unwrap_err(),
+        ZoneError::Deleted
+    );
+    assert_eq!(active_zones(&pool, &source).await, vec![a, c]);
+}
@@ -0,0 +1,143 @@
+//! Grouping of simulated flights by scenario against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::{PostgisError, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+/// Counts the stored segments of a flight
+async fn segments(pool: &deadpool_postgres::Pool, identifier: &str) -> i64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(
+                r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."flight_segments" WHERE "flight_identifier" = $1;"#
+            ),
+            &[&identifier],
+        )
+        .await
+        .expect("could not count segments")
+        .get(0)
+}
+
+/// Flights of one scenario are fetched and torn down without touching
+///  another scenario or unrelated flights
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_scenario() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (scenario_a, scenario_b) = (format!("sc-{suffix}-a"), format!("sc-{suffix}-b"));
+    let time_start = Utc::now();
+
+    let flight = |name: &str, scenario_id: Option<&str>, offset_minutes: i64| {
+        let time_start = time_start + Duration::try_minutes(offset_minutes).unwrap();
+        UpdateFlightPathRequest {
+            flight_identifier: Some(format!("sc-{suffix}-{name}")),
+            aircraft_identifier: Some(format!("sc-{suffix}-{name}-ac")),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            simulated: true,
+            scenario_id: scenario_id.map(str::to_string),
+            path: vec![
+                PointZ {
+                    latitude: 52.3745905,
+                    longitude: 4.9160036,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude: 52.3749819,
+                    longitude: 4.9156925,
+                    altitude_meters: 120.0,
+                },
+            ],
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+            ..Default::default()
+        }
+    };
+
+    for request in [
+        flight("a2", Some(&scenario_a), 5),
+        flight("a1", Some(&scenario_a), 0),
+        flight("b1", Some(&scenario_b), 0),
+        flight("none", None, 0),
+    ] {
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("could not store flight");
+    }
+
+    // A real flight can't join a scenario
+    let e = flight::update_flight_path(
+        UpdateFlightPathRequest {
+            simulated: false,
+            ..flight("real", Some(&scenario_a), 0)
+        },
+        config.max_flight_duration_secs,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(e, PostgisError::FlightPath(FlightError::Label));
+
+    let identifiers = |flights: Vec<flight::ScenarioFlight>| {
+        flights
+            .into_iter()
+            .map(|f| f.flight_identifier)
+            .collect::<Vec<String>>()
+    };
+
+    let flights = flight::get_scenario_flights(&scenario_a, &pool)
+        .await
+        .expect("could not get scenario a");
+    assert_eq!(flights[0].aircraft_identifier, format!("sc-{suffix}-a1-ac"));
+    assert_eq!(
+        identifiers(flights),
+        vec![format!("sc-{suffix}-a1"), format!("sc-{suffix}-a2")]
+    );
+
+    let flights = flight::get_scenario_flights(&scenario_b, &pool)
+        .await
+        .expect("could not get scenario b");
+    assert_eq!(identifiers(flights), vec![format!("sc-{suffix}-b1")]);
+
+    // Teardown removes the flights and their segments, only of that scenario
+    let removed = flight::remove_scenario(&scenario_a, &pool)
+        .await
+        .expect("could not remove scenario a");
+    assert_eq!(removed, 2);
+    assert_eq!(segments(&pool, &format!("sc-{suffix}-a1")).await, 0);
+
+    assert!(flight::get_scenario_flights(&scenario_a, &pool)
+        .await
+        .expect("could not get scenario a")
+        .is_empty());
+
+    let flights = flight::get_scenario_flights(&scenario_b, &pool)
+        .await
+        .expect("could not get scenario b");
+    assert_eq!(identifiers(flights), vec![format!("sc-{suffix}-b1")]);
+    assert!(segments(&pool, &format!("sc-{suffix}-b1")).await > 0);
+    assert!(segments(&pool, &format!("sc-{suffix}-none")).await > 0);
+
+    // Removing it again is a no-op
+    let removed = flight::remove_scenario(&scenario_a, &pool)
+        .await
+        .expect("could not remove scenario a again");
+    assert_eq!(removed, 0);
+
+    let e = flight::get_scenario_flights("scenario;", &pool)
+        .await
+        .unwrap_err();
+    assert_eq!(e, PostgisError::FlightPath(FlightError::Label));
+
+    flight::remove_scenario(&scenario_b, &pool)
+        .await
+        .expect("could not remove scenario b");
+}
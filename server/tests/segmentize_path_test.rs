@@ -0,0 +1,87 @@
+//! Segmentation previews through the gRPC server against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use postgis::ewkb::PointZ;
+use svc_gis::grpc::server::grpc_server::{self, SegmentizePathRequest};
+use svc_gis::grpc::server::{RpcService, ServerImpl};
+use svc_gis::postgis::utils;
+use tonic::Request;
+
+/// The RPC returns the segments of a direct call to `segmentize`
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_segmentize_path_rpc() {
+    common::setup().await;
+
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    let segment_length_meters = 100.0;
+
+    let path = vec![
+        grpc_server::PointZ {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        grpc_server::PointZ {
+            latitude: latitude + 0.005,
+            longitude,
+            altitude_meters: 150.0,
+        },
+        grpc_server::PointZ {
+            latitude: latitude + 0.005,
+            longitude: longitude + 0.005,
+            altitude_meters: 150.0,
+        },
+    ];
+
+    let response = ServerImpl::default()
+        .segmentize_path(Request::new(SegmentizePathRequest {
+            path: path.clone(),
+            time_start: Some(time_start.into()),
+            time_end: Some(time_end.into()),
+            segment_length_meters,
+        }))
+        .await
+        .expect("could not segmentize path")
+        .into_inner();
+
+    let points = path
+        .into_iter()
+        .map(PointZ::try_from)
+        .collect::<Result<Vec<PointZ>, _>>()
+        .expect("could not convert path");
+    let expected = utils::segmentize(points, time_start, time_end, segment_length_meters)
+        .await
+        .expect("could not segmentize path")
+        .into_iter()
+        .map(|segment| grpc_server::PathSegment {
+            start: segment.geom.points.first().copied().map(Into::into),
+            end: segment.geom.points.last().copied().map(Into::into),
+            time_start: Some(segment.time_start.into()),
+            time_end: Some(segment.time_end.into()),
+        })
+        .collect::<Vec<_>>();
+
+    // More than one segment per leg of the path
+    assert!(expected.len() > 2);
+    assert_eq!(response.segments, expected);
+
+    // Invalid requests are rejected before reaching the database
+    let status = ServerImpl::default()
+        .segmentize_path(Request::new(SegmentizePathRequest {
+            path: vec![],
+            time_start: Some(time_start.into()),
+            time_end: Some(time_end.into()),
+            segment_length_meters,
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Internal);
+}
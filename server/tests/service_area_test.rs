@@ -0,0 +1,145 @@
+//! Service area restriction of stored geometries against a live database
+//!
+//! Kept in its own test binary so that the service area doesn't apply to
+//!  other tests.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use std::sync::atomic::Ordering;
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, PointZ, UpdateFlightPathRequest, Zone, ZoneType,
+};
+use svc_gis::postgis::flight::{self, FlightError};
+use svc_gis::postgis::service_area::{self, OUT_OF_AREA_REJECTIONS};
+use svc_gis::postgis::zone::{self, ZoneError};
+use svc_gis::postgis::{aircraft, PostgisError};
+use svc_gis::types::{AircraftPosition, AircraftType, Position};
+
+/// Amsterdam
+const LATITUDE: f64 = 52.3745905;
+const LONGITUDE: f64 = 4.9160036;
+
+/// A fat-fingered longitude in the middle of the Atlantic
+const ATLANTIC_LONGITUDE: f64 = -34.9160036;
+
+fn flight(identifier: &str, longitude_end: f64) -> UpdateFlightPathRequest {
+    let time_start = Utc::now();
+    UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.to_string()),
+        aircraft_identifier: Some(format!("{identifier}-ac")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude: LATITUDE,
+                longitude: LONGITUDE,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: LATITUDE,
+                longitude: longitude_end,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    }
+}
+
+fn zone(identifier: &str, longitude: f64) -> Zone {
+    Zone {
+        identifier: identifier.to_string(),
+        zone_type: ZoneType::Restriction as i32,
+        vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: LATITUDE + dy * 0.001,
+                longitude: longitude + dx * 0.001,
+            })
+            .collect(),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 100.0,
+        ..Default::default()
+    }
+}
+
+/// Flight paths, zones and positions outside of the service area and its
+///  buffer are rejected and counted, the others are stored as before
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_service_area() {
+    let (config, _) = common::setup().await;
+
+    let area = service_area::parse_service_area("4.8 52.3, 5.0 52.3, 5.0 52.4, 4.8 52.4")
+        .expect("could not parse service area");
+    service_area::SERVICE_AREA.set(area).unwrap();
+    service_area::SERVICE_AREA_BUFFER_METERS
+        .set(1_000.0)
+        .unwrap();
+
+    let suffix = common::unique_suffix();
+    let rejections = || OUT_OF_AREA_REJECTIONS.load(Ordering::Relaxed);
+
+    // Ends about 680 m east of the area, within the buffer
+    flight::update_flight_path(
+        flight(&format!("sa-{suffix}-a"), 5.01),
+        config.max_flight_duration_secs,
+    )
+    .await
+    .expect("flight within the buffer rejected");
+
+    let e = flight::update_flight_path(
+        flight(&format!("sa-{suffix}-b"), ATLANTIC_LONGITUDE),
+        config.max_flight_duration_secs,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(e, PostgisError::FlightPath(FlightError::ServiceArea));
+    assert_eq!(rejections(), 1);
+
+    zone::update_zones(vec![zone(&format!("sa-{suffix}-a"), LONGITUDE)], false)
+        .await
+        .expect("zone inside the area rejected");
+
+    let e = zone::update_zones(
+        vec![zone(&format!("sa-{suffix}-b"), ATLANTIC_LONGITUDE)],
+        false,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(e, ZoneError::ServiceArea);
+    assert_eq!(rejections(), 2);
+
+    // Positions outside are dropped from the batch
+    let identifier = format!("sa-{suffix}-ac");
+    aircraft::update_aircraft_position(
+        vec![AircraftPosition {
+            identifier: identifier.clone(),
+            position: Position {
+                latitude: LATITUDE,
+                longitude: ATLANTIC_LONGITUDE,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        }],
+        false,
+    )
+    .await
+    .expect("position update failed");
+    assert_eq!(rejections(), 3);
+    assert!(aircraft::get_aircraft_pointz(&identifier).await.is_err());
+
+    let info = svc_gis::info::get_service_info().await;
+    assert!(info
+        .service_area
+        .unwrap()
+        .starts_with("POLYGON((4.8 52.3, 5 52.3"));
+    assert_eq!(info.service_area_buffer_meters, 1_000.0);
+}
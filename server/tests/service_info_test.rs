@@ -0,0 +1,25 @@
+//! Service info against a live database
+
+mod common;
+
+use svc_gis::postgis::PSQL_SCHEMA_VERSION;
+
+/// After initialization the applied schema version is reported
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_service_info() {
+    common::setup().await;
+
+    assert_eq!(
+        svc_gis::postgis::get_schema_version().await.unwrap(),
+        Some(PSQL_SCHEMA_VERSION)
+    );
+
+    let info = svc_gis::info::get_service_info().await;
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.schema_version, Some(PSQL_SCHEMA_VERSION));
+    assert_eq!(info.database_status, "ok");
+}
@@ -0,0 +1,229 @@
+//! Soft-deletion of zones and flights against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, PointZ, UpdateFlightPathRequest, Zone, ZoneType,
+};
+use svc_gis::postgis::zone::{self, ZoneError};
+use svc_gis::postgis::{flight, maintenance, PSQL_SCHEMA};
+use svc_gis::types::AircraftType;
+
+fn zone(identifier: &str) -> Zone {
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    Zone {
+        identifier: identifier.to_string(),
+        zone_type: ZoneType::Restriction as i32,
+        vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: latitude + dy * 0.0001,
+                longitude: longitude + dx * 0.0001,
+            })
+            .collect(),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 100.0,
+        ..Default::default()
+    }
+}
+
+/// Delete, restore, re-create and purge a zone
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_zone_soft_delete() {
+    common::setup().await;
+
+    let identifier = format!("it-{}", Utc::now().timestamp_micros());
+    zone::update_zones(vec![zone(&identifier)], false)
+        .await
+        .unwrap();
+
+    // delete -> restore
+    zone::delete_zone(&identifier).await.unwrap();
+    assert_eq!(
+        zone::delete_zone(&identifier).await.unwrap_err(),
+        ZoneError::NotFound
+    );
+    zone::restore_zone(&identifier, 60).await.unwrap();
+
+    // delete -> re-create is blocked while the tombstone exists
+    zone::delete_zone(&identifier).await.unwrap();
+    assert_eq!(
+        zone::update_zones(vec![zone(&identifier)], false)
+            .await
+            .unwrap_err(),
+        ZoneError::Deleted
+    );
+
+    // Outside of the undo window
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    assert_eq!(
+        zone::restore_zone(&identifier, 1).await.unwrap_err(),
+        ZoneError::NotFound
+    );
+
+    // delete -> purge frees the identifier
+    assert!(zone::purge_zones(1).await.unwrap() >= 1);
+    assert_eq!(
+        zone::restore_zone(&identifier, 60).await.unwrap_err(),
+        ZoneError::NotFound
+    );
+    zone::update_zones(vec![zone(&identifier)], false)
+        .await
+        .unwrap();
+}
+
+fn flight_path(identifier: &str) -> UpdateFlightPathRequest {
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now() + Duration::try_minutes(5).unwrap();
+    UpdateFlightPathRequest {
+        flight_identifier: Some(identifier.to_string()),
+        aircraft_identifier: Some(identifier.to_string()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: [longitude, longitude + 0.01]
+            .iter()
+            .map(|longitude| PointZ {
+                latitude,
+                longitude: *longitude,
+                altitude_meters: 100.0,
+            })
+            .collect(),
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    }
+}
+
+/// Deleted zone and flight identifiers can be reused once the scheduled
+///  purge of the default config has removed them after retention
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_recreate_after_retention() {
+    let (_, pool) = common::setup().await;
+    let config = svc_gis::Config::default();
+    assert!(config.soft_delete_purge_interval_secs > 0);
+
+    let identifier = format!("rr-{}", common::unique_suffix());
+    zone::update_zones(vec![zone(&identifier)], false)
+        .await
+        .expect("could not create zone");
+    flight::update_flight_path(flight_path(&identifier), config.max_flight_duration_secs)
+        .await
+        .expect("could not create flight");
+
+    zone::delete_zone(&identifier)
+        .await
+        .expect("could not delete zone");
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+
+    // Blocked while within retention
+    maintenance::purge_deleted(config.soft_delete_retention_secs)
+        .await
+        .expect("purge failed");
+    assert_eq!(
+        zone::update_zones(vec![zone(&identifier)], false)
+            .await
+            .unwrap_err(),
+        ZoneError::Deleted
+    );
+    flight::update_flight_path(flight_path(&identifier), config.max_flight_duration_secs)
+        .await
+        .unwrap_err();
+
+    // Deleted before the retention
+    let client = pool.get().await.expect("could not get client");
+    for (table, column) in [("zones", "identifier"), ("flights", "flight_identifier")] {
+        client
+            .execute(
+                &format!(
+                    r#"UPDATE "{PSQL_SCHEMA}"."{table}"
+                    SET "deleted_at" = NOW() - make_interval(secs => $2::FLOAT8)
+                    WHERE "{column}" = $1;"#
+                ),
+                &[
+                    &identifier,
+                    &(config.soft_delete_retention_secs as f64 + 60.0),
+                ],
+            )
+            .await
+            .expect("could not backdate deletion");
+    }
+
+    maintenance::purge_deleted(config.soft_delete_retention_secs)
+        .await
+        .expect("purge failed");
+    zone::update_zones(vec![zone(&identifier)], false)
+        .await
+        .expect("could not re-create zone");
+    flight::update_flight_path(flight_path(&identifier), config.max_flight_duration_secs)
+        .await
+        .expect("could not re-create flight");
+
+    zone::delete_zone(&identifier)
+        .await
+        .expect("could not delete zone");
+    flight::delete_flight(&identifier, None)
+        .await
+        .expect("could not delete flight");
+}
+
+/// The undo window and the retention are measured with the service clock,
+///  not the database clock
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_undo_window_follows_clock() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let zone_identifier = format!("uw-{suffix}");
+    let flight_identifier = format!("uw-{suffix}");
+    zone::update_zones(vec![zone(&zone_identifier)], false)
+        .await
+        .unwrap();
+    flight::update_flight_path(
+        flight_path(&flight_identifier),
+        config.max_flight_duration_secs,
+    )
+    .await
+    .unwrap();
+
+    zone::delete_zone(&zone_identifier).await.unwrap();
+    flight::delete_flight(&flight_identifier, None)
+        .await
+        .unwrap();
+
+    // Two minutes later, past a one minute undo window
+    let clock = svc_gis::clock::freeze(Utc::now() + Duration::try_minutes(2).unwrap());
+    assert_eq!(
+        zone::restore_zone(&zone_identifier, 60).await.unwrap_err(),
+        ZoneError::NotFound
+    );
+    assert!(flight::restore_flight(&flight_identifier, 60)
+        .await
+        .is_err());
+
+    // Back within the window
+    clock.advance(-Duration::try_minutes(2).unwrap());
+    zone::restore_zone(&zone_identifier, 60).await.unwrap();
+    flight::restore_flight(&flight_identifier, 60)
+        .await
+        .unwrap();
+    drop(clock);
+
+    zone::delete_zone(&zone_identifier).await.unwrap();
+    flight::delete_flight(&flight_identifier, None)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,99 @@
+//! Recovery from stale cached statements against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::{aircraft, flight, PSQL_SCHEMA};
+use svc_gis::types::{AircraftPosition, AircraftType, Position};
+
+/// Changes the length of the `aircraft.session_id` column
+async fn alter_session_id(client: &deadpool_postgres::Client, length: u32) {
+    client
+        .batch_execute(&format!(
+            r#"ALTER TABLE "{PSQL_SCHEMA}"."aircraft"
+            ALTER COLUMN "session_id" TYPE VARCHAR({length});"#
+        ))
+        .await
+        .expect("could not alter column");
+}
+
+/// A column type change after a statement was cached is recovered from
+///  on the next query, without recycling the connection
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_stale_plan_recovery() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let flight_identifier = format!("sp-{suffix}");
+    let aircraft_identifier = format!("sp-{suffix}-ac");
+
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(aircraft_identifier.clone()),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    aircraft::update_aircraft_position(
+        vec![AircraftPosition {
+            identifier: aircraft_identifier,
+            position: Position {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        }],
+        false,
+    )
+    .await
+    .expect("position update failed");
+
+    // A single connection, so the cached statement is reused after the
+    //  column change
+    let mut single = config.clone();
+    single.pg.pool = Some(deadpool_postgres::PoolConfig::new(1));
+    let single = svc_gis::postgis::pool::create_pool(single);
+
+    let states = flight::get_states_for_flights(vec![flight_identifier.clone()], &single)
+        .await
+        .expect("could not get states");
+    assert!(states.contains_key(&flight_identifier));
+
+    alter_session_id(&single.get().await.expect("could not get client"), 24).await;
+
+    let result = flight::get_states_for_flights(vec![flight_identifier.clone()], &single).await;
+
+    alter_session_id(&single.get().await.expect("could not get client"), 20).await;
+
+    let states = result.expect("stale plan was not recovered");
+    assert!(states.contains_key(&flight_identifier));
+}
@@ -0,0 +1,44 @@
+//! Statement timeout of pooled connections against a live database
+
+mod common;
+
+use std::time::{Duration, Instant};
+use svc_gis::postgis::DbErrorKind;
+
+/// A statement running longer than the timeout is cancelled by the database
+///  and classified as a timeout
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_statement_timeout() {
+    let mut config = svc_gis::Config::try_from_env().expect("could not load config");
+    config.pg_statement_timeout_secs = 1;
+
+    // Migrations aren't bound by the timeout
+    let (_, pool) = common::setup_with(config).await;
+
+    let client = pool.get().await.expect("could not get client");
+    let row = client
+        .query_one("SHOW statement_timeout;", &[])
+        .await
+        .expect("could not get statement timeout");
+    assert_eq!(row.get::<_, String>(0), "1s");
+
+    let start = Instant::now();
+    let e = client
+        .query_one("SELECT pg_sleep(10);", &[])
+        .await
+        .unwrap_err();
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(DbErrorKind::from(&e), DbErrorKind::Timeout);
+
+    // The connection is still usable
+    let row = client
+        .query_one("SELECT 1::INTEGER;", &[])
+        .await
+        .expect("connection unusable after the timeout");
+    assert_eq!(row.get::<_, i32>(0), 1);
+}
@@ -0,0 +1,93 @@
+//! Flights flown by several aircraft against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Flight, GetFlightsRequest, PointZ, UpdateFlightPathRequest,
+};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+/// Every aircraft of a swarm flight is listed by `get_flights`, and an
+///  update replaces the previous set of aircraft
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_swarm_flight() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let flight_identifier = format!("sw-{suffix}");
+    let aircraft: Vec<String> = (0..3).map(|i| format!("sw-{suffix}-{i}")).collect();
+
+    let time_start = Utc::now() + Duration::try_hours(1).unwrap();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    let mut request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(aircraft[0].clone()),
+        aircraft_identifiers: aircraft[1..].to_vec(),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some(time_end.into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request.clone(), config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let flights_request = GetFlightsRequest {
+        window_min_x: longitude - 0.001,
+        window_min_y: latitude - 0.001,
+        window_max_x: longitude + 0.011,
+        window_max_y: latitude + 0.001,
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        ..Default::default()
+    };
+
+    let members = |flights: Vec<Flight>| {
+        let mut members: Vec<String> = flights
+            .into_iter()
+            .filter(|f| f.session_id.as_deref() == Some(flight_identifier.as_str()))
+            .filter_map(|f| f.aircraft_id)
+            .collect();
+        members.sort();
+        members
+    };
+
+    let flights = flight::get_flights(flights_request.clone())
+        .await
+        .expect("could not get flights");
+    assert_eq!(members(flights), aircraft);
+
+    // Dropping an aircraft from the swarm
+    request.aircraft_identifiers = vec![aircraft[2].clone()];
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let flights = flight::get_flights(flights_request)
+        .await
+        .expect("could not get flights");
+    assert_eq!(
+        members(flights),
+        vec![aircraft[0].clone(), aircraft[2].clone()]
+    );
+}
@@ -0,0 +1,142 @@
+//! Tag-filtered flight queries against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    GetFlightConflictsRequest, GetFlightsRequest, PointZ, TagFilter, TagMatch,
+    UpdateFlightPathRequest,
+};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+/// Flight identifiers returned by get_flights with the provided filter
+async fn flights_matching(
+    request: &GetFlightsRequest,
+    tags: &[&str],
+    mode: TagMatch,
+) -> Vec<String> {
+    let request = GetFlightsRequest {
+        tag_filter: Some(TagFilter {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            mode: mode as i32,
+        }),
+        ..request.clone()
+    };
+
+    let mut identifiers = flight::get_flights(request)
+        .await
+        .expect("could not get flights")
+        .into_iter()
+        .filter_map(|flight| flight.session_id)
+        .collect::<Vec<_>>();
+    identifiers.sort();
+    identifiers
+}
+
+/// get_flights and get_flight_conflicts only return flights matching the
+///  tag filter
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_tag_filter() {
+    let (config, _) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let operator_id = format!("tf-{suffix}-op");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+    let time_end = time_start + Duration::try_minutes(10).unwrap();
+    let path = vec![
+        PointZ {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        PointZ {
+            latitude,
+            longitude: longitude + 0.01,
+            altitude_meters: 100.0,
+        },
+    ];
+
+    let flights = [
+        ("vip", vec!["vip", "exercise-redwing"]),
+        ("training", vec!["training"]),
+        ("untagged", vec![]),
+    ];
+
+    for (name, tags) in &flights {
+        let request = UpdateFlightPathRequest {
+            flight_identifier: Some(format!("tf-{suffix}-{name}")),
+            aircraft_identifier: Some(format!("tf-{suffix}-{name}-ac")),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            operator_id: Some(operator_id.clone()),
+            path: path.clone(),
+            timestamp_start: Some(time_start.into()),
+            timestamp_end: Some(time_end.into()),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("flight update failed");
+    }
+
+    let vip = format!("tf-{suffix}-vip");
+    let training = format!("tf-{suffix}-training");
+    let untagged = format!("tf-{suffix}-untagged");
+
+    let request = GetFlightsRequest {
+        window_min_x: longitude - 0.01,
+        window_min_y: latitude - 0.01,
+        window_max_x: longitude + 0.02,
+        window_max_y: latitude + 0.01,
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        operator_id: Some(operator_id.clone()),
+        ..Default::default()
+    };
+
+    let mut all = vec![training.clone(), untagged.clone(), vip.clone()];
+    all.sort();
+    assert_eq!(flights_matching(&request, &[], TagMatch::Any).await, all);
+    assert_eq!(
+        flights_matching(&request, &["vip", "training"], TagMatch::Any).await,
+        vec![training.clone(), vip.clone()]
+    );
+    assert_eq!(
+        flights_matching(&request, &["vip", "exercise-redwing"], TagMatch::All).await,
+        vec![vip.clone()]
+    );
+    assert_eq!(
+        flights_matching(&request, &["vip"], TagMatch::Exclude).await,
+        vec![training.clone(), untagged.clone()]
+    );
+
+    // Conflicts ignore excluded flights
+    let conflicts = flight::get_flight_conflicts(GetFlightConflictsRequest {
+        path: path.clone(),
+        time_start: Some(time_start.into()),
+        time_end: Some(time_end.into()),
+        distance_meters: 50.0,
+        tag_filter: Some(TagFilter {
+            tags: vec!["training".to_string()],
+            mode: TagMatch::Exclude as i32,
+        }),
+    })
+    .await
+    .expect("could not get conflicts");
+
+    let identifiers = conflicts
+        .conflicts
+        .iter()
+        .map(|conflict| conflict.flight_identifier.clone())
+        .collect::<Vec<_>>();
+    assert!(identifiers.contains(&vip));
+    assert!(identifiers.contains(&untagged));
+    assert!(!identifiers.contains(&training));
+}
@@ -0,0 +1,53 @@
+//! Aircraft telemetry history against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::types::{AircraftTelemetry, Position};
+
+/// A combined telemetry update writes exactly one full history row
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_telemetry_history_single_row() {
+    common::setup().await;
+
+    let identifier = format!("it-{}", Utc::now().timestamp_micros());
+    let timestamp_network = Utc::now() - Duration::try_seconds(1).unwrap();
+    let item = AircraftTelemetry {
+        identifier: identifier.clone(),
+        position: Position {
+            latitude: 52.3745905,
+            longitude: 4.9160036,
+            altitude_meters: 100.0,
+        },
+        velocity_horizontal_ground_mps: 10.0,
+        velocity_horizontal_air_mps: Some(12.0),
+        velocity_vertical_mps: 1.0,
+        track_angle_degrees: 90.0,
+        timestamp_network,
+        timestamp_asset: Some(timestamp_network),
+    };
+
+    svc_gis::postgis::aircraft::update_aircraft_telemetry(vec![item], false)
+        .await
+        .expect("telemetry update failed");
+
+    let history = svc_gis::postgis::aircraft::get_telemetry_history(
+        &identifier,
+        timestamp_network - Duration::try_minutes(1).unwrap(),
+        Utc::now(),
+    )
+    .await
+    .expect("could not get history");
+
+    assert_eq!(history.len(), 1);
+    let snapshot = &history[0];
+    assert_eq!(snapshot.velocity_horizontal_ground_mps, Some(10.0));
+    assert_eq!(snapshot.velocity_horizontal_air_mps, Some(12.0));
+    assert_eq!(snapshot.velocity_vertical_mps, Some(1.0));
+    assert_eq!(snapshot.track_angle_degrees, Some(90.0));
+    assert!(snapshot.timestamp_asset.is_some());
+}
@@ -0,0 +1,101 @@
+//! Aircraft update latency while reads saturate the main pool, against a
+//!  live database
+
+mod common;
+
+use chrono::Utc;
+use std::time::{Duration, Instant};
+use svc_gis::postgis::{aircraft, pool, DEADPOOL_POSTGIS_TELEMETRY};
+use svc_gis::types::{AircraftPosition, Position};
+
+/// Position updates measured per run
+const SAMPLES: usize = 200;
+
+/// Duration of each read holding a main pool connection
+const READ_MS: u64 = 250;
+
+/// Gets the 99th percentile of the latency of position updates
+async fn ingest_p99(prefix: &str) -> Duration {
+    let mut latencies = Vec::with_capacity(SAMPLES);
+    for i in 0..SAMPLES {
+        let item = AircraftPosition {
+            identifier: format!("{prefix}-{i}"),
+            position: Position {
+                latitude: 52.3945905,
+                longitude: 4.9760036,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        };
+
+        let start = Instant::now();
+        aircraft::update_aircraft_position(vec![item], false)
+            .await
+            .expect("position update failed");
+        latencies.push(start.elapsed());
+    }
+
+    latencies.sort();
+    latencies[SAMPLES * 99 / 100 - 1]
+}
+
+/// Ingest p99 latency stays flat while every main pool connection is busy
+///  with reads, since aircraft updates use the telemetry pool
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn it_ingest_latency_under_read_load() {
+    let (config, main_pool) = common::setup().await;
+    let telemetry_pool =
+        pool::create_telemetry_pool(config.clone()).expect("no telemetry pool configured");
+    DEADPOOL_POSTGIS_TELEMETRY
+        .set(telemetry_pool)
+        .expect("could not set telemetry pool");
+
+    let prefix = format!("tl{}", common::unique_suffix());
+    let baseline = ingest_p99(&format!("{prefix}-idle")).await;
+
+    // Saturate the main pool with slow reads, plus some waiting
+    let stop = Instant::now() + Duration::from_secs(60);
+    let readers = main_pool.status().max_size + 4;
+    let reads = (0..readers)
+        .map(|_| {
+            let main_pool = main_pool.clone();
+            tokio::spawn(async move {
+                while Instant::now() < stop {
+                    let Ok(client) = main_pool.get().await else {
+                        continue;
+                    };
+
+                    let _ = client
+                        .execute("SELECT pg_sleep($1);", &[&(READ_MS as f64 / 1000.0)])
+                        .await;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    tokio::time::sleep(Duration::from_millis(READ_MS)).await;
+    let (_, gauges) = pool::pool_gauges()
+        .into_iter()
+        .find(|(name, _)| *name == "main")
+        .expect("main pool not reported");
+    assert_eq!(gauges.available, 0);
+    assert!(gauges.waiting > 0);
+
+    let saturated = ingest_p99(&format!("{prefix}-busy")).await;
+    for read in reads {
+        read.abort();
+    }
+
+    println!("ingest p99: {baseline:?} idle, {saturated:?} with saturated reads");
+
+    // Without the telemetry pool each update would wait for a read
+    assert!(saturated < Duration::from_millis(READ_MS));
+    assert!(saturated <= baseline * 3 + Duration::from_millis(20));
+}
@@ -0,0 +1,241 @@
+//! Mapbox Vector Tiles produced from a live database
+
+mod common;
+
+use chrono::Utc;
+use prost::Message;
+use svc_gis::grpc::server::grpc_server::{Coordinates, GetTileRequest, TileLayer, Zone, ZoneType};
+use svc_gis::postgis::{aircraft, tile, zone};
+use svc_gis::types::{AircraftTelemetry, Position};
+
+/// Tile of the [Mapbox Vector Tile specification](https://github.com/mapbox/vector-tile-spec/blob/master/2.1/vector_tile.proto)
+#[derive(Clone, PartialEq, Message)]
+struct VectorTile {
+    #[prost(message, repeated, tag = "3")]
+    layers: Vec<VectorLayer>,
+}
+
+/// Layer of a vector tile
+#[derive(Clone, PartialEq, Message)]
+struct VectorLayer {
+    #[prost(uint32, required, tag = "15")]
+    version: u32,
+
+    #[prost(string, required, tag = "1")]
+    name: String,
+
+    #[prost(message, repeated, tag = "2")]
+    features: Vec<VectorFeature>,
+
+    #[prost(string, repeated, tag = "3")]
+    keys: Vec<String>,
+
+    #[prost(message, repeated, tag = "4")]
+    values: Vec<VectorValue>,
+
+    #[prost(uint32, optional, tag = "5")]
+    extent: Option<u32>,
+}
+
+/// Feature of a vector tile layer
+#[derive(Clone, PartialEq, Message)]
+struct VectorFeature {
+    #[prost(uint32, repeated, tag = "2")]
+    tags: Vec<u32>,
+
+    #[prost(uint32, optional, tag = "3")]
+    geom_type: Option<u32>,
+
+    #[prost(uint32, repeated, tag = "4")]
+    geometry: Vec<u32>,
+}
+
+/// Attribute value of a vector tile feature, only strings are decoded
+#[derive(Clone, PartialEq, Message)]
+struct VectorValue {
+    #[prost(string, optional, tag = "1")]
+    string_value: Option<String>,
+}
+
+/// Polygon geometry type of the specification
+const GEOM_TYPE_POLYGON: u32 = 3;
+
+const ZOOM: u32 = 14;
+const TILE_X: u32 = 1000;
+const TILE_Y: u32 = 11000;
+
+/// Gets the `(latitude, longitude)` of the center of the test tile
+fn tile_center() -> (f64, f64) {
+    let n = f64::from(1u32 << ZOOM);
+    let longitude = (f64::from(TILE_X) + 0.5) / n * 360.0 - 180.0;
+    let latitude = (std::f64::consts::PI * (1.0 - 2.0 * (f64::from(TILE_Y) + 0.5) / n))
+        .sinh()
+        .atan()
+        .to_degrees();
+
+    (latitude, longitude)
+}
+
+/// Gets the `identifier` attribute of a feature
+fn identifier<'a>(layer: &'a VectorLayer, feature: &VectorFeature) -> Option<&'a str> {
+    feature.tags.chunks(2).find_map(|tag| {
+        (layer.keys.get(tag[0] as usize)? == "identifier")
+            .then(|| layer.values.get(tag[1] as usize)?.string_value.as_deref())
+            .flatten()
+    })
+}
+
+/// Gets the number of vertices of each ring of a polygon geometry
+///
+/// Each ring is a `MoveTo` of one point, a `LineTo` of the other points and
+///  a `ClosePath`, the first point isn't repeated.
+fn ring_sizes(geometry: &[u32]) -> Vec<usize> {
+    const MOVE_TO: u32 = 1;
+    const LINE_TO: u32 = 2;
+    const CLOSE_PATH: u32 = 7;
+
+    let mut sizes = vec![];
+    let mut size = 0;
+    let mut i = 0;
+    while i < geometry.len() {
+        let (command, count) = (geometry[i] & 0x7, (geometry[i] >> 3) as usize);
+        match command {
+            MOVE_TO | LINE_TO => {
+                size += count;
+                i += 1 + 2 * count;
+            }
+            CLOSE_PATH => {
+                sizes.push(size);
+                size = 0;
+                i += 1;
+            }
+            _ => panic!("unknown geometry command {command}"),
+        }
+    }
+
+    sizes
+}
+
+/// A tile holds the requested layers, with a concave zone drawn as is and
+///  the aircraft in the tile
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_get_tile() {
+    common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let zone_identifier = format!("tile-zone-{suffix}");
+    let aircraft_identifier = format!("tile{suffix}");
+    let (latitude, longitude) = tile_center();
+
+    // L-shaped, its convex hull has a vertex less
+    let vertices = [
+        (-1., -1.),
+        (1., -1.),
+        (1., 0.),
+        (0., 0.),
+        (0., 1.),
+        (-1., 1.),
+        (-1., -1.),
+    ];
+
+    zone::update_zones(
+        vec![Zone {
+            identifier: zone_identifier.clone(),
+            zone_type: ZoneType::Restriction as i32,
+            vertices: vertices
+                .iter()
+                .map(|(dy, dx)| Coordinates {
+                    latitude: latitude + dy * 0.003,
+                    longitude: longitude + dx * 0.003,
+                })
+                .collect(),
+            altitude_meters_min: 0.0,
+            altitude_meters_max: 100.0,
+            ..Default::default()
+        }],
+        false,
+    )
+    .await
+    .expect("could not update zones");
+
+    aircraft::update_aircraft_telemetry(
+        vec![AircraftTelemetry {
+            identifier: aircraft_identifier.clone(),
+            position: Position {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            velocity_horizontal_ground_mps: 10.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: 0.0,
+            track_angle_degrees: 90.0,
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+        }],
+        false,
+    )
+    .await
+    .expect("telemetry update failed");
+
+    let bytes = tile::get_tile(GetTileRequest {
+        z: ZOOM,
+        x: TILE_X,
+        y: TILE_Y,
+        layers: vec![TileLayer::Zones as i32, TileLayer::Aircraft as i32],
+    })
+    .await
+    .expect("could not get tile");
+
+    let decoded = VectorTile::decode(bytes.as_slice()).expect("could not decode tile");
+    let names: Vec<&str> = decoded.layers.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            tile::get_layer_name(TileLayer::Zones),
+            tile::get_layer_name(TileLayer::Aircraft)
+        ]
+    );
+
+    let zones = &decoded.layers[0];
+    let feature = zones
+        .features
+        .iter()
+        .find(|feature| identifier(zones, feature) == Some(zone_identifier.as_str()))
+        .expect("zone not in tile");
+    assert_eq!(feature.geom_type, Some(GEOM_TYPE_POLYGON));
+    assert_eq!(ring_sizes(&feature.geometry), vec![vertices.len() - 1]);
+
+    let aircraft = &decoded.layers[1];
+    assert!(aircraft
+        .features
+        .iter()
+        .any(|feature| identifier(aircraft, feature) == Some(aircraft_identifier.as_str())));
+
+    zone::delete_zone(&zone_identifier)
+        .await
+        .expect("could not delete zone");
+
+    // Deleted zones are hidden
+    let bytes = tile::get_tile(GetTileRequest {
+        z: ZOOM,
+        x: TILE_X,
+        y: TILE_Y,
+        layers: vec![TileLayer::Zones as i32],
+    })
+    .await
+    .expect("could not get tile");
+
+    let decoded = VectorTile::decode(bytes.as_slice()).expect("could not decode tile");
+    assert!(decoded
+        .layers
+        .iter()
+        .all(|layer| layer
+            .features
+            .iter()
+            .all(|feature| identifier(layer, feature) != Some(zone_identifier.as_str()))));
+}
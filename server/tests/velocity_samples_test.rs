@@ -0,0 +1,118 @@
+//! Recent velocity samples of aircraft in getFlights against a live database
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{Flight, GetFlightsRequest};
+use svc_gis::postgis::aircraft;
+use svc_gis::postgis::flight::{self, MAX_VELOCITY_SAMPLES};
+use svc_gis::types::{AircraftTelemetry, Position};
+
+/// Ground speeds of the velocity samples of an aircraft
+fn speeds(flights: &[Flight], identifier: &str) -> Vec<f32> {
+    flights
+        .iter()
+        .find(|flight| flight.aircraft_id.as_deref() == Some(identifier))
+        .expect("aircraft not found")
+        .velocity_samples
+        .iter()
+        .map(|sample| sample.ground_speed_mps)
+        .collect()
+}
+
+/// The newest samples are returned oldest first, aircraft with fewer
+///  samples get what's available
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_velocity_samples() {
+    common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let (busy, quiet) = (format!("vs-{suffix}-busy"), format!("vs-{suffix}-quiet"));
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now() - Duration::try_seconds(30).unwrap();
+
+    let telemetry = |identifier: &str, index: i64| AircraftTelemetry {
+        identifier: identifier.to_string(),
+        position: Position {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        },
+        velocity_horizontal_ground_mps: index as f32,
+        velocity_horizontal_air_mps: None,
+        velocity_vertical_mps: 0.5,
+        track_angle_degrees: (index * 10) as f32,
+        timestamp_network: time_start + Duration::try_seconds(index).unwrap(),
+        timestamp_asset: None,
+    };
+
+    for index in 0..(MAX_VELOCITY_SAMPLES as i64 + 2) {
+        aircraft::update_aircraft_telemetry(vec![telemetry(&busy, index)], false)
+            .await
+            .expect("telemetry update failed");
+    }
+
+    for index in 0..2 {
+        aircraft::update_aircraft_telemetry(vec![telemetry(&quiet, index)], false)
+            .await
+            .expect("telemetry update failed");
+    }
+
+    let request = GetFlightsRequest {
+        window_min_x: longitude - 0.001,
+        window_min_y: latitude - 0.001,
+        window_max_x: longitude + 0.001,
+        window_max_y: latitude + 0.001,
+        time_start: Some(time_start.into()),
+        time_end: Some(Utc::now().into()),
+        ..Default::default()
+    };
+
+    // None unless requested
+    let flights = flight::get_flights(request.clone())
+        .await
+        .expect("could not get flights");
+    assert!(speeds(&flights, &busy).is_empty());
+
+    let flights = flight::get_flights(GetFlightsRequest {
+        velocity_samples: MAX_VELOCITY_SAMPLES,
+        ..request.clone()
+    })
+    .await
+    .expect("could not get flights");
+
+    let expected: Vec<f32> = (2..(MAX_VELOCITY_SAMPLES as i64 + 2))
+        .map(|index| index as f32)
+        .collect();
+    assert_eq!(speeds(&flights, &busy), expected);
+    assert_eq!(speeds(&flights, &quiet), vec![0.0, 1.0]);
+
+    let samples = &flights
+        .iter()
+        .find(|flight| flight.aircraft_id.as_deref() == Some(busy.as_str()))
+        .unwrap()
+        .velocity_samples;
+    let timestamps: Vec<DateTime<Utc>> = samples
+        .iter()
+        .map(|sample| sample.timestamp.clone().expect("no timestamp").into())
+        .collect();
+    assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(samples[0].track_angle_degrees, 20.0);
+    assert_eq!(samples[0].vertical_speed_mps, 0.5);
+
+    let flights = flight::get_flights(GetFlightsRequest {
+        velocity_samples: 3,
+        ..request
+    })
+    .await
+    .expect("could not get flights");
+    let newest = MAX_VELOCITY_SAMPLES as f32 + 1.0;
+    assert_eq!(
+        speeds(&flights, &busy),
+        vec![newest - 2.0, newest - 1.0, newest]
+    );
+}
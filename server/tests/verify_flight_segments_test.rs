@@ -0,0 +1,96 @@
+//! Consistency of flight paths and their segments against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight::{self, SegmentDiscrepancy, SEGMENT_TOLERANCE_METERS};
+use svc_gis::postgis::PSQL_SCHEMA;
+use svc_gis::types::AircraftType;
+
+/// Segments written with the path match it, a corrupted segment set is
+///  flagged
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_verify_flight_segments() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let flight_identifier = format!("vs-{suffix}");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let time_start = Utc::now();
+
+    // About 700 m, stored as many short segments
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(format!("vs-{suffix}-ac")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(time_start.into()),
+        timestamp_end: Some((time_start + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let discrepancies =
+        flight::verify_flight_segments(&flight_identifier, SEGMENT_TOLERANCE_METERS, &pool)
+            .await
+            .expect("could not verify segments");
+    assert_eq!(discrepancies, vec![]);
+
+    // Remove a segment from the middle of the path
+    let client = pool.get().await.expect("could not get client");
+    let removed = client
+        .execute(
+            &format!(
+                r#"DELETE FROM "{PSQL_SCHEMA}"."flight_segments"
+                WHERE "flight_identifier" = $1 AND "time_start" = (
+                    SELECT "time_start" FROM "{PSQL_SCHEMA}"."flight_segments"
+                    WHERE "flight_identifier" = $1
+                    ORDER BY "time_start"
+                    OFFSET 3 LIMIT 1
+                );"#
+            ),
+            &[&flight_identifier],
+        )
+        .await
+        .expect("could not corrupt segments");
+    assert_eq!(removed, 1);
+
+    let discrepancies =
+        flight::verify_flight_segments(&flight_identifier, SEGMENT_TOLERANCE_METERS, &pool)
+            .await
+            .expect("could not verify segments");
+    assert!(discrepancies
+        .iter()
+        .any(|d| matches!(d, SegmentDiscrepancy::Gap { index: 3, .. })));
+    assert!(discrepancies
+        .iter()
+        .any(|d| matches!(d, SegmentDiscrepancy::Length { .. })));
+
+    // Unknown flights are not found rather than consistent
+    let e = flight::verify_flight_segments(&format!("vs-{suffix}-x"), 1.0, &pool)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        e,
+        svc_gis::postgis::PostgisError::FlightPath(flight::FlightError::NotFound)
+    );
+}
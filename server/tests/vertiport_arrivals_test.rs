@@ -0,0 +1,120 @@
+//! Scheduled vertiport arrivals against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{PointZ, UpdateFlightPathRequest};
+use svc_gis::postgis::flight;
+use svc_gis::types::AircraftType;
+
+/// Only flights with the vertiport as destination and arriving within the
+///  window are returned, ordered by arrival
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_vertiport_arrivals() {
+    let (config, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let target = format!("va-{suffix}-target");
+    let other = format!("va-{suffix}-other");
+    let (latitude, longitude) = (52.3745905, 4.9160036);
+    let now = Utc::now();
+
+    // (name, destination, minutes until arrival)
+    let flights = [
+        ("late", Some(&target), 40),
+        ("early", Some(&target), 20),
+        ("elsewhere", Some(&other), 30),
+        ("unknown", None, 30),
+        ("outside", Some(&target), 120),
+    ];
+
+    for (name, destination, minutes) in flights {
+        let time_end = now + Duration::try_minutes(minutes).unwrap();
+        let request = UpdateFlightPathRequest {
+            flight_identifier: Some(format!("va-{suffix}-{name}")),
+            aircraft_identifier: Some(format!("va-{suffix}-{name}-ac")),
+            aircraft_type: AircraftType::Rotorcraft as i32,
+            path: vec![
+                PointZ {
+                    latitude,
+                    longitude,
+                    altitude_meters: 100.0,
+                },
+                PointZ {
+                    latitude,
+                    longitude: longitude + 0.01,
+                    altitude_meters: 100.0,
+                },
+            ],
+            timestamp_start: Some(now.into()),
+            timestamp_end: Some(time_end.into()),
+            destination_identifier: destination.cloned(),
+            ..Default::default()
+        };
+
+        flight::update_flight_path(request, config.max_flight_duration_secs)
+            .await
+            .expect("flight update failed");
+    }
+
+    let arrivals =
+        flight::vertiport_arrivals(&target, now, now + Duration::try_hours(1).unwrap(), &pool)
+            .await
+            .expect("could not get arrivals");
+
+    let identifiers = arrivals
+        .iter()
+        .map(|arrival| arrival.flight_identifier.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        identifiers,
+        vec![format!("va-{suffix}-early"), format!("va-{suffix}-late")]
+    );
+    assert!(arrivals[0].time_end < arrivals[1].time_end);
+
+    // An update without a destination keeps the stored one
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(format!("va-{suffix}-early")),
+        aircraft_identifier: Some(format!("va-{suffix}-early-ac")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude,
+                longitude,
+                altitude_meters: 120.0,
+            },
+            PointZ {
+                latitude,
+                longitude: longitude + 0.01,
+                altitude_meters: 120.0,
+            },
+        ],
+        timestamp_start: Some(now.into()),
+        timestamp_end: Some((now + Duration::try_minutes(20).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    let arrivals =
+        flight::vertiport_arrivals(&target, now, now + Duration::try_hours(1).unwrap(), &pool)
+            .await
+            .expect("could not get arrivals");
+    assert_eq!(arrivals.len(), 2);
+
+    let arrivals =
+        flight::vertiport_arrivals(&other, now, now + Duration::try_hours(1).unwrap(), &pool)
+            .await
+            .expect("could not get arrivals");
+    assert_eq!(arrivals.len(), 1);
+    assert_eq!(
+        arrivals[0].flight_identifier,
+        format!("va-{suffix}-elsewhere")
+    );
+}
@@ -0,0 +1,162 @@
+//! What-if evaluation of a synthetic aircraft against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{
+    Coordinates, PointZ, UpdateFlightPathRequest, WhatIfRequest, Zone, ZoneType,
+};
+use svc_gis::postgis::{aircraft, flight, what_if, zone, PSQL_SCHEMA};
+use svc_gis::types::{AircraftPosition, AircraftType, Position};
+
+/// Counts the rows of the aircraft table
+async fn count_aircraft(pool: &deadpool_postgres::Pool) -> i64 {
+    let client = pool.get().await.expect("could not get client");
+    client
+        .query_one(
+            &format!(r#"SELECT COUNT(*) FROM "{PSQL_SCHEMA}"."aircraft";"#),
+            &[],
+        )
+        .await
+        .expect("could not count aircraft")
+        .get(0)
+}
+
+/// A synthetic aircraft flying east reports the zone and the flight ahead
+///  of it and the aircraft next to it, and isn't stored
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_what_if() {
+    let (config, pool) = common::setup().await;
+
+    // An area far from other tests so their zones and flights aren't hit
+    let suffix = common::unique_suffix();
+    let (latitude, longitude) = (-40.0 + (suffix % 1000) as f64 * 1e-3, 175.0);
+    let ahead = longitude + 0.0035; // ~300 meters east
+    let now = Utc::now();
+
+    let zone_identifier = format!("wi-{suffix}");
+    let zone = Zone {
+        identifier: zone_identifier.clone(),
+        zone_type: ZoneType::Restriction as i32,
+        vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+            .iter()
+            .map(|(dy, dx)| Coordinates {
+                latitude: latitude + dy * 0.0005,
+                longitude: ahead + dx * 0.0005,
+            })
+            .collect(),
+        altitude_meters_min: 0.0,
+        altitude_meters_max: 500.0,
+        ..Default::default()
+    };
+
+    zone::update_zones(vec![zone], false)
+        .await
+        .expect("zone update failed");
+
+    // Crosses the synthetic path from south to north
+    let flight_identifier = format!("wi-{suffix}");
+    let request = UpdateFlightPathRequest {
+        flight_identifier: Some(flight_identifier.clone()),
+        aircraft_identifier: Some(format!("wi-{suffix}-ac")),
+        aircraft_type: AircraftType::Rotorcraft as i32,
+        path: vec![
+            PointZ {
+                latitude: latitude - 0.005,
+                longitude: ahead,
+                altitude_meters: 100.0,
+            },
+            PointZ {
+                latitude: latitude + 0.005,
+                longitude: ahead,
+                altitude_meters: 100.0,
+            },
+        ],
+        timestamp_start: Some(now.into()),
+        timestamp_end: Some((now + Duration::try_minutes(10).unwrap()).into()),
+        ..Default::default()
+    };
+
+    flight::update_flight_path(request, config.max_flight_duration_secs)
+        .await
+        .expect("flight update failed");
+
+    // ~20 meters north of the synthetic aircraft
+    let neighbor = format!("wi-{suffix}-nb");
+    aircraft::update_aircraft_position(
+        vec![AircraftPosition {
+            identifier: neighbor.clone(),
+            position: Position {
+                latitude: latitude + 0.0002,
+                longitude,
+                altitude_meters: 100.0,
+            },
+            timestamp_network: Utc::now(),
+            timestamp_asset: None,
+            horizontal_accuracy_meters: None,
+            vertical_accuracy_meters: None,
+        }],
+        false,
+    )
+    .await
+    .expect("position update failed");
+
+    let aircraft_count = count_aircraft(&pool).await;
+
+    let response = what_if::what_if(WhatIfRequest {
+        position: Some(PointZ {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        }),
+        ground_speed_mps: 10.0,
+        track_angle_degrees: 90.0,
+        vertical_speed_mps: 0.0,
+        lookahead_secs: 60,
+        distance_meters: 50.0,
+        ..Default::default()
+    })
+    .await
+    .expect("what-if failed");
+
+    assert!(response
+        .zones
+        .iter()
+        .any(|hit| hit.identifier == zone_identifier
+            && hit.zone_type == ZoneType::Restriction as i32));
+
+    let conflicts = response
+        .conflicts
+        .iter()
+        .filter(|conflict| conflict.flight_identifier == flight_identifier)
+        .count();
+    assert_eq!(conflicts, 1);
+
+    assert_eq!(response.traffic.len(), 1);
+    assert_eq!(response.traffic[0].identifier, neighbor);
+    assert!(response.traffic[0].distance_meters < 50.0);
+
+    // Nothing of the synthetic aircraft was stored
+    assert_eq!(count_aircraft(&pool).await, aircraft_count);
+
+    // Hovering in place only finds the neighbor
+    let response = what_if::what_if(WhatIfRequest {
+        position: Some(PointZ {
+            latitude,
+            longitude,
+            altitude_meters: 100.0,
+        }),
+        distance_meters: 50.0,
+        ..Default::default()
+    })
+    .await
+    .expect("what-if failed");
+
+    assert!(response.zones.is_empty());
+    assert!(response.conflicts.is_empty());
+    assert_eq!(response.traffic.len(), 1);
+}
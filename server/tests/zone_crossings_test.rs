@@ -0,0 +1,93 @@
+//! Zone crossings from the aircraft position history against a live database
+
+mod common;
+
+use chrono::{Duration, Utc};
+use svc_gis::grpc::server::grpc_server::{Coordinates, Zone, ZoneType};
+use svc_gis::postgis::zone::{self, CrossingKind};
+use svc_gis::types::{AircraftTelemetry, Position};
+
+const LATITUDE: f64 = 52.3745905;
+const LONGITUDE: f64 = 4.9160036;
+
+/// An aircraft flying through a zone enters and exits it once
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_zone_crossings() {
+    let (_, pool) = common::setup().await;
+
+    let suffix = common::unique_suffix();
+    let zone_identifier = format!("it-zone-{suffix}");
+    let aircraft_identifier = format!("it-{suffix}");
+
+    zone::update_zones(
+        vec![Zone {
+            identifier: zone_identifier.clone(),
+            zone_type: ZoneType::Restriction as i32,
+            vertices: [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.), (-1., -1.)]
+                .iter()
+                .map(|(dy, dx)| Coordinates {
+                    latitude: LATITUDE + dy * 0.0005,
+                    longitude: LONGITUDE + dx * 0.0005,
+                })
+                .collect(),
+            altitude_meters_min: 0.0,
+            altitude_meters_max: 100.0,
+            ..Default::default()
+        }],
+        false,
+    )
+    .await
+    .expect("zone update failed");
+
+    // South of the zone, inside, then north of the zone
+    let time_start = Utc::now() - Duration::try_seconds(50).unwrap();
+    for (i, latitude_offset) in [-0.002, 0.0, 0.002].iter().enumerate() {
+        let timestamp_network = time_start + Duration::try_seconds(10 * i as i64).unwrap();
+        let item = AircraftTelemetry {
+            identifier: aircraft_identifier.clone(),
+            position: Position {
+                latitude: LATITUDE + latitude_offset,
+                longitude: LONGITUDE,
+                altitude_meters: 50.0,
+            },
+            velocity_horizontal_ground_mps: 20.0,
+            velocity_horizontal_air_mps: None,
+            velocity_vertical_mps: 0.0,
+            track_angle_degrees: 0.0,
+            timestamp_network,
+            timestamp_asset: None,
+        };
+
+        svc_gis::postgis::aircraft::update_aircraft_telemetry(vec![item], false)
+            .await
+            .expect("telemetry update failed");
+    }
+
+    let crossings = zone::zone_crossings(
+        &zone_identifier,
+        time_start - Duration::try_seconds(1).unwrap(),
+        Utc::now(),
+        &pool,
+    )
+    .await
+    .expect("could not get zone crossings")
+    .into_iter()
+    .filter(|c| c.aircraft_identifier == aircraft_identifier)
+    .collect::<Vec<_>>();
+
+    assert_eq!(crossings.len(), 2);
+    assert_eq!(crossings[0].kind, CrossingKind::Entry);
+    assert_eq!(crossings[1].kind, CrossingKind::Exit);
+    assert!(crossings[0].timestamp < crossings[1].timestamp);
+
+    assert_eq!(
+        zone::zone_crossings("it-missing", time_start, Utc::now(), &pool)
+            .await
+            .unwrap_err(),
+        zone::ZoneError::NotFound
+    );
+}
@@ -0,0 +1,50 @@
+//! PostGIS zone geometry validation against a live database
+
+mod common;
+
+use postgis::ewkb::{LineStringT, PointZ, PolygonZ};
+use svc_gis::postgis::zone;
+
+/// A bow-tie polygon near Amsterdam, its edges cross at (52.375, 4.915)
+fn bow_tie() -> PolygonZ {
+    let points = [
+        (4.914, 52.374),
+        (4.916, 52.376),
+        (4.914, 52.376),
+        (4.916, 52.374),
+        (4.914, 52.374),
+    ]
+    .iter()
+    .map(|(x, y)| PointZ::new(*x, *y, 100.0, Some(4326)))
+    .collect();
+
+    PolygonZ {
+        rings: vec![LineStringT {
+            points,
+            srid: Some(4326),
+        }],
+        srid: Some(4326),
+    }
+}
+
+/// PostGIS explains why a geometry is invalid and where
+///
+/// Requires a PostGIS database configured through the environment (see
+///  `.env.repo`): `cargo test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn it_zone_validity() {
+    common::setup().await;
+
+    let issues = zone::postgis_geometry_issues(&[("NFZ_BOW_TIE".to_string(), bow_tie())])
+        .await
+        .expect("could not check geometries");
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].identifier, "NFZ_BOW_TIE");
+    assert!(issues[0].reason.starts_with("Self-intersection["));
+
+    let location = issues[0].location.clone().expect("no location");
+    assert!((location.latitude - 52.375).abs() < 1e-6);
+    assert!((location.longitude - 4.915).abs() < 1e-6);
+}